@@ -0,0 +1,332 @@
+//! The error codes of the program. With the `anchor` feature they are an anchor `#[error_code]` and `Error` is
+//! the anchor error, without it a plain enum with the same numbers and messages, which is also the error type.
+
+/// The number of the first error code, the same as the anchor error codes
+pub const ERROR_CODE_OFFSET: u32 = 6000;
+
+#[cfg(feature = "anchor")]
+pub use anchor_lang::error::Error;
+#[cfg(not(feature = "anchor"))]
+pub type Error = ErrorCode;
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+macro_rules! error_codes {
+    ($($(#[doc = $doc:literal])* #[msg($msg:literal)] $variant:ident,)*) => {
+        #[cfg(feature = "anchor")]
+        #[anchor_lang::error_code]
+        pub enum ErrorCode {
+            $($(#[doc = $doc])* #[msg($msg)] $variant,)*
+        }
+
+        #[cfg(not(feature = "anchor"))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[repr(u32)]
+        pub enum ErrorCode {
+            $($(#[doc = $doc])* $variant,)*
+        }
+
+        #[cfg(not(feature = "anchor"))]
+        impl ErrorCode {
+            pub fn name(&self) -> String {
+                match self {
+                    $(ErrorCode::$variant => stringify!($variant).to_string(),)*
+                }
+            }
+        }
+
+        #[cfg(not(feature = "anchor"))]
+        impl core::fmt::Display for ErrorCode {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    $(ErrorCode::$variant => f.write_str($msg),)*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "anchor"))]
+impl From<ErrorCode> for u32 {
+    fn from(error_code: ErrorCode) -> u32 {
+        ERROR_CODE_OFFSET + error_code as u32
+    }
+}
+
+#[cfg(not(feature = "anchor"))]
+impl std::error::Error for ErrorCode {}
+
+error_codes! {
+    #[msg("LOK")]
+    LOK,
+    #[msg("Not approved")]
+    NotApproved,
+    #[msg("invalid update amm config flag")]
+    InvalidUpdateConfigFlag,
+    #[msg("Account lack")]
+    AccountLack,
+    #[msg("Remove liquitity, collect fees owed and reward then you can close position account")]
+    ClosePositionErr,
+
+    #[msg("Minting amount should be greater than 0")]
+    ZeroMintAmount,
+
+    #[msg("Tick out of range")]
+    InvalidTickIndex,
+    #[msg("The lower tick must be below the upper tick")]
+    TickInvalidOrder,
+    #[msg("The tick must be greater, or equal to the minimum tick(-443636)")]
+    TickLowerOverflow,
+    #[msg("The tick must be lesser than, or equal to the maximum tick(443636)")]
+    TickUpperOverflow,
+    #[msg("tick % tick_spacing must be zero")]
+    TickAndSpacingNotMatch,
+    #[msg("Invalid tick array account")]
+    InvalidTickArray,
+    #[msg("Invalid tick array boundary")]
+    InvalidTickArrayBoundary,
+
+    #[msg("Square root price limit overflow")]
+    SqrtPriceLimitOverflow,
+    // second inequality must be < because the price can never reach the price at the max tick
+    #[msg("sqrt_price_x64 out of range")]
+    SqrtPriceX64,
+
+    // Liquidity Sub
+    #[msg("Liquidity sub delta L must be smaller than before")]
+    LiquiditySubValueErr,
+    // Liquidity Add
+    #[msg("Liquidity add delta L must be greater, or equal to before")]
+    LiquidityAddValueErr,
+    #[msg("Invalid liquidity when update position")]
+    InvalidLiquidity,
+    #[msg("Both token amount must not be zero while supply liquidity")]
+    ForbidBothZeroForSupplyLiquidity,
+    #[msg("Liquidity insufficient")]
+    LiquidityInsufficient,
+
+    /// swap errors
+    // Non fungible position manager
+    #[msg("Transaction too old")]
+    TransactionTooOld,
+    #[msg("Price slippage check")]
+    PriceSlippageCheck,
+    #[msg("Too little output received")]
+    TooLittleOutputReceived,
+    #[msg("Too much input paid")]
+    TooMuchInputPaid,
+    #[msg("Swap special amount can not be zero")]
+    ZeroAmountSpecified,
+    #[msg("Input pool vault is invalid")]
+    InvalidInputPoolVault,
+    #[msg("Swap input or output amount is too small")]
+    TooSmallInputOrOutputAmount,
+    #[msg("Not enought tick array account")]
+    NotEnoughTickArrayAccount,
+    #[msg("Invalid first tick array account")]
+    InvalidFirstTickArrayAccount,
+
+    /// reward errors
+    #[msg("Invalid reward index")]
+    InvalidRewardIndex,
+    #[msg("The init reward token reach to the max")]
+    FullRewardInfo,
+    #[msg("The init reward token already in use")]
+    RewardTokenAlreadyInUse,
+    #[msg("The reward tokens must contain one of pool vault mint except the last reward")]
+    ExceptRewardMint,
+    #[msg("Invalid reward init param")]
+    InvalidRewardInitParam,
+    #[msg("Invalid collect reward desired amount")]
+    InvalidRewardDesiredAmount,
+    #[msg("Invalid collect reward input account number")]
+    InvalidRewardInputAccountNumber,
+    #[msg("Invalid reward period")]
+    InvalidRewardPeriod,
+    #[msg(
+        "Modification of emissiones is allowed within 72 hours from the end of the previous cycle"
+    )]
+    NotApproveUpdateRewardEmissiones,
+    #[msg("uninitialized reward info")]
+    UnInitializedRewardInfo,
+
+    #[msg("Not support token_2022 mint extension")]
+    NotSupportMint,
+    #[msg("Missing tickarray bitmap extension account")]
+    MissingTickArrayBitmapExtensionAccount,
+    #[msg("Insufficient liquidity for this direction")]
+    InsufficientLiquidityForDirection,
+    #[msg("Max token overflow")]
+    MaxTokenOverflow,
+    #[msg("Calculate overflow")]
+    CalculateOverflow,
+    #[msg("TransferFee calculate not match")]
+    TransferFeeCalculateNotMatch,
+
+    #[msg("invalid account owner")]
+    IllegalAccountOwner,
+    #[msg("Invalid account")]
+    InvalidAccount,
+
+    #[msg("Invalid decay fee params")]
+    DecayFeeNeitherOnSellMint0NorMint1,
+
+    #[msg("Liquidity can only be moved between different pools of the same token pair")]
+    InvalidMoveLiquidityPools,
+
+    // Math errors of sqrt_price_math, liquidity_math and swap_math
+    // The input is not in the domain of the formula, e.g. zero price or liquidity, or an output larger than the reserves
+    #[msg("Math input out of range")]
+    InputOutOfRange,
+    // A value in the middle of the calculation overflows its integer type
+    #[msg("Math intermediate overflow")]
+    IntermediateOverflow,
+    // The token amount is computed, but it doesn't fit in u64
+    #[msg("Math result exceeds u64")]
+    ResultExceedsU64,
+
+    #[msg("Token-2022 confidential transfer mints are not supported, use a mint without the ConfidentialTransferMint extension")]
+    ConfidentialTransferNotSupported,
+
+    #[msg("Invalid bootstrap price floor, the decay fee must be on sell for exactly one mint and the initial price must not break the floor")]
+    InvalidBootstrapPriceFloor,
+    #[msg("The swap breaks the bootstrap price floor of the pool")]
+    BootstrapPriceFloorBreached,
+
+    #[msg("Invalid pool template")]
+    InvalidPoolTemplate,
+    #[msg("The amm config of the pool template has been updated since the template was created")]
+    PoolTemplateOutdated,
+
+    #[msg("The tick array account is not at the address derived from its pool and start tick index")]
+    InvalidTickArrayAddress,
+
+    #[msg("Invalid deployment plan")]
+    InvalidDeploymentPlan,
+    #[msg("The position doesn't match the next position of the deployment plan")]
+    DeploymentPositionMismatch,
+    #[msg("Not all positions of the deployment plan are deployed")]
+    DeploymentNotComplete,
+    #[msg("The deployed positions must be closed or emptied by the owner to abort the deployment")]
+    DeploymentPositionsNotEmptied,
+
+    #[msg("Invalid range limits, min_range_ticks must not be greater than max_range_ticks")]
+    InvalidRangeLimits,
+    #[msg("The position range is narrower than the min_range_ticks of the amm config")]
+    RangeTooNarrow,
+    #[msg("The position range is wider than the max_range_ticks of the amm config")]
+    RangeTooWide,
+    #[msg("The position liquidity was increased too recently to decrease liquidity or collect fees")]
+    PositionTooYoung,
+
+    #[msg("The pool is inactive, it takes no new deposits")]
+    PoolInactive,
+    #[msg("The pool has not been dormant long enough to be marked inactive")]
+    PoolNotDormant,
+
+    #[msg("The tick is initialized, only an uninitialized tick can be repaired or released")]
+    TickInitialized,
+
+    #[msg("The output token account must hold the output mint and be owned by the token program of the mint")]
+    InvalidOutputTokenAccount,
+    #[msg("A swap batch must have at least one and at most MAX_SWAP_BATCH_ORDERS orders")]
+    InvalidSwapBatchSize,
+
+    #[msg("The sqrt_price_limit of a zero_for_one swap must be below the current price")]
+    LimitAboveCurrentForZeroForOne,
+    #[msg("The sqrt_price_limit of a one_for_zero swap must be above the current price")]
+    LimitBelowCurrentForOneForZero,
+
+    #[msg("The trade fee rate must be a whole number of basis points below 100%")]
+    InvalidFeeRate,
+
+    #[msg("The sqrt price of the pool is out of the asserted bounds")]
+    PoolPriceOutOfBounds,
+    #[msg("The liquidity of the pool is below the asserted minimum")]
+    PoolLiquidityBelowMinimum,
+    #[msg("The last swap of the pool is older than the max slot age")]
+    PoolUpdateTooOld,
+    #[msg("The pool was swapped after the slot the quote was made off")]
+    PoolUpdatedAfterQuote,
+
+    #[msg("The recipient token accounts of a liquidation must be owned by the receiver of the collateral terms")]
+    InvalidCollateralReceiver,
+
+    #[msg("The dynamic tick array can't be reallocated beyond the size of a full tick array")]
+    DynTickArrayExceedsMaxLen,
+
+    #[msg("An amount of the swap settlement overflowed")]
+    SettlementOverflow,
+
+    #[msg("The liquidity of the tick overflows or exceeds the max liquidity per tick")]
+    TickLiquidityOverflow,
+
+    #[msg("The pool price doesn't meet the price condition of the swap")]
+    PriceConditionNotMet,
+
+    #[msg("The pool has liquidity or initialized tick arrays")]
+    PoolNotEmpty,
+
+    #[msg("The swap delegate is not approved by the token account or its allowance is insufficient")]
+    InsufficientDelegateAllowance,
+
+    #[msg("The swap fills mostly against the liquidity of the trader")]
+    SelfTradeNotAllowed,
+
+    #[msg("The status masks are empty or overlap")]
+    InvalidPoolStatusMask,
+
+    #[msg("The tick array is too close to the current tick or has too many ticks to be frozen")]
+    TickArrayNotFreezable,
+
+    #[msg("The ticks don't match the commitment of the frozen tick array")]
+    InvalidFrozenTicks,
+
+    #[msg("The tick array has initialized ticks")]
+    TickArrayNotEmpty,
+
+    #[msg("The vault position has not been out of range for long enough to be rebalanced")]
+    RebalanceNotDue,
+
+    #[msg("The position has no liquidity in range at the current price")]
+    PositionNotInRange,
+
+    #[msg("The route must have from 2 to 4 hops")]
+    InvalidRouteHopCount,
+
+    #[msg("The positions to collect must be 1 to 8 distinct positions of the pool")]
+    InvalidCollectPositions,
+
+    #[msg("The decay fee window is invalid or the decay fee has not ended")]
+    InvalidDecayFeeWindow,
+
+    #[msg("The price implied by the exact amounts deviates from the pool price beyond the tolerance")]
+    ExactAmountsPriceDeviation,
+
+    #[msg("The observation cardinality must grow")]
+    InvalidObservationCardinality,
+
+    #[msg("The max swap iterations is above the protocol max")]
+    InvalidMaxSwapIterations,
+
+    #[msg("The swap needs more steps than the max swap iterations")]
+    MaxSwapIterationsExceeded,
+
+    #[msg("The tick array can't pay the rent of the released tick, allocate it with get_or_create_tick_array first")]
+    TickArrayRentShortfall,
+
+    #[msg("The position is collateralized, only its collateral manager can decrease its liquidity until the terms are released")]
+    PositionCollateralized,
+
+    #[msg("The preallocated TickStates of a tick array are above the prealloc tick count of the pool")]
+    InvalidPreallocTickCount,
+
+    #[msg("The sqrt price limit must be within (MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64)")]
+    SqrtPriceLimitOutOfBounds,
+
+    #[msg("The tick spacing of the amm config doesn't match the new tick spacing of the pool")]
+    AmmConfigTickSpacingMismatch,
+
+    #[msg("The liquidity of the vault position was not moved into the new position")]
+    RebalanceLiquidityNotMoved,
+}
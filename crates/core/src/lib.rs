@@ -0,0 +1,20 @@
+//! The math, the error codes and the plain account layouts of the Byreal CLMM, without the program entrypoint
+//! and the token programs, so that off-chain services can quote and settle without building the program.
+//! The program crate re-exports the math and the error codes as `byreal_clmm::libraries` and `byreal_clmm::error`.
+//!
+//! anchor-lang is only a dependency with the `anchor` feature, which the program enables: the error codes are then
+//! an anchor `#[error_code]` and the math returns the anchor `Result`. Without it they are plain Rust.
+//!
+//! The anchor accounts (`PoolState`, `TickArrayState`, ...) stay in the program crate: their account derives
+//! are owned by the program id and their methods are inherent impls of the program, which can't be declared
+//! on a type of another crate. `states` has their plain layouts, which the program tests keep in sync with them.
+// without the anchor feature `Error` is `ErrorCode`, converting an error code into it is the identity
+#![cfg_attr(not(feature = "anchor"), allow(clippy::useless_conversion))]
+pub mod error;
+pub mod libraries;
+mod macros;
+pub mod quote;
+pub mod states;
+pub mod transfer_fee;
+
+use core as core_;
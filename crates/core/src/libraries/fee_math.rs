@@ -0,0 +1,105 @@
+//! Conversions between the fee rates, in hundredths of a bip (10^-6), and basis points, and the decay fee rate
+use super::full_math::MulDiv;
+use crate::error::ErrorCode;
+use crate::error::Result;
+use crate::macros::require;
+
+/// The denominator of the fee rates, a fee rate of 1_000_000 is 100%
+pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
+
+/// The basis points in a whole
+pub const BPS_DENOMINATOR_VALUE: u32 = 10_000;
+
+/// The fee rate of one basis point
+pub const FEE_RATE_PER_BPS: u32 = FEE_RATE_DENOMINATOR_VALUE / BPS_DENOMINATOR_VALUE;
+
+// a basis point must be a whole number of fee rate units
+#[allow(clippy::manual_is_multiple_of)] // is_multiple_of is newer than the rustc of the Solana platform tools
+const _: () = assert!(FEE_RATE_DENOMINATOR_VALUE % BPS_DENOMINATOR_VALUE == 0);
+
+/// The trade fee rates of the common fee tiers
+pub const FEE_RATE_1_BPS: u32 = bps_to_fee_rate(1);
+pub const FEE_RATE_5_BPS: u32 = bps_to_fee_rate(5);
+pub const FEE_RATE_30_BPS: u32 = bps_to_fee_rate(30);
+pub const FEE_RATE_100_BPS: u32 = bps_to_fee_rate(100);
+
+/// Convert basis points to a fee rate, usable in constants
+pub const fn bps_to_fee_rate(bps: u16) -> u32 {
+    bps as u32 * FEE_RATE_PER_BPS
+}
+
+/// Convert a fee rate to basis points, the fee rate must be a whole number of basis points not above 100%
+#[allow(clippy::manual_is_multiple_of)] // is_multiple_of is newer than the rustc of the Solana platform tools
+pub fn fee_rate_to_bps(fee_rate: u32) -> Result<u16> {
+    require!(
+        fee_rate % FEE_RATE_PER_BPS == 0 && fee_rate <= FEE_RATE_DENOMINATOR_VALUE,
+        ErrorCode::InvalidFeeRate
+    );
+    Ok((fee_rate / FEE_RATE_PER_BPS) as u16)
+}
+
+/// The decay fee rate of a pool `interval_count` decrease intervals after its open time,
+/// `init_fee_rate` percent decreased by `decrease_rate` percent at each interval, rounded up
+pub fn decay_fee_rate(init_fee_rate: u8, decrease_rate: u8, interval_count: u64) -> u32 {
+    let decay_fee_decrease_rate = decrease_rate as u64 * 10_000;
+
+    // 10^6
+    let hundredths_of_a_bip = FEE_RATE_DENOMINATOR_VALUE as u64;
+    let mut rate = hundredths_of_a_bip;
+    // use `fast-power` to calculate (1-x)^c
+    // x = decay_fee_decrease_rate / 10^6
+    // c = interval_count
+    {
+        let mut exp = interval_count;
+        let mut base = hundredths_of_a_bip
+            .checked_sub(decay_fee_decrease_rate)
+            .unwrap();
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                rate = rate.mul_div_ceil(base, hundredths_of_a_bip).unwrap();
+            }
+            base = base.mul_div_ceil(base, hundredths_of_a_bip).unwrap();
+            exp /= 2;
+        }
+    }
+
+    // because init_fee_rate is in percentage, we need to divide it by 100
+    rate = rate.mul_div_ceil(init_fee_rate as u64, 100u64).unwrap();
+
+    rate as u32
+}
+
+#[cfg(test)]
+mod fee_math_test {
+    use super::*;
+
+    #[test]
+    fn fee_tier_test() {
+        assert_eq!(FEE_RATE_1_BPS, 100);
+        assert_eq!(FEE_RATE_5_BPS, 500);
+        assert_eq!(FEE_RATE_30_BPS, 3000);
+        assert_eq!(FEE_RATE_100_BPS, 10000);
+        for bps in [0, 1, 5, 30, 100, 10_000] {
+            assert_eq!(fee_rate_to_bps(bps_to_fee_rate(bps)).unwrap(), bps);
+        }
+    }
+
+    #[test]
+    fn fee_rate_to_bps_test() {
+        assert_eq!(fee_rate_to_bps(2500).unwrap(), 25);
+        assert_eq!(fee_rate_to_bps(FEE_RATE_DENOMINATOR_VALUE).unwrap(), 10_000);
+        // a fraction of a basis point
+        for fee_rate in [1, 150, 2550] {
+            assert_eq!(
+                fee_rate_to_bps(fee_rate).err(),
+                Some(ErrorCode::InvalidFeeRate.into())
+            );
+        }
+        // above 100%
+        assert_eq!(
+            fee_rate_to_bps(FEE_RATE_DENOMINATOR_VALUE + FEE_RATE_PER_BPS).err(),
+            Some(ErrorCode::InvalidFeeRate.into())
+        );
+    }
+}
@@ -83,6 +83,8 @@ pub trait MulDiv<RHS = Self> {
     fn to_underflow_u64(self) -> u64;
 }
 
+// the big numbers are Copy, the casts take them by value like the primitive `as` casts
+#[allow(clippy::wrong_self_convention)]
 pub trait Upcast256 {
     fn as_u256(self) -> U256;
 }
@@ -92,6 +94,8 @@ impl Upcast256 for U128 {
     }
 }
 
+// the big numbers are Copy, the casts take them by value like the primitive `as` casts
+#[allow(clippy::wrong_self_convention)]
 pub trait Downcast256 {
     /// Unsafe cast to U128
     /// Bits beyond the 128th position are lost
@@ -103,6 +107,8 @@ impl Downcast256 for U256 {
     }
 }
 
+// the big numbers are Copy, the casts take them by value like the primitive `as` casts
+#[allow(clippy::wrong_self_convention)]
 pub trait Upcast512 {
     fn as_u512(self) -> U512;
 }
@@ -112,6 +118,8 @@ impl Upcast512 for U256 {
     }
 }
 
+// the big numbers are Copy, the casts take them by value like the primitive `as` casts
+#[allow(clippy::wrong_self_convention)]
 pub trait Downcast512 {
     /// Unsafe cast to U256
     /// Bits beyond the 256th position are lost
@@ -4,8 +4,10 @@ use super::fixed_point_64;
 use super::full_math::MulDiv;
 use super::tick_math;
 use super::unsafe_math::UnsafeMathTrait;
+use super::{Liquidity, SqrtPriceX64, Tick};
 use crate::error::ErrorCode;
-use anchor_lang::prelude::*;
+use crate::error::Result;
+use crate::macros::*;
 
 /// Add a signed liquidity delta to liquidity and revert if it overflows or underflows
 ///
@@ -14,7 +16,7 @@ use anchor_lang::prelude::*;
 /// * `x` - The liquidity (L) before change
 /// * `y` - The delta (ΔL) by which liquidity should be changed
 ///
-pub fn add_delta(x: u128, y: i128) -> Result<u128> {
+pub fn add_delta_raw(x: u128, y: i128) -> Result<u128> {
     let z: u128;
     if y < 0 {
         z = x - u128::try_from(-y).unwrap();
@@ -29,7 +31,7 @@ pub fn add_delta(x: u128, y: i128) -> Result<u128> {
 
 /// Computes the amount of liquidity received for a given amount of token_0 and price range
 /// Calculates ΔL = Δx (√P_upper x √P_lower)/(√P_upper - √P_lower)
-pub fn get_liquidity_from_amount_0(
+pub fn get_liquidity_from_amount_0_raw(
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
     amount_0: u64,
@@ -56,7 +58,7 @@ pub fn get_liquidity_from_amount_0(
 
 /// Computes the amount of liquidity received for a given amount of token_1 and price range
 /// Calculates ΔL = Δy / (√P_upper - √P_lower)
-pub fn get_liquidity_from_amount_1(
+pub fn get_liquidity_from_amount_1_raw(
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
     amount_1: u64,
@@ -77,7 +79,7 @@ pub fn get_liquidity_from_amount_1(
 
 /// Computes the maximum amount of liquidity received for a given amount of token_0, token_1, the current
 /// pool prices and the prices at the tick boundaries
-pub fn get_liquidity_from_amounts(
+pub fn get_liquidity_from_amounts_raw(
     sqrt_ratio_x64: u128,
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
@@ -91,23 +93,23 @@ pub fn get_liquidity_from_amounts(
 
     if sqrt_ratio_x64 <= sqrt_ratio_a_x64 {
         // If P ≤ P_lower, only token_0 liquidity is active
-        get_liquidity_from_amount_0(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_0)
+        get_liquidity_from_amount_0_raw(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_0)
     } else if sqrt_ratio_x64 < sqrt_ratio_b_x64 {
         // If P_lower < P < P_upper, active liquidity is the minimum of the liquidity provided
         // by token_0 and token_1
         u128::min(
-            get_liquidity_from_amount_0(sqrt_ratio_x64, sqrt_ratio_b_x64, amount_0),
-            get_liquidity_from_amount_1(sqrt_ratio_a_x64, sqrt_ratio_x64, amount_1),
+            get_liquidity_from_amount_0_raw(sqrt_ratio_x64, sqrt_ratio_b_x64, amount_0),
+            get_liquidity_from_amount_1_raw(sqrt_ratio_a_x64, sqrt_ratio_x64, amount_1),
         )
     } else {
         // If P ≥ P_upper, only token_1 liquidity is active
-        get_liquidity_from_amount_1(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_1)
+        get_liquidity_from_amount_1_raw(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_1)
     }
 }
 
 /// Computes the maximum amount of liquidity received for a given amount of token_0, token_1, the current
 /// pool prices and the prices at the tick boundaries
-pub fn get_liquidity_from_single_amount_0(
+pub fn get_liquidity_from_single_amount_0_raw(
     sqrt_ratio_x64: u128,
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
@@ -120,11 +122,11 @@ pub fn get_liquidity_from_single_amount_0(
 
     if sqrt_ratio_x64 <= sqrt_ratio_a_x64 {
         // If P ≤ P_lower, only token_0 liquidity is active
-        get_liquidity_from_amount_0(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_0)
+        get_liquidity_from_amount_0_raw(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_0)
     } else if sqrt_ratio_x64 < sqrt_ratio_b_x64 {
         // If P_lower < P < P_upper, active liquidity is the minimum of the liquidity provided
         // by token_0 and token_1
-        get_liquidity_from_amount_0(sqrt_ratio_x64, sqrt_ratio_b_x64, amount_0)
+        get_liquidity_from_amount_0_raw(sqrt_ratio_x64, sqrt_ratio_b_x64, amount_0)
     } else {
         // If P ≥ P_upper, only token_1 liquidity is active
         0
@@ -133,7 +135,7 @@ pub fn get_liquidity_from_single_amount_0(
 
 /// Computes the maximum amount of liquidity received for a given amount of token_0, token_1, the current
 /// pool prices and the prices at the tick boundaries
-pub fn get_liquidity_from_single_amount_1(
+pub fn get_liquidity_from_single_amount_1_raw(
     sqrt_ratio_x64: u128,
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
@@ -150,20 +152,21 @@ pub fn get_liquidity_from_single_amount_1(
     } else if sqrt_ratio_x64 < sqrt_ratio_b_x64 {
         // If P_lower < P < P_upper, active liquidity is the minimum of the liquidity provided
         // by token_0 and token_1
-        get_liquidity_from_amount_1(sqrt_ratio_a_x64, sqrt_ratio_x64, amount_1)
+        get_liquidity_from_amount_1_raw(sqrt_ratio_a_x64, sqrt_ratio_x64, amount_1)
     } else {
         // If P ≥ P_upper, only token_1 liquidity is active
-        get_liquidity_from_amount_1(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_1)
+        get_liquidity_from_amount_1_raw(sqrt_ratio_a_x64, sqrt_ratio_b_x64, amount_1)
     }
 }
 
 /// Gets the delta amount_0 for given liquidity and price range
+/// Returns `ResultExceedsU64` if the amount doesn't fit in u64
 ///
 /// # Formula
 ///
 /// * `Δx = L * (1 / √P_lower - 1 / √P_upper)`
 /// * i.e. `L * (√P_upper - √P_lower) / (√P_upper * √P_lower)`
-pub fn get_delta_amount_0_unsigned(
+pub fn get_delta_amount_0_unsigned_raw(
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
     liquidity: u128,
@@ -177,30 +180,31 @@ pub fn get_delta_amount_0_unsigned(
     let numerator_1 = U256::from(liquidity) << fixed_point_64::RESOLUTION;
     let numerator_2 = U256::from(sqrt_ratio_b_x64 - sqrt_ratio_a_x64);
 
-    assert!(sqrt_ratio_a_x64 > 0);
+    require!(sqrt_ratio_a_x64 > 0, ErrorCode::InputOutOfRange);
 
     let result = if round_up {
         U256::div_rounding_up(
             numerator_1
                 .mul_div_ceil(numerator_2, U256::from(sqrt_ratio_b_x64))
-                .unwrap(),
+                .ok_or(ErrorCode::IntermediateOverflow)?,
             U256::from(sqrt_ratio_a_x64),
         )
     } else {
         numerator_1
             .mul_div_floor(numerator_2, U256::from(sqrt_ratio_b_x64))
-            .unwrap()
+            .ok_or(ErrorCode::IntermediateOverflow)?
             / U256::from(sqrt_ratio_a_x64)
     };
     if result > U256::from(u64::MAX) {
-        return Err(ErrorCode::MaxTokenOverflow.into());
+        return Err(ErrorCode::ResultExceedsU64.into());
     }
-    return Ok(result.as_u64());
+    Ok(result.as_u64())
 }
 
 /// Gets the delta amount_1 for given liquidity and price range
+/// Returns `ResultExceedsU64` if the amount doesn't fit in u64
 /// * `Δy = L (√P_upper - √P_lower)`
-pub fn get_delta_amount_1_unsigned(
+pub fn get_delta_amount_1_unsigned_raw(
     mut sqrt_ratio_a_x64: u128,
     mut sqrt_ratio_b_x64: u128,
     liquidity: u128,
@@ -222,28 +226,28 @@ pub fn get_delta_amount_1_unsigned(
             U256::from(fixed_point_64::Q64),
         )
     }
-    .unwrap();
+    .ok_or(ErrorCode::IntermediateOverflow)?;
     if result > U256::from(u64::MAX) {
-        return Err(ErrorCode::MaxTokenOverflow.into());
+        return Err(ErrorCode::ResultExceedsU64.into());
     }
-    return Ok(result.as_u64());
+    Ok(result.as_u64())
 }
 
 /// Helper function to get signed delta amount_0 for given liquidity and price range
-pub fn get_delta_amount_0_signed(
+pub fn get_delta_amount_0_signed_raw(
     sqrt_ratio_a_x64: u128,
     sqrt_ratio_b_x64: u128,
     liquidity: i128,
 ) -> Result<u64> {
     if liquidity < 0 {
-        get_delta_amount_0_unsigned(
+        get_delta_amount_0_unsigned_raw(
             sqrt_ratio_a_x64,
             sqrt_ratio_b_x64,
             u128::try_from(-liquidity).unwrap(),
             false,
         )
     } else {
-        get_delta_amount_0_unsigned(
+        get_delta_amount_0_unsigned_raw(
             sqrt_ratio_a_x64,
             sqrt_ratio_b_x64,
             u128::try_from(liquidity).unwrap(),
@@ -253,20 +257,20 @@ pub fn get_delta_amount_0_signed(
 }
 
 /// Helper function to get signed delta amount_1 for given liquidity and price range
-pub fn get_delta_amount_1_signed(
+pub fn get_delta_amount_1_signed_raw(
     sqrt_ratio_a_x64: u128,
     sqrt_ratio_b_x64: u128,
     liquidity: i128,
 ) -> Result<u64> {
     if liquidity < 0 {
-        get_delta_amount_1_unsigned(
+        get_delta_amount_1_unsigned_raw(
             sqrt_ratio_a_x64,
             sqrt_ratio_b_x64,
             u128::try_from(-liquidity).unwrap(),
             false,
         )
     } else {
-        get_delta_amount_1_unsigned(
+        get_delta_amount_1_unsigned_raw(
             sqrt_ratio_a_x64,
             sqrt_ratio_b_x64,
             u128::try_from(liquidity).unwrap(),
@@ -275,7 +279,7 @@ pub fn get_delta_amount_1_signed(
     }
 }
 
-pub fn get_delta_amounts_signed(
+pub fn get_delta_amounts_signed_raw(
     tick_current: i32,
     sqrt_price_x64_current: u128,
     tick_lower: i32,
@@ -285,32 +289,175 @@ pub fn get_delta_amounts_signed(
     let mut amount_0 = 0;
     let mut amount_1 = 0;
     if tick_current < tick_lower {
-        amount_0 = get_delta_amount_0_signed(
-            tick_math::get_sqrt_price_at_tick(tick_lower)?,
-            tick_math::get_sqrt_price_at_tick(tick_upper)?,
+        amount_0 = get_delta_amount_0_signed_raw(
+            tick_math::get_sqrt_price_at_tick_raw(tick_lower)?,
+            tick_math::get_sqrt_price_at_tick_raw(tick_upper)?,
             liquidity_delta,
         )
         .unwrap();
     } else if tick_current < tick_upper {
-        amount_0 = get_delta_amount_0_signed(
+        amount_0 = get_delta_amount_0_signed_raw(
             sqrt_price_x64_current,
-            tick_math::get_sqrt_price_at_tick(tick_upper)?,
+            tick_math::get_sqrt_price_at_tick_raw(tick_upper)?,
             liquidity_delta,
         )
         .unwrap();
-        amount_1 = get_delta_amount_1_signed(
-            tick_math::get_sqrt_price_at_tick(tick_lower)?,
+        amount_1 = get_delta_amount_1_signed_raw(
+            tick_math::get_sqrt_price_at_tick_raw(tick_lower)?,
             sqrt_price_x64_current,
             liquidity_delta,
         )
         .unwrap();
     } else {
-        amount_1 = get_delta_amount_1_signed(
-            tick_math::get_sqrt_price_at_tick(tick_lower)?,
-            tick_math::get_sqrt_price_at_tick(tick_upper)?,
+        amount_1 = get_delta_amount_1_signed_raw(
+            tick_math::get_sqrt_price_at_tick_raw(tick_lower)?,
+            tick_math::get_sqrt_price_at_tick_raw(tick_upper)?,
             liquidity_delta,
         )
         .unwrap();
     }
     Ok((amount_0, amount_1))
 }
+
+/// Add a signed liquidity delta to liquidity, see `add_delta_raw`
+pub fn add_delta(liquidity: Liquidity, liquidity_delta: i128) -> Result<Liquidity> {
+    add_delta_raw(liquidity.get(), liquidity_delta).map(Liquidity)
+}
+
+/// Computes the liquidity of an amount of token_0 in a price range, see `get_liquidity_from_amount_0_raw`
+pub fn get_liquidity_from_amount_0(
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    amount_0: u64,
+) -> Liquidity {
+    Liquidity(get_liquidity_from_amount_0_raw(
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        amount_0,
+    ))
+}
+
+/// Computes the liquidity of an amount of token_1 in a price range, see `get_liquidity_from_amount_1_raw`
+pub fn get_liquidity_from_amount_1(
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    amount_1: u64,
+) -> Liquidity {
+    Liquidity(get_liquidity_from_amount_1_raw(
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        amount_1,
+    ))
+}
+
+/// Computes the max liquidity of the amounts at the current price, see `get_liquidity_from_amounts_raw`
+pub fn get_liquidity_from_amounts(
+    sqrt_price: SqrtPriceX64,
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    amount_0: u64,
+    amount_1: u64,
+) -> Liquidity {
+    Liquidity(get_liquidity_from_amounts_raw(
+        sqrt_price.get(),
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        amount_0,
+        amount_1,
+    ))
+}
+
+/// Computes the liquidity of an amount of token_0 at the current price, see `get_liquidity_from_single_amount_0_raw`
+pub fn get_liquidity_from_single_amount_0(
+    sqrt_price: SqrtPriceX64,
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    amount_0: u64,
+) -> Liquidity {
+    Liquidity(get_liquidity_from_single_amount_0_raw(
+        sqrt_price.get(),
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        amount_0,
+    ))
+}
+
+/// Computes the liquidity of an amount of token_1 at the current price, see `get_liquidity_from_single_amount_1_raw`
+pub fn get_liquidity_from_single_amount_1(
+    sqrt_price: SqrtPriceX64,
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    amount_1: u64,
+) -> Liquidity {
+    Liquidity(get_liquidity_from_single_amount_1_raw(
+        sqrt_price.get(),
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        amount_1,
+    ))
+}
+
+/// Gets the amount_0 of a liquidity in a price range, see `get_delta_amount_0_unsigned_raw`
+pub fn get_delta_amount_0_unsigned(
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    liquidity: Liquidity,
+    round_up: bool,
+) -> Result<u64> {
+    get_delta_amount_0_unsigned_raw(
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        liquidity.get(),
+        round_up,
+    )
+}
+
+/// Gets the amount_1 of a liquidity in a price range, see `get_delta_amount_1_unsigned_raw`
+pub fn get_delta_amount_1_unsigned(
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    liquidity: Liquidity,
+    round_up: bool,
+) -> Result<u64> {
+    get_delta_amount_1_unsigned_raw(
+        sqrt_price_a.get(),
+        sqrt_price_b.get(),
+        liquidity.get(),
+        round_up,
+    )
+}
+
+/// Gets the amount_0 of a signed liquidity delta in a price range, see `get_delta_amount_0_signed_raw`
+pub fn get_delta_amount_0_signed(
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    liquidity_delta: i128,
+) -> Result<u64> {
+    get_delta_amount_0_signed_raw(sqrt_price_a.get(), sqrt_price_b.get(), liquidity_delta)
+}
+
+/// Gets the amount_1 of a signed liquidity delta in a price range, see `get_delta_amount_1_signed_raw`
+pub fn get_delta_amount_1_signed(
+    sqrt_price_a: SqrtPriceX64,
+    sqrt_price_b: SqrtPriceX64,
+    liquidity_delta: i128,
+) -> Result<u64> {
+    get_delta_amount_1_signed_raw(sqrt_price_a.get(), sqrt_price_b.get(), liquidity_delta)
+}
+
+/// Gets the amounts of a signed liquidity delta of a position, see `get_delta_amounts_signed_raw`
+pub fn get_delta_amounts_signed(
+    tick_current: Tick,
+    sqrt_price_current: SqrtPriceX64,
+    tick_lower: Tick,
+    tick_upper: Tick,
+    liquidity_delta: i128,
+) -> Result<(u64, u64)> {
+    get_delta_amounts_signed_raw(
+        tick_current.get(),
+        sqrt_price_current.get(),
+        tick_lower.get(),
+        tick_upper.get(),
+        liquidity_delta,
+    )
+}
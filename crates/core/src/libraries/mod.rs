@@ -0,0 +1,26 @@
+pub mod big_num;
+pub mod fee_math;
+pub mod fixed_point_64;
+pub mod full_math;
+pub mod liquidity_math;
+pub mod settle_math;
+pub mod sqrt_price_math;
+pub mod swap_math;
+pub mod tick_math;
+pub mod units;
+pub mod unsafe_math;
+
+#[cfg(all(test, feature = "reference-math"))]
+mod reference_math;
+
+pub use big_num::*;
+pub use fee_math::*;
+pub use fixed_point_64::*;
+pub use full_math::*;
+pub use liquidity_math::*;
+pub use settle_math::*;
+pub use sqrt_price_math::*;
+pub use swap_math::*;
+pub use tick_math::*;
+pub use units::*;
+pub use unsafe_math::*;
@@ -0,0 +1,431 @@
+//! Differential fuzzing of the swap math against a reference implementation, test only:
+//! `cargo test -p byreal-clmm-core --features reference-math`
+//!
+//! The reference is a port of the canonical Uniswap v3 formulas in Q64.64, evaluated exactly in U512
+//! so that no intermediate overflows and every rounding is a single floor or ceil of the exact value.
+//! It shares none of the crate's `mul_div`, rounding helpers or overflow fallbacks, so an optimization
+//! of those has to keep the results identical, except for the deltas codified in the tests below.
+
+use super::big_num::U512;
+use super::fee_math::FEE_RATE_DENOMINATOR_VALUE;
+
+/// The reference formulas, `None` when the exact result doesn't fit its type or is out of range
+mod reference {
+    use super::*;
+
+    const RESOLUTION: usize = 64;
+
+    fn wide(value: u128) -> U512 {
+        U512::from(value)
+    }
+
+    fn div_floor(numerator: U512, denominator: U512) -> U512 {
+        numerator / denominator
+    }
+
+    fn div_ceil(numerator: U512, denominator: U512) -> U512 {
+        let quotient = numerator / denominator;
+        if quotient * denominator == numerator {
+            quotient
+        } else {
+            quotient + U512::one()
+        }
+    }
+
+    fn narrow_u64(value: U512) -> Option<u64> {
+        if value > wide(u64::MAX as u128) {
+            None
+        } else {
+            Some(value.as_u64())
+        }
+    }
+
+    fn narrow_u128(value: U512) -> Option<u128> {
+        if value > wide(u128::MAX) {
+            None
+        } else {
+            Some(value.as_u128())
+        }
+    }
+
+    /// `Δx = L * (√P_b - √P_a) / (√P_a * √P_b)`
+    pub fn get_delta_amount_0(
+        sqrt_price_a_x64: u128,
+        sqrt_price_b_x64: u128,
+        liquidity: u128,
+        round_up: bool,
+    ) -> Option<u64> {
+        let (lower, upper) = if sqrt_price_a_x64 < sqrt_price_b_x64 {
+            (sqrt_price_a_x64, sqrt_price_b_x64)
+        } else {
+            (sqrt_price_b_x64, sqrt_price_a_x64)
+        };
+        if lower == 0 {
+            return None;
+        }
+        let numerator = (wide(liquidity) << RESOLUTION) * wide(upper - lower);
+        let denominator = wide(lower) * wide(upper);
+        narrow_u64(if round_up {
+            div_ceil(numerator, denominator)
+        } else {
+            div_floor(numerator, denominator)
+        })
+    }
+
+    /// `Δy = L * (√P_b - √P_a)`
+    pub fn get_delta_amount_1(
+        sqrt_price_a_x64: u128,
+        sqrt_price_b_x64: u128,
+        liquidity: u128,
+        round_up: bool,
+    ) -> Option<u64> {
+        let numerator = wide(liquidity) * wide(sqrt_price_a_x64.abs_diff(sqrt_price_b_x64));
+        let denominator = U512::one() << RESOLUTION;
+        narrow_u64(if round_up {
+            div_ceil(numerator, denominator)
+        } else {
+            div_floor(numerator, denominator)
+        })
+    }
+
+    /// `√P' = L * √P / (L ± Δx * √P)`, rounded up
+    pub fn get_next_sqrt_price_from_amount_0(
+        sqrt_price_x64: u128,
+        liquidity: u128,
+        amount: u64,
+        add: bool,
+    ) -> Option<u128> {
+        if amount == 0 {
+            return Some(sqrt_price_x64);
+        }
+        let numerator = wide(liquidity) << RESOLUTION;
+        let product = wide(amount as u128) * wide(sqrt_price_x64);
+        let denominator = if add {
+            numerator + product
+        } else if numerator > product {
+            numerator - product
+        } else {
+            return None;
+        };
+        narrow_u128(div_ceil(numerator * wide(sqrt_price_x64), denominator))
+    }
+
+    /// `√P' = √P ± Δy / L`, rounded down
+    pub fn get_next_sqrt_price_from_amount_1(
+        sqrt_price_x64: u128,
+        liquidity: u128,
+        amount: u64,
+        add: bool,
+    ) -> Option<u128> {
+        let amount = wide(amount as u128) << RESOLUTION;
+        if add {
+            narrow_u128(wide(sqrt_price_x64) + div_floor(amount, wide(liquidity)))
+        } else {
+            let quotient = div_ceil(amount, wide(liquidity));
+            if quotient > wide(sqrt_price_x64) {
+                return None;
+            }
+            narrow_u128(wide(sqrt_price_x64) - quotient)
+        }
+    }
+
+    pub fn get_next_sqrt_price_from_input(
+        sqrt_price_x64: u128,
+        liquidity: u128,
+        amount_in: u64,
+        zero_for_one: bool,
+    ) -> Option<u128> {
+        if sqrt_price_x64 == 0 || liquidity == 0 {
+            return None;
+        }
+        if zero_for_one {
+            get_next_sqrt_price_from_amount_0(sqrt_price_x64, liquidity, amount_in, true)
+        } else {
+            get_next_sqrt_price_from_amount_1(sqrt_price_x64, liquidity, amount_in, true)
+        }
+    }
+
+    pub fn get_next_sqrt_price_from_output(
+        sqrt_price_x64: u128,
+        liquidity: u128,
+        amount_out: u64,
+        zero_for_one: bool,
+    ) -> Option<u128> {
+        if sqrt_price_x64 == 0 || liquidity == 0 {
+            return None;
+        }
+        if zero_for_one {
+            get_next_sqrt_price_from_amount_1(sqrt_price_x64, liquidity, amount_out, false)
+        } else {
+            get_next_sqrt_price_from_amount_0(sqrt_price_x64, liquidity, amount_out, false)
+        }
+    }
+
+    /// (sqrt_price_next_x64, amount_in, amount_out, fee_amount) of `SwapMath.computeSwapStep`
+    pub fn compute_swap_step(
+        sqrt_price_current_x64: u128,
+        sqrt_price_target_x64: u128,
+        liquidity: u128,
+        amount_remaining: u64,
+        fee_rate: u32,
+        is_base_input: bool,
+        zero_for_one: bool,
+    ) -> Option<(u128, u64, u64, u64)> {
+        let fee_denominator = wide(FEE_RATE_DENOMINATOR_VALUE as u128);
+        // the amount to reach the target may not fit u64, the target is then out of reach
+        let amount_to_target = if is_base_input == zero_for_one {
+            get_delta_amount_0(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                is_base_input,
+            )
+        } else {
+            get_delta_amount_1(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                is_base_input,
+            )
+        };
+        let amount_remaining_less_fee = if is_base_input {
+            narrow_u64(div_floor(
+                wide(amount_remaining as u128)
+                    * wide((FEE_RATE_DENOMINATOR_VALUE - fee_rate) as u128),
+                fee_denominator,
+            ))?
+        } else {
+            amount_remaining
+        };
+        let max = amount_to_target.is_some_and(|amount| amount_remaining_less_fee >= amount);
+        let sqrt_price_next_x64 = if max {
+            sqrt_price_target_x64
+        } else if is_base_input {
+            get_next_sqrt_price_from_input(
+                sqrt_price_current_x64,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?
+        } else {
+            get_next_sqrt_price_from_output(
+                sqrt_price_current_x64,
+                liquidity,
+                amount_remaining,
+                zero_for_one,
+            )?
+        };
+        let max = sqrt_price_next_x64 == sqrt_price_target_x64;
+
+        let (amount_in, amount_out) = if zero_for_one {
+            (
+                if max && is_base_input {
+                    amount_to_target?
+                } else {
+                    get_delta_amount_0(
+                        sqrt_price_next_x64,
+                        sqrt_price_current_x64,
+                        liquidity,
+                        true,
+                    )?
+                },
+                if max && !is_base_input {
+                    amount_to_target?
+                } else {
+                    get_delta_amount_1(
+                        sqrt_price_next_x64,
+                        sqrt_price_current_x64,
+                        liquidity,
+                        false,
+                    )?
+                },
+            )
+        } else {
+            (
+                if max && is_base_input {
+                    amount_to_target?
+                } else {
+                    get_delta_amount_1(
+                        sqrt_price_current_x64,
+                        sqrt_price_next_x64,
+                        liquidity,
+                        true,
+                    )?
+                },
+                if max && !is_base_input {
+                    amount_to_target?
+                } else {
+                    get_delta_amount_0(
+                        sqrt_price_current_x64,
+                        sqrt_price_next_x64,
+                        liquidity,
+                        false,
+                    )?
+                },
+            )
+        };
+        let amount_out = if is_base_input {
+            amount_out
+        } else {
+            amount_out.min(amount_remaining)
+        };
+        let fee_amount = if is_base_input && !max {
+            amount_remaining.checked_sub(amount_in)?
+        } else {
+            narrow_u64(div_ceil(
+                wide(amount_in as u128) * wide(fee_rate as u128),
+                wide((FEE_RATE_DENOMINATOR_VALUE - fee_rate) as u128),
+            ))?
+        };
+        Some((sqrt_price_next_x64, amount_in, amount_out, fee_amount))
+    }
+
+    /// `√(1.0001^tick) * 2^64` in f64, the relative error grows with |tick| from the rounding of 1.0001
+    pub fn get_sqrt_price_at_tick(tick: i32) -> f64 {
+        1.0001f64.powf(tick as f64 / 2.0) * 2f64.powi(64)
+    }
+
+    /// `⌊log_1.0001(P)⌋` in f64, it can be off by one tick next to a tick boundary
+    pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> i32 {
+        let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+        (2.0 * sqrt_price.ln() / 1.0001f64.ln()).floor() as i32
+    }
+}
+
+#[cfg(test)]
+mod reference_math_test {
+    use super::*;
+    use crate::libraries::{liquidity_math, sqrt_price_math, swap_math, tick_math};
+    use proptest::prelude::*;
+
+    /// The max relative error of `get_sqrt_price_at_tick` to the f64 reference, 2.3e-10 next to MAX_TICK
+    /// over all ticks: the truncated magic constants of the tick math plus the f64 rounding of 1.0001
+    /// raised to at most |MAX_TICK| / 2
+    const MAX_TICK_PRICE_RELATIVE_ERROR: f64 = 3e-10;
+
+    fn sqrt_price() -> impl Strategy<Value = u128> {
+        tick_math::MIN_SQRT_PRICE_X64..tick_math::MAX_SQRT_PRICE_X64
+    }
+
+    fn liquidity() -> impl Strategy<Value = u128> {
+        prop_oneof![1..u64::MAX as u128, 1..u128::MAX]
+    }
+
+    fn amount() -> impl Strategy<Value = u64> {
+        prop_oneof![0..u32::MAX as u64, 0..u64::MAX]
+    }
+
+    proptest! {
+        // the delta amounts are exact to the rounding direction, no delta is allowed
+        #[test]
+        fn get_delta_amount_test(
+            sqrt_price_a_x64 in sqrt_price(),
+            sqrt_price_b_x64 in sqrt_price(),
+            liquidity in liquidity(),
+            round_up in proptest::bool::ANY,
+        ) {
+            prop_assert_eq!(
+                liquidity_math::get_delta_amount_0_unsigned_raw(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, round_up).ok(),
+                reference::get_delta_amount_0(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, round_up)
+            );
+            prop_assert_eq!(
+                liquidity_math::get_delta_amount_1_unsigned_raw(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, round_up).ok(),
+                reference::get_delta_amount_1(sqrt_price_a_x64, sqrt_price_b_x64, liquidity, round_up)
+            );
+        }
+
+        // the next prices are exact to the rounding direction, no delta is allowed
+        #[test]
+        fn get_next_sqrt_price_test(
+            sqrt_price_x64 in sqrt_price(),
+            liquidity in liquidity(),
+            amount in amount(),
+            zero_for_one in proptest::bool::ANY,
+        ) {
+            prop_assert_eq!(
+                sqrt_price_math::get_next_sqrt_price_from_input_raw(sqrt_price_x64, liquidity, amount, zero_for_one).ok(),
+                reference::get_next_sqrt_price_from_input(sqrt_price_x64, liquidity, amount, zero_for_one)
+            );
+            prop_assert_eq!(
+                sqrt_price_math::get_next_sqrt_price_from_output_raw(sqrt_price_x64, liquidity, amount, zero_for_one).ok(),
+                reference::get_next_sqrt_price_from_output(sqrt_price_x64, liquidity, amount, zero_for_one)
+            );
+        }
+
+        // a swap step the reference can compute is computed identically, no delta is allowed
+        #[test]
+        fn compute_swap_step_test(
+            sqrt_price_current_x64 in sqrt_price(),
+            sqrt_price_target_x64 in sqrt_price(),
+            liquidity in liquidity(),
+            amount_remaining in amount(),
+            fee_rate in 0..FEE_RATE_DENOMINATOR_VALUE / 2,
+            is_base_input in proptest::bool::ANY,
+        ) {
+            let zero_for_one = sqrt_price_current_x64 >= sqrt_price_target_x64;
+            let expected = reference::compute_swap_step(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                amount_remaining,
+                fee_rate,
+                is_base_input,
+                zero_for_one,
+            );
+            let swap_step = swap_math::compute_swap_step_raw(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                amount_remaining,
+                fee_rate,
+                is_base_input,
+                zero_for_one,
+            );
+            match (swap_step, expected) {
+                (Ok(swap_step), Some(expected)) => prop_assert_eq!(
+                    (
+                        swap_step.sqrt_price_next_x64,
+                        swap_step.amount_in,
+                        swap_step.amount_out,
+                        swap_step.fee_amount
+                    ),
+                    expected
+                ),
+                (swap_step, expected) => prop_assert!(
+                    swap_step.is_err() && expected.is_none(),
+                    "swap step {:?}, reference {:?}",
+                    swap_step,
+                    expected
+                ),
+            }
+        }
+
+        #[test]
+        fn get_sqrt_price_at_tick_test(tick in tick_math::MIN_TICK..=tick_math::MAX_TICK) {
+            let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick_raw(tick).unwrap();
+            let expected = reference::get_sqrt_price_at_tick(tick);
+            // plus the floor to an integer Q64.64, which dominates next to MIN_TICK
+            let error = (sqrt_price_x64 as f64 - expected).abs();
+            prop_assert!(
+                error <= expected * MAX_TICK_PRICE_RELATIVE_ERROR + 1.0,
+                "tick {}, sqrt price {}, reference {}",
+                tick,
+                sqrt_price_x64,
+                expected
+            );
+        }
+
+        // off by one tick at most, next to a tick boundary where the f64 reference is ambiguous
+        #[test]
+        fn get_tick_at_sqrt_price_test(sqrt_price_x64 in sqrt_price()) {
+            let tick = tick_math::get_tick_at_sqrt_price_raw(sqrt_price_x64).unwrap();
+            let expected = reference::get_tick_at_sqrt_price(sqrt_price_x64);
+            prop_assert!((tick - expected).abs() <= 1, "sqrt price {}, tick {}, reference {}", sqrt_price_x64, tick, expected);
+            if tick != expected {
+                let boundary = tick_math::get_sqrt_price_at_tick_raw(tick.max(expected)).unwrap();
+                let relative_distance = (sqrt_price_x64 as f64 - boundary as f64).abs() / boundary as f64;
+                prop_assert!(relative_distance <= MAX_TICK_PRICE_RELATIVE_ERROR);
+            }
+        }
+    }
+}
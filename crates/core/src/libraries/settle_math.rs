@@ -0,0 +1,98 @@
+//! The arithmetic of the swap settlement, the vault transfer amounts and the fee accumulators of the pool.
+//! An overflow panics by default like the `checked_*().unwrap()` it replaces, with the `checked-settle`
+//! feature it logs the settled value and returns `SettlementOverflow` instead, to audit the accounting.
+#[cfg(feature = "checked-settle")]
+use crate::error::ErrorCode;
+use crate::error::Result;
+#[cfg(feature = "checked-settle")]
+use crate::macros::{error, msg};
+
+pub trait SettleInt: Copy + std::fmt::Display {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_settle_int {
+    ($($t:ty),*) => {
+        $(impl SettleInt for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+        })*
+    };
+}
+
+impl_settle_int!(u64, u128);
+
+#[cfg(feature = "checked-settle")]
+fn settle_result<T: SettleInt>(
+    result: Option<T>,
+    name: &str,
+    op: &str,
+    lhs: T,
+    rhs: T,
+) -> Result<T> {
+    result.ok_or_else(|| {
+        msg!("settlement overflow, {}: {} {} {}", name, lhs, op, rhs);
+        error!(ErrorCode::SettlementOverflow)
+    })
+}
+
+#[cfg(not(feature = "checked-settle"))]
+fn settle_result<T: SettleInt>(
+    result: Option<T>,
+    _name: &str,
+    _op: &str,
+    _lhs: T,
+    _rhs: T,
+) -> Result<T> {
+    Ok(result.unwrap())
+}
+
+/// Add `rhs` to the settled value `name`
+pub fn settle_add<T: SettleInt>(name: &str, lhs: T, rhs: T) -> Result<T> {
+    settle_result(lhs.checked_add(rhs), name, "+", lhs, rhs)
+}
+
+/// Subtract `rhs` from the settled value `name`
+pub fn settle_sub<T: SettleInt>(name: &str, lhs: T, rhs: T) -> Result<T> {
+    settle_result(lhs.checked_sub(rhs), name, "-", lhs, rhs)
+}
+
+#[cfg(test)]
+mod settle_math_test {
+    use super::*;
+
+    #[test]
+    fn settle_test() {
+        assert_eq!(settle_add("total_fees_token_0", 1u64, 2).unwrap(), 3);
+        assert_eq!(settle_sub("amount_0", 3u64, 2).unwrap(), 1);
+        assert_eq!(
+            settle_add("swap_in_amount_token_0", u128::MAX - 1, 1).unwrap(),
+            u128::MAX
+        );
+    }
+
+    #[cfg(feature = "checked-settle")]
+    #[test]
+    fn checked_settle_overflow_test() {
+        assert_eq!(
+            settle_add("protocol_fees_token_0", u64::MAX, 1).err(),
+            Some(ErrorCode::SettlementOverflow.into())
+        );
+        assert_eq!(
+            settle_sub("amount_out", 1u64, 2).err(),
+            Some(ErrorCode::SettlementOverflow.into())
+        );
+    }
+
+    #[cfg(not(feature = "checked-settle"))]
+    #[test]
+    #[should_panic]
+    fn unchecked_settle_overflow_test() {
+        let _ = settle_add("protocol_fees_token_0", u64::MAX, 1);
+    }
+}
@@ -0,0 +1,232 @@
+use super::full_math::MulDiv;
+use super::unsafe_math::UnsafeMathTrait;
+use super::{fixed_point_64, Liquidity, SqrtPriceX64, U256};
+use crate::error::ErrorCode;
+use crate::error::Result;
+use crate::macros::require;
+
+/// Gets the next sqrt price √P' given a delta of token_0
+///
+/// Always round up because
+/// 1. In the exact output case, token 0 supply decreases leading to price increase.
+///    Move price up so that exact output is met.
+/// 2. In the exact input case, token 0 supply increases leading to price decrease.
+///    Do not round down to minimize price impact. We only need to meet input
+///    change and not guarantee exact output.
+///
+/// Use function for exact input or exact output swaps for token 0
+///
+/// # Formula
+///
+/// * `√P' = √P * L / (L + Δx * √P)`
+/// * If Δx * √P overflows, use alternate form `√P' = L / (L/√P + Δx)`
+///
+/// # Proof
+///
+/// For constant y,
+/// √P * L = y
+/// √P' * L' = √P * L
+/// √P' = √P * L / L'
+/// √P' = √P * L / L'
+/// √P' = √P * L / (L + Δx*√P)
+///
+pub fn get_next_sqrt_price_from_amount_0_rounding_up_raw(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128> {
+    if amount == 0 {
+        return Ok(sqrt_price_x64);
+    };
+    let numerator_1 = (U256::from(liquidity)) << fixed_point_64::RESOLUTION;
+
+    let result = if add {
+        if let Some(product) = U256::from(amount).checked_mul(U256::from(sqrt_price_x64)) {
+            let denominator = numerator_1 + product;
+            if denominator >= numerator_1 {
+                let result = numerator_1
+                    .mul_div_ceil(U256::from(sqrt_price_x64), denominator)
+                    .ok_or(ErrorCode::IntermediateOverflow)?;
+                return to_sqrt_price(result);
+            };
+        }
+
+        U256::div_rounding_up(
+            numerator_1,
+            (numerator_1 / U256::from(sqrt_price_x64))
+                .checked_add(U256::from(amount))
+                .ok_or(ErrorCode::IntermediateOverflow)?,
+        )
+    } else {
+        let product = U256::from(amount)
+            .checked_mul(U256::from(sqrt_price_x64))
+            .ok_or(ErrorCode::IntermediateOverflow)?;
+        // the output amount must be less than the token_0 reserves of the liquidity
+        let denominator = numerator_1
+            .checked_sub(product)
+            .ok_or(ErrorCode::InputOutOfRange)?;
+        numerator_1
+            .mul_div_ceil(U256::from(sqrt_price_x64), denominator)
+            .ok_or(ErrorCode::IntermediateOverflow)?
+    };
+    to_sqrt_price(result)
+}
+
+/// Gets the next sqrt price given a delta of token_1
+///
+/// Always round down because
+/// 1. In the exact output case, token 1 supply decreases leading to price decrease.
+///    Move price down by rounding down so that exact output of token 0 is met.
+/// 2. In the exact input case, token 1 supply increases leading to price increase.
+///    Do not round down to minimize price impact. We only need to meet input
+///    change and not gurantee exact output for token 0.
+///
+///
+/// # Formula
+///
+/// * `√P' = √P + Δy / L`
+///
+pub fn get_next_sqrt_price_from_amount_1_rounding_down_raw(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount: u64,
+    add: bool,
+) -> Result<u128> {
+    if add {
+        let quotient = U256::from(u128::from(amount) << fixed_point_64::RESOLUTION) / liquidity;
+        sqrt_price_x64
+            .checked_add(to_sqrt_price(quotient)?)
+            .ok_or(ErrorCode::IntermediateOverflow.into())
+    } else {
+        let quotient = U256::div_rounding_up(
+            U256::from(u128::from(amount) << fixed_point_64::RESOLUTION),
+            U256::from(liquidity),
+        );
+        // the output amount must be less than the token_1 reserves of the liquidity
+        sqrt_price_x64
+            .checked_sub(to_sqrt_price(quotient)?)
+            .ok_or(ErrorCode::InputOutOfRange.into())
+    }
+}
+
+/// Gets the next sqrt price given an input amount of token_0 or token_1
+/// Throws if price or liquidity are 0, or if the next price is out of bounds
+pub fn get_next_sqrt_price_from_input_raw(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Result<u128> {
+    require!(sqrt_price_x64 > 0, ErrorCode::InputOutOfRange);
+    require!(liquidity > 0, ErrorCode::InputOutOfRange);
+
+    // round to make sure that we don't pass the target price
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_0_rounding_up_raw(
+            sqrt_price_x64,
+            liquidity,
+            amount_in,
+            true,
+        )
+    } else {
+        get_next_sqrt_price_from_amount_1_rounding_down_raw(
+            sqrt_price_x64,
+            liquidity,
+            amount_in,
+            true,
+        )
+    }
+}
+
+/// Gets the next sqrt price given an output amount of token0 or token1
+///
+/// Throws if price or liquidity are 0 or the next price is out of bounds
+///
+pub fn get_next_sqrt_price_from_output_raw(
+    sqrt_price_x64: u128,
+    liquidity: u128,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Result<u128> {
+    require!(sqrt_price_x64 > 0, ErrorCode::InputOutOfRange);
+    require!(liquidity > 0, ErrorCode::InputOutOfRange);
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount_1_rounding_down_raw(
+            sqrt_price_x64,
+            liquidity,
+            amount_out,
+            false,
+        )
+    } else {
+        get_next_sqrt_price_from_amount_0_rounding_up_raw(
+            sqrt_price_x64,
+            liquidity,
+            amount_out,
+            false,
+        )
+    }
+}
+
+/// Narrow a U256 price to u128, a price that doesn't fit is an intermediate overflow
+fn to_sqrt_price(value: U256) -> Result<u128> {
+    if value > U256::from(u128::MAX) {
+        return Err(ErrorCode::IntermediateOverflow.into());
+    }
+    Ok(value.as_u128())
+}
+
+/// Gets the next sqrt price given a delta of token_0, see `get_next_sqrt_price_from_amount_0_rounding_up_raw`
+pub fn get_next_sqrt_price_from_amount_0_rounding_up(
+    sqrt_price: SqrtPriceX64,
+    liquidity: Liquidity,
+    amount: u64,
+    add: bool,
+) -> Result<SqrtPriceX64> {
+    get_next_sqrt_price_from_amount_0_rounding_up_raw(
+        sqrt_price.get(),
+        liquidity.get(),
+        amount,
+        add,
+    )
+    .map(SqrtPriceX64)
+}
+
+/// Gets the next sqrt price given a delta of token_1, see `get_next_sqrt_price_from_amount_1_rounding_down_raw`
+pub fn get_next_sqrt_price_from_amount_1_rounding_down(
+    sqrt_price: SqrtPriceX64,
+    liquidity: Liquidity,
+    amount: u64,
+    add: bool,
+) -> Result<SqrtPriceX64> {
+    get_next_sqrt_price_from_amount_1_rounding_down_raw(
+        sqrt_price.get(),
+        liquidity.get(),
+        amount,
+        add,
+    )
+    .map(SqrtPriceX64)
+}
+
+/// Gets the next sqrt price given an input amount, see `get_next_sqrt_price_from_input_raw`
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price: SqrtPriceX64,
+    liquidity: Liquidity,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Result<SqrtPriceX64> {
+    get_next_sqrt_price_from_input_raw(sqrt_price.get(), liquidity.get(), amount_in, zero_for_one)
+        .map(SqrtPriceX64)
+}
+
+/// Gets the next sqrt price given an output amount, see `get_next_sqrt_price_from_output_raw`
+pub fn get_next_sqrt_price_from_output(
+    sqrt_price: SqrtPriceX64,
+    liquidity: Liquidity,
+    amount_out: u64,
+    zero_for_one: bool,
+) -> Result<SqrtPriceX64> {
+    get_next_sqrt_price_from_output_raw(sqrt_price.get(), liquidity.get(), amount_out, zero_for_one)
+        .map(SqrtPriceX64)
+}
@@ -1,9 +1,12 @@
+use super::fee_math::FEE_RATE_DENOMINATOR_VALUE;
 use super::full_math::MulDiv;
 use super::liquidity_math;
 use super::sqrt_price_math;
+use super::tick_math;
+use super::{Liquidity, SqrtPriceX64};
 use crate::error::ErrorCode;
-use crate::states::config::FEE_RATE_DENOMINATOR_VALUE;
-use anchor_lang::prelude::*;
+use crate::error::Result;
+use crate::macros::require;
 /// Result of a swap step
 #[derive(Default, Debug)]
 pub struct SwapStep {
@@ -15,7 +18,7 @@ pub struct SwapStep {
 }
 
 /// Computes the result of swapping some amount in, or amount out, given the parameters of the swap
-pub fn compute_swap_step(
+pub fn compute_swap_step_raw(
     sqrt_price_current_x64: u128,
     sqrt_price_target_x64: u128,
     liquidity: u128,
@@ -23,14 +26,13 @@ pub fn compute_swap_step(
     fee_rate: u32,
     is_base_input: bool,
     zero_for_one: bool,
-    block_timestamp: u32,
 ) -> Result<SwapStep> {
     // let exact_in = amount_remaining >= 0;
     let mut swap_step = SwapStep::default();
     if is_base_input {
         // round up amount_in
         // In exact input case, amount_remaining is positive
-        let amount_remaining_less_fee = (amount_remaining as u64)
+        let amount_remaining_less_fee = amount_remaining
             .mul_div_floor(
                 (FEE_RATE_DENOMINATOR_VALUE - fee_rate).into(),
                 u64::from(FEE_RATE_DENOMINATOR_VALUE),
@@ -43,22 +45,21 @@ pub fn compute_swap_step(
             liquidity,
             zero_for_one,
             is_base_input,
-            block_timestamp,
         )?;
-        if amount_in.is_some() {
-            swap_step.amount_in = amount_in.unwrap();
+        if let Some(amount_in) = amount_in {
+            swap_step.amount_in = amount_in;
         }
 
         swap_step.sqrt_price_next_x64 =
             if amount_in.is_some() && amount_remaining_less_fee >= swap_step.amount_in {
                 sqrt_price_target_x64
             } else {
-                sqrt_price_math::get_next_sqrt_price_from_input(
+                sqrt_price_math::get_next_sqrt_price_from_input_raw(
                     sqrt_price_current_x64,
                     liquidity,
                     amount_remaining_less_fee,
                     zero_for_one,
-                )
+                )?
             };
     } else {
         let amount_out = calculate_amount_in_range(
@@ -67,22 +68,21 @@ pub fn compute_swap_step(
             liquidity,
             zero_for_one,
             is_base_input,
-            block_timestamp,
         )?;
-        if amount_out.is_some() {
-            swap_step.amount_out = amount_out.unwrap();
+        if let Some(amount_out) = amount_out {
+            swap_step.amount_out = amount_out;
         }
         // In exact output case, amount_remaining is negative
         swap_step.sqrt_price_next_x64 =
             if amount_out.is_some() && amount_remaining >= swap_step.amount_out {
                 sqrt_price_target_x64
             } else {
-                sqrt_price_math::get_next_sqrt_price_from_output(
+                sqrt_price_math::get_next_sqrt_price_from_output_raw(
                     sqrt_price_current_x64,
                     liquidity,
                     amount_remaining,
                     zero_for_one,
-                )
+                )?
             }
     }
 
@@ -92,7 +92,7 @@ pub fn compute_swap_step(
     if zero_for_one {
         // if max is reached for exact input case, entire amount_in is needed
         if !(max && is_base_input) {
-            swap_step.amount_in = liquidity_math::get_delta_amount_0_unsigned(
+            swap_step.amount_in = liquidity_math::get_delta_amount_0_unsigned_raw(
                 swap_step.sqrt_price_next_x64,
                 sqrt_price_current_x64,
                 liquidity,
@@ -100,8 +100,8 @@ pub fn compute_swap_step(
             )?
         };
         // if max is reached for exact output case, entire amount_out is needed
-        if !(max && !is_base_input) {
-            swap_step.amount_out = liquidity_math::get_delta_amount_1_unsigned(
+        if !max || is_base_input {
+            swap_step.amount_out = liquidity_math::get_delta_amount_1_unsigned_raw(
                 swap_step.sqrt_price_next_x64,
                 sqrt_price_current_x64,
                 liquidity,
@@ -110,15 +110,15 @@ pub fn compute_swap_step(
         };
     } else {
         if !(max && is_base_input) {
-            swap_step.amount_in = liquidity_math::get_delta_amount_1_unsigned(
+            swap_step.amount_in = liquidity_math::get_delta_amount_1_unsigned_raw(
                 sqrt_price_current_x64,
                 swap_step.sqrt_price_next_x64,
                 liquidity,
                 true,
             )?
         };
-        if !(max && !is_base_input) {
-            swap_step.amount_out = liquidity_math::get_delta_amount_0_unsigned(
+        if !max || is_base_input {
+            swap_step.amount_out = liquidity_math::get_delta_amount_0_unsigned_raw(
                 sqrt_price_current_x64,
                 swap_step.sqrt_price_next_x64,
                 liquidity,
@@ -136,9 +136,7 @@ pub fn compute_swap_step(
         if is_base_input && swap_step.sqrt_price_next_x64 != sqrt_price_target_x64 {
             // we didn't reach the target, so take the remainder of the maximum input as fee
             // swap dust is granted as fee
-            u64::from(amount_remaining)
-                .checked_sub(swap_step.amount_in)
-                .unwrap()
+            amount_remaining.checked_sub(swap_step.amount_in).unwrap()
         } else {
             // take pip percentage as fee
             swap_step
@@ -156,138 +154,127 @@ pub fn compute_swap_step(
 /// Pre calcumate amount_in or amount_out for the specified price range
 /// The amount maybe overflow of u64 due to the `sqrt_price_target_x64` maybe unreasonable.
 /// Therefore, this situation needs to be handled in `compute_swap_step` to recalculate the price that can be reached based on the amount.
-#[cfg(not(test))]
+///
+/// Math errors are mapped as:
+/// * `ResultExceedsU64` - `Ok(None)`, the target price can't be reached by any u64 amount
+/// * `InputOutOfRange`, `IntermediateOverflow` - returned as is
 fn calculate_amount_in_range(
     sqrt_price_current_x64: u128,
     sqrt_price_target_x64: u128,
     liquidity: u128,
     zero_for_one: bool,
     is_base_input: bool,
-    _block_timestamp: u32,
 ) -> Result<Option<u64>> {
-    if is_base_input {
-        let result = if zero_for_one {
-            liquidity_math::get_delta_amount_0_unsigned(
+    let result = if is_base_input {
+        if zero_for_one {
+            liquidity_math::get_delta_amount_0_unsigned_raw(
                 sqrt_price_target_x64,
                 sqrt_price_current_x64,
                 liquidity,
                 true,
             )
         } else {
-            liquidity_math::get_delta_amount_1_unsigned(
+            liquidity_math::get_delta_amount_1_unsigned_raw(
                 sqrt_price_current_x64,
                 sqrt_price_target_x64,
                 liquidity,
                 true,
             )
-        };
-
-        if result.is_ok() {
-            return Ok(Some(result.unwrap()));
-        } else {
-            if result.err().unwrap() == crate::error::ErrorCode::MaxTokenOverflow.into() {
-                return Ok(None);
-            } else {
-                return Err(ErrorCode::SqrtPriceLimitOverflow.into());
-            }
         }
     } else {
-        let result = if zero_for_one {
-            liquidity_math::get_delta_amount_1_unsigned(
+        if zero_for_one {
+            liquidity_math::get_delta_amount_1_unsigned_raw(
                 sqrt_price_target_x64,
                 sqrt_price_current_x64,
                 liquidity,
                 false,
             )
         } else {
-            liquidity_math::get_delta_amount_0_unsigned(
+            liquidity_math::get_delta_amount_0_unsigned_raw(
                 sqrt_price_current_x64,
                 sqrt_price_target_x64,
                 liquidity,
                 false,
             )
-        };
-        if result.is_ok() {
-            return Ok(Some(result.unwrap()));
-        } else {
-            if result.err().unwrap() == crate::error::ErrorCode::MaxTokenOverflow.into() {
-                return Ok(None);
-            } else {
-                return Err(ErrorCode::SqrtPriceLimitOverflow.into());
-            }
         }
+    };
+
+    match result {
+        Ok(amount) => Ok(Some(amount)),
+        Err(err) if err == ErrorCode::ResultExceedsU64.into() => Ok(None),
+        Err(err) => Err(err),
     }
 }
 
-#[cfg(test)]
-fn calculate_amount_in_range(
-    sqrt_price_current_x64: u128,
-    sqrt_price_target_x64: u128,
-    liquidity: u128,
-    zero_for_one: bool,
+/// Computes the result of a swap step, see `compute_swap_step_raw`
+pub fn compute_swap_step(
+    sqrt_price_current: SqrtPriceX64,
+    sqrt_price_target: SqrtPriceX64,
+    liquidity: Liquidity,
+    amount_remaining: u64,
+    fee_rate: u32,
     is_base_input: bool,
-    block_timestamp: u32,
-) -> Result<Option<u64>> {
-    if is_base_input {
-        let result = if zero_for_one {
-            liquidity_math::get_delta_amount_0_unsigned(
-                sqrt_price_target_x64,
-                sqrt_price_current_x64,
-                liquidity,
-                true,
-            )
-        } else {
-            liquidity_math::get_delta_amount_1_unsigned(
-                sqrt_price_current_x64,
-                sqrt_price_target_x64,
-                liquidity,
-                true,
-            )
-        };
+    zero_for_one: bool,
+) -> Result<SwapStep> {
+    compute_swap_step_raw(
+        sqrt_price_current.get(),
+        sqrt_price_target.get(),
+        liquidity.get(),
+        amount_remaining,
+        fee_rate,
+        is_base_input,
+        zero_for_one,
+    )
+}
 
-        if block_timestamp == 0 {
-            if result.is_err() {
-                return Err(ErrorCode::MaxTokenOverflow.into());
-            } else {
-                return Ok(Some(result.unwrap()));
-            }
-        }
-        if result.is_ok() {
-            return Ok(Some(result.unwrap()));
+/// Substitute the widest limit for a zero `sqrt_price_limit_x64`, MIN_SQRT_PRICE_X64 + 1 for zero_for_one
+/// and MAX_SQRT_PRICE_X64 - 1 otherwise, so that the swap is only bounded by the liquidity.
+/// A non zero limit outside of (MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64) is rejected rather than clamped.
+pub fn normalize_sqrt_price_limit(sqrt_price_limit_x64: u128, zero_for_one: bool) -> Result<u128> {
+    if sqrt_price_limit_x64 == 0 {
+        return Ok(if zero_for_one {
+            tick_math::MIN_SQRT_PRICE_X64 + 1
         } else {
-            if result.err().unwrap() == crate::error::ErrorCode::MaxTokenOverflow.into() {
-                return Ok(None);
-            } else {
-                return Err(ErrorCode::SqrtPriceLimitOverflow.into());
-            }
-        }
+            tick_math::MAX_SQRT_PRICE_X64 - 1
+        });
+    }
+    require!(
+        sqrt_price_limit_x64 > tick_math::MIN_SQRT_PRICE_X64
+            && sqrt_price_limit_x64 < tick_math::MAX_SQRT_PRICE_X64,
+        ErrorCode::SqrtPriceLimitOutOfBounds
+    );
+    Ok(sqrt_price_limit_x64)
+}
+
+/// Check `sqrt_price_limit_x64` is on the side of the current price the swap moves toward,
+/// below it for zero_for_one and above it otherwise, and inside the price range
+pub fn check_sqrt_price_limit(
+    sqrt_price_x64: u128,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+) -> Result<()> {
+    if zero_for_one {
+        require!(
+            sqrt_price_limit_x64 < sqrt_price_x64,
+            ErrorCode::LimitAboveCurrentForZeroForOne
+        );
+        require!(
+            sqrt_price_limit_x64 > tick_math::MIN_SQRT_PRICE_X64,
+            ErrorCode::SqrtPriceLimitOutOfBounds
+        );
     } else {
-        let result = if zero_for_one {
-            liquidity_math::get_delta_amount_1_unsigned(
-                sqrt_price_target_x64,
-                sqrt_price_current_x64,
-                liquidity,
-                false,
-            )
-        } else {
-            liquidity_math::get_delta_amount_0_unsigned(
-                sqrt_price_current_x64,
-                sqrt_price_target_x64,
-                liquidity,
-                false,
-            )
-        };
-        if result.is_ok() || block_timestamp == 0 {
-            return Ok(Some(result.unwrap()));
-        } else {
-            if result.err().unwrap() == crate::error::ErrorCode::MaxTokenOverflow.into() {
-                return Ok(None);
-            } else {
-                return Err(ErrorCode::SqrtPriceLimitOverflow.into());
-            }
-        }
+        require!(
+            sqrt_price_limit_x64 > sqrt_price_x64,
+            ErrorCode::LimitBelowCurrentForOneForZero
+        );
+        require!(
+            sqrt_price_limit_x64 < tick_math::MAX_SQRT_PRICE_X64,
+            ErrorCode::SqrtPriceLimitOutOfBounds
+        );
     }
+    Ok(())
 }
+
 #[cfg(test)]
 mod swap_math_test {
     use crate::libraries::tick_math;
@@ -308,7 +295,7 @@ mod swap_math_test {
             prop_assume!(sqrt_price_current_x64 != sqrt_price_target_x64);
 
             let zero_for_one = sqrt_price_current_x64 > sqrt_price_target_x64;
-            let swap_step = compute_swap_step(
+            let swap_step = compute_swap_step_raw(
                 sqrt_price_current_x64,
                 sqrt_price_target_x64,
                 liquidity,
@@ -316,7 +303,6 @@ mod swap_math_test {
                 fee_rate,
                 is_base_input,
                 zero_for_one,
-                1,
             ).unwrap();
 
             let amount_in = swap_step.amount_in;
@@ -340,5 +326,94 @@ mod swap_math_test {
             assert!(sqrt_price_next_x64 >= price_lower);
             assert!(sqrt_price_next_x64 <= price_upper);
         }
+
+        #[test]
+        fn calculate_amount_in_range_overflow_test(
+            sqrt_price_current_x64 in tick_math::MIN_SQRT_PRICE_X64..tick_math::MAX_SQRT_PRICE_X64,
+            sqrt_price_target_x64 in tick_math::MIN_SQRT_PRICE_X64..tick_math::MAX_SQRT_PRICE_X64,
+            liquidity in (u64::MAX as u128)..(1u128 << 120),
+            amount_remaining in 1..u64::MAX,
+            fee_rate in 1..FEE_RATE_DENOMINATOR_VALUE/2,
+            is_base_input in proptest::bool::ANY,
+        ) {
+            prop_assume!(sqrt_price_current_x64 != sqrt_price_target_x64);
+
+            let zero_for_one = sqrt_price_current_x64 > sqrt_price_target_x64;
+            let amount_in_range = calculate_amount_in_range(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                zero_for_one,
+                is_base_input,
+            ).unwrap();
+
+            // the amount is None if and only if it exceeds u64
+            let expect_amount = match (is_base_input, zero_for_one) {
+                (true, true) => liquidity_math::get_delta_amount_0_unsigned_raw(sqrt_price_target_x64, sqrt_price_current_x64, liquidity, true),
+                (true, false) => liquidity_math::get_delta_amount_1_unsigned_raw(sqrt_price_current_x64, sqrt_price_target_x64, liquidity, true),
+                (false, true) => liquidity_math::get_delta_amount_1_unsigned_raw(sqrt_price_target_x64, sqrt_price_current_x64, liquidity, false),
+                (false, false) => liquidity_math::get_delta_amount_0_unsigned_raw(sqrt_price_current_x64, sqrt_price_target_x64, liquidity, false),
+            };
+            match expect_amount {
+                Ok(amount) => assert_eq!(amount_in_range, Some(amount)),
+                Err(err) => {
+                    assert!(err == ErrorCode::ResultExceedsU64.into());
+                    assert_eq!(amount_in_range, None);
+                }
+            }
+            prop_assume!(amount_in_range.is_none());
+
+            // when the target price is unreachable, the whole remaining amount is used and the target is not crossed
+            let swap_step = compute_swap_step_raw(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                liquidity,
+                amount_remaining,
+                fee_rate,
+                is_base_input,
+                zero_for_one,
+            );
+            // the other side of the whole remaining amount, the output of an exact input
+            // or the input of an exact output, can exceed u64 too
+            if swap_step.is_err() {
+                assert!(swap_step.err() == Some(ErrorCode::ResultExceedsU64.into()));
+                return Ok(());
+            }
+            let swap_step = swap_step.unwrap();
+
+            if is_base_input {
+                assert_eq!(swap_step.amount_in + swap_step.fee_amount, amount_remaining);
+            } else {
+                assert!(swap_step.amount_out <= amount_remaining);
+            }
+            assert!(swap_step.sqrt_price_next_x64 != sqrt_price_target_x64);
+            let price_lower = sqrt_price_current_x64.min(sqrt_price_target_x64);
+            let price_upper = sqrt_price_current_x64.max(sqrt_price_target_x64);
+            assert!(swap_step.sqrt_price_next_x64 >= price_lower);
+            assert!(swap_step.sqrt_price_next_x64 <= price_upper);
+        }
+    }
+
+    #[test]
+    fn calculate_amount_in_range_full_range_overflow_test() {
+        // no u64 amount can move max liquidity across the whole price range
+        for (is_base_input, zero_for_one) in
+            [(true, true), (true, false), (false, true), (false, false)]
+        {
+            let (sqrt_price_current_x64, sqrt_price_target_x64) = if zero_for_one {
+                (tick_math::MAX_SQRT_PRICE_X64, tick_math::MIN_SQRT_PRICE_X64)
+            } else {
+                (tick_math::MIN_SQRT_PRICE_X64, tick_math::MAX_SQRT_PRICE_X64)
+            };
+            let amount = calculate_amount_in_range(
+                sqrt_price_current_x64,
+                sqrt_price_target_x64,
+                u64::MAX as u128,
+                zero_for_one,
+                is_base_input,
+            )
+            .unwrap();
+            assert_eq!(amount, None);
+        }
     }
 }
@@ -1,15 +1,18 @@
-use crate::{error::ErrorCode, libraries::big_num::U128};
+use crate::{
+    error::{Error, ErrorCode},
+    libraries::{big_num::U128, SqrtPriceX64, Tick},
+};
 
-use anchor_lang::require;
+use crate::macros::require;
 
 /// The minimum tick
 pub const MIN_TICK: i32 = -443636;
 /// The minimum tick
 pub const MAX_TICK: i32 = -MIN_TICK;
 
-/// The minimum value that can be returned from #get_sqrt_price_at_tick. Equivalent to get_sqrt_price_at_tick(MIN_TICK)
+/// The minimum value that can be returned from #get_sqrt_price_at_tick. Equivalent to get_sqrt_price_at_tick_raw(MIN_TICK)
 pub const MIN_SQRT_PRICE_X64: u128 = 4295048016;
-/// The maximum value that can be returned from #get_sqrt_price_at_tick. Equivalent to get_sqrt_price_at_tick(MAX_TICK)
+/// The maximum value that can be returned from #get_sqrt_price_at_tick. Equivalent to get_sqrt_price_at_tick_raw(MAX_TICK)
 pub const MAX_SQRT_PRICE_X64: u128 = 79226673521066979257578248091;
 
 // Number 64, encoded as a U128
@@ -28,8 +31,8 @@ const BIT_PRECISION: u32 = 16;
 /// # Arguments
 /// * `tick` - Price tick
 ///
-pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128, anchor_lang::error::Error> {
-    let abs_tick = tick.abs() as u32;
+pub fn get_sqrt_price_at_tick_raw(tick: i32) -> Result<u128, Error> {
+    let abs_tick = tick.unsigned_abs();
     require!(abs_tick <= MAX_TICK as u32, ErrorCode::TickUpperOverflow);
 
     // i = 0
@@ -120,14 +123,14 @@ pub fn get_sqrt_price_at_tick(tick: i32) -> Result<u128, anchor_lang::error::Err
     Ok(ratio.as_u128())
 }
 
-/// Calculates the greatest tick value such that get_sqrt_price_at_tick(tick) <= ratio
+/// Calculates the greatest tick value such that get_sqrt_price_at_tick_raw(tick) <= ratio
 /// Throws if sqrt_price_x64 < MIN_SQRT_RATIO or sqrt_price_x64 > MAX_SQRT_RATIO
 ///
 /// Formula: `i = log base(√1.0001) (√P)`
-pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32, anchor_lang::error::Error> {
+pub fn get_tick_at_sqrt_price_raw(sqrt_price_x64: u128) -> Result<i32, Error> {
     // second inequality must be < because the price can never reach the price at the max tick
     require!(
-        sqrt_price_x64 >= MIN_SQRT_PRICE_X64 && sqrt_price_x64 < MAX_SQRT_PRICE_X64,
+        (MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64).contains(&sqrt_price_x64),
         ErrorCode::SqrtPriceX64
     );
 
@@ -152,7 +155,7 @@ pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32, anchor_lang::
 
     while bit > 0 && precision < BIT_PRECISION {
         r *= r;
-        let is_r_more_than_two = r >> 127 as u32;
+        let is_r_more_than_two = r >> 127;
         r >>= 63 + is_r_more_than_two;
         log2p_fraction_x64 += bit * is_r_more_than_two as i128;
         bit >>= 1;
@@ -175,13 +178,23 @@ pub fn get_tick_at_sqrt_price(sqrt_price_x64: u128) -> Result<i32, anchor_lang::
 
     Ok(if tick_low == tick_high {
         tick_low
-    } else if get_sqrt_price_at_tick(tick_high).unwrap() <= sqrt_price_x64 {
+    } else if get_sqrt_price_at_tick_raw(tick_high).unwrap() <= sqrt_price_x64 {
         tick_high
     } else {
         tick_low
     })
 }
 
+/// Calculates the sqrt price at `tick`, see `get_sqrt_price_at_tick_raw`
+pub fn get_sqrt_price_at_tick(tick: Tick) -> Result<SqrtPriceX64, Error> {
+    get_sqrt_price_at_tick_raw(tick.get()).map(SqrtPriceX64)
+}
+
+/// Calculates the greatest tick whose sqrt price is at most `sqrt_price`, see `get_tick_at_sqrt_price_raw`
+pub fn get_tick_at_sqrt_price(sqrt_price: SqrtPriceX64) -> Result<Tick, Error> {
+    get_tick_at_sqrt_price_raw(sqrt_price.get()).map(Tick)
+}
+
 #[cfg(test)]
 mod tick_math_test {
     use super::*;
@@ -192,13 +205,13 @@ mod tick_math_test {
         #[test]
         fn check_get_sqrt_price_at_tick_at_min_or_max_tick() {
             assert_eq!(
-                get_sqrt_price_at_tick(MIN_TICK).unwrap(),
+                get_sqrt_price_at_tick_raw(MIN_TICK).unwrap(),
                 MIN_SQRT_PRICE_X64
             );
             let min_sqrt_price = MIN_SQRT_PRICE_X64 as f64 / fixed_point_64::Q64 as f64;
             println!("min_sqrt_price: {}", min_sqrt_price);
             assert_eq!(
-                get_sqrt_price_at_tick(MAX_TICK).unwrap(),
+                get_sqrt_price_at_tick_raw(MAX_TICK).unwrap(),
                 MAX_SQRT_PRICE_X64
             );
             let max_sqrt_price = MAX_SQRT_PRICE_X64 as f64 / fixed_point_64::Q64 as f64;
@@ -212,13 +225,13 @@ mod tick_math_test {
         #[test]
         fn check_get_tick_at_sqrt_price_at_min_or_max_sqrt_price() {
             assert_eq!(
-                get_tick_at_sqrt_price(MIN_SQRT_PRICE_X64).unwrap(),
+                get_tick_at_sqrt_price_raw(MIN_SQRT_PRICE_X64).unwrap(),
                 MIN_TICK,
             );
 
             // we can't reach MAX_SQRT_PRICE_X64
             assert_eq!(
-                get_tick_at_sqrt_price(MAX_SQRT_PRICE_X64 - 1).unwrap(),
+                get_tick_at_sqrt_price_raw(MAX_SQRT_PRICE_X64 - 1).unwrap(),
                 MAX_TICK - 1,
             );
         }
@@ -227,25 +240,25 @@ mod tick_math_test {
     #[test]
     fn tick_round_down() {
         // tick is negative
-        let sqrt_price_x64 = get_sqrt_price_at_tick(-28861).unwrap();
-        let mut tick = get_tick_at_sqrt_price(sqrt_price_x64).unwrap();
+        let sqrt_price_x64 = get_sqrt_price_at_tick_raw(-28861).unwrap();
+        let mut tick = get_tick_at_sqrt_price_raw(sqrt_price_x64).unwrap();
         assert_eq!(tick, -28861);
-        tick = get_tick_at_sqrt_price(sqrt_price_x64 + 1).unwrap();
+        tick = get_tick_at_sqrt_price_raw(sqrt_price_x64 + 1).unwrap();
         assert_eq!(tick, -28861);
-        tick = get_tick_at_sqrt_price(get_sqrt_price_at_tick(-28860).unwrap() - 1).unwrap();
+        tick = get_tick_at_sqrt_price_raw(get_sqrt_price_at_tick_raw(-28860).unwrap() - 1).unwrap();
         assert_eq!(tick, -28861);
-        tick = get_tick_at_sqrt_price(sqrt_price_x64 - 1).unwrap();
+        tick = get_tick_at_sqrt_price_raw(sqrt_price_x64 - 1).unwrap();
         assert_eq!(tick, -28862);
 
         // tick is positive
-        let sqrt_price_x64 = get_sqrt_price_at_tick(28861).unwrap();
-        tick = get_tick_at_sqrt_price(sqrt_price_x64).unwrap();
+        let sqrt_price_x64 = get_sqrt_price_at_tick_raw(28861).unwrap();
+        tick = get_tick_at_sqrt_price_raw(sqrt_price_x64).unwrap();
         assert_eq!(tick, 28861);
-        tick = get_tick_at_sqrt_price(sqrt_price_x64 + 1).unwrap();
+        tick = get_tick_at_sqrt_price_raw(sqrt_price_x64 + 1).unwrap();
         assert_eq!(tick, 28861);
-        tick = get_tick_at_sqrt_price(get_sqrt_price_at_tick(28862).unwrap() - 1).unwrap();
+        tick = get_tick_at_sqrt_price_raw(get_sqrt_price_at_tick_raw(28862).unwrap() - 1).unwrap();
         assert_eq!(tick, 28861);
-        tick = get_tick_at_sqrt_price(sqrt_price_x64 - 1).unwrap();
+        tick = get_tick_at_sqrt_price_raw(sqrt_price_x64 - 1).unwrap();
         assert_eq!(tick, 28860);
     }
 
@@ -258,13 +271,13 @@ mod tick_math_test {
             fn get_sqrt_price_at_tick_test (
                 tick in MIN_TICK+1..MAX_TICK-1,
             ) {
-                let sqrt_price_x64 = get_sqrt_price_at_tick(tick).unwrap();
+                let sqrt_price_x64 = get_sqrt_price_at_tick_raw(tick).unwrap();
 
                 assert!(sqrt_price_x64 >= MIN_SQRT_PRICE_X64);
                 assert!(sqrt_price_x64 <= MAX_SQRT_PRICE_X64);
 
-                let minus_tick_price_x64 = get_sqrt_price_at_tick(tick - 1).unwrap();
-                let plus_tick_price_x64 = get_sqrt_price_at_tick(tick + 1).unwrap();
+                let minus_tick_price_x64 = get_sqrt_price_at_tick_raw(tick - 1).unwrap();
+                let plus_tick_price_x64 = get_sqrt_price_at_tick_raw(tick + 1).unwrap();
                 assert!(minus_tick_price_x64 < sqrt_price_x64 && sqrt_price_x64 < plus_tick_price_x64);
             }
 
@@ -272,12 +285,12 @@ mod tick_math_test {
             fn get_tick_at_sqrt_price_test (
                 sqrt_price in MIN_SQRT_PRICE_X64..MAX_SQRT_PRICE_X64
             ) {
-                let tick = get_tick_at_sqrt_price(sqrt_price).unwrap();
+                let tick = get_tick_at_sqrt_price_raw(sqrt_price).unwrap();
 
                 assert!(tick >= MIN_TICK);
                 assert!(tick <= MAX_TICK);
 
-                assert!(sqrt_price >= get_sqrt_price_at_tick(tick).unwrap() && sqrt_price < get_sqrt_price_at_tick(tick + 1).unwrap())
+                assert!(sqrt_price >= get_sqrt_price_at_tick_raw(tick).unwrap() && sqrt_price < get_sqrt_price_at_tick_raw(tick + 1).unwrap())
             }
 
             #[test]
@@ -285,8 +298,8 @@ mod tick_math_test {
                 tick in MIN_TICK..MAX_TICK
             ) {
 
-                let sqrt_price_x64 = get_sqrt_price_at_tick(tick).unwrap();
-                let resolved_tick = get_tick_at_sqrt_price(sqrt_price_x64).unwrap();
+                let sqrt_price_x64 = get_sqrt_price_at_tick_raw(tick).unwrap();
+                let resolved_tick = get_tick_at_sqrt_price_raw(sqrt_price_x64).unwrap();
                 assert!(resolved_tick == tick);
             }
 
@@ -296,8 +309,8 @@ mod tick_math_test {
                 tick in MIN_TICK+1..MAX_TICK
             ) {
 
-                let sqrt_price_x64 = get_sqrt_price_at_tick(tick).unwrap();
-                let last_sqrt_price_x64 = get_sqrt_price_at_tick(tick-1).unwrap();
+                let sqrt_price_x64 = get_sqrt_price_at_tick_raw(tick).unwrap();
+                let last_sqrt_price_x64 = get_sqrt_price_at_tick_raw(tick-1).unwrap();
                 assert!(last_sqrt_price_x64 < sqrt_price_x64);
             }
 
@@ -306,8 +319,8 @@ mod tick_math_test {
                 sqrt_price in (MIN_SQRT_PRICE_X64 + 10)..MAX_SQRT_PRICE_X64
             ) {
 
-                let tick = get_tick_at_sqrt_price(sqrt_price).unwrap();
-                let last_tick = get_tick_at_sqrt_price(sqrt_price - 10).unwrap();
+                let tick = get_tick_at_sqrt_price_raw(sqrt_price).unwrap();
+                let last_tick = get_tick_at_sqrt_price_raw(sqrt_price - 10).unwrap();
                 assert!(last_tick <= tick);
             }
         }
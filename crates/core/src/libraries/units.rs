@@ -0,0 +1,290 @@
+//! Newtypes of the quantities of the pool, so that a sqrt price, a price, a tick and a liquidity can't be
+//! passed one for another. The functions of `tick_math`, `sqrt_price_math`, `liquidity_math` and `swap_math`
+//! take and return them, their `*_raw` shims take the raw integers for the callers that hold the packed
+//! fields of the accounts. A raw integer is wrapped explicitly, e.g. `SqrtPriceX64(pool_state.sqrt_price_x64)`,
+//! there is no implicit conversion from it.
+use super::{fixed_point_64, liquidity_math, sqrt_price_math, tick_math};
+use crate::error::Result;
+
+/// A sqrt price `√(token_1/token_0)` as a Q64.64, not a price
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SqrtPriceX64(pub u128);
+
+/// A tick, the price at tick `i` is `1.0001^i`
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tick(pub i32);
+
+/// A liquidity `L = √(x * y)`
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Liquidity(pub u128);
+
+impl SqrtPriceX64 {
+    pub const MIN: Self = Self(tick_math::MIN_SQRT_PRICE_X64);
+    pub const MAX: Self = Self(tick_math::MAX_SQRT_PRICE_X64);
+
+    pub const fn get(self) -> u128 {
+        self.0
+    }
+
+    /// Get the sqrt price of a price of token_0 in token_1, in the smallest units of the tokens.
+    /// The conversion is lossy, it must not be used where the exact sqrt price matters.
+    pub fn from_price(price: f64) -> Self {
+        Self((price.sqrt() * fixed_point_64::Q64 as f64) as u128)
+    }
+
+    /// Get the price of token_0 in token_1, in the smallest units of the tokens
+    pub fn to_price(self) -> f64 {
+        let sqrt_price = self.0 as f64 / fixed_point_64::Q64 as f64;
+        sqrt_price * sqrt_price
+    }
+
+    /// See `tick_math::get_tick_at_sqrt_price`
+    pub fn to_tick(self) -> Result<Tick> {
+        tick_math::get_tick_at_sqrt_price(self)
+    }
+
+    /// See `sqrt_price_math::get_next_sqrt_price_from_input`
+    pub fn next_from_input(
+        self,
+        liquidity: Liquidity,
+        amount_in: u64,
+        zero_for_one: bool,
+    ) -> Result<Self> {
+        sqrt_price_math::get_next_sqrt_price_from_input(self, liquidity, amount_in, zero_for_one)
+    }
+
+    /// See `sqrt_price_math::get_next_sqrt_price_from_output`
+    pub fn next_from_output(
+        self,
+        liquidity: Liquidity,
+        amount_out: u64,
+        zero_for_one: bool,
+    ) -> Result<Self> {
+        sqrt_price_math::get_next_sqrt_price_from_output(self, liquidity, amount_out, zero_for_one)
+    }
+}
+
+impl Tick {
+    pub const MIN: Self = Self(tick_math::MIN_TICK);
+    pub const MAX: Self = Self(tick_math::MAX_TICK);
+
+    pub const fn get(self) -> i32 {
+        self.0
+    }
+
+    /// See `tick_math::get_sqrt_price_at_tick`
+    pub fn to_sqrt_price(self) -> Result<SqrtPriceX64> {
+        tick_math::get_sqrt_price_at_tick(self)
+    }
+}
+
+impl Liquidity {
+    pub const fn get(self) -> u128 {
+        self.0
+    }
+
+    /// See `liquidity_math::add_delta`
+    pub fn add_delta(self, liquidity_delta: i128) -> Result<Self> {
+        liquidity_math::add_delta(self, liquidity_delta)
+    }
+
+    /// See `liquidity_math::get_liquidity_from_amounts`
+    pub fn from_amounts(
+        sqrt_price: SqrtPriceX64,
+        sqrt_price_a: SqrtPriceX64,
+        sqrt_price_b: SqrtPriceX64,
+        amount_0: u64,
+        amount_1: u64,
+    ) -> Self {
+        liquidity_math::get_liquidity_from_amounts(
+            sqrt_price,
+            sqrt_price_a,
+            sqrt_price_b,
+            amount_0,
+            amount_1,
+        )
+    }
+
+    /// See `liquidity_math::get_delta_amount_0_unsigned`
+    pub fn amount_0_delta(
+        self,
+        sqrt_price_a: SqrtPriceX64,
+        sqrt_price_b: SqrtPriceX64,
+        round_up: bool,
+    ) -> Result<u64> {
+        liquidity_math::get_delta_amount_0_unsigned(sqrt_price_a, sqrt_price_b, self, round_up)
+    }
+
+    /// See `liquidity_math::get_delta_amount_1_unsigned`
+    pub fn amount_1_delta(
+        self,
+        sqrt_price_a: SqrtPriceX64,
+        sqrt_price_b: SqrtPriceX64,
+        round_up: bool,
+    ) -> Result<u64> {
+        liquidity_math::get_delta_amount_1_unsigned(sqrt_price_a, sqrt_price_b, self, round_up)
+    }
+}
+
+impl From<SqrtPriceX64> for u128 {
+    fn from(sqrt_price_x64: SqrtPriceX64) -> Self {
+        sqrt_price_x64.0
+    }
+}
+
+impl From<i32> for Tick {
+    fn from(tick: i32) -> Self {
+        Self(tick)
+    }
+}
+
+impl From<Tick> for i32 {
+    fn from(tick: Tick) -> Self {
+        tick.0
+    }
+}
+
+impl From<Liquidity> for u128 {
+    fn from(liquidity: Liquidity) -> Self {
+        liquidity.0
+    }
+}
+
+#[cfg(test)]
+mod units_test {
+    use super::*;
+    use crate::libraries::swap_math;
+
+    #[test]
+    fn same_as_raw_test() {
+        for tick in [
+            tick_math::MIN_TICK,
+            -1000,
+            0,
+            1,
+            1000,
+            tick_math::MAX_TICK - 1,
+        ] {
+            let sqrt_price = Tick(tick).to_sqrt_price().unwrap();
+            assert_eq!(
+                sqrt_price.get(),
+                tick_math::get_sqrt_price_at_tick_raw(tick).unwrap()
+            );
+            assert_eq!(sqrt_price.to_tick().unwrap(), Tick(tick));
+        }
+        assert_eq!(Tick::MIN.to_sqrt_price().unwrap(), SqrtPriceX64::MIN);
+        assert!(Tick(tick_math::MAX_TICK + 1).to_sqrt_price().is_err());
+
+        let liquidity = Liquidity(1_000_000_000);
+        let sqrt_price_a = Tick(-100).to_sqrt_price().unwrap();
+        let sqrt_price_b = Tick(100).to_sqrt_price().unwrap();
+        assert_eq!(
+            liquidity
+                .amount_0_delta(sqrt_price_a, sqrt_price_b, true)
+                .unwrap(),
+            liquidity_math::get_delta_amount_0_unsigned_raw(
+                sqrt_price_a.into(),
+                sqrt_price_b.into(),
+                liquidity.into(),
+                true
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            SqrtPriceX64(fixed_point_64::Q64)
+                .next_from_input(liquidity, 1000, true)
+                .unwrap()
+                .get(),
+            sqrt_price_math::get_next_sqrt_price_from_input_raw(
+                fixed_point_64::Q64,
+                liquidity.get(),
+                1000,
+                true
+            )
+            .unwrap()
+        );
+        assert_eq!(liquidity.add_delta(-1).unwrap(), Liquidity(999_999_999));
+    }
+
+    #[test]
+    fn typed_same_as_raw_test() {
+        let sqrt_price = Tick(10).to_sqrt_price().unwrap();
+        let sqrt_price_target = Tick(-10).to_sqrt_price().unwrap();
+        let liquidity = Liquidity(1_000_000_000);
+        let step = swap_math::compute_swap_step(
+            sqrt_price,
+            sqrt_price_target,
+            liquidity,
+            1000,
+            3000,
+            true,
+            true,
+        )
+        .unwrap();
+        let step_raw = swap_math::compute_swap_step_raw(
+            sqrt_price.get(),
+            sqrt_price_target.get(),
+            liquidity.get(),
+            1000,
+            3000,
+            true,
+            true,
+        )
+        .unwrap();
+        assert_eq!(step.sqrt_price_next_x64, step_raw.sqrt_price_next_x64);
+        assert_eq!(step.amount_in, step_raw.amount_in);
+        assert_eq!(step.amount_out, step_raw.amount_out);
+        assert_eq!(step.fee_amount, step_raw.fee_amount);
+
+        assert_eq!(
+            liquidity_math::get_delta_amounts_signed(
+                Tick(10),
+                sqrt_price,
+                Tick(-100),
+                Tick(100),
+                1_000_000
+            )
+            .unwrap(),
+            liquidity_math::get_delta_amounts_signed_raw(
+                10,
+                sqrt_price.get(),
+                -100,
+                100,
+                1_000_000
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Liquidity::from_amounts(
+                sqrt_price,
+                Tick(-100).to_sqrt_price().unwrap(),
+                Tick(100).to_sqrt_price().unwrap(),
+                1000,
+                1000
+            )
+            .get(),
+            liquidity_math::get_liquidity_from_amounts_raw(
+                sqrt_price.get(),
+                tick_math::get_sqrt_price_at_tick_raw(-100).unwrap(),
+                tick_math::get_sqrt_price_at_tick_raw(100).unwrap(),
+                1000,
+                1000
+            )
+        );
+    }
+
+    #[test]
+    fn price_test() {
+        assert_eq!(SqrtPriceX64(fixed_point_64::Q64).to_price(), 1.0);
+        assert_eq!(
+            SqrtPriceX64::from_price(4.0),
+            SqrtPriceX64(2 * fixed_point_64::Q64)
+        );
+        // the price at tick 1000 is 1.0001^1000, not the sqrt price
+        let price = Tick(1000).to_sqrt_price().unwrap().to_price();
+        assert!((price - 1.0001f64.powi(1000)).abs() < 1e-9);
+    }
+}
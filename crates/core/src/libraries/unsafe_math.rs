@@ -10,6 +10,7 @@ pub trait UnsafeMathTrait {
 }
 
 impl UnsafeMathTrait for u64 {
+    #[allow(clippy::manual_is_multiple_of)] // is_multiple_of is newer than the rustc of the Solana platform tools
     fn div_rounding_up(x: Self, y: Self) -> Self {
         x / y + ((x % y > 0) as u64)
     }
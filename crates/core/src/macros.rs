@@ -0,0 +1,55 @@
+//! The `require!` macros of the math, the anchor ones with the `anchor` feature, which also log the source
+//! of the error, and plain ones returning `error::Error` without it.
+//! The anchor `require_gt!` and `require_gte!` call `error!` by name, import them with a glob.
+
+// which ones are used depends on the features
+#[cfg(feature = "anchor")]
+#[allow(unused_imports)]
+pub(crate) use anchor_lang::{error, prelude::msg, require, require_gt, require_gte};
+
+#[cfg(not(feature = "anchor"))]
+#[allow(unused_macros, unused_imports)]
+mod plain {
+    macro_rules! error {
+        ($error:expr) => {
+            $crate::error::Error::from($error)
+        };
+    }
+
+    macro_rules! require {
+        ($invariant:expr, $error:expr $(,)?) => {
+            if !($invariant) {
+                return Err($crate::error::Error::from($error));
+            }
+        };
+    }
+
+    macro_rules! require_gt {
+        ($value1:expr, $value2:expr, $error:expr $(,)?) => {
+            if $value1 <= $value2 {
+                return Err($crate::error::Error::from($error));
+            }
+        };
+    }
+
+    macro_rules! require_gte {
+        ($value1:expr, $value2:expr, $error:expr $(,)?) => {
+            if $value1 < $value2 {
+                return Err($crate::error::Error::from($error));
+            }
+        };
+    }
+
+    // nowhere to log to off-chain
+    macro_rules! msg {
+        ($($arg:tt)*) => {
+            let _ = format_args!($($arg)*);
+        };
+    }
+
+    pub(crate) use {error, msg, require, require_gt, require_gte};
+}
+
+#[cfg(not(feature = "anchor"))]
+#[allow(unused_imports)]
+pub(crate) use plain::*;
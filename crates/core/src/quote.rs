@@ -0,0 +1,497 @@
+//! Swap quotes on the plain account layouts of `states`, for the services embedding the exact swap math
+//! without anchor and the program. The swap loop is the one of the program `swap_engine::swap_internal`, it
+//! steps to the initialized ticks of the tick arrays passed instead of looking the tick arrays up in the bitmaps
+//! of the pool. The program tests check both quote the same.
+
+use crate::error::{ErrorCode, Result};
+use crate::libraries::{liquidity_math, swap_math, tick_math};
+use crate::macros::*;
+use crate::states::*;
+use crate::transfer_fee::TransferFeeConfig;
+
+/// The tick arrays the default tick array bitmap of a pool records on each side of tick 0
+const TICK_ARRAY_BITMAP_SIZE: i32 = 512;
+
+/// A decoded tick array account
+// a quote decodes a few tick arrays, boxing the dynamic header too isn't worth the indirection
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum TickArrayData {
+    Fixed(Box<TickArrayState>),
+    Dynamic(DynTickArrayState, Vec<TickState>),
+}
+
+impl TickArrayData {
+    /// Decode a fixed or dynamic tick array from the account data
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.starts_with(&DynTickArrayState::DISCRIMINATOR) {
+            let header = DynTickArrayState::try_from_account_data(data)?;
+            require!(
+                data.len() == header.all_data_len(),
+                ErrorCode::InvalidTickArray
+            );
+            let ticks = data[DynTickArrayState::LEN..]
+                .chunks_exact(std::mem::size_of::<TickState>())
+                .map(bytemuck::pod_read_unaligned)
+                .collect();
+            Ok(TickArrayData::Dynamic(header, ticks))
+        } else {
+            let tick_array = TickArrayState::try_from_account_data(data)?;
+            Ok(TickArrayData::Fixed(Box::new(tick_array)))
+        }
+    }
+
+    /// Whether it is a fixed tick array, with all TickStates allocated
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, TickArrayData::Fixed(_))
+    }
+
+    pub fn pool_id(&self) -> Address {
+        match self {
+            TickArrayData::Fixed(tick_array) => tick_array.pool_id,
+            TickArrayData::Dynamic(header, _) => header.pool_id,
+        }
+    }
+
+    pub fn start_tick_index(&self) -> i32 {
+        match self {
+            TickArrayData::Fixed(tick_array) => tick_array.start_tick_index,
+            TickArrayData::Dynamic(header, _) => header.start_tick_index,
+        }
+    }
+
+    /// The initialized TickStates of this tick array, those of a dynamic tick array through its offset index
+    pub fn initialized_tick_states(&self) -> impl Iterator<Item = &TickState> {
+        let (fixed, dynamic) = match self {
+            TickArrayData::Fixed(tick_array) => (Some(tick_array.ticks.iter()), None),
+            TickArrayData::Dynamic(header, ticks) => (
+                None,
+                Some(header.tick_offset_index.iter().filter_map(|&index| {
+                    usize::from(index)
+                        .checked_sub(1)
+                        .and_then(|index| ticks.get(index))
+                })),
+            ),
+        };
+        fixed
+            .into_iter()
+            .flatten()
+            .chain(dynamic.into_iter().flatten())
+            .filter(|tick_state| tick_state.is_initialized())
+    }
+}
+
+/// The result of a simulated swap
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Input token amount including the trade fee, excluding the transfer fee
+    pub amount_in: u64,
+    /// Output token amount, excluding the transfer fee
+    pub amount_out: u64,
+    /// Input token amount debited from the wallet, `amount_in` plus the transfer fee of the input mint
+    pub amount_in_after_transfer_fee: u64,
+    /// Output token amount credited to the wallet, `amount_out` minus the transfer fee of the output mint
+    pub amount_out_after_transfer_fee: u64,
+    /// Trade fee paid in input token, including the protocol and fund fee
+    pub fee_amount: u64,
+    /// The pool sqrt price after the swap
+    pub sqrt_price_x64: u128,
+    /// The pool tick after the swap
+    pub tick_current: i32,
+    /// The pool liquidity after the swap
+    pub liquidity: u128,
+    /// The initialized ticks crossed by the swap
+    pub ticks_crossed: u32,
+    /// Whether the swap stopped before `amount_specified` was filled, at the sqrt price limit or
+    /// at the end of the liquidity. Without a sqrt price limit, `swap_v2` fails instead
+    pub is_partial_fill: bool,
+}
+
+/// The swap parameters of a quote
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QuoteScenario {
+    /// amount_in if `is_base_input`, otherwise amount_out
+    pub amount_specified: u64,
+    /// The Q64.64 sqrt price √P limit, zero means no limit
+    pub sqrt_price_limit_x64: u128,
+    pub zero_for_one: bool,
+    pub is_base_input: bool,
+}
+
+/// The Token-2022 transfer fee configs of the pool mints, `None` for a mint without transfer fee
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MintTransferFees {
+    pub transfer_fee_config_0: Option<TransferFeeConfig>,
+    pub transfer_fee_config_1: Option<TransferFeeConfig>,
+    /// The epoch the transfers happen at, selects the older or newer transfer fee of the configs
+    pub epoch: u64,
+}
+
+impl MintTransferFees {
+    /// Get the transfer fee config from the account data of a mint, `None` if it has no transfer fee
+    pub fn transfer_fee_config(mint_data: &[u8]) -> Option<TransferFeeConfig> {
+        TransferFeeConfig::from_mint_data(mint_data)
+    }
+
+    fn transfer_fee(config: &Option<TransferFeeConfig>, epoch: u64, pre_fee_amount: u64) -> u64 {
+        config.as_ref().map_or(0, |config| {
+            config.calculate_epoch_fee(epoch, pre_fee_amount)
+        })
+    }
+
+    fn transfer_inverse_fee(
+        config: &Option<TransferFeeConfig>,
+        epoch: u64,
+        post_fee_amount: u64,
+    ) -> Result<u64> {
+        config.as_ref().map_or(Ok(0), |config| {
+            config.calculate_inverse_epoch_fee(epoch, post_fee_amount)
+        })
+    }
+
+    /// Quote `scenario` with these transfer fees, `scenario.amount_specified` is the wallet amount and
+    /// the transfer fees are charged the same way as `swap_v2`. `quote` simulates the swap of the amount
+    /// the pool receives or pays, the returned quote adds the transfer fees to its amounts
+    pub fn quote_with_transfer_fees(
+        &self,
+        scenario: &QuoteScenario,
+        quote: impl FnOnce(&QuoteScenario) -> Result<SwapQuote>,
+    ) -> Result<SwapQuote> {
+        let epoch = self.epoch;
+        let (config_in, config_out) = if scenario.zero_for_one {
+            (&self.transfer_fee_config_0, &self.transfer_fee_config_1)
+        } else {
+            (&self.transfer_fee_config_1, &self.transfer_fee_config_0)
+        };
+        // the amount includes transfer_fee as input and excludes it as output
+        let (amount_calculate_specified, transfer_fee) = if scenario.is_base_input {
+            let transfer_fee = Self::transfer_fee(config_in, epoch, scenario.amount_specified);
+            (scenario.amount_specified - transfer_fee, transfer_fee)
+        } else {
+            let transfer_fee =
+                Self::transfer_inverse_fee(config_out, epoch, scenario.amount_specified)?;
+            (
+                scenario.amount_specified.checked_add(transfer_fee).unwrap(),
+                transfer_fee,
+            )
+        };
+        let mut quote = quote(&QuoteScenario {
+            amount_specified: amount_calculate_specified,
+            ..*scenario
+        })?;
+
+        let transfer_fee_in =
+            if scenario.is_base_input && quote.amount_in == amount_calculate_specified {
+                transfer_fee
+            } else {
+                Self::transfer_inverse_fee(config_in, epoch, quote.amount_in)?
+            };
+        let transfer_fee_out = Self::transfer_fee(config_out, epoch, quote.amount_out);
+        quote.amount_in_after_transfer_fee = quote.amount_in.checked_add(transfer_fee_in).unwrap();
+        quote.amount_out_after_transfer_fee = quote.amount_out - transfer_fee_out;
+        Ok(quote)
+    }
+}
+
+/// Simulate a swap on the decoded accounts, the same way as `swap_v2` does on chain
+///
+/// # Arguments
+///
+/// * `amm_config` - The config of the pool
+/// * `pool_id` - The address of the pool, the tick arrays of other pools are ignored
+/// * `pool_state` - The pool to swap in
+/// * `tick_arrays` - The initialized tick arrays of the pool, in any order. A tick array the swap crosses
+///   must be there if it is in the range of the default tick array bitmap of the pool, otherwise the swap fails
+///   with `NotEnoughTickArrayAccount`. Out of that range, the ticks of the tick arrays missing are not crossed
+/// * `transfer_fees` - The transfer fees of the pool mints, the default for none
+/// * `amount_specified` - amount_in debited from the wallet if `is_base_input`,
+///   otherwise amount_out credited to the wallet, as `swap_v2` takes it
+/// * `sqrt_price_limit_x64` - The Q64.64 sqrt price √P limit, zero means no limit
+/// * `zero_for_one` - Swap token_0 for token_1 or the opposite
+/// * `is_base_input` - swap base input or swap base output
+/// * `block_timestamp` - The unix timestamp the swap is simulated at
+///
+// the arguments of the program `compute_swap_quote`, with the pool address
+#[allow(clippy::too_many_arguments)]
+pub fn compute_swap_quote(
+    amm_config: &AmmConfig,
+    pool_id: &Address,
+    pool_state: &PoolState,
+    tick_arrays: &[TickArrayData],
+    transfer_fees: &MintTransferFees,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u64,
+) -> Result<SwapQuote> {
+    let scenario = QuoteScenario {
+        amount_specified,
+        sqrt_price_limit_x64,
+        zero_for_one,
+        is_base_input,
+    };
+    transfer_fees.quote_with_transfer_fees(&scenario, |scenario| {
+        quote(
+            amm_config,
+            pool_id,
+            pool_state,
+            tick_arrays,
+            scenario,
+            block_timestamp,
+        )
+    })
+}
+
+/// Simulate a swap of `scenario` at `block_timestamp` without transfer fees, see `compute_swap_quote`
+pub fn quote(
+    amm_config: &AmmConfig,
+    pool_id: &Address,
+    pool_state: &PoolState,
+    tick_arrays: &[TickArrayData],
+    scenario: &QuoteScenario,
+    block_timestamp: u64,
+) -> Result<SwapQuote> {
+    let QuoteScenario {
+        amount_specified,
+        zero_for_one,
+        is_base_input,
+        ..
+    } = *scenario;
+    require_gt!(block_timestamp, pool_state.open_time, ErrorCode::NotApproved);
+    let ticks = InitializedTicks::new(pool_id, pool_state, tick_arrays, zero_for_one);
+    require!(
+        !ticks.start_indexes.is_empty(),
+        ErrorCode::NotEnoughTickArrayAccount
+    );
+    let sqrt_price_limit_x64 =
+        swap_math::normalize_sqrt_price_limit(scenario.sqrt_price_limit_x64, zero_for_one)?;
+    let max_swap_iterations = amm_config.get_max_swap_iterations()?;
+    require!(amount_specified != 0, ErrorCode::ZeroAmountSpecified);
+    require!(
+        pool_state.is_swap_enabled() && amm_config.is_swap_enabled(),
+        ErrorCode::NotApproved
+    );
+    swap_math::check_sqrt_price_limit(pool_state.sqrt_price_x64, sqrt_price_limit_x64, zero_for_one)?;
+
+    let is_decay_fee_on_sell = if zero_for_one {
+        pool_state.is_decay_fee_on_sell_mint0()
+    } else {
+        pool_state.is_decay_fee_on_sell_mint1()
+    };
+    // the swap disables an expired decay fee, and a decay fee on sell not above the trade fee
+    let mut is_decay_fee_enabled =
+        pool_state.is_decay_fee_enabled() && !pool_state.is_decay_fee_expired(block_timestamp);
+
+    let mut amount_specified_remaining = amount_specified;
+    let mut amount_calculated = 0u64;
+    let mut fee_amount = 0u64;
+    let mut sqrt_price_x64 = pool_state.sqrt_price_x64;
+    let mut tick = pool_state.tick_current;
+    let mut liquidity = pool_state.liquidity;
+    let mut ticks_crossed = 0u32;
+    let mut swap_iterations: u16 = 0;
+    while amount_specified_remaining != 0 && sqrt_price_x64 != sqrt_price_limit_x64 {
+        swap_iterations += 1;
+        require_gte!(
+            max_swap_iterations,
+            swap_iterations,
+            ErrorCode::MaxSwapIterationsExceeded
+        );
+        let sqrt_price_start_x64 = sqrt_price_x64;
+        let (tick_next, liquidity_net) = ticks.next(tick)?;
+        let sqrt_price_next_x64 = tick_math::get_sqrt_price_at_tick_raw(tick_next)?;
+        let target_price = if (zero_for_one && sqrt_price_next_x64 < sqrt_price_limit_x64)
+            || (!zero_for_one && sqrt_price_next_x64 > sqrt_price_limit_x64)
+        {
+            sqrt_price_limit_x64
+        } else {
+            sqrt_price_next_x64
+        };
+
+        let mut trade_fee_rate = amm_config.trade_fee_rate;
+        if is_decay_fee_enabled && is_decay_fee_on_sell {
+            let decay_fee_rate = pool_state.get_decay_fee_rate(block_timestamp);
+            if decay_fee_rate > trade_fee_rate {
+                trade_fee_rate = decay_fee_rate;
+            } else {
+                is_decay_fee_enabled = false;
+            }
+        }
+
+        // nothing to trade in a range without liquidity, the step jumps to the next tick or the price limit
+        let swap_step = if liquidity == 0 {
+            swap_math::SwapStep {
+                sqrt_price_next_x64: target_price,
+                ..Default::default()
+            }
+        } else {
+            swap_math::compute_swap_step_raw(
+                sqrt_price_start_x64,
+                target_price,
+                liquidity,
+                amount_specified_remaining,
+                trade_fee_rate,
+                is_base_input,
+                zero_for_one,
+            )?
+        };
+        sqrt_price_x64 = swap_step.sqrt_price_next_x64;
+        if is_base_input {
+            amount_specified_remaining = amount_specified_remaining
+                .checked_sub(swap_step.amount_in + swap_step.fee_amount)
+                .unwrap();
+            amount_calculated = amount_calculated
+                .checked_add(swap_step.amount_out)
+                .unwrap();
+        } else {
+            amount_specified_remaining = amount_specified_remaining
+                .checked_sub(swap_step.amount_out)
+                .unwrap();
+            amount_calculated = swap_step
+                .amount_in
+                .checked_add(swap_step.fee_amount)
+                .and_then(|amount| amount_calculated.checked_add(amount))
+                .ok_or(ErrorCode::CalculateOverflow)?;
+        }
+        fee_amount = fee_amount.saturating_add(swap_step.fee_amount);
+
+        // shift tick if we reached the next price
+        if sqrt_price_x64 == sqrt_price_next_x64 {
+            let liquidity_net = if zero_for_one {
+                -liquidity_net
+            } else {
+                liquidity_net
+            };
+            liquidity = liquidity_math::add_delta_raw(liquidity, liquidity_net)?;
+            ticks_crossed += 1;
+            tick = if zero_for_one { tick_next - 1 } else { tick_next };
+        } else if sqrt_price_x64 != sqrt_price_start_x64 {
+            // recompute unless we're on a lower tick boundary (i.e. already transitioned ticks), and haven't moved
+            tick = tick_math::get_tick_at_sqrt_price_raw(sqrt_price_x64)?;
+        }
+    }
+
+    if is_decay_fee_enabled && is_decay_fee_on_sell && pool_state.is_bootstrap_price_floor_enabled()
+    {
+        let floor = pool_state.bootstrap_sqrt_price_floor_x64;
+        if zero_for_one {
+            require_gte!(sqrt_price_x64, floor, ErrorCode::BootstrapPriceFloorBreached);
+        } else {
+            require_gte!(floor, sqrt_price_x64, ErrorCode::BootstrapPriceFloorBreached);
+        }
+    }
+
+    let amount_swapped = amount_specified - amount_specified_remaining;
+    let (amount_in, amount_out) = if is_base_input {
+        (amount_swapped, amount_calculated)
+    } else {
+        (amount_calculated, amount_swapped)
+    };
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        amount_in_after_transfer_fee: amount_in,
+        amount_out_after_transfer_fee: amount_out,
+        fee_amount,
+        sqrt_price_x64,
+        tick_current: tick,
+        liquidity,
+        ticks_crossed,
+        is_partial_fill: amount_swapped < amount_specified,
+    })
+}
+
+/// The start index of the tick array of `tick`
+fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = i32::from(tick_spacing) * TICK_ARRAY_SIZE;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// The initialized ticks of the tick arrays of a pool, in the swap direction
+struct InitializedTicks<'a> {
+    pool_state: &'a PoolState,
+    zero_for_one: bool,
+    /// The (tick, liquidity_net) of the initialized ticks in ascending tick order
+    ticks: Vec<(i32, i128)>,
+    /// The start indexes of the tick arrays in ascending order
+    start_indexes: Vec<i32>,
+}
+
+impl<'a> InitializedTicks<'a> {
+    fn new(
+        pool_id: &Address,
+        pool_state: &'a PoolState,
+        tick_arrays: &[TickArrayData],
+        zero_for_one: bool,
+    ) -> Self {
+        let mut ticks = Vec::new();
+        let mut start_indexes = Vec::new();
+        for tick_array in tick_arrays
+            .iter()
+            .filter(|tick_array| tick_array.pool_id() == *pool_id)
+        {
+            start_indexes.push(tick_array.start_tick_index());
+            ticks.extend(
+                tick_array
+                    .initialized_tick_states()
+                    .map(|tick_state| (tick_state.tick, tick_state.liquidity_net)),
+            );
+        }
+        ticks.sort_unstable_by_key(|&(tick, _)| tick);
+        start_indexes.sort_unstable();
+        Self {
+            pool_state,
+            zero_for_one,
+            ticks,
+            start_indexes,
+        }
+    }
+
+    /// The next initialized tick from `tick` in the swap direction, at or below it for zero_for_one and above it
+    /// otherwise. Fails with `NotEnoughTickArrayAccount` if the default tick array bitmap of the pool has an
+    /// initialized tick array before it that is missing, and with `LiquidityInsufficient` if there is none
+    fn next(&self, tick: i32) -> Result<(i32, i128)> {
+        let index = self.ticks.partition_point(|&(initialized_tick, _)| initialized_tick <= tick);
+        let next = if self.zero_for_one {
+            index.checked_sub(1).map(|index| self.ticks[index])
+        } else {
+            self.ticks.get(index).copied()
+        };
+
+        // the tick arrays from the one of `tick` to the one of the next tick, excluded, or to the end of the bitmap
+        let tick_spacing = self.pool_state.tick_spacing;
+        let ticks_in_array = i32::from(tick_spacing) * TICK_ARRAY_SIZE;
+        let boundary = ticks_in_array * TICK_ARRAY_BITMAP_SIZE;
+        let start_index = tick_array_start_index(tick, tick_spacing);
+        let next_start_index =
+            next.map(|(next_tick, _)| tick_array_start_index(next_tick, tick_spacing));
+        let (from, to) = if self.zero_for_one {
+            (
+                next_start_index.map_or(-boundary, |next_start_index| {
+                    next_start_index + ticks_in_array
+                }),
+                start_index,
+            )
+        } else {
+            (
+                start_index,
+                next_start_index.map_or(boundary, |next_start_index| next_start_index)
+                    - ticks_in_array,
+            )
+        };
+        let tick_array_bitmap = { self.pool_state.tick_array_bitmap };
+        let mut start_index = from.max(-boundary);
+        while start_index <= to.min(boundary - ticks_in_array) {
+            let bit = (start_index / ticks_in_array + TICK_ARRAY_BITMAP_SIZE) as usize;
+            require!(
+                tick_array_bitmap[bit / 64] & (1 << (bit % 64)) == 0
+                    || self.start_indexes.binary_search(&start_index).is_ok(),
+                ErrorCode::NotEnoughTickArrayAccount
+            );
+            start_index += ticks_in_array;
+        }
+
+        next.ok_or_else(|| error!(ErrorCode::LiquidityInsufficient))
+    }
+}
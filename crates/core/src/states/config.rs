@@ -0,0 +1,56 @@
+use super::{AccountLayout, Address, POOL_STATUS_SWAP_BIT};
+use crate::error::{ErrorCode, Result};
+use crate::macros::*;
+use bytemuck::{Pod, Zeroable};
+
+/// The protocol max of the steps of a swap, each step crosses at most one initialized tick or tick array
+pub const MAX_SWAP_ITERATIONS: u16 = 1024;
+
+/// The layout of the `AmmConfig` account. It is a borsh account, whose fields are all fixed size,
+/// so its borsh encoding is this packed layout
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct AmmConfig {
+    pub bump: u8,
+    pub index: u16,
+    pub owner: Address,
+    pub protocol_fee_rate: u32,
+    /// The trade fee, in hundredths of a bip (10^-6)
+    pub trade_fee_rate: u32,
+    pub tick_spacing: u16,
+    pub fund_fee_rate: u32,
+    pub min_range_ticks: u32,
+    pub fund_owner: Address,
+    pub max_range_ticks: u32,
+    /// The operations disabled in all the pools of the config, with the bits of the pool status
+    pub status: u8,
+    pub default_prealloc_ticks: u8,
+    /// The max steps of a swap, 0 for the protocol max
+    pub max_swap_iterations: u16,
+    pub padding: [u64; 2],
+}
+
+impl AccountLayout for AmmConfig {
+    const DISCRIMINATOR: [u8; 8] = [218, 244, 33, 104, 203, 203, 43, 111];
+}
+
+impl AmmConfig {
+    pub fn is_swap_enabled(&self) -> bool {
+        self.status & (1 << POOL_STATUS_SWAP_BIT) == 0
+    }
+
+    /// The max steps of a swap, the config default or `MAX_SWAP_ITERATIONS` if it is 0
+    pub fn get_max_swap_iterations(&self) -> Result<u16> {
+        let max_swap_iterations = self.max_swap_iterations;
+        require_gte!(
+            MAX_SWAP_ITERATIONS,
+            max_swap_iterations,
+            ErrorCode::InvalidMaxSwapIterations
+        );
+        Ok(if max_swap_iterations == 0 {
+            MAX_SWAP_ITERATIONS
+        } else {
+            max_swap_iterations
+        })
+    }
+}
@@ -0,0 +1,54 @@
+//! The plain layouts of the program accounts read off-chain, the pool, its amm config and its tick arrays.
+//! They are `bytemuck::Pod` structs with the fields of the program accounts in the same order, decoded from
+//! the raw account data without anchor. The anchor accounts, with their methods, stay in the program crate,
+//! which checks that their layouts are the same as these ones.
+//! With the `anchor` feature they implement `anchor_lang::Discriminator`, and `anchor_lang::ZeroCopy` for the
+//! zero copy ones, with the discriminators of the program accounts.
+pub mod config;
+pub mod pool;
+pub mod tick_array;
+
+pub use config::*;
+pub use pool::*;
+pub use tick_array::*;
+
+use crate::error::{ErrorCode, Result};
+use crate::macros::require;
+use bytemuck::Pod;
+
+/// The bytes of a public key
+pub type Address = [u8; 32];
+
+/// An account layout prefixed by the 8 bytes discriminator of its anchor account
+pub trait AccountLayout: Pod {
+    const DISCRIMINATOR: [u8; 8];
+    /// The length of the account data, discriminator included
+    const LEN: usize = 8 + std::mem::size_of::<Self>();
+
+    /// Decode the account from its raw data, the data can be unaligned and longer than the layout
+    fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        require!(
+            data.starts_with(&Self::DISCRIMINATOR) && data.len() >= Self::LEN,
+            ErrorCode::InvalidAccount
+        );
+        Ok(bytemuck::pod_read_unaligned(&data[8..Self::LEN]))
+    }
+}
+
+/// Implement the anchor discriminator of the layouts, and `ZeroCopy` for the zero copy ones
+macro_rules! anchor_account_layout {
+    ($($layout:ty),* $(; zero_copy $($zero_copy:ty),*)?) => {
+        $(
+            #[cfg(feature = "anchor")]
+            impl anchor_lang::Discriminator for $layout {
+                const DISCRIMINATOR: &'static [u8] = &<$layout as AccountLayout>::DISCRIMINATOR;
+            }
+        )*
+        $($(
+            #[cfg(feature = "anchor")]
+            impl anchor_lang::ZeroCopy for $zero_copy {}
+        )*)?
+    };
+}
+
+anchor_account_layout!(AmmConfig, PoolState, TickArrayState, DynTickArrayState; zero_copy PoolState, TickArrayState, DynTickArrayState);
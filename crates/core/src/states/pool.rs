@@ -0,0 +1,153 @@
+use super::{AccountLayout, Address};
+use crate::libraries::fee_math;
+use bytemuck::{Pod, Zeroable};
+
+pub const REWARD_NUM: usize = 3;
+pub const HOT_TICK_ARRAY_NUM: usize = 8;
+/// The bit of the pool and amm config status disabling the swaps, `PoolStatusBitIndex::Swap` in the program
+pub const POOL_STATUS_SWAP_BIT: u8 = 4;
+
+/// The layout of the `PoolState` account
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct PoolState {
+    pub bump: [u8; 1],
+    pub amm_config: Address,
+    pub owner: Address,
+    pub token_mint_0: Address,
+    pub token_mint_1: Address,
+    pub token_vault_0: Address,
+    pub token_vault_1: Address,
+    pub observation_key: Address,
+    pub mint_decimals_0: u8,
+    pub mint_decimals_1: u8,
+    pub tick_spacing: u16,
+    /// The liquidity in range
+    pub liquidity: u128,
+    /// The current price as a sqrt(token_1/token_0) Q64.64 value
+    pub sqrt_price_x64: u128,
+    /// The current tick, the tick of the last tick transition
+    pub tick_current: i32,
+    pub padding3: u16,
+    pub padding4: u16,
+    pub fee_growth_global_0_x64: u128,
+    pub fee_growth_global_1_x64: u128,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub swap_in_amount_token_0: u128,
+    pub swap_out_amount_token_1: u128,
+    pub swap_in_amount_token_1: u128,
+    pub swap_out_amount_token_0: u128,
+    /// The disabled operations, bit4 disables the swaps
+    pub status: u8,
+    pub padding: [u8; 7],
+    pub reward_infos: [RewardInfo; REWARD_NUM],
+    pub tick_array_bitmap: [u64; 16],
+    pub total_fees_token_0: u64,
+    pub total_fees_claimed_token_0: u64,
+    pub total_fees_token_1: u64,
+    pub total_fees_claimed_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+    /// The timestamp the swaps are allowed from
+    pub open_time: u64,
+    pub recent_epoch: u64,
+    /// bit0 enables the decay fee, bit1 and bit2 charge it on the sells of mint0 and mint1
+    pub decay_fee_flag: u8,
+    pub decay_fee_init_fee_rate: u8,
+    pub decay_fee_decrease_rate: u8,
+    pub decay_fee_decrease_interval: u8,
+    pub maker_rebate_rate: u32,
+    pub event_seq: u64,
+    pub bootstrap_sqrt_price_floor_x64: u128,
+    pub tick_array_flag: u8,
+    pub min_position_age_slots: u16,
+    pub prealloc_tick_count: u8,
+    pub tick_array_count: u32,
+    pub hot_tick_array_start_indexes: [i32; HOT_TICK_ARRAY_NUM],
+    pub hot_tick_array_head: u8,
+    pub hot_tick_array_len: u8,
+    pub padding1_3: [u8; 6],
+    pub min_range_ticks: u32,
+    pub max_range_ticks: u32,
+    pub last_swap_ts: u64,
+    pub last_liquidity_change_ts: u64,
+    pub sunset_ts: u64,
+    pub last_swap_slot: u64,
+    /// The decay fee ends at this timestamp, 0 if it only ends when it decays to the trade fee
+    pub decay_fee_end_time: u64,
+    pub seconds_per_liquidity_cumulative_x64: u128,
+    pub seconds_per_liquidity_updated_at: u64,
+    pub seed_amm_config: Address,
+    pub padding1: [u64; 1],
+    pub padding2: [u64; 32],
+}
+
+impl AccountLayout for PoolState {
+    const DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+}
+
+/// The reads of the swap, the same as the methods of the same name of the program `PoolState`
+impl PoolState {
+    pub fn is_swap_enabled(&self) -> bool {
+        self.status & (1 << POOL_STATUS_SWAP_BIT) == 0
+    }
+
+    pub fn is_decay_fee_enabled(&self) -> bool {
+        self.decay_fee_flag & (1 << 0) != 0
+    }
+
+    pub fn is_decay_fee_on_sell_mint0(&self) -> bool {
+        self.decay_fee_flag & (1 << 1) != 0
+    }
+
+    pub fn is_decay_fee_on_sell_mint1(&self) -> bool {
+        self.decay_fee_flag & (1 << 2) != 0
+    }
+
+    /// Whether the decay fee is enabled past its end time, a swap disables it
+    pub fn is_decay_fee_expired(&self, current_timestamp: u64) -> bool {
+        self.is_decay_fee_enabled()
+            && self.decay_fee_end_time != 0
+            && current_timestamp >= self.decay_fee_end_time
+    }
+
+    /// The price floor only applies in the decay window
+    pub fn is_bootstrap_price_floor_enabled(&self) -> bool {
+        self.is_decay_fee_enabled() && self.decay_fee_flag & (1 << 3) != 0
+    }
+
+    /// Get the decay fee rate based on the current time, in hundredths of a bip (10^-6)
+    pub fn get_decay_fee_rate(&self, current_timestamp: u64) -> u32 {
+        if !self.is_decay_fee_enabled()
+            || current_timestamp < self.open_time
+            || (self.decay_fee_end_time != 0 && current_timestamp >= self.decay_fee_end_time)
+        {
+            return 0;
+        }
+        let interval_count =
+            (current_timestamp - self.open_time) / self.decay_fee_decrease_interval as u64;
+        fee_math::decay_fee_rate(
+            self.decay_fee_init_fee_rate,
+            self.decay_fee_decrease_rate,
+            interval_count,
+        )
+    }
+}
+
+/// The layout of the reward infos of the pool
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct RewardInfo {
+    pub reward_state: u8,
+    pub open_time: u64,
+    pub end_time: u64,
+    pub last_update_time: u64,
+    pub emissions_per_second_x64: u128,
+    pub reward_total_emissioned: u64,
+    pub reward_claimed: u64,
+    pub token_mint: Address,
+    pub token_vault: Address,
+    pub authority: Address,
+    pub reward_growth_global_x64: u128,
+}
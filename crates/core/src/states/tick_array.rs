@@ -0,0 +1,77 @@
+use super::{AccountLayout, Address, REWARD_NUM};
+use bytemuck::{Pod, Zeroable};
+
+pub const TICK_ARRAY_SIZE_USIZE: usize = 60;
+pub const TICK_ARRAY_SIZE: i32 = 60;
+
+/// The layout of a tick of the tick arrays
+#[repr(C, packed)]
+#[derive(Clone, Copy, Default, Pod, Zeroable)]
+pub struct TickState {
+    pub tick: i32,
+    /// The liquidity added when the tick is crossed from left to right, subtracted from right to left
+    pub liquidity_net: i128,
+    /// The liquidity of the positions bounded by the tick, 0 if the tick is not initialized
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+    pub reward_growths_outside_x64: [u128; REWARD_NUM],
+    pub maker_rebate_growth_0_x64: u128,
+    pub maker_rebate_growth_1_x64: u128,
+    pub seconds_per_liquidity_outside_x64: u128,
+    pub seconds_outside: u32,
+}
+
+impl TickState {
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross != 0
+    }
+}
+
+/// The layout of the `TickArrayState` account, a fixed tick array with all its ticks allocated
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct TickArrayState {
+    pub pool_id: Address,
+    pub start_tick_index: i32,
+    pub ticks: [TickState; TICK_ARRAY_SIZE_USIZE],
+    pub initialized_tick_count: u8,
+    pub recent_epoch: u64,
+    pub padding: [u8; 107],
+}
+
+impl AccountLayout for TickArrayState {
+    const DISCRIMINATOR: [u8; 8] = [192, 155, 85, 205, 49, 249, 129, 42];
+}
+
+/// The layout of the header of the `DynTickArrayState` account, a dynamic tick array,
+/// followed by its allocated `TickState`s
+#[repr(C, packed)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct DynTickArrayState {
+    pub pool_id: Address,
+    pub start_tick_index: i32,
+    pub padding_0: [u8; 4],
+    /// The position + 1 of the `TickState` of each tick of the array after the header, 0 if it is not allocated
+    pub tick_offset_index: [u8; TICK_ARRAY_SIZE_USIZE],
+    pub alloc_tick_count: u8,
+    pub initialized_tick_count: u8,
+    pub reserved_tick_count: u8,
+    pub padding_1: [u8; 1],
+    pub recent_epoch: u64,
+    pub treasury_rent: u64,
+    pub padding_2: [u8; 88],
+}
+
+impl AccountLayout for DynTickArrayState {
+    const DISCRIMINATOR: [u8; 8] = [106, 139, 152, 36, 117, 153, 184, 56];
+}
+
+impl DynTickArrayState {
+    /// The length of the account data, the header followed by its allocated and reserved `TickState`s
+    pub fn all_data_len(&self) -> usize {
+        Self::LEN
+            + (self.alloc_tick_count as usize + self.reserved_tick_count as usize)
+                * std::mem::size_of::<TickState>()
+    }
+}
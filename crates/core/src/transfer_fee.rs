@@ -0,0 +1,170 @@
+//! The Token-2022 transfer fees the quotes charge, decoded from the raw mint data without the token program
+//! crates. The fees are computed the same way as `spl_token_2022`, whose `TransferFeeConfig` the swap
+//! instructions of the program use, the program tests check they match.
+
+use crate::error::{ErrorCode, Result};
+use crate::macros::*;
+use std::cmp;
+
+pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+
+/// The length of a mint without extensions
+pub const MINT_LEN: usize = 82;
+/// The offset of the `is_initialized` flag of a mint
+pub const MINT_IS_INITIALIZED_OFFSET: usize = 45;
+/// The mint is padded to the length of a token account, then followed by its account type and its extensions
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+/// A mint can't have the length of a multisig account
+const MULTISIG_LEN: usize = 355;
+const EXTENSION_TYPE_UNINITIALIZED: u16 = 0;
+const EXTENSION_TYPE_TRANSFER_FEE_CONFIG: u16 = 1;
+/// The two authorities and the withheld amount, before the older and newer transfer fees
+const TRANSFER_FEE_CONFIG_FEES_OFFSET: usize = 32 + 32 + 8;
+const TRANSFER_FEE_LEN: usize = 8 + 8 + 2;
+const TRANSFER_FEE_CONFIG_LEN: usize = TRANSFER_FEE_CONFIG_FEES_OFFSET + 2 * TRANSFER_FEE_LEN;
+
+/// A transfer fee of a mint, from `epoch`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub transfer_fee_basis_points: u16,
+}
+
+impl TransferFee {
+    fn unpack(data: &[u8]) -> Self {
+        TransferFee {
+            epoch: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(data[8..16].try_into().unwrap()),
+            transfer_fee_basis_points: u16::from_le_bytes(data[16..18].try_into().unwrap()),
+        }
+    }
+
+    fn ceil_div(numerator: u128, denominator: u128) -> Option<u128> {
+        numerator
+            .checked_add(denominator)?
+            .checked_sub(1)?
+            .checked_div(denominator)
+    }
+
+    /// Get the fee of transferring `pre_fee_amount`
+    pub fn calculate_fee(&self, pre_fee_amount: u64) -> Option<u64> {
+        let transfer_fee_basis_points = u128::from(self.transfer_fee_basis_points);
+        if transfer_fee_basis_points == 0 || pre_fee_amount == 0 {
+            Some(0)
+        } else {
+            let numerator = u128::from(pre_fee_amount).checked_mul(transfer_fee_basis_points)?;
+            let raw_fee = Self::ceil_div(numerator, u128::from(MAX_FEE_BASIS_POINTS))?
+                .try_into()
+                .ok()?;
+            Some(cmp::min(raw_fee, self.maximum_fee))
+        }
+    }
+
+    /// Get the amount to transfer for `post_fee_amount` to be received
+    pub fn calculate_pre_fee_amount(&self, post_fee_amount: u64) -> Option<u64> {
+        let one_in_basis_points = u128::from(MAX_FEE_BASIS_POINTS);
+        let transfer_fee_basis_points = u128::from(self.transfer_fee_basis_points);
+        if transfer_fee_basis_points == 0 {
+            Some(post_fee_amount)
+        } else if post_fee_amount == 0 {
+            Some(0)
+        } else if transfer_fee_basis_points == one_in_basis_points {
+            self.maximum_fee.checked_add(post_fee_amount)
+        } else {
+            let numerator = u128::from(post_fee_amount).checked_mul(one_in_basis_points)?;
+            let denominator = one_in_basis_points.checked_sub(transfer_fee_basis_points)?;
+            let raw_pre_fee_amount = Self::ceil_div(numerator, denominator)?;
+            if raw_pre_fee_amount.checked_sub(u128::from(post_fee_amount))?
+                >= u128::from(self.maximum_fee)
+            {
+                post_fee_amount.checked_add(self.maximum_fee)
+            } else {
+                u64::try_from(raw_pre_fee_amount).ok()
+            }
+        }
+    }
+
+    /// Get the fee of transferring the amount for `post_fee_amount` to be received
+    pub fn calculate_inverse_fee(&self, post_fee_amount: u64) -> Option<u64> {
+        let pre_fee_amount = self.calculate_pre_fee_amount(post_fee_amount)?;
+        self.calculate_fee(pre_fee_amount)
+    }
+}
+
+/// The transfer fees of the `TransferFeeConfig` extension of a mint
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransferFeeConfig {
+    pub older_transfer_fee: TransferFee,
+    pub newer_transfer_fee: TransferFee,
+}
+
+impl TransferFeeConfig {
+    /// Get the transfer fee config from the account data of a mint, `None` if it has no transfer fee
+    pub fn from_mint_data(data: &[u8]) -> Option<Self> {
+        if data.len() <= ACCOUNT_TYPE_OFFSET
+            || data.len() == MULTISIG_LEN
+            || data[MINT_IS_INITIALIZED_OFFSET] != 1
+            || data[MINT_LEN..ACCOUNT_TYPE_OFFSET]
+                .iter()
+                .any(|byte| *byte != 0)
+            || data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT
+        {
+            return None;
+        }
+        let mut tlv_data = &data[ACCOUNT_TYPE_OFFSET + 1..];
+        while tlv_data.len() >= 4 {
+            let extension_type = u16::from_le_bytes([tlv_data[0], tlv_data[1]]);
+            let length = usize::from(u16::from_le_bytes([tlv_data[2], tlv_data[3]]));
+            let value = tlv_data.get(4..4 + length)?;
+            match extension_type {
+                EXTENSION_TYPE_UNINITIALIZED => return None,
+                EXTENSION_TYPE_TRANSFER_FEE_CONFIG => {
+                    if length != TRANSFER_FEE_CONFIG_LEN {
+                        return None;
+                    }
+                    let fees = &value[TRANSFER_FEE_CONFIG_FEES_OFFSET..];
+                    return Some(TransferFeeConfig {
+                        older_transfer_fee: TransferFee::unpack(&fees[..TRANSFER_FEE_LEN]),
+                        newer_transfer_fee: TransferFee::unpack(&fees[TRANSFER_FEE_LEN..]),
+                    });
+                }
+                _ => tlv_data = &tlv_data[4 + length..],
+            }
+        }
+        None
+    }
+
+    /// Get the transfer fee in effect at `epoch`
+    pub fn get_epoch_fee(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer_transfer_fee.epoch {
+            &self.newer_transfer_fee
+        } else {
+            &self.older_transfer_fee
+        }
+    }
+
+    /// Calculate the fee for input amount at `epoch`, as `util::calculate_transfer_fee`
+    pub fn calculate_epoch_fee(&self, epoch: u64, pre_fee_amount: u64) -> u64 {
+        self.get_epoch_fee(epoch)
+            .calculate_fee(pre_fee_amount)
+            .unwrap()
+    }
+
+    /// Calculate the fee for output amount at `epoch`, as `util::calculate_transfer_inverse_fee`
+    pub fn calculate_inverse_epoch_fee(&self, epoch: u64, post_fee_amount: u64) -> Result<u64> {
+        let transfer_fee = self.get_epoch_fee(epoch);
+        if transfer_fee.transfer_fee_basis_points == MAX_FEE_BASIS_POINTS {
+            return Ok(transfer_fee.maximum_fee);
+        }
+        let fee = transfer_fee.calculate_inverse_fee(post_fee_amount).unwrap();
+        let fee_for_check = transfer_fee
+            .calculate_fee(post_fee_amount.checked_add(fee).unwrap())
+            .unwrap();
+        if fee != fee_for_check {
+            return Err(error!(ErrorCode::TransferFeeCalculateNotMatch));
+        }
+        Ok(fee)
+    }
+}
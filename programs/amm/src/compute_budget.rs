@@ -0,0 +1,204 @@
+use crate::quote::{count_ticks_crossed, SwapQuote, TickArrayData};
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// The max compute unit limit of a transaction
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Compute units of a `swap_v2` that doesn't cross any initialized tick,
+/// including the token transfers and the observation update
+pub const SWAP_BASE_COMPUTE_UNITS: u32 = 60_000;
+/// Compute units added by each initialized tick crossed, the tick transition and the reward growths update
+pub const TICK_CROSS_COMPUTE_UNITS: u32 = 12_000;
+/// Compute units added by each tick array account loaded after the first one
+pub const TICK_ARRAY_COMPUTE_UNITS: u32 = 8_000;
+/// The margin added over the estimation, in bps
+pub const COMPUTE_UNIT_MARGIN_BPS: u32 = 2_000;
+
+/// The initialized ticks and tick arrays a quoted swap crosses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapCrossings {
+    pub ticks: u32,
+    /// The tick arrays crossed after the one of the current tick
+    pub tick_arrays: u32,
+}
+
+impl SwapCrossings {
+    /// Count the initialized ticks of `tick_arrays` between the current tick of the pool and the tick after the quoted swap
+    pub fn from_quote(
+        pool_state: &PoolState,
+        tick_arrays: &[TickArrayData],
+        quote: &SwapQuote,
+        zero_for_one: bool,
+    ) -> Self {
+        let tick_before = pool_state.tick_current;
+        let tick_after = quote.tick_current;
+        let ticks = count_ticks_crossed(tick_arrays, tick_before, tick_after, zero_for_one);
+
+        let tick_spacing = pool_state.tick_spacing;
+        let start_index_before = TickUtils::get_array_start_index(tick_before, tick_spacing);
+        let start_index_after = TickUtils::get_array_start_index(tick_after, tick_spacing);
+        let tick_arrays = (start_index_before.abs_diff(start_index_after)
+            / (TICK_ARRAY_SIZE * i32::from(tick_spacing)) as u32)
+            .min(tick_arrays.len() as u32);
+
+        Self { ticks, tick_arrays }
+    }
+
+    /// The recommended compute unit limit of a `swap_v2` with these crossings, with the margin
+    pub fn swap_compute_unit_limit(&self) -> u32 {
+        let estimated = u64::from(SWAP_BASE_COMPUTE_UNITS)
+            + u64::from(self.ticks) * u64::from(TICK_CROSS_COMPUTE_UNITS)
+            + u64::from(self.tick_arrays) * u64::from(TICK_ARRAY_COMPUTE_UNITS);
+        let limit = estimated * u64::from(10_000 + COMPUTE_UNIT_MARGIN_BPS) / 10_000;
+        limit.min(u64::from(MAX_COMPUTE_UNIT_LIMIT)) as u32
+    }
+}
+
+/// Build the compute budget instruction setting the compute unit limit of the transaction
+pub fn set_compute_unit_limit_ix(units: u32) -> Instruction {
+    let mut data = vec![2u8];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Build the compute budget instruction setting the priority fee of the transaction, in micro-lamports per compute unit
+pub fn set_compute_unit_price_ix(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3u8];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Prepend the compute budget instructions to the instructions of a transaction
+///
+/// # Arguments
+///
+/// * `instructions` - The instructions of the transaction
+/// * `units` - The compute unit limit, e.g. from `SwapCrossings::swap_compute_unit_limit`
+/// * `micro_lamports` - The priority fee per compute unit, no price instruction if None
+///
+pub fn with_compute_budget(
+    instructions: Vec<Instruction>,
+    units: u32,
+    micro_lamports: Option<u64>,
+) -> Vec<Instruction> {
+    let mut with_budget = vec![set_compute_unit_limit_ix(units)];
+    if let Some(micro_lamports) = micro_lamports {
+        with_budget.push(set_compute_unit_price_ix(micro_lamports));
+    }
+    with_budget.extend(instructions);
+    with_budget
+}
+
+#[cfg(test)]
+mod compute_budget_test {
+    use super::*;
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+
+    #[test]
+    fn swap_compute_unit_limit_test() {
+        let pool_state = build_pool(-32395, 60, 3651942632306380802, 5124165121219).take();
+        let pool_id = pool_state.key();
+        let tick_arrays: Vec<TickArrayData> = [
+            (
+                -32400,
+                vec![
+                    build_tick(-32400, 277065331032, -277065331032).take(),
+                    build_tick(-29220, 1330680689, -1330680689).take(),
+                ],
+            ),
+            (
+                -36000,
+                vec![
+                    build_tick(-32460, 1194569667438, 536061033698).take(),
+                    build_tick(-32520, 790917615645, 790917615645).take(),
+                ],
+            ),
+        ]
+        .into_iter()
+        .map(|(start_index, ticks)| {
+            let tick_array = build_fix_tick_array_with_tick_states(pool_id, start_index, 60, ticks);
+            TickArrayData::Fixed(Box::new(tick_array.take()))
+        })
+        .collect();
+
+        // stays in the current range
+        let quote = SwapQuote {
+            tick_current: -32399,
+            ..Default::default()
+        };
+        let crossings = SwapCrossings::from_quote(&pool_state, &tick_arrays, &quote, true);
+        assert_eq!(crossings, SwapCrossings::default());
+        assert_eq!(
+            crossings.swap_compute_unit_limit(),
+            SWAP_BASE_COMPUTE_UNITS * 12 / 10
+        );
+
+        // crosses -32400 and -32460 into the tick array -36000
+        let quote = SwapQuote {
+            tick_current: -32500,
+            ..Default::default()
+        };
+        let crossings = SwapCrossings::from_quote(&pool_state, &tick_arrays, &quote, true);
+        assert_eq!(
+            crossings,
+            SwapCrossings {
+                ticks: 2,
+                tick_arrays: 1
+            }
+        );
+        assert_eq!(
+            crossings.swap_compute_unit_limit(),
+            (SWAP_BASE_COMPUTE_UNITS + 2 * TICK_CROSS_COMPUTE_UNITS + TICK_ARRAY_COMPUTE_UNITS)
+                * 12
+                / 10
+        );
+
+        // crosses -29220 upward
+        let quote = SwapQuote {
+            tick_current: -29000,
+            ..Default::default()
+        };
+        let crossings = SwapCrossings::from_quote(&pool_state, &tick_arrays, &quote, false);
+        assert_eq!(crossings.ticks, 1);
+        assert_eq!(crossings.tick_arrays, 0);
+
+        // capped at the max limit of a transaction
+        let crossings = SwapCrossings {
+            ticks: 1000,
+            tick_arrays: 10,
+        };
+        assert_eq!(crossings.swap_compute_unit_limit(), MAX_COMPUTE_UNIT_LIMIT);
+    }
+
+    #[test]
+    fn with_compute_budget_test() {
+        let swap_ix = Instruction {
+            program_id: crate::id(),
+            accounts: vec![],
+            data: vec![1, 2, 3],
+        };
+        let instructions = with_compute_budget(vec![swap_ix.clone()], 200_000, Some(5));
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].program_id, COMPUTE_BUDGET_PROGRAM_ID);
+        assert_eq!(instructions[0].data, vec![2, 0x40, 0x0d, 0x03, 0x00]);
+        assert_eq!(instructions[1].data, vec![3, 5, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(instructions[2], swap_ix);
+
+        let instructions = with_compute_budget(vec![swap_ix], 200_000, None);
+        assert_eq!(instructions.len(), 2);
+    }
+}
@@ -0,0 +1,107 @@
+//! The permissionless maintenance instructions of the program, for keeper networks to integrate once.
+//! Every crank emits a `CrankReceipt`, and tips its keeper from the `CrankBounty` of the pool
+//! if both are passed in the remaining accounts: the bounty account, and the keeper as a writable signer.
+use crate::states::{CrankBounty, PoolState};
+use crate::util::get_unix_timestamp;
+use anchor_lang::prelude::*;
+
+/// A permissionless crank, the discriminant indexes `CrankBounty::last_paid_at`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrankKind {
+    UpdateRewardInfos,
+    UpdatePoolApr,
+    SyncPoolRangeLimits,
+}
+
+pub const CRANK_KIND_NUM: usize = 3;
+
+/// A registered crank and the instruction running it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrankInfo {
+    pub kind: CrankKind,
+    /// The name of the instruction
+    pub instruction: &'static str,
+    /// When a keeper should run it
+    pub schedule: &'static str,
+}
+
+/// All the permissionless cranks of the program
+pub const CRANKS: [CrankInfo; CRANK_KIND_NUM] = [
+    CrankInfo {
+        kind: CrankKind::UpdateRewardInfos,
+        instruction: "update_reward_infos",
+        schedule: "periodically while a reward is emitting, so the growths follow the emissions",
+    },
+    CrankInfo {
+        kind: CrankKind::UpdatePoolApr,
+        instruction: "update_pool_apr",
+        schedule: "every FEE_SNAPSHOT_INTERVAL, for the pools with a PoolAprState",
+    },
+    CrankInfo {
+        kind: CrankKind::SyncPoolRangeLimits,
+        instruction: "sync_pool_range_limits",
+        schedule: "after the range limits of the amm config are updated",
+    },
+];
+
+/// Emitted by every permissionless crank
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct CrankReceipt {
+    pub crank: CrankKind,
+    pub pool_state: Pubkey,
+    /// The keeper passed in the remaining accounts, the default pubkey if none
+    pub keeper: Pubkey,
+    /// The lamports paid to the keeper from the crank bounty of the pool
+    pub bounty: u64,
+    pub timestamp: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Pay the keeper of a crank if the crank bounty of the pool and the keeper are in the remaining accounts,
+/// and emit the `CrankReceipt`
+pub fn settle_crank<'info>(
+    kind: CrankKind,
+    pool_id: Pubkey,
+    pool_state: &mut PoolState,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let timestamp = get_unix_timestamp()?;
+    let bounty_key = CrankBounty::key(pool_id, &crate::id());
+    let bounty_info = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == bounty_key);
+    let keeper_info = remaining_accounts
+        .iter()
+        .find(|account_info| account_info.is_signer && account_info.is_writable);
+
+    let bounty = match (bounty_info, keeper_info) {
+        (Some(bounty_info), Some(keeper_info)) => {
+            CrankBounty::pay(bounty_info, keeper_info, pool_id, kind, timestamp)?
+        }
+        _ => 0,
+    };
+
+    emit!(CrankReceipt {
+        crank: kind,
+        pool_state: pool_id,
+        keeper: keeper_info.map_or(Pubkey::default(), |keeper_info| keeper_info.key()),
+        bounty,
+        timestamp,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod cranks_test {
+    use super::*;
+
+    #[test]
+    fn cranks_registry_test() {
+        for (index, crank) in CRANKS.iter().enumerate() {
+            assert_eq!(crank.kind as usize, index);
+        }
+    }
+}
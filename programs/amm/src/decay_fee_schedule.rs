@@ -0,0 +1,143 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// The trade fee rate of a decay fee pool at a point in time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecayFeePoint {
+    pub timestamp: u64,
+    /// The decay fee rate, in hundredths of a bip (10^-6)
+    pub decay_fee_rate: u32,
+    /// The fee rate charged on sells of the decay fee mint, the max of the decay fee rate and the base fee rate
+    pub trade_fee_rate: u32,
+}
+
+/// The decay fee curve of a pool over a time range
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecayFeeSchedule {
+    pub points: Vec<DecayFeePoint>,
+    /// The first timestamp at which the decay fee is no more than the base fee rate, the swaps disable the decay fee from then on.
+    /// None if the pool doesn't use decay fee, or the decay fee never drops to the base fee rate.
+    pub crossover_timestamp: Option<u64>,
+}
+
+/// Sample the decay fee of a pool from `from_ts` to `to_ts` (inclusive) every `step` seconds,
+/// with the same math as the swap instructions, so that UIs can plot the fee decay.
+///
+/// # Arguments
+///
+/// * `pool_state` - The pool with decay fee
+/// * `trade_fee_rate` - The base trade fee rate of the pool's amm config
+/// * `from_ts` - The first sampled unix timestamp
+/// * `to_ts` - The last sampled unix timestamp
+/// * `step` - The seconds between two samples
+///
+pub fn decay_fee_schedule(
+    pool_state: &PoolState,
+    trade_fee_rate: u32,
+    from_ts: u64,
+    to_ts: u64,
+    step: u64,
+) -> Result<DecayFeeSchedule> {
+    require_gt!(step, 0);
+    require_gte!(to_ts, from_ts);
+
+    let points = (from_ts..=to_ts)
+        .step_by(usize::try_from(step).unwrap_or(usize::MAX))
+        .map(|timestamp| {
+            let decay_fee_rate = pool_state.get_decay_fee_rate(timestamp);
+            DecayFeePoint {
+                timestamp,
+                decay_fee_rate,
+                trade_fee_rate: decay_fee_rate.max(trade_fee_rate),
+            }
+        })
+        .collect();
+    Ok(DecayFeeSchedule {
+        points,
+        crossover_timestamp: decay_fee_crossover_timestamp(pool_state, trade_fee_rate),
+    })
+}
+
+/// The decay fee rate only changes at the interval boundaries after the open time,
+/// walk them until the rate drops to the base fee rate or stops decreasing.
+fn decay_fee_crossover_timestamp(pool_state: &PoolState, trade_fee_rate: u32) -> Option<u64> {
+    let interval = u64::from(pool_state.decay_fee_decrease_interval);
+    if !pool_state.is_decay_fee_enabled() || interval == 0 {
+        return None;
+    }
+    let open_time = pool_state.open_time;
+    let mut last_rate = u32::MAX;
+    let mut timestamp = open_time;
+    loop {
+        let rate = pool_state.get_decay_fee_rate(timestamp);
+        if rate <= trade_fee_rate {
+            return Some(timestamp);
+        }
+        if rate >= last_rate {
+            return None;
+        }
+        last_rate = rate;
+        timestamp = timestamp.checked_add(interval)?;
+    }
+}
+
+#[cfg(test)]
+mod decay_fee_schedule_test {
+    use super::*;
+
+    fn build_decay_fee_pool(decrease_rate: u8) -> PoolState {
+        let mut pool_state = PoolState {
+            open_time: 1000,
+            ..Default::default()
+        };
+        pool_state
+            .initialize_decay_fee(true, false, 80, decrease_rate, 10)
+            .unwrap();
+        pool_state
+    }
+
+    #[test]
+    fn decay_fee_schedule_test() {
+        let pool_state = build_decay_fee_pool(10);
+        let schedule = decay_fee_schedule(&pool_state, 500_000, 995, 1025, 10).unwrap();
+        assert_eq!(
+            schedule.points,
+            vec![
+                // not open yet
+                DecayFeePoint {
+                    timestamp: 995,
+                    decay_fee_rate: 0,
+                    trade_fee_rate: 500_000,
+                },
+                DecayFeePoint {
+                    timestamp: 1005,
+                    decay_fee_rate: 800_000,
+                    trade_fee_rate: 800_000,
+                },
+                DecayFeePoint {
+                    timestamp: 1015,
+                    decay_fee_rate: 720_000,
+                    trade_fee_rate: 720_000,
+                },
+                DecayFeePoint {
+                    timestamp: 1025,
+                    decay_fee_rate: 648_000,
+                    trade_fee_rate: 648_000,
+                },
+            ]
+        );
+        // 800_000 * 0.9^5 = 472_392
+        assert_eq!(schedule.crossover_timestamp, Some(1050));
+        assert_eq!(pool_state.get_decay_fee_rate(1049), 524_880);
+        assert_eq!(pool_state.get_decay_fee_rate(1050), 472_392);
+
+        // the decay fee never drops
+        let pool_state = build_decay_fee_pool(0);
+        let schedule = decay_fee_schedule(&pool_state, 2500, 1000, 1000, 1).unwrap();
+        assert_eq!(schedule.points.len(), 1);
+        assert_eq!(schedule.crossover_timestamp, None);
+
+        assert!(decay_fee_schedule(&pool_state, 2500, 1000, 2000, 0).is_err());
+        assert!(decay_fee_schedule(&pool_state, 2500, 2000, 1000, 1).is_err());
+    }
+}
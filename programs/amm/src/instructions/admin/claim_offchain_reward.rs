@@ -34,7 +34,6 @@ pub struct ClaimOffchainRewardAccounts<'info> {
     )]
     pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    ///
     #[account(
         mut,
         token::mint = token_mint,
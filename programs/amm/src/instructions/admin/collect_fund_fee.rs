@@ -121,6 +121,7 @@ pub fn collect_fund_fee(
         recipient_token_account_1: ctx.accounts.recipient_token_account_1.key(),
         amount_0,
         amount_1,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
     });
 
     Ok(())
@@ -46,6 +46,7 @@ pub fn create_amm_config(
     protocol_fee_rate: u32,
     fund_fee_rate: u32,
 ) -> Result<()> {
+    AmmConfig::check_trade_fee_rate(trade_fee_rate)?;
     let amm_config = ctx.accounts.amm_config.deref_mut();
     amm_config.owner = ctx.accounts.owner.key();
     amm_config.bump = ctx.bumps.amm_config;
@@ -64,6 +65,11 @@ pub fn create_amm_config(
         tick_spacing: amm_config.tick_spacing,
         fund_fee_rate: amm_config.fund_fee_rate,
         fund_owner: amm_config.fund_owner,
+        min_range_ticks: amm_config.min_range_ticks,
+        max_range_ticks: amm_config.max_range_ticks,
+        status: amm_config.status,
+        default_prealloc_ticks: amm_config.default_prealloc_ticks,
+        max_swap_iterations: amm_config.max_swap_iterations,
     });
 
     Ok(())
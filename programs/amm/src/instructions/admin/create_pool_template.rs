@@ -0,0 +1,58 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct CreatePoolTemplate<'info> {
+    /// Address to be set as pool manager in admin group.
+    #[account(
+        mut,
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    /// The config the pools created from the template belong to.
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Initialize an account to store the pool template
+    #[account(
+        init,
+        seeds = [
+            POOL_TEMPLATE_SEED.as_bytes(),
+            &index.to_be_bytes()
+        ],
+        bump,
+        payer = owner,
+        space = PoolTemplate::LEN
+    )]
+    pub pool_template: Account<'info, PoolTemplate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_pool_template(
+    ctx: Context<CreatePoolTemplate>,
+    index: u16,
+    params: PoolTemplateParams,
+) -> Result<()> {
+    let amm_config_key = ctx.accounts.amm_config.key();
+    let bump = ctx.bumps.pool_template;
+    ctx.accounts.pool_template.deref_mut().initialize(
+        bump,
+        index,
+        amm_config_key,
+        &ctx.accounts.amm_config,
+        params,
+    )
+}
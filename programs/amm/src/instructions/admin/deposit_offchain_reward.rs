@@ -35,7 +35,6 @@ pub struct DepositOffchainRewardAccounts<'info> {
     )]
     pub token_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    ///
     #[account(
         mut,
         token::mint = token_mint,
@@ -19,6 +19,12 @@ pub use withdraw_offchain_reward::*;
 pub mod update_amm_config;
 pub use update_amm_config::*;
 
+pub mod set_swap_kill_switch;
+pub use set_swap_kill_switch::*;
+
+pub mod set_kill_switch_authority;
+pub use set_kill_switch_authority::*;
+
 pub mod collect_protocol_fee;
 pub use collect_protocol_fee::*;
 
@@ -37,8 +43,32 @@ pub use transfer_reward_owner::*;
 pub mod update_pool_status;
 pub use update_pool_status::*;
 
+pub mod update_pool_status_bits;
+pub use update_pool_status_bits::*;
+
+pub mod retier_pool;
+pub use retier_pool::*;
+
+pub mod update_pool_maker_rebate;
+pub use update_pool_maker_rebate::*;
+
+pub mod update_pool_min_position_age;
+pub use update_pool_min_position_age::*;
+
+pub mod sunset_pool;
+pub use sunset_pool::*;
+
+pub mod repair_tick;
+pub use repair_tick::*;
+
+pub mod create_pool_template;
+pub use create_pool_template::*;
+
 pub mod create_support_mint_associated;
 pub use create_support_mint_associated::*;
 
+pub mod update_support_mint_associated;
+pub use update_support_mint_associated::*;
+
 pub mod close_protocol_position;
 pub use close_protocol_position::*;
@@ -0,0 +1,188 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::get_unix_timestamp;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RepairTick<'info> {
+    #[account(
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: fixed or dynamic tick array of the pool, checked in the function body
+    #[account(mut)]
+    pub tick_array: UncheckedAccount<'info>,
+}
+
+pub fn repair_tick(ctx: Context<RepairTick>, tick_index: i32) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    // seed with the reward growths accrued up to now, as the other instructions touching the ticks do
+    let reward_infos = pool_state.update_reward_infos(get_unix_timestamp()?)?;
+
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+    let tick_state = repair_tick_in_array(
+        &tick_array_info,
+        ctx.accounts.pool_state.key(),
+        &pool_state,
+        &reward_infos,
+        tick_index,
+    )?;
+
+    emit!(TickRepairedEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        tick_array: tick_array_info.key(),
+        tick: tick_index,
+        fee_growth_outside_0_x64: tick_state.fee_growth_outside_0_x64,
+        fee_growth_outside_1_x64: tick_state.fee_growth_outside_1_x64,
+        reward_growths_outside_x64: tick_state.reward_growths_outside_x64,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
+
+/// Reseed the growths outside of `tick_index` in a tick array of `pool_id`, see `TickState::seed_growths_outside`.
+/// Fails if the tick is initialized, returns the repaired tick otherwise.
+pub fn repair_tick_in_array(
+    tick_array_info: &AccountInfo,
+    pool_id: Pubkey,
+    pool_state: &PoolState,
+    reward_infos: &[RewardInfo; REWARD_NUM],
+    tick_index: i32,
+) -> Result<TickState> {
+    let tick_array = TickArrayContainer::try_from_without_check(tick_array_info)?;
+    check_tick_array_address(&tick_array.key()?, &tick_array_info.key())?;
+    require_keys_eq!(
+        tick_array.get_pool_id()?,
+        pool_id,
+        ErrorCode::InvalidTickArray
+    );
+    let tick_spacing = pool_state.tick_spacing;
+    TickUtils::check_tick_array_start_index(
+        tick_array.get_start_tick_index()?,
+        tick_index,
+        tick_spacing,
+    )?;
+
+    let mut tick_array_ref = tick_array.get_ref_mut()?;
+    let mut tick_state = *tick_array_ref.get_tick_state_mut(tick_index, tick_spacing)?;
+    tick_state.initialize(tick_index, tick_spacing)?;
+    require!(
+        tick_state.seed_growths_outside(
+            pool_state.tick_current,
+            pool_state.fee_growth_global_0_x64,
+            pool_state.fee_growth_global_1_x64,
+            reward_infos,
+        ),
+        ErrorCode::TickInitialized
+    );
+    tick_array_ref.update_tick_state(tick_index, tick_spacing, &tick_state)?;
+    Ok(tick_state)
+}
+
+#[cfg(test)]
+mod repair_tick_test {
+    use super::*;
+    use crate::libraries::mock_anchor_account_info_v3;
+    use crate::states::tick_array_test::build_fix_tick_array_with_tick_states;
+
+    fn build_tick_state(
+        tick: i32,
+        fee_growth_outside_x64: u128,
+        liquidity_gross: u128,
+    ) -> TickState {
+        TickState {
+            tick,
+            liquidity_gross,
+            liquidity_net: liquidity_gross as i128,
+            fee_growth_outside_0_x64: fee_growth_outside_x64,
+            fee_growth_outside_1_x64: fee_growth_outside_x64,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn repair_tick_in_array_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = PoolState {
+            tick_spacing: 10,
+            tick_current: -200,
+            fee_growth_global_0_x64: 1000,
+            fee_growth_global_1_x64: 2000,
+            ..Default::default()
+        };
+        let reward_infos = pool_state.reward_infos;
+        let (key, _) = Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &(-600i32).to_be_bytes(),
+            ],
+            &owner,
+        );
+        let tick_array = build_fix_tick_array_with_tick_states(
+            pool_id,
+            -600,
+            10,
+            vec![
+                // initialized above the current tick, its growths outside are the checkpoints of its positions
+                build_tick_state(-100, 5000, 100),
+            ],
+        );
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+
+        // an uninitialized tick above the current tick is seeded as never crossed
+        let tick_state =
+            repair_tick_in_array(&account_info, pool_id, &pool_state, &reward_infos, -150).unwrap();
+        assert_eq!({ tick_state.fee_growth_outside_0_x64 }, 0);
+        assert_eq!({ tick_state.fee_growth_outside_1_x64 }, 0);
+        assert_eq!({ tick_state.liquidity_net }, 0);
+        // an uninitialized tick at or below the current tick takes the global values
+        let tick_state =
+            repair_tick_in_array(&account_info, pool_id, &pool_state, &reward_infos, -400).unwrap();
+        assert_eq!({ tick_state.fee_growth_outside_0_x64 }, 1000);
+        assert_eq!({ tick_state.fee_growth_outside_1_x64 }, 2000);
+        let tick_array_loaded = TickArrayContainer::try_from(&account_info, -400, 10).unwrap();
+        assert_eq!(
+            {
+                tick_array_loaded
+                    .get_tick_state(-400, 10)
+                    .unwrap()
+                    .fee_growth_outside_0_x64
+            },
+            1000
+        );
+
+        // an initialized tick is never reseeded, even with growths outside above the global values
+        assert_eq!(
+            repair_tick_in_array(&account_info, pool_id, &pool_state, &reward_infos, -100).err(),
+            Some(ErrorCode::TickInitialized.into())
+        );
+        // a tick array of another pool
+        assert_eq!(
+            repair_tick_in_array(
+                &account_info,
+                Pubkey::new_unique(),
+                &pool_state,
+                &reward_infos,
+                -100
+            )
+            .err(),
+            Some(ErrorCode::InvalidTickArray.into())
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(new_tick_spacing: u16)]
+pub struct RetierPool<'info> {
+    #[account(
+        address = admin_group.normal_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The amm config the pool is moved to, its tick spacing is the new tick spacing of the pool
+    #[account(
+        constraint = new_amm_config.tick_spacing == new_tick_spacing @ ErrorCode::AmmConfigTickSpacingMismatch
+    )]
+    pub new_amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The bitmap extension of the pool, must be empty as well
+    #[account(
+        seeds = [
+            POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump
+    )]
+    pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+}
+
+/// One-time migration of a misconfigured pool to another amm config and its tick spacing, keeping its address.
+/// Only allowed while the pool has no liquidity and empty bitmaps, so that no tick or position depends on
+/// the old tick spacing.
+///
+/// The tick arrays allocated without initialized ticks, by `get_or_create_tick_array` or `reserve_ticks`,
+/// are not in the bitmap, they are counted by `PoolState::tick_array_count` and must be closed with
+/// `close_tick_array` first. A pool created before the count can't prove it has none, its tick arrays left
+/// at the old tick spacing are reclaimed with `close_tick_array` afterwards.
+pub fn retier_pool(ctx: Context<RetierPool>, new_tick_spacing: u16) -> Result<()> {
+    require!(
+        ctx.accounts.tick_array_bitmap.load()?.is_empty(),
+        ErrorCode::PoolNotEmpty
+    );
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let old_amm_config = pool_state.amm_config;
+    let old_tick_spacing = pool_state.tick_spacing;
+    pool_state.retier(ctx.accounts.new_amm_config.key(), new_tick_spacing)?;
+
+    emit!(PoolRetierEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        old_amm_config,
+        new_amm_config: ctx.accounts.new_amm_config.key(),
+        old_tick_spacing,
+        new_tick_spacing,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
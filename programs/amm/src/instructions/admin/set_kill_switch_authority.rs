@@ -0,0 +1,51 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetKillSwitchAuthority<'info> {
+    /// only super admin can set the kill switch authority
+    #[account(
+        mut,
+        address = crate::admin::ID @ ErrorCode::NotApproved
+    )]
+    pub payer: Signer<'info>,
+
+    /// The amm config whose swaps the kill switch authority can flip
+    pub amm_config: Account<'info, AmmConfig>,
+
+    /// The kill switch of the amm config, created on the first setting
+    #[account(
+        init_if_needed,
+        seeds = [
+            KILL_SWITCH_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = AmmConfigKillSwitch::LEN
+    )]
+    pub kill_switch: Account<'info, AmmConfigKillSwitch>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set the address who can only disable/enable the swaps of the pools of the amm config,
+/// default pubkey to remove it
+pub fn set_kill_switch_authority(
+    ctx: Context<SetKillSwitchAuthority>,
+    kill_switch_authority: Pubkey,
+) -> Result<()> {
+    let amm_config = ctx.accounts.amm_config.key();
+    let kill_switch = &mut ctx.accounts.kill_switch;
+    kill_switch.bump = ctx.bumps.kill_switch;
+    kill_switch.amm_config = amm_config;
+    kill_switch.authority = kill_switch_authority;
+
+    emit!(KillSwitchAuthorityChangeEvent {
+        amm_config,
+        kill_switch_authority,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSwapKillSwitch<'info> {
+    /// The kill switch authority of the amm config
+    #[account(
+        constraint = kill_switch.is_authority(&authority.key()) @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// The kill switch of the amm config, the authority of another config can't flip this one
+    #[account(
+        seeds = [
+            KILL_SWITCH_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+        ],
+        bump = kill_switch.bump,
+    )]
+    pub kill_switch: Account<'info, AmmConfigKillSwitch>,
+
+    /// Amm config whose pools are halted or resumed
+    #[account(mut)]
+    pub amm_config: Account<'info, AmmConfig>,
+}
+
+/// Only flip the swap bit of the config status, all the other bits are kept
+pub fn set_swap_kill_switch(ctx: Context<SetSwapKillSwitch>, disable_swap: bool) -> Result<()> {
+    let amm_config = &mut ctx.accounts.amm_config;
+    amm_config.set_status_by_bit(PoolStatusBitIndex::Swap, !disable_swap);
+    log_info!(
+        ix = "set_swap_kill_switch",
+        "amm_config:{}, status:{}",
+        amm_config.key(),
+        amm_config.status
+    );
+
+    emit!(ConfigChangeEvent {
+        index: amm_config.index,
+        owner: amm_config.owner,
+        trade_fee_rate: amm_config.trade_fee_rate,
+        protocol_fee_rate: amm_config.protocol_fee_rate,
+        tick_spacing: amm_config.tick_spacing,
+        fund_fee_rate: amm_config.fund_fee_rate,
+        fund_owner: amm_config.fund_owner,
+        min_range_ticks: amm_config.min_range_ticks,
+        max_range_ticks: amm_config.max_range_ticks,
+        status: amm_config.status,
+        default_prealloc_ticks: amm_config.default_prealloc_ticks,
+        max_swap_iterations: amm_config.max_swap_iterations,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::get_unix_timestamp;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SunsetPool<'info> {
+    #[account(
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn sunset_pool(ctx: Context<SunsetPool>, dormancy_seconds: u64) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let timestamp = get_unix_timestamp()?;
+    pool_state.sunset(dormancy_seconds, timestamp)?;
+
+    emit!(PoolSunsetEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        last_activity_ts: pool_state.last_activity_ts(),
+        sunset_ts: timestamp,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
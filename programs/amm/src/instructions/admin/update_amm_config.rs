@@ -26,7 +26,7 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
     let amm_config = &mut ctx.accounts.amm_config;
     let match_param = Some(param);
     match match_param {
-        Some(0) => update_trade_fee_rate(amm_config, value),
+        Some(0) => update_trade_fee_rate(amm_config, value)?,
         Some(1) => update_protocol_fee_rate(amm_config, value),
         Some(2) => update_fund_fee_rate(amm_config, value),
         Some(3) => {
@@ -37,6 +37,11 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
             let new_fund_owner = *ctx.remaining_accounts.iter().next().unwrap().key;
             set_new_fund_owner(amm_config, new_fund_owner);
         }
+        Some(5) => update_range_limits(amm_config, value, amm_config.max_range_ticks)?,
+        Some(6) => update_range_limits(amm_config, amm_config.min_range_ticks, value)?,
+        Some(7) => update_status(amm_config, value)?,
+        Some(8) => update_default_prealloc_ticks(amm_config, value)?,
+        Some(9) => update_max_swap_iterations(amm_config, value)?,
         _ => return err!(ErrorCode::InvalidUpdateConfigFlag),
     }
 
@@ -48,6 +53,11 @@ pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -
         tick_spacing: amm_config.tick_spacing,
         fund_fee_rate: amm_config.fund_fee_rate,
         fund_owner: amm_config.fund_owner,
+        min_range_ticks: amm_config.min_range_ticks,
+        max_range_ticks: amm_config.max_range_ticks,
+        status: amm_config.status,
+        default_prealloc_ticks: amm_config.default_prealloc_ticks,
+        max_swap_iterations: amm_config.max_swap_iterations,
     });
 
     Ok(())
@@ -59,9 +69,10 @@ fn update_protocol_fee_rate(amm_config: &mut Account<AmmConfig>, protocol_fee_ra
     amm_config.protocol_fee_rate = protocol_fee_rate;
 }
 
-fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u32) {
-    assert!(trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE);
+fn update_trade_fee_rate(amm_config: &mut Account<AmmConfig>, trade_fee_rate: u32) -> Result<()> {
+    amm_config.check_trade_fee_rate_update(trade_fee_rate)?;
     amm_config.trade_fee_rate = trade_fee_rate;
+    Ok(())
 }
 
 fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u32) {
@@ -70,9 +81,62 @@ fn update_fund_fee_rate(amm_config: &mut Account<AmmConfig>, fund_fee_rate: u32)
     amm_config.fund_fee_rate = fund_fee_rate;
 }
 
+/// Only the pools created afterwards or synced by `sync_pool_range_limits` enforce the new limits
+fn update_range_limits(
+    amm_config: &mut Account<AmmConfig>,
+    min_range_ticks: u32,
+    max_range_ticks: u32,
+) -> Result<()> {
+    AmmConfig::check_range_limits(min_range_ticks, max_range_ticks)?;
+    amm_config.min_range_ticks = min_range_ticks;
+    amm_config.max_range_ticks = max_range_ticks;
+    Ok(())
+}
+
+/// Halt or resume operations in all the pools of the config at once
+fn update_status(amm_config: &mut Account<AmmConfig>, status: u32) -> Result<()> {
+    amm_config.status =
+        u8::try_from(status).map_err(|_| error!(ErrorCode::InvalidUpdateConfigFlag))?;
+    log_info!(
+        ix = "update_amm_config",
+        "amm_config:{}, status:{}",
+        amm_config.key(),
+        status
+    );
+    Ok(())
+}
+
+/// Only the pools created afterwards or synced by `sync_pool_range_limits` allocate the new count
+fn update_default_prealloc_ticks(
+    amm_config: &mut Account<AmmConfig>,
+    default_prealloc_ticks: u32,
+) -> Result<()> {
+    require_gte!(
+        TICK_ARRAY_SIZE as u32,
+        default_prealloc_ticks,
+        ErrorCode::InvalidUpdateConfigFlag
+    );
+    amm_config.default_prealloc_ticks = default_prealloc_ticks as u8;
+    Ok(())
+}
+
+/// Zero restores the protocol max
+fn update_max_swap_iterations(
+    amm_config: &mut Account<AmmConfig>,
+    max_swap_iterations: u32,
+) -> Result<()> {
+    require_gte!(
+        u32::from(MAX_SWAP_ITERATIONS),
+        max_swap_iterations,
+        ErrorCode::InvalidMaxSwapIterations
+    );
+    amm_config.max_swap_iterations = max_swap_iterations as u16;
+    Ok(())
+}
+
 fn set_new_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) {
-    #[cfg(feature = "enable-log")]
-    msg!(
+    log_info!(
+        ix = "update_amm_config",
         "amm_config, old_owner:{}, new_owner:{}",
         amm_config.owner.to_string(),
         new_owner.key().to_string()
@@ -81,8 +145,8 @@ fn set_new_owner(amm_config: &mut Account<AmmConfig>, new_owner: Pubkey) {
 }
 
 fn set_new_fund_owner(amm_config: &mut Account<AmmConfig>, new_fund_owner: Pubkey) {
-    #[cfg(feature = "enable-log")]
-    msg!(
+    log_info!(
+        ix = "update_amm_config",
         "amm_config, old_fund_owner:{}, new_fund_owner:{}",
         amm_config.fund_owner.to_string(),
         new_fund_owner.key().to_string()
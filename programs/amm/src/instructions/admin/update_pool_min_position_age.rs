@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolMinPositionAge<'info> {
+    #[account(
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+pub fn update_pool_min_position_age(
+    ctx: Context<UpdatePoolMinPositionAge>,
+    min_position_age_slots: u16,
+) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_min_position_age_slots(min_position_age_slots);
+    Ok(())
+}
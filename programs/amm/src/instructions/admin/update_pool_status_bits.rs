@@ -0,0 +1,72 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolStatusBits<'info> {
+    /// The emergency manager, or the kill switch authority of the amm config of the pool for the swap bit only
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    // remaining accounts
+    // kill_switch: the `AmmConfigKillSwitch` of the amm config of the pool, optional, only for its authority
+}
+
+/// Disable the actions of the bits of `set_mask` and enable those of `clear_mask`, the other bits are kept.
+/// Every bit of both masks must be allowed to the authority, see `AmmAdminGroup::pool_status_bits_allowed`.
+pub fn update_pool_status_bits<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, UpdatePoolStatusBits<'info>>,
+    set_mask: u8,
+    clear_mask: u8,
+) -> Result<()> {
+    require!(
+        set_mask | clear_mask != 0 && set_mask & clear_mask == 0,
+        ErrorCode::InvalidPoolStatusMask
+    );
+    let authority = ctx.accounts.authority.key();
+    let amm_config = ctx.accounts.pool_state.load()?.amm_config;
+    let kill_switch = AmmConfigKillSwitch::find_in(ctx.remaining_accounts, amm_config)
+        .map(Account::<AmmConfigKillSwitch>::try_from)
+        .transpose()?;
+    let allowed = ctx
+        .accounts
+        .admin_group
+        .pool_status_bits_allowed(&authority, kill_switch.as_deref());
+    require!(
+        (set_mask | clear_mask) & !allowed == 0,
+        ErrorCode::NotApproved
+    );
+
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let status_before = pool_state.status;
+    pool_state.update_status_bits(set_mask, clear_mask);
+    let status_after = pool_state.status;
+    log_info!(
+        ix = "update_pool_status_bits",
+        pool = ctx.accounts.pool_state.key(),
+        "status_before:{}, status_after:{}",
+        status_before,
+        status_after
+    );
+
+    emit!(PoolStatusBitsUpdatedEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        authority,
+        set_mask,
+        clear_mask,
+        status_before,
+        status_after,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
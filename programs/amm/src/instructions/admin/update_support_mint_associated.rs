@@ -0,0 +1,39 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateSupportMintAssociated<'info> {
+    #[account(
+        address = admin_group.normal_manager @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    /// The support mint state account of the mint
+    #[account(
+        mut,
+        seeds = [
+            SUPPORT_MINT_SEED.as_bytes(),
+            support_mint_associated.mint.as_ref(),
+        ],
+        bump = support_mint_associated.bump,
+    )]
+    pub support_mint_associated: Account<'info, SupportMintAssociated>,
+}
+
+pub fn update_support_mint_associated(
+    ctx: Context<UpdateSupportMintAssociated>,
+    allow_denied_extensions: bool,
+) -> Result<()> {
+    ctx.accounts.support_mint_associated.allow_denied_extensions = allow_denied_extensions;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::get_slot;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AssertPoolState<'info> {
+    /// The pool to check, read only
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Abort the transaction if the pool left the bounds, otherwise do nothing.
+/// Appended after a composition with other programs to guard the pool against a manipulation mid transaction.
+pub fn assert_pool_state(
+    ctx: Context<AssertPoolState>,
+    min_sqrt_price_x64: u128,
+    max_sqrt_price_x64: u128,
+    min_liquidity: u128,
+) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    check_pool_state_bounds(
+        &pool_state,
+        min_sqrt_price_x64,
+        max_sqrt_price_x64,
+        min_liquidity,
+    )
+}
+
+/// Abort the transaction if the last swap of the pool is older than `max_slot_age` slots,
+/// or happened after `max_last_swap_slot`, the slot of the snapshot a quote was made off.
+pub fn assert_recent_pool_update(
+    ctx: Context<AssertPoolState>,
+    max_slot_age: u64,
+    max_last_swap_slot: u64,
+) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    pool_state.check_last_swap_slot(get_slot()?, max_slot_age, max_last_swap_slot)
+}
+
+pub fn check_pool_state_bounds(
+    pool_state: &PoolState,
+    min_sqrt_price_x64: u128,
+    max_sqrt_price_x64: u128,
+    min_liquidity: u128,
+) -> Result<()> {
+    let sqrt_price_x64 = pool_state.sqrt_price_x64;
+    require!(
+        sqrt_price_x64 >= min_sqrt_price_x64 && sqrt_price_x64 <= max_sqrt_price_x64,
+        ErrorCode::PoolPriceOutOfBounds
+    );
+    require_gte!(
+        pool_state.liquidity,
+        min_liquidity,
+        ErrorCode::PoolLiquidityBelowMinimum
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod assert_pool_state_test {
+    use super::*;
+    use crate::libraries::tick_math;
+    use crate::states::pool_test::build_pool;
+
+    #[test]
+    fn check_pool_state_bounds_test() {
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick_raw(0).unwrap();
+        let pool_state = build_pool(0, 60, sqrt_price_x64, 1000);
+        let pool_state = pool_state.borrow();
+
+        // the bounds are inclusive
+        check_pool_state_bounds(&pool_state, sqrt_price_x64, sqrt_price_x64, 1000).unwrap();
+        check_pool_state_bounds(
+            &pool_state,
+            tick_math::MIN_SQRT_PRICE_X64,
+            tick_math::MAX_SQRT_PRICE_X64,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_pool_state_bounds(&pool_state, sqrt_price_x64 + 1, u128::MAX, 0).err(),
+            Some(ErrorCode::PoolPriceOutOfBounds.into())
+        );
+        assert_eq!(
+            check_pool_state_bounds(&pool_state, 0, sqrt_price_x64 - 1, 0).err(),
+            Some(ErrorCode::PoolPriceOutOfBounds.into())
+        );
+        assert_eq!(
+            check_pool_state_bounds(&pool_state, 0, u128::MAX, 1001).err(),
+            Some(ErrorCode::PoolLiquidityBelowMinimum.into())
+        );
+    }
+}
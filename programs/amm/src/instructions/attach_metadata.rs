@@ -0,0 +1,82 @@
+use super::open_position::{
+    disable_nft_minting, get_compact_metadata_data, initialize_token_metadata_extension,
+};
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct AttachMetadata<'info> {
+    /// Pays the rent of the metadata
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The owner of the position NFT
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the position NFT
+    #[account(
+        token::mint = position_nft_mint,
+        token::authority = nft_owner,
+        constraint = nft_account.amount == 1
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position of the NFT, the update authority of the metadata
+    #[account(
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position, still the mint authority of the NFT
+    #[account(address = personal_position.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The token-2022 mint of the position NFT
+    #[account(
+        mut,
+        address = personal_position.nft_mint,
+        mint::token_program = token_program_2022,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Program to initialize the metadata of the NFT mint
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Program to transfer the rent of the metadata
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize the compact metadata of a token-2022 position NFT opened without metadata, then disable its minting
+pub fn attach_metadata(ctx: Context<AttachMetadata>) -> Result<()> {
+    // The mint authority is removed once the metadata is initialized
+    require!(
+        ctx.accounts.position_nft_mint.mint_authority == Some(ctx.accounts.pool_state.key()).into(),
+        ErrorCode::NotApproved
+    );
+
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let seeds = pool_state.seeds();
+    let position_nft_mint_info = ctx.accounts.position_nft_mint.to_account_info();
+    let (name, symbol, uri) = get_compact_metadata_data(&ctx.accounts.personal_position);
+    initialize_token_metadata_extension(
+        &ctx.accounts.payer,
+        &position_nft_mint_info,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.personal_position.to_account_info(),
+        &ctx.accounts.token_program_2022,
+        name,
+        symbol,
+        uri,
+        &[&seeds],
+    )?;
+
+    disable_nft_minting(
+        &ctx.accounts.token_program_2022.to_account_info(),
+        &ctx.accounts.pool_state,
+        &position_nft_mint_info,
+        &seeds,
+    )
+}
@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::get_rent;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AuditTickArray<'info> {
+    /// Writable to stamp the sequence number of the audit event
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Only fixed tick arrays can be audited, a dynamic tick array fails the discriminator check
+    pub tick_array: AccountLoader<'info, TickArrayState>,
+}
+
+pub fn audit_tick_array(ctx: Context<AuditTickArray>) -> Result<TickArrayAudit> {
+    let tick_array = ctx.accounts.tick_array.load()?;
+    require_keys_eq!(
+        tick_array.pool_id,
+        ctx.accounts.pool_state.key(),
+        ErrorCode::InvalidTickArray
+    );
+
+    let audit = tick_array.audit(&get_rent()?);
+    emit!(TickArrayAuditEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        tick_array: ctx.accounts.tick_array.key(),
+        audit,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(audit)
+}
@@ -0,0 +1,39 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(plan: DeploymentPlan)]
+pub struct BeginDeploy<'info> {
+    /// The owner of the positions to deploy
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Tracks the progress of the deployment, one deployment per owner and pool at a time
+    #[account(
+        init,
+        seeds = [
+            DEPLOYMENT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = DeploymentState::need_len(plan.position_count)
+    )]
+    pub deployment_state: Box<Account<'info, DeploymentState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn begin_deploy(ctx: Context<BeginDeploy>, plan: DeploymentPlan) -> Result<()> {
+    let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
+    ctx.accounts.deployment_state.initialize(
+        ctx.bumps.deployment_state,
+        ctx.accounts.owner.key(),
+        ctx.accounts.pool_state.key(),
+        plan,
+        tick_spacing,
+    )
+}
@@ -0,0 +1,202 @@
+use super::collect_fees_and_rewards::accrue_position;
+use super::decrease_liquidity::collect_rewards;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use crate::util::get_transfer_fee;
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+#[instruction(token_mint: Pubkey)]
+pub struct ClaimRewardsAs<'info> {
+    /// The position owner or delegated authority, also the authority of the reward token accounts swapped from
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Claim the rewards of this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// The token account receiving all the rewards as `token_mint`
+    #[account(mut, token::mint = output_token_mint)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint all the rewards are swapped into
+    #[account(address = token_mint)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Memo program
+    pub memo_program: Program<'info, Memo>,
+    // remaining accounts
+    // 1. (reward vault, recipient token account, reward mint) of each initialized reward, same as `decrease_liquidity_v2`.
+    //    The recipient of a reward in `token_mint` must be `output_token_account`.
+    // 2. for each initialized reward not in `token_mint`, in the reward order, the swap into `token_mint`:
+    //    amm_config, pool_state, input_vault, output_vault, observation_state,
+    //    then the `tick_array_counts` tick arrays and the optional tick array bitmap extension of the pool
+}
+
+/// Claim all the pending rewards of a position and swap them into `token_mint` in the pools of this program
+pub fn claim_rewards_as<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClaimRewardsAs<'info>>,
+    token_mint: Pubkey,
+    tick_array_counts: Vec<u8>,
+    amount_out_minimum: u64,
+) -> Result<()> {
+    // the rewards of a collateralized position back its loan, see `liquidate_position`
+    ctx.accounts.personal_position.check_not_collateralized()?;
+    // accrue the rewards of the position, the fees stay owed to the position
+    accrue_position(
+        &ctx.accounts.pool_state.key(),
+        &mut *ctx.accounts.pool_state.load_mut()?,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.tick_array_lower.to_account_info(),
+        &ctx.accounts.tick_array_upper.to_account_info(),
+    )?;
+
+    let reward_mints: Vec<Pubkey> = ctx
+        .accounts
+        .pool_state
+        .load()?
+        .reward_infos
+        .iter()
+        .filter(|reward_info| reward_info.initialized())
+        .map(|reward_info| reward_info.token_mint)
+        .collect();
+    let reward_accounts_len = reward_mints.len() * 3;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        reward_accounts_len,
+        ErrorCode::InvalidRewardInputAccountNumber
+    );
+    let (reward_accounts, mut swap_accounts) = ctx.remaining_accounts.split_at(reward_accounts_len);
+    for (reward_mint, reward_group) in reward_mints.iter().zip(reward_accounts.chunks(3)) {
+        if *reward_mint == token_mint {
+            require_keys_eq!(
+                reward_group[1].key(),
+                ctx.accounts.output_token_account.key(),
+                ErrorCode::InvalidRewardInputAccountNumber
+            );
+        }
+    }
+
+    let token_program_2022 = ctx.accounts.token_program_2022.to_account_info();
+    let reward_amounts = collect_rewards(
+        &ctx.accounts.pool_state,
+        &reward_accounts.iter().collect::<Vec<_>>(),
+        &ctx.accounts.token_program,
+        Some(token_program_2022),
+        &mut ctx.accounts.personal_position,
+        true,
+    )?;
+
+    let mut amount_out = 0u64;
+    let mut swap_index = 0;
+    for (i, (reward_mint, reward_group)) in reward_mints
+        .iter()
+        .zip(reward_accounts.chunks(3))
+        .enumerate()
+    {
+        let reward_vault_mint = Box::new(InterfaceAccount::<Mint>::try_from(&reward_group[2])?);
+        let received =
+            reward_amounts[i] - get_transfer_fee(reward_vault_mint.clone(), reward_amounts[i])?;
+        if *reward_mint == token_mint {
+            amount_out = amount_out.checked_add(received).unwrap();
+            continue;
+        }
+
+        let tick_array_count = *tick_array_counts
+            .get(swap_index)
+            .ok_or(ErrorCode::InvalidRewardInputAccountNumber)?
+            as usize;
+        swap_index += 1;
+        require_gte!(
+            swap_accounts.len(),
+            5 + tick_array_count,
+            ErrorCode::InvalidRewardInputAccountNumber
+        );
+        let (pool_accounts, rest) = swap_accounts.split_at(5 + tick_array_count);
+        swap_accounts = rest;
+        if received == 0 {
+            continue;
+        }
+
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&pool_accounts[0])?);
+        let pool_state = AccountLoader::<PoolState>::try_from(&pool_accounts[1])?;
+        let observation_state = AccountLoader::<ObservationState>::try_from(&pool_accounts[4])?;
+        {
+            let pool_state = pool_state.load()?;
+            require_keys_eq!(pool_state.observation_key, observation_state.key());
+            require_keys_eq!(pool_state.amm_config, amm_config.key());
+        }
+        amount_out = amount_out
+            .checked_add(
+                exact_internal_v2(
+                    &mut SwapSingleV2 {
+                        payer: ctx.accounts.nft_owner.clone(),
+                        amm_config,
+                        pool_state,
+                        input_token_account: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                            &reward_group[1],
+                        )?),
+                        output_token_account: ctx.accounts.output_token_account.clone(),
+                        input_vault: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                            &pool_accounts[2],
+                        )?),
+                        output_vault: Box::new(InterfaceAccount::<TokenAccount>::try_from(
+                            &pool_accounts[3],
+                        )?),
+                        observation_state,
+                        token_program: ctx.accounts.token_program.clone(),
+                        token_program_2022: ctx.accounts.token_program_2022.clone(),
+                        memo_program: ctx.accounts.memo_program.clone(),
+                        input_vault_mint: reward_vault_mint,
+                        output_vault_mint: ctx.accounts.output_token_mint.clone(),
+                    },
+                    &pool_accounts[5..],
+                    received,
+                    0,
+                    true,
+                )?
+                .amount_out,
+            )
+            .unwrap();
+    }
+    require_gte!(
+        amount_out,
+        amount_out_minimum,
+        ErrorCode::TooLittleOutputReceived
+    );
+
+    emit!(ClaimRewardsAsEvent {
+        position_nft_mint: ctx.accounts.personal_position.nft_mint,
+        token_mint,
+        reward_amounts,
+        amount_out,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::get_unix_timestamp;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ClearDecayFee<'info> {
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Permissionless, it only clears the decay fee flag of a pool past its `decay_fee_end_time`,
+/// so that the quotes and swaps stop computing the decay fee rate
+pub fn clear_decay_fee(ctx: Context<ClearDecayFee>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    require!(
+        pool_state.is_decay_fee_expired(get_unix_timestamp()?),
+        ErrorCode::InvalidDecayFeeWindow
+    );
+    pool_state.disable_decay_fee()
+}
@@ -47,6 +47,7 @@ pub struct ClosePosition<'info> {
 pub fn close_position<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, ClosePosition<'info>>,
 ) -> Result<()> {
+    ctx.accounts.personal_position.check_not_collateralized()?;
     if ctx.accounts.personal_position.liquidity != 0
         || ctx.accounts.personal_position.token_fees_owed_0 != 0
         || ctx.accounts.personal_position.token_fees_owed_1 != 0
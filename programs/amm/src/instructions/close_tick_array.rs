@@ -0,0 +1,88 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+#[derive(Accounts)]
+pub struct CloseTickArray<'info> {
+    #[account(
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: dynamic tick array of the pool, checked in the function body
+    #[account(mut)]
+    pub tick_array: UncheckedAccount<'info>,
+
+    /// CHECK: receives the rent of the closed tick array
+    #[account(mut)]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+/// The remaining accounts hold the tick array bitmap extension of the pool, if the tick array is beyond
+/// the default bitmap
+pub fn close_tick_array<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseTickArray<'info>>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+
+    let start_tick_index = {
+        let tick_array_loader = DynTickArrayLoader::try_from(&tick_array_info)?;
+        let (header, ticks) = tick_array_loader.load()?;
+        require_keys_eq!(header.pool_id, pool_id, ErrorCode::InvalidTickArray);
+        header.check_empty(&ticks)?;
+
+        let alloc_tick_count = usize::from(header.alloc_tick_count);
+        TickArrayArchivedEvent::emit_from(
+            &header,
+            &ticks[..alloc_tick_count],
+            true,
+            pool_state.next_event_seq(),
+        );
+        header.start_tick_index
+    };
+
+    // the bit is cleared when the last tick is uninitialized, clear it anyway if it was left set.
+    // A tick array left at the old tick spacing of a pool retiered before the tick array count has no bit
+    // in the current bitmap
+    let tickarray_bitmap_extension_key = TickArrayBitmapExtension::key(pool_id, &crate::id());
+    let tickarray_bitmap_extension = ctx
+        .remaining_accounts
+        .iter()
+        .find(|account_info| account_info.key() == tickarray_bitmap_extension_key);
+    if TickUtils::check_is_valid_start_index(start_tick_index, pool_state.tick_spacing)
+        && pool_state.is_tick_array_initialized(tickarray_bitmap_extension, start_tick_index)?
+    {
+        pool_state.flip_tick_array_bit(tickarray_bitmap_extension, start_tick_index)?;
+    }
+
+    let lamports = tick_array_info.lamports();
+    **tick_array_info.try_borrow_mut_lamports()? = 0;
+    **ctx.accounts.rent_receiver.try_borrow_mut_lamports()? += lamports;
+    tick_array_info.assign(&system_program::ID);
+    tick_array_info.resize(0)?;
+    pool_state.remove_tick_array();
+
+    log_info!(
+        ix = "close_tick_array",
+        pool = pool_id,
+        "start_tick_index:{}, lamports:{}",
+        start_tick_index,
+        lamports
+    );
+    Ok(())
+}
@@ -0,0 +1,458 @@
+use super::decrease_liquidity::check_unclaimed_fees_and_vault;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{
+    get_recent_epoch, get_slot, get_unix_timestamp, transfer_from_pool_vault_to_user,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The max number of positions collected by a `collect_fees_and_rewards`
+pub const MAX_COLLECT_POSITIONS: usize = 8;
+/// The accounts of each position: nft_account, personal_position, tick_array_lower and tick_array_upper
+const POSITION_ACCOUNTS_LEN: usize = 4;
+
+#[derive(Accounts)]
+pub struct CollectFeesAndRewards<'info> {
+    /// The owner of all the position NFTs
+    pub nft_owner: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive the token_0 fees of all the positions
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The destination token account for receive the token_1 fees of all the positions
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Memo program
+    pub memo_program: Program<'info, Memo>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // 1. (reward vault, recipient token account, reward mint) of each initialized reward, same as `decrease_liquidity_v2`
+    // 2. (nft_account, personal_position, tick_array_lower, tick_array_upper) of each position,
+    //    the position must be writable and the tick arrays are read only
+}
+
+/// Accrue the fees and rewards earned by a position since its last update, without changing its liquidity.
+/// The ticks of a position without liquidity may have been released, it has nothing to accrue.
+pub fn accrue_position<'info>(
+    pool_id: &Pubkey,
+    pool_state: &mut PoolState,
+    personal_position: &mut PersonalPositionState,
+    tick_array_lower: &AccountInfo<'info>,
+    tick_array_upper: &AccountInfo<'info>,
+) -> Result<()> {
+    if personal_position.liquidity == 0 {
+        return Ok(());
+    }
+    let tick_spacing = pool_state.tick_spacing;
+    let tick_lower = TickArrayContainer::try_from_pool(
+        tick_array_lower,
+        pool_id,
+        personal_position.tick_lower_index,
+        tick_spacing,
+    )?
+    .get_tick_state(personal_position.tick_lower_index, tick_spacing)?;
+    let tick_upper = TickArrayContainer::try_from_pool(
+        tick_array_upper,
+        pool_id,
+        personal_position.tick_upper_index,
+        tick_spacing,
+    )?
+    .get_tick_state(personal_position.tick_upper_index, tick_spacing)?;
+
+    let updated_reward_infos = pool_state.update_reward_infos(get_unix_timestamp()?)?;
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = TickUtils::get_fee_growth_inside(
+        &tick_lower,
+        &tick_upper,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    let reward_growths_inside = TickUtils::get_reward_growths_inside(
+        &tick_lower,
+        &tick_upper,
+        pool_state.tick_current,
+        &updated_reward_infos,
+    );
+    personal_position.decrease_liquidity(
+        0,
+        fee_growth_inside_0_x64,
+        fee_growth_inside_1_x64,
+        reward_growths_inside,
+        TickUtils::get_maker_rebate_growth_boundary(&tick_lower, &tick_upper),
+        get_recent_epoch()?,
+    )
+}
+
+/// Collect the fees and rewards of up to `MAX_COLLECT_POSITIONS` positions of the same pool and owner,
+/// with a single vault transfer per token. Each position is evented like a `decrease_liquidity_v2` of zero liquidity.
+pub fn collect_fees_and_rewards<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CollectFeesAndRewards<'info>>,
+) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let (collect_fee, collect_reward, reward_count) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        (
+            pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee),
+            pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward),
+            pool_state
+                .reward_infos
+                .iter()
+                .filter(|reward_info| reward_info.initialized())
+                .count(),
+        )
+    };
+    if !collect_fee && !collect_reward {
+        return err!(ErrorCode::NotApproved);
+    }
+
+    let reward_accounts_len = reward_count * 3;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        reward_accounts_len,
+        ErrorCode::InvalidRewardInputAccountNumber
+    );
+    let (reward_accounts, position_accounts) = ctx.remaining_accounts.split_at(reward_accounts_len);
+    let position_count = position_accounts.len() / POSITION_ACCOUNTS_LEN;
+    require!(
+        position_accounts.len() % POSITION_ACCOUNTS_LEN == 0
+            && position_count > 0
+            && position_count <= MAX_COLLECT_POSITIONS,
+        ErrorCode::InvalidCollectPositions
+    );
+
+    // accrue every position and take its owed fees
+    let mut positions: Vec<(Box<Account<PersonalPositionState>>, u64, u64)> =
+        Vec::with_capacity(position_count);
+    let mut total_fees_0 = 0u64;
+    let mut total_fees_1 = 0u64;
+    {
+        let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+        let slot = get_slot()?;
+        for position_group in position_accounts.chunks(POSITION_ACCOUNTS_LEN) {
+            let nft_account = InterfaceAccount::<TokenAccount>::try_from(&position_group[0])?;
+            let mut personal_position = Box::new(Account::<PersonalPositionState>::try_from(
+                &position_group[1],
+            )?);
+            require_keys_eq!(personal_position.pool_id, pool_id);
+            require_keys_eq!(nft_account.mint, personal_position.nft_mint);
+            require_eq!(nft_account.amount, 1);
+            require_keys_eq!(
+                nft_account.owner,
+                ctx.accounts.nft_owner.key(),
+                ErrorCode::NotApproved
+            );
+            // the fees and rewards of a collateralized position back its loan
+            personal_position.check_not_collateralized()?;
+            // a position loaded twice would be paid twice
+            require!(
+                positions
+                    .iter()
+                    .all(|(position, _, _)| position.key() != personal_position.key()),
+                ErrorCode::InvalidCollectPositions
+            );
+            pool_state.check_position_age(personal_position.last_increase_slot, slot)?;
+
+            accrue_position(
+                &pool_id,
+                pool_state,
+                &mut personal_position,
+                &position_group[2],
+                &position_group[3],
+            )?;
+
+            let (mut fees_owed_0, mut fees_owed_1) = (0, 0);
+            if collect_fee {
+                fees_owed_0 = personal_position.token_fees_owed_0;
+                fees_owed_1 = personal_position.token_fees_owed_1;
+                personal_position.token_fees_owed_0 = 0;
+                personal_position.token_fees_owed_1 = 0;
+                total_fees_0 = total_fees_0.checked_add(fees_owed_0).unwrap();
+                total_fees_1 = total_fees_1.checked_add(fees_owed_1).unwrap();
+            }
+            positions.push((personal_position, fees_owed_0, fees_owed_1));
+        }
+
+        require_gte!(
+            pool_state.total_fees_token_0 - pool_state.total_fees_claimed_token_0,
+            total_fees_0
+        );
+        require_gte!(
+            pool_state.total_fees_token_1 - pool_state.total_fees_claimed_token_1,
+            total_fees_1
+        );
+        pool_state.total_fees_claimed_token_0 = pool_state
+            .total_fees_claimed_token_0
+            .checked_add(total_fees_0)
+            .unwrap();
+        pool_state.total_fees_claimed_token_1 = pool_state
+            .total_fees_claimed_token_1
+            .checked_add(total_fees_1)
+            .unwrap();
+    }
+
+    let token_program_2022 = ctx.accounts.token_program_2022.to_account_info();
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(token_program_2022.clone()),
+        total_fees_0,
+    )?;
+    transfer_from_pool_vault_to_user(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        &ctx.accounts.token_program,
+        Some(token_program_2022.clone()),
+        total_fees_1,
+    )?;
+    check_unclaimed_fees_and_vault(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+    )?;
+
+    // the rewards owed to the positions, in the position order, paid as far as the reward vault allows
+    let mut reward_amounts = vec![[0u64; REWARD_NUM]; positions.len()];
+    if collect_reward {
+        require_eq!(
+            reward_accounts.len(),
+            reward_accounts_len,
+            ErrorCode::InvalidRewardInputAccountNumber
+        );
+        for (i, reward_group) in reward_accounts.chunks(3).enumerate() {
+            let reward_token_vault = InterfaceAccount::<TokenAccount>::try_from(&reward_group[0])?;
+            let recipient_token_account =
+                InterfaceAccount::<TokenAccount>::try_from(&reward_group[1])?;
+            let reward_vault_mint = Box::new(InterfaceAccount::<Mint>::try_from(&reward_group[2])?);
+            require_keys_eq!(reward_token_vault.mint, recipient_token_account.mint);
+            require_keys_eq!(
+                reward_token_vault.key(),
+                ctx.accounts.pool_state.load()?.reward_infos[i].token_vault
+            );
+
+            let mut transfer_amount = 0u64;
+            for ((personal_position, _, _), position_reward_amounts) in
+                positions.iter_mut().zip(reward_amounts.iter_mut())
+            {
+                let reward_amount_owed = personal_position.reward_infos[i].reward_amount_owed;
+                let amount = reward_amount_owed.min(reward_token_vault.amount - transfer_amount);
+                personal_position.reward_infos[i].reward_amount_owed = reward_amount_owed - amount;
+                position_reward_amounts[i] = amount;
+                transfer_amount += amount;
+            }
+            if transfer_amount == 0 {
+                continue;
+            }
+            {
+                let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+                pool_state.check_unclaimed_reward(i, transfer_amount)?;
+                pool_state.add_reward_clamed(i, transfer_amount)?;
+            }
+            transfer_from_pool_vault_to_user(
+                &ctx.accounts.pool_state,
+                &reward_token_vault.to_account_info(),
+                &recipient_token_account.to_account_info(),
+                Some(reward_vault_mint),
+                &ctx.accounts.token_program,
+                Some(token_program_2022.clone()),
+                transfer_amount,
+            )?;
+        }
+    }
+
+    for ((personal_position, fees_owed_0, fees_owed_1), reward_amounts) in
+        positions.iter().zip(reward_amounts)
+    {
+        personal_position.exit(&crate::id())?;
+        emit!(DecreaseLiquidityEvent {
+            position_nft_mint: personal_position.nft_mint,
+            liquidity: 0,
+            decrease_amount_0: 0,
+            decrease_amount_1: 0,
+            fee_amount_0: *fees_owed_0,
+            fee_amount_1: *fees_owed_1,
+            reward_amounts,
+            transfer_fee_0: 0,
+            transfer_fee_1: 0,
+            event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod collect_fees_and_rewards_test {
+    use super::*;
+    use crate::libraries::mock_anchor_account_info_v3;
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::build_fix_tick_array_with_tick_states;
+    use std::cell::RefCell;
+
+    pub const TICK_SPACING: u16 = 10;
+    pub const TICK_LOWER_INDEX: i32 = -100;
+    pub const TICK_UPPER_INDEX: i32 = -50;
+    pub const TICK_ARRAY_START_INDEX: i32 = -600;
+
+    /// A pool with the current tick inside [TICK_LOWER_INDEX, TICK_UPPER_INDEX) and a fee growth of 5 per liquidity
+    pub fn build_accrue_pool() -> RefCell<PoolState> {
+        let pool_state = build_pool(-60, TICK_SPACING, 0, 0);
+        pool_state.borrow_mut().fee_growth_global_0_x64 = 5 << 64;
+        pool_state.borrow_mut().fee_growth_global_1_x64 = 0;
+        pool_state
+    }
+
+    pub fn build_position(pool_id: Pubkey, liquidity: u128) -> PersonalPositionState {
+        PersonalPositionState {
+            pool_id,
+            tick_lower_index: TICK_LOWER_INDEX,
+            tick_upper_index: TICK_UPPER_INDEX,
+            liquidity,
+            ..Default::default()
+        }
+    }
+
+    /// The PDA of the tick array holding both ticks of the position, and the tick array of `pool_id`
+    /// with the fee growth outside of its upper tick set to `fee_growth_outside_0_x64`
+    pub fn build_position_tick_array(
+        pool_id: Pubkey,
+        fee_growth_outside_0_x64: u128,
+    ) -> (Pubkey, RefCell<TickArrayState>) {
+        let tick_states = [TICK_LOWER_INDEX, TICK_UPPER_INDEX].map(|tick| {
+            TickState {
+                tick,
+                liquidity_gross: 1,
+                ..Default::default()
+            }
+        });
+        let mut tick_states = tick_states.to_vec();
+        tick_states[1].fee_growth_outside_0_x64 = fee_growth_outside_0_x64;
+        let tick_array = build_fix_tick_array_with_tick_states(
+            pool_id,
+            TICK_ARRAY_START_INDEX,
+            TICK_SPACING,
+            tick_states,
+        );
+        let (key, _) = Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &TICK_ARRAY_START_INDEX.to_be_bytes(),
+            ],
+            &crate::id(),
+        );
+        (key, tick_array)
+    }
+
+    #[test]
+    fn accrue_position_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        let mut personal_position = build_position(pool_id, 1000);
+
+        let (key, tick_array) = build_position_tick_array(pool_id, 0);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        accrue_position(
+            &pool_id,
+            &mut pool_state.borrow_mut(),
+            &mut personal_position,
+            &account_info,
+            &account_info,
+        )
+        .unwrap();
+        assert_eq!(personal_position.token_fees_owed_0, 5000);
+        assert_eq!(personal_position.token_fees_owed_1, 0);
+    }
+
+    #[test]
+    fn accrue_position_foreign_tick_array_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        let mut personal_position = build_position(pool_id, 1000);
+
+        // the tick array of another pool, at its PDA, with an inflated fee growth outside
+        let other_pool_id = Pubkey::new_unique();
+        let (key, tick_array) = build_position_tick_array(other_pool_id, u128::MAX / 2);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        assert_eq!(
+            accrue_position(
+                &pool_id,
+                &mut pool_state.borrow_mut(),
+                &mut personal_position,
+                &account_info,
+                &account_info,
+            )
+            .err(),
+            Some(anchor_lang::error::ErrorCode::RequireKeysEqViolated.into())
+        );
+
+        // a tick array of the pool, but not at its PDA
+        let (_, tick_array) = build_position_tick_array(pool_id, u128::MAX / 2);
+        let spoofed_key = Pubkey::new_unique();
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&spoofed_key, &owner, &*tick_array.borrow(), None);
+        assert_eq!(
+            accrue_position(
+                &pool_id,
+                &mut pool_state.borrow_mut(),
+                &mut personal_position,
+                &account_info,
+                &account_info,
+            )
+            .err(),
+            Some(ErrorCode::InvalidTickArrayAddress.into())
+        );
+        assert_eq!(personal_position.token_fees_owed_0, 0);
+    }
+}
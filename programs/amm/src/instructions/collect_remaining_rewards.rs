@@ -1,6 +1,6 @@
 use crate::error::ErrorCode;
 use crate::states::*;
-use crate::util::transfer_from_pool_vault_to_user;
+use crate::util::{get_unix_timestamp, transfer_from_pool_vault_to_user};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     memo::Memo,
@@ -9,7 +9,7 @@ use anchor_spl::{
 };
 
 /// Memo msg for collect remaining
-pub const COLLECT_REMAINING_MEMO_MSG: &'static [u8] = b"byreal_collect_remaining";
+pub const COLLECT_REMAINING_MEMO_MSG: &[u8] = b"byreal_collect_remaining";
 
 #[derive(Accounts)]
 pub struct CollectRemainingRewards<'info> {
@@ -87,7 +87,7 @@ fn get_remaining_reward_amount(
     reward_token_vault: &InterfaceAccount<TokenAccount>,
     reward_index: u8,
 ) -> Result<u64> {
-    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let current_timestamp = get_unix_timestamp()?;
     let mut pool_state = pool_state_loader.load_mut()?;
     pool_state.update_reward_infos(current_timestamp)?;
 
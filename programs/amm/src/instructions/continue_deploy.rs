@@ -0,0 +1,34 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct ContinueDeploy<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub deployment_state: Box<Account<'info, DeploymentState>>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The next position of the ladder, opened by the owner
+    #[account(constraint = personal_position.pool_id == deployment_state.pool_id)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+pub fn continue_deploy(ctx: Context<ContinueDeploy>) -> Result<()> {
+    let personal_position = &ctx.accounts.personal_position;
+    ctx.accounts.deployment_state.record_position(
+        personal_position.key(),
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        personal_position.liquidity,
+    )?;
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use super::create_pool_decay_fee::*;
-use anchor_lang::{prelude::*, solana_program};
+use crate::util::get_unix_timestamp;
+use anchor_lang::prelude::*;
 
 pub fn create_pool(ctx: Context<CreatePool>, sqrt_price_x64: u128, open_time: u64) -> Result<()> {
     // we can set open-time as a future time, or current time
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let block_timestamp = get_unix_timestamp()?;
     let open_time = if open_time > block_timestamp {
         open_time
     } else {
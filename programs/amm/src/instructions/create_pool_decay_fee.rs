@@ -2,7 +2,7 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use crate::util::create_token_vault_account;
 use crate::{libraries::tick_math, util};
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenInterface};
 use std::ops::DerefMut;
 // use solana_program::{program::invoke_signed, system_instruction};
@@ -176,105 +176,165 @@ pub struct CreatePoolDecayFeeParams {
     /// interval_count = (current_time - open_time) / decay_fee_decrease_interval
     /// current_decay_fee = init_decay_fee_rate*((1-decay_fee_decrease_rate/100)^interval_count)
     pub decay_fee_decrease_interval: u8,
+
+    /// Liquidity bootstrapping launch mode, only used when use_decay_fee is true.
+    /// If set, sells of the mint with decay fee can't push its price below this floor until the decay fee ends:
+    /// it is a lower bound of the sqrt price if the decay fee is on sell for mint0, an upper bound if on sell for mint1.
+    pub bootstrap_sqrt_price_floor_x64: Option<u128>,
+
+    /// Create the tick arrays of the pool as fixed tick arrays instead of dynamic tick arrays.
+    /// Fixed tick arrays cost more rent, but less compute for the pools expecting dense tick usage.
+    pub use_fixed_tick_array: bool,
+
+    /// The max duration in seconds of the decay fee after the open time, only used when use_decay_fee is true.
+    /// The decay fee ends at the latest `open_time + decay_fee_max_window`, even if it is still above the trade fee.
+    /// If None, it only ends when it decays to the trade fee.
+    pub decay_fee_max_window: Option<u64>,
 }
 
 pub fn create_pool_decay_fee(
     ctx: Context<CreatePool>,
     params: CreatePoolDecayFeeParams,
 ) -> Result<()> {
-    let mint0_associated_is_initialized = util::support_mint_associated_is_initialized(
-        &ctx.remaining_accounts,
-        &ctx.accounts.token_mint_0,
-    )?;
-    let mint1_associated_is_initialized = util::support_mint_associated_is_initialized(
-        &ctx.remaining_accounts,
-        &ctx.accounts.token_mint_1,
-    )?;
-    if !(util::is_supported_mint(&ctx.accounts.token_mint_0, mint0_associated_is_initialized)
-        .unwrap()
-        && util::is_supported_mint(&ctx.accounts.token_mint_1, mint1_associated_is_initialized)
-            .unwrap())
+    create_pool_internal(ctx, params, None)
+}
+
+/// Create the pool, and apply the template parameters not covered by `params` if it is created from a template
+pub(crate) fn create_pool_internal(
+    ctx: Context<CreatePool>,
+    params: CreatePoolDecayFeeParams,
+    template: Option<&PoolTemplate>,
+) -> Result<()> {
+    let mint0_associated =
+        util::get_support_mint_associated(ctx.remaining_accounts, &ctx.accounts.token_mint_0)?;
+    let mint1_associated =
+        util::get_support_mint_associated(ctx.remaining_accounts, &ctx.accounts.token_mint_1)?;
+    if !(util::is_supported_mint(&ctx.accounts.token_mint_0, mint0_associated.as_ref())?
+        && util::is_supported_mint(&ctx.accounts.token_mint_1, mint1_associated.as_ref())?)
     {
         return err!(ErrorCode::NotSupportMint);
     }
 
+    let accounts = ctx.accounts;
+    initialize_pool(
+        &accounts.pool_creator,
+        &accounts.amm_config,
+        &accounts.pool_state,
+        ctx.bumps.pool_state,
+        &mut accounts.offchain_reward_config,
+        ctx.bumps.offchain_reward_config,
+        &accounts.token_mint_0,
+        &accounts.token_mint_1,
+        &accounts.token_vault_0,
+        ctx.bumps.token_vault_0,
+        &accounts.token_vault_1,
+        ctx.bumps.token_vault_1,
+        &accounts.observation_state,
+        &accounts.tick_array_bitmap,
+        &accounts.token_program_0,
+        &accounts.token_program_1,
+        &accounts.system_program,
+        params,
+        template,
+    )
+}
+
+/// Initialize the accounts of a new pool, shared by the instructions creating a pool
+pub(crate) fn initialize_pool<'info>(
+    pool_creator: &Signer<'info>,
+    amm_config: &Account<'info, AmmConfig>,
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    pool_bump: u8,
+    offchain_reward_config: &mut Account<'info, OffchainRewardConfig>,
+    offchain_reward_config_bump: u8,
+    token_mint_0: &InterfaceAccount<'info, Mint>,
+    token_mint_1: &InterfaceAccount<'info, Mint>,
+    token_vault_0: &AccountInfo<'info>,
+    token_vault_0_bump: u8,
+    token_vault_1: &AccountInfo<'info>,
+    token_vault_1_bump: u8,
+    observation_state: &AccountLoader<'info, ObservationState>,
+    tick_array_bitmap: &AccountLoader<'info, TickArrayBitmapExtension>,
+    token_program_0: &Interface<'info, TokenInterface>,
+    token_program_1: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+    params: CreatePoolDecayFeeParams,
+    template: Option<&PoolTemplate>,
+) -> Result<()> {
     // we can set open-time as a future time
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let block_timestamp = util::get_unix_timestamp()?;
     let open_time = params.open_time.unwrap_or(block_timestamp);
     require_gte!(open_time, block_timestamp);
 
-    let pool_id = ctx.accounts.pool_state.key();
+    let pool_id = pool_state_loader.key();
 
     // init offchain reward config
     {
-        let reward_vault = ctx.accounts.offchain_reward_config.key();
-        let vault_bump = ctx.bumps.offchain_reward_config;
-        let offchain_reward_config = ctx.accounts.offchain_reward_config.deref_mut();
+        let reward_vault = offchain_reward_config.key();
+        let offchain_reward_config = offchain_reward_config.deref_mut();
 
-        offchain_reward_config.initialize(pool_id, reward_vault, vault_bump)?;
+        offchain_reward_config.initialize(pool_id, reward_vault, offchain_reward_config_bump)?;
     }
 
-    let mut pool_state = ctx.accounts.pool_state.load_init()?;
+    let mut pool_state = pool_state_loader.load_init()?;
 
-    let tick = tick_math::get_tick_at_sqrt_price(params.sqrt_price_x64)?;
-    #[cfg(feature = "enable-log")]
-    msg!(
-        "create pool with decay-fee, init_price: {}, init_tick:{}",
+    let tick = tick_math::get_tick_at_sqrt_price_raw(params.sqrt_price_x64)?;
+    log_info!(
+        ix = "create_pool",
+        pool = pool_state_loader.key(),
+        "init_price: {}, init_tick:{}",
         params.sqrt_price_x64,
         tick
     );
 
     // init token vault accounts
     create_token_vault_account(
-        &ctx.accounts.pool_creator,
-        &ctx.accounts.pool_state.to_account_info(),
-        &ctx.accounts.token_vault_0,
-        &ctx.accounts.token_mint_0,
-        &ctx.accounts.system_program,
-        &ctx.accounts.token_program_0,
+        pool_creator,
+        &pool_state_loader.to_account_info(),
+        token_vault_0,
+        token_mint_0,
+        system_program,
+        token_program_0,
         &[
             POOL_VAULT_SEED.as_bytes(),
-            ctx.accounts.pool_state.key().as_ref(),
-            ctx.accounts.token_mint_0.key().as_ref(),
-            &[ctx.bumps.token_vault_0][..],
+            pool_state_loader.key().as_ref(),
+            token_mint_0.key().as_ref(),
+            &[token_vault_0_bump][..],
         ],
     )?;
 
     create_token_vault_account(
-        &ctx.accounts.pool_creator,
-        &ctx.accounts.pool_state.to_account_info(),
-        &ctx.accounts.token_vault_1,
-        &ctx.accounts.token_mint_1,
-        &ctx.accounts.system_program,
-        &ctx.accounts.token_program_1,
+        pool_creator,
+        &pool_state_loader.to_account_info(),
+        token_vault_1,
+        token_mint_1,
+        system_program,
+        token_program_1,
         &[
             POOL_VAULT_SEED.as_bytes(),
-            ctx.accounts.pool_state.key().as_ref(),
-            ctx.accounts.token_mint_1.key().as_ref(),
-            &[ctx.bumps.token_vault_1][..],
+            pool_state_loader.key().as_ref(),
+            token_mint_1.key().as_ref(),
+            &[token_vault_1_bump][..],
         ],
     )?;
 
     // init observation
-    ctx.accounts
-        .observation_state
-        .load_init()?
-        .initialize(pool_id)?;
+    observation_state.load_init()?.initialize(pool_id)?;
 
-    let bump = ctx.bumps.pool_state;
     pool_state.initialize(
-        bump,
+        pool_bump,
         params.sqrt_price_x64,
         open_time,
         tick,
-        ctx.accounts.pool_creator.key(),
-        ctx.accounts.token_vault_0.key(),
-        ctx.accounts.token_vault_1.key(),
-        ctx.accounts.amm_config.as_ref(),
-        ctx.accounts.token_mint_0.as_ref(),
-        ctx.accounts.token_mint_1.as_ref(),
-        ctx.accounts.observation_state.key(),
+        pool_creator.key(),
+        token_vault_0.key(),
+        token_vault_1.key(),
+        amm_config,
+        token_mint_0,
+        token_mint_1,
+        observation_state.key(),
     )?;
+    pool_state.set_use_fixed_tick_array(params.use_fixed_tick_array);
 
     // if use decay fee, then config it
     if params.use_decay_fee {
@@ -285,22 +345,39 @@ pub fn create_pool_decay_fee(
             params.decay_fee_decrease_rate,
             params.decay_fee_decrease_interval,
         )?;
+        if let Some(sqrt_price_floor_x64) = params.bootstrap_sqrt_price_floor_x64 {
+            pool_state.initialize_bootstrap_price_floor(sqrt_price_floor_x64)?;
+        }
+        if let Some(max_window) = params.decay_fee_max_window {
+            pool_state.set_decay_fee_max_window(max_window)?;
+        }
+    } else {
+        require!(
+            params.bootstrap_sqrt_price_floor_x64.is_none(),
+            ErrorCode::InvalidBootstrapPriceFloor
+        );
+        require!(
+            params.decay_fee_max_window.is_none(),
+            ErrorCode::InvalidDecayFeeWindow
+        );
+    }
+
+    if let Some(template) = template {
+        template.apply(pool_state.deref_mut())?;
     }
 
-    ctx.accounts
-        .tick_array_bitmap
-        .load_init()?
-        .initialize(pool_id);
+    tick_array_bitmap.load_init()?.initialize(pool_id);
 
     emit!(PoolCreatedEvent {
-        token_mint_0: ctx.accounts.token_mint_0.key(),
-        token_mint_1: ctx.accounts.token_mint_1.key(),
-        tick_spacing: ctx.accounts.amm_config.tick_spacing,
-        pool_state: ctx.accounts.pool_state.key(),
+        token_mint_0: token_mint_0.key(),
+        token_mint_1: token_mint_1.key(),
+        tick_spacing: amm_config.tick_spacing,
+        pool_state: pool_state_loader.key(),
         sqrt_price_x64: params.sqrt_price_x64,
         tick,
-        token_vault_0: ctx.accounts.token_vault_0.key(),
-        token_vault_1: ctx.accounts.token_vault_1.key(),
+        token_vault_0: token_vault_0.key(),
+        token_vault_1: token_vault_1.key(),
+        event_seq: pool_state.next_event_seq(),
     });
     Ok(())
 }
@@ -0,0 +1,39 @@
+use super::create_pool_decay_fee::*;
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// Create a pool with the parameters of a `PoolTemplate`, the template account must be the first remaining account.
+pub fn create_pool_from_template(
+    ctx: Context<CreatePool>,
+    sqrt_price_x64: u128,
+    open_time: Option<u64>,
+) -> Result<()> {
+    let template_info = ctx
+        .remaining_accounts
+        .first()
+        .ok_or(error!(ErrorCode::InvalidPoolTemplate))?;
+    require_keys_eq!(
+        *template_info.owner,
+        crate::id(),
+        ErrorCode::IllegalAccountOwner
+    );
+    let template = PoolTemplate::try_deserialize(&mut template_info.data.borrow().as_ref())?;
+    template.check_config(ctx.accounts.amm_config.key(), &ctx.accounts.amm_config)?;
+
+    let template_params = &template.params;
+    let params = CreatePoolDecayFeeParams {
+        sqrt_price_x64,
+        open_time,
+        use_decay_fee: template_params.use_decay_fee,
+        decay_fee_on_sell_mint0: template_params.decay_fee_on_sell_mint0,
+        decay_fee_on_sell_mint1: template_params.decay_fee_on_sell_mint1,
+        init_decay_fee_rate: template_params.init_decay_fee_rate,
+        decay_fee_decrease_rate: template_params.decay_fee_decrease_rate,
+        decay_fee_decrease_interval: template_params.decay_fee_decrease_interval,
+        bootstrap_sqrt_price_floor_x64: None,
+        use_fixed_tick_array: template_params.use_fixed_tick_array,
+        decay_fee_max_window: None,
+    };
+    create_pool_internal(ctx, params, Some(&template))
+}
@@ -2,8 +2,8 @@ use super::modify_position;
 use crate::error::ErrorCode;
 use crate::instructions::LiquidityChangeResult;
 use crate::states::*;
-use crate::util::get_recent_epoch;
 use crate::util::{self, transfer_from_pool_vault_to_user};
+use crate::util::{get_recent_epoch, get_slot, get_unix_timestamp};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 use anchor_spl::token_interface::{self, Mint, Token2022};
@@ -12,10 +12,11 @@ use std::cell::RefMut;
 use std::ops::Deref;
 
 /// Memo msg for decrease liquidity
-pub const DECREASE_MEMO_MSG: &'static [u8] = b"byreal_decrease";
+pub const DECREASE_MEMO_MSG: &[u8] = b"byreal_decrease";
 #[derive(Accounts)]
 pub struct DecreaseLiquidity<'info> {
-    /// The position owner or delegated authority
+    /// The position owner or delegated authority, receives the rent freed by the tick arrays that shrink
+    #[account(mut)]
     pub nft_owner: Signer<'info>,
 
     /// The token account for the tokenized position
@@ -78,6 +79,10 @@ pub struct DecreaseLiquidity<'info> {
 
     /// SPL program to transfer out tokens
     pub token_program: Program<'info, Token>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -95,23 +100,28 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     amount_0_min: u64,
     amount_1_min: u64,
 ) -> Result<()> {
+    ctx.accounts.personal_position.check_not_collateralized()?;
     let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
     let tick_lower = ctx.accounts.personal_position.tick_lower_index;
     let tick_upper = ctx.accounts.personal_position.tick_upper_index;
 
-    let tick_array_lower_loader = TickArrayContainer::try_from(
+    let position_liquidity = ctx.accounts.personal_position.liquidity;
+    let tick_array_lower_loader = load_position_tick_array(
         &ctx.accounts.tick_array_lower.to_account_info(),
         tick_lower,
         tick_spacing,
+        position_liquidity,
     )?;
-    let tick_array_upper_loader = TickArrayContainer::try_from(
+    let tick_array_upper_loader = load_position_tick_array(
         &ctx.accounts.tick_array_upper.to_account_info(),
         tick_upper,
         tick_spacing,
+        position_liquidity,
     )?;
 
     decrease_liquidity(
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &mut ctx.accounts.personal_position,
         &ctx.accounts.token_vault_0.to_account_info(),
         &ctx.accounts.token_vault_1.to_account_info(),
@@ -124,15 +134,32 @@ pub fn decrease_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
         None,
         None,
         None,
-        &ctx.remaining_accounts,
+        Some(&ctx.accounts.nft_owner.to_account_info()),
+        ctx.remaining_accounts,
         liquidity,
         amount_0_min,
         amount_1_min,
     )
 }
 
+/// Load the tick array of a position tick, the tick isn't checked for a position without liquidity
+/// because it may have been released by `TickArrayContainer::free_one_tick`
+pub fn load_position_tick_array<'info>(
+    tick_array_info: &AccountInfo<'info>,
+    tick_index: i32,
+    tick_spacing: u16,
+    position_liquidity: u128,
+) -> Result<TickArrayContainer<'info>> {
+    if position_liquidity == 0 {
+        TickArrayContainer::try_from_without_check(tick_array_info)
+    } else {
+        TickArrayContainer::try_from(tick_array_info, tick_index, tick_spacing)
+    }
+}
+
 pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     pool_state_loader: &'b AccountLoader<'info, PoolState>,
+    amm_config: &'b AmmConfig,
     personal_position: &'b mut Box<Account<'info, PersonalPositionState>>,
     token_vault_0: &'b AccountInfo<'info>,
     token_vault_1: &'b AccountInfo<'info>,
@@ -145,6 +172,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     _memo_program: Option<UncheckedAccount<'info>>,
     vault_0_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
     vault_1_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    rent_recipient: Option<&'b AccountInfo<'info>>,
     remaining_accounts: &'c [AccountInfo<'info>],
     liquidity: u128,
     amount_0_min: u64,
@@ -170,11 +198,13 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     let pool_sqrt_price_x64;
     let pool_tick_current;
     let mut tickarray_bitmap_extension = None;
+    let mut tick_rent_treasury = None;
 
     let remaining_collect_accounts = &mut Vec::new();
     {
         let pool_state = pool_state_loader.load()?;
-        if !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+        if (!pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+            || !amm_config.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity))
             && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
             && !pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)
         {
@@ -189,14 +219,18 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
             tick_array_upper_loader.get_start_tick_index()?,
         ]);
 
-        for account_info in remaining_accounts.into_iter() {
-            if account_info
-                .key()
-                .eq(&TickArrayBitmapExtension::key(pool_state.key()))
-            {
+        for account_info in remaining_accounts.iter() {
+            if account_info.key().eq(&TickArrayBitmapExtension::key(
+                pool_state.key(),
+                &crate::id(),
+            )) {
                 tickarray_bitmap_extension = Some(account_info);
                 continue;
             }
+            if account_info.key() == TickRentTreasury::key(pool_state.key(), &crate::id()) {
+                tick_rent_treasury = Some(account_info);
+                continue;
+            }
             remaining_collect_accounts.push(account_info);
         }
         if use_tickarray_bitmap_extension {
@@ -210,10 +244,13 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     let (decrease_amount_0, latest_fees_owed_0, decrease_amount_1, latest_fees_owed_1) =
         decrease_liquidity_and_update_position(
             pool_state_loader,
+            amm_config,
             personal_position,
             tick_array_lower_loader,
             tick_array_upper_loader,
             tickarray_bitmap_extension,
+            tick_rent_treasury,
+            rent_recipient,
             liquidity,
         )?;
 
@@ -229,7 +266,7 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
     }
     emit!(LiquidityCalculateEvent {
         pool_liquidity: liquidity_before,
-        pool_sqrt_price_x64: pool_sqrt_price_x64,
+        pool_sqrt_price_x64,
         pool_tick: pool_tick_current,
         calc_amount_0: decrease_amount_0,
         calc_amount_1: decrease_amount_1,
@@ -237,9 +274,11 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         trade_fee_owed_1: latest_fees_owed_1,
         transfer_fee_0,
         transfer_fee_1,
+        event_seq: pool_state_loader.load_mut()?.next_event_seq(),
     });
-    #[cfg(feature = "enable-log")]
-    msg!(
+    log_info!(
+        ix = "decrease_liquidity",
+        pool = pool_state_loader.key(),
         "decrease_amount_0: {}, transfer_fee_0: {}, latest_fees_owed_0: {}, decrease_amount_1: {}, transfer_fee_1: {}, latest_fees_owed_1: {}",
         decrease_amount_0,
         transfer_fee_0,
@@ -296,22 +335,19 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
         token_program,
         token_2022_program_opt.clone(),
         personal_position,
-        if token_2022_program_opt.is_none() {
-            false
-        } else {
-            true
-        },
+        token_2022_program_opt.is_some(),
     )?;
     emit!(DecreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
         liquidity,
-        decrease_amount_0: decrease_amount_0,
-        decrease_amount_1: decrease_amount_1,
+        decrease_amount_0,
+        decrease_amount_1,
         fee_amount_0: latest_fees_owed_0,
         fee_amount_1: latest_fees_owed_1,
         reward_amounts,
-        transfer_fee_0: transfer_fee_0,
-        transfer_fee_1: transfer_fee_1,
+        transfer_fee_0,
+        transfer_fee_1,
+        event_seq: pool_state_loader.load_mut()?.next_event_seq(),
     });
 
     Ok(())
@@ -319,28 +355,39 @@ pub fn decrease_liquidity<'a, 'b, 'c: 'info, 'info>(
 
 pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
     pool_state_loader: &AccountLoader<'info, PoolState>,
+    amm_config: &AmmConfig,
     personal_position: &mut Box<Account<'info, PersonalPositionState>>,
     tick_array_lower: &TickArrayContainer<'info>,
     tick_array_upper: &TickArrayContainer<'info>,
     tick_array_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    tick_rent_treasury: Option<&'c AccountInfo<'info>>,
+    rent_recipient: Option<&AccountInfo<'info>>,
     liquidity: u128,
 ) -> Result<(u64, u64, u64, u64)> {
     let mut pool_state = pool_state_loader.load_mut()?;
+    pool_state.check_position_age(personal_position.last_increase_slot, get_slot()?)?;
     let mut decrease_amount_0 = 0;
     let mut decrease_amount_1 = 0;
-    if pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity) {
+    // the ticks of a position without liquidity may have been released, it only has owed fees and rewards left
+    if pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+        && amm_config.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+        && personal_position.liquidity > 0
+    {
         let LiquidityChangeResult {
             amount_0,
             amount_1,
             fee_growth_inside_0_x64: fee_growth_inside_0_x64_latest,
             fee_growth_inside_1_x64: fee_growth_inside_1_x64_latest,
             reward_growths_inside: reward_growths_inside_latest,
+            maker_rebate_growth_boundary_x64: maker_rebate_growth_boundary_x64_latest,
             ..
         } = burn_liquidity(
             &mut pool_state,
             tick_array_lower,
             tick_array_upper,
             tick_array_bitmap_extension,
+            tick_rent_treasury,
+            rent_recipient,
             personal_position.tick_lower_index,
             personal_position.tick_upper_index,
             liquidity,
@@ -351,6 +398,7 @@ pub fn decrease_liquidity_and_update_position<'a, 'b, 'c: 'info, 'info>(
             fee_growth_inside_0_x64_latest,
             fee_growth_inside_1_x64_latest,
             reward_growths_inside_latest,
+            maker_rebate_growth_boundary_x64_latest,
             get_recent_epoch()?,
         )?;
         decrease_amount_0 = amount_0;
@@ -398,6 +446,8 @@ pub fn burn_liquidity<'c: 'info, 'info>(
     tick_array_lower_loader: &TickArrayContainer<'info>,
     tick_array_upper_loader: &TickArrayContainer<'info>,
     tickarray_bitmap_extension: Option<&'c AccountInfo<'info>>,
+    tick_rent_treasury: Option<&'c AccountInfo<'info>>,
+    rent_recipient: Option<&AccountInfo<'info>>,
     tick_lower_index: i32,
     tick_upper_index: i32,
     liquidity: u128,
@@ -417,14 +467,12 @@ pub fn burn_liquidity<'c: 'info, 'info>(
             .get_ref_mut()?
             .get_tick_state_mut(tick_upper_index, pool_state.tick_spacing)?,
     );
-    let clock = Clock::get()?;
-
     let result = modify_position(
         -i128::try_from(liquidity).unwrap(),
         pool_state,
         &mut tick_lower_state,
         &mut tick_upper_state,
-        clock.unix_timestamp as u64,
+        get_unix_timestamp()?,
     )?;
 
     // update tick_state
@@ -464,22 +512,44 @@ pub fn burn_liquidity<'c: 'info, 'info>(
         }
     }
 
+    // release the ticks left without liquidity, so that dynamic tick arrays shrink back
+    let tick_spacing = pool_state.tick_spacing;
+    if result.tick_lower_flipped {
+        tick_array_lower_loader.free_one_tick(
+            tick_lower_index,
+            tick_spacing,
+            pool_state,
+            rent_recipient,
+            tick_rent_treasury,
+        )?;
+    }
+    if result.tick_upper_flipped {
+        tick_array_upper_loader.free_one_tick(
+            tick_upper_index,
+            tick_spacing,
+            pool_state,
+            rent_recipient,
+            tick_rent_treasury,
+        )?;
+    }
+
     emit!(LiquidityChangeEvent {
         pool_state: pool_state.key(),
         tick: pool_state.tick_current,
         tick_lower: tick_lower_index,
         tick_upper: tick_upper_index,
-        liquidity_before: liquidity_before,
+        liquidity_before,
         liquidity_after: pool_state.liquidity,
+        event_seq: pool_state.next_event_seq(),
     });
 
     Ok(result)
 }
 
-pub fn collect_rewards<'a, 'b, 'c, 'info>(
+pub fn collect_rewards<'info>(
     pool_state_loader: &AccountLoader<'info, PoolState>,
     remaining_accounts: &[&'info AccountInfo<'info>],
-    token_program: &'b Program<'info, Token>,
+    token_program: &Program<'info, Token>,
     token_program_2022: Option<AccountInfo<'info>>,
     personal_position_state: &mut PersonalPositionState,
     need_reward_mint: bool,
@@ -493,7 +563,7 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
     }
     let mut reward_group_account_num = 3;
     if !need_reward_mint {
-        reward_group_account_num = reward_group_account_num - 1
+        reward_group_account_num -= 1
     }
     check_required_accounts_length(
         pool_state_loader,
@@ -503,6 +573,7 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
 
     let remaining_accounts_len = remaining_accounts.len();
     let mut remaining_accounts = remaining_accounts.iter();
+    #[allow(clippy::needless_range_loop)] // the index also addresses the pool reward infos
     for i in 0..remaining_accounts_len / reward_group_account_num {
         let reward_token_vault = InterfaceAccount::<token_interface::TokenAccount>::try_from(
             remaining_accounts.next().unwrap(),
@@ -551,11 +622,11 @@ pub fn collect_rewards<'a, 'b, 'c, 'info>(
                 .add_reward_clamed(i, transfer_amount)?;
 
             transfer_from_pool_vault_to_user(
-                &pool_state_loader,
+                pool_state_loader,
                 &reward_token_vault.to_account_info(),
                 &recipient_token_account.to_account_info(),
                 reward_vault_mint.clone(),
-                &token_program,
+                token_program,
                 token_program_2022.clone(),
                 transfer_amount,
             )?;
@@ -575,7 +646,7 @@ fn check_required_accounts_length(
     let mut valid_reward_count = 0;
     for item in pool_state.reward_infos {
         if item.initialized() {
-            valid_reward_count = valid_reward_count + 1;
+            valid_reward_count += 1;
         }
     }
     let remaining_accounts_len = remaining_accounts.len();
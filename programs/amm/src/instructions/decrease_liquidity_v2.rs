@@ -1,4 +1,4 @@
-use super::decrease_liquidity::decrease_liquidity;
+use super::decrease_liquidity::{decrease_liquidity, load_position_tick_array};
 use crate::states::*;
 use anchor_lang::prelude::*;
 use anchor_spl::memo::spl_memo;
@@ -7,7 +7,8 @@ use anchor_spl::token_interface::Mint;
 use anchor_spl::token_interface::{Token2022, TokenAccount};
 #[derive(Accounts)]
 pub struct DecreaseLiquidityV2<'info> {
-    /// The position owner or delegated authority
+    /// The position owner or delegated authority, receives the rent freed by the tick arrays that shrink
+    #[account(mut)]
     pub nft_owner: Signer<'info>,
 
     /// The token account for the tokenized position
@@ -91,6 +92,10 @@ pub struct DecreaseLiquidityV2<'info> {
         address = token_vault_1.mint
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -108,23 +113,29 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     amount_0_min: u64,
     amount_1_min: u64,
 ) -> Result<()> {
-    let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
+    ctx.accounts.personal_position.check_not_collateralized()?;
+    let tick_spacing =
+        PoolStateView::load(&ctx.accounts.pool_state.to_account_info())?.tick_spacing();
     let tick_lower = ctx.accounts.personal_position.tick_lower_index;
     let tick_upper = ctx.accounts.personal_position.tick_upper_index;
 
-    let tick_array_lower_loader = TickArrayContainer::try_from(
+    let position_liquidity = ctx.accounts.personal_position.liquidity;
+    let tick_array_lower_loader = load_position_tick_array(
         &ctx.accounts.tick_array_lower.to_account_info(),
         tick_lower,
         tick_spacing,
+        position_liquidity,
     )?;
-    let tick_array_upper_loader = TickArrayContainer::try_from(
+    let tick_array_upper_loader = load_position_tick_array(
         &ctx.accounts.tick_array_upper.to_account_info(),
         tick_upper,
         tick_spacing,
+        position_liquidity,
     )?;
 
     decrease_liquidity(
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &mut ctx.accounts.personal_position,
         &ctx.accounts.token_vault_0.to_account_info(),
         &ctx.accounts.token_vault_1.to_account_info(),
@@ -137,7 +148,8 @@ pub fn decrease_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
         Some(ctx.accounts.memo_program.clone()),
         Some(ctx.accounts.vault_0_mint.clone()),
         Some(ctx.accounts.vault_1_mint.clone()),
-        &ctx.remaining_accounts,
+        Some(&ctx.accounts.nft_owner.to_account_info()),
+        ctx.remaining_accounts,
         liquidity,
         amount_0_min,
         amount_1_min,
@@ -0,0 +1,63 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct FinishDeploy<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner, close = owner)]
+    pub deployment_state: Box<Account<'info, DeploymentState>>,
+
+    /// The pool of the deployment, to stamp the sequence number of the event
+    #[account(mut, address = deployment_state.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+    // remaining accounts
+    // the deployed personal positions, only required when aborting
+}
+
+/// Close the deployment state.
+/// If `abort` is true, the deployed positions are not unwound here: the owner closes them or decreases all
+/// their liquidity first, and they are passed as remaining accounts to check it. Otherwise all positions
+/// of the plan must be deployed.
+pub fn finish_deploy<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FinishDeploy<'info>>,
+    abort: bool,
+) -> Result<()> {
+    let deployment_state = &ctx.accounts.deployment_state;
+    if abort {
+        for position in deployment_state.positions.iter() {
+            let position_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account_info| account_info.key == position)
+                .ok_or(error!(ErrorCode::DeploymentPositionsNotEmptied))?;
+            // a closed position account is reassigned to the system program
+            if position_info.owner != &crate::id() || position_info.data_is_empty() {
+                continue;
+            }
+            let personal_position = Account::<PersonalPositionState>::try_from(position_info)?;
+            require_eq!(
+                personal_position.liquidity,
+                0,
+                ErrorCode::DeploymentPositionsNotEmptied
+            );
+        }
+    } else {
+        require!(
+            deployment_state.is_complete(),
+            ErrorCode::DeploymentNotComplete
+        );
+    }
+
+    emit!(DeploymentFinishedEvent {
+        deployment_state: deployment_state.key(),
+        pool_state: deployment_state.pool_id,
+        owner: deployment_state.owner,
+        position_count: deployment_state.positions.len() as u16,
+        aborted: abort,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(())
+}
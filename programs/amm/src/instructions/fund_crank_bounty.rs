@@ -0,0 +1,73 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+#[derive(Accounts)]
+pub struct FundCrankBounty<'info> {
+    /// The pool creator, who tips the keepers
+    #[account(
+        mut,
+        address = pool_state.load()?.owner @ ErrorCode::NotApproved
+    )]
+    pub funder: Signer<'info>,
+
+    /// The pool whose cranks are tipped
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Holds the prepaid lamports, created on the first funding
+    #[account(
+        init_if_needed,
+        seeds = [
+            CRANK_BOUNTY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = funder,
+        space = CrankBounty::LEN
+    )]
+    pub crank_bounty: Account<'info, CrankBounty>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_crank_bounty(
+    ctx: Context<FundCrankBounty>,
+    amount: u64,
+    bounty_lamports: u64,
+    min_interval: u64,
+) -> Result<()> {
+    let crank_bounty = &mut ctx.accounts.crank_bounty;
+    if crank_bounty.pool_id == Pubkey::default() {
+        crank_bounty.initialize(ctx.bumps.crank_bounty, ctx.accounts.pool_state.key());
+    }
+    crank_bounty.bounty_lamports = bounty_lamports;
+    crank_bounty.min_interval = min_interval;
+
+    if amount > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: crank_bounty.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        crank_bounty.total_deposited = crank_bounty.total_deposited.checked_add(amount).unwrap();
+    }
+
+    emit!(FundCrankBountyEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+        bounty_lamports,
+        min_interval,
+        total_deposited: crank_bounty.total_deposited,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+
+    Ok(())
+}
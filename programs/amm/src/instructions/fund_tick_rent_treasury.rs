@@ -0,0 +1,65 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+#[derive(Accounts)]
+pub struct FundTickRentTreasury<'info> {
+    /// The pool creator, who prepays the tick array rent
+    #[account(
+        mut,
+        address = pool_state.load()?.owner @ ErrorCode::NotApproved
+    )]
+    pub funder: Signer<'info>,
+
+    /// The pool whose tick array rent is prepaid
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Holds the prepaid lamports, created on the first funding
+    #[account(
+        init_if_needed,
+        seeds = [
+            TICK_RENT_TREASURY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = funder,
+        space = TickRentTreasury::LEN
+    )]
+    pub tick_rent_treasury: Account<'info, TickRentTreasury>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_tick_rent_treasury(ctx: Context<FundTickRentTreasury>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0);
+
+    let treasury = &mut ctx.accounts.tick_rent_treasury;
+    treasury.deposit(
+        ctx.bumps.tick_rent_treasury,
+        ctx.accounts.pool_state.key(),
+        amount,
+    );
+
+    transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: treasury.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    emit!(FundTickRentTreasuryEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+        total_deposited: treasury.total_deposited,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use crate::error::ErrorCode;
+use crate::interface::TickArrayRent;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(tick_array_start_index: i32)]
+pub struct GetOrCreateTickArray<'info> {
+    /// Pays the rent of the tick array, or of the TickState allocated in a dynamic tick array
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Stamps the sequence number of the resize event
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: created if it doesn't exist, checked in the function body otherwise
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining account
+    // optional, pays the tick array rent instead of payer, only if the payer is the pool owner
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
+}
+
+pub fn get_or_create_tick_array<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, GetOrCreateTickArray<'info>>,
+    tick_array_start_index: i32,
+    access_tick_index: i32,
+    prealloc_tick_count: Option<u8>,
+) -> Result<TickArrayRent> {
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+    let tick_array_lamports_before = tick_array_info.lamports();
+    let payer_lamports_before = ctx.accounts.payer.lamports();
+
+    let pool_state = &mut ctx.accounts.pool_state.load_mut()?;
+    // the larger reservations go through `reserve_ticks`
+    if let Some(prealloc_tick_count) = prealloc_tick_count {
+        require_gte!(
+            pool_state.prealloc_tick_count.max(1),
+            prealloc_tick_count,
+            ErrorCode::InvalidPreallocTickCount
+        );
+    }
+    let tick_rent_treasury = TickRentTreasury::find_for_pool_owner(
+        ctx.remaining_accounts,
+        ctx.accounts.pool_state.key(),
+        pool_state.owner,
+        ctx.accounts.payer.key(),
+    );
+    let tick_spacing = pool_state.tick_spacing;
+    let tick_array = TickArrayContainer::get_or_create_tick_array(
+        ctx.accounts.payer.to_account_info(),
+        tick_array_info.clone(),
+        ctx.accounts.system_program.to_account_info(),
+        tick_rent_treasury,
+        &ctx.accounts.pool_state,
+        pool_state,
+        tick_array_start_index,
+        access_tick_index,
+        tick_spacing,
+        prealloc_tick_count,
+    )?;
+
+    Ok(TickArrayRent {
+        rent_charged: tick_array_info
+            .lamports()
+            .saturating_sub(tick_array_lamports_before),
+        payer_rent: payer_lamports_before.saturating_sub(ctx.accounts.payer.lamports()),
+        data_len: tick_array_info.data_len() as u64,
+        is_dynamic: matches!(tick_array, TickArrayContainer::Dynamic(_)),
+    })
+}
@@ -73,6 +73,10 @@ pub struct IncreaseLiquidity<'info> {
 
     /// Program to create mint account and mint tokens
     pub token_program: Program<'info, Token>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -82,6 +86,16 @@ pub struct IncreaseLiquidity<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // optional, pays the rent of a position tick released from a dynamic tick array when the position was emptied
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
 }
 
 pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
@@ -94,6 +108,7 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &mut ctx.accounts.personal_position,
         &ctx.accounts.tick_array_lower.to_account_info(),
         &ctx.accounts.tick_array_upper.to_account_info(),
@@ -105,7 +120,7 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
         None,
         None,
         None,
-        &ctx.remaining_accounts,
+        ctx.remaining_accounts,
         liquidity,
         amount_0_max,
         amount_1_max,
@@ -116,6 +131,7 @@ pub fn increase_liquidity_v1<'a, 'b, 'c: 'info, 'info>(
 pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
     nft_owner: &'b Signer<'info>,
     pool_state_loader: &'b AccountLoader<'info, PoolState>,
+    amm_config: &'b AmmConfig,
     personal_position: &'b mut Box<Account<'info, PersonalPositionState>>,
     tick_array_lower_account: &'b AccountInfo<'info>,
     tick_array_upper_account: &'b AccountInfo<'info>,
@@ -136,18 +152,41 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
 ) -> Result<()> {
     let mut liquidity = liquidity;
     let pool_state = &mut pool_state_loader.load_mut()?;
-    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+    if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        || !amm_config.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+    {
         return err!(ErrorCode::NotApproved);
     }
 
     let tick_spacing = pool_state.tick_spacing;
     let tick_lower = personal_position.tick_lower_index;
     let tick_upper = personal_position.tick_upper_index;
+    require!(!pool_state.is_inactive(), ErrorCode::PoolInactive);
+    // the empty positions opened before the tick spacing was changed by `retier_pool` can't add liquidity
+    require!(
+        tick_lower % i32::from(tick_spacing) == 0 && tick_upper % i32::from(tick_spacing) == 0,
+        ErrorCode::TickAndSpacingNotMatch
+    );
+    pool_state.check_range_width(tick_lower, tick_upper)?;
 
-    let tick_array_lower_loader =
-        TickArrayContainer::try_from(&tick_array_lower_account, tick_lower, tick_spacing)?;
-    let tick_array_upper_loader =
-        TickArrayContainer::try_from(&tick_array_upper_account, tick_upper, tick_spacing)?;
+    // the ticks of a position emptied by decrease_liquidity may have been released from a dynamic tick array
+    let tick_rent_treasury = TickRentTreasury::find_in(remaining_accounts, pool_state_loader.key());
+    let tick_array_lower_loader = TickArrayContainer::try_from_to_add_liquidity(
+        tick_array_lower_account,
+        tick_rent_treasury,
+        pool_state_loader,
+        pool_state,
+        tick_lower,
+        tick_spacing,
+    )?;
+    let tick_array_upper_loader = TickArrayContainer::try_from_to_add_liquidity(
+        tick_array_upper_account,
+        tick_rent_treasury,
+        pool_state_loader,
+        pool_state,
+        tick_upper,
+        tick_spacing,
+    )?;
 
     // check tick array pool id
     require_keys_eq!(tick_array_lower_loader.get_pool_id()?, pool_state.key());
@@ -164,9 +203,10 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         fee_growth_inside_0_x64: fee_growth_inside_0_x64_latest,
         fee_growth_inside_1_x64: fee_growth_inside_1_x64_latest,
         reward_growths_inside: reward_growths_inside_latest,
+        maker_rebate_growth_boundary_x64: maker_rebate_growth_boundary_x64_latest,
         ..
     } = add_liquidity(
-        &nft_owner,
+        nft_owner,
         token_account_0,
         token_account_1,
         token_vault_0,
@@ -180,7 +220,7 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         if use_tickarray_bitmap_extension {
             require_keys_eq!(
                 remaining_accounts[0].key(),
-                TickArrayBitmapExtension::key(pool_state_loader.key())
+                TickArrayBitmapExtension::key(pool_state_loader.key(), &crate::id())
             );
             Some(&remaining_accounts[0])
         } else {
@@ -200,15 +240,18 @@ pub fn increase_liquidity<'a, 'b, 'c: 'info, 'info>(
         fee_growth_inside_0_x64_latest,
         fee_growth_inside_1_x64_latest,
         reward_growths_inside_latest,
+        maker_rebate_growth_boundary_x64_latest,
         get_recent_epoch()?,
     )?;
+    personal_position.last_increase_slot = get_slot()?;
     emit!(IncreaseLiquidityEvent {
         position_nft_mint: personal_position.nft_mint,
         liquidity,
         amount_0,
         amount_1,
         amount_0_transfer_fee,
-        amount_1_transfer_fee
+        amount_1_transfer_fee,
+        event_seq: pool_state.next_event_seq(),
     });
 
     Ok(())
@@ -225,7 +268,6 @@ pub fn calculate_latest_token_fees(
             .mul_div_floor(U128::from(liquidity), U128::from(fixed_point_64::Q64))
             .unwrap()
             .to_underflow_u64();
-    #[cfg(feature = "enable-log")]
-    msg!("calculate_latest_token_fees fee_growth_delta:{}, fee_growth_inside_latest_x64:{}, fee_growth_inside_last_x64:{}, liquidity:{}", fee_growth_delta, fee_growth_inside_latest_x64, fee_growth_inside_last_x64, liquidity);
+    log_debug!(ix = "calculate_latest_token_fees", "fee_growth_delta:{}, fee_growth_inside_latest_x64:{}, fee_growth_inside_last_x64:{}, liquidity:{}", fee_growth_delta, fee_growth_inside_latest_x64, fee_growth_inside_last_x64, liquidity);
     last_total_fees.checked_add(fee_growth_delta).unwrap()
 }
@@ -84,6 +84,10 @@ pub struct IncreaseLiquidityV2<'info> {
             address = token_vault_1.mint
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -93,6 +97,16 @@ pub struct IncreaseLiquidityV2<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    // optional, pays the rent of a position tick released from a dynamic tick array when the position was emptied
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
 }
 
 pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
@@ -105,6 +119,7 @@ pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
     increase_liquidity(
         &ctx.accounts.nft_owner,
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &mut ctx.accounts.personal_position,
         &ctx.accounts.tick_array_lower.to_account_info(),
         &ctx.accounts.tick_array_upper.to_account_info(),
@@ -116,7 +131,7 @@ pub fn increase_liquidity_v2<'a, 'b, 'c: 'info, 'info>(
         Some(&ctx.accounts.token_program_2022),
         Some(ctx.accounts.vault_0_mint.clone()),
         Some(ctx.accounts.vault_1_mint.clone()),
-        &ctx.remaining_accounts,
+        ctx.remaining_accounts,
         liquidity,
         amount_0_max,
         amount_1_max,
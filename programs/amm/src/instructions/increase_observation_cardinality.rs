@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::realloc_account_if_needed;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct IncreaseObservationCardinality<'info> {
+    /// Pays the rent of the grown observation account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The observation account of the pool
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless, it grows the observation account to hold `extension_cardinality_next` extension observations.
+/// The growth of a single instruction is limited by the max account data increase of the runtime.
+pub fn increase_observation_cardinality(
+    ctx: Context<IncreaseObservationCardinality>,
+    extension_cardinality_next: u16,
+) -> Result<()> {
+    require_gt!(
+        extension_cardinality_next,
+        ctx.accounts
+            .observation_state
+            .load()?
+            .extension_cardinality_next,
+        ErrorCode::InvalidObservationCardinality
+    );
+    realloc_account_if_needed(
+        &ctx.accounts.observation_state.to_account_info(),
+        ObservationState::space(extension_cardinality_next),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    ctx.accounts
+        .observation_state
+        .load_mut()?
+        .extension_cardinality_next = extension_cardinality_next;
+    Ok(())
+}
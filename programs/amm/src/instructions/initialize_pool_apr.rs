@@ -0,0 +1,31 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializePoolApr<'info> {
+    /// Pays the rent of the APR account, anyone can create it
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        init,
+        seeds = [
+            POOL_APR_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PoolAprState::LEN
+    )]
+    pub pool_apr_state: AccountLoader<'info, PoolAprState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_pool_apr(ctx: Context<InitializePoolApr>) -> Result<()> {
+    let mut pool_apr_state = ctx.accounts.pool_apr_state.load_init()?;
+    pool_apr_state.initialize(ctx.bumps.pool_apr_state, ctx.accounts.pool_state.key());
+    Ok(())
+}
@@ -97,8 +97,8 @@ impl InitializeRewardParam {
             return Err(ErrorCode::InvalidRewardInitParam.into());
         }
         let time_delta = self.end_time.checked_sub(self.open_time).unwrap();
-        if time_delta < reward_period_limit::MIN_REWARD_PERIOD
-            || time_delta > reward_period_limit::MAX_REWARD_PERIOD
+        if !(reward_period_limit::MIN_REWARD_PERIOD..=reward_period_limit::MAX_REWARD_PERIOD)
+            .contains(&time_delta)
         {
             return Err(ErrorCode::InvalidRewardPeriod.into());
         }
@@ -110,16 +110,9 @@ pub fn initialize_reward(
     ctx: Context<InitializeReward>,
     param: InitializeRewardParam,
 ) -> Result<()> {
-    let mint_associated_is_initialized = util::support_mint_associated_is_initialized(
-        &ctx.remaining_accounts,
-        &ctx.accounts.reward_token_mint,
-    )?;
-    if !util::is_supported_mint(
-        &ctx.accounts.reward_token_mint,
-        mint_associated_is_initialized,
-    )
-    .unwrap()
-    {
+    let mint_associated =
+        util::get_support_mint_associated(ctx.remaining_accounts, &ctx.accounts.reward_token_mint)?;
+    if !util::is_supported_mint(&ctx.accounts.reward_token_mint, mint_associated.as_ref())? {
         return err!(ErrorCode::NotSupportMint);
     }
     let operation_state = ctx.accounts.operation_state.load()?;
@@ -131,10 +124,14 @@ pub fn initialize_reward(
     );
 
     // Clock
-    let clock = Clock::get()?;
-    #[cfg(feature = "enable-log")]
-    msg!("current block timestamp:{}", clock.unix_timestamp);
-    param.check(clock.unix_timestamp as u64)?;
+    let block_timestamp = util::get_unix_timestamp()?;
+    log_info!(
+        ix = "initialize_reward",
+        pool = ctx.accounts.pool_state.key(),
+        "current block timestamp:{}",
+        block_timestamp
+    );
+    param.check(block_timestamp)?;
 
     let reward_amount = U256::from(param.end_time - param.open_time)
         .mul_div_ceil(
@@ -0,0 +1,193 @@
+use super::decrease_liquidity::{decrease_liquidity, load_position_tick_array};
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::memo::spl_memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct LiquidatePosition<'info> {
+    /// The collateral manager of the position, instead of the position owner
+    pub collateral_manager: Signer<'info>,
+
+    /// The owner-signed terms of the position
+    #[account(
+        seeds = [
+            POSITION_COLLATERAL_SEED.as_bytes(),
+            personal_position.nft_mint.as_ref(),
+        ],
+        bump = position_collateral.bump,
+        has_one = collateral_manager,
+    )]
+    pub position_collateral: Account<'info, PositionCollateralState>,
+
+    /// The custody of the terms holding the position NFT
+    #[account(
+        address = position_collateral.custody,
+        constraint = nft_custody.amount == 1,
+    )]
+    pub nft_custody: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Decrease liquidity for this position
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Token_0 vault
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token_1 vault
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array
+    /// Stores init state for the lower tick
+    #[account(mut)]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array
+    /// Stores init state for the upper tick
+    #[account(mut)]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// The token account of the pre-declared receiver for amount_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint,
+        token::authority = position_collateral.receiver,
+    )]
+    pub recipient_token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account of the pre-declared receiver for amount_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint,
+        token::authority = position_collateral.receiver,
+    )]
+    pub recipient_token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// SPL program to transfer out tokens
+    pub token_program: Program<'info, Token>,
+    /// Token program 2022
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// memo program
+    /// CHECK:
+    #[account(
+        address = spl_memo::id()
+    )]
+    pub memo_program: UncheckedAccount<'info>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    // remaining accounts, same as `decrease_liquidity_v2`:
+    // the optional tick array bitmap extension and tick rent treasury, then the (reward vault, recipient token account, reward mint)
+    // of each initialized reward, the recipient token accounts must be owned by the receiver
+}
+
+/// Decrease the liquidity of a collateralized position and collect its fees and rewards to the receiver of the terms
+pub fn liquidate_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, LiquidatePosition<'info>>,
+    liquidity: u128,
+    amount_0_min: u64,
+    amount_1_min: u64,
+) -> Result<()> {
+    check_reward_recipients(
+        ctx.remaining_accounts,
+        ctx.accounts.pool_state.key(),
+        ctx.accounts.position_collateral.receiver,
+    )?;
+
+    let tick_spacing =
+        PoolStateView::load(&ctx.accounts.pool_state.to_account_info())?.tick_spacing();
+    let tick_lower = ctx.accounts.personal_position.tick_lower_index;
+    let tick_upper = ctx.accounts.personal_position.tick_upper_index;
+
+    let position_liquidity = ctx.accounts.personal_position.liquidity;
+    let tick_array_lower_loader = load_position_tick_array(
+        &ctx.accounts.tick_array_lower.to_account_info(),
+        tick_lower,
+        tick_spacing,
+        position_liquidity,
+    )?;
+    let tick_array_upper_loader = load_position_tick_array(
+        &ctx.accounts.tick_array_upper.to_account_info(),
+        tick_upper,
+        tick_spacing,
+        position_liquidity,
+    )?;
+
+    decrease_liquidity(
+        &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &tick_array_lower_loader,
+        &tick_array_upper_loader,
+        &ctx.accounts.recipient_token_account_0.to_account_info(),
+        &ctx.accounts.recipient_token_account_1.to_account_info(),
+        &ctx.accounts.token_program,
+        Some(ctx.accounts.token_program_2022.clone()),
+        Some(ctx.accounts.memo_program.clone()),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        None,
+        ctx.remaining_accounts,
+        liquidity,
+        amount_0_min,
+        amount_1_min,
+    )
+}
+
+/// The rewards are collected to the remaining accounts, so their recipients must be owned by the receiver too
+fn check_reward_recipients<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    pool_id: Pubkey,
+    receiver: Pubkey,
+) -> Result<()> {
+    let bitmap_extension_key = TickArrayBitmapExtension::key(pool_id, &crate::id());
+    let tick_rent_treasury_key = TickRentTreasury::key(pool_id, &crate::id());
+    let reward_accounts: Vec<&'info AccountInfo<'info>> = remaining_accounts
+        .iter()
+        .filter(|account_info| {
+            account_info.key() != bitmap_extension_key
+                && account_info.key() != tick_rent_treasury_key
+        })
+        .collect();
+    for reward_group in reward_accounts.chunks(3) {
+        if let Some(recipient_token_account) = reward_group.get(1) {
+            let recipient_token_account =
+                InterfaceAccount::<TokenAccount>::try_from(recipient_token_account)?;
+            require_keys_eq!(
+                recipient_token_account.owner,
+                receiver,
+                ErrorCode::InvalidCollateralReceiver
+            );
+        }
+    }
+    Ok(())
+}
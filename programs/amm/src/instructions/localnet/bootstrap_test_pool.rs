@@ -0,0 +1,215 @@
+use super::faucet_mint_to::*;
+use crate::instructions::create_pool_decay_fee::{initialize_pool, CreatePoolDecayFeeParams};
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct BootstrapTestPoolParams {
+    pub decimals_0: u8,
+    pub decimals_1: u8,
+    /// The initial sqrt price of the pool, in x64 format.
+    pub sqrt_price_x64: u128,
+    /// The test tokens minted to the payer
+    pub mint_amount_0: u64,
+    pub mint_amount_1: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: BootstrapTestPoolParams)]
+pub struct BootstrapTestPool<'info> {
+    /// Pays to create the test tokens and the pool, and receives the test tokens
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The mint authority of the test tokens
+    #[account(
+        seeds = [
+            FAUCET_AUTHORITY_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub faucet_authority: UncheckedAccount<'info>,
+
+    /// Which config the pool belongs to.
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// Token_0 mint, initialized with the faucet authority
+    #[account(
+        init,
+        mint::decimals = params.decimals_0,
+        mint::authority = faucet_authority,
+        mint::token_program = token_program,
+        payer = payer,
+    )]
+    pub token_mint_0: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token_1 mint, initialized with the faucet authority, the key must be greater then token_0 mint.
+    #[account(
+        init,
+        mint::decimals = params.decimals_1,
+        mint::authority = faucet_authority,
+        mint::token_program = token_program,
+        payer = payer,
+        constraint = token_mint_0.key() < token_mint_1.key(),
+    )]
+    pub token_mint_1: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The payer token_0 account receiving the test tokens
+    #[account(
+        init,
+        associated_token::mint = token_mint_0,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+        payer = payer,
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer token_1 account receiving the test tokens
+    #[account(
+        init,
+        associated_token::mint = token_mint_1,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+        payer = payer,
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Initialize an account to store the pool state
+    #[account(
+        init,
+        seeds = [
+            POOL_SEED.as_bytes(),
+            amm_config.key().as_ref(),
+            token_mint_0.key().as_ref(),
+            token_mint_1.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = PoolState::LEN
+    )]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Initialize an account to store the off-chain reward config
+    #[account(
+        init,
+        seeds = [
+            OFFCHAIN_REWARD_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = OffchainRewardConfig::need_len(0)
+    )]
+    pub offchain_reward_config: Box<Account<'info, OffchainRewardConfig>>,
+
+    /// CHECK: Token_0 vault for the pool, initialized in contract
+    #[account(
+        mut,
+        seeds =[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            token_mint_0.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_vault_0: UncheckedAccount<'info>,
+
+    /// CHECK: Token_1 vault for the pool, initialized in contract
+    #[account(
+        mut,
+        seeds =[
+            POOL_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            token_mint_1.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub token_vault_1: UncheckedAccount<'info>,
+
+    /// Initialize an account to store oracle observations
+    #[account(
+        init,
+        seeds = [
+            OBSERVATION_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ObservationState::LEN
+    )]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// Initialize an account to store if a tick array is initialized.
+    #[account(
+        init,
+        seeds = [
+            POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = TickArrayBitmapExtension::LEN
+    )]
+    pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+
+    /// Spl token program or token program 2022
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Program to create the payer token accounts
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// To create a new program account
+    pub system_program: Program<'info, System>,
+    /// Sysvar for program account
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Create two test tokens, mint them to the payer and create their pool, without the pool manager.
+/// Positions are seeded by `seed_test_position` in the same transaction.
+pub fn bootstrap_test_pool(
+    ctx: Context<BootstrapTestPool>,
+    params: BootstrapTestPoolParams,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    mint_test_tokens(
+        &accounts.faucet_authority,
+        ctx.bumps.faucet_authority,
+        &accounts.token_mint_0.to_account_info(),
+        &accounts.token_account_0.to_account_info(),
+        &accounts.token_program.to_account_info(),
+        params.mint_amount_0,
+    )?;
+    mint_test_tokens(
+        &accounts.faucet_authority,
+        ctx.bumps.faucet_authority,
+        &accounts.token_mint_1.to_account_info(),
+        &accounts.token_account_1.to_account_info(),
+        &accounts.token_program.to_account_info(),
+        params.mint_amount_1,
+    )?;
+
+    initialize_pool(
+        &accounts.payer,
+        &accounts.amm_config,
+        &accounts.pool_state,
+        ctx.bumps.pool_state,
+        &mut accounts.offchain_reward_config,
+        ctx.bumps.offchain_reward_config,
+        &accounts.token_mint_0,
+        &accounts.token_mint_1,
+        &accounts.token_vault_0,
+        ctx.bumps.token_vault_0,
+        &accounts.token_vault_1,
+        ctx.bumps.token_vault_1,
+        &accounts.observation_state,
+        &accounts.tick_array_bitmap,
+        &accounts.token_program,
+        &accounts.token_program,
+        &accounts.system_program,
+        CreatePoolDecayFeeParams {
+            sqrt_price_x64: params.sqrt_price_x64,
+            ..Default::default()
+        },
+        None,
+    )
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface};
+
+/// Seed of the mint authority of the test tokens
+pub const FAUCET_AUTHORITY_SEED: &str = "faucet_authority";
+
+#[derive(Accounts)]
+pub struct FaucetMintTo<'info> {
+    /// CHECK: The mint authority of the test tokens
+    #[account(
+        seeds = [
+            FAUCET_AUTHORITY_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub faucet_authority: UncheckedAccount<'info>,
+
+    /// The test token mint, created by `bootstrap_test_pool`
+    #[account(
+        mut,
+        mint::authority = faucet_authority,
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account receiving the test tokens
+    #[account(
+        mut,
+        token::mint = token_mint,
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn faucet_mint_to(ctx: Context<FaucetMintTo>, amount: u64) -> Result<()> {
+    mint_test_tokens(
+        &ctx.accounts.faucet_authority,
+        ctx.bumps.faucet_authority,
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.token_account.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        amount,
+    )
+}
+
+/// Mint `amount` test tokens signed by the faucet authority
+pub(crate) fn mint_test_tokens<'info>(
+    faucet_authority: &AccountInfo<'info>,
+    faucet_authority_bump: u8,
+    token_mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                mint: token_mint.clone(),
+                to: token_account.clone(),
+                authority: faucet_authority.clone(),
+            },
+            &[&[FAUCET_AUTHORITY_SEED.as_bytes(), &[faucet_authority_bump]]],
+        ),
+        amount,
+    )
+}
@@ -0,0 +1,8 @@
+pub mod faucet_mint_to;
+pub use faucet_mint_to::*;
+
+pub mod bootstrap_test_pool;
+pub use bootstrap_test_pool::*;
+
+pub mod seed_test_position;
+pub use seed_test_position::*;
@@ -0,0 +1,301 @@
+use super::faucet_mint_to::*;
+use crate::instructions::open_position::open_position;
+use crate::libraries::liquidity_math;
+use crate::states::*;
+use crate::util::create_position_nft_mint_with_extensions;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The preset ranges of the test positions, around the current tick of the pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TestPositionPreset {
+    FullRange,
+    /// 100 tick spacings on each side of the current tick
+    Wide,
+    /// 10 tick spacings on each side of the current tick
+    Narrow,
+}
+
+impl TestPositionPreset {
+    /// The tick range of the preset, containing the current tick and within the usable ticks
+    pub fn tick_range(self, tick_current: i32, tick_spacing: u16) -> (i32, i32) {
+        let min_tick = TickUtils::min_usable_tick(tick_spacing);
+        let max_tick = TickUtils::max_usable_tick(tick_spacing);
+        let spacings = match self {
+            TestPositionPreset::FullRange => return (min_tick, max_tick),
+            TestPositionPreset::Wide => 100,
+            TestPositionPreset::Narrow => 10,
+        };
+        let tick_spacing = i32::from(tick_spacing);
+        let tick_aligned = tick_current.div_euclid(tick_spacing) * tick_spacing;
+        (
+            (tick_aligned - spacings * tick_spacing).max(min_tick),
+            (tick_aligned + spacings * tick_spacing).min(max_tick),
+        )
+    }
+
+    /// The start indexes of the tick arrays of the tick range
+    pub fn tick_array_start_indexes(self, pool_state: &PoolState) -> (i32, i32) {
+        let tick_spacing = pool_state.tick_spacing;
+        let (tick_lower, tick_upper) = self.tick_range(pool_state.tick_current, tick_spacing);
+        (
+            TickUtils::get_array_start_index(tick_lower, tick_spacing),
+            TickUtils::get_array_start_index(tick_upper, tick_spacing),
+        )
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(preset: TestPositionPreset)]
+pub struct SeedTestPosition<'info> {
+    /// Pays to mint the position
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The mint authority of the test tokens
+    #[account(
+        seeds = [
+            FAUCET_AUTHORITY_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub faucet_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address, initialize in contract
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: ATA address where position NFT will be minted, initialize in contract
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// Add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: Account to store data for the position's lower tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &preset.tick_array_start_indexes(&*pool_state.load()?).0.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &preset.tick_array_start_indexes(&*pool_state.load()?).1.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// personal position state
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The payer token_0 account, receives the test tokens deposited to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The payer token_1 account, receives the test tokens deposited to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to mint the test tokens and transfer for token account
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT mint/token account and transfer for token22 account
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0, a test token
+    #[account(
+        mut,
+        address = token_vault_0.mint,
+        mint::authority = faucet_authority,
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The mint of token vault 1, a test token
+    #[account(
+        mut,
+        address = token_vault_1.mint,
+        mint::authority = faucet_authority,
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+}
+
+/// Open a position at a preset range of a test pool, the tokens deposited are minted by the faucet
+pub fn seed_test_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SeedTestPosition<'info>>,
+    preset: TestPositionPreset,
+    liquidity: u128,
+) -> Result<()> {
+    let (tick_lower_index, tick_upper_index, tick_array_start_indexes, amount_0, amount_1) = {
+        let pool_state = ctx.accounts.pool_state.load()?;
+        let (tick_lower_index, tick_upper_index) =
+            preset.tick_range(pool_state.tick_current, pool_state.tick_spacing);
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+            pool_state.tick_current,
+            pool_state.sqrt_price_x64,
+            tick_lower_index,
+            tick_upper_index,
+            i128::try_from(liquidity).unwrap(),
+        )?;
+        (
+            tick_lower_index,
+            tick_upper_index,
+            preset.tick_array_start_indexes(&pool_state),
+            amount_0,
+            amount_1,
+        )
+    };
+
+    mint_test_tokens(
+        &ctx.accounts.faucet_authority,
+        ctx.bumps.faucet_authority,
+        &ctx.accounts.vault_0_mint.to_account_info(),
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        amount_0,
+    )?;
+    mint_test_tokens(
+        &ctx.accounts.faucet_authority,
+        ctx.bumps.faucet_authority,
+        &ctx.accounts.vault_1_mint.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        amount_1,
+    )?;
+
+    create_position_nft_mint_with_extensions(
+        &ctx.accounts.payer,
+        &ctx.accounts.position_nft_mint,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.personal_position.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_2022,
+    )?;
+
+    // create user position nft account
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.position_nft_account.to_account_info(),
+            authority: ctx.accounts.position_nft_owner.to_account_info(),
+            mint: ctx.accounts.position_nft_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+
+    open_position(
+        &ctx.accounts.payer,
+        &ctx.accounts.position_nft_owner,
+        &ctx.accounts.position_nft_mint,
+        &ctx.accounts.position_nft_account,
+        None,
+        &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.rent,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+        None,
+        Some(&ctx.accounts.token_program_2022),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        ctx.remaining_accounts,
+        ctx.bumps.personal_position,
+        liquidity,
+        amount_0,
+        amount_1,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_start_indexes.0,
+        tick_array_start_indexes.1,
+        false,
+        None,
+        true,
+    )
+}
+
+#[cfg(test)]
+mod seed_test_position_test {
+    use super::*;
+
+    #[test]
+    fn tick_range_test() {
+        assert_eq!(
+            TestPositionPreset::FullRange.tick_range(100, 60),
+            (-443580, 443580)
+        );
+        assert_eq!(TestPositionPreset::Narrow.tick_range(100, 60), (-540, 660));
+        assert_eq!(TestPositionPreset::Narrow.tick_range(-100, 60), (-720, 480));
+        assert_eq!(TestPositionPreset::Wide.tick_range(-100, 60), (-6120, 5880));
+        // clamped at the usable ticks
+        assert_eq!(
+            TestPositionPreset::Narrow.tick_range(443500, 60),
+            (442860, 443580)
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use super::collect_fees_and_rewards::accrue_position;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{burn, close_spl_account};
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    /// The owner of both positions, receives the rent of the closed accounts
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the position kept
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position kept, receives the liquidity of `merged_position`
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Mint address bound to the merged position, closed if it is a token-2022 mint
+    #[account(
+        mut,
+        address = merged_position.nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub merged_position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account for the merged position, burnt and closed
+    #[account(
+        mut,
+        token::mint = merged_position_nft_mint,
+        token::authority = nft_owner,
+        constraint = merged_nft_account.amount == 1,
+        token::token_program = token_program,
+    )]
+    pub merged_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position merged into `personal_position`, closed
+    #[account(
+        mut,
+        seeds = [POSITION_SEED.as_bytes(), merged_position_nft_mint.key().as_ref()],
+        bump,
+        close = nft_owner,
+        constraint = merged_position.key() != personal_position.key(),
+        constraint = merged_position.tick_lower_index == personal_position.tick_lower_index
+            && merged_position.tick_upper_index == personal_position.tick_upper_index
+            @ ErrorCode::InvalidTickIndex,
+    )]
+    pub merged_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// System program to close the position state account
+    pub system_program: Program<'info, System>,
+
+    /// Token/Token2022 program of the merged position NFT, to close its token account and mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accrue both positions from the tick arrays of the pool, then move the liquidity and the fees
+/// and rewards owed of `merged_position` to `personal_position`, a position of the same range.
+pub fn accrue_and_merge<'info>(
+    pool_id: &Pubkey,
+    pool_state: &mut PoolState,
+    personal_position: &mut PersonalPositionState,
+    merged_position: &mut PersonalPositionState,
+    tick_array_lower: &AccountInfo<'info>,
+    tick_array_upper: &AccountInfo<'info>,
+) -> Result<()> {
+    accrue_position(
+        pool_id,
+        pool_state,
+        personal_position,
+        tick_array_lower,
+        tick_array_upper,
+    )?;
+    accrue_position(
+        pool_id,
+        pool_state,
+        merged_position,
+        tick_array_lower,
+        tick_array_upper,
+    )?;
+    personal_position.merge_from(merged_position)
+}
+
+/// The ticks and the pool liquidity are unchanged: both positions accrue their fees and rewards up to now,
+/// then the liquidity and the fees and rewards owed of `merged_position` are added to `personal_position`
+/// and the merged position NFT and accounts are closed, the reverse of `split_position`.
+pub fn merge_positions<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, MergePositions<'info>>,
+) -> Result<()> {
+    {
+        let pool_id = ctx.accounts.pool_state.key();
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let tick_array_lower = ctx.accounts.tick_array_lower.to_account_info();
+        let tick_array_upper = ctx.accounts.tick_array_upper.to_account_info();
+        let personal_position = &mut ctx.accounts.personal_position;
+        let merged_position = &mut ctx.accounts.merged_position;
+        let liquidity = merged_position.liquidity;
+        accrue_and_merge(
+            &pool_id,
+            &mut pool_state,
+            personal_position,
+            merged_position,
+            &tick_array_lower,
+            &tick_array_upper,
+        )?;
+
+        emit!(PositionsMergedEvent {
+            pool_state: ctx.accounts.pool_state.key(),
+            position_nft_mint: personal_position.nft_mint,
+            merged_position_nft_mint: merged_position.nft_mint,
+            liquidity,
+            event_seq: pool_state.next_event_seq(),
+        });
+    }
+
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let merged_position_nft_mint = ctx.accounts.merged_position_nft_mint.to_account_info();
+    let merged_nft_account = ctx.accounts.merged_nft_account.to_account_info();
+    burn(
+        &ctx.accounts.nft_owner,
+        &merged_position_nft_mint,
+        &merged_nft_account,
+        &token_program,
+        &[],
+        1,
+    )?;
+    close_spl_account(
+        &ctx.accounts.nft_owner,
+        &ctx.accounts.nft_owner,
+        &merged_nft_account,
+        &token_program,
+        &[],
+    )?;
+    if *merged_position_nft_mint.owner == spl_token_2022::id() {
+        close_spl_account(
+            &ctx.accounts.merged_position.to_account_info(),
+            &ctx.accounts.nft_owner,
+            &merged_position_nft_mint,
+            &token_program,
+            &[&ctx.accounts.merged_position.seeds()],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod merge_positions_test {
+    use super::*;
+    use crate::instructions::collect_fees_and_rewards::collect_fees_and_rewards_test::*;
+    use crate::libraries::mock_anchor_account_info_v3;
+
+    #[test]
+    fn accrue_and_merge_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        let mut personal_position = build_position(pool_id, 2000);
+        let mut merged_position = build_position(pool_id, 1000);
+
+        let (key, tick_array) = build_position_tick_array(pool_id, 0);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        accrue_and_merge(
+            &pool_id,
+            &mut pool_state.borrow_mut(),
+            &mut personal_position,
+            &mut merged_position,
+            &account_info,
+            &account_info,
+        )
+        .unwrap();
+        assert_eq!(personal_position.liquidity, 3000);
+        assert_eq!(personal_position.token_fees_owed_0, 15000);
+        assert_eq!(merged_position.liquidity, 0);
+        assert_eq!(merged_position.token_fees_owed_0, 0);
+    }
+
+    #[test]
+    fn accrue_and_merge_foreign_tick_array_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        let mut personal_position = build_position(pool_id, 2000);
+        let mut merged_position = build_position(pool_id, 1000);
+
+        // the tick array of another pool with an inflated fee growth outside of the upper tick
+        let other_pool_id = Pubkey::new_unique();
+        let (key, tick_array) = build_position_tick_array(other_pool_id, u128::MAX / 2);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        assert_eq!(
+            accrue_and_merge(
+                &pool_id,
+                &mut pool_state.borrow_mut(),
+                &mut personal_position,
+                &mut merged_position,
+                &account_info,
+                &account_info,
+            )
+            .err(),
+            Some(anchor_lang::error::ErrorCode::RequireKeysEqViolated.into())
+        );
+        assert_eq!(personal_position.liquidity, 2000);
+        assert_eq!(personal_position.token_fees_owed_0, 0);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_rent, AccountLoad};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct MigrateTickArrayToDynamic<'info> {
+    /// amm admin group account, its fee keeper receives the refunded rent
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: fixed tick array of the pool, checked in the function body
+    #[account(mut)]
+    pub tick_array: UncheckedAccount<'info>,
+
+    /// CHECK: receives the rent refunded by the shrink of the tick array account
+    #[account(
+        mut,
+        address = admin_group.fee_keeper @ ErrorCode::NotApproved
+    )]
+    pub rent_receiver: UncheckedAccount<'info>,
+}
+
+/// Permissionless, rewrite a fixed tick array in place as a dynamic tick array with only its initialized ticks
+pub fn migrate_tick_array_to_dynamic(ctx: Context<MigrateTickArrayToDynamic>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+
+    let (header, ticks) = {
+        let tick_array_loader = AccountLoad::<TickArrayState>::try_from(&tick_array_info)?;
+        let tick_array = tick_array_loader.load()?;
+        require_keys_eq!(
+            tick_array.pool_id,
+            ctx.accounts.pool_state.key(),
+            ErrorCode::InvalidTickArray
+        );
+        DynTickArrayState::from_fixed(&tick_array)
+    };
+    // the dynamic header is larger than the fixed one, a tick array with nearly all ticks initialized can't shrink
+    let new_account_space = header.all_data_len();
+    require_gt!(
+        tick_array_info.data_len(),
+        new_account_space,
+        ErrorCode::InvalidTickArray
+    );
+
+    {
+        let mut data = tick_array_info.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(DynTickArrayState::DISCRIMINATOR);
+        data[8..DynTickArrayState::HEADER_LEN].copy_from_slice(bytemuck::bytes_of(&header));
+        data[DynTickArrayState::HEADER_LEN..new_account_space]
+            .copy_from_slice(bytemuck::cast_slice(ticks.as_slice()));
+        data[new_account_space..].fill(0);
+    }
+    tick_array_info.resize(new_account_space)?;
+
+    let refunded_lamports = tick_array_info
+        .lamports()
+        .saturating_sub(get_rent()?.minimum_balance(new_account_space));
+    **tick_array_info.try_borrow_mut_lamports()? -= refunded_lamports;
+    **ctx.accounts.rent_receiver.try_borrow_mut_lamports()? += refunded_lamports;
+
+    emit!(TickArrayMigratedEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        start_tick_index: header.start_tick_index,
+        alloc_tick_count: header.alloc_tick_count,
+        data_len: new_account_space as u32,
+        refunded_lamports,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
@@ -4,6 +4,38 @@ pub use create_pool::*;
 pub mod create_pool_decay_fee;
 pub use create_pool_decay_fee::*;
 
+pub mod create_pool_from_template;
+pub use create_pool_from_template::*;
+
+pub mod fund_tick_rent_treasury;
+pub use fund_tick_rent_treasury::*;
+
+pub mod withdraw_tick_rent_treasury;
+pub use withdraw_tick_rent_treasury::*;
+
+pub mod reserve_ticks;
+pub use reserve_ticks::*;
+
+pub mod get_or_create_tick_array;
+pub use get_or_create_tick_array::*;
+
+pub mod audit_tick_array;
+pub use audit_tick_array::*;
+
+pub mod close_tick_array;
+pub use close_tick_array::*;
+
+pub mod migrate_tick_array_to_dynamic;
+pub use migrate_tick_array_to_dynamic::*;
+
+pub mod sync_pool_range_limits;
+pub use sync_pool_range_limits::*;
+
+pub mod clear_decay_fee;
+pub use clear_decay_fee::*;
+pub mod increase_observation_cardinality;
+pub use increase_observation_cardinality::*;
+
 pub mod open_position;
 pub use open_position::*;
 
@@ -12,6 +44,11 @@ pub use open_position_v2::*;
 
 pub mod open_position_with_token22_nft;
 pub use open_position_with_token22_nft::*;
+pub mod open_position_with_exact_amounts;
+pub use open_position_with_exact_amounts::*;
+
+pub mod open_full_range_position;
+pub use open_full_range_position::*;
 
 pub mod close_position;
 pub use close_position::*;
@@ -28,15 +65,95 @@ pub use decrease_liquidity::*;
 pub mod decrease_liquidity_v2;
 pub use decrease_liquidity_v2::*;
 
+pub mod move_liquidity;
+pub use move_liquidity::*;
+
+pub mod begin_deploy;
+pub use begin_deploy::*;
+
+pub mod continue_deploy;
+pub use continue_deploy::*;
+
+pub mod finish_deploy;
+pub use finish_deploy::*;
+
 pub mod swap;
 pub use swap::*;
 
 pub mod swap_v2;
 pub use swap_v2::*;
 
+pub mod swap_v2_compact;
+pub use swap_v2_compact::*;
+
+pub mod swap_if_price;
+pub use swap_if_price::*;
+pub mod swap_v2_max_iterations;
+pub use swap_v2_max_iterations::*;
+
+pub mod swap_v2_delegated;
+pub use swap_v2_delegated::*;
+
+pub mod swap_v2_stp;
+pub use swap_v2_stp::*;
+
+pub mod swap_batch;
+pub use swap_batch::*;
+
+pub mod assert_pool_state;
+pub use assert_pool_state::*;
+
+pub mod value_position;
+pub use value_position::*;
+
+pub mod reconcile_vaults;
+pub use reconcile_vaults::*;
+
+pub mod set_position_collateral;
+pub use set_position_collateral::*;
+
+pub mod release_position_collateral;
+pub use release_position_collateral::*;
+
+pub mod liquidate_position;
+pub use liquidate_position::*;
+
+pub mod claim_rewards_as;
+pub use claim_rewards_as::*;
+
+pub mod collect_fees_and_rewards;
+pub use collect_fees_and_rewards::*;
+
+pub mod transfer_liquidity;
+pub use transfer_liquidity::*;
+
+pub mod split_position;
+pub use split_position::*;
+
+pub mod merge_positions;
+pub use merge_positions::*;
+
+pub mod initialize_pool_apr;
+pub use initialize_pool_apr::*;
+
+pub mod update_pool_apr;
+pub use update_pool_apr::*;
+
+pub mod fund_crank_bounty;
+pub use fund_crank_bounty::*;
+
+pub mod rebalance_bounty;
+pub use rebalance_bounty::*;
+
+pub mod attach_metadata;
+pub use attach_metadata::*;
+
 pub mod swap_router_base_in;
 pub use swap_router_base_in::*;
 
+pub mod swap_router_base_in_v2;
+pub use swap_router_base_in_v2::*;
+
 pub mod update_reward_info;
 pub use update_reward_info::*;
 
@@ -51,3 +168,13 @@ pub use collect_remaining_rewards::*;
 
 pub mod admin;
 pub use admin::*;
+
+#[cfg(feature = "tick-array-compression")]
+pub mod tick_array_compression;
+#[cfg(feature = "tick-array-compression")]
+pub use tick_array_compression::*;
+
+#[cfg(feature = "localnet")]
+pub mod localnet;
+#[cfg(feature = "localnet")]
+pub use localnet::*;
@@ -0,0 +1,328 @@
+use super::decrease_liquidity::decrease_liquidity;
+use super::open_position::open_position;
+use crate::error::ErrorCode;
+use crate::instructions::*;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use crate::util;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MoveLiquidity<'info> {
+    /// The position to move the liquidity out, all of its liquidity, fees and rewards are withdrawn.
+    /// It is left empty, to be closed by its owner with `close_position`.
+    pub source: DecreaseLiquidityV2<'info>,
+
+    /// The position opened to move the liquidity in, in another pool of the same token pair,
+    /// the withdrawn tokens are deposited through `token_account_0` and `token_account_1`.
+    /// Its tick array addresses are derived from the first four arguments, as in `open_position_v2`.
+    pub target: OpenPositionV2<'info>,
+
+    /// Rebalance the token ratio in the target pool before depositing, skipped if `swap_amount` is zero
+    pub swap: SwapSingleV2<'info>,
+    // remaining accounts
+    // source_remaining_accounts: the remaining accounts of decrease_liquidity_v2 for source pool, length is `source_remaining_len`
+    // target_remaining_accounts: the remaining accounts of open_position_v2 for target pool, length is `target_remaining_len`
+    // swap_remaining_accounts: the remaining accounts of swap_v2 in target pool
+}
+
+/// The liquidity of the moved position in `[tick_lower_index, tick_upper_index)` of a pool at `sqrt_price_x64`,
+/// for the tokens withdrawn from the source position, its liquidity and owed fees, net of the deposit transfer fees
+pub fn get_moved_liquidity(
+    sqrt_price_x64: u128,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    amount_0: u64,
+    amount_1: u64,
+) -> Result<u128> {
+    Ok(liquidity_math::get_liquidity_from_amounts_raw(
+        sqrt_price_x64,
+        tick_math::get_sqrt_price_at_tick_raw(tick_lower_index)?,
+        tick_math::get_sqrt_price_at_tick_raw(tick_upper_index)?,
+        amount_0,
+        amount_1,
+    ))
+}
+
+/// Withdraw all the liquidity and owed fees of the source position, optionally rebalance them with a swap
+/// in the target pool, then open the target position with them. The owed rewards of the source position are
+/// paid to its owner, the reward tokens of a pool can't be deposited in another.
+pub fn move_liquidity<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, MoveLiquidity<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    amount_0_min: u64,
+    amount_1_min: u64,
+    swap_amount: u64,
+    swap_other_amount_threshold: u64,
+    swap_sqrt_price_limit_x64: u128,
+    liquidity_min: u128,
+    with_metadata: bool,
+    source_remaining_len: u8,
+    target_remaining_len: u8,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+    let source_pool_id = accounts.source.pool_state.key();
+    let target_pool_id = accounts.target.pool_state.key();
+    {
+        let source_pool_info = accounts.source.pool_state.to_account_info();
+        let target_pool_info = accounts.target.pool_state.to_account_info();
+        let source_pool = PoolStateView::load(&source_pool_info)?;
+        let target_pool = PoolStateView::load(&target_pool_info)?;
+        require!(
+            source_pool_id != target_pool_id
+                && source_pool.token_mint_0() == target_pool.token_mint_0()
+                && source_pool.token_mint_1() == target_pool.token_mint_1(),
+            ErrorCode::InvalidMoveLiquidityPools
+        );
+    }
+    require_keys_eq!(accounts.swap.pool_state.key(), target_pool_id);
+    accounts.source.personal_position.check_not_collateralized()?;
+    // all tokens must flow through the same owner accounts
+    require_keys_eq!(accounts.source.nft_owner.key(), accounts.target.payer.key());
+    require_keys_eq!(accounts.source.nft_owner.key(), accounts.swap.payer.key());
+    require_keys_eq!(
+        accounts.source.recipient_token_account_0.key(),
+        accounts.target.token_account_0.key()
+    );
+    require_keys_eq!(
+        accounts.source.recipient_token_account_1.key(),
+        accounts.target.token_account_1.key()
+    );
+    let (swap_input, swap_output) = (
+        accounts.swap.input_token_account.key(),
+        accounts.swap.output_token_account.key(),
+    );
+    let (token_account_0, token_account_1) = (
+        accounts.target.token_account_0.key(),
+        accounts.target.token_account_1.key(),
+    );
+    require!(
+        (swap_input == token_account_0 && swap_output == token_account_1)
+            || (swap_input == token_account_1 && swap_output == token_account_0),
+        ErrorCode::InvalidAccount
+    );
+
+    let source_remaining_len = usize::from(source_remaining_len);
+    let target_remaining_len = usize::from(target_remaining_len);
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        source_remaining_len + target_remaining_len
+    );
+    let (source_remaining_accounts, remaining_accounts) =
+        ctx.remaining_accounts.split_at(source_remaining_len);
+    let (target_remaining_accounts, swap_remaining_accounts) =
+        remaining_accounts.split_at(target_remaining_len);
+
+    let balance_0_before = accounts.target.token_account_0.amount;
+    let balance_1_before = accounts.target.token_account_1.amount;
+
+    // withdraw all liquidity of source position
+    let source = &mut accounts.source;
+    let tick_spacing = PoolStateView::load(&source.pool_state.to_account_info())?.tick_spacing();
+    let tick_array_lower_loader = TickArrayContainer::try_from(
+        &source.tick_array_lower.to_account_info(),
+        source.personal_position.tick_lower_index,
+        tick_spacing,
+    )?;
+    let tick_array_upper_loader = TickArrayContainer::try_from(
+        &source.tick_array_upper.to_account_info(),
+        source.personal_position.tick_upper_index,
+        tick_spacing,
+    )?;
+    let source_liquidity = source.personal_position.liquidity;
+    require_gt!(source_liquidity, 0);
+    decrease_liquidity(
+        &source.pool_state,
+        &source.amm_config,
+        &mut source.personal_position,
+        &source.token_vault_0.to_account_info(),
+        &source.token_vault_1.to_account_info(),
+        &tick_array_lower_loader,
+        &tick_array_upper_loader,
+        &source.recipient_token_account_0.to_account_info(),
+        &source.recipient_token_account_1.to_account_info(),
+        &source.token_program,
+        Some(source.token_program_2022.clone()),
+        Some(source.memo_program.clone()),
+        Some(source.vault_0_mint.clone()),
+        Some(source.vault_1_mint.clone()),
+        Some(&source.nft_owner.to_account_info()),
+        source_remaining_accounts,
+        source_liquidity,
+        amount_0_min,
+        amount_1_min,
+    )?;
+
+    // rebalance the token ratio in target pool
+    if swap_amount > 0 {
+        // the user token accounts are changed by the withdrawal above
+        accounts.swap.input_token_account.reload()?;
+        accounts.swap.output_token_account.reload()?;
+        let amount_out = exact_internal_v2(
+            &mut accounts.swap,
+            swap_remaining_accounts,
+            swap_amount,
+            swap_sqrt_price_limit_x64,
+            true,
+        )?
+        .amount_out;
+        require_gte!(
+            amount_out,
+            swap_other_amount_threshold,
+            ErrorCode::TooLittleOutputReceived
+        );
+    }
+
+    // only the tokens withdrawn from source position can be deposited
+    let target = &mut accounts.target;
+    target.token_account_0.reload()?;
+    target.token_account_1.reload()?;
+    let amount_0_max = target
+        .token_account_0
+        .amount
+        .saturating_sub(balance_0_before);
+    let amount_1_max = target
+        .token_account_1
+        .amount
+        .saturating_sub(balance_1_before);
+
+    let liquidity = {
+        let sqrt_price_x64 = target.pool_state.load()?.sqrt_price_x64;
+        let transfer_fee_0 = util::get_transfer_fee(target.vault_0_mint.clone(), amount_0_max)?;
+        let transfer_fee_1 = util::get_transfer_fee(target.vault_1_mint.clone(), amount_1_max)?;
+        get_moved_liquidity(
+            sqrt_price_x64,
+            tick_lower_index,
+            tick_upper_index,
+            amount_0_max
+                .checked_sub(transfer_fee_0)
+                .ok_or(ErrorCode::TransferFeeCalculateNotMatch)?,
+            amount_1_max
+                .checked_sub(transfer_fee_1)
+                .ok_or(ErrorCode::TransferFeeCalculateNotMatch)?,
+        )?
+    };
+    log_info!(
+        ix = "move_liquidity",
+        pool = target_pool_id,
+        "source_liquidity:{}, amount_0_max:{}, amount_1_max:{}, target_liquidity:{}",
+        source_liquidity,
+        amount_0_max,
+        amount_1_max,
+        liquidity
+    );
+    require_gt!(liquidity, 0);
+    require_gte!(liquidity, liquidity_min, ErrorCode::PriceSlippageCheck);
+
+    open_position(
+        &target.payer,
+        &target.position_nft_owner,
+        &target.position_nft_mint.to_account_info(),
+        &target.position_nft_account.to_account_info(),
+        Some(&target.metadata_account),
+        &target.pool_state,
+        &target.amm_config,
+        &target.tick_array_lower,
+        &target.tick_array_upper,
+        &mut target.personal_position,
+        &target.token_account_0.to_account_info(),
+        &target.token_account_1.to_account_info(),
+        &target.token_vault_0.to_account_info(),
+        &target.token_vault_1.to_account_info(),
+        &target.rent,
+        &target.system_program,
+        &target.token_program,
+        &target.associated_token_program,
+        Some(&target.metadata_program),
+        Some(&target.token_program_2022),
+        Some(target.vault_0_mint.clone()),
+        Some(target.vault_1_mint.clone()),
+        target_remaining_accounts,
+        ctx.bumps.target.personal_position,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+        with_metadata,
+        None,
+        false,
+    )
+}
+
+#[cfg(test)]
+mod move_liquidity_test {
+    use super::*;
+    use crate::instructions::collect_fees_and_rewards::accrue_position;
+    use crate::instructions::collect_fees_and_rewards::collect_fees_and_rewards_test::*;
+    use crate::libraries::mock_anchor_account_info_v3;
+
+    #[test]
+    fn get_moved_liquidity_with_fees_and_rewards_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        pool_state.borrow_mut().fee_growth_global_1_x64 = 5 << 64;
+        pool_state.borrow_mut().reward_infos[0].token_mint = Pubkey::new_unique();
+        pool_state.borrow_mut().reward_infos[0].reward_growth_global_x64 = 2 << 64;
+        let liquidity = 1_000_000;
+        let mut personal_position = build_position(pool_id, liquidity);
+
+        // the source position accrues its fees and rewards when its liquidity is withdrawn
+        let (key, tick_array) = build_position_tick_array(pool_id, 0);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        accrue_position(
+            &pool_id,
+            &mut pool_state.borrow_mut(),
+            &mut personal_position,
+            &account_info,
+            &account_info,
+        )
+        .unwrap();
+        assert_eq!(personal_position.token_fees_owed_0, 5_000_000);
+        assert_eq!(personal_position.token_fees_owed_1, 5_000_000);
+        // the owed rewards are paid to the owner from the reward vaults of the source pool, they aren't moved
+        assert_eq!(
+            personal_position.reward_infos[0].reward_amount_owed,
+            2_000_000
+        );
+
+        // the same range in a target pool at the same price
+        let tick_current = pool_state.borrow().tick_current;
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap();
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+            tick_current,
+            sqrt_price_x64,
+            TICK_LOWER_INDEX,
+            TICK_UPPER_INDEX,
+            -i128::try_from(liquidity).unwrap(),
+        )
+        .unwrap();
+        let principal_liquidity = get_moved_liquidity(
+            sqrt_price_x64,
+            TICK_LOWER_INDEX,
+            TICK_UPPER_INDEX,
+            amount_0,
+            amount_1,
+        )
+        .unwrap();
+        assert!(principal_liquidity <= liquidity);
+
+        // the owed fees are withdrawn with the liquidity and carried into the moved position
+        let moved_liquidity = get_moved_liquidity(
+            sqrt_price_x64,
+            TICK_LOWER_INDEX,
+            TICK_UPPER_INDEX,
+            amount_0 + personal_position.token_fees_owed_0,
+            amount_1 + personal_position.token_fees_owed_1,
+        )
+        .unwrap();
+        assert!(moved_liquidity > liquidity);
+    }
+}
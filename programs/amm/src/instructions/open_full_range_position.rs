@@ -0,0 +1,238 @@
+use super::open_position::open_position;
+use crate::states::*;
+use crate::util::create_position_nft_mint_with_extensions;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct OpenFullRangePosition<'info> {
+    /// Pays to mint the position
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Receives the position NFT
+    pub position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address, initialize in contract
+    #[account(mut)]
+    pub position_nft_mint: Signer<'info>,
+
+    /// CHECK: ATA address where position NFT will be minted, initialize in contract
+    #[account(mut)]
+    pub position_nft_account: UncheckedAccount<'info>,
+
+    /// Add liquidity for this pool
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: Deprecated: protocol_position is deprecated and kept for compatibility.
+    pub protocol_position: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's lower tick, the tick array of the min usable tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &full_range_tick_array_start_indexes(pool_state.load()?.tick_spacing).0.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Account to store data for the position's upper tick, the tick array of the max usable tick
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &full_range_tick_array_start_indexes(pool_state.load()?.tick_spacing).1.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// personal position state
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token_0 account deposit token to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token_1 account deposit token to the pool
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program to transfer for token account
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create NFT mint/token account and transfer for token22 account
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+    // remaining account
+    // #[account(
+    //     seeds = [
+    //         POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    //
+    // optional, pays the tick array rent instead of payer, must be after tick_array_bitmap if both are provided
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
+}
+
+/// The start indexes of the tick arrays of the min and max usable ticks
+pub fn full_range_tick_array_start_indexes(tick_spacing: u16) -> (i32, i32) {
+    (
+        TickUtils::get_array_start_index(TickUtils::min_usable_tick(tick_spacing), tick_spacing),
+        TickUtils::get_array_start_index(TickUtils::max_usable_tick(tick_spacing), tick_spacing),
+    )
+}
+
+pub fn open_full_range_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenFullRangePosition<'info>>,
+    liquidity: u128,
+    amount_0_max: u64,
+    amount_1_max: u64,
+    with_metadata: bool,
+    base_flag: Option<bool>,
+) -> Result<()> {
+    let tick_spacing = ctx.accounts.pool_state.load()?.tick_spacing;
+    let tick_lower_index = TickUtils::min_usable_tick(tick_spacing);
+    let tick_upper_index = TickUtils::max_usable_tick(tick_spacing);
+    let (tick_array_lower_start_index, tick_array_upper_start_index) =
+        full_range_tick_array_start_indexes(tick_spacing);
+
+    create_position_nft_mint_with_extensions(
+        &ctx.accounts.payer,
+        &ctx.accounts.position_nft_mint,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.personal_position.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_2022,
+    )?;
+
+    // create user position nft account
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.position_nft_account.to_account_info(),
+            authority: ctx.accounts.position_nft_owner.to_account_info(),
+            mint: ctx.accounts.position_nft_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+
+    open_position(
+        &ctx.accounts.payer,
+        &ctx.accounts.position_nft_owner,
+        &ctx.accounts.position_nft_mint,
+        &ctx.accounts.position_nft_account,
+        None,
+        &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
+        &ctx.accounts.tick_array_lower,
+        &ctx.accounts.tick_array_upper,
+        &mut ctx.accounts.personal_position,
+        &ctx.accounts.token_account_0.to_account_info(),
+        &ctx.accounts.token_account_1.to_account_info(),
+        &ctx.accounts.token_vault_0.to_account_info(),
+        &ctx.accounts.token_vault_1.to_account_info(),
+        &ctx.accounts.rent,
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program,
+        &ctx.accounts.associated_token_program,
+        None,
+        Some(&ctx.accounts.token_program_2022),
+        Some(ctx.accounts.vault_0_mint.clone()),
+        Some(ctx.accounts.vault_1_mint.clone()),
+        ctx.remaining_accounts,
+        ctx.bumps.personal_position,
+        liquidity,
+        amount_0_max,
+        amount_1_max,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+        with_metadata,
+        base_flag,
+        true,
+    )
+}
+
+#[cfg(test)]
+mod open_full_range_position_test {
+    use super::*;
+
+    #[test]
+    fn full_range_tick_array_start_indexes_test() {
+        assert_eq!(full_range_tick_array_start_indexes(1), (-443640, 443580));
+        assert_eq!(full_range_tick_array_start_indexes(60), (-446400, 442800));
+    }
+}
@@ -21,7 +21,7 @@ use spl_token_2022::{
     extension::{BaseStateWithExtensions, StateWithExtensions},
 };
 use std::cell::RefMut;
-#[cfg(feature = "enable-log")]
+#[cfg(feature = "enable-debug-log")]
 use std::convert::identity;
 use std::ops::Deref;
 
@@ -142,6 +142,10 @@ pub struct OpenPosition<'info> {
     /// Program to create NFT metadata
     /// CHECK: Metadata program address constraint applied
     pub metadata_program: Program<'info, Metadata>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -151,6 +155,17 @@ pub struct OpenPosition<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    //
+    // optional, pays the tick array rent instead of payer, must be after tick_array_bitmap if both are provided
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
 }
 
 pub fn open_position_v1<'a, 'b, 'c: 'info, 'info>(
@@ -172,6 +187,7 @@ pub fn open_position_v1<'a, 'b, 'c: 'info, 'info>(
         &ctx.accounts.position_nft_account.to_account_info(),
         Some(&ctx.accounts.metadata_account),
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
         &mut ctx.accounts.personal_position,
@@ -187,7 +203,7 @@ pub fn open_position_v1<'a, 'b, 'c: 'info, 'info>(
         None,
         None,
         None,
-        &ctx.remaining_accounts,
+        ctx.remaining_accounts,
         ctx.bumps.personal_position,
         liquidity,
         amount_0_max,
@@ -209,6 +225,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
     position_nft_account: &'b AccountInfo<'info>,
     metadata_account: Option<&'b UncheckedAccount<'info>>,
     pool_state_loader: &'b AccountLoader<'info, PoolState>,
+    amm_config: &'b AmmConfig,
     tick_array_lower_account: &'b UncheckedAccount<'info>,
     tick_array_upper_account: &'b UncheckedAccount<'info>,
     personal_position: &'b mut Box<Account<'info, PersonalPositionState>>,
@@ -241,10 +258,14 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
     let mut liquidity = liquidity;
     {
         let pool_state = &mut pool_state_loader.load_mut()?;
-        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity) {
+        if !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+            || !amm_config.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
+        {
             return err!(ErrorCode::NotApproved);
         }
         TickUtils::check_ticks_order(tick_lower_index, tick_upper_index)?;
+        require!(!pool_state.is_inactive(), ErrorCode::PoolInactive);
+        pool_state.check_range_width(tick_lower_index, tick_upper_index)?;
         TickUtils::check_tick_array_start_index(
             tick_array_lower_start_index,
             tick_lower_index,
@@ -256,6 +277,11 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             pool_state.tick_spacing,
         )?;
 
+        // the tick rent treasury is optional, it is looked up by address in the remaining accounts
+        let tick_rent_treasury =
+            TickRentTreasury::find_in(remaining_accounts, pool_state_loader.key());
+        let tick_spacing = pool_state.tick_spacing;
+
         // Why not use anchor's `init-if-needed` to create?
         // Beacuse `tick_array_lower` and `tick_array_upper` can be the same account, anchor can initialze tick_array_lower but it causes a crash when anchor to initialze the `tick_array_upper`,
         // the problem is variable scope, tick_array_lower_loader not exit to save the discriminator while build tick_array_upper_loader.
@@ -263,20 +289,26 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             payer.to_account_info(),
             tick_array_lower_account.to_account_info(),
             system_program.to_account_info(),
-            &pool_state_loader,
+            tick_rent_treasury,
+            pool_state_loader,
+            pool_state,
             tick_array_lower_start_index,
             tick_lower_index,
-            pool_state.tick_spacing,
+            tick_spacing,
+            None,
         )?;
 
         let tick_array_upper_loader = TickArrayContainer::get_or_create_tick_array(
             payer.to_account_info(),
             tick_array_upper_account.to_account_info(),
             system_program.to_account_info(),
-            &pool_state_loader,
+            tick_rent_treasury,
+            pool_state_loader,
+            pool_state,
             tick_array_upper_start_index,
             tick_upper_index,
-            pool_state.tick_spacing,
+            tick_spacing,
+            None,
         )?;
 
         let use_tickarray_bitmap_extension = pool_state.is_overflow_default_tickarray_bitmap(vec![
@@ -292,6 +324,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             fee_growth_inside_0_x64,
             fee_growth_inside_1_x64,
             reward_growths_inside,
+            maker_rebate_growth_boundary_x64,
             ..
         } = add_liquidity(
             payer,
@@ -308,7 +341,7 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             if use_tickarray_bitmap_extension {
                 require_keys_eq!(
                     remaining_accounts[0].key(),
-                    TickArrayBitmapExtension::key(pool_state_loader.key())
+                    TickArrayBitmapExtension::key(pool_state_loader.key(), &crate::id())
                 );
                 Some(&remaining_accounts[0])
             } else {
@@ -333,20 +366,23 @@ pub fn open_position<'a, 'b, 'c: 'info, 'info>(
             fee_growth_inside_0_x64,
             fee_growth_inside_1_x64,
             reward_growths_inside,
+            maker_rebate_growth_boundary_x64,
             get_recent_epoch()?,
         )?;
+        personal_position.last_increase_slot = get_slot()?;
 
         emit!(CreatePersonalPositionEvent {
             pool_state: pool_state_loader.key(),
             minter: payer.key(),
             nft_owner: position_nft_owner.key(),
-            tick_lower_index: tick_lower_index,
-            tick_upper_index: tick_upper_index,
-            liquidity: liquidity,
+            tick_lower_index,
+            tick_upper_index,
+            liquidity,
             deposit_amount_0: amount_0,
             deposit_amount_1: amount_1,
             deposit_amount_0_transfer_fee: amount_0_transfer_fee,
-            deposit_amount_1_transfer_fee: amount_1_transfer_fee
+            deposit_amount_1_transfer_fee: amount_1_transfer_fee,
+            event_seq: pool_state.next_event_seq(),
         });
     }
 
@@ -378,6 +414,7 @@ pub struct LiquidityChangeResult {
     pub fee_growth_inside_0_x64: u128,
     pub fee_growth_inside_1_x64: u128,
     pub reward_growths_inside: [u128; 3],
+    pub maker_rebate_growth_boundary_x64: [u128; 2],
 }
 
 /// Add liquidity to an initialized pool
@@ -412,14 +449,15 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
             // because only v2 instruction support token_2022, vault_0_mint must be exist
             let amount_0_transfer_fee =
                 get_transfer_fee(vault_0_mint.clone().unwrap(), amount_0_max).unwrap();
-            *liquidity = liquidity_math::get_liquidity_from_single_amount_0(
+            *liquidity = liquidity_math::get_liquidity_from_single_amount_0_raw(
                 pool_state.sqrt_price_x64,
-                tick_math::get_sqrt_price_at_tick(tick_lower_index)?,
-                tick_math::get_sqrt_price_at_tick(tick_upper_index)?,
+                tick_math::get_sqrt_price_at_tick_raw(tick_lower_index)?,
+                tick_math::get_sqrt_price_at_tick_raw(tick_upper_index)?,
                 amount_0_max.checked_sub(amount_0_transfer_fee).unwrap(),
             );
-            #[cfg(feature = "enable-log")]
-            msg!(
+            log_info!(
+                ix = "add_liquidity",
+                pool = pool_state.key(),
                 "liquidity: {}, amount_0_max:{}, amount_0_transfer_fee:{}",
                 *liquidity,
                 amount_0_max,
@@ -430,14 +468,15 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
             // because only v2 instruction support token_2022, vault_1_mint must be exist
             let amount_1_transfer_fee =
                 get_transfer_fee(vault_1_mint.clone().unwrap(), amount_1_max).unwrap();
-            *liquidity = liquidity_math::get_liquidity_from_single_amount_1(
+            *liquidity = liquidity_math::get_liquidity_from_single_amount_1_raw(
                 pool_state.sqrt_price_x64,
-                tick_math::get_sqrt_price_at_tick(tick_lower_index)?,
-                tick_math::get_sqrt_price_at_tick(tick_upper_index)?,
+                tick_math::get_sqrt_price_at_tick_raw(tick_lower_index)?,
+                tick_math::get_sqrt_price_at_tick_raw(tick_upper_index)?,
                 amount_1_max.checked_sub(amount_1_transfer_fee).unwrap(),
             );
-            #[cfg(feature = "enable-log")]
-            msg!(
+            log_info!(
+                ix = "add_liquidity",
+                pool = pool_state.key(),
                 "liquidity: {}, amount_1_max:{}, amount_1_transfer_fee:{}",
                 *liquidity,
                 amount_1_max,
@@ -446,6 +485,12 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         }
     }
     assert!(*liquidity > 0);
+    // fail early, before the tick arrays are loaded, the ticks check their liquidity_gross in apply_liquidity_change
+    require_gte!(
+        TickUtils::max_liquidity_per_tick(pool_state.tick_spacing),
+        *liquidity,
+        ErrorCode::TickLiquidityOverflow
+    );
     let liquidity_before = pool_state.liquidity;
 
     require_keys_eq!(tick_array_lower_loader.get_pool_id()?, pool_state.key());
@@ -471,13 +516,12 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
     if tick_upper_state.tick == 0 {
         tick_upper_state.tick = tick_upper_index;
     }
-    let clock = Clock::get()?;
     let mut result = modify_position(
         i128::try_from(*liquidity).unwrap(),
         pool_state,
         &mut tick_lower_state,
         &mut tick_upper_state,
-        clock.unix_timestamp as u64,
+        get_unix_timestamp()?,
     )?;
 
     // update tick_state
@@ -548,9 +592,11 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         trade_fee_owed_1: 0,
         transfer_fee_0: amount_0_transfer_fee,
         transfer_fee_1: amount_1_transfer_fee,
+        event_seq: pool_state.next_event_seq(),
     });
-    #[cfg(feature = "enable-log")]
-    msg!(
+    log_info!(
+        ix = "add_liquidity",
+        pool = pool_state.key(),
         "amount_0: {}, amount_0_transfer_fee: {}, amount_1: {}, amount_1_transfer_fee: {}",
         amount_0,
         amount_0_transfer_fee,
@@ -568,15 +614,15 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         ErrorCode::PriceSlippageCheck
     );
     let mut token_2022_program_opt: Option<AccountInfo> = None;
-    if token_program_2022.is_some() {
-        token_2022_program_opt = Some(token_program_2022.clone().unwrap().to_account_info());
+    if let Some(token_program_2022) = token_program_2022 {
+        token_2022_program_opt = Some(token_program_2022.to_account_info());
     }
     transfer_from_user_to_pool_vault(
         payer,
         token_account_0,
         token_vault_0,
         vault_0_mint,
-        &token_program,
+        token_program,
         token_2022_program_opt.clone(),
         amount_0 + amount_0_transfer_fee,
     )?;
@@ -585,7 +631,7 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         token_account_1,
         token_vault_1,
         vault_1_mint,
-        &token_program,
+        token_program,
         token_2022_program_opt.clone(),
         amount_1 + amount_1_transfer_fee,
     )?;
@@ -594,8 +640,9 @@ pub fn add_liquidity<'b, 'c: 'info, 'info>(
         tick: pool_state.tick_current,
         tick_lower: tick_lower_index,
         tick_upper: tick_upper_index,
-        liquidity_before: liquidity_before,
+        liquidity_before,
         liquidity_after: pool_state.liquidity,
+        event_seq: pool_state.next_event_seq(),
     });
     Ok(result)
 }
@@ -608,12 +655,17 @@ pub fn modify_position(
     timestamp: u64,
 ) -> Result<LiquidityChangeResult> {
     let updated_reward_infos = pool_state.update_reward_infos(timestamp)?;
+    let seconds_per_liquidity_cumulative_x64 = pool_state.update_seconds_per_liquidity(timestamp);
+    if liquidity_delta != 0 {
+        pool_state.last_liquidity_change_ts = timestamp;
+    }
 
     let mut flipped_lower = false;
     let mut flipped_upper = false;
 
     // update the ticks if liquidity delta is non-zero
     if liquidity_delta != 0 {
+        let max_liquidity_per_tick = TickUtils::max_liquidity_per_tick(pool_state.tick_spacing);
         // Update tick state and find if tick is flipped
         flipped_lower = tick_lower_state.update(
             pool_state.tick_current,
@@ -622,6 +674,9 @@ pub fn modify_position(
             pool_state.fee_growth_global_1_x64,
             false,
             &updated_reward_infos,
+            max_liquidity_per_tick,
+            seconds_per_liquidity_cumulative_x64,
+            timestamp as u32,
         )?;
         flipped_upper = tick_upper_state.update(
             pool_state.tick_current,
@@ -630,9 +685,13 @@ pub fn modify_position(
             pool_state.fee_growth_global_1_x64,
             true,
             &updated_reward_infos,
+            max_liquidity_per_tick,
+            seconds_per_liquidity_cumulative_x64,
+            timestamp as u32,
         )?;
-        #[cfg(feature = "enable-log")]
-        msg!(
+        log_debug!(
+            ix = "modify_position",
+            pool = pool_state.key(),
             "tick_upper.reward_growths_outside_x64:{:?}, tick_lower.reward_growths_outside_x64:{:?}",
             identity(tick_upper_state.reward_growths_outside_x64),
             identity(tick_lower_state.reward_growths_outside_x64)
@@ -656,6 +715,10 @@ pub fn modify_position(
         &updated_reward_infos,
     );
 
+    // must be read before the ticks are cleared
+    let maker_rebate_growth_boundary_x64 =
+        TickUtils::get_maker_rebate_growth_boundary(tick_lower_state, tick_upper_state);
+
     if liquidity_delta < 0 {
         if flipped_lower {
             tick_lower_state.clear();
@@ -669,7 +732,7 @@ pub fn modify_position(
     let mut amount_1 = 0;
 
     if liquidity_delta != 0 {
-        (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed(
+        (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
             pool_state.tick_current,
             pool_state.sqrt_price_x64,
             tick_lower_state.tick,
@@ -680,24 +743,25 @@ pub fn modify_position(
             && pool_state.tick_current < tick_upper_state.tick
         {
             pool_state.liquidity =
-                liquidity_math::add_delta(pool_state.liquidity, liquidity_delta)?;
+                liquidity_math::add_delta_raw(pool_state.liquidity, liquidity_delta)?;
         }
     }
 
     Ok(LiquidityChangeResult {
-        amount_0: amount_0,
-        amount_1: amount_1,
+        amount_0,
+        amount_1,
         amount_0_transfer_fee: 0,
         amount_1_transfer_fee: 0,
         tick_lower_flipped: flipped_lower,
         tick_upper_flipped: flipped_upper,
-        fee_growth_inside_0_x64: fee_growth_inside_0_x64,
-        fee_growth_inside_1_x64: fee_growth_inside_1_x64,
-        reward_growths_inside: reward_growths_inside,
+        fee_growth_inside_0_x64,
+        fee_growth_inside_1_x64,
+        reward_growths_inside,
+        maker_rebate_growth_boundary_x64,
     })
 }
 
-fn mint_nft_and_remove_mint_authority<'info>(
+pub fn mint_nft_and_remove_mint_authority<'info>(
     payer: &Signer<'info>,
     pool_state_loader: &AccountLoader<'info, PoolState>,
     personal_position: &Account<'info, PersonalPositionState>,
@@ -724,8 +788,8 @@ fn mint_nft_and_remove_mint_authority<'info>(
     };
 
     if with_metadata {
-        let (name, symbol, uri) = get_metadata_data(personal_position.key());
         if use_metadata_extension {
+            let (name, symbol, uri) = get_compact_metadata_data(personal_position);
             initialize_token_metadata_extension(
                 payer,
                 &position_nft_mint_info,
@@ -738,6 +802,7 @@ fn mint_nft_and_remove_mint_authority<'info>(
                 &[&seeds],
             )?;
         } else {
+            let (name, symbol, uri) = get_metadata_data(personal_position.key());
             initialize_metadata_account(
                 payer,
                 &pool_state_info,
@@ -767,15 +832,34 @@ fn mint_nft_and_remove_mint_authority<'info>(
         1,
     )?;
 
-    // Disable minting
+    // A token-2022 NFT opened without metadata keeps the pool as mint authority, which can only
+    // initialize its metadata with `attach_metadata`, then minting is disabled
+    if use_metadata_extension && !with_metadata {
+        return Ok(());
+    }
+    disable_nft_minting(
+        &token_program_info,
+        pool_state_loader,
+        &position_nft_mint_info,
+        &seeds,
+    )
+}
+
+/// Remove the mint authority of the position NFT, no more NFT can be minted
+pub fn disable_nft_minting<'info>(
+    token_program: &AccountInfo<'info>,
+    pool_state_loader: &AccountLoader<'info, PoolState>,
+    position_nft_mint: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+) -> Result<()> {
     token_2022::set_authority(
         CpiContext::new_with_signer(
-            token_program_info.to_account_info(),
+            token_program.clone(),
             token_2022::SetAuthority {
                 current_authority: pool_state_loader.to_account_info(),
-                account_or_mint: position_nft_mint_info,
+                account_or_mint: position_nft_mint.clone(),
             },
-            &[&seeds],
+            &[seeds],
         ),
         AuthorityType::MintTokens,
         None,
@@ -783,14 +867,32 @@ fn mint_nft_and_remove_mint_authority<'info>(
 }
 
 fn get_metadata_data(personal_position_id: Pubkey) -> (String, String, String) {
-    return (
+    (
         String::from("Byreal CLMM Position"),
         String::from("BCP"),
         format!(
             "https://www.byreal.io/clmm-position?id={}",
-            personal_position_id.to_string()
+            personal_position_id
         ),
-    );
+    )
+}
+
+/// The metadata of the token-2022 position NFTs, stored in the mint: the range is encoded in the name
+/// instead of an off-chain image, so that it costs less rent and compute than the Metaplex metadata
+pub fn get_compact_metadata_data(
+    personal_position: &Account<PersonalPositionState>,
+) -> (String, String, String) {
+    (
+        format!(
+            "BCP {}:{}",
+            personal_position.tick_lower_index, personal_position.tick_upper_index
+        ),
+        String::from("BCP"),
+        format!(
+            "https://www.byreal.io/clmm-position?id={}",
+            personal_position.key()
+        ),
+    )
 }
 
 fn initialize_metadata_account<'info>(
@@ -863,7 +965,7 @@ pub fn initialize_token_metadata_extension<'info>(
         StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
     let new_account_len =
         mint_state_unpacked.try_get_new_account_len_for_variable_len_extension(&metadata)?;
-    let new_rent_exempt_lamports = Rent::get()?.minimum_balance(new_account_len);
+    let new_rent_exempt_lamports = get_rent()?.minimum_balance(new_account_len);
     let additional_lamports = new_rent_exempt_lamports.saturating_sub(position_nft_mint.lamports());
     // CPI call will borrow the account data
     drop(mint_data);
@@ -916,7 +1018,7 @@ mod modify_position_test {
         let pool_state_ref = build_pool(
             tick_current,
             10,
-            tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+            tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
             liquidity,
         );
         let pool_state = &mut pool_state_ref.borrow_mut();
@@ -943,8 +1045,8 @@ mod modify_position_test {
         .unwrap();
         assert!(amount_0_int != 0);
         assert!(amount_1_int != 0);
-        assert_eq!(flip_tick_lower, true);
-        assert_eq!(flip_tick_upper, true);
+        assert!(flip_tick_lower);
+        assert!(flip_tick_upper);
 
         // check pool active liquidity
         let new_liquidity = pool_state.liquidity;
@@ -971,7 +1073,7 @@ mod modify_position_test {
         let pool_state_ref = build_pool(
             tick_current,
             10,
-            tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+            tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
             liquidity,
         );
         let pool_state = &mut pool_state_ref.borrow_mut();
@@ -998,8 +1100,8 @@ mod modify_position_test {
         .unwrap();
         assert!(amount_0_int == 0);
         assert!(amount_1_int != 0);
-        assert_eq!(flip_tick_lower, true);
-        assert_eq!(flip_tick_upper, true);
+        assert!(flip_tick_lower);
+        assert!(flip_tick_upper);
 
         // check pool active liquidity
         let new_liquidity = pool_state.liquidity;
@@ -1026,7 +1128,7 @@ mod modify_position_test {
         let pool_state_ref = build_pool(
             tick_current,
             10,
-            tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+            tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
             liquidity,
         );
         let pool_state = &mut pool_state_ref.borrow_mut();
@@ -1053,8 +1155,8 @@ mod modify_position_test {
         .unwrap();
         assert!(amount_0_int != 0);
         assert!(amount_1_int == 0);
-        assert_eq!(flip_tick_lower, true);
-        assert_eq!(flip_tick_upper, true);
+        assert!(flip_tick_lower);
+        assert!(flip_tick_upper);
 
         // check pool active liquidity
         let new_liquidity = pool_state.liquidity;
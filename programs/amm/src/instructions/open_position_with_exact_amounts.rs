@@ -0,0 +1,198 @@
+use super::open_position_with_token22_nft::{
+    open_position_with_token22_nft, OpenPositionWithToken22Nft,
+};
+use crate::error::ErrorCode;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::util::get_transfer_fee;
+use anchor_lang::prelude::*;
+
+/// The max liquidity not exceeding either of `amount_0` and `amount_1` at `sqrt_price_x64`,
+/// failing if the price implied by the amounts deviates from the pool price by more than `max_price_deviation_bps`.
+///
+/// The deviation is measured between the liquidity each side of the amounts can provide, a side
+/// which can't be used at the current price but has a non-zero amount deviates fully.
+pub fn get_exact_amounts_liquidity(
+    sqrt_price_x64: u128,
+    sqrt_price_lower_x64: u128,
+    sqrt_price_upper_x64: u128,
+    amount_0: u64,
+    amount_1: u64,
+    max_price_deviation_bps: u16,
+) -> Result<u128> {
+    require_gte!(10000, max_price_deviation_bps, ErrorCode::ExactAmountsPriceDeviation);
+    let liquidity_0 = if sqrt_price_x64 < sqrt_price_upper_x64 {
+        liquidity_math::get_liquidity_from_amount_0_raw(
+            sqrt_price_x64.max(sqrt_price_lower_x64),
+            sqrt_price_upper_x64,
+            amount_0,
+        )
+    } else {
+        0
+    };
+    let liquidity_1 = if sqrt_price_x64 > sqrt_price_lower_x64 {
+        liquidity_math::get_liquidity_from_amount_1_raw(
+            sqrt_price_lower_x64,
+            sqrt_price_x64.min(sqrt_price_upper_x64),
+            amount_1,
+        )
+    } else {
+        0
+    };
+
+    // a side with zero amount doesn't imply any price
+    let liquidities: Vec<u128> = [(amount_0, liquidity_0), (amount_1, liquidity_1)]
+        .iter()
+        .filter(|(amount, _)| *amount > 0)
+        .map(|(_, liquidity)| *liquidity)
+        .collect();
+    let liquidity_max = liquidities.iter().copied().max().unwrap_or(0);
+    let liquidity_min = liquidities.iter().copied().min().unwrap_or(0);
+    if let Some(deviation_bps) = (liquidity_max - liquidity_min)
+        .checked_mul(10000)
+        .unwrap()
+        .checked_div(liquidity_max)
+    {
+        require_gte!(
+            u128::from(max_price_deviation_bps),
+            deviation_bps,
+            ErrorCode::ExactAmountsPriceDeviation
+        );
+    }
+    Ok(liquidity_min)
+}
+
+/// Open a position with the max liquidity of the exact amounts, the same accounts as `open_position_with_token22_nft`
+pub fn open_position_with_exact_amounts<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+    tick_array_lower_start_index: i32,
+    tick_array_upper_start_index: i32,
+    amount_0: u64,
+    amount_1: u64,
+    max_price_deviation_bps: u16,
+    with_metadata: bool,
+) -> Result<()> {
+    // the liquidity is computed from the amounts received by the vaults
+    let liquidity = get_exact_amounts_liquidity(
+        ctx.accounts.pool_state.load()?.sqrt_price_x64,
+        tick_math::get_sqrt_price_at_tick_raw(tick_lower_index)?,
+        tick_math::get_sqrt_price_at_tick_raw(tick_upper_index)?,
+        amount_0 - get_transfer_fee(ctx.accounts.vault_0_mint.clone(), amount_0)?,
+        amount_1 - get_transfer_fee(ctx.accounts.vault_1_mint.clone(), amount_1)?,
+        max_price_deviation_bps,
+    )?;
+    require_gt!(liquidity, 0, ErrorCode::ExactAmountsPriceDeviation);
+
+    // the exact amounts bound the amounts paid for the liquidity, only what is needed is transferred
+    open_position_with_token22_nft(
+        ctx,
+        liquidity,
+        amount_0,
+        amount_1,
+        tick_lower_index,
+        tick_upper_index,
+        tick_array_lower_start_index,
+        tick_array_upper_start_index,
+        with_metadata,
+        None,
+    )
+}
+
+#[cfg(test)]
+mod open_position_with_exact_amounts_test {
+    use super::*;
+
+    #[test]
+    fn get_exact_amounts_liquidity_test() {
+        let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick_raw(-1000).unwrap();
+        let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick_raw(1000).unwrap();
+        let sqrt_price_x64 = tick_math::get_sqrt_price_at_tick_raw(0).unwrap();
+
+        // in range, balanced amounts at a symmetric range
+        let liquidity = get_exact_amounts_liquidity(
+            sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            1_000_000,
+            1_000_000,
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            liquidity,
+            liquidity_math::get_liquidity_from_amounts_raw(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                1_000_000,
+            )
+        );
+
+        // token_1 is twice as much as implied by the pool price
+        assert!(get_exact_amounts_liquidity(
+            sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            1_000_000,
+            2_000_000,
+            4000,
+        )
+        .is_err());
+        assert_eq!(
+            get_exact_amounts_liquidity(
+                sqrt_price_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                2_000_000,
+                6000,
+            )
+            .unwrap(),
+            liquidity_math::get_liquidity_from_amount_0_raw(
+                sqrt_price_x64,
+                sqrt_price_upper_x64,
+                1_000_000
+            )
+        );
+
+        // below the range only token_0 is used
+        let sqrt_price_below_x64 = tick_math::get_sqrt_price_at_tick_raw(-2000).unwrap();
+        assert_eq!(
+            get_exact_amounts_liquidity(
+                sqrt_price_below_x64,
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000,
+                0,
+                0,
+            )
+            .unwrap(),
+            liquidity_math::get_liquidity_from_amount_0_raw(
+                sqrt_price_lower_x64,
+                sqrt_price_upper_x64,
+                1_000_000
+            )
+        );
+        assert!(get_exact_amounts_liquidity(
+            sqrt_price_below_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            1_000_000,
+            1,
+            9999,
+        )
+        .is_err());
+
+        assert!(get_exact_amounts_liquidity(
+            sqrt_price_x64,
+            sqrt_price_lower_x64,
+            sqrt_price_upper_x64,
+            1_000_000,
+            1_000_000,
+            10001,
+        )
+        .is_err());
+    }
+}
@@ -119,6 +119,10 @@ pub struct OpenPositionWithToken22Nft<'info> {
         address = token_vault_1.mint
     )]
     pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The config of the pool, its status disables an operation in all the pools of the config
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
     // remaining account
     // #[account(
     //     seeds = [
@@ -128,6 +132,17 @@ pub struct OpenPositionWithToken22Nft<'info> {
     //     bump
     // )]
     // pub tick_array_bitmap: AccountLoader<'info, TickArrayBitmapExtension>,
+    //
+    // optional, pays the tick array rent instead of payer, must be after tick_array_bitmap if both are provided
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
 }
 
 pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
@@ -149,7 +164,6 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         &ctx.accounts.personal_position.to_account_info(),
         &ctx.accounts.system_program,
         &ctx.accounts.token_program_2022,
-        with_metadata,
     )?;
 
     // create user position nft account
@@ -172,6 +186,7 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         &ctx.accounts.position_nft_account,
         None,
         &ctx.accounts.pool_state,
+        &ctx.accounts.amm_config,
         &ctx.accounts.tick_array_lower,
         &ctx.accounts.tick_array_upper,
         &mut ctx.accounts.personal_position,
@@ -187,7 +202,7 @@ pub fn open_position_with_token22_nft<'a, 'b, 'c: 'info, 'info>(
         Some(&ctx.accounts.token_program_2022),
         Some(ctx.accounts.vault_0_mint.clone()),
         Some(ctx.accounts.vault_1_mint.clone()),
-        &ctx.remaining_accounts,
+        ctx.remaining_accounts,
         ctx.bumps.personal_position,
         liquidity,
         amount_0_max,
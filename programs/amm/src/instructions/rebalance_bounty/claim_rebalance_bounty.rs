@@ -0,0 +1,106 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_rent, get_slot};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct ClaimRebalanceBounty<'info> {
+    /// The keeper of the rebalance, receives the bounty
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// Writable to stamp the sequence number of the event
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            REBALANCE_BOUNTY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = rebalance_bounty.bump,
+    )]
+    pub rebalance_bounty: Account<'info, RebalanceBounty>,
+
+    #[account(mut, constraint = rebalance_vault.pool_id == pool_state.key())]
+    pub rebalance_vault: Account<'info, RebalanceVault>,
+
+    /// The vault position left out of range, its liquidity must have been withdrawn and it must not be closed yet
+    #[account(address = rebalance_vault.personal_position)]
+    pub from_personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token account of the vault owner for the new vault position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        constraint = nft_account.owner == rebalance_vault.owner @ ErrorCode::NotApproved,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The new vault position, in range
+    #[account(
+        constraint = personal_position.pool_id == pool_state.key(),
+        constraint = personal_position.key() != rebalance_vault.personal_position,
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+}
+
+/// Pay the keeper once the vault owner has moved the liquidity of its out of range position into `personal_position`,
+/// i.e. the tracked position is empty and `personal_position` holds at least its tracked liquidity.
+/// Meant to be appended to the rebalancing transaction. The vault then tracks `personal_position`,
+/// so only the first claim of a rebalance is paid.
+pub fn claim_rebalance_bounty(ctx: Context<ClaimRebalanceBounty>) -> Result<()> {
+    let tick_current = ctx.accounts.pool_state.load()?.tick_current;
+    let rebalance_bounty = &mut ctx.accounts.rebalance_bounty;
+    let rebalance_vault = &mut ctx.accounts.rebalance_vault;
+    let personal_position = &ctx.accounts.personal_position;
+
+    let out_of_range_slots = rebalance_vault.check_rebalance_due(
+        tick_current,
+        get_slot()?,
+        rebalance_bounty.min_out_of_range_slots,
+    )?;
+    require!(
+        personal_position.liquidity > 0
+            && personal_position.tick_lower_index <= tick_current
+            && tick_current < personal_position.tick_upper_index,
+        ErrorCode::PositionNotInRange
+    );
+    rebalance_vault.check_liquidity_moved(
+        ctx.accounts.from_personal_position.liquidity,
+        personal_position.liquidity,
+    )?;
+
+    let bounty_info = rebalance_bounty.to_account_info();
+    let rent_exempt_lamports = get_rent()?.minimum_balance(bounty_info.data_len());
+    let available = bounty_info.lamports().saturating_sub(rent_exempt_lamports);
+    let bounty = rebalance_bounty.bounty_lamports.min(available);
+    if bounty > 0 {
+        **bounty_info.try_borrow_mut_lamports()? -= bounty;
+        **ctx.accounts.keeper.try_borrow_mut_lamports()? += bounty;
+        rebalance_bounty.total_paid = rebalance_bounty.total_paid.checked_add(bounty).unwrap();
+    }
+
+    let from_personal_position = rebalance_vault.personal_position;
+    rebalance_vault.set_position(
+        personal_position.key(),
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        personal_position.liquidity,
+    );
+    rebalance_vault.rebalance_count += 1;
+
+    emit!(RebalanceBountyClaimedEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        rebalance_vault: rebalance_vault.key(),
+        keeper: ctx.accounts.keeper.key(),
+        from_personal_position,
+        to_personal_position: personal_position.key(),
+        out_of_range_slots,
+        bounty,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+#[derive(Accounts)]
+pub struct FundRebalanceBounty<'info> {
+    /// The pool creator, who pays the rebalances
+    #[account(
+        mut,
+        address = pool_state.load()?.owner @ ErrorCode::NotApproved
+    )]
+    pub funder: Signer<'info>,
+
+    /// The pool whose vault positions are rebalanced
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// Holds the prepaid lamports, created on the first funding
+    #[account(
+        init_if_needed,
+        seeds = [
+            REBALANCE_BOUNTY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump,
+        payer = funder,
+        space = RebalanceBounty::LEN
+    )]
+    pub rebalance_bounty: Account<'info, RebalanceBounty>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn fund_rebalance_bounty(
+    ctx: Context<FundRebalanceBounty>,
+    amount: u64,
+    bounty_lamports: u64,
+    min_out_of_range_slots: u64,
+) -> Result<()> {
+    let rebalance_bounty = &mut ctx.accounts.rebalance_bounty;
+    if rebalance_bounty.pool_id == Pubkey::default() {
+        rebalance_bounty.initialize(ctx.bumps.rebalance_bounty, ctx.accounts.pool_state.key());
+    }
+    rebalance_bounty.bounty_lamports = bounty_lamports;
+    rebalance_bounty.min_out_of_range_slots = min_out_of_range_slots;
+
+    if amount > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: rebalance_bounty.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+        rebalance_bounty.total_deposited = rebalance_bounty
+            .total_deposited
+            .checked_add(amount)
+            .unwrap();
+    }
+
+    emit!(FundRebalanceBountyEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        funder: ctx.accounts.funder.key(),
+        amount,
+        bounty_lamports,
+        min_out_of_range_slots,
+        total_deposited: rebalance_bounty.total_deposited,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+
+    Ok(())
+}
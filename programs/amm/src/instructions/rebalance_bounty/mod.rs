@@ -0,0 +1,11 @@
+pub mod fund_rebalance_bounty;
+pub use fund_rebalance_bounty::*;
+
+pub mod register_rebalance_vault;
+pub use register_rebalance_vault::*;
+
+pub mod observe_rebalance_vault;
+pub use observe_rebalance_vault::*;
+
+pub mod claim_rebalance_bounty;
+pub use claim_rebalance_bounty::*;
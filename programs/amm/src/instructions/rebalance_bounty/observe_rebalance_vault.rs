@@ -0,0 +1,28 @@
+use crate::states::*;
+use crate::util::get_slot;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ObserveRebalanceVault<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(mut, constraint = rebalance_vault.pool_id == pool_state.key())]
+    pub rebalance_vault: Account<'info, RebalanceVault>,
+}
+
+/// Permissionless, keepers call it when the price leaves the range of a vault position
+/// to start the out of range time of the position
+pub fn observe_rebalance_vault(ctx: Context<ObserveRebalanceVault>) -> Result<()> {
+    let tick_current = ctx.accounts.pool_state.load()?.tick_current;
+    let rebalance_vault = &mut ctx.accounts.rebalance_vault;
+    rebalance_vault.observe(tick_current, get_slot()?);
+
+    log_info!(
+        ix = "observe_rebalance_vault",
+        pool = rebalance_vault.pool_id,
+        "tick_current:{}, out_of_range_since_slot:{}",
+        tick_current,
+        rebalance_vault.out_of_range_since_slot
+    );
+    Ok(())
+}
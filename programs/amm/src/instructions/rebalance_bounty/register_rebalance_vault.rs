@@ -0,0 +1,75 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct RegisterRebalanceVault<'info> {
+    /// The owner of the vault position, pays the rent of the vault
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The pool creator, who pays the rebalances and so decides which positions earn them
+    #[account(address = pool_state.load()?.owner @ ErrorCode::NotApproved)]
+    pub funder: Signer<'info>,
+
+    /// The token account for the tokenized position
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault position
+    #[account(
+        constraint = personal_position.pool_id == pool_state.key(),
+        constraint = personal_position.liquidity > 0 @ ErrorCode::InvalidLiquidity,
+    )]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// Writable to stamp the sequence number of the event
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// An owner has one vault per pool, registering again replaces its position
+    #[account(
+        init_if_needed,
+        seeds = [
+            REBALANCE_VAULT_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = RebalanceVault::LEN
+    )]
+    pub rebalance_vault: Account<'info, RebalanceVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_rebalance_vault(ctx: Context<RegisterRebalanceVault>) -> Result<()> {
+    let pool_id = ctx.accounts.pool_state.key();
+    let owner = ctx.accounts.owner.key();
+    let personal_position = &ctx.accounts.personal_position;
+    let rebalance_vault = &mut ctx.accounts.rebalance_vault;
+    if rebalance_vault.pool_id == Pubkey::default() {
+        rebalance_vault.initialize(ctx.bumps.rebalance_vault, pool_id, owner);
+    }
+    rebalance_vault.set_position(
+        personal_position.key(),
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        personal_position.liquidity,
+    );
+
+    emit!(RebalanceVaultRegisteredEvent {
+        pool_state: pool_id,
+        rebalance_vault: rebalance_vault.key(),
+        owner,
+        personal_position: personal_position.key(),
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(())
+}
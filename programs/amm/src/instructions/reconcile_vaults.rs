@@ -0,0 +1,34 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct ReconcileVaults<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(address = pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Permissionless and read only, log the vault balances net of the fee liabilities for monitoring.
+/// The net balances back the liquidity, see `reconcile::reconcile_pool` for the full reconciliation off chain.
+pub fn reconcile_vaults(ctx: Context<ReconcileVaults>) -> Result<()> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let (fee_liabilities_0, fee_liabilities_1) = pool_state.fee_liabilities();
+    let vault_0_amount = ctx.accounts.token_vault_0.amount;
+    let vault_1_amount = ctx.accounts.token_vault_1.amount;
+    msg!(
+        "reconcile pool:{}, vault_0:{}, fee_liabilities_0:{}, delta_0:{}, vault_1:{}, fee_liabilities_1:{}, delta_1:{}",
+        ctx.accounts.pool_state.key(),
+        vault_0_amount,
+        fee_liabilities_0,
+        i128::from(vault_0_amount) - i128::from(fee_liabilities_0),
+        vault_1_amount,
+        fee_liabilities_1,
+        i128::from(vault_1_amount) - i128::from(fee_liabilities_1)
+    );
+    Ok(())
+}
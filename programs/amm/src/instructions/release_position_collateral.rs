@@ -0,0 +1,93 @@
+use crate::states::*;
+use crate::util::close_spl_account;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ReleasePositionCollateral<'info> {
+    pub collateral_manager: Signer<'info>,
+
+    /// CHECK: The owner who signed the terms, receives the rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = collateral_manager,
+        has_one = owner,
+        has_one = custody,
+        close = owner
+    )]
+    pub position_collateral: Account<'info, PositionCollateralState>,
+
+    /// The position of the terms, its owner can move its liquidity and close it again
+    #[account(mut, constraint = personal_position.nft_mint == position_collateral.nft_mint)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position, stamps the event sequence
+    #[account(mut, address = position_collateral.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The mint of the position NFT
+    #[account(
+        address = position_collateral.nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The custody of the terms, closed once the NFT is returned
+    #[account(mut)]
+    pub custody: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The token account of the owner the NFT is returned to
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the position NFT
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Only the collateral manager can release the terms. Until then the position is collateralized:
+/// its NFT is in the custody of the terms and its owner can't decrease, transfer, split, merge, move or close it,
+/// see `PersonalPositionState::check_not_collateralized`
+pub fn release_position_collateral(ctx: Context<ReleasePositionCollateral>) -> Result<()> {
+    ctx.accounts.personal_position.collateralized = false;
+    let position_collateral = &ctx.accounts.position_collateral;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let custody = ctx.accounts.custody.to_account_info();
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            token_interface::TransferChecked {
+                from: custody.clone(),
+                mint: ctx.accounts.position_nft_mint.to_account_info(),
+                to: ctx.accounts.nft_account.to_account_info(),
+                authority: position_collateral.to_account_info(),
+            },
+            &[&position_collateral.seeds()],
+        ),
+        1,
+        ctx.accounts.position_nft_mint.decimals,
+    )?;
+    close_spl_account(
+        &position_collateral.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        &custody,
+        &token_program,
+        &[&position_collateral.seeds()],
+    )?;
+
+    emit!(PositionCollateralEvent {
+        position_nft_mint: position_collateral.nft_mint,
+        collateral_manager: position_collateral.collateral_manager,
+        receiver: position_collateral.receiver,
+        released: true,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(())
+}
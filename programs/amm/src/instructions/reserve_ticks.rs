@@ -0,0 +1,65 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(tick_array_start_index: i32)]
+pub struct ReserveTicks<'info> {
+    /// Pays the rent of the reserved TickStates
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Stamps the sequence number of the resize event
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: only dynamic tick array is supported, checked in the function body
+    #[account(
+        mut,
+        seeds = [
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+            &tick_array_start_index.to_be_bytes(),
+        ],
+        bump,
+    )]
+    pub tick_array: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // remaining account
+    // optional, pays the tick array rent instead of payer, only if the payer is the pool owner
+    // #[account(
+    //     mut,
+    //     seeds = [
+    //         TICK_RENT_TREASURY_SEED.as_bytes(),
+    //         pool_state.key().as_ref(),
+    //     ],
+    //     bump
+    // )]
+    // pub tick_rent_treasury: Account<'info, TickRentTreasury>,
+}
+
+pub fn reserve_ticks<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ReserveTicks<'info>>,
+    _tick_array_start_index: i32,
+    count: u8,
+) -> Result<()> {
+    let pool_owner = ctx.accounts.pool_state.load()?.owner;
+    let tick_rent_treasury = TickRentTreasury::find_for_pool_owner(
+        ctx.remaining_accounts,
+        ctx.accounts.pool_state.key(),
+        pool_owner,
+        ctx.accounts.payer.key(),
+    );
+
+    TickArrayContainer::reserve_ticks(
+        ctx.accounts.payer.to_account_info(),
+        ctx.accounts.tick_array.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+        tick_rent_treasury,
+        &ctx.accounts.pool_state,
+        &mut *ctx.accounts.pool_state.load_mut()?,
+        count,
+    )?;
+
+    Ok(())
+}
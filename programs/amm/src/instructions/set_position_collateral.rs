@@ -0,0 +1,108 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct SetPositionCollateral<'info> {
+    /// The position owner, signs the terms and pays the rent
+    #[account(mut)]
+    pub nft_owner: Signer<'info>,
+
+    /// The mint of the position NFT
+    #[account(
+        address = personal_position.nft_mint,
+        mint::token_program = token_program,
+    )]
+    pub position_nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The token account for the tokenized position, the NFT is moved into the custody of the terms
+    #[account(
+        mut,
+        token::mint = position_nft_mint,
+        token::authority = nft_owner,
+        token::token_program = token_program,
+        constraint = nft_account.amount == 1,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The position, marked as collateralized until the terms are released
+    #[account(mut)]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The pool of the position, stamps the event sequence
+    #[account(mut, address = personal_position.pool_id)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The terms of the position, a position has at most one collateral manager at a time
+    #[account(
+        init,
+        seeds = [
+            POSITION_COLLATERAL_SEED.as_bytes(),
+            personal_position.nft_mint.as_ref(),
+        ],
+        bump,
+        payer = nft_owner,
+        space = PositionCollateralState::LEN
+    )]
+    pub position_collateral: Account<'info, PositionCollateralState>,
+
+    /// Holds the position NFT until the terms are released
+    #[account(
+        init,
+        associated_token::mint = position_nft_mint,
+        associated_token::authority = position_collateral,
+        associated_token::token_program = token_program,
+        payer = nft_owner,
+    )]
+    pub nft_custody: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Spl token program or token program 2022 of the position NFT
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_position_collateral(
+    ctx: Context<SetPositionCollateral>,
+    collateral_manager: Pubkey,
+    receiver: Pubkey,
+) -> Result<()> {
+    let nft_mint = ctx.accounts.personal_position.nft_mint;
+    ctx.accounts.position_collateral.initialize(
+        ctx.bumps.position_collateral,
+        nft_mint,
+        ctx.accounts.nft_owner.key(),
+        collateral_manager,
+        receiver,
+        ctx.accounts.personal_position.pool_id,
+        ctx.accounts.nft_custody.key(),
+    );
+    ctx.accounts.personal_position.collateralized = true;
+
+    // escrow the NFT, so the terms can't be left behind by moving it to another holder
+    token_interface::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_interface::TransferChecked {
+                from: ctx.accounts.nft_account.to_account_info(),
+                mint: ctx.accounts.position_nft_mint.to_account_info(),
+                to: ctx.accounts.nft_custody.to_account_info(),
+                authority: ctx.accounts.nft_owner.to_account_info(),
+            },
+        ),
+        1,
+        ctx.accounts.position_nft_mint.decimals,
+    )?;
+
+    emit!(PositionCollateralEvent {
+        position_nft_mint: nft_mint,
+        collateral_manager,
+        receiver,
+        released: false,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+    Ok(())
+}
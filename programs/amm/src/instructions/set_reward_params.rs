@@ -65,7 +65,7 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
     let admin_operator = admin_keys.contains(&ctx.accounts.authority.key())
         && ctx.accounts.authority.key() != Pubkey::default();
 
-    let current_timestamp = u64::try_from(Clock::get()?.unix_timestamp).unwrap();
+    let current_timestamp = util::get_unix_timestamp()?;
     require_gt!(open_time, current_timestamp);
 
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
@@ -110,11 +110,11 @@ pub fn set_reward_params<'a, 'b, 'c: 'info, 'info>(
         let mut remaining_accounts = ctx.remaining_accounts.iter();
 
         let reward_token_vault =
-            InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
+            InterfaceAccount::<TokenAccount>::try_from(remaining_accounts.next().unwrap())?;
         let authority_token_account =
-            InterfaceAccount::<TokenAccount>::try_from(&remaining_accounts.next().unwrap())?;
+            InterfaceAccount::<TokenAccount>::try_from(remaining_accounts.next().unwrap())?;
         let reward_vault_mint =
-            InterfaceAccount::<Mint>::try_from(&remaining_accounts.next().unwrap())?;
+            InterfaceAccount::<Mint>::try_from(remaining_accounts.next().unwrap())?;
 
         require_keys_eq!(reward_token_vault.mint, authority_token_account.mint);
         require_keys_eq!(reward_token_vault.key(), reward_info.token_vault);
@@ -149,8 +149,8 @@ fn normal_update(
     if reward_info.last_update_time == reward_info.end_time {
         // reward emission has finished
         let time_delta = end_time.checked_sub(open_time).unwrap();
-        if time_delta < reward_period_limit::MIN_REWARD_PERIOD
-            || time_delta > reward_period_limit::MAX_REWARD_PERIOD
+        if !(reward_period_limit::MIN_REWARD_PERIOD..=reward_period_limit::MAX_REWARD_PERIOD)
+            .contains(&time_delta)
         {
             return Err(ErrorCode::InvalidRewardPeriod.into());
         }
@@ -170,8 +170,8 @@ fn normal_update(
         // reward emission does not finish
         let left_reward_time = reward_info.end_time.checked_sub(current_timestamp).unwrap();
         let extend_period = end_time.checked_sub(reward_info.end_time).unwrap();
-        if extend_period < reward_period_limit::MIN_REWARD_PERIOD
-            || extend_period > reward_period_limit::MAX_REWARD_PERIOD
+        if !(reward_period_limit::MIN_REWARD_PERIOD..=reward_period_limit::MAX_REWARD_PERIOD)
+            .contains(&extend_period)
         {
             return err!(ErrorCode::NotApproveUpdateRewardEmissiones);
         }
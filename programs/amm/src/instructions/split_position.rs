@@ -0,0 +1,235 @@
+use super::collect_fees_and_rewards::accrue_position;
+use super::open_position::mint_nft_and_remove_mint_authority;
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::create_position_nft_mint_with_extensions;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{create, AssociatedToken, Create};
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Token2022, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SplitPosition<'info> {
+    /// Pays to mint the new position
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The owner of the position split
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the position split
+    #[account(
+        constraint = nft_account.mint == personal_position.nft_mint,
+        constraint = nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// CHECK: Receives the new position NFT
+    pub new_position_nft_owner: UncheckedAccount<'info>,
+
+    /// Unique token mint address of the new position, initialize in contract
+    #[account(mut)]
+    pub new_position_nft_mint: Signer<'info>,
+
+    /// CHECK: ATA address where the new position NFT will be minted, initialize in contract
+    #[account(mut)]
+    pub new_position_nft_account: UncheckedAccount<'info>,
+
+    /// The new position, holding the split liquidity
+    #[account(
+        init,
+        seeds = [POSITION_SEED.as_bytes(), new_position_nft_mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = PersonalPositionState::LEN
+    )]
+    pub new_personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_upper: UncheckedAccount<'info>,
+
+    /// Sysvar for token mint and ATA creation
+    pub rent: Sysvar<'info, Rent>,
+
+    /// Program to create the position manager state account
+    pub system_program: Program<'info, System>,
+
+    /// Program of the position NFTs minted by the SPL token program
+    pub token_program: Program<'info, Token>,
+
+    /// Program to create an ATA for receiving position NFT
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Program to create the new position NFT mint and token account
+    pub token_program_2022: Program<'info, Token2022>,
+}
+
+/// Accrue `personal_position` from the tick arrays of the pool, then move `liquidity` and its share
+/// of the owed fees and rewards to `new_personal_position`, a position of the same range.
+pub fn accrue_and_split<'info>(
+    pool_id: &Pubkey,
+    pool_state: &mut PoolState,
+    personal_position: &mut PersonalPositionState,
+    new_personal_position: &mut PersonalPositionState,
+    tick_array_lower: &AccountInfo<'info>,
+    tick_array_upper: &AccountInfo<'info>,
+    liquidity: u128,
+) -> Result<()> {
+    accrue_position(
+        pool_id,
+        pool_state,
+        personal_position,
+        tick_array_lower,
+        tick_array_upper,
+    )?;
+    new_personal_position.pool_id = personal_position.pool_id;
+    new_personal_position.tick_lower_index = personal_position.tick_lower_index;
+    new_personal_position.tick_upper_index = personal_position.tick_upper_index;
+    personal_position.split_to(new_personal_position, liquidity)
+}
+
+/// The ticks and the pool liquidity are unchanged: the position accrues its fees and rewards up to now,
+/// then `liquidity` and the same share of the owed fees and rewards are moved to a new token-2022 position NFT.
+pub fn split_position<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SplitPosition<'info>>,
+    liquidity: u128,
+    with_metadata: bool,
+) -> Result<()> {
+    require_gt!(liquidity, 0, ErrorCode::InvalidLiquidity);
+    {
+        let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+        let personal_position = &mut ctx.accounts.personal_position;
+        let new_personal_position = &mut ctx.accounts.new_personal_position;
+        new_personal_position.bump = [ctx.bumps.new_personal_position];
+        new_personal_position.nft_mint = ctx.accounts.new_position_nft_mint.key();
+        accrue_and_split(
+            &ctx.accounts.pool_state.key(),
+            &mut pool_state,
+            personal_position,
+            new_personal_position,
+            &ctx.accounts.tick_array_lower.to_account_info(),
+            &ctx.accounts.tick_array_upper.to_account_info(),
+            liquidity,
+        )?;
+
+        emit!(PositionSplitEvent {
+            pool_state: ctx.accounts.pool_state.key(),
+            position_nft_mint: personal_position.nft_mint,
+            new_position_nft_mint: new_personal_position.nft_mint,
+            new_position_nft_owner: ctx.accounts.new_position_nft_owner.key(),
+            liquidity,
+            event_seq: pool_state.next_event_seq(),
+        });
+    }
+
+    create_position_nft_mint_with_extensions(
+        &ctx.accounts.payer,
+        &ctx.accounts.new_position_nft_mint,
+        &ctx.accounts.pool_state.to_account_info(),
+        &ctx.accounts.new_personal_position.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.token_program_2022,
+    )?;
+    create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.new_position_nft_account.to_account_info(),
+            authority: ctx.accounts.new_position_nft_owner.to_account_info(),
+            mint: ctx.accounts.new_position_nft_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program_2022.to_account_info(),
+        },
+    ))?;
+    mint_nft_and_remove_mint_authority(
+        &ctx.accounts.payer,
+        &ctx.accounts.pool_state,
+        &ctx.accounts.new_personal_position,
+        &ctx.accounts.new_position_nft_mint,
+        &ctx.accounts.new_position_nft_account,
+        None,
+        None,
+        &ctx.accounts.token_program,
+        Some(&ctx.accounts.token_program_2022),
+        &ctx.accounts.system_program,
+        &ctx.accounts.rent,
+        with_metadata,
+        true,
+    )
+}
+
+#[cfg(test)]
+mod split_position_test {
+    use super::*;
+    use crate::instructions::collect_fees_and_rewards::collect_fees_and_rewards_test::*;
+    use crate::libraries::mock_anchor_account_info_v3;
+
+    #[test]
+    fn accrue_and_split_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        let mut personal_position = build_position(pool_id, 3000);
+        let mut new_personal_position = PersonalPositionState::default();
+
+        let (key, tick_array) = build_position_tick_array(pool_id, 0);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        accrue_and_split(
+            &pool_id,
+            &mut pool_state.borrow_mut(),
+            &mut personal_position,
+            &mut new_personal_position,
+            &account_info,
+            &account_info,
+            1000,
+        )
+        .unwrap();
+        assert_eq!(personal_position.liquidity, 2000);
+        assert_eq!(personal_position.token_fees_owed_0, 10000);
+        assert_eq!(new_personal_position.liquidity, 1000);
+        assert_eq!(new_personal_position.token_fees_owed_0, 5000);
+        assert_eq!(new_personal_position.tick_lower_index, TICK_LOWER_INDEX);
+        assert_eq!(new_personal_position.tick_upper_index, TICK_UPPER_INDEX);
+    }
+
+    #[test]
+    fn accrue_and_split_foreign_tick_array_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_state = build_accrue_pool();
+        let mut personal_position = build_position(pool_id, 3000);
+        let mut new_personal_position = PersonalPositionState::default();
+
+        // the tick array of another pool with an inflated fee growth outside of the upper tick
+        let other_pool_id = Pubkey::new_unique();
+        let (key, tick_array) = build_position_tick_array(other_pool_id, u128::MAX / 2);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        assert_eq!(
+            accrue_and_split(
+                &pool_id,
+                &mut pool_state.borrow_mut(),
+                &mut personal_position,
+                &mut new_personal_position,
+                &account_info,
+                &account_info,
+                1000,
+            )
+            .err(),
+            Some(anchor_lang::error::ErrorCode::RequireKeysEqViolated.into())
+        );
+        assert_eq!(new_personal_position.liquidity, 0);
+        assert_eq!(new_personal_position.token_fees_owed_0, 0);
+    }
+}
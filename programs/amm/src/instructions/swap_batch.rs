@@ -0,0 +1,195 @@
+use crate::error::ErrorCode;
+use crate::interface::{SwapOrder, SwapResult};
+use crate::states::*;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use anchor_lang::prelude::*;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
+
+/// The max number of orders in a `swap_batch`
+pub const MAX_SWAP_BATCH_ORDERS: usize = 4;
+
+#[derive(Accounts)]
+pub struct SwapBatch<'info> {
+    /// The user performing the swaps
+    pub payer: Signer<'info>,
+
+    /// The factory state to read protocol fees
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    /// The program account of the pool in which the swaps will be performed
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The user token account for token_0
+    #[account(
+        mut,
+        token::mint = token_vault_0.mint
+    )]
+    pub token_account_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user token account for token_1
+    #[account(
+        mut,
+        token::mint = token_vault_1.mint
+    )]
+    pub token_account_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_0
+    #[account(
+        mut,
+        constraint = token_vault_0.key() == pool_state.load()?.token_vault_0
+    )]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The address that holds pool tokens for token_1
+    #[account(
+        mut,
+        constraint = token_vault_1.key() == pool_state.load()?.token_vault_1
+    )]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The program account for the most recent oracle observation
+    #[account(mut, address = pool_state.load()?.observation_key)]
+    pub observation_state: AccountLoader<'info, ObservationState>,
+
+    /// SPL program for token transfers
+    pub token_program: Program<'info, Token>,
+
+    /// SPL program 2022 for token transfers
+    pub token_program_2022: Program<'info, Token2022>,
+
+    /// Memo program
+    pub memo_program: Program<'info, Memo>,
+
+    /// The mint of token vault 0
+    #[account(
+        address = token_vault_0.mint
+    )]
+    pub vault_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of token vault 1
+    #[account(
+        address = token_vault_1.mint
+    )]
+    pub vault_1_mint: Box<InterfaceAccount<'info, Mint>>,
+    // remaining accounts
+    // the tick arrays of the zero_for_one orders, from the current tick array downwards
+    // tick_array_account_1
+    // tick_array_account_...
+    // tickarray_bitmap_extension: must add account if need, between the tick arrays of the two directions
+    // the tick arrays of the one_for_zero orders, from the current tick array upwards
+    // tick_array_account_1
+    // tick_array_account_...
+}
+
+pub fn swap_batch<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapBatch<'info>>,
+    orders: Vec<SwapOrder>,
+    zero_for_one_tick_array_count: u8,
+) -> Result<Vec<SwapResult>> {
+    require!(
+        !orders.is_empty() && orders.len() <= MAX_SWAP_BATCH_ORDERS,
+        ErrorCode::InvalidSwapBatchSize
+    );
+    let zero_for_one_tick_array_count = usize::from(zero_for_one_tick_array_count);
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        zero_for_one_tick_array_count,
+        ErrorCode::NotEnoughTickArrayAccount
+    );
+    // both directions start from the current tick array but walk the tick arrays in opposite orders,
+    // the bitmap extension between them is shared by the two slices
+    let tick_array_bitmap_extension_key =
+        TickArrayBitmapExtension::key(ctx.accounts.pool_state.key(), &crate::id());
+    let has_tick_array_bitmap_extension = ctx
+        .remaining_accounts
+        .get(zero_for_one_tick_array_count)
+        .is_some_and(|account_info| account_info.key() == tick_array_bitmap_extension_key);
+    let zero_for_one_remaining_accounts = &ctx.remaining_accounts
+        [..zero_for_one_tick_array_count + usize::from(has_tick_array_bitmap_extension)];
+    let one_for_zero_remaining_accounts = &ctx.remaining_accounts[zero_for_one_tick_array_count..];
+
+    let accounts = ctx.accounts;
+    let mut results = Vec::with_capacity(orders.len());
+    for order in orders {
+        // the balances are changed by the previous orders
+        accounts.token_account_0.reload()?;
+        accounts.token_account_1.reload()?;
+        accounts.token_vault_0.reload()?;
+        accounts.token_vault_1.reload()?;
+
+        let (
+            input_token_account,
+            output_token_account,
+            input_vault,
+            output_vault,
+            input_vault_mint,
+            output_vault_mint,
+        ) = if order.zero_for_one {
+            (
+                accounts.token_account_0.clone(),
+                accounts.token_account_1.clone(),
+                accounts.token_vault_0.clone(),
+                accounts.token_vault_1.clone(),
+                accounts.vault_0_mint.clone(),
+                accounts.vault_1_mint.clone(),
+            )
+        } else {
+            (
+                accounts.token_account_1.clone(),
+                accounts.token_account_0.clone(),
+                accounts.token_vault_1.clone(),
+                accounts.token_vault_0.clone(),
+                accounts.vault_1_mint.clone(),
+                accounts.vault_0_mint.clone(),
+            )
+        };
+
+        // each order is settled and emits its SwapEvent like a swap_v2
+        let result = exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: accounts.payer.clone(),
+                amm_config: accounts.amm_config.clone(),
+                pool_state: accounts.pool_state.clone(),
+                input_token_account,
+                output_token_account,
+                input_vault,
+                output_vault,
+                observation_state: accounts.observation_state.clone(),
+                token_program: accounts.token_program.clone(),
+                token_program_2022: accounts.token_program_2022.clone(),
+                memo_program: accounts.memo_program.clone(),
+                input_vault_mint,
+                output_vault_mint,
+            },
+            if order.zero_for_one {
+                zero_for_one_remaining_accounts
+            } else {
+                one_for_zero_remaining_accounts
+            },
+            order.amount,
+            order.sqrt_price_limit_x64,
+            order.is_base_input,
+        )?;
+        let amount_result = result.other_amount(order.is_base_input);
+        if order.is_base_input {
+            require_gte!(
+                amount_result,
+                order.other_amount_threshold,
+                ErrorCode::TooLittleOutputReceived
+            );
+        } else {
+            require_gte!(
+                order.other_amount_threshold,
+                amount_result,
+                ErrorCode::TooMuchInputPaid
+            );
+        }
+        results.push(result);
+    }
+
+    Ok(results)
+}
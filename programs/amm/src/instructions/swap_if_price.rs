@@ -0,0 +1,33 @@
+use super::{swap_v2, SwapSingleV2};
+use crate::error::ErrorCode;
+use crate::interface::{PriceCondition, SwapResult};
+use anchor_lang::prelude::*;
+
+/// Skip the swap, or fail if `condition.fail_if_not_met`, unless the pool sqrt price before the swap meets `condition`
+pub fn swap_if_price<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    condition: PriceCondition,
+) -> Result<SwapResult> {
+    let sqrt_price_x64 = ctx.accounts.pool_state.load()?.sqrt_price_x64;
+    if !condition.is_met(sqrt_price_x64) {
+        require!(!condition.fail_if_not_met, ErrorCode::PriceConditionNotMet);
+        log_info!(
+            ix = "swap_if_price",
+            pool = ctx.accounts.pool_state.key(),
+            "condition not met, sqrt_price_x64:{}",
+            sqrt_price_x64
+        );
+        return Ok(SwapResult::default());
+    }
+    swap_v2(
+        ctx,
+        amount,
+        other_amount_threshold,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )
+}
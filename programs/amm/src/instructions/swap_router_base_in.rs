@@ -2,6 +2,7 @@ use crate::error::ErrorCode;
 use crate::states::*;
 use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_spl::memo::Memo;
 use anchor_spl::{
     token::Token,
@@ -43,7 +44,9 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
         let mut remaining_accounts = accounts.iter();
         let account_info = remaining_accounts.next().unwrap();
         if accounts.len() != ctx.remaining_accounts.len()
-            && account_info.data_len() != AmmConfig::LEN
+            && !account_info
+                .try_borrow_data()?
+                .starts_with(AmmConfig::DISCRIMINATOR)
         {
             accounts = remaining_accounts.as_slice();
             continue;
@@ -52,7 +55,7 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
         let pool_state_loader =
             AccountLoader::<PoolState>::try_from(remaining_accounts.next().unwrap())?;
         let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(
-            &remaining_accounts.next().unwrap(),
+            remaining_accounts.next().unwrap(),
         )?);
         let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
             remaining_accounts.next().unwrap(),
@@ -96,7 +99,8 @@ pub fn swap_router_base_in<'a, 'b, 'c: 'info, 'info>(
             amount_in_internal,
             0,
             true,
-        )?;
+        )?
+        .amount_out;
         // output token is the new swap input token
         input_token_account = output_token_account;
         input_token_mint = output_token_mint;
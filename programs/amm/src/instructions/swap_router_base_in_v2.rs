@@ -0,0 +1,125 @@
+use crate::error::ErrorCode;
+use crate::interface::SwapResult;
+use crate::states::*;
+use crate::swap_router_base_in::SwapRouterBaseIn;
+use crate::swap_v2::{exact_internal_v2, SwapSingleV2};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+/// The min number of hops in a `swap_router_base_in_v2` route, a single hop is a `swap_v2`
+pub const MIN_ROUTER_HOPS: usize = 2;
+/// The max number of hops in a `swap_router_base_in_v2` route
+pub const MAX_ROUTER_HOPS: usize = 4;
+/// The accounts of a hop before its tick arrays: amm_config, pool_state, output_token_account,
+/// input_vault, output_vault, output_token_mint and observation_state
+const HOP_ACCOUNTS_LEN: usize = 7;
+
+/// Split the remaining accounts of a route into its hops, each with the accounts of the hop and
+/// the tick arrays of its pool, fixed or dynamic, with the optional bitmap extension among them.
+/// The tick arrays of a hop end at the first account that is neither, the amm_config of the next hop.
+fn split_route_hops<'c, 'info>(
+    mut accounts: &'c [AccountInfo<'info>],
+) -> Result<Vec<(&'c [AccountInfo<'info>], &'c [AccountInfo<'info>])>> {
+    let mut hops = Vec::with_capacity(MAX_ROUTER_HOPS);
+    while !accounts.is_empty() {
+        require!(
+            hops.len() < MAX_ROUTER_HOPS,
+            ErrorCode::InvalidRouteHopCount
+        );
+        require_gt!(
+            accounts.len(),
+            HOP_ACCOUNTS_LEN,
+            ErrorCode::NotEnoughTickArrayAccount
+        );
+        let (hop_accounts, rest) = accounts.split_at(HOP_ACCOUNTS_LEN);
+        let pool_id = hop_accounts[1].key();
+        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_id, &crate::id());
+        let tick_array_count = rest
+            .iter()
+            .take_while(|account_info| {
+                account_info.key() == tick_array_bitmap_extension_key
+                    || TickArrayContainer::try_from_without_check(account_info)
+                        .and_then(|tick_array| tick_array.get_pool_id())
+                        .is_ok_and(|tick_array_pool_id| tick_array_pool_id == pool_id)
+            })
+            .count();
+        let (tick_arrays, rest) = rest.split_at(tick_array_count);
+        hops.push((hop_accounts, tick_arrays));
+        accounts = rest;
+    }
+    require_gte!(hops.len(), MIN_ROUTER_HOPS, ErrorCode::InvalidRouteHopCount);
+    Ok(hops)
+}
+
+/// Swap `amount_in` through 2 to 4 pools, the output of a hop is the input of the next one.
+/// Only the final output is checked against `amount_out_minimum`, each hop fills its input completely.
+pub fn swap_router_base_in_v2<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+    amount_in: u64,
+    amount_out_minimum: u64,
+) -> Result<Vec<SwapResult>> {
+    let hops = split_route_hops(ctx.remaining_accounts)?;
+
+    let mut amount_in_internal = amount_in;
+    let mut input_token_account = Box::new(ctx.accounts.input_token_account.clone());
+    let mut input_token_mint = Box::new(ctx.accounts.input_token_mint.clone());
+    let mut results = Vec::with_capacity(hops.len());
+    for (hop_accounts, tick_arrays) in hops {
+        let amm_config = Box::new(Account::<AmmConfig>::try_from(&hop_accounts[0])?);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&hop_accounts[1])?;
+        let output_token_account = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            &hop_accounts[2],
+        )?);
+        let input_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            &hop_accounts[3],
+        )?);
+        let output_vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(
+            &hop_accounts[4],
+        )?);
+        let output_token_mint = Box::new(InterfaceAccount::<Mint>::try_from(&hop_accounts[5])?);
+        let observation_state = AccountLoader::<ObservationState>::try_from(&hop_accounts[6])?;
+        // the balance of the input token account is changed by the previous hop
+        input_token_account.reload()?;
+        {
+            let pool_state = pool_state_loader.load()?;
+            // check observation account is owned by the pool
+            require_keys_eq!(pool_state.observation_key, observation_state.key());
+            // check ammConfig account is associate with the pool
+            require_keys_eq!(pool_state.amm_config, amm_config.key());
+        }
+
+        let result = exact_internal_v2(
+            &mut SwapSingleV2 {
+                payer: ctx.accounts.payer.clone(),
+                amm_config,
+                input_token_account,
+                pool_state: pool_state_loader,
+                output_token_account: output_token_account.clone(),
+                input_vault,
+                output_vault,
+                input_vault_mint: input_token_mint,
+                output_vault_mint: output_token_mint.clone(),
+                observation_state,
+                token_program: ctx.accounts.token_program.clone(),
+                token_program_2022: ctx.accounts.token_program_2022.clone(),
+                memo_program: ctx.accounts.memo_program.clone(),
+            },
+            tick_arrays,
+            amount_in_internal,
+            0,
+            true,
+        )?;
+        amount_in_internal = result.amount_out;
+        results.push(result);
+        // output token is the new swap input token
+        input_token_account = output_token_account;
+        input_token_mint = output_token_mint;
+    }
+    require_gte!(
+        amount_in_internal,
+        amount_out_minimum,
+        ErrorCode::TooLittleOutputReceived
+    );
+
+    Ok(results)
+}
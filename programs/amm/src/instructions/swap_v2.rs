@@ -2,17 +2,20 @@ use std::collections::VecDeque;
 use std::ops::Deref;
 
 use crate::error::ErrorCode;
-use crate::libraries::tick_math;
-use crate::swap::swap_internal;
+use crate::interface::SwapResult;
+use crate::libraries::settle_math::{settle_add, settle_sub};
+use crate::swap::{
+    normalize_sqrt_price_limit, swap_fee_counters, swap_internal_with_max_iterations,
+};
 use crate::util::*;
 use crate::{states::*, util};
-use anchor_lang::{prelude::*, solana_program};
+use anchor_lang::prelude::*;
 use anchor_spl::memo::Memo;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{Mint, Token2022, TokenAccount};
 
 /// Memo msg for swap
-pub const SWAP_MEMO_MSG: &'static [u8] = b"byreal_swap";
+pub const SWAP_MEMO_MSG: &[u8] = b"byreal_swap";
 #[derive(Accounts)]
 pub struct SwapSingleV2<'info> {
     /// The user performing the swap
@@ -30,7 +33,8 @@ pub struct SwapSingleV2<'info> {
     #[account(mut)]
     pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    /// The user token account for output token
+    /// The token account receiving the output token, it may be owned by a recipient other than the payer,
+    /// e.g. a merchant or a settlement contract
     #[account(mut)]
     pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -74,22 +78,54 @@ pub struct SwapSingleV2<'info> {
 }
 
 /// Performs a single exact input/output swap
-/// if is_base_input = true, return value is the max_amount_out, otherwise is min_amount_in
+/// if is_base_input = true, `SwapResult::other_amount` is the max_amount_out, otherwise is min_amount_in
 pub fn exact_internal_v2<'c: 'info, 'info>(
     ctx: &mut SwapSingleV2<'info>,
     remaining_accounts: &'c [AccountInfo<'info>],
     amount_specified: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
-) -> Result<u64> {
+) -> Result<SwapResult> {
+    exact_internal_v2_with_options(
+        ctx,
+        remaining_accounts,
+        amount_specified,
+        sqrt_price_limit_x64,
+        is_base_input,
+        None,
+        None,
+    )
+}
+
+/// Same as `exact_internal_v2` with optional overrides. If `input_delegate` is set the input is pulled by this delegate, with its seeds,
+/// from a token account owned by someone other than the payer, the output must then go back to that owner.
+/// `max_swap_iterations` overrides the max steps of the swap of the amm config.
+pub fn exact_internal_v2_with_options<'c: 'info, 'info>(
+    ctx: &mut SwapSingleV2<'info>,
+    remaining_accounts: &'c [AccountInfo<'info>],
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    input_delegate: Option<(&AccountInfo<'info>, &[&[u8]])>,
+    max_swap_iterations: Option<u16>,
+) -> Result<SwapResult> {
+    if input_delegate.is_some() {
+        require_keys_eq!(
+            ctx.output_token_account.owner,
+            ctx.input_token_account.owner,
+            ErrorCode::InvalidOutputTokenAccount
+        );
+    }
     // invoke_memo_instruction(SWAP_MEMO_MSG, ctx.memo_program.to_account_info())?;
 
-    let block_timestamp = solana_program::clock::Clock::get()?.unix_timestamp as u64;
+    let block_timestamp = get_unix_timestamp()?;
 
     let amount_0;
     let amount_1;
     let zero_for_one;
     let swap_price_before;
+    let fees_before;
+    let fees_after;
 
     let input_balance_before = ctx.input_token_account.amount;
     let output_balance_before = ctx.output_token_account.amount;
@@ -118,12 +154,21 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             },
             ErrorCode::InvalidInputPoolVault
         );
+        // the output token account may belong to any recipient, but must hold the output mint
+        // and be owned by the token program of the mint, Token-2022 accounts can't receive a spl token mint and vice versa
+        require_keys_eq!(ctx.output_token_account.mint, ctx.output_vault.mint, ErrorCode::InvalidOutputTokenAccount);
+        require_keys_eq!(
+            *ctx.output_token_account.to_account_info().owner,
+            *ctx.output_vault_mint.to_account_info().owner,
+            ErrorCode::InvalidOutputTokenAccount
+        );
 
         let mut tickarray_bitmap_extension = None;
         let tick_array_states = &mut VecDeque::new();
 
-        let tick_array_bitmap_extension_key = TickArrayBitmapExtension::key(pool_state.key());
-        for account_info in remaining_accounts.into_iter() {
+        let tick_array_bitmap_extension_key =
+            TickArrayBitmapExtension::key(pool_state.key(), &crate::id());
+        for account_info in remaining_accounts.iter() {
             if account_info.key().eq(&tick_array_bitmap_extension_key) {
                 tickarray_bitmap_extension = Some(
                     *(AccountLoader::<TickArrayBitmapExtension>::try_from(account_info)?
@@ -135,30 +180,27 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             tick_array_states.push_back(TickArrayContainer::load_data_mut(account_info)?);
         }
 
-        (amount_0, amount_1) = swap_internal(
+        fees_before = swap_fee_counters(pool_state, zero_for_one);
+        (amount_0, amount_1) = swap_internal_with_max_iterations(
             &ctx.amm_config,
             pool_state,
             tick_array_states,
             &mut ctx.observation_state.load_mut()?,
             &tickarray_bitmap_extension,
             amount_calculate_specified,
-            if sqrt_price_limit_x64 == 0 {
-                if zero_for_one {
-                    tick_math::MIN_SQRT_PRICE_X64 + 1
-                } else {
-                    tick_math::MAX_SQRT_PRICE_X64 - 1
-                }
-            } else {
-                sqrt_price_limit_x64
-            },
+            normalize_sqrt_price_limit(sqrt_price_limit_x64, zero_for_one)?,
             zero_for_one,
             is_base_input,
             oracle::block_timestamp(),
+            max_swap_iterations,
         )?;
+        ObservationState::update_extension(&ctx.observation_state.to_account_info())?;
+        fees_after = swap_fee_counters(pool_state, zero_for_one);
 
-        #[cfg(feature = "enable-log")]
-        msg!(
-            "exact_swap_internal, is_base_input:{}, amount_0: {}, amount_1: {}",
+        log_info!(
+            ix = "swap_v2",
+            pool = pool_state.key(),
+            "is_base_input:{}, amount_0: {}, amount_1: {}",
             is_base_input,
             amount_0,
             amount_1
@@ -203,10 +245,12 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         transfer_fee_1 = util::get_transfer_fee(vault_1_mint.clone(), amount_1).unwrap();
 
         amount_0_without_fee = amount_0;
-        amount_1_without_fee = amount_1.checked_sub(transfer_fee_1).unwrap();
-        (transfer_amount_0, transfer_amount_1) = (amount_0 + transfer_fee_0, amount_1);
-        #[cfg(feature = "enable-log")]
-        msg!(
+        amount_1_without_fee = settle_sub("amount_1", amount_1, transfer_fee_1)?;
+        (transfer_amount_0, transfer_amount_1) =
+            (settle_add("amount_0", amount_0, transfer_fee_0)?, amount_1);
+        log_info!(
+            ix = "swap_v2",
+            pool = ctx.pool_state.key(),
             "amount_0:{}, transfer_fee_0:{}, amount_1:{}, transfer_fee_1:{}",
             amount_0,
             transfer_fee_0,
@@ -214,15 +258,27 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             transfer_fee_1
         );
         //  x -> y, deposit x token from user to pool vault.
-        transfer_from_user_to_pool_vault(
-            &ctx.payer,
-            &token_account_0.to_account_info(),
-            &vault_0.to_account_info(),
-            Some(vault_0_mint),
-            &ctx.token_program,
-            Some(ctx.token_program_2022.to_account_info()),
-            transfer_amount_0,
-        )?;
+        match input_delegate {
+            Some((delegate, delegate_seeds)) => transfer_from_delegated_account_to_pool_vault(
+                delegate,
+                delegate_seeds,
+                &token_account_0.to_account_info(),
+                &vault_0.to_account_info(),
+                Some(vault_0_mint),
+                &ctx.token_program,
+                Some(ctx.token_program_2022.to_account_info()),
+                transfer_amount_0,
+            )?,
+            None => transfer_from_user_to_pool_vault(
+                &ctx.payer,
+                &token_account_0.to_account_info(),
+                &vault_0.to_account_info(),
+                Some(vault_0_mint),
+                &ctx.token_program,
+                Some(ctx.token_program_2022.to_account_info()),
+                transfer_amount_0,
+            )?,
+        }
         if vault_1.amount <= transfer_amount_1 {
             // freeze pool, disable all instructions
             ctx.pool_state.load_mut()?.set_status(255);
@@ -245,26 +301,40 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
             util::get_transfer_inverse_fee(vault_1_mint.clone(), amount_1).unwrap()
         };
 
-        amount_0_without_fee = amount_0.checked_sub(transfer_fee_0).unwrap();
+        amount_0_without_fee = settle_sub("amount_0", amount_0, transfer_fee_0)?;
         amount_1_without_fee = amount_1;
-        (transfer_amount_0, transfer_amount_1) = (amount_0, amount_1 + transfer_fee_1);
-        #[cfg(feature = "enable-log")]
-        msg!(
+        (transfer_amount_0, transfer_amount_1) =
+            (amount_0, settle_add("amount_1", amount_1, transfer_fee_1)?);
+        log_info!(
+            ix = "swap_v2",
+            pool = ctx.pool_state.key(),
             "amount_0:{}, transfer_fee_0:{}, amount_1:{}, transfer_fee_1:{}",
             amount_0,
             transfer_fee_0,
             amount_1,
             transfer_fee_1
         );
-        transfer_from_user_to_pool_vault(
-            &ctx.payer,
-            &token_account_1.to_account_info(),
-            &vault_1.to_account_info(),
-            Some(vault_1_mint),
-            &ctx.token_program,
-            Some(ctx.token_program_2022.to_account_info()),
-            transfer_amount_1,
-        )?;
+        match input_delegate {
+            Some((delegate, delegate_seeds)) => transfer_from_delegated_account_to_pool_vault(
+                delegate,
+                delegate_seeds,
+                &token_account_1.to_account_info(),
+                &vault_1.to_account_info(),
+                Some(vault_1_mint),
+                &ctx.token_program,
+                Some(ctx.token_program_2022.to_account_info()),
+                transfer_amount_1,
+            )?,
+            None => transfer_from_user_to_pool_vault(
+                &ctx.payer,
+                &token_account_1.to_account_info(),
+                &vault_1.to_account_info(),
+                Some(vault_1_mint),
+                &ctx.token_program,
+                Some(ctx.token_program_2022.to_account_info()),
+                transfer_amount_1,
+            )?,
+        }
         if vault_0.amount <= transfer_amount_0 {
             // freeze pool, disable all instructions
             ctx.pool_state.load_mut()?.set_status(255);
@@ -282,7 +352,7 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
     ctx.output_token_account.reload()?;
     ctx.input_token_account.reload()?;
 
-    let pool_state = ctx.pool_state.load()?;
+    let mut pool_state = ctx.pool_state.load_mut()?;
     emit!(SwapEvent {
         pool_state: pool_state.key(),
         sender: ctx.payer.key(),
@@ -295,7 +365,8 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         zero_for_one,
         sqrt_price_x64: pool_state.sqrt_price_x64,
         liquidity: pool_state.liquidity,
-        tick: pool_state.tick_current
+        tick: pool_state.tick_current,
+        event_seq: pool_state.next_event_seq(),
     });
     if zero_for_one {
         require_gt!(swap_price_before, pool_state.sqrt_price_x64);
@@ -319,17 +390,21 @@ pub fn exact_internal_v2<'c: 'info, 'info>(
         }
     }
 
-    if is_base_input {
-        Ok(ctx
-            .output_token_account
-            .amount
-            .checked_sub(output_balance_before)
-            .unwrap())
-    } else {
-        Ok(input_balance_before
-            .checked_sub(ctx.input_token_account.amount)
-            .unwrap())
-    }
+    Ok(SwapResult::new(
+        settle_sub(
+            "input_token_account",
+            input_balance_before,
+            ctx.input_token_account.amount,
+        )?,
+        settle_sub(
+            "output_token_account",
+            ctx.output_token_account.amount,
+            output_balance_before,
+        )?,
+        if zero_for_one { amount_0 } else { amount_1 },
+        fees_before,
+        fees_after,
+    ))
 }
 
 pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
@@ -338,14 +413,15 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
     other_amount_threshold: u64,
     sqrt_price_limit_x64: u128,
     is_base_input: bool,
-) -> Result<()> {
-    let amount_result = exact_internal_v2(
+) -> Result<SwapResult> {
+    let result = exact_internal_v2(
         ctx.accounts,
         ctx.remaining_accounts,
         amount,
         sqrt_price_limit_x64,
         is_base_input,
     )?;
+    let amount_result = result.other_amount(is_base_input);
     if is_base_input {
         require_gte!(
             amount_result,
@@ -356,5 +432,5 @@ pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
         require_gte!(other_amount_threshold, amount_result, ErrorCode::TooMuchInputPaid);
     }
 
-    Ok(())
+    Ok(result)
 }
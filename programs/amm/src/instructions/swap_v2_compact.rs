@@ -0,0 +1,225 @@
+use super::{swap_v2, SwapSingleV2};
+use crate::interface::SwapResult;
+use anchor_lang::prelude::*;
+use std::io::{Error, ErrorKind, Read, Write};
+
+/// Swap base input if set, otherwise swap base output
+pub const COMPACT_SWAP_FLAG_BASE_INPUT: u8 = 1 << 0;
+/// `other_amount_threshold` is present, otherwise it has no slippage limit
+pub const COMPACT_SWAP_FLAG_HAS_THRESHOLD: u8 = 1 << 1;
+/// `sqrt_price_limit_x64` is present, otherwise it is zero
+pub const COMPACT_SWAP_FLAG_HAS_SQRT_PRICE_LIMIT: u8 = 1 << 2;
+
+const COMPACT_SWAP_FLAGS_MASK: u8 = COMPACT_SWAP_FLAG_BASE_INPUT
+    | COMPACT_SWAP_FLAG_HAS_THRESHOLD
+    | COMPACT_SWAP_FLAG_HAS_SQRT_PRICE_LIMIT;
+
+/// The arguments of `swap_v2`, packed as
+///
+/// | flags: u8 | amount: u64 | other_amount_threshold: u64, optional | sqrt_price_limit_x64: u128, optional |
+///
+/// All integers are little-endian. Optional fields are omitted when they hold their default value,
+/// which takes 9 bytes at best instead of the 33 bytes of the borsh encoded `swap_v2` arguments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompactSwapArgs {
+    pub amount: u64,
+    pub other_amount_threshold: u64,
+    pub sqrt_price_limit_x64: u128,
+    pub is_base_input: bool,
+}
+
+impl CompactSwapArgs {
+    pub fn new(
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    ) -> Self {
+        Self {
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        }
+    }
+
+    /// The threshold meaning no slippage limit, min output for base input and max input for base output
+    pub fn default_threshold(is_base_input: bool) -> u64 {
+        if is_base_input {
+            0
+        } else {
+            u64::MAX
+        }
+    }
+
+    pub fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.is_base_input {
+            flags |= COMPACT_SWAP_FLAG_BASE_INPUT;
+        }
+        if self.other_amount_threshold != Self::default_threshold(self.is_base_input) {
+            flags |= COMPACT_SWAP_FLAG_HAS_THRESHOLD;
+        }
+        if self.sqrt_price_limit_x64 != 0 {
+            flags |= COMPACT_SWAP_FLAG_HAS_SQRT_PRICE_LIMIT;
+        }
+        flags
+    }
+
+    /// The encoded length in bytes
+    pub fn packed_len(&self) -> usize {
+        let flags = self.flags();
+        let mut len = 1 + 8;
+        if flags & COMPACT_SWAP_FLAG_HAS_THRESHOLD != 0 {
+            len += 8;
+        }
+        if flags & COMPACT_SWAP_FLAG_HAS_SQRT_PRICE_LIMIT != 0 {
+            len += 16;
+        }
+        len
+    }
+
+    /// Encode the arguments, used by clients to build the instruction data
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.packed_len());
+        self.serialize(&mut data).unwrap();
+        data
+    }
+
+    /// Decode the arguments, trailing bytes are rejected
+    pub fn unpack(mut data: &[u8]) -> std::io::Result<Self> {
+        let args = Self::deserialize_reader(&mut data)?;
+        if !data.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "trailing bytes in compact swap args",
+            ));
+        }
+        Ok(args)
+    }
+}
+
+impl AnchorSerialize for CompactSwapArgs {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let flags = self.flags();
+        writer.write_all(&[flags])?;
+        writer.write_all(&self.amount.to_le_bytes())?;
+        if flags & COMPACT_SWAP_FLAG_HAS_THRESHOLD != 0 {
+            writer.write_all(&self.other_amount_threshold.to_le_bytes())?;
+        }
+        if flags & COMPACT_SWAP_FLAG_HAS_SQRT_PRICE_LIMIT != 0 {
+            writer.write_all(&self.sqrt_price_limit_x64.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl AnchorDeserialize for CompactSwapArgs {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut flags = [0u8; 1];
+        reader.read_exact(&mut flags)?;
+        let flags = flags[0];
+        if flags & !COMPACT_SWAP_FLAGS_MASK != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "unknown compact swap flags",
+            ));
+        }
+        let is_base_input = flags & COMPACT_SWAP_FLAG_BASE_INPUT != 0;
+
+        let mut amount = [0u8; 8];
+        reader.read_exact(&mut amount)?;
+
+        let other_amount_threshold = if flags & COMPACT_SWAP_FLAG_HAS_THRESHOLD != 0 {
+            let mut threshold = [0u8; 8];
+            reader.read_exact(&mut threshold)?;
+            u64::from_le_bytes(threshold)
+        } else {
+            Self::default_threshold(is_base_input)
+        };
+
+        let sqrt_price_limit_x64 = if flags & COMPACT_SWAP_FLAG_HAS_SQRT_PRICE_LIMIT != 0 {
+            let mut sqrt_price_limit = [0u8; 16];
+            reader.read_exact(&mut sqrt_price_limit)?;
+            u128::from_le_bytes(sqrt_price_limit)
+        } else {
+            0
+        };
+
+        Ok(Self {
+            amount: u64::from_le_bytes(amount),
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        })
+    }
+}
+
+// The wire format is not borsh, clients must encode it with `CompactSwapArgs::pack`
+#[cfg(feature = "idl-build")]
+impl anchor_lang::idl::IdlBuild for CompactSwapArgs {}
+
+pub fn swap_v2_compact<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    args: CompactSwapArgs,
+) -> Result<SwapResult> {
+    swap_v2(
+        ctx,
+        args.amount,
+        args.other_amount_threshold,
+        args.sqrt_price_limit_x64,
+        args.is_base_input,
+    )
+}
+
+#[cfg(test)]
+mod compact_swap_args_test {
+    use super::*;
+
+    #[test]
+    fn pack_omit_default_fields_test() {
+        let args = CompactSwapArgs::new(1_000_000, 0, 0, true);
+        let data = args.pack();
+        assert_eq!(data.len(), 9);
+        assert_eq!(data.len(), args.packed_len());
+        assert_eq!(data[0], COMPACT_SWAP_FLAG_BASE_INPUT);
+        assert_eq!(CompactSwapArgs::unpack(&data).unwrap(), args);
+
+        let args = CompactSwapArgs::new(1_000_000, u64::MAX, 0, false);
+        let data = args.pack();
+        assert_eq!(data.len(), 9);
+        assert_eq!(data[0], 0);
+        assert_eq!(CompactSwapArgs::unpack(&data).unwrap(), args);
+    }
+
+    #[test]
+    fn pack_all_fields_test() {
+        let args = CompactSwapArgs::new(1_000_000, 990_000, 79226673521066979257578248091, true);
+        let data = args.pack();
+        assert_eq!(data.len(), 33);
+        assert_eq!(data.len(), args.packed_len());
+        assert_eq!(CompactSwapArgs::unpack(&data).unwrap(), args);
+
+        let args = CompactSwapArgs::new(1_000_000, 0, 79226673521066979257578248091, false);
+        let data = args.pack();
+        assert_eq!(data.len(), 33);
+        assert_eq!(CompactSwapArgs::unpack(&data).unwrap(), args);
+    }
+
+    #[test]
+    fn unpack_invalid_data_test() {
+        // unknown flags
+        let mut data = CompactSwapArgs::new(1, 0, 0, true).pack();
+        data[0] |= 1 << 7;
+        assert!(CompactSwapArgs::unpack(&data).is_err());
+
+        // missing the flagged sqrt_price_limit_x64
+        let data = CompactSwapArgs::new(1, 2, 3, true).pack();
+        assert!(CompactSwapArgs::unpack(&data[..data.len() - 1]).is_err());
+
+        // trailing bytes
+        let mut data = CompactSwapArgs::new(1, 0, 0, true).pack();
+        data.push(0);
+        assert!(CompactSwapArgs::unpack(&data).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::error::ErrorCode;
+use crate::instructions::*;
+use crate::interface::SwapResult;
+use anchor_lang::prelude::*;
+
+pub const SWAP_DELEGATE_SEED: &str = "swap_delegate";
+
+#[derive(Accounts)]
+pub struct SwapV2Delegated<'info> {
+    /// The swap, `swap.payer` is the trader allowed by the owner of `swap.input_token_account`,
+    /// `swap.output_token_account` must be owned by the same owner
+    pub swap: SwapSingleV2<'info>,
+
+    /// CHECK: The delegate approved by the owner of the input token account for this trader,
+    /// the approved amount is the allowance left to trade
+    #[account(
+        seeds = [
+            SWAP_DELEGATE_SEED.as_bytes(),
+            swap.input_token_account.owner.as_ref(),
+            swap.payer.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub swap_delegate: UncheckedAccount<'info>,
+    // remaining accounts
+    // the remaining accounts of swap_v2
+}
+
+/// Swap like `swap_v2`, with the input pulled by the program delegate from a token account the payer doesn't own,
+/// e.g. a smart wallet pre-approving a bounded amount for a trading key instead of co-signing each swap
+pub fn swap_v2_delegated<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapV2Delegated<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+) -> Result<SwapResult> {
+    let owner = ctx.accounts.swap.input_token_account.owner;
+    let trader = ctx.accounts.swap.payer.key();
+    let bump = [ctx.bumps.swap_delegate];
+    let delegate_seeds: &[&[u8]] = &[
+        SWAP_DELEGATE_SEED.as_bytes(),
+        owner.as_ref(),
+        trader.as_ref(),
+        &bump,
+    ];
+    let delegate_info = ctx.accounts.swap_delegate.to_account_info();
+
+    let result = exact_internal_v2_with_options(
+        &mut ctx.accounts.swap,
+        ctx.remaining_accounts,
+        amount,
+        sqrt_price_limit_x64,
+        is_base_input,
+        Some((&delegate_info, delegate_seeds)),
+        None,
+    )?;
+    let amount_result = result.other_amount(is_base_input);
+    if is_base_input {
+        require_gte!(
+            amount_result,
+            other_amount_threshold,
+            ErrorCode::TooLittleOutputReceived
+        );
+    } else {
+        require_gte!(
+            other_amount_threshold,
+            amount_result,
+            ErrorCode::TooMuchInputPaid
+        );
+    }
+
+    Ok(result)
+}
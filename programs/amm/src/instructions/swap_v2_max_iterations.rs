@@ -0,0 +1,41 @@
+use super::{exact_internal_v2_with_options, SwapSingleV2};
+use crate::error::ErrorCode;
+use crate::interface::SwapResult;
+use anchor_lang::prelude::*;
+
+/// Same as `swap_v2`, with the max steps of the swap overriding the default of the amm config,
+/// at most `MAX_SWAP_ITERATIONS`
+pub fn swap_v2_with_max_iterations<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    max_swap_iterations: u16,
+) -> Result<SwapResult> {
+    let result = exact_internal_v2_with_options(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        amount,
+        sqrt_price_limit_x64,
+        is_base_input,
+        None,
+        Some(max_swap_iterations),
+    )?;
+    let amount_result = result.other_amount(is_base_input);
+    if is_base_input {
+        require_gte!(
+            amount_result,
+            other_amount_threshold,
+            ErrorCode::TooLittleOutputReceived
+        );
+    } else {
+        require_gte!(
+            other_amount_threshold,
+            amount_result,
+            ErrorCode::TooMuchInputPaid
+        );
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,222 @@
+use super::{exact_internal_v2, SwapSingleV2};
+use crate::error::ErrorCode;
+use crate::interface::SwapResult;
+use crate::libraries::{liquidity_math, tick_math};
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+/// Get the input amount, excluding the trade fee, filled by a position with `liquidity` in `[tick_lower, tick_upper)`
+/// when a swap moves the price from `sqrt_price_before_x64` to `sqrt_price_after_x64`
+pub fn position_fill_amount(
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    sqrt_price_before_x64: u128,
+    sqrt_price_after_x64: u128,
+    zero_for_one: bool,
+) -> Result<u64> {
+    let sqrt_price_lower_x64 = tick_math::get_sqrt_price_at_tick_raw(tick_lower)?;
+    let sqrt_price_upper_x64 = tick_math::get_sqrt_price_at_tick_raw(tick_upper)?;
+    let from_x64 = sqrt_price_before_x64
+        .min(sqrt_price_after_x64)
+        .max(sqrt_price_lower_x64);
+    let to_x64 = sqrt_price_before_x64
+        .max(sqrt_price_after_x64)
+        .min(sqrt_price_upper_x64);
+    if liquidity == 0 || from_x64 >= to_x64 {
+        return Ok(0);
+    }
+    if zero_for_one {
+        liquidity_math::get_delta_amount_0_unsigned_raw(from_x64, to_x64, liquidity, false)
+    } else {
+        liquidity_math::get_delta_amount_1_unsigned_raw(from_x64, to_x64, liquidity, false)
+    }
+}
+
+/// Swap like `swap_v2`, but fail if more than half of the input swapped in the pool, excluding the trade fee,
+/// is filled by the positions of the payer, so that a market maker quoting both sides doesn't trade with itself.
+/// The first `2 * maker_position_count` remaining accounts are the (personal position, position NFT account) pairs
+/// of the payer in the pool, then the remaining accounts of `swap_v2`.
+/// Only the positions passed are accounted for, the market maker is responsible for passing all of them.
+pub fn swap_v2_stp<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+    amount: u64,
+    other_amount_threshold: u64,
+    sqrt_price_limit_x64: u128,
+    is_base_input: bool,
+    maker_position_count: u8,
+) -> Result<SwapResult> {
+    let maker_accounts_len = 2 * usize::from(maker_position_count);
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        maker_accounts_len,
+        ErrorCode::AccountLack
+    );
+    let (maker_accounts, swap_remaining_accounts) =
+        ctx.remaining_accounts.split_at(maker_accounts_len);
+
+    let pool_id = ctx.accounts.pool_state.key();
+    let payer = ctx.accounts.payer.key();
+    let mut maker_positions = Vec::with_capacity(usize::from(maker_position_count));
+    for pair in maker_accounts.chunks_exact(2) {
+        let personal_position = Account::<PersonalPositionState>::try_from(&pair[0])?;
+        let nft_account = InterfaceAccount::<TokenAccount>::try_from(&pair[1])?;
+        require_keys_eq!(personal_position.pool_id, pool_id);
+        require!(
+            nft_account.mint == personal_position.nft_mint
+                && nft_account.owner == payer
+                && nft_account.amount == 1,
+            ErrorCode::NotApproved
+        );
+        maker_positions.push((
+            personal_position.tick_lower_index,
+            personal_position.tick_upper_index,
+            personal_position.liquidity,
+        ));
+    }
+
+    let zero_for_one =
+        ctx.accounts.input_vault.mint == ctx.accounts.pool_state.load()?.token_mint_0;
+    let sqrt_price_before_x64 = ctx.accounts.pool_state.load()?.sqrt_price_x64;
+    let input_vault_before = ctx.accounts.input_vault.amount;
+
+    let result = exact_internal_v2(
+        ctx.accounts,
+        swap_remaining_accounts,
+        amount,
+        sqrt_price_limit_x64,
+        is_base_input,
+    )?;
+    let amount_result = result.other_amount(is_base_input);
+    if is_base_input {
+        require_gte!(
+            amount_result,
+            other_amount_threshold,
+            ErrorCode::TooLittleOutputReceived
+        );
+    } else {
+        require_gte!(
+            other_amount_threshold,
+            amount_result,
+            ErrorCode::TooMuchInputPaid
+        );
+    }
+
+    // the input vault receives the input swapped in the pool, net of the transfer fee
+    ctx.accounts.input_vault.reload()?;
+    let pool_amount_in = ctx
+        .accounts
+        .input_vault
+        .amount
+        .saturating_sub(input_vault_before);
+    let filled_amount = pool_amount_in.saturating_sub(result.fee_amount);
+    let sqrt_price_after_x64 = ctx.accounts.pool_state.load()?.sqrt_price_x64;
+    let mut maker_filled_amount: u64 = 0;
+    for (tick_lower, tick_upper, liquidity) in maker_positions {
+        maker_filled_amount = maker_filled_amount.saturating_add(position_fill_amount(
+            tick_lower,
+            tick_upper,
+            liquidity,
+            sqrt_price_before_x64,
+            sqrt_price_after_x64,
+            zero_for_one,
+        )?);
+    }
+    log_info!(
+        ix = "swap_v2_stp",
+        pool = pool_id,
+        "filled_amount:{}, maker_filled_amount:{}",
+        filled_amount,
+        maker_filled_amount
+    );
+    require!(
+        u128::from(maker_filled_amount) * 2 <= u128::from(filled_amount),
+        ErrorCode::SelfTradeNotAllowed
+    );
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod swap_v2_stp_test {
+    use super::*;
+
+    #[test]
+    fn position_fill_amount_test() {
+        let liquidity = 1_000_000_000u128;
+        let sqrt_price_before_x64 = tick_math::get_sqrt_price_at_tick_raw(100).unwrap();
+        let sqrt_price_after_x64 = tick_math::get_sqrt_price_at_tick_raw(-100).unwrap();
+        let full = liquidity_math::get_delta_amount_0_unsigned_raw(
+            sqrt_price_after_x64,
+            sqrt_price_before_x64,
+            liquidity,
+            false,
+        )
+        .unwrap();
+
+        // a position over the whole price path fills all of it
+        assert_eq!(
+            position_fill_amount(
+                -200,
+                200,
+                liquidity,
+                sqrt_price_before_x64,
+                sqrt_price_after_x64,
+                true
+            )
+            .unwrap(),
+            full
+        );
+        // a position over half of the price path, in ticks
+        let half = position_fill_amount(
+            -200,
+            0,
+            liquidity,
+            sqrt_price_before_x64,
+            sqrt_price_after_x64,
+            true,
+        )
+        .unwrap();
+        assert!(half > 0 && half < full);
+        // out of the price path or without liquidity
+        for (tick_lower, tick_upper, position_liquidity) in [
+            (100, 200, liquidity),
+            (-300, -100, liquidity),
+            (-200, 200, 0),
+        ] {
+            assert_eq!(
+                position_fill_amount(
+                    tick_lower,
+                    tick_upper,
+                    position_liquidity,
+                    sqrt_price_before_x64,
+                    sqrt_price_after_x64,
+                    true
+                )
+                .unwrap(),
+                0
+            );
+        }
+
+        // one for zero fills in token_1, the direction of the price path doesn't matter
+        assert_eq!(
+            position_fill_amount(
+                -200,
+                200,
+                liquidity,
+                sqrt_price_after_x64,
+                sqrt_price_before_x64,
+                false
+            )
+            .unwrap(),
+            liquidity_math::get_delta_amount_1_unsigned_raw(
+                sqrt_price_after_x64,
+                sqrt_price_before_x64,
+                liquidity,
+                false
+            )
+            .unwrap()
+        );
+    }
+}
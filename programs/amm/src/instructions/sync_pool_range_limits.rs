@@ -0,0 +1,26 @@
+use crate::cranks::{settle_crank, CrankKind};
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SyncPoolRangeLimits<'info> {
+    /// The config of the pool
+    #[account(address = pool_state.load()?.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+}
+
+/// Permissionless, it only copies the current limits and tick pre-allocation count of the pool's amm config
+pub fn sync_pool_range_limits(ctx: Context<SyncPoolRangeLimits>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    pool_state.set_range_limits(&ctx.accounts.amm_config);
+    pool_state.set_prealloc_tick_count(&ctx.accounts.amm_config);
+    settle_crank(
+        CrankKind::SyncPoolRangeLimits,
+        ctx.accounts.pool_state.key(),
+        &mut pool_state,
+        ctx.remaining_accounts,
+    )
+}
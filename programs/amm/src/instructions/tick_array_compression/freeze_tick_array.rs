@@ -0,0 +1,91 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_recent_epoch, get_rent, AccountLoad};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct FreezeTickArray<'info> {
+    #[account(
+        address = admin_group.pool_manager @ ErrorCode::NotApproved
+    )]
+    pub authority: Signer<'info>,
+
+    /// amm admin group account to store admin permissions.
+    #[account(
+        seeds = [
+            ADMIN_GROUP_SEED.as_bytes()
+        ],
+        bump,
+    )]
+    pub admin_group: Box<Account<'info, AmmAdminGroup>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: fixed tick array of the pool, checked in the function body
+    #[account(mut)]
+    pub tick_array: UncheckedAccount<'info>,
+
+    /// CHECK: receives the rent refunded by the shrink of the tick array account
+    #[account(mut)]
+    pub rent_recipient: UncheckedAccount<'info>,
+}
+
+pub fn freeze_tick_array(ctx: Context<FreezeTickArray>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+
+    let (frozen_tick_array, ticks) = {
+        let tick_array_loader = AccountLoad::<TickArrayState>::try_from(&tick_array_info)?;
+        let tick_array = tick_array_loader.load()?;
+        require_keys_eq!(
+            tick_array.pool_id,
+            ctx.accounts.pool_state.key(),
+            ErrorCode::InvalidTickArray
+        );
+        let ticks = FrozenTick::non_empty(&tick_array.ticks);
+        FrozenTickArrayState::check_freezable(
+            tick_array.start_tick_index,
+            pool_state.tick_current,
+            pool_state.tick_spacing,
+            ticks.len(),
+        )?;
+        let frozen_tick_array = FrozenTickArrayState {
+            pool_id: tick_array.pool_id,
+            start_tick_index: tick_array.start_tick_index,
+            root: tick_array_merkle_root(&tick_array.ticks),
+            non_empty_offsets: ticks
+                .iter()
+                .fold(0u64, |offsets, tick| offsets | 1 << tick.offset),
+            initialized_tick_count: tick_array.initialized_tick_count,
+            recent_epoch: get_recent_epoch()?,
+            padding: [0; 32],
+        };
+        (frozen_tick_array, ticks)
+    };
+
+    // shrink the tick array account in place, so that the address can't be created again until it is thawed
+    AccountInfo::resize(&tick_array_info, FrozenTickArrayState::LEN)?;
+    {
+        let mut data = tick_array_info.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(FrozenTickArrayState::DISCRIMINATOR);
+        data[8..].copy_from_slice(bytemuck::bytes_of(&frozen_tick_array));
+    }
+    let refunded_lamports = tick_array_info
+        .lamports()
+        .saturating_sub(get_rent()?.minimum_balance(FrozenTickArrayState::LEN));
+    **tick_array_info.try_borrow_mut_lamports()? -= refunded_lamports;
+    **ctx.accounts.rent_recipient.try_borrow_mut_lamports()? += refunded_lamports;
+
+    emit!(TickArrayFrozenEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        tick_array: tick_array_info.key(),
+        start_tick_index: frozen_tick_array.start_tick_index,
+        root: frozen_tick_array.root,
+        ticks,
+        refunded_lamports,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
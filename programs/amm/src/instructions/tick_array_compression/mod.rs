@@ -0,0 +1,5 @@
+pub mod freeze_tick_array;
+pub use freeze_tick_array::*;
+
+pub mod thaw_tick_array;
+pub use thaw_tick_array::*;
@@ -0,0 +1,81 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{realloc_account_if_needed, AccountLoad};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct ThawTickArray<'info> {
+    /// Pays the rent of the restored tick array
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: frozen tick array of the pool, checked in the function body
+    #[account(mut)]
+    pub tick_array: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// `ticks` are the non empty TickStates of the tick array when it was frozen, from `TickArrayFrozenEvent`
+pub fn thaw_tick_array(ctx: Context<ThawTickArray>, ticks: Vec<FrozenTick>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let tick_array_info = ctx.accounts.tick_array.to_account_info();
+
+    let frozen_tick_array =
+        *AccountLoad::<FrozenTickArrayState>::try_from(&tick_array_info)?.load()?;
+    require_keys_eq!(
+        frozen_tick_array.pool_id,
+        ctx.accounts.pool_state.key(),
+        ErrorCode::InvalidTickArray
+    );
+    let offsets = ticks.iter().try_fold(0u64, |offsets, tick| {
+        require_gt!(
+            TICK_ARRAY_SIZE_USIZE,
+            usize::from(tick.offset),
+            ErrorCode::InvalidFrozenTicks
+        );
+        Ok(offsets | 1 << tick.offset)
+    })?;
+    require!(
+        offsets == frozen_tick_array.non_empty_offsets,
+        ErrorCode::InvalidFrozenTicks
+    );
+
+    // restore the tick array account in place, the whole transaction fails if the ticks don't match the root
+    realloc_account_if_needed(
+        &tick_array_info,
+        TickArrayState::LEN,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    {
+        let mut data = tick_array_info.try_borrow_mut_data()?;
+        data.fill(0);
+        data[..8].copy_from_slice(TickArrayState::DISCRIMINATOR);
+        let tick_array: &mut TickArrayState =
+            bytemuck::from_bytes_mut(&mut data[8..TickArrayState::LEN]);
+        tick_array.pool_id = frozen_tick_array.pool_id;
+        tick_array.start_tick_index = frozen_tick_array.start_tick_index;
+        tick_array.initialized_tick_count = frozen_tick_array.initialized_tick_count;
+        tick_array.recent_epoch = frozen_tick_array.recent_epoch;
+        for tick in &ticks {
+            tick_array.ticks[usize::from(tick.offset)] = tick.tick_state();
+        }
+        require!(
+            tick_array_merkle_root(&tick_array.ticks) == frozen_tick_array.root,
+            ErrorCode::InvalidFrozenTicks
+        );
+    }
+
+    emit!(TickArrayThawedEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        tick_array: tick_array_info.key(),
+        start_tick_index: frozen_tick_array.start_tick_index,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use crate::util::{get_recent_epoch, get_slot, get_unix_timestamp};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct TransferLiquidity<'info> {
+    /// The owner of both positions
+    pub nft_owner: Signer<'info>,
+
+    /// The token account for the position the liquidity is moved from
+    #[account(
+        constraint = from_nft_account.mint == from_position.nft_mint,
+        constraint = from_nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub from_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, constraint = from_position.pool_id == pool_state.key())]
+    pub from_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// The token account for the position the liquidity is moved to
+    #[account(
+        constraint = to_nft_account.mint == to_position.nft_mint,
+        constraint = to_nft_account.amount == 1,
+        token::authority = nft_owner,
+    )]
+    pub to_nft_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = to_position.pool_id == pool_state.key(),
+        constraint = to_position.key() != from_position.key(),
+        constraint = to_position.tick_lower_index == from_position.tick_lower_index
+            && to_position.tick_upper_index == from_position.tick_upper_index
+            @ ErrorCode::InvalidTickIndex,
+    )]
+    pub to_position: Box<Account<'info, PersonalPositionState>>,
+
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: both support fix-tick-array and dynamic-tick-array, read only
+    pub tick_array_upper: UncheckedAccount<'info>,
+}
+
+/// The ticks and the pool liquidity are unchanged, only the two positions are updated: both accrue their fees
+/// and rewards up to now, so that they keep the same growth snapshots, then the liquidity is moved.
+pub fn transfer_liquidity(ctx: Context<TransferLiquidity>, liquidity: u128) -> Result<()> {
+    require_gt!(liquidity, 0, ErrorCode::InvalidLiquidity);
+    ctx.accounts.from_position.check_not_collateralized()?;
+    ctx.accounts.to_position.check_not_collateralized()?;
+    let pool_id = ctx.accounts.pool_state.key();
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let tick_spacing = pool_state.tick_spacing;
+    let from_position = &mut ctx.accounts.from_position;
+    let to_position = &mut ctx.accounts.to_position;
+    // the liquidity can't be decreased earlier through another position
+    pool_state.check_position_age(from_position.last_increase_slot, get_slot()?)?;
+
+    let tick_lower_index = from_position.tick_lower_index;
+    let tick_upper_index = from_position.tick_upper_index;
+    let tick_lower = TickArrayContainer::try_from_pool(
+        &ctx.accounts.tick_array_lower.to_account_info(),
+        &pool_id,
+        tick_lower_index,
+        tick_spacing,
+    )?
+    .get_tick_state(tick_lower_index, tick_spacing)?;
+    let tick_upper = TickArrayContainer::try_from_pool(
+        &ctx.accounts.tick_array_upper.to_account_info(),
+        &pool_id,
+        tick_upper_index,
+        tick_spacing,
+    )?
+    .get_tick_state(tick_upper_index, tick_spacing)?;
+
+    let updated_reward_infos = pool_state.update_reward_infos(get_unix_timestamp()?)?;
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = TickUtils::get_fee_growth_inside(
+        &tick_lower,
+        &tick_upper,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    let reward_growths_inside = TickUtils::get_reward_growths_inside(
+        &tick_lower,
+        &tick_upper,
+        pool_state.tick_current,
+        &updated_reward_infos,
+    );
+    let maker_rebate_growth_boundary_x64 =
+        TickUtils::get_maker_rebate_growth_boundary(&tick_lower, &tick_upper);
+    let recent_epoch = get_recent_epoch()?;
+
+    require_gte!(
+        from_position.liquidity,
+        liquidity,
+        ErrorCode::InvalidLiquidity
+    );
+    from_position.decrease_liquidity(
+        liquidity,
+        fee_growth_inside_0_x64,
+        fee_growth_inside_1_x64,
+        reward_growths_inside,
+        maker_rebate_growth_boundary_x64,
+        recent_epoch,
+    )?;
+    to_position.increase_liquidity(
+        liquidity,
+        fee_growth_inside_0_x64,
+        fee_growth_inside_1_x64,
+        reward_growths_inside,
+        maker_rebate_growth_boundary_x64,
+        recent_epoch,
+    )?;
+    to_position.last_increase_slot = to_position
+        .last_increase_slot
+        .max(from_position.last_increase_slot);
+
+    emit!(LiquidityTransferredEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        from_position_nft_mint: from_position.nft_mint,
+        to_position_nft_mint: to_position.nft_mint,
+        liquidity,
+        event_seq: pool_state.next_event_seq(),
+    });
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::cranks::{settle_crank, CrankKind};
+use crate::states::*;
+use crate::util::get_unix_timestamp;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+#[derive(Accounts)]
+pub struct UpdatePoolApr<'info> {
+    /// Writable to stamp the sequence number of the crank receipt
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(
+        mut,
+        seeds = [
+            POOL_APR_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = pool_apr_state.load()?.bump,
+    )]
+    pub pool_apr_state: AccountLoader<'info, PoolAprState>,
+
+    #[account(address = pool_state.load()?.token_vault_0)]
+    pub token_vault_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.load()?.token_vault_1)]
+    pub token_vault_1: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Permissionless crank, snapshots the cumulative LP fees of the pool at most once per `FEE_SNAPSHOT_INTERVAL`
+/// and updates the trailing fee APRs against the vault balances net of the fee liabilities
+pub fn update_pool_apr(ctx: Context<UpdatePoolApr>) -> Result<()> {
+    let mut pool_state = ctx.accounts.pool_state.load_mut()?;
+    let (fee_liabilities_0, fee_liabilities_1) = pool_state.fee_liabilities();
+    let mut pool_apr_state = ctx.accounts.pool_apr_state.load_mut()?;
+    pool_apr_state.update(
+        FeeSnapshot {
+            timestamp: get_unix_timestamp()?,
+            total_fees_token_0: pool_state.total_fees_token_0,
+            total_fees_token_1: pool_state.total_fees_token_1,
+        },
+        pool_state.sqrt_price_x64,
+        ctx.accounts
+            .token_vault_0
+            .amount
+            .saturating_sub(fee_liabilities_0),
+        ctx.accounts
+            .token_vault_1
+            .amount
+            .saturating_sub(fee_liabilities_1),
+    );
+    settle_crank(
+        CrankKind::UpdatePoolApr,
+        ctx.accounts.pool_state.key(),
+        &mut pool_state,
+        ctx.remaining_accounts,
+    )
+}
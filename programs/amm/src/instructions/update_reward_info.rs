@@ -1,4 +1,6 @@
+use crate::cranks::{settle_crank, CrankKind};
 use crate::states::*;
+use crate::util::get_unix_timestamp;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -11,14 +13,19 @@ pub struct UpdateRewardInfos<'info> {
 pub fn update_reward_infos<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, UpdateRewardInfos<'info>>,
 ) -> Result<()> {
-    let clock = Clock::get()?;
+    let block_timestamp = get_unix_timestamp()?;
     let mut pool_state = ctx.accounts.pool_state.load_mut()?;
-    let updated_reward_infos =
-        pool_state.update_reward_infos(u64::try_from(clock.unix_timestamp).unwrap())?;
+    let updated_reward_infos = pool_state.update_reward_infos(block_timestamp)?;
 
     emit!(UpdateRewardInfosEvent {
-        reward_growth_global_x64: RewardInfo::get_reward_growths(&updated_reward_infos)
+        reward_growth_global_x64: RewardInfo::get_reward_growths(&updated_reward_infos),
+        event_seq: pool_state.next_event_seq(),
     });
 
-    Ok(())
+    settle_crank(
+        CrankKind::UpdateRewardInfos,
+        ctx.accounts.pool_state.key(),
+        &mut pool_state,
+        ctx.remaining_accounts,
+    )
 }
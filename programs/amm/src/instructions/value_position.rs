@@ -0,0 +1,198 @@
+use super::calculate_latest_token_fees;
+use crate::error::ErrorCode;
+use crate::interface::PositionValuation;
+use crate::libraries::liquidity_math;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ValuePosition<'info> {
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    #[account(constraint = personal_position.pool_id == pool_state.key())]
+    pub personal_position: Box<Account<'info, PersonalPositionState>>,
+
+    /// CHECK: Loaded by `TickArrayContainer`, must contain the lower tick of the position
+    pub tick_array_lower: UncheckedAccount<'info>,
+
+    /// CHECK: Loaded by `TickArrayContainer`, must contain the upper tick of the position
+    pub tick_array_upper: UncheckedAccount<'info>,
+}
+
+/// Read only, value the position at the current price of the pool
+pub fn value_position(ctx: Context<ValuePosition>) -> Result<PositionValuation> {
+    let pool_state = ctx.accounts.pool_state.load()?;
+    let personal_position = &ctx.accounts.personal_position;
+    let tick_lower_index = personal_position.tick_lower_index;
+    let tick_upper_index = personal_position.tick_upper_index;
+
+    let tick_array_lower = TickArrayContainer::try_from(
+        &ctx.accounts.tick_array_lower.to_account_info(),
+        tick_lower_index,
+        pool_state.tick_spacing,
+    )?;
+    let tick_array_upper = TickArrayContainer::try_from(
+        &ctx.accounts.tick_array_upper.to_account_info(),
+        tick_upper_index,
+        pool_state.tick_spacing,
+    )?;
+    let pool_id = ctx.accounts.pool_state.key();
+    require_keys_eq!(
+        tick_array_lower.get_pool_id()?,
+        pool_id,
+        ErrorCode::InvalidTickArray
+    );
+    require_keys_eq!(
+        tick_array_upper.get_pool_id()?,
+        pool_id,
+        ErrorCode::InvalidTickArray
+    );
+
+    get_position_valuation(
+        &pool_state,
+        personal_position,
+        &tick_array_lower.get_tick_state(tick_lower_index, pool_state.tick_spacing)?,
+        &tick_array_upper.get_tick_state(tick_upper_index, pool_state.tick_spacing)?,
+    )
+}
+
+/// The token amounts of the liquidity and the uncollected fees of a position, the fees are accrued
+/// the same as `PersonalPositionState::decrease_liquidity` would, including the maker rebates
+pub fn get_position_valuation(
+    pool_state: &PoolState,
+    personal_position: &PersonalPositionState,
+    tick_lower: &TickState,
+    tick_upper: &TickState,
+) -> Result<PositionValuation> {
+    let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+        pool_state.tick_current,
+        pool_state.sqrt_price_x64,
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        -i128::try_from(personal_position.liquidity).map_err(|_| ErrorCode::CalculateOverflow)?,
+    )?;
+
+    let (fee_growth_inside_0_x64, fee_growth_inside_1_x64) = TickUtils::get_fee_growth_inside(
+        tick_lower,
+        tick_upper,
+        pool_state.tick_current,
+        pool_state.fee_growth_global_0_x64,
+        pool_state.fee_growth_global_1_x64,
+    );
+    let maker_rebate_growth_boundary_x64 =
+        TickUtils::get_maker_rebate_growth_boundary(tick_lower, tick_upper);
+    let liquidity = personal_position.liquidity;
+    let fees_owed_0 = calculate_latest_token_fees(
+        calculate_latest_token_fees(
+            personal_position.token_fees_owed_0,
+            personal_position.fee_growth_inside_0_last_x64,
+            fee_growth_inside_0_x64,
+            liquidity,
+        ),
+        personal_position.maker_rebate_growth_boundary_last_x64[0],
+        maker_rebate_growth_boundary_x64[0],
+        liquidity,
+    );
+    let fees_owed_1 = calculate_latest_token_fees(
+        calculate_latest_token_fees(
+            personal_position.token_fees_owed_1,
+            personal_position.fee_growth_inside_1_last_x64,
+            fee_growth_inside_1_x64,
+            liquidity,
+        ),
+        personal_position.maker_rebate_growth_boundary_last_x64[1],
+        maker_rebate_growth_boundary_x64[1],
+        liquidity,
+    );
+
+    Ok(PositionValuation {
+        amount_0,
+        amount_1,
+        fees_owed_0,
+        fees_owed_1,
+        sqrt_price_x64: pool_state.sqrt_price_x64,
+        tick_current: pool_state.tick_current,
+    })
+}
+
+#[cfg(test)]
+mod value_position_test {
+    use super::*;
+    use crate::libraries::{fixed_point_64, tick_math};
+    use crate::states::pool_test::build_pool;
+
+    #[test]
+    fn get_position_valuation_test() {
+        let liquidity = 1_000_000_000u128;
+        let pool_state = build_pool(0, 60, tick_math::get_sqrt_price_at_tick_raw(0).unwrap(), 0);
+        let mut pool_state = pool_state.borrow_mut();
+        pool_state.fee_growth_global_0_x64 = 3 * fixed_point_64::Q64;
+        pool_state.fee_growth_global_1_x64 = 5 * fixed_point_64::Q64;
+
+        let mut personal_position = PersonalPositionState {
+            tick_lower_index: -600,
+            tick_upper_index: 600,
+            liquidity,
+            fee_growth_inside_0_last_x64: fixed_point_64::Q64,
+            fee_growth_inside_1_last_x64: fixed_point_64::Q64,
+            token_fees_owed_0: 10,
+            token_fees_owed_1: 20,
+            ..Default::default()
+        };
+
+        let tick_lower = TickState {
+            tick: -600,
+            ..Default::default()
+        };
+        let mut tick_upper = TickState {
+            tick: 600,
+            ..Default::default()
+        };
+        // a maker rebate paid to the positions bounded by the upper tick
+        tick_upper.maker_rebate_growth_0_x64 = fixed_point_64::Q64;
+
+        let valuation =
+            get_position_valuation(&pool_state, &personal_position, &tick_lower, &tick_upper)
+                .unwrap();
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+            0,
+            pool_state.sqrt_price_x64,
+            -600,
+            600,
+            -(liquidity as i128),
+        )
+        .unwrap();
+        assert_eq!(valuation.amount_0, amount_0);
+        assert_eq!(valuation.amount_1, amount_1);
+        // all the fee growth is inside the range
+        assert_eq!(
+            valuation.fees_owed_0,
+            10 + 2 * liquidity as u64 + liquidity as u64
+        );
+        assert_eq!(valuation.fees_owed_1, 20 + 4 * liquidity as u64);
+        assert_eq!(valuation.tick_current, 0);
+
+        // the same fees as an update of the position
+        personal_position
+            .decrease_liquidity(
+                0,
+                3 * fixed_point_64::Q64,
+                5 * fixed_point_64::Q64,
+                [0; REWARD_NUM],
+                [fixed_point_64::Q64, 0],
+                0,
+            )
+            .unwrap();
+        assert_eq!(personal_position.token_fees_owed_0, valuation.fees_owed_0);
+        assert_eq!(personal_position.token_fees_owed_1, valuation.fees_owed_1);
+
+        // out of range, the position is all token_1
+        pool_state.tick_current = 1200;
+        pool_state.sqrt_price_x64 = tick_math::get_sqrt_price_at_tick_raw(1200).unwrap();
+        let valuation =
+            get_position_valuation(&pool_state, &personal_position, &tick_lower, &tick_upper)
+                .unwrap();
+        assert_eq!(valuation.amount_0, 0);
+        assert!(valuation.amount_1 > amount_1);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct WithdrawTickRentTreasury<'info> {
+    /// The pool owner, who receives the withdrawn lamports
+    #[account(
+        mut,
+        address = pool_state.load()?.owner @ ErrorCode::NotApproved
+    )]
+    pub owner: Signer<'info>,
+
+    /// The pool whose tick rent treasury is withdrawn from
+    #[account(mut)]
+    pub pool_state: AccountLoader<'info, PoolState>,
+
+    /// The treasury of the pool, it keeps its rent exemption
+    #[account(
+        mut,
+        seeds = [
+            TICK_RENT_TREASURY_SEED.as_bytes(),
+            pool_state.key().as_ref(),
+        ],
+        bump = tick_rent_treasury.bump,
+    )]
+    pub tick_rent_treasury: Account<'info, TickRentTreasury>,
+}
+
+pub fn withdraw_tick_rent_treasury(
+    ctx: Context<WithdrawTickRentTreasury>,
+    amount_requested: u64,
+) -> Result<()> {
+    require_gt!(amount_requested, 0);
+
+    let treasury_info = ctx.accounts.tick_rent_treasury.to_account_info();
+    let amount = amount_requested.min(TickRentTreasury::surplus_lamports(&treasury_info)?);
+    if amount > 0 {
+        **treasury_info.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.owner.try_borrow_mut_lamports()? += amount;
+    }
+
+    let treasury = &mut ctx.accounts.tick_rent_treasury;
+    treasury.total_withdrawn = treasury.total_withdrawn.checked_add(amount).unwrap();
+
+    emit!(WithdrawTickRentTreasuryEvent {
+        pool_state: ctx.accounts.pool_state.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+        total_withdrawn: treasury.total_withdrawn,
+        event_seq: ctx.accounts.pool_state.load_mut()?.next_event_seq(),
+    });
+
+    Ok(())
+}
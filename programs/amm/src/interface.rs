@@ -0,0 +1,187 @@
+//! Types shared with the programs calling this program through CPI
+use crate::states::FEE_RATE_DENOMINATOR_VALUE;
+use anchor_lang::prelude::*;
+
+/// The return data of the swap instructions, so that aggregator programs get the execution of a swap
+/// with `get_return_data` instead of parsing the logs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    /// The input token amount paid by the user, including the transfer fee
+    pub amount_in: u64,
+    /// The output token amount received by the user, excluding the transfer fee
+    pub amount_out: u64,
+    /// The trade fee paid in input token, including the protocol and fund fee
+    pub fee_amount: u64,
+    /// The part of `fee_amount` taken by the protocol
+    pub protocol_fee: u64,
+    /// The part of `fee_amount` taken by the fund
+    pub fund_fee: u64,
+    /// `fee_amount` over the input amount swapped in the pool, in hundredths of a bip (10^-6)
+    pub effective_fee_rate: u32,
+}
+
+impl SwapResult {
+    /// # Arguments
+    ///
+    /// * `amount_in` - The input token amount paid by the user
+    /// * `amount_out` - The output token amount received by the user
+    /// * `pool_amount_in` - The input token amount swapped in the pool, excluding the transfer fee
+    /// * `fees_before` - The (lp, protocol, fund) fees of the input token accumulated by the pool before the swap
+    /// * `fees_after` - The (lp, protocol, fund) fees of the input token accumulated by the pool after the swap
+    ///
+    pub fn new(
+        amount_in: u64,
+        amount_out: u64,
+        pool_amount_in: u64,
+        fees_before: [u64; 3],
+        fees_after: [u64; 3],
+    ) -> Self {
+        let lp_fee = fees_after[0] - fees_before[0];
+        let protocol_fee = fees_after[1] - fees_before[1];
+        let fund_fee = fees_after[2] - fees_before[2];
+        let fee_amount = lp_fee + protocol_fee + fund_fee;
+        let effective_fee_rate = if pool_amount_in == 0 {
+            0
+        } else {
+            (u128::from(fee_amount) * u128::from(FEE_RATE_DENOMINATOR_VALUE)
+                / u128::from(pool_amount_in)) as u32
+        };
+        Self {
+            amount_in,
+            amount_out,
+            fee_amount,
+            protocol_fee,
+            fund_fee,
+            effective_fee_rate,
+        }
+    }
+
+    /// The amount checked against `other_amount_threshold`,
+    /// the output amount of a base input swap, otherwise the input amount
+    pub fn other_amount(&self, is_base_input: bool) -> u64 {
+        if is_base_input {
+            self.amount_out
+        } else {
+            self.amount_in
+        }
+    }
+}
+
+/// An order of `swap_batch`, executed like a `swap_v2` with the same arguments
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapOrder {
+    /// Swap token_0 for token_1 if true, otherwise token_1 for token_0
+    pub zero_for_one: bool,
+    /// The input amount of a base input swap, otherwise the output amount
+    pub amount: u64,
+    /// The min output amount of a base input swap, otherwise the max input amount
+    pub other_amount_threshold: u64,
+    /// The Q64.64 sqrt price limit, zero for no limit
+    pub sqrt_price_limit_x64: u128,
+    pub is_base_input: bool,
+}
+
+/// How the pool sqrt price is compared to the sqrt price of a `PriceCondition`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriceComparator {
+    /// The pool sqrt price must be greater than or equal to the condition sqrt price
+    Gte,
+    /// The pool sqrt price must be less than or equal to the condition sqrt price
+    Lte,
+}
+
+/// The condition of `swap_if_price`, checked against the pool sqrt price before the swap,
+/// so that keepers can execute limit orders without an order book account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PriceCondition {
+    /// The Q64.64 sqrt price the pool sqrt price is compared to
+    pub sqrt_price_x64: u128,
+    pub comparator: PriceComparator,
+    /// Fail if the condition is not met, otherwise the swap is skipped and the instruction succeeds
+    pub fail_if_not_met: bool,
+}
+
+impl PriceCondition {
+    pub fn is_met(&self, pool_sqrt_price_x64: u128) -> bool {
+        match self.comparator {
+            PriceComparator::Gte => pool_sqrt_price_x64 >= self.sqrt_price_x64,
+            PriceComparator::Lte => pool_sqrt_price_x64 <= self.sqrt_price_x64,
+        }
+    }
+}
+
+/// The return data of `value_position`, so that lending programs can value a position NFT as collateral
+/// without re-implementing the CLMM math
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionValuation {
+    /// The token_0 amount of the liquidity at the current price, rounded down
+    pub amount_0: u64,
+    /// The token_1 amount of the liquidity at the current price, rounded down
+    pub amount_1: u64,
+    /// The uncollected token_0 fees, including the fees accrued since the last update of the position
+    pub fees_owed_0: u64,
+    /// The uncollected token_1 fees, including the fees accrued since the last update of the position
+    pub fees_owed_1: u64,
+    /// The sqrt price of the pool the position is valued at
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+}
+
+/// The return data of `get_or_create_tick_array`, so that wallets can show the rent a position
+/// touching the tick will cost by simulating it before the user signs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickArrayRent {
+    /// The lamports added to the tick array account, by the payer and the tick rent treasury
+    pub rent_charged: u64,
+    /// The part of `rent_charged` paid by the payer
+    pub payer_rent: u64,
+    /// The tick array account data length after the instruction
+    pub data_len: u64,
+    /// Whether the tick array is a dynamic tick array, a fixed one is created with all its TickStates
+    pub is_dynamic: bool,
+}
+
+#[cfg(test)]
+mod interface_test {
+    use super::*;
+
+    #[test]
+    fn swap_result_test() {
+        let result = SwapResult::new(
+            1_000_100,
+            990_000,
+            1_000_000,
+            [10, 20, 30],
+            [2110, 230, 340],
+        );
+        assert_eq!(result.fee_amount, 2100 + 210 + 310);
+        assert_eq!(result.protocol_fee, 210);
+        assert_eq!(result.fund_fee, 310);
+        assert_eq!(result.effective_fee_rate, 2620);
+        assert_eq!(result.other_amount(true), 990_000);
+        assert_eq!(result.other_amount(false), 1_000_100);
+
+        // round trip of the return data
+        let mut data = Vec::new();
+        result.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), 8 * 5 + 4);
+        assert_eq!(SwapResult::try_from_slice(&data).unwrap(), result);
+    }
+
+    #[test]
+    fn price_condition_test() {
+        let mut condition = PriceCondition {
+            sqrt_price_x64: 1 << 64,
+            comparator: PriceComparator::Gte,
+            fail_if_not_met: false,
+        };
+        assert!(condition.is_met(1 << 64));
+        assert!(condition.is_met((1 << 64) + 1));
+        assert!(!condition.is_met((1 << 64) - 1));
+
+        condition.comparator = PriceComparator::Lte;
+        assert!(condition.is_met(1 << 64));
+        assert!(!condition.is_met((1 << 64) + 1));
+        assert!(condition.is_met((1 << 64) - 1));
+    }
+}
@@ -0,0 +1,419 @@
+use crate::quote::{
+    compute_swap_quote, deserialize_zero_copy, tick_array_address, MintTransferFees, TickArrayData,
+};
+use crate::states::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::{AccountDeserialize, ToAccountMetas};
+use anyhow::{anyhow, Context, Result};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, Quote, QuoteParams, Swap,
+    SwapAndAccountMetas, SwapMode, SwapParams,
+};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+
+/// How many initialized tick arrays are tracked on each side of the current price
+pub const TICK_ARRAYS_PER_DIRECTION: usize = 3;
+
+/// Implementation of the jupiter `Amm` interface for one pool
+#[derive(Clone)]
+pub struct ByrealClmmAmm {
+    key: Pubkey,
+    pool_state: PoolState,
+    amm_config: Option<AmmConfig>,
+    tickarray_bitmap_extension: Option<TickArrayBitmapExtension>,
+    tick_arrays: HashMap<Pubkey, TickArrayData>,
+    transfer_fees: MintTransferFees,
+    clock_ref: ClockRef,
+}
+
+impl ByrealClmmAmm {
+    /// The start index of the tick arrays a swap may cross, the current one first
+    fn tick_array_start_indexes(&self, zero_for_one: bool) -> Vec<i32> {
+        let pool_state = &self.pool_state;
+        let mut start_index =
+            TickUtils::get_array_start_index(pool_state.tick_current, pool_state.tick_spacing);
+        let mut start_indexes = vec![start_index];
+        while start_indexes.len() <= TICK_ARRAYS_PER_DIRECTION {
+            match pool_state.next_initialized_tick_array_start_index(
+                &self.tickarray_bitmap_extension,
+                start_index,
+                zero_for_one,
+            ) {
+                Ok(Some(next_start_index)) => {
+                    start_indexes.push(next_start_index);
+                    start_index = next_start_index;
+                }
+                // no more liquidity, or the bitmap extension is not loaded yet
+                _ => break,
+            }
+        }
+        start_indexes
+    }
+
+    fn tick_array_keys(&self, zero_for_one: bool) -> Vec<Pubkey> {
+        self.tick_array_start_indexes(zero_for_one)
+            .into_iter()
+            .map(|start_index| tick_array_address(&self.key, start_index, &crate::id()))
+            .collect()
+    }
+}
+
+impl Amm for ByrealClmmAmm {
+    fn from_keyed_account(keyed_account: &KeyedAccount, amm_context: &AmmContext) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let pool_state = deserialize_zero_copy::<PoolState>(&keyed_account.account.data)
+            .map_err(|e| anyhow!("invalid pool state {}: {}", keyed_account.key, e))?;
+        Ok(Self {
+            key: keyed_account.key,
+            pool_state,
+            amm_config: None,
+            tickarray_bitmap_extension: None,
+            tick_arrays: HashMap::new(),
+            transfer_fees: MintTransferFees::default(),
+            clock_ref: amm_context.clock_ref.clone(),
+        })
+    }
+
+    fn label(&self) -> String {
+        "Byreal CLMM".to_string()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        crate::id()
+    }
+
+    fn key(&self) -> Pubkey {
+        self.key
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![self.pool_state.token_mint_0, self.pool_state.token_mint_1]
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        let mut keys = vec![
+            self.key,
+            self.pool_state.amm_config,
+            self.pool_state.token_mint_0,
+            self.pool_state.token_mint_1,
+            TickArrayBitmapExtension::key(self.key, &crate::id()),
+        ];
+        keys.extend(self.tick_array_keys(true));
+        keys.extend(self.tick_array_keys(false));
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> Result<()> {
+        if let Some(account) = account_map.get(&self.key) {
+            self.pool_state = deserialize_zero_copy::<PoolState>(&account.data)
+                .map_err(|e| anyhow!("invalid pool state {}: {}", self.key, e))?;
+        }
+        let amm_config = account_map
+            .get(&self.pool_state.amm_config)
+            .context("amm config not found")?;
+        self.amm_config = Some(
+            AmmConfig::try_deserialize(&mut amm_config.data.as_slice())
+                .map_err(|e| anyhow!("invalid amm config: {}", e))?,
+        );
+        // the transfer fee of a Token-2022 mint is charged on both the input and the output
+        self.transfer_fees.transfer_fee_config_0 = account_map
+            .get(&self.pool_state.token_mint_0)
+            .and_then(|account| MintTransferFees::transfer_fee_config(&account.data));
+        self.transfer_fees.transfer_fee_config_1 = account_map
+            .get(&self.pool_state.token_mint_1)
+            .and_then(|account| MintTransferFees::transfer_fee_config(&account.data));
+        // the bitmap extension only exists if the pool has liquidity out of the default bitmap
+        self.tickarray_bitmap_extension = account_map
+            .get(&TickArrayBitmapExtension::key(self.key, &crate::id()))
+            .and_then(|account| deserialize_zero_copy(&account.data).ok());
+
+        let mut tick_arrays = HashMap::new();
+        for key in self
+            .tick_array_keys(true)
+            .into_iter()
+            .chain(self.tick_array_keys(false))
+        {
+            // the current tick array may not be initialized
+            let Some(account) = account_map.get(&key) else {
+                continue;
+            };
+            let tick_array = TickArrayData::try_deserialize(&account.data)
+                .map_err(|e| anyhow!("invalid tick array {}: {}", key, e))?;
+            tick_arrays.insert(key, tick_array);
+        }
+        self.tick_arrays = tick_arrays;
+        Ok(())
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> Result<Quote> {
+        let amm_config = self.amm_config.as_ref().context("amm config not loaded")?;
+        let zero_for_one = quote_params.input_mint == self.pool_state.token_mint_0;
+        let tick_arrays: Vec<TickArrayData> = self.tick_arrays.values().cloned().collect();
+        let block_timestamp = self.clock_ref.unix_timestamp.load(Ordering::Relaxed) as u64;
+        let transfer_fees = MintTransferFees {
+            epoch: self.clock_ref.epoch.load(Ordering::Relaxed),
+            ..self.transfer_fees
+        };
+
+        let swap_quote = compute_swap_quote(
+            amm_config,
+            &self.pool_state,
+            &tick_arrays,
+            &self.tickarray_bitmap_extension,
+            &transfer_fees,
+            quote_params.amount,
+            0,
+            zero_for_one,
+            quote_params.swap_mode == SwapMode::ExactIn,
+            block_timestamp,
+        )
+        .map_err(|e| anyhow!("quote failed: {}", e))?;
+        // swap_v2 without a sqrt price limit fails instead of filling partially
+        if swap_quote.is_partial_fill {
+            return Err(anyhow!("not enough liquidity for {}", quote_params.amount));
+        }
+
+        Ok(Quote {
+            in_amount: swap_quote.amount_in_after_transfer_fee,
+            out_amount: swap_quote.amount_out_after_transfer_fee,
+            fee_amount: swap_quote.fee_amount,
+            fee_mint: quote_params.input_mint,
+            ..Default::default()
+        })
+    }
+
+    fn get_swap_and_account_metas(&self, swap_params: &SwapParams) -> Result<SwapAndAccountMetas> {
+        let pool_state = &self.pool_state;
+        let zero_for_one = swap_params.source_mint == pool_state.token_mint_0;
+        let (input_vault, output_vault) = if zero_for_one {
+            (pool_state.token_vault_0, pool_state.token_vault_1)
+        } else {
+            (pool_state.token_vault_1, pool_state.token_vault_0)
+        };
+
+        // the router takes the program of the swap first
+        let mut account_metas = vec![AccountMeta::new_readonly(crate::id(), false)];
+        account_metas.extend(
+            crate::accounts::SwapSingleV2 {
+                payer: swap_params.token_transfer_authority,
+                amm_config: pool_state.amm_config,
+                pool_state: self.key,
+                input_token_account: swap_params.source_token_account,
+                output_token_account: swap_params.destination_token_account,
+                input_vault,
+                output_vault,
+                observation_state: pool_state.observation_key,
+                token_program: anchor_spl::token::ID,
+                token_program_2022: anchor_spl::token_2022::ID,
+                memo_program: anchor_spl::memo::ID,
+                input_vault_mint: swap_params.source_mint,
+                output_vault_mint: swap_params.destination_mint,
+            }
+            .to_account_metas(None),
+        );
+
+        // remaining accounts: bitmap extension, then the tick arrays in the swap direction
+        account_metas.push(AccountMeta::new_readonly(
+            TickArrayBitmapExtension::key(self.key, &crate::id()),
+            false,
+        ));
+        for key in self.tick_array_keys(zero_for_one) {
+            if self.tick_arrays.contains_key(&key) {
+                account_metas.push(AccountMeta::new(key, false));
+            }
+        }
+
+        Ok(SwapAndAccountMetas {
+            // the swap_v2 of this program has the accounts and arguments of the raydium clmm one,
+            // which the jupiter interface has no dedicated variant for
+            swap: Swap::RaydiumClmmV2,
+            account_metas,
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    /// The pools marked inactive by `sunset_pool` are deprioritized by the router
+    fn is_active(&self) -> bool {
+        !self.pool_state.is_inactive()
+    }
+}
+
+#[cfg(test)]
+mod jupiter_test {
+    use super::*;
+    use crate::quote::quote_test::build_quote_accounts;
+    use anchor_lang::{AccountSerialize, Discriminator};
+
+    fn zero_copy_bytes<T: bytemuck::Pod + Discriminator>(account: &T) -> Vec<u8> {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(account));
+        data
+    }
+
+    fn keyed_account(key: Pubkey, data: Vec<u8>) -> KeyedAccount {
+        let mut keyed_account = KeyedAccount {
+            key,
+            account: Default::default(),
+            params: None,
+        };
+        keyed_account.account.owner = crate::id();
+        keyed_account.account.data = data;
+        keyed_account
+    }
+
+    #[test]
+    fn quote_fixture_pool_test() {
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let pool_id = pool_state.key();
+        let block_timestamp = oracle::block_timestamp_mock();
+        let amm_context = AmmContext {
+            clock_ref: ClockRef::default(),
+        };
+        amm_context
+            .clock_ref
+            .unix_timestamp
+            .store(block_timestamp as i64, Ordering::Relaxed);
+
+        let pool_account = keyed_account(pool_id, zero_copy_bytes(&pool_state));
+        let mut amm = ByrealClmmAmm::from_keyed_account(&pool_account, &amm_context).unwrap();
+        assert_eq!(
+            amm.get_reserve_mints(),
+            vec![pool_state.token_mint_0, pool_state.token_mint_1]
+        );
+
+        // the fixture accounts, the mints and the bitmap extension are missing
+        let mut amm_config_data = Vec::new();
+        amm_config.try_serialize(&mut amm_config_data).unwrap();
+        let mut account_map = AccountMap::default();
+        account_map.insert(pool_id, pool_account.account.clone());
+        account_map.insert(
+            pool_state.amm_config,
+            keyed_account(pool_state.amm_config, amm_config_data).account,
+        );
+        for tick_array in tick_arrays.iter() {
+            let TickArrayData::Fixed(tick_array_state) = tick_array else {
+                unreachable!()
+            };
+            let key = tick_array_address(&pool_id, tick_array.start_tick_index(), &crate::id());
+            account_map.insert(
+                key,
+                keyed_account(key, zero_copy_bytes(tick_array_state.as_ref())).account,
+            );
+        }
+        let accounts_to_update = amm.get_accounts_to_update();
+        assert!(account_map
+            .keys()
+            .all(|key| accounts_to_update.contains(key)));
+        amm.update(&account_map).unwrap();
+
+        // the same quote as the program, through the tick arrays loaded by the update
+        let amount = 1_000_000;
+        let quote = amm
+            .quote(&QuoteParams {
+                amount,
+                input_mint: pool_state.token_mint_0,
+                output_mint: pool_state.token_mint_1,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .unwrap();
+        let swap_quote = compute_swap_quote(
+            &amm_config,
+            &pool_state,
+            &tick_arrays,
+            &None,
+            &MintTransferFees::default(),
+            amount,
+            0,
+            true,
+            true,
+            block_timestamp,
+        )
+        .unwrap();
+        assert!(!swap_quote.is_partial_fill);
+        assert_eq!(quote.in_amount, swap_quote.amount_in_after_transfer_fee);
+        assert_eq!(quote.out_amount, swap_quote.amount_out_after_transfer_fee);
+        assert!(quote.out_amount > 0);
+        assert_eq!(quote.fee_amount, swap_quote.fee_amount);
+        assert_eq!(quote.fee_mint, pool_state.token_mint_0);
+
+        // more than the loaded liquidity
+        assert!(amm
+            .quote(&QuoteParams {
+                amount: u64::MAX / 2,
+                input_mint: pool_state.token_mint_0,
+                output_mint: pool_state.token_mint_1,
+                swap_mode: SwapMode::ExactIn,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn swap_and_account_metas_test() {
+        let (_, pool_state, _) = build_quote_accounts();
+        let pool_id = pool_state.key();
+        let amm = ByrealClmmAmm::from_keyed_account(
+            &keyed_account(pool_id, zero_copy_bytes(&pool_state)),
+            &AmmContext {
+                clock_ref: ClockRef::default(),
+            },
+        )
+        .unwrap();
+        let payer = Pubkey::new_unique();
+        let source_token_account = Pubkey::new_unique();
+        let destination_token_account = Pubkey::new_unique();
+        let swap_and_account_metas = amm
+            .get_swap_and_account_metas(&SwapParams {
+                swap_mode: SwapMode::ExactIn,
+                in_amount: 1_000_000,
+                out_amount: 0,
+                source_mint: pool_state.token_mint_1,
+                destination_mint: pool_state.token_mint_0,
+                source_token_account,
+                destination_token_account,
+                token_transfer_authority: payer,
+                quote_mint_to_referrer: None,
+                jupiter_program_id: &Pubkey::new_unique(),
+                missing_dynamic_accounts_as_default: false,
+            })
+            .unwrap();
+        assert_eq!(swap_and_account_metas.swap, Swap::RaydiumClmmV2);
+
+        // the program, the accounts of swap_v2, then the bitmap extension
+        let keys: Vec<Pubkey> = swap_and_account_metas
+            .account_metas
+            .iter()
+            .map(|account_meta| account_meta.pubkey)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                crate::id(),
+                payer,
+                pool_state.amm_config,
+                pool_id,
+                source_token_account,
+                destination_token_account,
+                pool_state.token_vault_1,
+                pool_state.token_vault_0,
+                pool_state.observation_key,
+                anchor_spl::token::ID,
+                anchor_spl::token_2022::ID,
+                anchor_spl::memo::ID,
+                pool_state.token_mint_1,
+                pool_state.token_mint_0,
+                TickArrayBitmapExtension::key(pool_id, &crate::id()),
+            ]
+        );
+        assert!(!swap_and_account_metas.account_metas[0].is_writable);
+        assert!(swap_and_account_metas.account_metas[1].is_signer);
+    }
+}
@@ -1,14 +1,77 @@
-pub mod error;
+// the instruction handlers and their helpers take the accounts one by one
+#![allow(clippy::too_many_arguments)]
+// the idl instructions #[program] generates next to the module call AccountInfo::realloc
+#![allow(deprecated)]
+
+// quote-only is the anchor-free quote of the core crate, none of the program modules below
+#[cfg(feature = "quote-only")]
+pub use byreal_clmm_core::{error, libraries, quote, states, transfer_fee};
+
+#[cfg(feature = "program")]
+#[macro_use]
+mod logging;
+
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod compute_budget;
+#[cfg(feature = "program")]
+pub mod cranks;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod decay_fee_schedule;
+#[cfg(feature = "program")]
+pub use byreal_clmm_core::error;
+#[cfg(feature = "program")]
 pub mod instructions;
+#[cfg(feature = "program")]
+pub mod interface;
+#[cfg(feature = "jupiter")]
+pub mod jupiter;
+#[cfg(feature = "program")]
 pub mod libraries;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod liquidity_export;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod position_pnl;
+#[cfg(feature = "program")]
+pub mod program_ids;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod pruning;
+#[cfg(feature = "program")]
+pub mod quote;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod reconcile;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod reward_simulation;
+#[cfg(feature = "program")]
+pub mod schema;
+#[cfg(feature = "program")]
 pub mod states;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod state_folder;
+#[cfg(feature = "program")]
+pub mod swap_engine;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod tick_array_diff;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod tick_array_proof;
+#[cfg(feature = "program")]
+pub mod transfer_fee;
+#[cfg(all(feature = "program", any(test, feature = "client")))]
+pub mod tx_size;
+#[cfg(feature = "program")]
 pub mod util;
 
+#[cfg(feature = "program")]
 use anchor_lang::prelude::*;
-use core as core_;
+#[cfg(feature = "program")]
 use instructions::*;
+#[cfg(feature = "program")]
 use states::*;
 
+#[cfg(not(any(feature = "program", feature = "quote-only")))]
+compile_error!("the instructions need the anchor and token program crates of the program feature, only quote-only leaves them out");
+#[cfg(all(feature = "quote-only", feature = "program"))]
+compile_error!("quote-only leaves the program out, build it without the default program feature");
+
 #[cfg(not(feature = "no-entrypoint"))]
 solana_security_txt::security_txt! {
     name: "byreal-clmm",
@@ -20,27 +83,21 @@ solana_security_txt::security_txt! {
     auditors: "byreal"
 }
 
-#[cfg(feature = "localnet")]
+#[cfg(all(feature = "program", feature = "localnet"))]
 declare_id!("45iBNkaENereLKMjLm2LHkF3hpDapf6mnvrM5HWFg9cY");
-#[cfg(feature = "devnet")]
+#[cfg(all(feature = "program", feature = "devnet"))]
 declare_id!("45iBNkaENereLKMjLm2LHkF3hpDapf6mnvrM5HWFg9cY");
 
-#[cfg(all(not(feature = "localnet"), not(feature = "devnet")))]
+#[cfg(all(feature = "program", not(feature = "localnet"), not(feature = "devnet")))]
 declare_id!("REALQqNEomY6cQGZJUGwywTBD2UmDT32rZcNnfxQ5N2");
 
+#[cfg(feature = "program")]
 pub mod admin {
-    use super::{pubkey, Pubkey};
-    // Fh3a8NURkS4ihZjbsMvtFB4T2GGqs4FgLXSVw6nMexRN localnet-eoa-sig
-    // 9ZG4mYtayKedcDkRbpGAc13uQDT2Ag9twJBbuwia9Lqg devnet-multisig
-    // AY196f8U5EvM999PVnvLmyvaUnzL4GLiFaGKUgnJXN6o mainnet-multisig
-    #[cfg(feature = "localnet")]
-    pub const ID: Pubkey = pubkey!("Fh3a8NURkS4ihZjbsMvtFB4T2GGqs4FgLXSVw6nMexRN");
-    #[cfg(feature = "devnet")]
-    pub const ID: Pubkey = pubkey!("9ZG4mYtayKedcDkRbpGAc13uQDT2Ag9twJBbuwia9Lqg");
-    #[cfg(all(not(feature = "localnet"), not(feature = "devnet")))]
-    pub const ID: Pubkey = pubkey!("AY196f8U5EvM999PVnvLmyvaUnzL4GLiFaGKUgnJXN6o");
+    use super::Pubkey;
+    pub const ID: Pubkey = crate::program_ids::Cluster::CURRENT.admin();
 }
 
+#[cfg(feature = "program")]
 #[program]
 pub mod byreal_clmm {
 
@@ -62,6 +119,21 @@ pub mod byreal_clmm {
         instructions::update_amm_admin_group(ctx, params)
     }
 
+    /// Set the kill switch authority of an amm config, which can only disable or enable the swaps of
+    /// the pools of that config.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `kill_switch_authority`- The new kill switch authority, the default pubkey to remove it
+    ///
+    pub fn set_kill_switch_authority(
+        ctx: Context<SetKillSwitchAuthority>,
+        kill_switch_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::set_kill_switch_authority(ctx, kill_switch_authority)
+    }
+
     // The configuration of AMM protocol, include trade fee and protocol fee
     /// # Arguments
     ///
@@ -99,6 +171,21 @@ pub mod byreal_clmm {
         instructions::create_support_mint_associated(ctx)
     }
 
+    /// Allow or deny a support token22 mint to create pools and rewards with the mint extensions of the denylist,
+    /// such as the confidential transfer extensions of a mint which never enables them.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `allow_denied_extensions` - Whether the denylisted extensions of the mint are allowed
+    ///
+    pub fn update_support_mint_associated(
+        ctx: Context<UpdateSupportMintAssociated>,
+        allow_denied_extensions: bool,
+    ) -> Result<()> {
+        instructions::update_support_mint_associated(ctx, allow_denied_extensions)
+    }
+
     /// Updates the owner of the amm config
     /// Must be called by the current owner or admin
     ///
@@ -110,12 +197,29 @@ pub mod byreal_clmm {
     /// * `fund_fee_rate`- The new fund fee rate of amm config, be set when `param` is 2
     /// * `new_owner`- The config's new owner, be set when `param` is 3
     /// * `new_fund_owner`- The config's new fund owner, be set when `param` is 4
-    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4, otherwise will report a error
+    /// * `min_range_ticks`- The min width in ticks of new positions, be set when `param` is 5
+    /// * `max_range_ticks`- The max width in ticks of new positions, be set when `param` is 6
+    /// * `status`- The emergency status of all the pools of the config, with the bits of the pool status, be set when `param` is 7
+    /// * `default_prealloc_ticks`- The TickState slots allocated up front by new dynamic tick arrays, at most 60, be set when `param` is 8
+    /// * `max_swap_iterations`- The max steps of a swap in the pools of the config, at most 1024, 0 for the max, be set when `param` is 9
+    /// * `param`- The value can be 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9, otherwise will report a error
     ///
     pub fn update_amm_config(ctx: Context<UpdateAmmConfig>, param: u8, value: u32) -> Result<()> {
         instructions::update_amm_config(ctx, param, value)
     }
 
+    /// Disable or enable the swaps of all the pools of an amm config, only the kill switch authority of that config
+    /// can do it, which can't change anything else.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `disable_swap`- Whether the swap bit of the config status is set
+    ///
+    pub fn set_swap_kill_switch(ctx: Context<SetSwapKillSwitch>, disable_swap: bool) -> Result<()> {
+        instructions::set_swap_kill_switch(ctx, disable_swap)
+    }
+
     /// Creates a pool for the given token pair and the initial price
     ///
     /// # Arguments
@@ -143,6 +247,22 @@ pub mod byreal_clmm {
         instructions::create_pool_decay_fee(ctx, params)
     }
 
+    /// Creates a pool for the given token pair and the initial price with the parameters of a pool template
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts, the pool template must be the first remaining account
+    /// * `sqrt_price_x64` - the initial sqrt price (amount_token_1 / amount_token_0) of the pool as a Q64.64
+    /// * `open_time` - The open time of the pool, None to open immediately
+    ///
+    pub fn create_pool_from_template(
+        ctx: Context<CreatePool>,
+        sqrt_price_x64: u128,
+        open_time: Option<u64>,
+    ) -> Result<()> {
+        instructions::create_pool_from_template(ctx, sqrt_price_x64, open_time)
+    }
+
     /// Update pool status for given value
     ///
     /// # Arguments
@@ -154,6 +274,107 @@ pub mod byreal_clmm {
         instructions::update_pool_status(ctx, status)
     }
 
+    /// Set and clear the given status bits of a pool, keeping the others, with a permission check per bit.
+    /// The emergency manager may change any bit, the kill switch authority of the amm config of the pool
+    /// only the swap bit.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `set_mask` - The status bits to set, disabling their actions
+    /// * `clear_mask` - The status bits to clear, enabling their actions
+    ///
+    pub fn update_pool_status_bits<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, UpdatePoolStatusBits<'info>>,
+        set_mask: u8,
+        clear_mask: u8,
+    ) -> Result<()> {
+        instructions::update_pool_status_bits(ctx, set_mask, clear_mask)
+    }
+
+    /// Move a misconfigured pool to another amm config and its tick spacing, keeping its address,
+    /// only allowed while the pool has no liquidity and no tick array account, see `PoolState::tick_array_count`.
+    /// The empty tick arrays of the pool are not tracked by the bitmap and must be closed with
+    /// `close_tick_array` before, the fixed ones migrated with `migrate_tick_array_to_dynamic` first.
+    /// A pool created before the tick array count only needs empty bitmaps, its tick arrays are closed after
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `new_tick_spacing` - The new tick spacing of the pool, must be the one of the new amm config
+    ///
+    pub fn retier_pool(ctx: Context<RetierPool>, new_tick_spacing: u16) -> Result<()> {
+        instructions::retier_pool(ctx, new_tick_spacing)
+    }
+
+    /// Update the share of the LP fee paid to the positions bounded by a tick when a swap crosses it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `maker_rebate_rate` - The maker rebate rate, in hundredths of a bip (10^-6), 0 to disable
+    ///
+    pub fn update_pool_maker_rebate(
+        ctx: Context<UpdatePoolMakerRebate>,
+        maker_rebate_rate: u32,
+    ) -> Result<()> {
+        instructions::update_pool_maker_rebate(ctx, maker_rebate_rate)
+    }
+
+    /// Update the min slots between a liquidity increase of a position and its next decrease or fee collection
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `min_position_age_slots` - The min position age in slots, 0 to disable
+    ///
+    pub fn update_pool_min_position_age(
+        ctx: Context<UpdatePoolMinPositionAge>,
+        min_position_age_slots: u16,
+    ) -> Result<()> {
+        instructions::update_pool_min_position_age(ctx, min_position_age_slots)
+    }
+
+    /// Mark a pool without swaps or liquidity changes for `dormancy_seconds` inactive,
+    /// an inactive pool takes no new deposits, the positions can still be withdrawn
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `dormancy_seconds` - The dormancy required, at least `MIN_SUNSET_DORMANCY_SECONDS`
+    ///
+    pub fn sunset_pool(ctx: Context<SunsetPool>, dormancy_seconds: u64) -> Result<()> {
+        instructions::sunset_pool(ctx, dormancy_seconds)
+    }
+
+    /// Reseed the fee and reward growths outside of an uninitialized tick from the pool's global values,
+    /// a tick allocated but never initialized may hold stale growths outside
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `tick_index` - The index of the tick to repair
+    ///
+    pub fn repair_tick(ctx: Context<RepairTick>, tick_index: i32) -> Result<()> {
+        instructions::repair_tick(ctx, tick_index)
+    }
+
+    /// Create a pool template, so that pools are created with consistent parameters
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `index` - The index of the pool template
+    /// * `params` - The decay fee, maker rebate and status parameters of the pools
+    ///
+    pub fn create_pool_template(
+        ctx: Context<CreatePoolTemplate>,
+        index: u16,
+        params: PoolTemplateParams,
+    ) -> Result<()> {
+        instructions::create_pool_template(ctx, index, params)
+    }
+
     /// Creates an operation account for the program
     ///
     /// # Arguments
@@ -228,7 +449,7 @@ pub mod byreal_clmm {
         instructions::collect_remaining_rewards(ctx, reward_index)
     }
 
-    /// Update rewards info of the given pool, can be called for everyone
+    /// Update rewards info of the given pool, can be called for everyone, see `cranks` for the keeper bounty
     ///
     /// # Arguments
     ///
@@ -323,6 +544,175 @@ pub mod byreal_clmm {
         instructions::collect_fund_fee(ctx, amount_0_requested, amount_1_requested)
     }
 
+    /// Prepay the rent of the pool's dynamic tick arrays, only the pool creator can fund the treasury.
+    /// When the treasury is passed in the remaining accounts of open position instructions,
+    /// the rent of created or grown tick arrays is drawn from it instead of the payer.
+    /// The pool owner can take back what is left with `withdraw_tick_rent_treasury`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The lamports to deposit into the treasury
+    ///
+    pub fn fund_tick_rent_treasury(ctx: Context<FundTickRentTreasury>, amount: u64) -> Result<()> {
+        instructions::fund_tick_rent_treasury(ctx, amount)
+    }
+
+    /// Withdraw the prepaid tick array rent left in the pool's treasury, only the pool owner can withdraw.
+    /// The treasury keeps its rent exemption, the withdrawn amount is capped at the lamports above it.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_requested` - The max lamports to withdraw from the treasury
+    ///
+    pub fn withdraw_tick_rent_treasury(
+        ctx: Context<WithdrawTickRentTreasury>,
+        amount_requested: u64,
+    ) -> Result<()> {
+        instructions::withdraw_tick_rent_treasury(ctx, amount_requested)
+    }
+
+    /// Pre-allocate TickStates in a dynamic tick array with one reallocation,
+    /// positions touching new ticks in this tick array use the reserved space instead of growing the account.
+    /// The tick rent treasury of the pool only pays the rent if the payer is the pool owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_array_start_index` - The start index of the tick array
+    /// * `count` - The number of TickStates to reserve
+    ///
+    pub fn reserve_ticks<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ReserveTicks<'info>>,
+        tick_array_start_index: i32,
+        count: u8,
+    ) -> Result<()> {
+        instructions::reserve_ticks(ctx, tick_array_start_index, count)
+    }
+
+    /// Create the tick array of `access_tick_index` if it doesn't exist, and allocate its TickState in a dynamic
+    /// tick array, as opening a position does. Returns the rent charged and the account size through the return data.
+    /// The tick rent treasury of the pool only pays the rent if the payer is the pool owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `tick_array_start_index` - The start index of the tick array
+    /// * `access_tick_index` - The tick index a position will use in this tick array
+    /// * `prealloc_tick_count` - The TickState slots allocated if a dynamic tick array is created,
+    ///   at most the pool's `prealloc_tick_count`, which is used if none
+    ///
+    pub fn get_or_create_tick_array<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, GetOrCreateTickArray<'info>>,
+        tick_array_start_index: i32,
+        access_tick_index: i32,
+        prealloc_tick_count: Option<u8>,
+    ) -> Result<interface::TickArrayRent> {
+        instructions::get_or_create_tick_array(
+            ctx,
+            tick_array_start_index,
+            access_tick_index,
+            prealloc_tick_count,
+        )
+    }
+
+    /// Scan a fixed tick array and report how many of its TickStates are initialized,
+    /// and the rent freed by migrating it to a dynamic tick array, through the return data and an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn audit_tick_array(ctx: Context<AuditTickArray>) -> Result<TickArrayAudit> {
+        instructions::audit_tick_array(ctx)
+    }
+
+    /// Close a dynamic tick array without initialized ticks and send its rent to the rent receiver.
+    /// The tick array bitmap extension is passed in the remaining accounts if the tick array is beyond the default bitmap.
+    /// Also reclaims the tick arrays left at the old tick spacing of a pool retiered before the tick array count.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn close_tick_array<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseTickArray<'info>>,
+    ) -> Result<()> {
+        instructions::close_tick_array(ctx)
+    }
+
+    /// Rewrite a fixed tick array in place as a dynamic tick array holding only its initialized ticks,
+    /// and refund the rent freed by the shrink to the fee keeper. Anyone can migrate a tick array.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn migrate_tick_array_to_dynamic(ctx: Context<MigrateTickArrayToDynamic>) -> Result<()> {
+        instructions::migrate_tick_array_to_dynamic(ctx)
+    }
+
+    /// Experimental, shrink a fixed tick array far out of range into a merkle commitment of its ticks
+    /// and refund its rent. The swaps and the positions using the tick array fail until it is thawed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    #[cfg(feature = "tick-array-compression")]
+    pub fn freeze_tick_array(ctx: Context<FreezeTickArray>) -> Result<()> {
+        instructions::freeze_tick_array(ctx)
+    }
+
+    /// Experimental, restore a tick array frozen by `freeze_tick_array`, from the ticks of its
+    /// `TickArrayFrozenEvent` verified against the commitment
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `ticks` - The non empty ticks of the tick array
+    ///
+    #[cfg(feature = "tick-array-compression")]
+    pub fn thaw_tick_array(ctx: Context<ThawTickArray>, ticks: Vec<FrozenTick>) -> Result<()> {
+        instructions::thaw_tick_array(ctx, ticks)
+    }
+
+    /// Copy the position width limits and the tick pre-allocation count of the amm config to the pool,
+    /// so that the limits updated by `update_amm_config` also apply to the existing pools.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn sync_pool_range_limits(ctx: Context<SyncPoolRangeLimits>) -> Result<()> {
+        instructions::sync_pool_range_limits(ctx)
+    }
+
+    /// Clear the decay fee flag of a pool past its decay fee end time, the next swap clears it otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn clear_decay_fee(ctx: Context<ClearDecayFee>) -> Result<()> {
+        instructions::clear_decay_fee(ctx)
+    }
+
+    /// Grow the observation account of a pool to record the TWAP observations in a longer ring buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `extension_cardinality_next` - The number of extension observations after the fixed observations
+    ///
+    pub fn increase_observation_cardinality(
+        ctx: Context<IncreaseObservationCardinality>,
+        extension_cardinality_next: u16,
+    ) -> Result<()> {
+        instructions::increase_observation_cardinality(ctx, extension_cardinality_next)
+    }
+
     /// #[deprecated(note = "Use `open_position_with_token22_nft` instead.")]
     /// Creates a new position wrapped in a NFT
     ///
@@ -444,6 +834,73 @@ pub mod byreal_clmm {
         )
     }
 
+    /// Creates a new position wrapped in a Token2022 NFT with the max liquidity not exceeding the exact amounts of token_0 and token_1, only the amounts needed by the liquidity are transferred.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, same as `open_position_with_token22_nft`
+    /// * `tick_lower_index` - The low boundary of market
+    /// * `tick_upper_index` - The upper boundary of market
+    /// * `tick_array_lower_start_index` - The start index of tick array which include tick low
+    /// * `tick_array_upper_start_index` - The start index of tick array which include tick upper
+    /// * `amount_0` - The exact amount of token_0 the liquidity can't exceed
+    /// * `amount_1` - The exact amount of token_1 the liquidity can't exceed
+    /// * `max_price_deviation_bps` - The tolerance of the price implied by the amounts to the pool price, in bps
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    ///
+    pub fn open_position_with_exact_amounts<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenPositionWithToken22Nft<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount_0: u64,
+        amount_1: u64,
+        max_price_deviation_bps: u16,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::open_position_with_exact_amounts(
+            ctx,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount_0,
+            amount_1,
+            max_price_deviation_bps,
+            with_metadata,
+        )
+    }
+
+    /// Creates a new position wrapped in a Token2022 NFT over the widest range of the pool, between the min and max usable ticks of its tick spacing.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The liquidity to be added, if zero, and the base_flag is specified, calculate liquidity base amount_0_max or amount_1_max according base_flag, otherwise open position with zero liquidity
+    /// * `amount_0_max` - The max amount of token_0 to spend, which serves as a slippage check
+    /// * `amount_1_max` - The max amount of token_1 to spend, which serves as a slippage check
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata
+    /// * `base_flag` - if the liquidity specified as zero, true: calculate liquidity base amount_0_max otherwise base amount_1_max
+    ///
+    pub fn open_full_range_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, OpenFullRangePosition<'info>>,
+        liquidity: u128,
+        amount_0_max: u64,
+        amount_1_max: u64,
+        with_metadata: bool,
+        base_flag: Option<bool>,
+    ) -> Result<()> {
+        instructions::open_full_range_position(
+            ctx,
+            liquidity,
+            amount_0_max,
+            amount_1_max,
+            with_metadata,
+            base_flag,
+        )
+    }
+
     /// Close the user's position and NFT account. If the NFT mint belongs to token2022, it will also be closed and the funds returned to the NFT owner.
     ///
     /// # Arguments
@@ -536,34 +993,99 @@ pub mod byreal_clmm {
         instructions::decrease_liquidity_v2(ctx, liquidity, amount_0_min, amount_1_min)
     }
 
-    /// #[deprecated(note = "Use `swap_v2` instead.")]
-    /// Swaps one token for as much as possible of another token across a single pool
+    /// Move all liquidity of a position to a new position of another pool with the same token pair (e.g. another fee tier) atomically.
+    /// The withdrawn liquidity and fees can be rebalanced by a swap in the target pool before they are deposited,
+    /// the source position is left empty to be closed by its owner.
     ///
     /// # Arguments
     ///
     /// * `ctx` - The context of accounts
-    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
-    /// * `other_amount_threshold` - For slippage check
-    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
-    /// * `is_base_input` - swap base input or swap base output
-    ///
-    pub fn swap<'a, 'b, 'c: 'info, 'info>(
-        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
-        amount: u64,
-        other_amount_threshold: u64,
-        sqrt_price_limit_x64: u128,
-        is_base_input: bool,
+    /// * `tick_lower_index` - The low boundary of the target position
+    /// * `tick_upper_index` - The upper boundary of the target position
+    /// * `tick_array_lower_start_index` - The start index of the tick array of the target position's lower tick
+    /// * `tick_array_upper_start_index` - The start index of the tick array of the target position's upper tick
+    /// * `amount_0_min` - The minimum amount of token_0 that should be withdrawn from the source position
+    /// * `amount_1_min` - The minimum amount of token_1 that should be withdrawn from the source position
+    /// * `swap_amount` - The input amount of the rebalance swap in target pool, zero means no swap
+    /// * `swap_other_amount_threshold` - The minimum output amount of the rebalance swap
+    /// * `swap_sqrt_price_limit_x64` - The price limit of the rebalance swap
+    /// * `liquidity_min` - The minimum liquidity that should be added to the target position
+    /// * `with_metadata` - The flag indicating whether to create NFT mint metadata for the target position
+    /// * `source_remaining_len` - The number of remaining accounts used by the source position
+    /// * `target_remaining_len` - The number of remaining accounts used by the target position, the rest are for the swap
+    ///
+    pub fn move_liquidity<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, MoveLiquidity<'info>>,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        tick_array_lower_start_index: i32,
+        tick_array_upper_start_index: i32,
+        amount_0_min: u64,
+        amount_1_min: u64,
+        swap_amount: u64,
+        swap_other_amount_threshold: u64,
+        swap_sqrt_price_limit_x64: u128,
+        liquidity_min: u128,
+        with_metadata: bool,
+        source_remaining_len: u8,
+        target_remaining_len: u8,
     ) -> Result<()> {
-        instructions::swap(
+        instructions::move_liquidity(
             ctx,
-            amount,
-            other_amount_threshold,
-            sqrt_price_limit_x64,
-            is_base_input,
+            tick_lower_index,
+            tick_upper_index,
+            tick_array_lower_start_index,
+            tick_array_upper_start_index,
+            amount_0_min,
+            amount_1_min,
+            swap_amount,
+            swap_other_amount_threshold,
+            swap_sqrt_price_limit_x64,
+            liquidity_min,
+            with_metadata,
+            source_remaining_len,
+            target_remaining_len,
         )
     }
 
-    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022
+    /// Begin deploying a ladder of adjacent positions across multiple transactions
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `plan` - The tick range, width, number and liquidity of the positions
+    ///
+    pub fn begin_deploy(ctx: Context<BeginDeploy>, plan: DeploymentPlan) -> Result<()> {
+        instructions::begin_deploy(ctx, plan)
+    }
+
+    /// Record the next position of the deployment, opened with the open position instructions.
+    /// Recording a position again is a no-op, so that the deployment can be resumed after a failed transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    ///
+    pub fn continue_deploy(ctx: Context<ContinueDeploy>) -> Result<()> {
+        instructions::continue_deploy(ctx)
+    }
+
+    /// Finish the deployment and close the deployment state
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`- The context of accounts
+    /// * `abort` - Abort the deployment, the owner must have closed or emptied the deployed positions before
+    ///
+    pub fn finish_deploy<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FinishDeploy<'info>>,
+        abort: bool,
+    ) -> Result<()> {
+        instructions::finish_deploy(ctx, abort)
+    }
+
+    /// #[deprecated(note = "Use `swap_v2` instead.")]
+    /// Swaps one token for as much as possible of another token across a single pool
     ///
     /// # Arguments
     ///
@@ -571,15 +1093,52 @@ pub mod byreal_clmm {
     /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
     /// * `other_amount_threshold` - For slippage check
     /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// be less than the limit, otherwise it cannot be greater. Zero means no limit, the swap then runs
+    /// to MIN_SQRT_PRICE_X64 + 1 or MAX_SQRT_PRICE_X64 - 1 and must be fully filled. A limit outside of
+    /// (MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64) fails with `SqrtPriceLimitOutOfBounds`
     /// * `is_base_input` - swap base input or swap base output
     ///
+    /// Returns the `SwapResult` in the return data
+    ///
+    pub fn swap<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingle<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    ) -> Result<interface::SwapResult> {
+        instructions::swap(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        )
+    }
+
+    /// Swaps one token for as much as possible of another token across a single pool, support token program 2022.
+    /// The output is paid to `output_token_account`, which may be owned by a recipient other than the payer.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// be less than the limit, otherwise it cannot be greater. Zero means no limit, the swap then runs
+    /// to MIN_SQRT_PRICE_X64 + 1 or MAX_SQRT_PRICE_X64 - 1 and must be fully filled. A limit outside of
+    /// (MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64) fails with `SqrtPriceLimitOutOfBounds`
+    /// * `is_base_input` - swap base input or swap base output
+    ///
+    /// Returns the `SwapResult` in the return data
+    ///
     pub fn swap_v2<'a, 'b, 'c: 'info, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
         amount: u64,
         other_amount_threshold: u64,
         sqrt_price_limit_x64: u128,
         is_base_input: bool,
-    ) -> Result<()> {
+    ) -> Result<interface::SwapResult> {
         instructions::swap_v2(
             ctx,
             amount,
@@ -589,6 +1148,512 @@ pub mod byreal_clmm {
         )
     }
 
+    /// Same as `swap_v2`, with the arguments packed by `CompactSwapArgs::pack` to save transaction bytes
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `args` - The compact encoded amount, other_amount_threshold, sqrt_price_limit and is_base_input
+    ///
+    pub fn swap_v2_compact<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        args: CompactSwapArgs,
+    ) -> Result<interface::SwapResult> {
+        instructions::swap_v2_compact(ctx, args)
+    }
+
+    /// Same as `swap_v2`, failing if the swap takes more steps than `max_swap_iterations` rather than
+    /// the default of the amm config, to bound the compute of the swap on sparse liquidity
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `max_swap_iterations` - The max steps of the swap, each crossing at most one initialized tick or tick array, at most 1024, 0 for the max
+    ///
+    pub fn swap_v2_with_max_iterations<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        max_swap_iterations: u16,
+    ) -> Result<interface::SwapResult> {
+        instructions::swap_v2_with_max_iterations(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            max_swap_iterations,
+        )
+    }
+
+    /// Same as `swap_v2`, only executed if the pool sqrt price meets `condition`,
+    /// otherwise the swap is skipped, or the instruction fails if `condition.fail_if_not_met`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit` - The Q64.64 sqrt price √P limit
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `condition` - The comparison of the pool sqrt price checked before the swap
+    ///
+    /// Returns the `SwapResult` in the return data, all zero if the swap is skipped
+    ///
+    pub fn swap_if_price<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        condition: interface::PriceCondition,
+    ) -> Result<interface::SwapResult> {
+        instructions::swap_if_price(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            condition,
+        )
+    }
+
+    /// Swaps like `swap_v2`, with the input taken from a token account whose owner approved the swap delegate
+    /// of the payer, up to the approved amount, instead of co-signing the swap.
+    /// The output goes to a token account of the same owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit_x64` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    ///
+    /// Returns the `SwapResult` in the return data
+    ///
+    pub fn swap_v2_delegated<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapV2Delegated<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+    ) -> Result<interface::SwapResult> {
+        instructions::swap_v2_delegated(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+        )
+    }
+
+    /// Swaps like `swap_v2` with self-trade prevention, fails if more than half of the swap is filled
+    /// by the positions of the payer, passed in the remaining accounts
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - Arranged in pairs with other_amount_threshold. (amount_in, amount_out_minimum) or (amount_out, amount_in_maximum)
+    /// * `other_amount_threshold` - For slippage check
+    /// * `sqrt_price_limit_x64` - The Q64.64 sqrt price √P limit. If zero for one, the price cannot
+    /// * `is_base_input` - swap base input or swap base output
+    /// * `maker_position_count` - The number of leading (personal position, position NFT account) pairs of the payer in the remaining accounts
+    ///
+    /// Returns the `SwapResult` in the return data
+    ///
+    pub fn swap_v2_stp<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapSingleV2<'info>>,
+        amount: u64,
+        other_amount_threshold: u64,
+        sqrt_price_limit_x64: u128,
+        is_base_input: bool,
+        maker_position_count: u8,
+    ) -> Result<interface::SwapResult> {
+        instructions::swap_v2_stp(
+            ctx,
+            amount,
+            other_amount_threshold,
+            sqrt_price_limit_x64,
+            is_base_input,
+            maker_position_count,
+        )
+    }
+
+    /// Executes up to `MAX_SWAP_BATCH_ORDERS` independent orders against a single pool, each settled and evented like a `swap_v2`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `orders` - The direction, amount, slippage threshold and price limit of each order, executed in sequence
+    /// * `zero_for_one_tick_array_count` - The number of leading remaining accounts that are the tick arrays of the zero_for_one orders
+    ///
+    /// Returns the `SwapResult` of each order in the return data
+    ///
+    pub fn swap_batch<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapBatch<'info>>,
+        orders: Vec<interface::SwapOrder>,
+        zero_for_one_tick_array_count: u8,
+    ) -> Result<Vec<interface::SwapResult>> {
+        instructions::swap_batch(ctx, orders, zero_for_one_tick_array_count)
+    }
+
+    /// Aborts the transaction if the pool left the bounds, does nothing otherwise.
+    /// Appended after a composition with other programs to guard against an unexpected pool manipulation mid transaction.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `min_sqrt_price_x64` - The minimum sqrt price of the pool, inclusive
+    /// * `max_sqrt_price_x64` - The maximum sqrt price of the pool, inclusive
+    /// * `min_liquidity` - The minimum in range liquidity of the pool
+    ///
+    pub fn assert_pool_state(
+        ctx: Context<AssertPoolState>,
+        min_sqrt_price_x64: u128,
+        max_sqrt_price_x64: u128,
+        min_liquidity: u128,
+    ) -> Result<()> {
+        instructions::assert_pool_state(ctx, min_sqrt_price_x64, max_sqrt_price_x64, min_liquidity)
+    }
+
+    /// Aborts the transaction if the last swap of the pool is too old, or newer than the snapshot a quote was made off.
+    /// Protects the RFQ flows quoting off a specific pool snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `max_slot_age` - The max slots since the last swap of the pool, u64::MAX to disable
+    /// * `max_last_swap_slot` - The slot of the quoted snapshot, the pool must not be swapped after it, u64::MAX to disable
+    ///
+    pub fn assert_recent_pool_update(
+        ctx: Context<AssertPoolState>,
+        max_slot_age: u64,
+        max_last_swap_slot: u64,
+    ) -> Result<()> {
+        instructions::assert_recent_pool_update(ctx, max_slot_age, max_last_swap_slot)
+    }
+
+    /// Read only, values a position at the current price of the pool, returning the token amounts of its liquidity
+    /// and its uncollected fees in the return data, so that lending programs can accept position NFTs as collateral.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn value_position(ctx: Context<ValuePosition>) -> Result<interface::PositionValuation> {
+        instructions::value_position(ctx)
+    }
+
+    /// Read only, logs the vault balances of the pool net of its fee liabilities, for monitoring
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn reconcile_vaults(ctx: Context<ReconcileVaults>) -> Result<()> {
+        instructions::reconcile_vaults(ctx)
+    }
+
+    /// Sets the owner-signed collateral terms of a position, allowing the collateral manager to liquidate it
+    /// to the pre-declared receiver with `liquidate_position`. The position NFT is escrowed in the custody of the terms
+    /// until they are released, meanwhile the owner can't decrease, transfer, split, merge, move or close the position.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `collateral_manager` - The authority allowed to liquidate the position, e.g. a lending program PDA
+    /// * `receiver` - The owner of the token accounts receiving the liquidated tokens
+    ///
+    pub fn set_position_collateral(
+        ctx: Context<SetPositionCollateral>,
+        collateral_manager: Pubkey,
+        receiver: Pubkey,
+    ) -> Result<()> {
+        instructions::set_position_collateral(ctx, collateral_manager, receiver)
+    }
+
+    /// Releases the collateral terms of a position, signed by the collateral manager,
+    /// and returns the escrowed position NFT to the owner who signed them
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn release_position_collateral(ctx: Context<ReleasePositionCollateral>) -> Result<()> {
+        instructions::release_position_collateral(ctx)
+    }
+
+    /// Decreases liquidity of a collateralized position and collects its fees and rewards to the receiver of the terms,
+    /// signed by the collateral manager instead of the position owner.
+    /// The position NFT must be in the custody of the terms.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The amount by which liquidity will be decreased, 0 to only collect the fees and rewards
+    /// * `amount_0_min` - The minimum amount of token_0 that should be accounted for the burned liquidity
+    /// * `amount_1_min` - The minimum amount of token_1 that should be accounted for the burned liquidity
+    ///
+    pub fn liquidate_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, LiquidatePosition<'info>>,
+        liquidity: u128,
+        amount_0_min: u64,
+        amount_1_min: u64,
+    ) -> Result<()> {
+        instructions::liquidate_position(ctx, liquidity, amount_0_min, amount_1_min)
+    }
+
+    /// Claims all the pending rewards of a position and swaps them into `token_mint` in the pools of this program,
+    /// delivering a single token instead of reward dust in many mints
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `token_mint` - The mint all the rewards are swapped into
+    /// * `tick_array_counts` - The number of tick arrays of the swap of each reward not in `token_mint`, in the reward order
+    /// * `amount_out_minimum` - The minimum total amount of `token_mint` received
+    ///
+    pub fn claim_rewards_as<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClaimRewardsAs<'info>>,
+        token_mint: Pubkey,
+        tick_array_counts: Vec<u8>,
+        amount_out_minimum: u64,
+    ) -> Result<()> {
+        instructions::claim_rewards_as(ctx, token_mint, tick_array_counts, amount_out_minimum)
+    }
+
+    /// Collects the fees and rewards of up to 8 positions of the same pool and owner in one transaction,
+    /// with a single vault transfer per token
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts, the positions are passed in the remaining accounts
+    ///
+    pub fn collect_fees_and_rewards<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CollectFeesAndRewards<'info>>,
+    ) -> Result<()> {
+        instructions::collect_fees_and_rewards(ctx)
+    }
+
+    /// Moves liquidity between two positions of the same owner, pool and tick range without touching the vaults,
+    /// the fees and rewards accrued so far stay owed to each position
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The liquidity moved from `from_position` to `to_position`
+    ///
+    pub fn transfer_liquidity(ctx: Context<TransferLiquidity>, liquidity: u128) -> Result<()> {
+        instructions::transfer_liquidity(ctx, liquidity)
+    }
+
+    /// Splits `liquidity` of a position into a new token-2022 position NFT of the same range, with the same share
+    /// of the fees and rewards owed, without touching the vaults or paying the price movement of a decrease and re-add
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `liquidity` - The liquidity moved to the new position, less than the liquidity of the position
+    /// * `with_metadata` - Whether to initialize the metadata of the new position NFT
+    ///
+    pub fn split_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SplitPosition<'info>>,
+        liquidity: u128,
+        with_metadata: bool,
+    ) -> Result<()> {
+        instructions::split_position(ctx, liquidity, with_metadata)
+    }
+
+    /// Merges a position into another position of the same owner, pool and range, summing their liquidity
+    /// and the fees and rewards owed, then closes the merged position NFT and accounts to reclaim the rent
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn merge_positions<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, MergePositions<'info>>,
+    ) -> Result<()> {
+        instructions::merge_positions(ctx)
+    }
+
+    /// Permissionless, creates the fee APR account of a pool, updated by `update_pool_apr`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn initialize_pool_apr(ctx: Context<InitializePoolApr>) -> Result<()> {
+        instructions::initialize_pool_apr(ctx)
+    }
+
+    /// Permissionless crank, updates the trailing 24h and 7d fee APRs of a pool, so that other programs
+    /// can read the yield of the pool on chain
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn update_pool_apr(ctx: Context<UpdatePoolApr>) -> Result<()> {
+        instructions::update_pool_apr(ctx)
+    }
+
+    /// Prepay the bounty tipping the keepers of the permissionless cranks of the pool, only the pool creator
+    /// can fund it. A keeper is tipped when it passes the bounty account and itself in the remaining accounts of a crank.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The lamports to deposit into the bounty
+    /// * `bounty_lamports` - The lamports paid to the keeper of a crank
+    /// * `min_interval` - The min seconds between two paid runs of the same kind of crank
+    ///
+    pub fn fund_crank_bounty(
+        ctx: Context<FundCrankBounty>,
+        amount: u64,
+        bounty_lamports: u64,
+        min_interval: u64,
+    ) -> Result<()> {
+        instructions::fund_crank_bounty(ctx, amount, bounty_lamports, min_interval)
+    }
+
+    /// Prepay the bounty paying the keepers that rebalance the vault positions of the pool, only the pool creator
+    /// can fund it. See `register_rebalance_vault` and `claim_rebalance_bounty`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The lamports to deposit into the bounty
+    /// * `bounty_lamports` - The lamports paid to the keeper of a rebalance
+    /// * `min_out_of_range_slots` - The min slots a vault position must be out of range before its rebalance is paid
+    ///
+    pub fn fund_rebalance_bounty(
+        ctx: Context<FundRebalanceBounty>,
+        amount: u64,
+        bounty_lamports: u64,
+        min_out_of_range_slots: u64,
+    ) -> Result<()> {
+        instructions::fund_rebalance_bounty(ctx, amount, bounty_lamports, min_out_of_range_slots)
+    }
+
+    /// Register a position of protocol-owned liquidity for the rebalance bounty of its pool,
+    /// or replace the position of the vault of the owner. Only the pool creator can register vaults.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn register_rebalance_vault(ctx: Context<RegisterRebalanceVault>) -> Result<()> {
+        instructions::register_rebalance_vault(ctx)
+    }
+
+    /// Start the out of range time of a vault position once the price has left its range,
+    /// or reset it when the price is back in range
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn observe_rebalance_vault(ctx: Context<ObserveRebalanceVault>) -> Result<()> {
+        instructions::observe_rebalance_vault(ctx)
+    }
+
+    /// Pay the rebalance bounty to the keeper once the liquidity of a vault position out of range
+    /// for `min_out_of_range_slots` has been moved into a new position in range
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn claim_rebalance_bounty(ctx: Context<ClaimRebalanceBounty>) -> Result<()> {
+        instructions::claim_rebalance_bounty(ctx)
+    }
+
+    /// Attach the compact metadata to a token-2022 position NFT opened without metadata,
+    /// so that wallets can display it, then disable the minting of the NFT
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    ///
+    pub fn attach_metadata(ctx: Context<AttachMetadata>) -> Result<()> {
+        instructions::attach_metadata(ctx)
+    }
+
+    /// Localnet only, create two test tokens minted by the faucet to the payer and create their pool,
+    /// without the pool manager
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `params` - The decimals and the amounts of the test tokens, and the initial price of the pool
+    ///
+    #[cfg(feature = "localnet")]
+    pub fn bootstrap_test_pool(
+        ctx: Context<BootstrapTestPool>,
+        params: BootstrapTestPoolParams,
+    ) -> Result<()> {
+        instructions::bootstrap_test_pool(ctx, params)
+    }
+
+    /// Localnet only, mint test tokens created by `bootstrap_test_pool`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount of test tokens to mint
+    ///
+    #[cfg(feature = "localnet")]
+    pub fn faucet_mint_to(ctx: Context<FaucetMintTo>, amount: u64) -> Result<()> {
+        instructions::faucet_mint_to(ctx, amount)
+    }
+
+    /// Localnet only, open a position at a preset range of a test pool, funded by the faucet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `preset` - The range of the position around the current tick
+    /// * `liquidity` - The liquidity of the position
+    ///
+    #[cfg(feature = "localnet")]
+    pub fn seed_test_position<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SeedTestPosition<'info>>,
+        preset: TestPositionPreset,
+        liquidity: u128,
+    ) -> Result<()> {
+        instructions::seed_test_position(ctx, preset, liquidity)
+    }
+
+    /// Swap token for as much as possible of another token across 2 to 4 pools, base input.
+    /// The tick arrays of each hop may be fixed or dynamic.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The context of accounts
+    /// * `amount_in` - Token amount to be swapped in
+    /// * `amount_out_minimum` - Panic if the output amount of the last hop is below minimum amount. For slippage.
+    ///
+    /// The remaining accounts are, for each hop: amm_config, pool_state, output_token_account, input_vault,
+    /// output_vault, output_token_mint, observation_state, then the tick arrays of the pool in the swap direction
+    /// with the tick array bitmap extension if needed.
+    /// Returns the `SwapResult` of each hop in the return data
+    ///
+    pub fn swap_router_base_in_v2<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SwapRouterBaseIn<'info>>,
+        amount_in: u64,
+        amount_out_minimum: u64,
+    ) -> Result<Vec<interface::SwapResult>> {
+        instructions::swap_router_base_in_v2(ctx, amount_in, amount_out_minimum)
+    }
+
     //== drop this method
     // /// Swap token for as much as possible of another token across the path provided, base input
     // ///
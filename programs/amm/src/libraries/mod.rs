@@ -1,24 +1,25 @@
-pub mod big_num;
-pub mod fixed_point_64;
-pub mod full_math;
-pub mod liquidity_math;
-pub mod sqrt_price_math;
-pub mod swap_math;
+pub use byreal_clmm_core::libraries::{
+    big_num, fee_math, fixed_point_64, full_math, liquidity_math, settle_math, sqrt_price_math,
+    swap_math, tick_math, units, unsafe_math,
+};
 
 pub mod tick_array_bit_map;
-pub mod tick_math;
-pub mod unsafe_math;
 
 pub use big_num::*;
+pub use fee_math::*;
 pub use fixed_point_64::*;
 pub use full_math::*;
 pub use liquidity_math::*;
+pub use settle_math::*;
 pub use sqrt_price_math::*;
 pub use swap_math::*;
 
 pub use tick_array_bit_map::*;
 pub use tick_math::*;
+pub use units::*;
 pub use unsafe_math::*;
 
+#[cfg(test)]
 pub mod test_account_utils;
+#[cfg(test)]
 pub use test_account_utils::*;
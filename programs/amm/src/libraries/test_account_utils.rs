@@ -1,5 +1,8 @@
 // 仅在测试时使用的utils
 
+// the mocks leak their lamports and data to hand out the references an AccountInfo holds
+#![allow(clippy::mut_from_ref, clippy::type_complexity)]
+
 use anchor_lang::prelude::*;
 #[cfg(test)]
 use anchor_lang::ZeroCopy;
@@ -39,12 +42,12 @@ pub fn mock_account_info<'a>(
     let data_rc = Rc::new(RefCell::new(data_ref));
 
     let account_info = AccountInfo {
-        key: key,
+        key,
         is_signer,
         is_writable,
         lamports: lamports_rc.clone(),
         data: data_rc.clone(),
-        owner: owner,
+        owner,
         executable: false,
         rent_epoch: 0,
     };
@@ -54,13 +57,13 @@ pub fn mock_account_info<'a>(
 
 /// only for test
 #[cfg(test)]
-pub fn mock_anchor_account_info<'a, 'b, T: ZeroCopy>(
+pub fn mock_anchor_account_info<'a, T: ZeroCopy>(
     key: &'a Pubkey,
     owner: &'a Pubkey,
     is_signer: bool,
     is_writable: bool,
     lamports: u64,
-    account: &'b T,
+    account: &T,
 ) -> (AccountInfo<'a>, Rc<RefCell<&'a mut u64>>, Rc<RefCell<&'a mut [u8]>>) {
     // 计算 data 长度：8 字节 discriminator + 序列化数据
     let mut buf = Vec::new();
@@ -80,13 +83,13 @@ pub fn mock_anchor_account_info<'a, 'b, T: ZeroCopy>(
 
 /// only for test
 #[cfg(test)]
-pub fn mock_anchor_account_info_v2<'a, 'b, T: ZeroCopy>(
+pub fn mock_anchor_account_info_v2<'a, T: ZeroCopy>(
     key: &'a Pubkey,
     owner: &'a Pubkey,
     is_signer: bool,
     is_writable: bool,
     lamports: u64,
-    account: &'b T,
+    account: &T,
     extra_account_data: Option<&[u8]>,
 ) -> (AccountInfo<'a>, Rc<RefCell<&'a mut u64>>, Rc<RefCell<&'a mut [u8]>>) {
     // 计算 data 长度：8 字节 discriminator + 序列化数据
@@ -110,10 +113,10 @@ pub fn mock_anchor_account_info_v2<'a, 'b, T: ZeroCopy>(
 }
 
 #[cfg(test)]
-pub fn mock_anchor_account_info_v3<'a, 'b, T: ZeroCopy>(
+pub fn mock_anchor_account_info_v3<'a, T: ZeroCopy>(
     key: &'a Pubkey,
     owner: &'a Pubkey,
-    account: &'b T,
+    account: &T,
     extra_account_data: Option<&[u8]>,
 ) -> (AccountInfo<'a>, Rc<RefCell<&'a mut u64>>, Rc<RefCell<&'a mut [u8]>>) {
     mock_anchor_account_info_v2(key, owner, false, true, 0, account, extra_account_data)
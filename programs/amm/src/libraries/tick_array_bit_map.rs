@@ -1,4 +1,4 @@
-///! Helper functions to get most and least significant non-zero bits
+//! Helper functions to get most and least significant non-zero bits
 use super::big_num::U1024;
 use crate::error::ErrorCode;
 use crate::states::*;
@@ -69,7 +69,7 @@ pub fn check_current_tick_array_is_initialized(
         return Ok((true, (compressed - 512) * multiplier));
     }
     // the current bit is not initialized
-    return Ok((false, (compressed - 512) * multiplier));
+    Ok((false, (compressed - 512) * multiplier))
 }
 
 /// The function is only called when `bit_map = pool.tick_array_bitmap`.
@@ -107,9 +107,8 @@ pub fn next_initialized_tick_array_start_index(
         // find from highter bits to lower bits
         let offset_bit_map = bit_map << (1024 - bit_pos - 1).try_into().unwrap();
         let next_bit = most_significant_bit(offset_bit_map);
-        if next_bit.is_some() {
-            let next_array_start_index =
-                (bit_pos - i32::from(next_bit.unwrap()) - 512) * multiplier;
+        if let Some(next_bit) = next_bit {
+            let next_array_start_index = (bit_pos - i32::from(next_bit) - 512) * multiplier;
             (true, next_array_start_index)
         } else {
             // not found til to the end
@@ -120,9 +119,8 @@ pub fn next_initialized_tick_array_start_index(
         // find from lower bits to highter bits
         let offset_bit_map = bit_map >> (bit_pos).try_into().unwrap();
         let next_bit = least_significant_bit(offset_bit_map);
-        if next_bit.is_some() {
-            let next_array_start_index =
-                (bit_pos + i32::from(next_bit.unwrap()) - 512) * multiplier;
+        if let Some(next_bit) = next_bit {
+            let next_array_start_index = (bit_pos + i32::from(next_bit) - 512) * multiplier;
             (true, next_array_start_index)
         } else {
             // not found til to the end
@@ -158,7 +156,7 @@ mod test {
             0,
             0,
             0,
-            u64::max_value() & 1 << 63,
+            1 << 63,
         ]);
         let mut tick_current = -307200;
         let mut start_index = -1;
@@ -361,7 +359,7 @@ mod test {
             tick_spacing as u16,
             false,
         );
-        assert!(is_found == false);
+        assert!(!is_found);
         assert!(array_start_index == tick_array_start_index);
 
         tick_array_start_index =
@@ -372,7 +370,7 @@ mod test {
             tick_spacing as u16,
             true,
         );
-        assert!(is_found == false);
+        assert!(!is_found);
         assert!(array_start_index == tick_array_start_index);
     }
 
@@ -398,23 +396,23 @@ mod test {
                 let (is_found, array_start_index) = next_initialized_tick_array_start_index(
                     bit_map,
                     start_index,
-                    tick_spacing as u16,
+                    tick_spacing,
                     false,
                 );
 
                 if i < loop_count - 1 {
-                    if is_found == false {
+                    if !is_found {
                         println!("start_index:{}", start_index)
                     }
-                    assert_eq!(is_found, true);
+                    assert!(is_found);
                     assert_eq!(array_start_index, expect_index);
                     start_index = array_start_index;
                 } else {
                     if tick_spacing == 60 {
-                        assert_eq!(is_found, true);
+                        assert!(is_found);
                         assert_eq!(array_start_index, expect_index);
                     } else {
-                        assert_eq!(is_found, false);
+                        assert!(!is_found);
                         assert_eq!(array_start_index, start_index);
                         assert_eq!(
                             array_start_index,
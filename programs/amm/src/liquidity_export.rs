@@ -0,0 +1,204 @@
+use crate::error::ErrorCode as ClmmErrorCode;
+use crate::quote::TickArrayData;
+use crate::states::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// The first bytes of an exported liquidity distribution
+pub const LIQUIDITY_DISTRIBUTION_MAGIC: [u8; 4] = *b"BCLD";
+
+/// The format version of the exported liquidity distribution, bumped on any change of the encoding
+pub const LIQUIDITY_DISTRIBUTION_VERSION: u8 = 1;
+
+/// The initialized ticks of a pool at a slot, in columns: the row `i` of every column is the `i`-th
+/// initialized tick in ascending tick order, so that each column maps to a column of a dataframe or a
+/// parquet file without re-encoding.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LiquidityDistribution {
+    pub version: u8,
+    pub pool_id: Pubkey,
+    /// The slot the pool and tick array accounts were read at
+    pub slot: u64,
+    pub tick_spacing: u16,
+    pub tick_current: i32,
+    pub sqrt_price_x64: u128,
+    /// The liquidity in range at `tick_current`
+    pub liquidity: u128,
+    pub fee_growth_global_0_x64: u128,
+    pub fee_growth_global_1_x64: u128,
+    pub ticks: Vec<i32>,
+    pub liquidity_net: Vec<i128>,
+    pub liquidity_gross: Vec<u128>,
+    pub fee_growth_outside_0_x64: Vec<u128>,
+    pub fee_growth_outside_1_x64: Vec<u128>,
+}
+
+impl LiquidityDistribution {
+    /// Collect the initialized ticks of the tick arrays of `pool_id`, fixed or dynamic, in any order.
+    /// All the tick arrays must belong to the pool.
+    pub fn export(
+        pool_id: Pubkey,
+        pool_state: &PoolState,
+        tick_arrays: &[TickArrayData],
+        slot: u64,
+    ) -> Result<Self> {
+        let mut tick_states: Vec<&TickState> = Vec::new();
+        for tick_array in tick_arrays {
+            require_keys_eq!(
+                tick_array.pool_id(),
+                pool_id,
+                ClmmErrorCode::InvalidTickArray
+            );
+            tick_states.extend(
+                (0..TICK_ARRAY_SIZE_USIZE)
+                    .filter_map(|offset| tick_array.tick_state_at(offset))
+                    .filter(|tick_state| tick_state.is_initialized()),
+            );
+        }
+        tick_states.sort_by_key(|tick_state| tick_state.tick);
+        tick_states.dedup_by_key(|tick_state| tick_state.tick);
+
+        Ok(Self {
+            version: LIQUIDITY_DISTRIBUTION_VERSION,
+            pool_id,
+            slot,
+            tick_spacing: pool_state.tick_spacing,
+            tick_current: pool_state.tick_current,
+            sqrt_price_x64: pool_state.sqrt_price_x64,
+            liquidity: pool_state.liquidity,
+            fee_growth_global_0_x64: pool_state.fee_growth_global_0_x64,
+            fee_growth_global_1_x64: pool_state.fee_growth_global_1_x64,
+            ticks: tick_states.iter().map(|t| t.tick).collect(),
+            liquidity_net: tick_states.iter().map(|t| t.liquidity_net).collect(),
+            liquidity_gross: tick_states.iter().map(|t| t.liquidity_gross).collect(),
+            fee_growth_outside_0_x64: tick_states
+                .iter()
+                .map(|t| t.fee_growth_outside_0_x64)
+                .collect(),
+            fee_growth_outside_1_x64: tick_states
+                .iter()
+                .map(|t| t.fee_growth_outside_1_x64)
+                .collect(),
+        })
+    }
+
+    /// The number of initialized ticks
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    /// Encode as `LIQUIDITY_DISTRIBUTION_MAGIC` followed by the borsh encoding, little endian
+    /// with each column prefixed by its u32 length
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = LIQUIDITY_DISTRIBUTION_MAGIC.to_vec();
+        self.serialize(&mut data).unwrap();
+        data
+    }
+
+    /// Decode the output of `to_bytes`, fails on another format version
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let Some(mut encoded) = data.strip_prefix(&LIQUIDITY_DISTRIBUTION_MAGIC[..]) else {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        };
+        if encoded.first() != Some(&LIQUIDITY_DISTRIBUTION_VERSION) {
+            return err!(ErrorCode::AccountDidNotDeserialize);
+        }
+        let distribution = Self::deserialize(&mut encoded)
+            .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))?;
+        let len = distribution.len();
+        require!(
+            encoded.is_empty()
+                && distribution.liquidity_net.len() == len
+                && distribution.liquidity_gross.len() == len
+                && distribution.fee_growth_outside_0_x64.len() == len
+                && distribution.fee_growth_outside_1_x64.len() == len,
+            ErrorCode::AccountDidNotDeserialize
+        );
+        Ok(distribution)
+    }
+}
+
+#[cfg(test)]
+mod liquidity_export_test {
+    use super::*;
+    use crate::states::dyn_tick_array_test::{
+        build_dyn_tick_array_with_tick_states, DynamicTickArrayBuildType,
+    };
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+
+    #[test]
+    fn export_test() {
+        let pool_state = build_pool(-50, 10, 1 << 64, 1_000);
+        let pool_id = pool_state.borrow().key();
+        let mut fee_tick = build_tick(-200, 30, -30).take();
+        fee_tick.fee_growth_outside_0_x64 = 7;
+        fee_tick.fee_growth_outside_1_x64 = 9;
+        let fixed = build_fix_tick_array_with_tick_states(
+            pool_id,
+            -600,
+            10,
+            vec![build_tick(-500, 30, 30).take(), fee_tick],
+        );
+        let (header, ticks) = build_dyn_tick_array_with_tick_states(
+            pool_id,
+            0,
+            10,
+            DynamicTickArrayBuildType::RandomIndex,
+            vec![
+                build_tick(300, 20, -20).take(),
+                build_tick(100, 20, 20).take(),
+            ],
+        );
+        let tick_arrays = vec![
+            TickArrayData::Dynamic(*header.borrow(), ticks.borrow().clone()),
+            TickArrayData::Fixed(Box::new(*fixed.borrow())),
+        ];
+
+        let distribution =
+            LiquidityDistribution::export(pool_id, &pool_state.borrow(), &tick_arrays, 42).unwrap();
+        assert_eq!(distribution.version, LIQUIDITY_DISTRIBUTION_VERSION);
+        assert_eq!(distribution.slot, 42);
+        assert_eq!(distribution.tick_current, -50);
+        assert_eq!(distribution.ticks, vec![-500, -200, 100, 300]);
+        assert_eq!(distribution.liquidity_net, vec![30, -30, 20, -20]);
+        assert_eq!(distribution.liquidity_gross, vec![30, 30, 20, 20]);
+        assert_eq!(distribution.fee_growth_outside_0_x64, vec![0, 7, 0, 0]);
+        assert_eq!(distribution.fee_growth_outside_1_x64, vec![0, 9, 0, 0]);
+
+        let data = distribution.to_bytes();
+        assert_eq!(
+            LiquidityDistribution::from_bytes(&data).unwrap(),
+            distribution
+        );
+
+        // another format version, or not an export
+        let mut other_version = data.clone();
+        other_version[LIQUIDITY_DISTRIBUTION_MAGIC.len()] += 1;
+        assert!(LiquidityDistribution::from_bytes(&other_version).is_err());
+        assert!(LiquidityDistribution::from_bytes(&data[4..]).is_err());
+        assert!(LiquidityDistribution::from_bytes(&data[..data.len() - 1]).is_err());
+
+        // a tick array of another pool
+        let other_pool = build_fix_tick_array_with_tick_states(
+            Pubkey::new_unique(),
+            -600,
+            10,
+            vec![build_tick(-500, 30, 30).take()],
+        );
+        assert_eq!(
+            LiquidityDistribution::export(
+                pool_id,
+                &pool_state.borrow(),
+                &[TickArrayData::Fixed(Box::new(*other_pool.borrow()))],
+                42
+            )
+            .err(),
+            Some(ClmmErrorCode::InvalidTickArray.into())
+        );
+    }
+}
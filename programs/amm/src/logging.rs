@@ -0,0 +1,45 @@
+//! Leveled logs compiled in only with the `enable-log` (info) and `enable-debug-log` (debug) features,
+//! so that the mainnet builds don't spend compute units on them.
+//! Every message is prefixed with its level, the instruction name and the pool key when there is one,
+//! `[info][swap][<pool>] ...`, so that the logs of an instruction or a pool can be grepped.
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level:literal, ix = $ix:literal, pool = $pool:expr, $fmt:literal $(, $arg:expr)* $(,)?) => {
+        anchor_lang::prelude::msg!(
+            concat!("[", $level, "][", $ix, "][{}] ", $fmt),
+            $pool
+            $(, $arg)*
+        )
+    };
+    ($level:literal, ix = $ix:literal, $fmt:literal $(, $arg:expr)* $(,)?) => {
+        anchor_lang::prelude::msg!(concat!("[", $level, "][", $ix, "] ", $fmt) $(, $arg)*)
+    };
+}
+
+/// Log the main steps of an instruction, with the `enable-log` feature
+///
+/// ```ignore
+/// log_info!(ix = "swap", pool = pool_state.key(), "amount_0:{}, amount_1:{}", amount_0, amount_1);
+/// ```
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "enable-log")]
+        $crate::__log!("info", $($arg)+);
+    };
+}
+
+/// Log the intermediate values of the math, with the `enable-debug-log` feature
+///
+/// ```ignore
+/// log_debug!(ix = "swap", pool = pool_state.key(), "state_tick:{}", state.tick);
+/// ```
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)+) => {
+        #[cfg(feature = "enable-debug-log")]
+        $crate::__log!("debug", $($arg)+);
+    };
+}
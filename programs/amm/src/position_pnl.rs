@@ -0,0 +1,281 @@
+use crate::libraries::{fixed_point_64, liquidity_math};
+use crate::states::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// A liquidity event of a position.
+/// The fees are collected by `DecreaseLiquidityEvent`, a decrease of zero liquidity only collects the fees.
+pub enum PositionEvent {
+    Create(CreatePersonalPositionEvent),
+    Increase(IncreaseLiquidityEvent),
+    Decrease(DecreaseLiquidityEvent),
+}
+
+impl PositionEvent {
+    pub fn event_seq(&self) -> u64 {
+        match self {
+            PositionEvent::Create(event) => event.event_seq,
+            PositionEvent::Increase(event) => event.event_seq,
+            PositionEvent::Decrease(event) => event.event_seq,
+        }
+    }
+}
+
+/// The price of the pool when the event with `event_seq` was emitted
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PricePoint {
+    pub event_seq: u64,
+    /// The price of token_0 in token_1, in the smallest units of the tokens
+    pub price: f64,
+}
+
+/// The profit and loss of a position, valued in the smallest unit of token_1.
+/// The token amounts are the amounts in the pool, excluding the transfer fees.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PositionPnl {
+    /// The value of all the deposits when they were deposited
+    pub deposited_value: f64,
+    /// The value of all the withdrawals when they were withdrawn, excluding the fees
+    pub withdrawn_value: f64,
+    /// The withdrawn value minus the cost of the withdrawn liquidity, with the average cost of the liquidity
+    pub realized_pnl: f64,
+    /// The current value of the position minus the cost of the remaining liquidity
+    pub unrealized_pnl: f64,
+    /// The value of the collected fees when they were collected
+    pub collected_fee_value: f64,
+    /// The current value of the fees owed to the position, as of the last update of the position
+    pub unclaimed_fee_value: f64,
+    /// The current value of the remaining liquidity
+    pub position_value: f64,
+    /// The current value of the tokens deposited for the remaining liquidity, had they been held instead
+    pub hodl_value: f64,
+    /// `position_value - hodl_value`, the loss of providing liquidity versus holding the tokens
+    pub impermanent_loss: f64,
+    /// The realized and unrealized pnl plus the fee income
+    pub total_pnl: f64,
+}
+
+/// Fold the events of a position in emission order into its profit and loss.
+///
+/// # Arguments
+///
+/// * `events` - The events of the position, in the order of `event_seq`
+/// * `price_series` - The prices of the pool in the order of `event_seq`, each event is valued with the last price at or before it
+/// * `personal_position` - The current state of the position
+/// * `pool_state` - The current state of the pool, the remaining liquidity and fees are valued at its price
+///
+pub fn position_pnl(
+    events: &[PositionEvent],
+    price_series: &[PricePoint],
+    personal_position: &PersonalPositionState,
+    pool_state: &PoolState,
+) -> Result<PositionPnl> {
+    let mut pnl = PositionPnl::default();
+    let mut liquidity: u128 = 0;
+    // the cost and the deposited tokens of the remaining liquidity
+    let mut cost_basis = 0f64;
+    let mut hodl_amount_0 = 0f64;
+    let mut hodl_amount_1 = 0f64;
+
+    for event in events {
+        let price = price_at(price_series, event.event_seq())?;
+        match event {
+            PositionEvent::Create(CreatePersonalPositionEvent {
+                liquidity: liquidity_delta,
+                deposit_amount_0: amount_0,
+                deposit_amount_1: amount_1,
+                ..
+            })
+            | PositionEvent::Increase(IncreaseLiquidityEvent {
+                liquidity: liquidity_delta,
+                amount_0,
+                amount_1,
+                ..
+            }) => {
+                let value = *amount_0 as f64 * price + *amount_1 as f64;
+                pnl.deposited_value += value;
+                cost_basis += value;
+                hodl_amount_0 += *amount_0 as f64;
+                hodl_amount_1 += *amount_1 as f64;
+                liquidity += *liquidity_delta;
+            }
+            PositionEvent::Decrease(event) => {
+                let fraction = if liquidity == 0 {
+                    0f64
+                } else {
+                    event.liquidity as f64 / liquidity as f64
+                };
+                let released_cost = cost_basis * fraction;
+                let value = event.decrease_amount_0 as f64 * price + event.decrease_amount_1 as f64;
+                pnl.withdrawn_value += value;
+                pnl.realized_pnl += value - released_cost;
+                pnl.collected_fee_value +=
+                    event.fee_amount_0 as f64 * price + event.fee_amount_1 as f64;
+                cost_basis -= released_cost;
+                hodl_amount_0 -= hodl_amount_0 * fraction;
+                hodl_amount_1 -= hodl_amount_1 * fraction;
+                liquidity = liquidity.saturating_sub(event.liquidity);
+            }
+        }
+    }
+
+    let sqrt_price = pool_state.sqrt_price_x64 as f64 / fixed_point_64::Q64 as f64;
+    let price = sqrt_price * sqrt_price;
+    let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+        pool_state.tick_current,
+        pool_state.sqrt_price_x64,
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+        -i128::try_from(personal_position.liquidity).map_err(|_| ErrorCode::RequireViolated)?,
+    )?;
+    pnl.position_value = amount_0 as f64 * price + amount_1 as f64;
+    pnl.unclaimed_fee_value = personal_position.token_fees_owed_0 as f64 * price
+        + personal_position.token_fees_owed_1 as f64;
+    pnl.unrealized_pnl = pnl.position_value - cost_basis;
+    pnl.hodl_value = hodl_amount_0 * price + hodl_amount_1;
+    pnl.impermanent_loss = pnl.position_value - pnl.hodl_value;
+    pnl.total_pnl =
+        pnl.realized_pnl + pnl.unrealized_pnl + pnl.collected_fee_value + pnl.unclaimed_fee_value;
+    Ok(pnl)
+}
+
+/// The last price at or before `event_seq`
+fn price_at(price_series: &[PricePoint], event_seq: u64) -> Result<f64> {
+    let index = price_series.partition_point(|point| point.event_seq <= event_seq);
+    require_gt!(index, 0, ErrorCode::RequireViolated);
+    Ok(price_series[index - 1].price)
+}
+
+#[cfg(test)]
+mod position_pnl_test {
+    use super::*;
+    use crate::libraries::tick_math;
+    use crate::states::pool_test::build_pool;
+
+    fn increase_event(
+        liquidity: u128,
+        amount_0: u64,
+        amount_1: u64,
+        event_seq: u64,
+    ) -> PositionEvent {
+        PositionEvent::Increase(IncreaseLiquidityEvent {
+            position_nft_mint: Pubkey::default(),
+            liquidity,
+            amount_0,
+            amount_1,
+            amount_0_transfer_fee: 0,
+            amount_1_transfer_fee: 0,
+            event_seq,
+        })
+    }
+
+    fn decrease_event(
+        liquidity: u128,
+        amounts: (u64, u64),
+        fee_amounts: (u64, u64),
+        event_seq: u64,
+    ) -> PositionEvent {
+        PositionEvent::Decrease(DecreaseLiquidityEvent {
+            position_nft_mint: Pubkey::default(),
+            liquidity,
+            decrease_amount_0: amounts.0,
+            decrease_amount_1: amounts.1,
+            fee_amount_0: fee_amounts.0,
+            fee_amount_1: fee_amounts.1,
+            reward_amounts: [0; REWARD_NUM],
+            transfer_fee_0: 0,
+            transfer_fee_1: 0,
+            event_seq,
+        })
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() <= 1e-6 * b.abs().max(1.0), "{} != {}", a, b);
+    }
+
+    #[test]
+    fn position_pnl_test() {
+        let liquidity = 1_000_000_000u128;
+        let (tick_lower, tick_upper) = (-600, 600);
+        let pool_state = build_pool(0, 60, tick_math::get_sqrt_price_at_tick_raw(0).unwrap(), 0);
+        let mut personal_position = PersonalPositionState {
+            tick_lower_index: tick_lower,
+            tick_upper_index: tick_upper,
+            ..Default::default()
+        };
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+            0,
+            pool_state.borrow().sqrt_price_x64,
+            tick_lower,
+            tick_upper,
+            -(liquidity as i128),
+        )
+        .unwrap();
+
+        let events = vec![
+            increase_event(liquidity, amount_0, amount_1, 1),
+            increase_event(liquidity, amount_0, amount_1, 3),
+            // withdraw half of the liquidity with fees
+            decrease_event(liquidity, (amount_0, amount_1), (100, 200), 5),
+        ];
+        let price_series = [
+            PricePoint {
+                event_seq: 0,
+                price: 1.0,
+            },
+            PricePoint {
+                event_seq: 4,
+                price: 1.0,
+            },
+        ];
+        personal_position.liquidity = liquidity;
+        personal_position.token_fees_owed_0 = 10;
+        personal_position.token_fees_owed_1 = 20;
+
+        let pnl = position_pnl(
+            &events,
+            &price_series,
+            &personal_position,
+            &pool_state.borrow(),
+        )
+        .unwrap();
+        let value = amount_0 as f64 + amount_1 as f64;
+        assert_close(pnl.deposited_value, 2.0 * value);
+        assert_close(pnl.withdrawn_value, value);
+        assert_close(pnl.realized_pnl, 0.0);
+        assert_close(pnl.collected_fee_value, 300.0);
+        assert_close(pnl.position_value, value);
+        assert_close(pnl.hodl_value, value);
+        assert_close(pnl.impermanent_loss, 0.0);
+        assert_close(pnl.unclaimed_fee_value, 30.0);
+        assert_close(pnl.total_pnl, 330.0);
+
+        // the price rises out of the range, the position is all token_1
+        let pool_state = build_pool(
+            1200,
+            60,
+            tick_math::get_sqrt_price_at_tick_raw(1200).unwrap(),
+            0,
+        );
+        let pnl = position_pnl(
+            &events,
+            &price_series,
+            &personal_position,
+            &pool_state.borrow(),
+        )
+        .unwrap();
+        let price = 1.0001f64.powi(1200);
+        assert_close(pnl.hodl_value, amount_0 as f64 * price + amount_1 as f64);
+        assert!(pnl.impermanent_loss < 0.0);
+        assert_close(pnl.unrealized_pnl, pnl.position_value - value);
+
+        // no price before the first event
+        assert!(position_pnl(
+            &events,
+            &price_series[1..],
+            &personal_position,
+            &pool_state.borrow()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! The program and admin ids of each deployment.
+//! The program is built for one deployment with the `localnet` or `devnet` feature, mainnet by default,
+//! clients and integration tests can select another deployment and pass its program id to the PDA helpers.
+use anchor_lang::prelude::*;
+
+/// A deployment of the program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cluster {
+    Localnet,
+    Devnet,
+    Mainnet,
+}
+
+impl Cluster {
+    /// The deployment the program is built for
+    #[cfg(feature = "localnet")]
+    pub const CURRENT: Cluster = Cluster::Localnet;
+    #[cfg(feature = "devnet")]
+    pub const CURRENT: Cluster = Cluster::Devnet;
+    #[cfg(all(not(feature = "localnet"), not(feature = "devnet")))]
+    pub const CURRENT: Cluster = Cluster::Mainnet;
+
+    pub const fn program_id(self) -> Pubkey {
+        match self {
+            Cluster::Localnet | Cluster::Devnet => {
+                pubkey!("45iBNkaENereLKMjLm2LHkF3hpDapf6mnvrM5HWFg9cY")
+            }
+            Cluster::Mainnet => pubkey!("REALQqNEomY6cQGZJUGwywTBD2UmDT32rZcNnfxQ5N2"),
+        }
+    }
+
+    pub const fn admin(self) -> Pubkey {
+        match self {
+            // localnet-eoa-sig
+            Cluster::Localnet => pubkey!("Fh3a8NURkS4ihZjbsMvtFB4T2GGqs4FgLXSVw6nMexRN"),
+            // devnet-multisig
+            Cluster::Devnet => pubkey!("9ZG4mYtayKedcDkRbpGAc13uQDT2Ag9twJBbuwia9Lqg"),
+            // mainnet-multisig
+            Cluster::Mainnet => pubkey!("AY196f8U5EvM999PVnvLmyvaUnzL4GLiFaGKUgnJXN6o"),
+        }
+    }
+
+    /// Parse the name of a cluster, `localnet`, `devnet` or `mainnet`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "localnet" => Some(Cluster::Localnet),
+            "devnet" => Some(Cluster::Devnet),
+            "mainnet" | "mainnet-beta" => Some(Cluster::Mainnet),
+            _ => None,
+        }
+    }
+}
+
+/// The env var overriding the program id used by the clients
+#[cfg(feature = "client")]
+pub const PROGRAM_ID_ENV: &str = "BYREAL_CLMM_PROGRAM_ID";
+/// The env var selecting the cluster of the program id used by the clients
+#[cfg(feature = "client")]
+pub const CLUSTER_ENV: &str = "BYREAL_CLMM_CLUSTER";
+
+/// Get the program id of the clients, from `BYREAL_CLMM_PROGRAM_ID`, then the deployment of `BYREAL_CLMM_CLUSTER`,
+/// otherwise the id the program is built with
+#[cfg(feature = "client")]
+pub fn program_id_from_env() -> std::result::Result<Pubkey, String> {
+    if let Ok(program_id) = std::env::var(PROGRAM_ID_ENV) {
+        return program_id
+            .trim()
+            .parse()
+            .map_err(|e| format!("invalid {}: {}", PROGRAM_ID_ENV, e));
+    }
+    if let Ok(cluster) = std::env::var(CLUSTER_ENV) {
+        return Cluster::from_name(&cluster)
+            .map(Cluster::program_id)
+            .ok_or(format!("invalid {}: {}", CLUSTER_ENV, cluster));
+    }
+    Ok(crate::id())
+}
+
+#[cfg(test)]
+mod program_ids_test {
+    use super::*;
+
+    #[test]
+    fn current_cluster_test() {
+        assert_eq!(Cluster::CURRENT.program_id(), crate::id());
+        assert_eq!(Cluster::CURRENT.admin(), crate::admin::ID);
+    }
+
+    #[test]
+    fn from_name_test() {
+        assert_eq!(Cluster::from_name("devnet"), Some(Cluster::Devnet));
+        assert_eq!(Cluster::from_name(" Mainnet-Beta "), Some(Cluster::Mainnet));
+        assert_eq!(Cluster::from_name("testnet"), None);
+    }
+}
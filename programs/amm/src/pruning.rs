@@ -0,0 +1,206 @@
+use crate::quote::{deserialize_zero_copy, TickArrayData};
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+/// The epoch an account was last written by the program, all the accounts of the program stamp it
+pub trait RecentEpoch {
+    fn recent_epoch(&self) -> u64;
+}
+
+impl RecentEpoch for PoolState {
+    fn recent_epoch(&self) -> u64 {
+        self.recent_epoch
+    }
+}
+
+impl RecentEpoch for PersonalPositionState {
+    fn recent_epoch(&self) -> u64 {
+        self.recent_epoch
+    }
+}
+
+impl RecentEpoch for ProtocolPositionState {
+    fn recent_epoch(&self) -> u64 {
+        self.recent_epoch
+    }
+}
+
+impl RecentEpoch for TickArrayState {
+    fn recent_epoch(&self) -> u64 {
+        self.recent_epoch
+    }
+}
+
+impl RecentEpoch for DynTickArrayState {
+    fn recent_epoch(&self) -> u64 {
+        self.recent_epoch
+    }
+}
+
+impl RecentEpoch for TickArrayData {
+    fn recent_epoch(&self) -> u64 {
+        match self {
+            TickArrayData::Fixed(tick_array) => tick_array.recent_epoch(),
+            TickArrayData::Dynamic(header, _) => header.recent_epoch(),
+        }
+    }
+}
+
+impl RecentEpoch for ObservationState {
+    fn recent_epoch(&self) -> u64 {
+        self.recent_epoch
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountKind {
+    Pool,
+    PersonalPosition,
+    ProtocolPosition,
+    TickArray,
+    Observation,
+}
+
+/// The recent epoch of a program account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountEpoch {
+    pub address: Pubkey,
+    pub kind: AccountKind,
+    pub recent_epoch: u64,
+}
+
+impl AccountEpoch {
+    /// Decode the recent epoch of an account from its data, None if it is not an account stamping it
+    pub fn decode(address: Pubkey, data: &[u8]) -> Option<Self> {
+        let (kind, recent_epoch) = if data.starts_with(PoolState::DISCRIMINATOR) {
+            let pool_state = deserialize_zero_copy::<PoolState>(data).ok()?;
+            (AccountKind::Pool, pool_state.recent_epoch())
+        } else if data.starts_with(PersonalPositionState::DISCRIMINATOR) {
+            let position = PersonalPositionState::try_deserialize(&mut &data[..]).ok()?;
+            (AccountKind::PersonalPosition, position.recent_epoch())
+        } else if data.starts_with(ProtocolPositionState::DISCRIMINATOR) {
+            let position = ProtocolPositionState::try_deserialize(&mut &data[..]).ok()?;
+            (AccountKind::ProtocolPosition, position.recent_epoch())
+        } else if data.starts_with(TickArrayState::DISCRIMINATOR)
+            || data.starts_with(DynTickArrayState::DISCRIMINATOR)
+        {
+            let tick_array = TickArrayData::try_deserialize(data).ok()?;
+            (AccountKind::TickArray, tick_array.recent_epoch())
+        } else if data.starts_with(ObservationState::DISCRIMINATOR) {
+            let observation_state = deserialize_zero_copy::<ObservationState>(data).ok()?;
+            (AccountKind::Observation, observation_state.recent_epoch())
+        } else {
+            return None;
+        };
+        Some(Self {
+            address,
+            kind,
+            recent_epoch,
+        })
+    }
+}
+
+/// Which accounts are listed by `prunable_accounts`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PruningPolicy {
+    /// The min number of epochs since the account was last written
+    pub min_idle_epochs: u64,
+    /// The kinds of accounts listed, all of them if empty
+    pub kinds: Vec<AccountKind>,
+}
+
+/// List the accounts untouched for at least `policy.min_idle_epochs`, the stalest first,
+/// e.g. to tier the storage of an RPC provider or to triage the accounts whose rent can be reclaimed.
+/// The accounts which are not program accounts stamping a recent epoch are skipped.
+pub fn prunable_accounts<'a>(
+    accounts: impl IntoIterator<Item = (Pubkey, &'a [u8])>,
+    current_epoch: u64,
+    policy: &PruningPolicy,
+) -> Vec<AccountEpoch> {
+    let mut prunable: Vec<AccountEpoch> = accounts
+        .into_iter()
+        .filter_map(|(address, data)| AccountEpoch::decode(address, data))
+        .filter(|account| policy.kinds.is_empty() || policy.kinds.contains(&account.kind))
+        .filter(|account| {
+            current_epoch.saturating_sub(account.recent_epoch) >= policy.min_idle_epochs
+        })
+        .collect();
+    prunable.sort_by_key(|account| account.recent_epoch);
+    prunable
+}
+
+#[cfg(test)]
+mod pruning_test {
+    use super::*;
+
+    fn zero_copy_bytes<T: bytemuck::Pod + Discriminator>(account: &T) -> Vec<u8> {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(account));
+        data
+    }
+
+    #[test]
+    fn prunable_accounts_test() {
+        let pool_state = PoolState {
+            recent_epoch: 90,
+            ..Default::default()
+        };
+        let tick_array = TickArrayState {
+            recent_epoch: 50,
+            ..Default::default()
+        };
+        let position = PersonalPositionState {
+            recent_epoch: 70,
+            ..Default::default()
+        };
+        let mut position_data = Vec::new();
+        position.try_serialize(&mut position_data).unwrap();
+
+        let pool_data = zero_copy_bytes(&pool_state);
+        let tick_array_data = zero_copy_bytes(&tick_array);
+        let pool_id = Pubkey::new_unique();
+        let tick_array_id = Pubkey::new_unique();
+        let position_id = Pubkey::new_unique();
+        let accounts = vec![
+            (pool_id, pool_data.as_slice()),
+            (tick_array_id, tick_array_data.as_slice()),
+            (position_id, position_data.as_slice()),
+            // not a program account
+            (Pubkey::new_unique(), &[0u8; 16][..]),
+        ];
+
+        let policy = PruningPolicy {
+            min_idle_epochs: 30,
+            kinds: vec![],
+        };
+        assert_eq!(
+            prunable_accounts(accounts.clone(), 100, &policy),
+            vec![
+                AccountEpoch {
+                    address: tick_array_id,
+                    kind: AccountKind::TickArray,
+                    recent_epoch: 50,
+                },
+                AccountEpoch {
+                    address: position_id,
+                    kind: AccountKind::PersonalPosition,
+                    recent_epoch: 70,
+                },
+            ]
+        );
+
+        let policy = PruningPolicy {
+            min_idle_epochs: 10,
+            kinds: vec![AccountKind::Pool, AccountKind::PersonalPosition],
+        };
+        let prunable = prunable_accounts(accounts, 100, &policy);
+        assert_eq!(
+            prunable
+                .iter()
+                .map(|account| account.address)
+                .collect::<Vec<_>>(),
+            vec![position_id, pool_id]
+        );
+    }
+}
@@ -0,0 +1,1647 @@
+use crate::error::ErrorCode as ClmmErrorCode;
+use crate::libraries::tick_math;
+use crate::states::*;
+use crate::swap_engine::{check_sqrt_price_limit, normalize_sqrt_price_limit, swap_internal};
+pub use byreal_clmm_core::quote::{MintTransferFees, QuoteScenario, SwapQuote};
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::ZeroCopy;
+use std::cell::{RefCell, RefMut};
+use std::collections::VecDeque;
+
+/// Decode a zero copy account from its raw data, the data can be unaligned
+pub fn deserialize_zero_copy<T: ZeroCopy>(data: &[u8]) -> Result<T> {
+    if data.len() < 8 {
+        return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+    }
+    if !data.starts_with(T::DISCRIMINATOR) {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    let end = 8 + std::mem::size_of::<T>();
+    if data.len() < end {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(bytemuck::pod_read_unaligned(&data[8..end]))
+}
+
+/// Get the address of the tick array starting at `start_tick_index`, fixed or dynamic
+pub fn tick_array_address(pool_id: &Pubkey, start_tick_index: i32, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TICK_ARRAY_SEED.as_bytes(),
+            pool_id.as_ref(),
+            &start_tick_index.to_be_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+/// Guess the tick arrays a small swap crosses from the tick arrays recently crossed by swaps in the pool,
+/// so that the accounts of a swap can be built without scanning the tick array bitmap.
+/// Returns the start indexes in the swap direction, beginning with the tick array of the current tick.
+pub fn hot_tick_array_start_indexes(pool_state: &PoolState, zero_for_one: bool) -> Vec<i32> {
+    let current_start_index =
+        TickUtils::get_array_start_index(pool_state.tick_current, pool_state.tick_spacing);
+    let mut start_indexes: Vec<i32> = pool_state
+        .hot_tick_array_start_indexes()
+        .into_iter()
+        .filter(|&start_index| {
+            if zero_for_one {
+                start_index < current_start_index
+            } else {
+                start_index > current_start_index
+            }
+        })
+        .collect();
+    if zero_for_one {
+        start_indexes.sort_unstable_by(|a, b| b.cmp(a));
+    } else {
+        start_indexes.sort_unstable();
+    }
+    start_indexes.insert(0, current_start_index);
+    start_indexes
+}
+
+/// A decoded tick array account
+// a quote decodes a few tick arrays, boxing the dynamic header too isn't worth the indirection
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+pub enum TickArrayData {
+    Fixed(Box<TickArrayState>),
+    Dynamic(DynTickArrayState, Vec<TickState>),
+}
+
+impl TickArrayData {
+    /// Decode a fixed or dynamic tick array from the account data
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.starts_with(DynTickArrayState::DISCRIMINATOR) {
+            let header = deserialize_zero_copy::<DynTickArrayState>(data)?;
+            require_eq!(
+                data.len(),
+                header.all_data_len(),
+                ClmmErrorCode::InvalidTickArray
+            );
+            let ticks = data[DynTickArrayState::HEADER_LEN..]
+                .chunks_exact(std::mem::size_of::<TickState>())
+                .map(bytemuck::pod_read_unaligned)
+                .collect();
+            Ok(TickArrayData::Dynamic(header, ticks))
+        } else {
+            let tick_array = deserialize_zero_copy::<TickArrayState>(data)?;
+            Ok(TickArrayData::Fixed(Box::new(tick_array)))
+        }
+    }
+
+    /// Whether it is a fixed tick array, with all TickStates allocated
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, TickArrayData::Fixed(_))
+    }
+
+    pub fn pool_id(&self) -> Pubkey {
+        match self {
+            TickArrayData::Fixed(tick_array) => tick_array.pool_id,
+            TickArrayData::Dynamic(header, _) => header.pool_id,
+        }
+    }
+
+    pub fn start_tick_index(&self) -> i32 {
+        match self {
+            TickArrayData::Fixed(tick_array) => tick_array.start_tick_index,
+            TickArrayData::Dynamic(header, _) => header.start_tick_index,
+        }
+    }
+
+    /// Get the allocated, reserved, initialized TickStates and the remaining headroom of this tick array
+    pub fn capacity(&self) -> TickArrayCapacity {
+        match self {
+            TickArrayData::Fixed(tick_array) => {
+                TickArrayCapacity::fixed(tick_array.initialized_tick_count)
+            }
+            TickArrayData::Dynamic(header, _) => header.capacity(),
+        }
+    }
+
+    /// The initialized TickStates of this tick array
+    pub fn initialized_tick_states(&self) -> impl Iterator<Item = &TickState> {
+        let ticks: &[TickState] = match self {
+            TickArrayData::Fixed(tick_array) => &tick_array.ticks,
+            TickArrayData::Dynamic(_, ticks) => ticks,
+        };
+        ticks
+            .iter()
+            .filter(|tick_state| tick_state.is_initialized())
+    }
+
+    /// Get the TickState of `tick_index` if it is allocated in this tick array
+    pub fn tick_state(&self, tick_index: i32, tick_spacing: u16) -> Option<TickState> {
+        let tick_spacing = i32::from(tick_spacing);
+        let offset = tick_index - self.start_tick_index();
+        if offset < 0 || offset % tick_spacing != 0 || offset / tick_spacing >= TICK_ARRAY_SIZE {
+            return None;
+        }
+        let offset = (offset / tick_spacing) as usize;
+        match self {
+            TickArrayData::Fixed(tick_array) => Some(tick_array.ticks[offset]),
+            TickArrayData::Dynamic(header, ticks) => match header.tick_offset_index[offset] {
+                0 => None,
+                index => ticks.get(usize::from(index) - 1).copied(),
+            },
+        }
+    }
+}
+
+/// The max liquidity that can be added to a position of the range before either of its ticks reaches
+/// `TickUtils::max_liquidity_per_tick`, to validate the liquidity of a position before submitting it.
+/// A tick not found in `tick_arrays` is considered uninitialized.
+pub fn max_addable_liquidity(
+    pool_state: &PoolState,
+    tick_arrays: &[TickArrayData],
+    tick_lower_index: i32,
+    tick_upper_index: i32,
+) -> u128 {
+    let tick_spacing = pool_state.tick_spacing;
+    let max_liquidity_per_tick = TickUtils::max_liquidity_per_tick(tick_spacing);
+    [tick_lower_index, tick_upper_index]
+        .into_iter()
+        .map(|tick_index| {
+            let liquidity_gross = tick_arrays
+                .iter()
+                .filter(|tick_array| tick_array.pool_id() == pool_state.key())
+                .find_map(|tick_array| tick_array.tick_state(tick_index, tick_spacing))
+                .map_or(0, |tick_state| tick_state.liquidity_gross);
+            max_liquidity_per_tick.saturating_sub(liquidity_gross)
+        })
+        .min()
+        .unwrap()
+}
+
+/// The account format of a tick array, a start index has a single tick array account in either format
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickArrayFormat {
+    /// A `TickArrayState` account, with all TickStates allocated
+    Fixed,
+    /// A `DynTickArrayState` account, smaller than a fixed one but it can be reallocated by the
+    /// instructions adding ticks to it
+    Dynamic,
+    /// The account data wasn't provided, it has to be fetched to know its format
+    Unknown,
+}
+
+/// A tick array account needed by a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapTickArrayAccount {
+    pub start_tick_index: i32,
+    pub address: Pubkey,
+    pub format: TickArrayFormat,
+    /// The account data length, zero if the format is unknown
+    pub data_len: u32,
+}
+
+/// Get the initialized tick arrays a swap walks through, in the order `swap_internal` consumes them,
+/// with the format of each account found in `tick_arrays`, so that transaction builders can size
+/// the transaction and the loaded account data of a pool with both fixed and dynamic tick arrays.
+/// Returns at most `max_count` tick arrays, beginning with the first one the swap needs.
+pub fn swap_tick_array_accounts(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool_state: &PoolState,
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    tick_arrays: &[TickArrayData],
+    zero_for_one: bool,
+    max_count: usize,
+) -> Result<Vec<SwapTickArrayAccount>> {
+    let mut accounts = Vec::with_capacity(max_count);
+    if max_count == 0 {
+        return Ok(accounts);
+    }
+    let (_, mut start_index) =
+        pool_state.get_first_initialized_tick_array(tickarray_bitmap_extension, zero_for_one)?;
+    loop {
+        let (format, data_len) = match tick_arrays
+            .iter()
+            .find(|tick_array| tick_array.start_tick_index() == start_index)
+        {
+            Some(tick_array) if tick_array.is_fixed() => {
+                (TickArrayFormat::Fixed, tick_array.capacity().data_len)
+            }
+            Some(tick_array) => (TickArrayFormat::Dynamic, tick_array.capacity().data_len),
+            None => (TickArrayFormat::Unknown, 0),
+        };
+        accounts.push(SwapTickArrayAccount {
+            start_tick_index: start_index,
+            address: tick_array_address(pool_id, start_index, program_id),
+            format,
+            data_len,
+        });
+        if accounts.len() == max_count {
+            break;
+        }
+        start_index = match pool_state.next_initialized_tick_array_start_index(
+            tickarray_bitmap_extension,
+            start_index,
+            zero_for_one,
+        ) {
+            Ok(Some(next_start_index)) => next_start_index,
+            Ok(None) => break,
+            // the default bitmap is exhausted and the pool has no bitmap extension
+            Err(_) if tickarray_bitmap_extension.is_none() => break,
+            Err(e) => return Err(e),
+        };
+    }
+    Ok(accounts)
+}
+
+/// Why the walk over the initialized tick arrays of a swap direction stopped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoverageEnd {
+    /// `max_count` tick arrays were walked, the pool may have more liquidity beyond them
+    MaxCount,
+    /// No more initialized tick array in the direction, the liquidity really ends
+    NoMoreLiquidity,
+    /// The walk reached the boundary of the default bitmap and the bitmap extension was not provided,
+    /// the pool may have liquidity in far ranges
+    MissingBitmapExtension,
+}
+
+/// Which initialized tick arrays of a swap direction are available to a quote
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Start indexes of the initialized tick arrays provided, in the order a swap crosses them
+    pub found: Vec<i32>,
+    /// Start indexes of the initialized tick arrays marked in the bitmaps but not provided
+    pub gaps: Vec<i32>,
+    pub end: CoverageEnd,
+}
+
+impl CoverageReport {
+    /// Whether a quote can only be truncated by the liquidity of the pool, not by missing accounts
+    pub fn is_complete(&self) -> bool {
+        self.gaps.is_empty() && self.end == CoverageEnd::NoMoreLiquidity
+    }
+}
+
+/// Walk the initialized tick arrays of a swap direction through the default bitmap and, for far ranges,
+/// the bitmap extension, and report which of them are missing from `tick_arrays`,
+/// so that routers can tell a quote truncated by missing data from one truncated by missing liquidity.
+/// Walks at most `max_count` tick arrays, beginning with the first one the swap needs.
+pub fn tick_array_coverage(
+    pool_state: &PoolState,
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    tick_arrays: &[TickArrayData],
+    zero_for_one: bool,
+    max_count: usize,
+) -> Result<CoverageReport> {
+    let mut report = CoverageReport {
+        found: Vec::new(),
+        gaps: Vec::new(),
+        end: CoverageEnd::MaxCount,
+    };
+    if max_count == 0 {
+        return Ok(report);
+    }
+    // the bitmap extension is useless if the default bitmap covers all the ticks
+    let needs_bitmap_extension = pool_state
+        .is_overflow_default_tickarray_bitmap(vec![tick_math::MIN_TICK, tick_math::MAX_TICK]);
+    if tickarray_bitmap_extension.is_none()
+        && pool_state.is_overflow_default_tickarray_bitmap(vec![pool_state.tick_current])
+    {
+        report.end = CoverageEnd::MissingBitmapExtension;
+        return Ok(report);
+    }
+    let mut start_index = match pool_state
+        .get_first_initialized_tick_array(tickarray_bitmap_extension, zero_for_one)
+    {
+        Ok((_, start_index)) => start_index,
+        Err(e) if e == ClmmErrorCode::InsufficientLiquidityForDirection.into() => {
+            report.end = CoverageEnd::NoMoreLiquidity;
+            return Ok(report);
+        }
+        Err(_) if tickarray_bitmap_extension.is_none() => {
+            report.end = if needs_bitmap_extension {
+                CoverageEnd::MissingBitmapExtension
+            } else {
+                CoverageEnd::NoMoreLiquidity
+            };
+            return Ok(report);
+        }
+        Err(e) => return Err(e),
+    };
+    loop {
+        if tick_arrays
+            .iter()
+            .any(|tick_array| tick_array.start_tick_index() == start_index)
+        {
+            report.found.push(start_index);
+        } else {
+            report.gaps.push(start_index);
+        }
+        if report.found.len() + report.gaps.len() == max_count {
+            return Ok(report);
+        }
+        start_index = match pool_state.next_initialized_tick_array_start_index(
+            tickarray_bitmap_extension,
+            start_index,
+            zero_for_one,
+        ) {
+            Ok(Some(next_start_index)) => next_start_index,
+            Ok(None) => break,
+            Err(_) if tickarray_bitmap_extension.is_none() => {
+                if needs_bitmap_extension {
+                    report.end = CoverageEnd::MissingBitmapExtension;
+                    return Ok(report);
+                }
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+    }
+    report.end = CoverageEnd::NoMoreLiquidity;
+    Ok(report)
+}
+
+/// Tick array copies that can be mutated by `swap_internal` without touching the decoded accounts
+// the cells are kept in a Vec, already on the heap
+#[allow(clippy::large_enum_variant)]
+enum TickArrayCell {
+    Fixed(RefCell<TickArrayState>),
+    Dynamic(RefCell<DynTickArrayState>, RefCell<Vec<TickState>>),
+}
+
+impl TickArrayCell {
+    fn new(tick_array: &TickArrayData) -> Self {
+        match tick_array {
+            TickArrayData::Fixed(tick_array) => TickArrayCell::Fixed(RefCell::new(**tick_array)),
+            TickArrayData::Dynamic(header, ticks) => {
+                TickArrayCell::Dynamic(RefCell::new(*header), RefCell::new(ticks.clone()))
+            }
+        }
+    }
+
+    fn borrow_mut(&self) -> TickArrayContainerRefMut<'_> {
+        match self {
+            TickArrayCell::Fixed(tick_array) => {
+                TickArrayContainerRefMut::Fixed(tick_array.borrow_mut())
+            }
+            TickArrayCell::Dynamic(header, ticks) => TickArrayContainerRefMut::Dynamic((
+                header.borrow_mut(),
+                RefMut::map(ticks.borrow_mut(), |ticks| ticks.as_mut_slice()),
+            )),
+        }
+    }
+}
+
+/// Count the initialized ticks of `tick_arrays` a swap crosses from `tick_before` to `tick_after`,
+/// a tick is crossed down when the price moves below it, and crossed up when the price reaches it
+pub fn count_ticks_crossed<'a>(
+    tick_arrays: impl IntoIterator<Item = &'a TickArrayData>,
+    tick_before: i32,
+    tick_after: i32,
+    zero_for_one: bool,
+) -> u32 {
+    let (tick_lower, tick_upper) = if zero_for_one {
+        (tick_after, tick_before)
+    } else {
+        (tick_before, tick_after)
+    };
+    tick_arrays
+        .into_iter()
+        .flat_map(TickArrayData::initialized_tick_states)
+        .filter(|tick_state| {
+            let tick = tick_state.tick;
+            tick_lower < tick && tick <= tick_upper
+        })
+        .count() as u32
+}
+
+/// Simulate a swap on the decoded accounts, the same way as `swap_v2` does on chain
+///
+/// # Arguments
+///
+/// * `amm_config` - The config of the pool
+/// * `pool_state` - The pool to swap in
+/// * `tick_arrays` - The initialized tick arrays of the pool, in any order
+/// * `tickarray_bitmap_extension` - Required if the swap crosses the default tick array bitmap
+/// * `transfer_fees` - The transfer fees of the pool mints, the default for none
+/// * `amount_specified` - amount_in debited from the wallet if `is_base_input`,
+///   otherwise amount_out credited to the wallet, as `swap_v2` takes it
+/// * `sqrt_price_limit_x64` - The Q64.64 sqrt price √P limit, zero means no limit
+/// * `zero_for_one` - Swap token_0 for token_1 or the opposite
+/// * `is_base_input` - swap base input or swap base output
+/// * `block_timestamp` - The unix timestamp the swap is simulated at
+///
+pub fn compute_swap_quote(
+    amm_config: &AmmConfig,
+    pool_state: &PoolState,
+    tick_arrays: &[TickArrayData],
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    transfer_fees: &MintTransferFees,
+    amount_specified: u64,
+    sqrt_price_limit_x64: u128,
+    zero_for_one: bool,
+    is_base_input: bool,
+    block_timestamp: u64,
+) -> Result<SwapQuote> {
+    QuoteSnapshot::new(
+        amm_config,
+        pool_state,
+        tick_arrays,
+        tickarray_bitmap_extension,
+    )
+    .quote_with_transfer_fees(
+        &QuoteScenario {
+            amount_specified,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            is_base_input,
+        },
+        transfer_fees,
+        block_timestamp,
+    )
+}
+
+/// Quote the swaps of each of `amounts` as input, see `QuoteSnapshot::quote_curve`
+///
+/// # Arguments
+///
+/// * `amm_config` - The config of the pool
+/// * `pool_state` - The pool to swap in
+/// * `tick_arrays` - The initialized tick arrays of the pool, in any order
+/// * `tickarray_bitmap_extension` - Required if the swaps cross the default tick array bitmap
+/// * `zero_for_one` - Swap token_0 for token_1 or the opposite
+/// * `amounts` - The input amounts in non-decreasing order
+/// * `block_timestamp` - The unix timestamp the swaps are simulated at
+///
+pub fn quote_curve(
+    amm_config: &AmmConfig,
+    pool_state: &PoolState,
+    tick_arrays: &[TickArrayData],
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    zero_for_one: bool,
+    amounts: &[u64],
+    block_timestamp: u64,
+) -> Result<Vec<SwapQuote>> {
+    QuoteSnapshot::new(
+        amm_config,
+        pool_state,
+        tick_arrays,
+        tickarray_bitmap_extension,
+    )
+    .quote_curve(zero_for_one, amounts, block_timestamp)
+}
+
+/// The decoded accounts of a pool with its tick arrays sorted once, so that many scenarios can be
+/// quoted without filtering and sorting the tick arrays again.
+/// It only holds shared references and can be shared across threads.
+pub struct QuoteSnapshot<'a> {
+    amm_config: &'a AmmConfig,
+    pool_state: &'a PoolState,
+    tickarray_bitmap_extension: &'a Option<TickArrayBitmapExtension>,
+    /// The tick arrays of the pool in ascending start tick index order
+    sorted_tick_arrays: Vec<&'a TickArrayData>,
+}
+
+impl<'a> QuoteSnapshot<'a> {
+    pub fn new(
+        amm_config: &'a AmmConfig,
+        pool_state: &'a PoolState,
+        tick_arrays: &'a [TickArrayData],
+        tickarray_bitmap_extension: &'a Option<TickArrayBitmapExtension>,
+    ) -> Self {
+        let pool_id = pool_state.key();
+        let mut sorted_tick_arrays: Vec<&TickArrayData> = tick_arrays
+            .iter()
+            .filter(|tick_array| tick_array.pool_id() == pool_id)
+            .collect();
+        sorted_tick_arrays.sort_by_key(|tick_array| tick_array.start_tick_index());
+        Self {
+            amm_config,
+            pool_state,
+            tickarray_bitmap_extension,
+            sorted_tick_arrays,
+        }
+    }
+
+    /// Simulate a swap of `scenario` at `block_timestamp`, see `compute_swap_quote`
+    pub fn quote(&self, scenario: &QuoteScenario, block_timestamp: u64) -> Result<SwapQuote> {
+        require_gt!(block_timestamp, self.pool_state.open_time);
+        let mut simulation = self.simulation(scenario.zero_for_one)?;
+        let (amount_in, amount_out) = simulation.swap(self, scenario, block_timestamp)?;
+        let amount_swapped = if scenario.is_base_input {
+            amount_in
+        } else {
+            amount_out
+        };
+        let mut quote = simulation.quote(self, scenario.zero_for_one);
+        quote.is_partial_fill = amount_swapped < scenario.amount_specified;
+        Ok(quote)
+    }
+
+    /// Simulate a swap of `scenario` at `block_timestamp` with the transfer fees of the pool mints,
+    /// `scenario.amount_specified` is the wallet amount and the transfer fees are charged the same way as `swap_v2`
+    pub fn quote_with_transfer_fees(
+        &self,
+        scenario: &QuoteScenario,
+        transfer_fees: &MintTransferFees,
+        block_timestamp: u64,
+    ) -> Result<SwapQuote> {
+        transfer_fees.quote_with_transfer_fees(scenario, |scenario| {
+            self.quote(scenario, block_timestamp)
+        })
+    }
+
+    /// Quote the swaps of each of `amounts` as input, for depth charts.
+    /// Each point continues the simulation of the previous one with the amount difference,
+    /// instead of simulating from the pool state again, so the points can differ from
+    /// `quote` by the rounding of the swap steps.
+    /// Once the pool runs out of liquidity in the swap direction, the remaining points repeat the last quote.
+    ///
+    /// # Arguments
+    ///
+    /// * `zero_for_one` - Swap token_0 for token_1 or the opposite
+    /// * `amounts` - The input amounts in non-decreasing order
+    /// * `block_timestamp` - The unix timestamp the swaps are simulated at
+    ///
+    pub fn quote_curve(
+        &self,
+        zero_for_one: bool,
+        amounts: &[u64],
+        block_timestamp: u64,
+    ) -> Result<Vec<SwapQuote>> {
+        require_gt!(block_timestamp, self.pool_state.open_time);
+        require!(
+            amounts.windows(2).all(|w| w[0] <= w[1]),
+            ErrorCode::RequireViolated
+        );
+        let mut simulation = self.simulation(zero_for_one)?;
+        let mut quotes = Vec::with_capacity(amounts.len());
+        let mut amount_in = 0;
+        let mut exhausted = false;
+        for &amount in amounts {
+            if !exhausted && amount > amount_in {
+                let scenario = QuoteScenario {
+                    amount_specified: amount - amount_in,
+                    sqrt_price_limit_x64: 0,
+                    zero_for_one,
+                    is_base_input: true,
+                };
+                let (step_amount_in, _) = simulation.swap(self, &scenario, block_timestamp)?;
+                exhausted = step_amount_in < scenario.amount_specified;
+                amount_in += step_amount_in;
+            }
+            let mut quote = simulation.quote(self, zero_for_one);
+            quote.is_partial_fill = amount_in < amount;
+            quotes.push(quote);
+        }
+        Ok(quotes)
+    }
+
+    fn simulation(&self, zero_for_one: bool) -> Result<Simulation> {
+        require!(
+            !self.sorted_tick_arrays.is_empty(),
+            ClmmErrorCode::NotEnoughTickArrayAccount
+        );
+        // swap_internal consumes the tick arrays in the swap direction
+        let tick_array_cells = if zero_for_one {
+            self.sorted_tick_arrays
+                .iter()
+                .rev()
+                .map(|tick_array| TickArrayCell::new(tick_array))
+                .collect()
+        } else {
+            self.sorted_tick_arrays
+                .iter()
+                .map(|tick_array| TickArrayCell::new(tick_array))
+                .collect()
+        };
+        let observation_state = ObservationState {
+            pool_id: self.pool_state.key(),
+            ..Default::default()
+        };
+        Ok(Simulation {
+            pool: RefCell::new(*self.pool_state),
+            observation: RefCell::new(observation_state),
+            tick_array_cells,
+            amount_0: 0,
+            amount_1: 0,
+        })
+    }
+}
+
+/// The copies of the pool and tick arrays mutated by `swap_internal`, without touching the decoded accounts
+struct Simulation {
+    pool: RefCell<PoolState>,
+    observation: RefCell<ObservationState>,
+    tick_array_cells: Vec<TickArrayCell>,
+    /// The accumulated amounts of all the simulated swaps
+    amount_0: u64,
+    amount_1: u64,
+}
+
+impl Simulation {
+    /// Simulate a swap on the current state of the copies, returns the (amount_in, amount_out) of the swap
+    fn swap(
+        &mut self,
+        snapshot: &QuoteSnapshot,
+        scenario: &QuoteScenario,
+        block_timestamp: u64,
+    ) -> Result<(u64, u64)> {
+        let zero_for_one = scenario.zero_for_one;
+        let sqrt_price_limit_x64 =
+            normalize_sqrt_price_limit(scenario.sqrt_price_limit_x64, zero_for_one)?;
+        check_sqrt_price_limit(
+            self.pool.borrow().sqrt_price_x64,
+            sqrt_price_limit_x64,
+            zero_for_one,
+        )?;
+        let mut tick_array_states: VecDeque<TickArrayContainerRefMut> = self
+            .tick_array_cells
+            .iter()
+            .map(TickArrayCell::borrow_mut)
+            .collect();
+        let (amount_0, amount_1) = swap_internal(
+            snapshot.amm_config,
+            &mut self.pool.borrow_mut(),
+            &mut tick_array_states,
+            &mut self.observation.borrow_mut(),
+            snapshot.tickarray_bitmap_extension,
+            scenario.amount_specified,
+            sqrt_price_limit_x64,
+            zero_for_one,
+            scenario.is_base_input,
+            block_timestamp as u32,
+        )?;
+        self.amount_0 += amount_0;
+        self.amount_1 += amount_1;
+        Ok(if zero_for_one {
+            (amount_0, amount_1)
+        } else {
+            (amount_1, amount_0)
+        })
+    }
+
+    /// The accumulated quote of the simulated swaps since the pool state of `snapshot`
+    fn quote(&self, snapshot: &QuoteSnapshot, zero_for_one: bool) -> SwapQuote {
+        let pool_state = snapshot.pool_state;
+        let pool_after = self.pool.borrow();
+        let (amount_in, amount_out, fee_amount) = if zero_for_one {
+            (
+                self.amount_0,
+                self.amount_1,
+                fees_of_token_0(&pool_after).saturating_sub(fees_of_token_0(pool_state)),
+            )
+        } else {
+            (
+                self.amount_1,
+                self.amount_0,
+                fees_of_token_1(&pool_after).saturating_sub(fees_of_token_1(pool_state)),
+            )
+        };
+        SwapQuote {
+            amount_in,
+            amount_out,
+            amount_in_after_transfer_fee: amount_in,
+            amount_out_after_transfer_fee: amount_out,
+            fee_amount,
+            sqrt_price_x64: pool_after.sqrt_price_x64,
+            tick_current: pool_after.tick_current,
+            liquidity: pool_after.liquidity,
+            ticks_crossed: count_ticks_crossed(
+                snapshot.sorted_tick_arrays.iter().copied(),
+                pool_state.tick_current,
+                pool_after.tick_current,
+                zero_for_one,
+            ),
+            is_partial_fill: false,
+        }
+    }
+}
+
+/// Quote many scenarios against the same pool in parallel, for routers computing full price curves.
+/// The results are in the order of `scenarios`.
+///
+/// # Arguments
+///
+/// * `amm_config` - The config of the pool
+/// * `pool_state` - The pool to swap in
+/// * `tick_arrays` - The initialized tick arrays of the pool, in any order
+/// * `tickarray_bitmap_extension` - Required if the swaps cross the default tick array bitmap
+/// * `scenarios` - The swaps to quote
+/// * `block_timestamp` - The unix timestamp the swaps are simulated at
+///
+#[cfg(feature = "rayon")]
+pub fn par_quote(
+    amm_config: &AmmConfig,
+    pool_state: &PoolState,
+    tick_arrays: &[TickArrayData],
+    tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
+    scenarios: &[QuoteScenario],
+    block_timestamp: u64,
+) -> Vec<Result<SwapQuote>> {
+    use rayon::prelude::*;
+
+    let snapshot = QuoteSnapshot::new(
+        amm_config,
+        pool_state,
+        tick_arrays,
+        tickarray_bitmap_extension,
+    );
+    scenarios
+        .par_iter()
+        .map(|scenario| snapshot.quote(scenario, block_timestamp))
+        .collect()
+}
+
+fn fees_of_token_0(pool_state: &PoolState) -> u64 {
+    pool_state
+        .total_fees_token_0
+        .saturating_add(pool_state.protocol_fees_token_0)
+        .saturating_add(pool_state.fund_fees_token_0)
+}
+
+fn fees_of_token_1(pool_state: &PoolState) -> u64 {
+    pool_state
+        .total_fees_token_1
+        .saturating_add(pool_state.protocol_fees_token_1)
+        .saturating_add(pool_state.fund_fees_token_1)
+}
+
+#[cfg(test)]
+pub mod quote_test {
+    use super::*;
+    use crate::states::dyn_tick_array_test::{
+        build_dyn_tick_array_with_tick_states, DynamicTickArrayBuildType,
+    };
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+    use std::convert::identity;
+
+    #[test]
+    fn hot_tick_array_start_indexes_test() {
+        let pool_state = build_pool(-32395, 60, 3651942632306380802, 5124165121219);
+        {
+            let mut pool_state = pool_state.borrow_mut();
+            for start_index in [-32400, -36000, -28800, -39600, 0] {
+                pool_state.record_hot_tick_array(start_index);
+            }
+        }
+        // the tick array of the current tick first, then the others in the swap direction
+        assert_eq!(
+            hot_tick_array_start_indexes(&pool_state.borrow(), true),
+            vec![-32400, -36000, -39600]
+        );
+        assert_eq!(
+            hot_tick_array_start_indexes(&pool_state.borrow(), false),
+            vec![-32400, -28800, 0]
+        );
+    }
+
+    #[test]
+    fn compute_swap_quote_match_swap_internal_test() {
+        let tick_spacing = 60;
+        let pool_state = build_pool(-32395, tick_spacing, 3651942632306380802, 5124165121219);
+        let pool_id = pool_state.borrow().key();
+        let tick_arrays = [build_fix_tick_array_with_tick_states(
+                pool_id,
+                -36000,
+                tick_spacing,
+                vec![
+                    build_tick(-32460, 1194569667438, 536061033698).take(),
+                    build_tick(-32520, 790917615645, 790917615645).take(),
+                ],
+            ),
+            build_fix_tick_array_with_tick_states(
+                pool_id,
+                -32400,
+                tick_spacing,
+                vec![
+                    build_tick(-32400, 277065331032, -277065331032).take(),
+                    build_tick(-29220, 1330680689, -1330680689).take(),
+                ],
+            )];
+        for tick_array in tick_arrays.iter() {
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, tick_array.borrow().start_tick_index)
+                .unwrap();
+        }
+        let amm_config = AmmConfig {
+            trade_fee_rate: 1000,
+            protocol_fee_rate: 120000,
+            tick_spacing,
+            ..Default::default()
+        };
+        let block_timestamp = oracle::block_timestamp_mock();
+        let tick_array_datas: Vec<TickArrayData> = tick_arrays
+            .iter()
+            .map(|tick_array| TickArrayData::Fixed(Box::new(*tick_array.borrow())))
+            .collect();
+
+        let quote = compute_swap_quote(
+            &amm_config,
+            &pool_state.borrow(),
+            &tick_array_datas,
+            &None,
+            &MintTransferFees::default(),
+            12188240002,
+            0,
+            true,
+            true,
+            block_timestamp,
+        )
+        .unwrap();
+        // the quote doesn't touch the decoded pool
+        assert_eq!(identity(pool_state.borrow().tick_current), -32395);
+
+        let observation_state = ObservationState {
+            pool_id,
+            ..Default::default()
+        };
+        let (amount_0, amount_1) = swap_internal(
+            &amm_config,
+            &mut pool_state.borrow_mut(),
+            &mut VecDeque::from(vec![
+                TickArrayContainerRefMut::Fixed(tick_arrays[1].borrow_mut()),
+                TickArrayContainerRefMut::Fixed(tick_arrays[0].borrow_mut()),
+            ]),
+            &mut RefCell::new(observation_state).borrow_mut(),
+            &None,
+            12188240002,
+            tick_math::MIN_SQRT_PRICE_X64 + 1,
+            true,
+            true,
+            block_timestamp as u32,
+        )
+        .unwrap();
+
+        assert_eq!(quote.amount_in, amount_0);
+        assert_eq!(quote.amount_out, amount_1);
+        assert_eq!(
+            quote.fee_amount,
+            pool_state.borrow().total_fees_token_0 + pool_state.borrow().protocol_fees_token_0
+        );
+        assert_eq!(quote.sqrt_price_x64, identity(pool_state.borrow().sqrt_price_x64));
+        assert_eq!(quote.tick_current, identity(pool_state.borrow().tick_current));
+        assert_eq!(quote.liquidity, identity(pool_state.borrow().liquidity));
+    }
+
+    #[test]
+    fn exact_output_quote_match_swap_internal_test() {
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let block_timestamp = oracle::block_timestamp_mock();
+        let quote_exact_in = compute_swap_quote(
+            &amm_config,
+            &pool_state,
+            &tick_arrays,
+            &None,
+            &MintTransferFees::default(),
+            12188240002,
+            0,
+            true,
+            true,
+            block_timestamp,
+        )
+        .unwrap();
+        // crosses -32400 and stops above -32460
+        assert_eq!(quote_exact_in.tick_current, -32404);
+        assert_eq!(quote_exact_in.ticks_crossed, 1);
+        assert!(!quote_exact_in.is_partial_fill);
+
+        let amount_out = quote_exact_in.amount_out;
+        let quote = compute_swap_quote(
+            &amm_config,
+            &pool_state,
+            &tick_arrays,
+            &None,
+            &MintTransferFees::default(),
+            amount_out,
+            0,
+            true,
+            false,
+            block_timestamp,
+        )
+        .unwrap();
+
+        let pool_after = RefCell::new(pool_state);
+        let tick_array_cells: Vec<RefCell<TickArrayState>> = tick_arrays
+            .iter()
+            .map(|tick_array| match tick_array {
+                TickArrayData::Fixed(tick_array) => RefCell::new(**tick_array),
+                TickArrayData::Dynamic(..) => unreachable!(),
+            })
+            .collect();
+        let observation_state = ObservationState {
+            pool_id: pool_state.key(),
+            ..Default::default()
+        };
+        let (amount_0, amount_1) = swap_internal(
+            &amm_config,
+            &mut pool_after.borrow_mut(),
+            &mut VecDeque::from(vec![
+                TickArrayContainerRefMut::Fixed(tick_array_cells[1].borrow_mut()),
+                TickArrayContainerRefMut::Fixed(tick_array_cells[0].borrow_mut()),
+            ]),
+            &mut RefCell::new(observation_state).borrow_mut(),
+            &None,
+            amount_out,
+            tick_math::MIN_SQRT_PRICE_X64 + 1,
+            true,
+            false,
+            block_timestamp as u32,
+        )
+        .unwrap();
+        let pool_after = pool_after.borrow();
+
+        assert_eq!(
+            quote,
+            SwapQuote {
+                amount_in: amount_0,
+                amount_out: amount_1,
+                amount_in_after_transfer_fee: amount_0,
+                amount_out_after_transfer_fee: amount_1,
+                fee_amount: fees_of_token_0(&pool_after)
+                    .saturating_sub(fees_of_token_0(&pool_state)),
+                sqrt_price_x64: pool_after.sqrt_price_x64,
+                tick_current: pool_after.tick_current,
+                liquidity: pool_after.liquidity,
+                ticks_crossed: 1,
+                is_partial_fill: false,
+            }
+        );
+        assert_eq!(quote.amount_out, amount_out);
+
+        // the sqrt price limit is reached before the output is filled
+        let sqrt_price_limit_x64 = tick_math::get_sqrt_price_at_tick_raw(-32402).unwrap();
+        let quote = compute_swap_quote(
+            &amm_config,
+            &pool_state,
+            &tick_arrays,
+            &None,
+            &MintTransferFees::default(),
+            amount_out,
+            sqrt_price_limit_x64,
+            true,
+            false,
+            block_timestamp,
+        )
+        .unwrap();
+        assert!(quote.is_partial_fill);
+        assert!(quote.amount_out < amount_out);
+        assert_eq!(quote.sqrt_price_x64, sqrt_price_limit_x64);
+        assert_eq!(quote.ticks_crossed, 1);
+    }
+
+    #[test]
+    fn quote_with_transfer_fees_test() {
+        use crate::transfer_fee::{TransferFee, TransferFeeConfig};
+
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let block_timestamp = oracle::block_timestamp_mock();
+        let quote = |transfer_fees: &MintTransferFees, amount: u64, is_base_input: bool| {
+            compute_swap_quote(
+                &amm_config,
+                &pool_state,
+                &tick_arrays,
+                &None,
+                transfer_fees,
+                amount,
+                0,
+                true,
+                is_base_input,
+                block_timestamp,
+            )
+            .unwrap()
+        };
+        let transfer_fee = |epoch: u64, transfer_fee_basis_points: u16| TransferFee {
+            epoch,
+            maximum_fee: u64::MAX,
+            transfer_fee_basis_points,
+        };
+        // 1% on token_0, 2% on token_1 from epoch 10
+        let transfer_fee_config_1 = TransferFeeConfig {
+            older_transfer_fee: transfer_fee(0, 100),
+            newer_transfer_fee: transfer_fee(10, 200),
+        };
+        let transfer_fees = MintTransferFees {
+            transfer_fee_config_0: Some(TransferFeeConfig {
+                older_transfer_fee: transfer_fee(0, 100),
+                newer_transfer_fee: transfer_fee(0, 100),
+            }),
+            transfer_fee_config_1: Some(transfer_fee_config_1),
+            epoch: 10,
+        };
+
+        // the input transfer fee is charged before the swap, the output one after
+        let quote_exact_in = quote(&transfer_fees, 1_000_000, true);
+        let quote_without_fees = quote(&MintTransferFees::default(), 990_000, true);
+        assert_eq!(quote_without_fees.amount_in_after_transfer_fee, 990_000);
+        assert_eq!(quote_exact_in.amount_in, 990_000);
+        assert_eq!(quote_exact_in.amount_out, quote_without_fees.amount_out);
+        assert_eq!(quote_exact_in.amount_in_after_transfer_fee, 1_000_000);
+        assert_eq!(
+            quote_exact_in.amount_out_after_transfer_fee,
+            quote_exact_in.amount_out
+                - transfer_fee_config_1.calculate_epoch_fee(10, quote_exact_in.amount_out)
+        );
+
+        // the older transfer fee applies before epoch 10
+        let quote_older_fee = quote(
+            &MintTransferFees {
+                epoch: 9,
+                ..transfer_fees
+            },
+            1_000_000,
+            true,
+        );
+        assert_eq!(quote_older_fee.amount_out, quote_exact_in.amount_out);
+        assert!(
+            quote_older_fee.amount_out_after_transfer_fee
+                > quote_exact_in.amount_out_after_transfer_fee
+        );
+
+        // the wallet receives exactly the specified output
+        let quote_exact_out = quote(&transfer_fees, 500_000, false);
+        assert_eq!(quote_exact_out.amount_out_after_transfer_fee, 500_000);
+        assert_eq!(
+            quote_exact_out.amount_out,
+            500_000
+                + transfer_fee_config_1
+                    .calculate_inverse_epoch_fee(10, 500_000)
+                    .unwrap()
+        );
+        assert!(quote_exact_out.amount_in_after_transfer_fee > quote_exact_out.amount_in);
+    }
+
+    #[test]
+    fn quote_sqrt_price_limit_wrong_side_test() {
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let sqrt_price_x64 = pool_state.sqrt_price_x64;
+        for (zero_for_one, sqrt_price_limit_x64, error) in [
+            (
+                true,
+                sqrt_price_x64 + 1,
+                ClmmErrorCode::LimitAboveCurrentForZeroForOne,
+            ),
+            (
+                false,
+                sqrt_price_x64 - 1,
+                ClmmErrorCode::LimitBelowCurrentForOneForZero,
+            ),
+        ] {
+            assert_eq!(
+                compute_swap_quote(
+                    &amm_config,
+                    &pool_state,
+                    &tick_arrays,
+                    &None,
+                    &MintTransferFees::default(),
+                    1_000_000,
+                    sqrt_price_limit_x64,
+                    zero_for_one,
+                    true,
+                    oracle::block_timestamp_mock(),
+                )
+                .err(),
+                Some(error.into())
+            );
+        }
+    }
+
+    #[test]
+    fn quote_jump_liquidity_gap_test() {
+        let tick_spacing = 60;
+        let amm_config = AmmConfig {
+            trade_fee_rate: 1000,
+            tick_spacing,
+            ..Default::default()
+        };
+        let quote_from = |tick_current: i32, sqrt_price_x64: u128, liquidity: u128| {
+            let pool_state = build_pool(tick_current, tick_spacing, sqrt_price_x64, liquidity);
+            let tick_array = build_fix_tick_array_with_tick_states(
+                pool_state.borrow().key(),
+                -32400,
+                tick_spacing,
+                vec![
+                    build_tick(-32400, 277065331032, -277065331032).take(),
+                    build_tick(-29220, 1330680689, -1330680689).take(),
+                    build_tick(-28860, 6408486554, -6408486554).take(),
+                ],
+            );
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, -32400)
+                .unwrap();
+            let quote = compute_swap_quote(
+                &amm_config,
+                &pool_state.borrow(),
+                &[TickArrayData::Fixed(Box::new(tick_array.take()))],
+                &None,
+                &MintTransferFees::default(),
+                1_000_000,
+                0,
+                true,
+                true,
+                oracle::block_timestamp_mock(),
+            )
+            .unwrap();
+            quote
+        };
+
+        // no liquidity above tick -28860, the quote jumps over the gap for free
+        let quote = quote_from(
+            -28000,
+            tick_math::get_sqrt_price_at_tick_raw(-28000).unwrap(),
+            0,
+        );
+        let quote_crossed = quote_from(
+            -28861,
+            tick_math::get_sqrt_price_at_tick_raw(-28860).unwrap(),
+            6408486554,
+        );
+        // only the quote jumping the gap crosses tick -28860
+        assert_eq!(quote.ticks_crossed, 1);
+        assert_eq!(
+            SwapQuote {
+                ticks_crossed: 0,
+                ..quote
+            },
+            quote_crossed
+        );
+        assert_eq!(quote.liquidity, 6408486554);
+    }
+
+    #[test]
+    fn swap_tick_array_accounts_test() {
+        let (_, pool_state, mut tick_arrays) = build_quote_accounts();
+        let pool_id = pool_state.key();
+
+        // migrate the tick array -36000 to a dynamic tick array
+        let (header, ticks) = build_dyn_tick_array_with_tick_states(
+            pool_id,
+            -36000,
+            60,
+            DynamicTickArrayBuildType::FromStartIndex,
+            vec![
+                build_tick(-32460, 1194569667438, 536061033698).take(),
+                build_tick(-32520, 790917615645, 790917615645).take(),
+            ],
+        );
+        tick_arrays[0] = TickArrayData::Dynamic(header.take(), ticks.take());
+        let dyn_data_len = tick_arrays[0].capacity().data_len;
+        let fixed_data_len = tick_arrays[1].capacity().data_len;
+        assert!(dyn_data_len < fixed_data_len);
+
+        let accounts = swap_tick_array_accounts(
+            &crate::id(),
+            &pool_id,
+            &pool_state,
+            &None,
+            &tick_arrays,
+            true,
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            accounts,
+            vec![
+                SwapTickArrayAccount {
+                    start_tick_index: -32400,
+                    address: tick_array_address(&pool_id, -32400, &crate::id()),
+                    format: TickArrayFormat::Fixed,
+                    data_len: fixed_data_len,
+                },
+                SwapTickArrayAccount {
+                    start_tick_index: -36000,
+                    address: tick_array_address(&pool_id, -36000, &crate::id()),
+                    format: TickArrayFormat::Dynamic,
+                    data_len: dyn_data_len,
+                },
+            ]
+        );
+
+        // limited by max_count, and the tick arrays not provided are unknown
+        let accounts = swap_tick_array_accounts(
+            &crate::id(),
+            &pool_id,
+            &pool_state,
+            &None,
+            &tick_arrays[..1],
+            true,
+            1,
+        )
+        .unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].start_tick_index, -32400);
+        assert_eq!(accounts[0].format, TickArrayFormat::Unknown);
+        assert_eq!(accounts[0].data_len, 0);
+    }
+
+    #[test]
+    fn tick_array_coverage_test() {
+        let (_, pool_state, tick_arrays) = build_quote_accounts();
+
+        let report = tick_array_coverage(&pool_state, &None, &tick_arrays, true, 10).unwrap();
+        assert_eq!(report.found, vec![-32400, -36000]);
+        assert!(report.gaps.is_empty());
+        // the default bitmap covers all the ticks of the tick spacing 60
+        assert_eq!(report.end, CoverageEnd::NoMoreLiquidity);
+        assert!(report.is_complete());
+
+        let report = tick_array_coverage(&pool_state, &None, &tick_arrays[1..], true, 10).unwrap();
+        assert_eq!(report.found, vec![-32400]);
+        assert_eq!(report.gaps, vec![-36000]);
+        assert!(!report.is_complete());
+
+        let report = tick_array_coverage(&pool_state, &None, &tick_arrays, true, 1).unwrap();
+        assert_eq!(report.found, vec![-32400]);
+        assert_eq!(report.end, CoverageEnd::MaxCount);
+        assert!(!report.is_complete());
+
+        // the far ranges of the tick spacing 1 are only in the bitmap extension
+        let pool_state = build_pool(0, 1, 1 << 64, 1000);
+        let pool_id = pool_state.borrow().key();
+        pool_state
+            .borrow_mut()
+            .flip_tick_array_bit(None, 0)
+            .unwrap();
+        let tick_arrays = vec![TickArrayData::Fixed(Box::new(
+            *build_fix_tick_array_with_tick_states(pool_id, 0, 1, vec![]).borrow(),
+        ))];
+        let report =
+            tick_array_coverage(&pool_state.borrow(), &None, &tick_arrays, false, 10).unwrap();
+        assert_eq!(report.found, vec![0]);
+        assert!(report.gaps.is_empty());
+        assert_eq!(report.end, CoverageEnd::MissingBitmapExtension);
+        assert!(!report.is_complete());
+    }
+
+    /// A pool with two initialized tick arrays below its current tick
+    pub fn build_quote_accounts() -> (AmmConfig, PoolState, Vec<TickArrayData>) {
+        let tick_spacing = 60;
+        let pool_state = build_pool(-32395, tick_spacing, 3651942632306380802, 5124165121219);
+        let pool_id = pool_state.borrow().key();
+        let tick_arrays = [build_fix_tick_array_with_tick_states(
+                pool_id,
+                -36000,
+                tick_spacing,
+                vec![
+                    build_tick(-32460, 1194569667438, 536061033698).take(),
+                    build_tick(-32520, 790917615645, 790917615645).take(),
+                ],
+            ),
+            build_fix_tick_array_with_tick_states(
+                pool_id,
+                -32400,
+                tick_spacing,
+                vec![
+                    build_tick(-32400, 277065331032, -277065331032).take(),
+                    build_tick(-29220, 1330680689, -1330680689).take(),
+                ],
+            )];
+        for tick_array in tick_arrays.iter() {
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, tick_array.borrow().start_tick_index)
+                .unwrap();
+        }
+        let amm_config = AmmConfig {
+            trade_fee_rate: 1000,
+            protocol_fee_rate: 120000,
+            tick_spacing,
+            ..Default::default()
+        };
+        let tick_array_datas = tick_arrays
+            .iter()
+            .map(|tick_array| TickArrayData::Fixed(Box::new(*tick_array.borrow())))
+            .collect();
+        (amm_config, pool_state.take(), tick_array_datas)
+    }
+
+    fn build_scenarios() -> Vec<QuoteScenario> {
+        [1_000_000, 100_000_000, 12188240002]
+            .into_iter()
+            .flat_map(|amount_specified| {
+                [(true, true), (true, false), (false, true), (false, false)].map(
+                    |(zero_for_one, is_base_input)| QuoteScenario {
+                        amount_specified,
+                        sqrt_price_limit_x64: 0,
+                        zero_for_one,
+                        is_base_input,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quote_snapshot_is_send_sync_test() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<QuoteSnapshot>();
+        assert_send_sync::<TickArrayData>();
+        assert_send_sync::<Result<SwapQuote>>();
+    }
+
+    #[test]
+    fn quote_snapshot_match_compute_swap_quote_test() {
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let block_timestamp = oracle::block_timestamp_mock();
+        let snapshot = QuoteSnapshot::new(&amm_config, &pool_state, &tick_arrays, &None);
+        for scenario in build_scenarios() {
+            let quote = compute_swap_quote(
+                &amm_config,
+                &pool_state,
+                &tick_arrays,
+                &None,
+                &MintTransferFees::default(),
+                scenario.amount_specified,
+                scenario.sqrt_price_limit_x64,
+                scenario.zero_for_one,
+                scenario.is_base_input,
+                block_timestamp,
+            )
+            .ok();
+            // quoting a scenario doesn't affect the next ones
+            assert_eq!(snapshot.quote(&scenario, block_timestamp).ok(), quote);
+            assert_eq!(snapshot.quote(&scenario, block_timestamp).ok(), quote);
+        }
+    }
+
+    /// The core crate layouts of the accounts, decoded from their account data
+    fn core_quote_accounts(
+        amm_config: &AmmConfig,
+        pool_state: &PoolState,
+        tick_arrays: &[TickArrayData],
+    ) -> (
+        byreal_clmm_core::states::AmmConfig,
+        byreal_clmm_core::states::PoolState,
+        Vec<byreal_clmm_core::quote::TickArrayData>,
+    ) {
+        use byreal_clmm_core::states::AccountLayout;
+
+        let mut data = vec![];
+        amm_config.try_serialize(&mut data).unwrap();
+        let core_amm_config =
+            byreal_clmm_core::states::AmmConfig::try_from_account_data(&data).unwrap();
+
+        let mut data = PoolState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(pool_state));
+        let core_pool_state =
+            byreal_clmm_core::states::PoolState::try_from_account_data(&data).unwrap();
+
+        let core_tick_arrays = tick_arrays
+            .iter()
+            .map(|tick_array| {
+                let data = match tick_array {
+                    TickArrayData::Fixed(tick_array) => {
+                        let mut data = TickArrayState::DISCRIMINATOR.to_vec();
+                        data.extend_from_slice(bytemuck::bytes_of(tick_array.as_ref()));
+                        data
+                    }
+                    TickArrayData::Dynamic(header, ticks) => {
+                        let mut data = DynTickArrayState::DISCRIMINATOR.to_vec();
+                        data.extend_from_slice(bytemuck::bytes_of(header));
+                        data.extend_from_slice(bytemuck::cast_slice(ticks));
+                        data
+                    }
+                };
+                byreal_clmm_core::quote::TickArrayData::try_deserialize(&data).unwrap()
+            })
+            .collect();
+        (core_amm_config, core_pool_state, core_tick_arrays)
+    }
+
+    fn assert_core_quote_match(
+        amm_config: &AmmConfig,
+        pool_state: &PoolState,
+        tick_arrays: &[TickArrayData],
+        block_timestamp: u64,
+    ) {
+        let (core_amm_config, core_pool_state, core_tick_arrays) =
+            core_quote_accounts(amm_config, pool_state, tick_arrays);
+        let pool_id = pool_state.key().to_bytes();
+        let limited_scenarios = build_scenarios().into_iter().map(|scenario| QuoteScenario {
+            sqrt_price_limit_x64: if scenario.zero_for_one {
+                3600000000000000000
+            } else {
+                3700000000000000000
+            },
+            ..scenario
+        });
+        for scenario in build_scenarios().into_iter().chain(limited_scenarios) {
+            let quote = compute_swap_quote(
+                amm_config,
+                pool_state,
+                tick_arrays,
+                &None,
+                &MintTransferFees::default(),
+                scenario.amount_specified,
+                scenario.sqrt_price_limit_x64,
+                scenario.zero_for_one,
+                scenario.is_base_input,
+                block_timestamp,
+            )
+            .ok();
+            let core_quote = byreal_clmm_core::quote::compute_swap_quote(
+                &core_amm_config,
+                &pool_id,
+                &core_pool_state,
+                &core_tick_arrays,
+                &MintTransferFees::default(),
+                scenario.amount_specified,
+                scenario.sqrt_price_limit_x64,
+                scenario.zero_for_one,
+                scenario.is_base_input,
+                block_timestamp,
+            )
+            .ok();
+            assert_eq!(core_quote, quote, "{:?}", scenario);
+        }
+    }
+
+    #[test]
+    fn core_quote_match_compute_swap_quote_test() {
+        let (amm_config, mut pool_state, tick_arrays) = build_quote_accounts();
+        let block_timestamp = oracle::block_timestamp_mock();
+        assert_core_quote_match(&amm_config, &pool_state, &tick_arrays, block_timestamp);
+
+        // the lower tick array is dynamic
+        let pool_id = pool_state.key();
+        let (dyn_tick_array, dyn_ticks) = build_dyn_tick_array_with_tick_states(
+            pool_id,
+            -36000,
+            60,
+            DynamicTickArrayBuildType::RandomIndex,
+            vec![
+                build_tick(-32460, 1194569667438, 536061033698).take(),
+                build_tick(-32520, 790917615645, 790917615645).take(),
+            ],
+        );
+        let mixed_tick_arrays = vec![
+            TickArrayData::Dynamic(dyn_tick_array.take(), dyn_ticks.take()),
+            tick_arrays[1].clone(),
+        ];
+        assert_core_quote_match(&amm_config, &pool_state, &mixed_tick_arrays, block_timestamp);
+
+        // a tick array is missing
+        assert_core_quote_match(&amm_config, &pool_state, &tick_arrays[1..], block_timestamp);
+
+        // the decay fee on both mints, three intervals after the open time
+        pool_state.open_time = block_timestamp - 30;
+        pool_state
+            .initialize_decay_fee(true, true, 80, 10, 10)
+            .unwrap();
+        assert_core_quote_match(&amm_config, &pool_state, &tick_arrays, block_timestamp);
+    }
+
+    #[test]
+    fn quote_curve_test() {
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let block_timestamp = oracle::block_timestamp_mock();
+        let snapshot = QuoteSnapshot::new(&amm_config, &pool_state, &tick_arrays, &None);
+        let amounts = [0, 1_000_000, 1_000_000, 100_000_000, 1_000_000_000];
+        for zero_for_one in [true, false] {
+            let curve = quote_curve(
+                &amm_config,
+                &pool_state,
+                &tick_arrays,
+                &None,
+                zero_for_one,
+                &amounts,
+                block_timestamp,
+            )
+            .unwrap();
+            assert_eq!(curve.len(), amounts.len());
+            assert_eq!(curve[0].amount_in, 0);
+            assert_eq!(curve[0].sqrt_price_x64, identity(pool_state.sqrt_price_x64));
+            assert_eq!(curve[1], curve[2]);
+            for (amount, quote) in amounts.iter().zip(curve.iter()).skip(1) {
+                let expected = snapshot
+                    .quote(
+                        &QuoteScenario {
+                            amount_specified: *amount,
+                            sqrt_price_limit_x64: 0,
+                            zero_for_one,
+                            is_base_input: true,
+                        },
+                        block_timestamp,
+                    )
+                    .unwrap();
+                assert_eq!(quote.amount_in, expected.amount_in);
+                // the incremental steps only differ by rounding
+                assert!(quote.amount_out.abs_diff(expected.amount_out) <= amounts.len() as u64);
+            }
+            for pair in curve.windows(2) {
+                assert!(pair[0].amount_out <= pair[1].amount_out);
+            }
+        }
+
+        // the amounts are not sorted
+        assert!(snapshot
+            .quote_curve(true, &[100, 10], block_timestamp)
+            .is_err());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_quote_test() {
+        let (amm_config, pool_state, tick_arrays) = build_quote_accounts();
+        let block_timestamp = oracle::block_timestamp_mock();
+        let scenarios = build_scenarios();
+        let snapshot = QuoteSnapshot::new(&amm_config, &pool_state, &tick_arrays, &None);
+        let quotes = par_quote(
+            &amm_config,
+            &pool_state,
+            &tick_arrays,
+            &None,
+            &scenarios,
+            block_timestamp,
+        );
+        assert_eq!(quotes.len(), scenarios.len());
+        for (scenario, quote) in scenarios.iter().zip(quotes) {
+            assert_eq!(quote.ok(), snapshot.quote(scenario, block_timestamp).ok());
+        }
+    }
+
+    #[test]
+    fn tick_array_data_deserialize_test() {
+        let pool_id = Pubkey::new_unique();
+        let tick_array = build_fix_tick_array_with_tick_states(
+            pool_id,
+            -36000,
+            60,
+            vec![build_tick(-32460, 100, 100).take()],
+        );
+        let mut data = TickArrayState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&*tick_array.borrow()));
+
+        let decoded = TickArrayData::try_deserialize(&data).unwrap();
+        assert_eq!(decoded.pool_id(), pool_id);
+        assert_eq!(decoded.start_tick_index(), -36000);
+        assert_eq!(
+            decoded.capacity().alloc_tick_count,
+            TICK_ARRAY_SIZE_USIZE as u8
+        );
+        assert_eq!(decoded.capacity().headroom_tick_count, 0);
+
+        assert!(TickArrayData::try_deserialize(&data[..8]).is_err());
+        data[0] = data[0].wrapping_add(1);
+        assert!(TickArrayData::try_deserialize(&data).is_err());
+    }
+
+    #[test]
+    fn max_addable_liquidity_test() {
+        let pool_state = build_pool(-32395, 60, 3651942632306380802, 5124165121219).take();
+        let pool_id = pool_state.key();
+        let max_liquidity_per_tick = TickUtils::max_liquidity_per_tick(60);
+        let fixed = build_fix_tick_array_with_tick_states(
+            pool_id,
+            -32400,
+            60,
+            vec![build_tick(-32400, 1000, 1000).take()],
+        );
+        let (dyn_tick_array, dyn_ticks) = build_dyn_tick_array_with_tick_states(
+            pool_id,
+            -36000,
+            60,
+            DynamicTickArrayBuildType::FromStartIndex,
+            vec![build_tick(-32460, 3000, -3000).take()],
+        );
+        let tick_arrays = vec![
+            TickArrayData::Fixed(Box::new(fixed.take())),
+            TickArrayData::Dynamic(dyn_tick_array.take(), dyn_ticks.take()),
+        ];
+        assert_eq!(
+            tick_arrays[0]
+                .tick_state(-32400, 60)
+                .map(|t| t.liquidity_gross),
+            Some(1000)
+        );
+        assert_eq!(
+            tick_arrays[0]
+                .tick_state(-32430, 60)
+                .map(|t| t.liquidity_gross),
+            None
+        );
+        assert_eq!(
+            tick_arrays[1]
+                .tick_state(-32460, 60)
+                .map(|t| t.liquidity_gross),
+            Some(3000)
+        );
+        assert!(tick_arrays[1].tick_state(-32520, 60).is_none());
+
+        assert_eq!(
+            max_addable_liquidity(&pool_state, &tick_arrays, -32460, -32400),
+            max_liquidity_per_tick - 3000
+        );
+        assert_eq!(
+            max_addable_liquidity(&pool_state, &tick_arrays, -32400, -32340),
+            max_liquidity_per_tick - 1000
+        );
+        // the ticks are not in the tick arrays
+        assert_eq!(
+            max_addable_liquidity(&pool_state, &tick_arrays, -600, 600),
+            max_liquidity_per_tick
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::libraries::liquidity_math;
+use crate::states::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+/// The accounts the liquidity backed by the vaults is recomputed from
+pub enum LiquiditySource<'a> {
+    /// All the open positions of the pool
+    Positions(&'a [PersonalPositionState]),
+    /// All the initialized ticks of the pool, in any order
+    Ticks(&'a [TickState]),
+}
+
+/// The expected vault balances of a pool and their discrepancy with the actual balances
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolReconciliation {
+    /// The token amounts of the liquidity at the current price, rounded down
+    pub liquidity_amount_0: u64,
+    pub liquidity_amount_1: u64,
+    /// The unclaimed LP fees, protocol fees and fund fees, see `PoolState::fee_liabilities`
+    pub fee_liabilities_0: u64,
+    pub fee_liabilities_1: u64,
+    pub expected_vault_0: u64,
+    pub expected_vault_1: u64,
+    /// The actual minus the expected vault balance, negative if the vault is short
+    pub discrepancy_0: i128,
+    pub discrepancy_1: i128,
+    /// The emitted but unclaimed amount of each reward, expected in the reward vaults
+    pub unclaimed_rewards: [u64; REWARD_NUM],
+}
+
+/// Recompute the expected vault balances of a pool from its liquidity, fee and reward accounting,
+/// and report the discrepancy with the actual vault balances.
+/// The liquidity is rounded down per position or per tick range, so a healthy pool has a small positive discrepancy.
+pub fn reconcile_pool(
+    pool_state: &PoolState,
+    vault_0_amount: u64,
+    vault_1_amount: u64,
+    source: LiquiditySource,
+) -> Result<PoolReconciliation> {
+    let mut liquidity_amount_0 = 0u64;
+    let mut liquidity_amount_1 = 0u64;
+    let mut add_range = |tick_lower: i32, tick_upper: i32, liquidity: u128| -> Result<()> {
+        if liquidity == 0 {
+            return Ok(());
+        }
+        let (amount_0, amount_1) = liquidity_math::get_delta_amounts_signed_raw(
+            pool_state.tick_current,
+            pool_state.sqrt_price_x64,
+            tick_lower,
+            tick_upper,
+            -i128::try_from(liquidity).map_err(|_| ErrorCode::RequireViolated)?,
+        )?;
+        liquidity_amount_0 = liquidity_amount_0.saturating_add(amount_0);
+        liquidity_amount_1 = liquidity_amount_1.saturating_add(amount_1);
+        Ok(())
+    };
+
+    match source {
+        LiquiditySource::Positions(positions) => {
+            for position in positions {
+                add_range(
+                    position.tick_lower_index,
+                    position.tick_upper_index,
+                    position.liquidity,
+                )?;
+            }
+        }
+        LiquiditySource::Ticks(ticks) => {
+            let mut ticks: Vec<&TickState> = ticks
+                .iter()
+                .filter(|tick_state| tick_state.liquidity_gross != 0)
+                .collect();
+            ticks.sort_by_key(|tick_state| tick_state.tick);
+            let mut liquidity: i128 = 0;
+            for window in ticks.windows(2) {
+                liquidity += window[0].liquidity_net;
+                add_range(
+                    window[0].tick,
+                    window[1].tick,
+                    u128::try_from(liquidity).map_err(|_| ErrorCode::RequireViolated)?,
+                )?;
+            }
+        }
+    }
+
+    let (fee_liabilities_0, fee_liabilities_1) = pool_state.fee_liabilities();
+    let expected_vault_0 = liquidity_amount_0.saturating_add(fee_liabilities_0);
+    let expected_vault_1 = liquidity_amount_1.saturating_add(fee_liabilities_1);
+    let mut unclaimed_rewards = [0u64; REWARD_NUM];
+    for (unclaimed, reward_info) in unclaimed_rewards.iter_mut().zip(pool_state.reward_infos) {
+        *unclaimed = reward_info
+            .reward_total_emissioned
+            .saturating_sub(reward_info.reward_claimed);
+    }
+
+    Ok(PoolReconciliation {
+        liquidity_amount_0,
+        liquidity_amount_1,
+        fee_liabilities_0,
+        fee_liabilities_1,
+        expected_vault_0,
+        expected_vault_1,
+        discrepancy_0: i128::from(vault_0_amount) - i128::from(expected_vault_0),
+        discrepancy_1: i128::from(vault_1_amount) - i128::from(expected_vault_1),
+        unclaimed_rewards,
+    })
+}
+
+#[cfg(test)]
+mod reconcile_test {
+    use super::*;
+    use crate::libraries::tick_math;
+    use crate::states::pool_test::build_pool;
+
+    fn position(tick_lower: i32, tick_upper: i32, liquidity: u128) -> PersonalPositionState {
+        PersonalPositionState {
+            tick_lower_index: tick_lower,
+            tick_upper_index: tick_upper,
+            liquidity,
+            ..Default::default()
+        }
+    }
+
+    fn tick(tick: i32, liquidity_net: i128) -> TickState {
+        TickState {
+            tick,
+            liquidity_net,
+            liquidity_gross: liquidity_net.unsigned_abs(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reconcile_pool_test() {
+        let pool_state = build_pool(0, 60, tick_math::get_sqrt_price_at_tick_raw(0).unwrap(), 0);
+        let mut pool_state = pool_state.borrow_mut();
+        pool_state.total_fees_token_0 = 1000;
+        pool_state.total_fees_claimed_token_0 = 400;
+        pool_state.protocol_fees_token_0 = 50;
+        pool_state.fund_fees_token_1 = 20;
+        pool_state.reward_infos[0].reward_total_emissioned = 300;
+        pool_state.reward_infos[0].reward_claimed = 100;
+
+        let positions = [
+            position(-600, 600, 1_000_000),
+            position(-120, 1200, 2_000_000),
+        ];
+        let by_positions = reconcile_pool(
+            &pool_state,
+            1_000_000,
+            1_000_000,
+            LiquiditySource::Positions(&positions),
+        )
+        .unwrap();
+        assert_eq!(by_positions.fee_liabilities_0, 650);
+        assert_eq!(by_positions.fee_liabilities_1, 20);
+        assert_eq!(
+            by_positions.expected_vault_0,
+            by_positions.liquidity_amount_0 + 650
+        );
+        assert_eq!(
+            by_positions.discrepancy_1,
+            1_000_000 - i128::from(by_positions.liquidity_amount_1) - 20
+        );
+        assert_eq!(by_positions.unclaimed_rewards, [200, 0, 0]);
+
+        // the ticks of the same positions give the same amounts, up to the rounding of each range
+        let ticks = [
+            tick(600, -1_000_000),
+            tick(-600, 1_000_000),
+            tick(1200, -2_000_000),
+            tick(-120, 2_000_000),
+        ];
+        let by_ticks = reconcile_pool(
+            &pool_state,
+            1_000_000,
+            1_000_000,
+            LiquiditySource::Ticks(&ticks),
+        )
+        .unwrap();
+        assert!(
+            by_ticks
+                .liquidity_amount_0
+                .abs_diff(by_positions.liquidity_amount_0)
+                <= 3
+        );
+        assert!(
+            by_ticks
+                .liquidity_amount_1
+                .abs_diff(by_positions.liquidity_amount_1)
+                <= 3
+        );
+
+        // a short vault
+        let short =
+            reconcile_pool(&pool_state, 0, 0, LiquiditySource::Positions(&positions)).unwrap();
+        assert_eq!(short.discrepancy_0, -i128::from(short.expected_vault_0));
+    }
+}
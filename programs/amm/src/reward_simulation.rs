@@ -0,0 +1,240 @@
+use crate::states::*;
+use std::convert::identity;
+
+/// The expected rewards of a position over a time horizon
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RewardSimulation {
+    /// The probability the position is in range at any time of the horizon
+    pub in_range_probability: f64,
+    /// The expected reward amounts earned over the horizon, by reward index.
+    /// Zero for an uninitialized reward, and excluding the rewards already owed to the position.
+    pub reward_amounts: [u64; REWARD_NUM],
+}
+
+/// Get the fraction of the time the average tick of the pool was in `[tick_lower, tick_upper)`, between
+/// consecutive observations, or `None` without two observations.
+pub fn in_range_probability(
+    observation_state: &ObservationState,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> Option<f64> {
+    let observation_index = usize::from(observation_state.observation_index);
+    let mut previous: Option<Observation> = None;
+    let mut total_time: u64 = 0;
+    let mut in_range_time: u64 = 0;
+    // from the oldest to the most recent observation
+    for offset in 1..=OBSERVATION_NUM {
+        let observation =
+            observation_state.observations[(observation_index + offset) % OBSERVATION_NUM];
+        if identity(observation.block_timestamp) == 0 {
+            continue;
+        }
+        if let Some(previous) = previous {
+            let time_delta = observation
+                .block_timestamp
+                .wrapping_sub(previous.block_timestamp);
+            if time_delta != 0 {
+                let average_tick = observation
+                    .tick_cumulative
+                    .wrapping_sub(previous.tick_cumulative)
+                    .div_euclid(i64::from(time_delta));
+                total_time += u64::from(time_delta);
+                if i64::from(tick_lower) <= average_tick && average_tick < i64::from(tick_upper) {
+                    in_range_time += u64::from(time_delta);
+                }
+            }
+        }
+        previous = Some(observation);
+    }
+    if total_time == 0 {
+        return None;
+    }
+    Some(in_range_time as f64 / total_time as f64)
+}
+
+/// Simulate the rewards a position earns over the next `horizon_secs` seconds, assuming the current
+/// emissions and liquidity of the pool, and the in range probability of the position from the tick history
+/// in `observation_state`. Without tick history, the position is assumed to stay in or out of range.
+/// The growth of the rewards is computed like the accrual on chain, in a single update.
+///
+/// # Arguments
+///
+/// * `personal_position` - The position to simulate the rewards of
+/// * `pool_state` - The pool of the position
+/// * `observation_state` - The observation account of the pool
+/// * `current_timestamp` - The start of the horizon
+/// * `horizon_secs` - The length of the horizon, in seconds
+///
+pub fn simulate_rewards(
+    personal_position: &PersonalPositionState,
+    pool_state: &PoolState,
+    observation_state: &ObservationState,
+    current_timestamp: u64,
+    horizon_secs: u64,
+) -> RewardSimulation {
+    let tick_current = pool_state.tick_current;
+    let is_in_range = personal_position.tick_lower_index <= tick_current
+        && tick_current < personal_position.tick_upper_index;
+    let in_range_probability = in_range_probability(
+        observation_state,
+        personal_position.tick_lower_index,
+        personal_position.tick_upper_index,
+    )
+    .unwrap_or(if is_in_range { 1.0 } else { 0.0 });
+
+    // the liquidity of the pool when the position is in range
+    let liquidity = if is_in_range {
+        pool_state.liquidity
+    } else {
+        pool_state
+            .liquidity
+            .saturating_add(personal_position.liquidity)
+    };
+
+    let mut reward_amounts = [0; REWARD_NUM];
+    if liquidity == 0 || personal_position.liquidity == 0 {
+        return RewardSimulation {
+            in_range_probability,
+            reward_amounts,
+        };
+    }
+    for (reward_amount, reward_info) in reward_amounts.iter_mut().zip(pool_state.reward_infos) {
+        if !reward_info.initialized() {
+            continue;
+        }
+        let start_time = current_timestamp.max(reward_info.open_time);
+        let end_time = current_timestamp
+            .saturating_add(horizon_secs)
+            .min(reward_info.end_time);
+        if end_time <= start_time {
+            continue;
+        }
+        let in_range_time = ((end_time - start_time) as f64 * in_range_probability) as u64;
+        let reward_growth_delta = RewardInfo::growth_delta_x64(
+            in_range_time,
+            reward_info.emissions_per_second_x64,
+            liquidity,
+        );
+        *reward_amount =
+            PositionRewardInfo::amount_owed_delta(reward_growth_delta, personal_position.liquidity);
+    }
+    RewardSimulation {
+        in_range_probability,
+        reward_amounts,
+    }
+}
+
+#[cfg(test)]
+mod reward_simulation_test {
+    use super::*;
+    use crate::libraries::fixed_point_64::Q64;
+    use crate::states::pool_test::build_pool;
+    use anchor_lang::prelude::Pubkey;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn build_position(tick_lower: i32, tick_upper: i32, liquidity: u128) -> PersonalPositionState {
+        PersonalPositionState {
+            tick_lower_index: tick_lower,
+            tick_upper_index: tick_upper,
+            liquidity,
+            ..Default::default()
+        }
+    }
+
+    /// The pool is at tick 50 for two periods, then at tick 500 for one
+    fn build_observation_state() -> ObservationState {
+        let mut observation_state = ObservationState::default();
+        let start = NOW as u32 - 3 * OBSERVATION_UPDATE_DURATION_DEFAULT;
+        observation_state.update(start, 0, 0);
+        for (i, tick) in [50, 50, 500].into_iter().enumerate() {
+            observation_state.update(
+                start + (i as u32 + 1) * OBSERVATION_UPDATE_DURATION_DEFAULT,
+                tick,
+                0,
+            );
+        }
+        observation_state
+    }
+
+    #[test]
+    fn in_range_probability_test() {
+        let observation_state = build_observation_state();
+        let probability = in_range_probability(&observation_state, 0, 100).unwrap();
+        assert!((probability - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(in_range_probability(&observation_state, 0, 1000), Some(1.0));
+        assert_eq!(in_range_probability(&observation_state, -100, 0), Some(0.0));
+        assert_eq!(
+            in_range_probability(&ObservationState::default(), 0, 100),
+            None
+        );
+    }
+
+    #[test]
+    fn simulate_rewards_test() {
+        let liquidity = 1u128 << 20;
+        let pool_state = build_pool(50, 10, 1 << 64, liquidity);
+        let reward_info = RewardInfo {
+            reward_state: 2,
+            open_time: NOW - 100,
+            end_time: NOW + 1000,
+            last_update_time: NOW,
+            emissions_per_second_x64: Q64,
+            token_mint: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        pool_state.borrow_mut().reward_infos[0] = reward_info;
+        // starting after the horizon
+        pool_state.borrow_mut().reward_infos[1] = RewardInfo {
+            open_time: NOW + 3000,
+            end_time: NOW + 4000,
+            ..reward_info
+        };
+        let observation_state = build_observation_state();
+        let position = build_position(0, 100, liquidity / 2);
+
+        // the horizon is cut at the end of the reward, 1000 seconds in range 2/3 of the time
+        let simulation = simulate_rewards(
+            &position,
+            &pool_state.borrow(),
+            &observation_state,
+            NOW,
+            3000,
+        );
+        assert_eq!(simulation.reward_amounts, [333, 0, 0]);
+
+        // the same amount as accrued on chain for the time in range
+        let reward_growth_global_x64 = pool_state
+            .borrow_mut()
+            .update_reward_infos(NOW + 666)
+            .unwrap()[0]
+            .reward_growth_global_x64;
+        assert_eq!(
+            PositionRewardInfo::amount_owed_delta(reward_growth_global_x64, position.liquidity),
+            simulation.reward_amounts[0]
+        );
+
+        // out of range without tick history, then with the position liquidity added in range
+        let out_of_range = build_position(100, 200, liquidity);
+        let simulation = simulate_rewards(
+            &out_of_range,
+            &pool_state.borrow(),
+            &ObservationState::default(),
+            NOW,
+            100,
+        );
+        assert_eq!(simulation.in_range_probability, 0.0);
+        assert_eq!(simulation.reward_amounts, [0; REWARD_NUM]);
+
+        let wide = build_position(-1000, 1000, liquidity);
+        let simulation = simulate_rewards(
+            &wide,
+            &pool_state.borrow(),
+            &ObservationState::default(),
+            NOW,
+            100,
+        );
+        assert_eq!(simulation.in_range_probability, 1.0);
+        assert_eq!(simulation.reward_amounts, [100, 0, 0]);
+    }
+}
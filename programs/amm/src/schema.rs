@@ -0,0 +1,1487 @@
+//! Account layout of each instruction, for transaction builders, explorers and fuzzers
+//! to construct or validate instructions without the IDL.
+//!
+//! Every table is declared together with the anchor `Accounts` struct it describes, a test
+//! builds the generated `crate::accounts` struct from the same field list and compares the
+//! account metas, so a table can't silently drift from its instruction.
+//! The accounts of a nested `Accounts` struct are named `field.account`.
+
+use anchor_lang::prelude::*;
+
+/// The account is read only
+pub const READONLY: u8 = 0;
+/// The account must be writable
+pub const WRITABLE: u8 = 1 << 0;
+/// The account must sign the transaction, including new keypair accounts created by the instruction
+pub const SIGNER: u8 = 1 << 1;
+/// The account can be omitted
+pub const OPTIONAL: u8 = 1 << 2;
+/// The account can be repeated any times, only used by remaining accounts
+pub const REPEATED: u8 = 1 << 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountSchema {
+    pub name: &'static str,
+    pub flags: u8,
+}
+
+impl AccountSchema {
+    pub const fn is_writable(&self) -> bool {
+        self.flags & WRITABLE != 0
+    }
+
+    pub const fn is_signer(&self) -> bool {
+        self.flags & SIGNER != 0
+    }
+
+    pub const fn is_optional(&self) -> bool {
+        self.flags & OPTIONAL != 0
+    }
+
+    pub const fn is_repeated(&self) -> bool {
+        self.flags & REPEATED != 0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InstructionSchema {
+    /// The instruction name, same as the method in the program module
+    pub name: &'static str,
+    /// The named accounts, in order
+    pub accounts: &'static [AccountSchema],
+    /// The remaining accounts, in order
+    pub remaining_accounts: &'static [AccountSchema],
+}
+
+impl InstructionSchema {
+    /// Check the account metas of an instruction against this schema, the remaining accounts are not checked
+    pub fn validate(&self, account_metas: &[AccountMeta]) -> bool {
+        if account_metas.len() < self.accounts.len() {
+            return false;
+        }
+        self.accounts
+            .iter()
+            .zip(account_metas)
+            .all(|(schema, meta)| {
+                (!schema.is_writable() || meta.is_writable)
+                    && (!schema.is_signer() || meta.is_signer)
+            })
+    }
+}
+
+/// Find the schema of an instruction by name
+pub fn find(name: &str) -> Option<&'static InstructionSchema> {
+    ALL.iter().find(|schema| schema.name == name)
+}
+
+#[cfg(test)]
+trait UniqueKey {
+    fn from_key(key: Pubkey) -> Self;
+}
+
+#[cfg(test)]
+impl UniqueKey for Pubkey {
+    fn from_key(key: Pubkey) -> Self {
+        key
+    }
+}
+
+#[cfg(test)]
+impl UniqueKey for Option<Pubkey> {
+    fn from_key(key: Pubkey) -> Self {
+        Some(key)
+    }
+}
+
+/// A new unique key for the next account of the table, recorded in `keys`
+#[cfg(test)]
+fn next_key<T: UniqueKey>(keys: &mut Vec<Pubkey>) -> T {
+    let key = Pubkey::new_unique();
+    keys.push(key);
+    T::from_key(key)
+}
+
+/// `keys` are the keys given to the accounts of the table in order, so each account meta
+/// maps back to the name of the field it was built from
+#[cfg(test)]
+fn check_in_sync(schema: &InstructionSchema, keys: &[Pubkey], account_metas: &[AccountMeta]) {
+    assert_eq!(
+        schema.accounts.len(),
+        account_metas.len(),
+        "{}",
+        schema.name
+    );
+    for (account, meta) in schema.accounts.iter().zip(account_metas) {
+        let field = keys
+            .iter()
+            .position(|key| *key == meta.pubkey)
+            .map(|index| schema.accounts[index].name);
+        assert_eq!(Some(account.name), field, "{}", schema.name);
+        assert_eq!(
+            account.is_writable(),
+            meta.is_writable,
+            "{}.{}",
+            schema.name,
+            account.name
+        );
+        // keypair accounts created by `init` sign the transaction but anchor doesn't mark them
+        if meta.is_signer {
+            assert!(account.is_signer(), "{}.{}", schema.name, account.name);
+        }
+    }
+}
+
+macro_rules! instruction_schemas {
+    ($(
+        $(#[$attr:meta])*
+        $ix:ident: $accounts:ident {
+            $($field:ident
+                $(: $($flag:ident)|+)?
+                $(@ $nested:ident { $($nested_field:ident: $nested_flags:expr),* $(,)? })?
+            ),* $(,)?
+        }
+        remaining {
+            $($remaining:ident: $remaining_flags:expr),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$attr])*
+            pub mod $ix {
+                use super::*;
+
+                pub const SCHEMA: InstructionSchema = InstructionSchema {
+                    name: stringify!($ix),
+                    accounts: &[$(
+                        $(AccountSchema { name: stringify!($field), flags: $($flag)|+ },)?
+                        $($(AccountSchema {
+                            name: concat!(stringify!($field), ".", stringify!($nested_field)),
+                            flags: $nested_flags,
+                        },)*)?
+                    )*],
+                    remaining_accounts: &[$(AccountSchema {
+                        name: stringify!($remaining),
+                        flags: $remaining_flags,
+                    }),*],
+                };
+
+                #[cfg(test)]
+                #[test]
+                fn accounts_schema_in_sync_test() {
+                    let mut keys = Vec::new();
+                    let account_metas = crate::accounts::$accounts {
+                        $($field:
+                            $({
+                                let _ = $($flag)|+;
+                                next_key(&mut keys)
+                            })?
+                            $(crate::accounts::$nested {
+                                $($nested_field: next_key(&mut keys)),*
+                            })?
+                        ),*
+                    }
+                    .to_account_metas(None);
+                    check_in_sync(&SCHEMA, &keys, &account_metas);
+                }
+            }
+        )*
+
+        /// The schemas of all described instructions
+        pub const ALL: &[InstructionSchema] = &[$($(#[$attr])* $ix::SCHEMA),*];
+    };
+}
+
+instruction_schemas! {
+    init_amm_admin_group: InitAdminGroupAccounts {
+        payer: WRITABLE | SIGNER,
+        admin_group: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    update_amm_admin_group: UpdateAdminGroupAccounts {
+        payer: WRITABLE | SIGNER,
+        admin_group: WRITABLE,
+    }
+    remaining {}
+
+    set_kill_switch_authority: SetKillSwitchAuthority {
+        payer: WRITABLE | SIGNER,
+        amm_config: READONLY,
+        kill_switch: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    create_amm_config: CreateAmmConfig {
+        owner: WRITABLE | SIGNER,
+        admin_group: READONLY,
+        amm_config: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    create_support_mint_associated: CreateSupportMintAssociated {
+        owner: WRITABLE | SIGNER,
+        admin_group: READONLY,
+        token_mint: READONLY,
+        support_mint_associated: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    update_support_mint_associated: UpdateSupportMintAssociated {
+        owner: SIGNER,
+        admin_group: READONLY,
+        support_mint_associated: WRITABLE,
+    }
+    remaining {}
+
+    update_amm_config: UpdateAmmConfig {
+        owner: SIGNER,
+        admin_group: READONLY,
+        amm_config: WRITABLE,
+    }
+    remaining {
+        new_owner: OPTIONAL,
+    }
+
+    set_swap_kill_switch: SetSwapKillSwitch {
+        authority: SIGNER,
+        kill_switch: READONLY,
+        amm_config: WRITABLE,
+    }
+    remaining {}
+
+    create_pool: CreatePool {
+        pool_creator: WRITABLE | SIGNER,
+        pool_manager: SIGNER,
+        admin_group: READONLY,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        offchain_reward_config: WRITABLE,
+        token_mint_0: READONLY,
+        token_mint_1: READONLY,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        observation_state: WRITABLE,
+        tick_array_bitmap: WRITABLE,
+        token_program_0: READONLY,
+        token_program_1: READONLY,
+        system_program: READONLY,
+        rent: READONLY,
+    }
+    remaining {
+        support_mint_associated: OPTIONAL | REPEATED,
+    }
+
+    create_pool_decay_fee: CreatePool {
+        pool_creator: WRITABLE | SIGNER,
+        pool_manager: SIGNER,
+        admin_group: READONLY,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        offchain_reward_config: WRITABLE,
+        token_mint_0: READONLY,
+        token_mint_1: READONLY,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        observation_state: WRITABLE,
+        tick_array_bitmap: WRITABLE,
+        token_program_0: READONLY,
+        token_program_1: READONLY,
+        system_program: READONLY,
+        rent: READONLY,
+    }
+    remaining {
+        support_mint_associated: OPTIONAL | REPEATED,
+    }
+
+    create_pool_from_template: CreatePool {
+        pool_creator: WRITABLE | SIGNER,
+        pool_manager: SIGNER,
+        admin_group: READONLY,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        offchain_reward_config: WRITABLE,
+        token_mint_0: READONLY,
+        token_mint_1: READONLY,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        observation_state: WRITABLE,
+        tick_array_bitmap: WRITABLE,
+        token_program_0: READONLY,
+        token_program_1: READONLY,
+        system_program: READONLY,
+        rent: READONLY,
+    }
+    remaining {
+        pool_template: READONLY,
+        support_mint_associated: OPTIONAL | REPEATED,
+    }
+
+    update_pool_status: UpdatePoolStatus {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {}
+
+    update_pool_status_bits: UpdatePoolStatusBits {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {
+        kill_switch: OPTIONAL,
+    }
+
+    retier_pool: RetierPool {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        new_amm_config: READONLY,
+        tick_array_bitmap: READONLY,
+    }
+    remaining {}
+
+    update_pool_maker_rebate: UpdatePoolMakerRebate {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {}
+
+    update_pool_min_position_age: UpdatePoolMinPositionAge {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {}
+
+    sunset_pool: SunsetPool {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {}
+
+    repair_tick: RepairTick {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+    }
+    remaining {}
+
+    create_pool_template: CreatePoolTemplate {
+        owner: WRITABLE | SIGNER,
+        admin_group: READONLY,
+        amm_config: READONLY,
+        pool_template: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    create_operation_account: CreateOperationAccount {
+        owner: WRITABLE | SIGNER,
+        admin_group: READONLY,
+        operation_state: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    update_operation_account: UpdateOperationAccount {
+        owner: SIGNER,
+        admin_group: READONLY,
+        operation_state: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    transfer_reward_owner: TransferRewardOwner {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {}
+
+    initialize_reward: InitializeReward {
+        reward_funder: WRITABLE | SIGNER,
+        admin_group: READONLY,
+        funder_token_account: WRITABLE,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        operation_state: READONLY,
+        reward_token_mint: READONLY,
+        reward_token_vault: WRITABLE,
+        reward_token_program: READONLY,
+        system_program: READONLY,
+        rent: READONLY,
+    }
+    remaining {
+        support_mint_associated: OPTIONAL,
+    }
+
+    collect_remaining_rewards: CollectRemainingRewards {
+        reward_funder: SIGNER,
+        admin_group: READONLY,
+        funder_token_account: WRITABLE,
+        pool_state: WRITABLE,
+        reward_token_vault: READONLY,
+        reward_vault_mint: READONLY,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+    }
+    remaining {}
+
+    update_reward_infos: UpdateRewardInfos {
+        pool_state: WRITABLE,
+    }
+    remaining {
+        crank_bounty: WRITABLE | OPTIONAL,
+        keeper: WRITABLE | SIGNER | OPTIONAL,
+    }
+
+    set_reward_params: SetRewardParams {
+        authority: SIGNER,
+        admin_group: READONLY,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        operation_state: READONLY,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+    }
+    remaining {
+        reward_token_vault: WRITABLE | OPTIONAL,
+        authority_token_account: WRITABLE | OPTIONAL,
+        reward_vault_mint: OPTIONAL,
+    }
+
+    deposit_offchain_reward: DepositOffchainRewardAccounts {
+        payer: WRITABLE | SIGNER,
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_id: READONLY,
+        token_mint: READONLY,
+        payer_token_account: WRITABLE,
+        reward_vault_token_account: WRITABLE,
+        reward_config: WRITABLE,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    claim_offchain_reward: ClaimOffchainRewardAccounts {
+        claimer: WRITABLE | SIGNER,
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_id: READONLY,
+        token_mint: READONLY,
+        claimer_token_account: WRITABLE,
+        reward_vault_token_account: WRITABLE,
+        reward_config: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+    }
+    remaining {}
+
+    withdraw_offchain_reward: WithdrawOffchainRewardAccounts {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_id: READONLY,
+        token_mint: READONLY,
+        receiver_token_account: WRITABLE,
+        reward_vault_token_account: WRITABLE,
+        reward_config: WRITABLE,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+    }
+    remaining {}
+
+    collect_protocol_fee: CollectProtocolFee {
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        recipient_token_account_0: WRITABLE,
+        recipient_token_account_1: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        associated_token_program: READONLY,
+    }
+    remaining {}
+
+    collect_fund_fee: CollectFundFee {
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        recipient_token_account_0: WRITABLE,
+        recipient_token_account_1: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        associated_token_program: READONLY,
+    }
+    remaining {}
+
+    fund_tick_rent_treasury: FundTickRentTreasury {
+        funder: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        tick_rent_treasury: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    withdraw_tick_rent_treasury: WithdrawTickRentTreasury {
+        owner: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        tick_rent_treasury: WRITABLE,
+    }
+    remaining {}
+
+    reserve_ticks: ReserveTicks {
+        payer: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    get_or_create_tick_array: GetOrCreateTickArray {
+        payer: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    audit_tick_array: AuditTickArray {
+        pool_state: WRITABLE,
+        tick_array: READONLY,
+    }
+    remaining {}
+
+    close_tick_array: CloseTickArray {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+        rent_receiver: WRITABLE,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+    }
+
+    migrate_tick_array_to_dynamic: MigrateTickArrayToDynamic {
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+        rent_receiver: WRITABLE,
+    }
+    remaining {}
+
+    #[cfg(feature = "tick-array-compression")]
+    freeze_tick_array: FreezeTickArray {
+        authority: SIGNER,
+        admin_group: READONLY,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+        rent_recipient: WRITABLE,
+    }
+    remaining {}
+
+    #[cfg(feature = "tick-array-compression")]
+    thaw_tick_array: ThawTickArray {
+        payer: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        tick_array: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    sync_pool_range_limits: SyncPoolRangeLimits {
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+    }
+    remaining {
+        crank_bounty: WRITABLE | OPTIONAL,
+        keeper: WRITABLE | SIGNER | OPTIONAL,
+    }
+
+    clear_decay_fee: ClearDecayFee {
+        pool_state: WRITABLE,
+    }
+    remaining {}
+
+    increase_observation_cardinality: IncreaseObservationCardinality {
+        payer: WRITABLE | SIGNER,
+        pool_state: READONLY,
+        observation_state: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    open_position: OpenPosition {
+        payer: WRITABLE | SIGNER,
+        position_nft_owner: READONLY,
+        position_nft_mint: WRITABLE | SIGNER,
+        position_nft_account: WRITABLE,
+        metadata_account: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        personal_position: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        metadata_program: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    open_position_v2: OpenPositionV2 {
+        payer: WRITABLE | SIGNER,
+        position_nft_owner: READONLY,
+        position_nft_mint: WRITABLE | SIGNER,
+        position_nft_account: WRITABLE,
+        metadata_account: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        personal_position: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        metadata_program: READONLY,
+        token_program_2022: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    open_position_with_token22_nft: OpenPositionWithToken22Nft {
+        payer: WRITABLE | SIGNER,
+        position_nft_owner: READONLY,
+        position_nft_mint: WRITABLE | SIGNER,
+        position_nft_account: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        personal_position: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        token_program_2022: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    open_position_with_exact_amounts: OpenPositionWithToken22Nft {
+        payer: WRITABLE | SIGNER,
+        position_nft_owner: READONLY,
+        position_nft_mint: WRITABLE | SIGNER,
+        position_nft_account: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        personal_position: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        token_program_2022: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    open_full_range_position: OpenFullRangePosition {
+        payer: WRITABLE | SIGNER,
+        position_nft_owner: READONLY,
+        position_nft_mint: WRITABLE | SIGNER,
+        position_nft_account: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        personal_position: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        token_program_2022: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    close_position: ClosePosition {
+        nft_owner: WRITABLE | SIGNER,
+        position_nft_mint: WRITABLE,
+        position_nft_account: WRITABLE,
+        personal_position: WRITABLE,
+        system_program: READONLY,
+        token_program: READONLY,
+    }
+    remaining {}
+
+    increase_liquidity: IncreaseLiquidity {
+        nft_owner: SIGNER,
+        nft_account: READONLY,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        personal_position: WRITABLE,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        token_program: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    increase_liquidity_v2: IncreaseLiquidityV2 {
+        nft_owner: SIGNER,
+        nft_account: READONLY,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        personal_position: WRITABLE,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+    }
+
+    decrease_liquidity: DecreaseLiquidity {
+        nft_owner: WRITABLE | SIGNER,
+        nft_account: READONLY,
+        personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        recipient_token_account_0: WRITABLE,
+        recipient_token_account_1: WRITABLE,
+        token_program: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+        reward_token_vault_and_recipient: WRITABLE | OPTIONAL | REPEATED,
+    }
+
+    decrease_liquidity_v2: DecreaseLiquidityV2 {
+        nft_owner: WRITABLE | SIGNER,
+        nft_account: READONLY,
+        personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        protocol_position: READONLY,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        recipient_token_account_0: WRITABLE,
+        recipient_token_account_1: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+        reward_token_vault_recipient_and_mint: WRITABLE | OPTIONAL | REPEATED,
+    }
+
+    move_liquidity: MoveLiquidity {
+        source @ DecreaseLiquidityV2 {
+            nft_owner: WRITABLE | SIGNER,
+            nft_account: READONLY,
+            personal_position: WRITABLE,
+            pool_state: WRITABLE,
+            protocol_position: READONLY,
+            token_vault_0: WRITABLE,
+            token_vault_1: WRITABLE,
+            tick_array_lower: WRITABLE,
+            tick_array_upper: WRITABLE,
+            recipient_token_account_0: WRITABLE,
+            recipient_token_account_1: WRITABLE,
+            token_program: READONLY,
+            token_program_2022: READONLY,
+            memo_program: READONLY,
+            vault_0_mint: READONLY,
+            vault_1_mint: READONLY,
+            amm_config: READONLY,
+        },
+        target @ OpenPositionV2 {
+            payer: WRITABLE | SIGNER,
+            position_nft_owner: READONLY,
+            position_nft_mint: WRITABLE | SIGNER,
+            position_nft_account: WRITABLE,
+            metadata_account: WRITABLE,
+            pool_state: WRITABLE,
+            protocol_position: READONLY,
+            tick_array_lower: WRITABLE,
+            tick_array_upper: WRITABLE,
+            personal_position: WRITABLE,
+            token_account_0: WRITABLE,
+            token_account_1: WRITABLE,
+            token_vault_0: WRITABLE,
+            token_vault_1: WRITABLE,
+            rent: READONLY,
+            system_program: READONLY,
+            token_program: READONLY,
+            associated_token_program: READONLY,
+            metadata_program: READONLY,
+            token_program_2022: READONLY,
+            vault_0_mint: READONLY,
+            vault_1_mint: READONLY,
+            amm_config: READONLY,
+        },
+        swap @ SwapSingleV2 {
+            payer: SIGNER,
+            amm_config: READONLY,
+            pool_state: WRITABLE,
+            input_token_account: WRITABLE,
+            output_token_account: WRITABLE,
+            input_vault: WRITABLE,
+            output_vault: WRITABLE,
+            observation_state: WRITABLE,
+            token_program: READONLY,
+            token_program_2022: READONLY,
+            memo_program: READONLY,
+            input_vault_mint: READONLY,
+            output_vault_mint: READONLY,
+        },
+    }
+    remaining {
+        source_tick_array_bitmap: OPTIONAL,
+        source_tick_rent_treasury: WRITABLE | OPTIONAL,
+        source_reward_token_vault_recipient_and_mint: WRITABLE | OPTIONAL | REPEATED,
+        target_tick_array_bitmap: OPTIONAL,
+        target_tick_rent_treasury: WRITABLE | OPTIONAL,
+        swap_tick_array_bitmap: OPTIONAL,
+        swap_tick_array: WRITABLE | OPTIONAL | REPEATED,
+    }
+
+    begin_deploy: BeginDeploy {
+        owner: WRITABLE | SIGNER,
+        pool_state: READONLY,
+        deployment_state: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    continue_deploy: ContinueDeploy {
+        owner: SIGNER,
+        deployment_state: WRITABLE,
+        nft_account: READONLY,
+        personal_position: READONLY,
+    }
+    remaining {}
+
+    finish_deploy: FinishDeploy {
+        owner: WRITABLE | SIGNER,
+        deployment_state: WRITABLE,
+        pool_state: WRITABLE,
+    }
+    remaining {
+        personal_position: OPTIONAL | REPEATED,
+    }
+
+    swap: SwapSingle {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        input_token_account: WRITABLE,
+        output_token_account: WRITABLE,
+        input_vault: WRITABLE,
+        output_vault: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        tick_array: WRITABLE,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | OPTIONAL | REPEATED,
+    }
+
+    swap_v2: SwapSingleV2 {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        input_token_account: WRITABLE,
+        output_token_account: WRITABLE,
+        input_vault: WRITABLE,
+        output_vault: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        input_vault_mint: READONLY,
+        output_vault_mint: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | REPEATED,
+    }
+
+    swap_v2_compact: SwapSingleV2 {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        input_token_account: WRITABLE,
+        output_token_account: WRITABLE,
+        input_vault: WRITABLE,
+        output_vault: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        input_vault_mint: READONLY,
+        output_vault_mint: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | REPEATED,
+    }
+
+    swap_v2_with_max_iterations: SwapSingleV2 {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        input_token_account: WRITABLE,
+        output_token_account: WRITABLE,
+        input_vault: WRITABLE,
+        output_vault: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        input_vault_mint: READONLY,
+        output_vault_mint: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | REPEATED,
+    }
+
+    swap_if_price: SwapSingleV2 {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        input_token_account: WRITABLE,
+        output_token_account: WRITABLE,
+        input_vault: WRITABLE,
+        output_vault: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        input_vault_mint: READONLY,
+        output_vault_mint: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | REPEATED,
+    }
+
+    swap_v2_delegated: SwapV2Delegated {
+        swap @ SwapSingleV2 {
+            payer: SIGNER,
+            amm_config: READONLY,
+            pool_state: WRITABLE,
+            input_token_account: WRITABLE,
+            output_token_account: WRITABLE,
+            input_vault: WRITABLE,
+            output_vault: WRITABLE,
+            observation_state: WRITABLE,
+            token_program: READONLY,
+            token_program_2022: READONLY,
+            memo_program: READONLY,
+            input_vault_mint: READONLY,
+            output_vault_mint: READONLY,
+        },
+        swap_delegate: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | REPEATED,
+    }
+
+    swap_v2_stp: SwapSingleV2 {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        input_token_account: WRITABLE,
+        output_token_account: WRITABLE,
+        input_vault: WRITABLE,
+        output_vault: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        input_vault_mint: READONLY,
+        output_vault_mint: READONLY,
+    }
+    remaining {
+        maker_personal_position_and_nft_account: OPTIONAL | REPEATED,
+        tick_array_bitmap: OPTIONAL,
+        tick_array: WRITABLE | REPEATED,
+    }
+
+    swap_batch: SwapBatch {
+        payer: SIGNER,
+        amm_config: READONLY,
+        pool_state: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        observation_state: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+    }
+    remaining {
+        zero_for_one_tick_array: WRITABLE | REPEATED,
+        tick_array_bitmap: OPTIONAL,
+        one_for_zero_tick_array: WRITABLE | REPEATED,
+    }
+
+    assert_pool_state: AssertPoolState {
+        pool_state: READONLY,
+    }
+    remaining {}
+
+    assert_recent_pool_update: AssertPoolState {
+        pool_state: READONLY,
+    }
+    remaining {}
+
+    value_position: ValuePosition {
+        pool_state: READONLY,
+        personal_position: READONLY,
+        tick_array_lower: READONLY,
+        tick_array_upper: READONLY,
+    }
+    remaining {}
+
+    reconcile_vaults: ReconcileVaults {
+        pool_state: READONLY,
+        token_vault_0: READONLY,
+        token_vault_1: READONLY,
+    }
+    remaining {}
+
+    set_position_collateral: SetPositionCollateral {
+        nft_owner: WRITABLE | SIGNER,
+        position_nft_mint: READONLY,
+        nft_account: WRITABLE,
+        personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        position_collateral: WRITABLE,
+        nft_custody: WRITABLE,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    release_position_collateral: ReleasePositionCollateral {
+        collateral_manager: SIGNER,
+        owner: WRITABLE,
+        position_collateral: WRITABLE,
+        personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        position_nft_mint: READONLY,
+        custody: WRITABLE,
+        nft_account: WRITABLE,
+        token_program: READONLY,
+    }
+    remaining {}
+
+    liquidate_position: LiquidatePosition {
+        collateral_manager: SIGNER,
+        position_collateral: READONLY,
+        nft_custody: READONLY,
+        personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        recipient_token_account_0: WRITABLE,
+        recipient_token_account_1: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+        amm_config: READONLY,
+    }
+    remaining {
+        tick_array_bitmap: OPTIONAL,
+        tick_rent_treasury: WRITABLE | OPTIONAL,
+        reward_token_vault_recipient_and_mint: WRITABLE | OPTIONAL | REPEATED,
+    }
+
+    claim_rewards_as: ClaimRewardsAs {
+        nft_owner: SIGNER,
+        nft_account: READONLY,
+        personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        tick_array_lower: READONLY,
+        tick_array_upper: READONLY,
+        output_token_account: WRITABLE,
+        output_token_mint: READONLY,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+    }
+    remaining {
+        reward_token_vault_recipient_and_mint: WRITABLE | REPEATED,
+        swap_pool_vaults_observation_and_tick_arrays: WRITABLE | OPTIONAL | REPEATED,
+    }
+
+    collect_fees_and_rewards: CollectFeesAndRewards {
+        nft_owner: SIGNER,
+        pool_state: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        recipient_token_account_0: WRITABLE,
+        recipient_token_account_1: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+        vault_0_mint: READONLY,
+        vault_1_mint: READONLY,
+    }
+    remaining {
+        reward_token_vault_recipient_and_mint: WRITABLE | OPTIONAL | REPEATED,
+        nft_account_position_and_tick_arrays: WRITABLE | REPEATED,
+    }
+
+    transfer_liquidity: TransferLiquidity {
+        nft_owner: SIGNER,
+        from_nft_account: READONLY,
+        from_position: WRITABLE,
+        to_nft_account: READONLY,
+        to_position: WRITABLE,
+        pool_state: WRITABLE,
+        tick_array_lower: READONLY,
+        tick_array_upper: READONLY,
+    }
+    remaining {}
+
+    split_position: SplitPosition {
+        payer: WRITABLE | SIGNER,
+        nft_owner: SIGNER,
+        nft_account: READONLY,
+        personal_position: WRITABLE,
+        new_position_nft_owner: READONLY,
+        new_position_nft_mint: WRITABLE | SIGNER,
+        new_position_nft_account: WRITABLE,
+        new_personal_position: WRITABLE,
+        pool_state: WRITABLE,
+        tick_array_lower: READONLY,
+        tick_array_upper: READONLY,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        token_program_2022: READONLY,
+    }
+    remaining {}
+
+    merge_positions: MergePositions {
+        nft_owner: WRITABLE | SIGNER,
+        nft_account: READONLY,
+        personal_position: WRITABLE,
+        merged_position_nft_mint: WRITABLE,
+        merged_nft_account: WRITABLE,
+        merged_position: WRITABLE,
+        pool_state: WRITABLE,
+        tick_array_lower: READONLY,
+        tick_array_upper: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+    }
+    remaining {}
+
+    initialize_pool_apr: InitializePoolApr {
+        payer: WRITABLE | SIGNER,
+        pool_state: READONLY,
+        pool_apr_state: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    update_pool_apr: UpdatePoolApr {
+        pool_state: WRITABLE,
+        pool_apr_state: WRITABLE,
+        token_vault_0: READONLY,
+        token_vault_1: READONLY,
+    }
+    remaining {
+        crank_bounty: WRITABLE | OPTIONAL,
+        keeper: WRITABLE | SIGNER | OPTIONAL,
+    }
+
+    fund_crank_bounty: FundCrankBounty {
+        funder: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        crank_bounty: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    fund_rebalance_bounty: FundRebalanceBounty {
+        funder: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        rebalance_bounty: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    register_rebalance_vault: RegisterRebalanceVault {
+        owner: WRITABLE | SIGNER,
+        funder: SIGNER,
+        nft_account: READONLY,
+        personal_position: READONLY,
+        pool_state: WRITABLE,
+        rebalance_vault: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    observe_rebalance_vault: ObserveRebalanceVault {
+        pool_state: READONLY,
+        rebalance_vault: WRITABLE,
+    }
+    remaining {}
+
+    claim_rebalance_bounty: ClaimRebalanceBounty {
+        keeper: WRITABLE | SIGNER,
+        pool_state: WRITABLE,
+        rebalance_bounty: WRITABLE,
+        rebalance_vault: WRITABLE,
+        from_personal_position: READONLY,
+        nft_account: READONLY,
+        personal_position: READONLY,
+    }
+    remaining {}
+
+    attach_metadata: AttachMetadata {
+        payer: WRITABLE | SIGNER,
+        nft_owner: SIGNER,
+        nft_account: READONLY,
+        personal_position: READONLY,
+        pool_state: READONLY,
+        position_nft_mint: WRITABLE,
+        token_program_2022: READONLY,
+        system_program: READONLY,
+    }
+    remaining {}
+
+    #[cfg(feature = "localnet")]
+    bootstrap_test_pool: BootstrapTestPool {
+        payer: WRITABLE | SIGNER,
+        faucet_authority: READONLY,
+        amm_config: READONLY,
+        token_mint_0: WRITABLE | SIGNER,
+        token_mint_1: WRITABLE | SIGNER,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        pool_state: WRITABLE,
+        offchain_reward_config: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        observation_state: WRITABLE,
+        tick_array_bitmap: WRITABLE,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        system_program: READONLY,
+        rent: READONLY,
+    }
+    remaining {}
+
+    #[cfg(feature = "localnet")]
+    faucet_mint_to: FaucetMintTo {
+        faucet_authority: READONLY,
+        token_mint: WRITABLE,
+        token_account: WRITABLE,
+        token_program: READONLY,
+    }
+    remaining {}
+
+    #[cfg(feature = "localnet")]
+    seed_test_position: SeedTestPosition {
+        payer: WRITABLE | SIGNER,
+        faucet_authority: READONLY,
+        position_nft_owner: READONLY,
+        position_nft_mint: WRITABLE | SIGNER,
+        position_nft_account: WRITABLE,
+        pool_state: WRITABLE,
+        tick_array_lower: WRITABLE,
+        tick_array_upper: WRITABLE,
+        personal_position: WRITABLE,
+        token_account_0: WRITABLE,
+        token_account_1: WRITABLE,
+        token_vault_0: WRITABLE,
+        token_vault_1: WRITABLE,
+        rent: READONLY,
+        system_program: READONLY,
+        token_program: READONLY,
+        associated_token_program: READONLY,
+        token_program_2022: READONLY,
+        vault_0_mint: WRITABLE,
+        amm_config: READONLY,
+        vault_1_mint: WRITABLE,
+    }
+    remaining {}
+
+    swap_router_base_in_v2: SwapRouterBaseIn {
+        payer: SIGNER,
+        input_token_account: WRITABLE,
+        input_token_mint: WRITABLE,
+        token_program: READONLY,
+        token_program_2022: READONLY,
+        memo_program: READONLY,
+    }
+    remaining {
+        hop_accounts_and_tick_arrays: WRITABLE | REPEATED,
+    }
+
+    close_protocol_position: CloseProtocolPositionAccounts {
+        admin_group: READONLY,
+        fee_keeper: WRITABLE,
+        protocol_position: WRITABLE,
+        system_program: READONLY,
+    }
+    remaining {}
+}
+
+#[cfg(test)]
+mod schema_test {
+    use super::*;
+
+    #[test]
+    fn idl_instructions_described_test() {
+        let idl: serde_json::Value =
+            serde_json::from_str(include_str!("../../../idl/byreal_clmm.json")).unwrap();
+        for instruction in idl["instructions"].as_array().unwrap() {
+            let name = instruction["name"].as_str().unwrap();
+            assert!(find(name).is_some(), "{} has no schema", name);
+        }
+    }
+
+    #[test]
+    fn program_instructions_described_test() {
+        // the IDL is only regenerated on release, the program module has the instructions of this build
+        let source = include_str!("lib.rs");
+        let program = &source[source.find("pub mod byreal_clmm").unwrap()..];
+        let mut previous_line = "";
+        for line in program.lines() {
+            if let Some(name) = line.trim().strip_prefix("pub fn ") {
+                let name = name.split(['(', '<']).next().unwrap();
+                // the schemas of the feature gated instructions are gated the same way
+                if !previous_line.trim().starts_with("#[cfg(") {
+                    assert!(find(name).is_some(), "{} has no schema", name);
+                }
+            }
+            previous_line = line;
+        }
+    }
+
+    #[test]
+    fn unique_instruction_names_test() {
+        for (i, schema) in ALL.iter().enumerate() {
+            assert!(
+                ALL[i + 1..].iter().all(|other| other.name != schema.name),
+                "{}",
+                schema.name
+            );
+        }
+    }
+}
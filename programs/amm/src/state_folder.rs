@@ -0,0 +1,259 @@
+use crate::quote::{deserialize_zero_copy, TickArrayData};
+use crate::states::*;
+use anchor_lang::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// The decoded value of an account followed by a `StateFolder`
+#[allow(clippy::large_enum_variant)] // the pool is boxed already, and most of the accounts are tick arrays
+enum FoldedAccount {
+    Pool(Box<PoolState>),
+    TickArray(TickArrayData),
+}
+
+/// An in-memory view of a pool and its tick arrays folded from raw account updates, e.g. of a geyser plugin.
+///
+/// The updates of a slot are staged until the slot is folded with `fold_slot`, so the view always holds
+/// whole blocks: the pool state and the tick arrays are consistent at `slot()`. The folded slots can be
+/// rolled back on a fork with `rollback`, until they are rooted with `root`.
+#[derive(Default)]
+pub struct StateFolder {
+    pool_id: Pubkey,
+    pool_state: Option<Box<PoolState>>,
+    tick_arrays: HashMap<Pubkey, TickArrayData>,
+    /// The staged updates of the slots not folded yet, by slot, in the order received
+    staged: BTreeMap<u64, Vec<(Pubkey, Option<FoldedAccount>)>>,
+    /// The previous values of the accounts updated by the folded slots that are not rooted, in folding order
+    undo_log: Vec<(u64, Pubkey, Option<FoldedAccount>)>,
+    folded_slot: u64,
+    rooted_slot: u64,
+}
+
+impl StateFolder {
+    pub fn new(pool_id: Pubkey) -> Self {
+        Self {
+            pool_id,
+            ..Default::default()
+        }
+    }
+
+    /// Stage the update of an account at `slot`, returns false if it is ignored:
+    /// the slot is already folded, or the account is neither the pool nor one of its tick arrays.
+    /// A tick array closed or frozen, i.e. with data that isn't a tick array any more, is removed from the view.
+    pub fn update(&mut self, pubkey: Pubkey, data: &[u8], slot: u64) -> bool {
+        if slot <= self.folded_slot {
+            return false;
+        }
+        let account = if pubkey == self.pool_id {
+            match deserialize_zero_copy::<PoolState>(data) {
+                Ok(pool_state) => Some(FoldedAccount::Pool(Box::new(pool_state))),
+                Err(_) => return false,
+            }
+        } else {
+            match TickArrayData::try_deserialize(data) {
+                Ok(tick_array) if tick_array.pool_id() == self.pool_id => {
+                    Some(FoldedAccount::TickArray(tick_array))
+                }
+                _ if self.is_followed(&pubkey) => None,
+                _ => return false,
+            }
+        };
+        self.staged.entry(slot).or_default().push((pubkey, account));
+        true
+    }
+
+    /// Fold the staged updates of all the slots up to `slot` into the view, in slot order
+    pub fn fold_slot(&mut self, slot: u64) {
+        if slot <= self.folded_slot {
+            return;
+        }
+        let later_slots = self.staged.split_off(&(slot + 1));
+        let staged = std::mem::replace(&mut self.staged, later_slots);
+        for (update_slot, updates) in staged {
+            for (pubkey, account) in updates {
+                let previous = self.replace(pubkey, account);
+                self.undo_log.push((update_slot, pubkey, previous));
+            }
+        }
+        self.folded_slot = slot;
+    }
+
+    /// Undo the folded slots after `slot` and drop their staged updates, when the cluster switches to a fork
+    /// whose last common slot is `slot`. The rooted slots can't be rolled back.
+    pub fn rollback(&mut self, slot: u64) -> Result<()> {
+        require_gte!(slot, self.rooted_slot);
+        while let Some((update_slot, pubkey, previous)) = self.undo_log.pop() {
+            if update_slot <= slot {
+                self.undo_log.push((update_slot, pubkey, previous));
+                break;
+            }
+            self.replace(pubkey, previous);
+        }
+        self.staged.retain(|staged_slot, _| *staged_slot <= slot);
+        self.folded_slot = self.folded_slot.min(slot);
+        Ok(())
+    }
+
+    /// Forget how to undo the slots up to `slot`, once it is rooted by the cluster
+    pub fn root(&mut self, slot: u64) {
+        let slot = slot.min(self.folded_slot);
+        self.undo_log
+            .retain(|(update_slot, _, _)| *update_slot > slot);
+        self.rooted_slot = self.rooted_slot.max(slot);
+    }
+
+    /// The last folded slot, the view is consistent at this slot
+    pub fn slot(&self) -> u64 {
+        self.folded_slot
+    }
+
+    pub fn pool_state(&self) -> Option<&PoolState> {
+        self.pool_state.as_deref()
+    }
+
+    /// The tick arrays of the pool in ascending start tick index order
+    pub fn tick_arrays(&self) -> Vec<&TickArrayData> {
+        let mut tick_arrays: Vec<&TickArrayData> = self.tick_arrays.values().collect();
+        tick_arrays.sort_by_key(|tick_array| tick_array.start_tick_index());
+        tick_arrays
+    }
+
+    fn is_followed(&self, pubkey: &Pubkey) -> bool {
+        self.tick_arrays.contains_key(pubkey)
+            || self.staged.values().flatten().any(|(key, _)| key == pubkey)
+    }
+
+    /// Set the value of an account in the view, returns the previous one
+    fn replace(&mut self, pubkey: Pubkey, account: Option<FoldedAccount>) -> Option<FoldedAccount> {
+        if pubkey == self.pool_id {
+            let previous = self.pool_state.take().map(FoldedAccount::Pool);
+            if let Some(FoldedAccount::Pool(pool_state)) = account {
+                self.pool_state = Some(pool_state);
+            }
+            previous
+        } else {
+            let previous = match account {
+                Some(FoldedAccount::TickArray(tick_array)) => {
+                    self.tick_arrays.insert(pubkey, tick_array)
+                }
+                _ => self.tick_arrays.remove(&pubkey),
+            };
+            previous.map(FoldedAccount::TickArray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod state_folder_test {
+    use super::*;
+    use crate::states::pool_test::build_pool;
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+    use anchor_lang::ZeroCopy;
+    use std::convert::identity;
+
+    fn account_data<T: ZeroCopy>(account: &T) -> Vec<u8> {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(account));
+        data
+    }
+
+    fn tick_array_data(pool_id: Pubkey, start_tick_index: i32, liquidity_gross: u128) -> Vec<u8> {
+        let tick_array = build_fix_tick_array_with_tick_states(
+            pool_id,
+            start_tick_index,
+            10,
+            vec![build_tick(start_tick_index + 10, liquidity_gross, 0).take()],
+        );
+        account_data(&tick_array.take())
+    }
+
+    fn liquidity_gross(folder: &StateFolder, start_tick_index: i32) -> Option<u128> {
+        folder
+            .tick_arrays()
+            .iter()
+            .find(|tick_array| tick_array.start_tick_index() == start_tick_index)
+            .map(|tick_array| {
+                tick_array
+                    .tick_state(start_tick_index + 10, 10)
+                    .unwrap()
+                    .liquidity_gross
+            })
+    }
+
+    #[test]
+    fn fold_slot_test() {
+        let pool_id = Pubkey::new_unique();
+        let tick_array_key = Pubkey::new_unique();
+        let folder = &mut StateFolder::new(pool_id);
+
+        let pool_state = build_pool(5, 10, 1 << 64, 1000).take();
+        assert!(folder.update(pool_id, &account_data(&pool_state), 10));
+        assert!(folder.update(tick_array_key, &tick_array_data(pool_id, 0, 1), 10));
+        // another pool, or another kind of account
+        assert!(!folder.update(
+            Pubkey::new_unique(),
+            &tick_array_data(Pubkey::new_unique(), 0, 1),
+            10
+        ));
+        assert!(!folder.update(Pubkey::new_unique(), &[1, 2, 3], 10));
+
+        // nothing is visible until the slot is folded
+        assert!(folder.pool_state().is_none());
+        assert!(folder.tick_arrays().is_empty());
+        folder.fold_slot(10);
+        assert_eq!(folder.slot(), 10);
+        assert_eq!(identity(folder.pool_state().unwrap().liquidity), 1000);
+        assert_eq!(liquidity_gross(folder, 0), Some(1));
+
+        // the last update of a slot wins, the slots already folded are fenced
+        assert!(folder.update(tick_array_key, &tick_array_data(pool_id, 0, 2), 11));
+        assert!(folder.update(tick_array_key, &tick_array_data(pool_id, 0, 3), 11));
+        assert!(!folder.update(tick_array_key, &tick_array_data(pool_id, 0, 9), 10));
+        folder.fold_slot(11);
+        assert_eq!(liquidity_gross(folder, 0), Some(3));
+
+        // a closed tick array is removed
+        assert!(folder.update(tick_array_key, &[], 12));
+        folder.fold_slot(12);
+        assert!(folder.tick_arrays().is_empty());
+    }
+
+    #[test]
+    fn rollback_test() {
+        let pool_id = Pubkey::new_unique();
+        let tick_array_key = Pubkey::new_unique();
+        let folder = &mut StateFolder::new(pool_id);
+
+        let mut pool_state = build_pool(5, 10, 1 << 64, 1000).take();
+        folder.update(pool_id, &account_data(&pool_state), 10);
+        folder.update(tick_array_key, &tick_array_data(pool_id, 0, 1), 10);
+        folder.fold_slot(10);
+
+        pool_state.liquidity = 2000;
+        folder.update(pool_id, &account_data(&pool_state), 11);
+        folder.update(tick_array_key, &[], 11);
+        folder.update(Pubkey::new_unique(), &tick_array_data(pool_id, 600, 1), 12);
+        folder.fold_slot(12);
+        // staged but not folded yet
+        folder.update(tick_array_key, &tick_array_data(pool_id, 0, 7), 13);
+        assert_eq!(identity(folder.pool_state().unwrap().liquidity), 2000);
+        assert_eq!(liquidity_gross(folder, 0), None);
+        assert_eq!(liquidity_gross(folder, 600), Some(1));
+
+        // the fork drops slots 11, 12 and 13
+        folder.rollback(10).unwrap();
+        assert_eq!(folder.slot(), 10);
+        assert_eq!(identity(folder.pool_state().unwrap().liquidity), 1000);
+        assert_eq!(liquidity_gross(folder, 0), Some(1));
+        assert_eq!(liquidity_gross(folder, 600), None);
+        folder.fold_slot(13);
+        assert_eq!(liquidity_gross(folder, 0), Some(1));
+
+        // the updates of the new fork are folded again, and can't be undone once rooted
+        folder.update(tick_array_key, &tick_array_data(pool_id, 0, 5), 14);
+        folder.fold_slot(14);
+        folder.root(14);
+        assert!(folder.rollback(13).is_err());
+        folder.rollback(14).unwrap();
+        assert_eq!(liquidity_gross(folder, 0), Some(5));
+    }
+}
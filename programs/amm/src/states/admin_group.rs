@@ -1,4 +1,5 @@
 use crate::error::ErrorCode;
+use crate::states::{AmmConfigKillSwitch, PoolStatusBitIndex};
 use anchor_lang::prelude::*;
 
 pub const ADMIN_GROUP_SEED: &str = "admin_group";
@@ -62,6 +63,23 @@ impl AmmAdminGroup {
 
         Ok(())
     }
+
+    /// The mask of the pool status bits `authority` may set or clear, any bit for the emergency manager,
+    /// only the swap bit for the kill switch authority of the amm config of the pool, if provided
+    pub fn pool_status_bits_allowed(
+        &self,
+        authority: &Pubkey,
+        kill_switch: Option<&AmmConfigKillSwitch>,
+    ) -> u8 {
+        let mut allowed = 0u8;
+        if *authority == self.emergency_manager {
+            allowed |= u8::MAX;
+        }
+        if kill_switch.is_some_and(|kill_switch| kill_switch.is_authority(authority)) {
+            allowed |= 1 << (PoolStatusBitIndex::Swap as u8);
+        }
+        allowed
+    }
 }
 
 #[event]
@@ -73,3 +91,45 @@ pub struct ModifyAmmAdminGroupEvent {
     pub emergency_manager: Pubkey,
     pub normal_manager: Pubkey,
 }
+
+#[cfg(test)]
+mod admin_group_test {
+    use super::*;
+
+    #[test]
+    fn pool_status_bits_allowed_test() {
+        let admin_group = AmmAdminGroup {
+            emergency_manager: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        let kill_switch = AmmConfigKillSwitch {
+            authority: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert_eq!(
+            admin_group.pool_status_bits_allowed(&admin_group.emergency_manager, None),
+            u8::MAX
+        );
+        assert_eq!(
+            admin_group.pool_status_bits_allowed(&kill_switch.authority, Some(&kill_switch)),
+            1 << (PoolStatusBitIndex::Swap as u8)
+        );
+        // the kill switch of the config of the pool must be provided
+        assert_eq!(
+            admin_group.pool_status_bits_allowed(&kill_switch.authority, None),
+            0
+        );
+        assert_eq!(
+            admin_group.pool_status_bits_allowed(&Pubkey::new_unique(), Some(&kill_switch)),
+            0
+        );
+        // no kill switch authority
+        assert_eq!(
+            admin_group.pool_status_bits_allowed(
+                &Pubkey::default(),
+                Some(&AmmConfigKillSwitch::default())
+            ),
+            0
+        );
+    }
+}
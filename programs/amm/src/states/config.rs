@@ -1,9 +1,14 @@
 use crate::error::ErrorCode;
+use crate::libraries::fee_math::fee_rate_to_bps;
+use crate::states::PoolStatusBitIndex;
 use anchor_lang::prelude::*;
 
 pub const AMM_CONFIG_SEED: &str = "amm_config";
 
-pub const FEE_RATE_DENOMINATOR_VALUE: u32 = 1_000_000;
+pub use crate::libraries::fee_math::FEE_RATE_DENOMINATOR_VALUE;
+
+/// The protocol max of the steps of a swap, each step crosses at most one initialized tick or tick array
+pub const MAX_SWAP_ITERATIONS: u16 = 1024;
 
 /// Holds the current owner of the factory
 #[account]
@@ -22,10 +27,23 @@ pub struct AmmConfig {
     pub tick_spacing: u16,
     /// The fund fee, denominated in hundredths of a bip (10^-6)
     pub fund_fee_rate: u32,
-    // padding space for upgrade
-    pub padding_u32: u32,
+    /// The min width in ticks of the positions opened in the pools of this config, 0 for no limit
+    pub min_range_ticks: u32,
     pub fund_owner: Pubkey,
-    pub padding: [u64; 3],
+    /// The max width in ticks of the positions opened in the pools of this config, 0 for no limit
+    pub max_range_ticks: u32,
+    /// Emergency status of all the pools of this config, with the bits of the pool status:
+    /// an operation is disabled in every pool of the config if its bit is set, whatever the pool status.
+    /// Honored by the swaps and the open, increase and decrease liquidity instructions, which take the config,
+    /// the collect bits only apply through the pool status
+    pub status: u8,
+    /// The TickState slots allocated up front by the dynamic tick arrays created in the pools of this config,
+    /// more rent on creation for fewer reallocations in busy pools. 0 or 1 allocates only the accessed tick
+    pub default_prealloc_ticks: u8,
+    /// The max steps of a swap in the pools of this config, 0 for `MAX_SWAP_ITERATIONS`
+    pub max_swap_iterations: u16,
+    // padding space for upgrade
+    pub padding: [u64; 2],
 }
 
 impl AmmConfig {
@@ -42,6 +60,66 @@ impl AmmConfig {
         );
         Ok(())
     }
+
+    /// Whether the operation of `bit` is enabled in the pools of this config, checked besides the pool status
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        let status = 1u8 << (bit as u8);
+        self.status & status == 0
+    }
+
+    /// Enable or disable the operation of `bit` in the pools of this config
+    pub fn set_status_by_bit(&mut self, bit: PoolStatusBitIndex, enabled: bool) {
+        let status = 1u8 << (bit as u8);
+        if enabled {
+            self.status &= !status;
+        } else {
+            self.status |= status;
+        }
+    }
+
+    /// The max steps of a swap, the override of the swap if any, otherwise the config default
+    pub fn get_max_swap_iterations(&self, max_swap_iterations: Option<u16>) -> Result<u16> {
+        let max_swap_iterations = max_swap_iterations.unwrap_or(self.max_swap_iterations);
+        require_gte!(
+            MAX_SWAP_ITERATIONS,
+            max_swap_iterations,
+            ErrorCode::InvalidMaxSwapIterations
+        );
+        Ok(if max_swap_iterations == 0 {
+            MAX_SWAP_ITERATIONS
+        } else {
+            max_swap_iterations
+        })
+    }
+
+    /// Check the width limits, zero is no limit
+    pub fn check_range_limits(min_range_ticks: u32, max_range_ticks: u32) -> Result<()> {
+        require!(
+            min_range_ticks == 0 || max_range_ticks == 0 || min_range_ticks <= max_range_ticks,
+            ErrorCode::InvalidRangeLimits
+        );
+        Ok(())
+    }
+
+    /// Check the trade fee rate is a whole number of basis points below 100%,
+    /// so that the fee tiers don't round oddly against FEE_RATE_DENOMINATOR_VALUE
+    pub fn check_trade_fee_rate(trade_fee_rate: u32) -> Result<()> {
+        fee_rate_to_bps(trade_fee_rate)?;
+        require!(
+            trade_fee_rate < FEE_RATE_DENOMINATOR_VALUE,
+            ErrorCode::InvalidFeeRate
+        );
+        Ok(())
+    }
+
+    /// Same as `check_trade_fee_rate`, but the current rate of the config is let through,
+    /// so that a fee tier created before the basis points rule can still be set again
+    pub fn check_trade_fee_rate_update(&self, trade_fee_rate: u32) -> Result<()> {
+        if trade_fee_rate == self.trade_fee_rate {
+            return Ok(());
+        }
+        Self::check_trade_fee_rate(trade_fee_rate)
+    }
 }
 
 /// Emitted when create or update a config
@@ -55,4 +133,119 @@ pub struct ConfigChangeEvent {
     pub tick_spacing: u16,
     pub fund_fee_rate: u32,
     pub fund_owner: Pubkey,
+    pub min_range_ticks: u32,
+    pub max_range_ticks: u32,
+    pub status: u8,
+    pub default_prealloc_ticks: u8,
+    pub max_swap_iterations: u16,
+}
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+
+    #[test]
+    fn core_layout_test() {
+        use byreal_clmm_core::states::{self as core_states, AccountLayout};
+
+        let amm_config = AmmConfig {
+            bump: 1,
+            index: 2,
+            owner: Pubkey::new_unique(),
+            protocol_fee_rate: 3,
+            trade_fee_rate: 4,
+            tick_spacing: 5,
+            fund_fee_rate: 6,
+            min_range_ticks: 7,
+            fund_owner: Pubkey::new_unique(),
+            max_range_ticks: 8,
+            status: 9,
+            default_prealloc_ticks: 10,
+            max_swap_iterations: 11,
+            padding: [12, 13],
+        };
+        let mut data = Vec::new();
+        amm_config.try_serialize(&mut data).unwrap();
+        let layout = core_states::AmmConfig::try_from_account_data(&data).unwrap();
+        assert_eq!(data.len(), core_states::AmmConfig::LEN);
+        assert_eq!(layout.bump, amm_config.bump);
+        assert_eq!({ layout.index }, amm_config.index);
+        assert_eq!(layout.owner, amm_config.owner.to_bytes());
+        assert_eq!({ layout.protocol_fee_rate }, amm_config.protocol_fee_rate);
+        assert_eq!({ layout.trade_fee_rate }, amm_config.trade_fee_rate);
+        assert_eq!({ layout.tick_spacing }, amm_config.tick_spacing);
+        assert_eq!({ layout.fund_fee_rate }, amm_config.fund_fee_rate);
+        assert_eq!({ layout.min_range_ticks }, amm_config.min_range_ticks);
+        assert_eq!(layout.fund_owner, amm_config.fund_owner.to_bytes());
+        assert_eq!({ layout.max_range_ticks }, amm_config.max_range_ticks);
+        assert_eq!(layout.status, amm_config.status);
+        assert_eq!(
+            layout.default_prealloc_ticks,
+            amm_config.default_prealloc_ticks
+        );
+        assert_eq!(
+            { layout.max_swap_iterations },
+            amm_config.max_swap_iterations
+        );
+        assert_eq!({ layout.padding }, amm_config.padding);
+        assert_eq!(core_states::MAX_SWAP_ITERATIONS, MAX_SWAP_ITERATIONS);
+    }
+
+    #[test]
+    fn get_max_swap_iterations_test() {
+        let mut amm_config = AmmConfig::default();
+        assert_eq!(
+            amm_config.get_max_swap_iterations(None).unwrap(),
+            MAX_SWAP_ITERATIONS
+        );
+        amm_config.max_swap_iterations = 100;
+        assert_eq!(amm_config.get_max_swap_iterations(None).unwrap(), 100);
+        assert_eq!(amm_config.get_max_swap_iterations(Some(200)).unwrap(), 200);
+        assert_eq!(
+            amm_config.get_max_swap_iterations(Some(0)).unwrap(),
+            MAX_SWAP_ITERATIONS
+        );
+        assert!(amm_config
+            .get_max_swap_iterations(Some(MAX_SWAP_ITERATIONS + 1))
+            .is_err());
+    }
+
+    #[test]
+    fn check_trade_fee_rate_update_test() {
+        // a sub basis point tier created before the rule
+        let amm_config = AmmConfig {
+            trade_fee_rate: 250,
+            ..Default::default()
+        };
+        assert!(AmmConfig::check_trade_fee_rate(250).is_err());
+        assert!(amm_config.check_trade_fee_rate_update(250).is_ok());
+        assert!(amm_config.check_trade_fee_rate_update(2500).is_ok());
+        assert!(amm_config.check_trade_fee_rate_update(2550).is_err());
+        assert!(amm_config
+            .check_trade_fee_rate_update(FEE_RATE_DENOMINATOR_VALUE)
+            .is_err());
+    }
+
+    #[test]
+    fn get_status_by_bit_test() {
+        let mut amm_config = AmmConfig::default();
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::Swap));
+        amm_config.status = 1 << (PoolStatusBitIndex::Swap as u8);
+        assert!(!amm_config.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+    }
+
+    #[test]
+    fn set_status_by_bit_test() {
+        let mut amm_config = AmmConfig {
+            status: 1 << (PoolStatusBitIndex::DecreaseLiquidity as u8),
+            ..Default::default()
+        };
+        amm_config.set_status_by_bit(PoolStatusBitIndex::Swap, false);
+        assert!(!amm_config.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(!amm_config.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+        amm_config.set_status_by_bit(PoolStatusBitIndex::Swap, true);
+        assert!(amm_config.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(!amm_config.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+    }
 }
@@ -0,0 +1,128 @@
+use crate::cranks::{CrankKind, CRANK_KIND_NUM};
+use crate::error::ErrorCode;
+use crate::util::get_rent;
+use anchor_lang::prelude::*;
+
+pub const CRANK_BOUNTY_SEED: &str = "crank_bounty";
+
+/// Lamports prepaid by the pool creator to tip the keepers running the permissionless cranks of the pool.
+/// A keeper is paid `bounty_lamports` at most once per `min_interval` seconds for each kind of crank.
+#[account]
+#[derive(Default, Debug)]
+pub struct CrankBounty {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool whose cranks are tipped
+    pub pool_id: Pubkey,
+    /// The lamports paid to the keeper of a crank
+    pub bounty_lamports: u64,
+    /// The min seconds between two paid runs of the same kind of crank
+    pub min_interval: u64,
+    /// The block timestamp of the last paid run of each kind of crank, indexed by `CrankKind`
+    pub last_paid_at: [u64; CRANK_KIND_NUM],
+    /// Total lamports deposited into the bounty
+    pub total_deposited: u64,
+    /// Total lamports paid to the keepers
+    pub total_paid: u64,
+    pub padding: [u64; 8],
+}
+
+impl CrankBounty {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 + 8 * CRANK_KIND_NUM + 8 + 8 + 64;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+    }
+
+    pub fn key(pool_id: Pubkey, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[CRANK_BOUNTY_SEED.as_bytes(), pool_id.as_ref()],
+            program_id,
+        )
+        .0
+    }
+
+    /// The bounty due to the keeper of a `kind` crank at `timestamp`, zero until the min interval elapsed
+    pub fn due_bounty(&self, kind: CrankKind, timestamp: u64) -> u64 {
+        let last_paid_at = self.last_paid_at[kind as usize];
+        if last_paid_at != 0 && timestamp < last_paid_at.saturating_add(self.min_interval) {
+            return 0;
+        }
+        self.bounty_lamports
+    }
+
+    /// Pay the bounty due for a `kind` crank from the bounty account to the keeper,
+    /// the bounty account itself always stays rent exempt. Returns the lamports paid.
+    pub fn pay<'info>(
+        bounty_info: &AccountInfo<'info>,
+        keeper: &AccountInfo<'info>,
+        pool_id: Pubkey,
+        kind: CrankKind,
+        timestamp: u64,
+    ) -> Result<u64> {
+        require_keys_eq!(
+            *bounty_info.owner,
+            crate::id(),
+            ErrorCode::IllegalAccountOwner
+        );
+        require!(bounty_info.is_writable, ErrorCode::InvalidAccount);
+
+        let mut bounty = CrankBounty::try_deserialize(&mut &bounty_info.data.borrow()[..])?;
+        require_keys_eq!(bounty.pool_id, pool_id, ErrorCode::InvalidAccount);
+
+        let rent_exempt_lamports = get_rent()?.minimum_balance(bounty_info.data_len());
+        let available = bounty_info.lamports().saturating_sub(rent_exempt_lamports);
+        let paid = bounty.due_bounty(kind, timestamp).min(available);
+        if paid == 0 {
+            return Ok(0);
+        }
+
+        **bounty_info.try_borrow_mut_lamports()? -= paid;
+        **keeper.try_borrow_mut_lamports()? += paid;
+
+        bounty.last_paid_at[kind as usize] = timestamp;
+        bounty.total_paid = bounty.total_paid.checked_add(paid).unwrap();
+        bounty.try_serialize(&mut &mut bounty_info.data.borrow_mut()[..])?;
+        Ok(paid)
+    }
+}
+
+/// Emitted when the crank bounty of a pool is funded or its parameters are changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FundCrankBountyEvent {
+    pub pool_state: Pubkey,
+    /// The account that funded the bounty
+    pub funder: Pubkey,
+    /// The lamports deposited in this instruction
+    pub amount: u64,
+    pub bounty_lamports: u64,
+    pub min_interval: u64,
+    /// Total lamports deposited into the bounty so far
+    pub total_deposited: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod crank_bounty_test {
+    use super::*;
+
+    #[test]
+    fn due_bounty_test() {
+        let bounty = &mut CrankBounty {
+            bounty_lamports: 5000,
+            min_interval: 600,
+            ..Default::default()
+        };
+        // never paid
+        assert_eq!(bounty.due_bounty(CrankKind::UpdatePoolApr, 100), 5000);
+
+        bounty.last_paid_at[CrankKind::UpdatePoolApr as usize] = 1000;
+        assert_eq!(bounty.due_bounty(CrankKind::UpdatePoolApr, 1599), 0);
+        assert_eq!(bounty.due_bounty(CrankKind::UpdatePoolApr, 1600), 5000);
+        // the other kinds of cranks are paid independently
+        assert_eq!(bounty.due_bounty(CrankKind::UpdateRewardInfos, 1000), 5000);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::error::ErrorCode;
+use crate::libraries::tick_math;
+use anchor_lang::prelude::*;
+
+pub const DEPLOYMENT_SEED: &str = "deployment";
+/// The max number of positions in one deployment
+pub const MAX_DEPLOYMENT_POSITIONS: u16 = 64;
+
+/// A ladder of adjacent positions of the same width and liquidity,
+/// the i-th position is [tick_lower_index + i * tick_width, tick_lower_index + (i + 1) * tick_width]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct DeploymentPlan {
+    /// The lower tick of the first position
+    pub tick_lower_index: i32,
+    /// The width in ticks of each position, a multiple of the pool tick spacing
+    pub tick_width: i32,
+    /// The number of positions
+    pub position_count: u16,
+    /// The min liquidity of each position
+    pub liquidity_per_position: u128,
+}
+
+/// Tracks the progress of deploying a wide position ladder across multiple transactions.
+/// Each position is opened by the usual open position instructions, then recorded with `continue_deploy`,
+/// recording the same position again is a no-op so that a failed transaction can simply be retried.
+#[account]
+#[derive(Default, Debug)]
+pub struct DeploymentState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The owner of the positions
+    pub owner: Pubkey,
+    /// The pool the positions are opened in
+    pub pool_id: Pubkey,
+    pub plan: DeploymentPlan,
+    /// The personal positions deployed so far, in the ladder order
+    pub positions: Vec<Pubkey>,
+}
+
+impl DeploymentState {
+    const BASE_LENGTH: usize = 8 + 1 + 32 + 32 + (4 + 4 + 2 + 16) + 4;
+
+    /// The length of the account to record `position_count` positions
+    pub fn need_len(position_count: u16) -> usize {
+        Self::BASE_LENGTH + position_count as usize * 32
+    }
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        owner: Pubkey,
+        pool_id: Pubkey,
+        plan: DeploymentPlan,
+        tick_spacing: u16,
+    ) -> Result<()> {
+        let tick_spacing = i32::from(tick_spacing);
+        require!(
+            plan.position_count > 0
+                && plan.position_count <= MAX_DEPLOYMENT_POSITIONS
+                && plan.tick_width > 0
+                && plan.tick_width % tick_spacing == 0
+                && plan.tick_lower_index % tick_spacing == 0
+                && plan.liquidity_per_position > 0,
+            ErrorCode::InvalidDeploymentPlan
+        );
+        let tick_upper_index = i64::from(plan.tick_lower_index)
+            + i64::from(plan.tick_width) * i64::from(plan.position_count);
+        require!(
+            plan.tick_lower_index >= tick_math::MIN_TICK
+                && tick_upper_index <= i64::from(tick_math::MAX_TICK),
+            ErrorCode::InvalidDeploymentPlan
+        );
+
+        self.bump = bump;
+        self.owner = owner;
+        self.pool_id = pool_id;
+        self.plan = plan;
+        self.positions = Vec::with_capacity(plan.position_count as usize);
+        Ok(())
+    }
+
+    /// The tick range of the `index`-th position of the ladder
+    pub fn position_range(&self, index: u16) -> (i32, i32) {
+        let tick_lower_index = self.plan.tick_lower_index + self.plan.tick_width * i32::from(index);
+        (tick_lower_index, tick_lower_index + self.plan.tick_width)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.positions.len() == self.plan.position_count as usize
+    }
+
+    /// Record the next position of the ladder, returns false if the position is already recorded
+    pub fn record_position(
+        &mut self,
+        position: Pubkey,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        liquidity: u128,
+    ) -> Result<bool> {
+        if self.positions.contains(&position) {
+            return Ok(false);
+        }
+        require!(!self.is_complete(), ErrorCode::DeploymentPositionMismatch);
+        let (expected_lower, expected_upper) = self.position_range(self.positions.len() as u16);
+        require!(
+            tick_lower_index == expected_lower
+                && tick_upper_index == expected_upper
+                && liquidity >= self.plan.liquidity_per_position,
+            ErrorCode::DeploymentPositionMismatch
+        );
+        self.positions.push(position);
+        Ok(true)
+    }
+}
+
+/// Emitted when a deployment is finished or aborted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DeploymentFinishedEvent {
+    pub deployment_state: Pubkey,
+    pub pool_state: Pubkey,
+    pub owner: Pubkey,
+    /// The number of positions deployed
+    pub position_count: u16,
+    /// True if the deployment is aborted, after the owner closed or emptied the deployed positions
+    pub aborted: bool,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod deployment_test {
+    use super::*;
+
+    fn build_plan() -> DeploymentPlan {
+        DeploymentPlan {
+            tick_lower_index: -600,
+            tick_width: 120,
+            position_count: 3,
+            liquidity_per_position: 1000,
+        }
+    }
+
+    #[test]
+    fn initialize_test() {
+        let mut deployment = DeploymentState::default();
+        let owner = Pubkey::new_unique();
+        let pool_id = Pubkey::new_unique();
+        // width is not a multiple of tick spacing
+        assert!(deployment
+            .initialize(255, owner, pool_id, build_plan(), 50)
+            .is_err());
+        // too many positions
+        assert!(deployment
+            .initialize(
+                255,
+                owner,
+                pool_id,
+                DeploymentPlan {
+                    position_count: MAX_DEPLOYMENT_POSITIONS + 1,
+                    ..build_plan()
+                },
+                60
+            )
+            .is_err());
+        // out of the tick range
+        assert!(deployment
+            .initialize(
+                255,
+                owner,
+                pool_id,
+                DeploymentPlan {
+                    tick_lower_index: 443580,
+                    ..build_plan()
+                },
+                60
+            )
+            .is_err());
+        deployment
+            .initialize(255, owner, pool_id, build_plan(), 60)
+            .unwrap();
+        assert_eq!(deployment.position_range(0), (-600, -480));
+        assert_eq!(deployment.position_range(2), (-360, -240));
+        let mut data = Vec::new();
+        deployment.try_serialize(&mut data).unwrap();
+        assert_eq!(DeploymentState::need_len(3), data.len() + 3 * 32);
+    }
+
+    #[test]
+    fn record_position_test() {
+        let mut deployment = DeploymentState::default();
+        deployment
+            .initialize(
+                255,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                build_plan(),
+                60,
+            )
+            .unwrap();
+        let positions = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+
+        // out of order
+        assert!(deployment
+            .record_position(positions[1], -480, -360, 1000)
+            .is_err());
+        // not enough liquidity
+        assert!(deployment
+            .record_position(positions[0], -600, -480, 999)
+            .is_err());
+
+        assert!(deployment
+            .record_position(positions[0], -600, -480, 1000)
+            .unwrap());
+        // recording again is a no-op
+        assert!(!deployment
+            .record_position(positions[0], -600, -480, 1000)
+            .unwrap());
+        assert!(deployment
+            .record_position(positions[1], -480, -360, 2000)
+            .unwrap());
+        assert!(!deployment.is_complete());
+        assert!(deployment
+            .record_position(positions[2], -360, -240, 1000)
+            .unwrap());
+        assert!(deployment.is_complete());
+        assert!(deployment
+            .record_position(Pubkey::new_unique(), -240, -120, 1000)
+            .is_err());
+        assert_eq!(deployment.positions, positions.to_vec());
+    }
+}
@@ -1,7 +1,9 @@
 use std::cell::{Ref, RefMut};
 
 use crate::error::ErrorCode as ClmmErrorCode;
-use crate::states::{TickState, TickUtils, TICK_ARRAY_SIZE, TICK_ARRAY_SIZE_USIZE};
+use crate::states::{
+    TickArrayState, TickState, TickUtils, REWARD_NUM, TICK_ARRAY_SIZE, TICK_ARRAY_SIZE_USIZE,
+};
 use crate::util::*;
 use anchor_lang::error::{Error, ErrorCode};
 use anchor_lang::prelude::*;
@@ -26,11 +28,16 @@ pub struct DynTickArrayState {
     pub alloc_tick_count: u8,
     /// how many ticks are initialized in this tick array
     pub initialized_tick_count: u8,
-    pub padding_1: [u8; 2],
+    /// how many TickState slots are pre-allocated after the allocated ones, but not bound to a tick yet
+    pub reserved_tick_count: u8,
+    pub padding_1: [u8; 1],
     // account update recent epoch
     pub recent_epoch: u64,
+    /// lamports of the rent of this account drawn from the tick rent treasury,
+    /// returned to the treasury first when the account shrinks
+    pub treasury_rent: u64,
     // Unused bytes for future upgrades.
-    pub padding_2: [u8; 96],
+    pub padding_2: [u8; 88],
 }
 // TickState array, max size is TICK_ARRAY_SIZE_USIZE
 
@@ -43,9 +50,11 @@ impl Default for DynTickArrayState {
             tick_offset_index: [0; TICK_ARRAY_SIZE_USIZE],
             alloc_tick_count: 0,
             initialized_tick_count: 0,
-            padding_1: [0; 2],
+            reserved_tick_count: 0,
+            padding_1: [0; 1],
             recent_epoch: 0,
-            padding_2: [0; 96],
+            treasury_rent: 0,
+            padding_2: [0; 88],
         }
     }
 }
@@ -56,8 +65,70 @@ impl DynTickArrayState {
     // when first create, we only allocate space for header + one TickState
     pub const FIRST_CREATE_LEN: usize = Self::HEADER_LEN + TickState::LEN;
 
+    // the size of a dynamic tick array with all the ticks allocated, it never grows beyond it
+    pub const MAX_LEN: usize = Self::HEADER_LEN + TICK_ARRAY_SIZE_USIZE * TickState::LEN;
+
+    /// Check the account space of a reallocation doesn't exceed `MAX_LEN`
+    pub fn check_realloc_len(new_account_space: usize) -> Result<()> {
+        require_gte!(
+            Self::MAX_LEN,
+            new_account_space,
+            ClmmErrorCode::DynTickArrayExceedsMaxLen
+        );
+        Ok(())
+    }
+
     pub fn all_data_len(&self) -> usize {
-        Self::HEADER_LEN + self.alloc_tick_count as usize * TickState::LEN
+        Self::HEADER_LEN
+            + (self.alloc_tick_count as usize + self.reserved_tick_count as usize) * TickState::LEN
+    }
+
+    /// Get the allocated, reserved, initialized TickStates and the remaining headroom of this tick array
+    pub fn capacity(&self) -> TickArrayCapacity {
+        let alloc_tick_count = self.alloc_tick_count;
+        let reserved_tick_count = self.reserved_tick_count;
+        TickArrayCapacity {
+            alloc_tick_count,
+            reserved_tick_count,
+            initialized_tick_count: self.initialized_tick_count,
+            headroom_tick_count: TICK_ARRAY_SIZE_USIZE as u8
+                - alloc_tick_count
+                - reserved_tick_count,
+            data_len: self.all_data_len() as u32,
+        }
+    }
+
+    /// Check no TickState of this tick array is initialized or referenced by a position,
+    /// `ticks` are the allocated and reserved TickStates following the header
+    pub fn check_empty(&self, ticks: &[TickState]) -> Result<()> {
+        let is_empty = ticks
+            .iter()
+            .all(|tick_state| !tick_state.is_initialized() && tick_state.liquidity_net == 0);
+        require!(
+            self.initialized_tick_count == 0 && is_empty,
+            ClmmErrorCode::TickArrayNotEmpty
+        );
+        Ok(())
+    }
+
+    /// Record `lamports` of rent drawn from the tick rent treasury to grow this account
+    pub fn add_treasury_rent(&mut self, lamports: u64) {
+        self.treasury_rent = self.treasury_rent.checked_add(lamports).unwrap();
+    }
+
+    /// Pre-allocate `count` TickState slots, the account must be resized to `all_data_len()` after this.
+    /// The reserved slots are consumed by `use_one_tick` without growing the account again.
+    pub fn reserve_ticks(&mut self, count: u8) -> Result<()> {
+        require_gt!(count, 0);
+        let total_tick_count = usize::from(self.alloc_tick_count)
+            + usize::from(self.reserved_tick_count)
+            + usize::from(count);
+        require!(
+            total_tick_count <= TICK_ARRAY_SIZE_USIZE,
+            ClmmErrorCode::InvalidTickArray
+        );
+        self.reserved_tick_count += count;
+        Ok(())
     }
 
     pub fn initialize(
@@ -74,7 +145,27 @@ impl DynTickArrayState {
         Ok(())
     }
 
-    /// Mark a TickState as used in this tick array.
+    /// Build the dynamic layout of a fixed tick array, only its initialized TickStates are kept, in offset order
+    pub fn from_fixed(tick_array: &TickArrayState) -> (Self, Vec<TickState>) {
+        let mut header = Self {
+            pool_id: tick_array.pool_id,
+            start_tick_index: tick_array.start_tick_index,
+            recent_epoch: tick_array.recent_epoch,
+            ..Default::default()
+        };
+        let mut ticks = Vec::new();
+        for (offset, tick_state) in tick_array.ticks.iter().enumerate() {
+            if tick_state.is_initialized() {
+                ticks.push(*tick_state);
+                header.tick_offset_index[offset] = ticks.len() as u8;
+            }
+        }
+        header.alloc_tick_count = ticks.len() as u8;
+        header.initialized_tick_count = header.alloc_tick_count;
+        (header, ticks)
+    }
+
+    /// Mark a TickState as used in this tick array, a reserved slot is used first if there is any.
     /// return the index of this tick in the DynTickStateArray
     pub fn use_one_tick(&mut self, tick_index: i32, tick_spacing: u16) -> Result<u8> {
         require_eq!(
@@ -94,6 +185,10 @@ impl DynTickArrayState {
             ClmmErrorCode::InvalidTickIndex
         );
 
+        // the reserved slots follow the allocated ones, so the next slot is always at alloc_tick_count
+        if self.reserved_tick_count > 0 {
+            self.reserved_tick_count -= 1;
+        }
         self.alloc_tick_count += 1;
         self.tick_offset_index[offset] = self.alloc_tick_count;
 
@@ -102,6 +197,44 @@ impl DynTickArrayState {
         Ok(tick_state_index)
     }
 
+    /// Release the TickState of an uninitialized tick, the reverse of `use_one_tick`.
+    /// The last allocated TickState is moved into the released slot to keep the allocated ones packed,
+    /// the account must be resized to `all_data_len()` after this to drop the trailing slot.
+    /// `ticks` are the allocated and reserved TickStates following the header, return the released TickState
+    pub fn free_one_tick(
+        &mut self,
+        ticks: &mut [TickState],
+        tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<TickState> {
+        let tick_state_index = usize::from(self.get_tick_index_in_array(tick_index, tick_spacing)?);
+        let released = ticks[tick_state_index];
+        require!(!released.is_initialized(), ClmmErrorCode::TickInitialized);
+
+        let last_index = usize::from(self.alloc_tick_count) - 1;
+        if tick_state_index != last_index {
+            ticks[tick_state_index] = ticks[last_index];
+            let moved_offset = self
+                .tick_offset_index
+                .iter()
+                .position(|slot| usize::from(*slot) == last_index + 1)
+                .unwrap();
+            self.tick_offset_index[moved_offset] = tick_state_index as u8 + 1;
+        }
+        // the vacated slot is now the first reserved one, the trailing slot dropped by the resize is empty
+        ticks[last_index] = TickState::default();
+
+        let offset = TickUtils::get_tick_offset_in_tick_array(
+            self.start_tick_index,
+            tick_index,
+            tick_spacing,
+        )?;
+        self.tick_offset_index[offset] = 0;
+        self.alloc_tick_count -= 1;
+
+        Ok(released)
+    }
+
     /// Get the index of a tick in the TickState array.
     /// The TickState array is placed after the header in the account data.
     /// function like tick_array.get_tick_offset_in_array(tick_index, tick_spacing)
@@ -148,10 +281,10 @@ impl DynTickArrayState {
                 {
                     return Ok(Some(self.tick_offset_index[offset_in_array as usize] - 1));
                 }
-                offset_in_array = offset_in_array - 1;
+                offset_in_array -= 1;
             }
         } else {
-            offset_in_array = offset_in_array + 1;
+            offset_in_array += 1;
             while offset_in_array < TICK_ARRAY_SIZE {
                 if self.tick_offset_index[offset_in_array as usize] > 0
                     && tick_state_slice
@@ -160,7 +293,7 @@ impl DynTickArrayState {
                 {
                     return Ok(Some(self.tick_offset_index[offset_in_array as usize] - 1));
                 }
-                offset_in_array = offset_in_array + 1;
+                offset_in_array += 1;
             }
         }
         Ok(None)
@@ -181,7 +314,7 @@ impl DynTickArrayState {
                 {
                     return Ok(self.tick_offset_index[i as usize] - 1);
                 }
-                i = i - 1;
+                i -= 1;
             }
         } else {
             let mut i = 0;
@@ -191,7 +324,7 @@ impl DynTickArrayState {
                 {
                     return Ok(self.tick_offset_index[i] - 1);
                 }
-                i = i + 1;
+                i += 1;
             }
         }
         err!(ClmmErrorCode::InvalidTickArray)
@@ -208,6 +341,154 @@ impl DynTickArrayState {
     }
 }
 
+/// Space usage of a tick array account
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct TickArrayCapacity {
+    /// TickStates bound to a tick
+    pub alloc_tick_count: u8,
+    /// TickStates pre-allocated but not bound to a tick yet
+    pub reserved_tick_count: u8,
+    /// TickStates with liquidity_gross > 0
+    pub initialized_tick_count: u8,
+    /// TickStates that can still be added by reallocation
+    pub headroom_tick_count: u8,
+    /// The account data length
+    pub data_len: u32,
+}
+
+impl TickArrayCapacity {
+    /// A fixed tick array always has all TickStates allocated
+    pub fn fixed(initialized_tick_count: u8) -> Self {
+        Self {
+            alloc_tick_count: TICK_ARRAY_SIZE_USIZE as u8,
+            reserved_tick_count: 0,
+            initialized_tick_count,
+            headroom_tick_count: 0,
+            data_len: TickArrayState::LEN as u32,
+        }
+    }
+}
+
+/// Emitted when a dynamic tick array account is created or grown
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickArrayResizedEvent {
+    /// The pool of the tick array
+    pub pool_state: Pubkey,
+    /// The start tick index of the tick array
+    pub start_tick_index: i32,
+    /// TickStates bound to a tick
+    pub alloc_tick_count: u8,
+    /// TickStates pre-allocated but not bound to a tick yet
+    pub reserved_tick_count: u8,
+    /// TickStates with liquidity_gross > 0
+    pub initialized_tick_count: u8,
+    /// TickStates that can still be added by reallocation
+    pub headroom_tick_count: u8,
+    /// The account data length after resizing
+    pub data_len: u32,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+impl TickArrayResizedEvent {
+    pub fn emit_from(header: &DynTickArrayState, event_seq: u64) {
+        let capacity = header.capacity();
+        emit!(TickArrayResizedEvent {
+            pool_state: header.pool_id,
+            start_tick_index: header.start_tick_index,
+            alloc_tick_count: capacity.alloc_tick_count,
+            reserved_tick_count: capacity.reserved_tick_count,
+            initialized_tick_count: capacity.initialized_tick_count,
+            headroom_tick_count: capacity.headroom_tick_count,
+            data_len: capacity.data_len,
+            event_seq,
+        });
+    }
+}
+
+/// Emitted when a fixed tick array account is migrated in place to a dynamic tick array
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickArrayMigratedEvent {
+    /// The pool of the tick array
+    pub pool_state: Pubkey,
+    /// The start tick index of the tick array
+    pub start_tick_index: i32,
+    /// The initialized TickStates kept by the migration
+    pub alloc_tick_count: u8,
+    /// The account data length after the migration
+    pub data_len: u32,
+    /// The rent refunded by the account shrink
+    pub refunded_lamports: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// The final growths outside of a tick removed from a tick array
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct TickArchive {
+    pub tick: i32,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+    pub reward_growths_outside_x64: [u128; REWARD_NUM],
+}
+
+impl From<&TickState> for TickArchive {
+    fn from(tick_state: &TickState) -> Self {
+        Self {
+            tick: tick_state.tick,
+            fee_growth_outside_0_x64: tick_state.fee_growth_outside_0_x64,
+            fee_growth_outside_1_x64: tick_state.fee_growth_outside_1_x64,
+            reward_growths_outside_x64: tick_state.reward_growths_outside_x64,
+        }
+    }
+}
+
+/// Emitted when a dynamic tick array account is shrunk or closed, with the snapshots of the ticks
+/// removed from the account, so that indexers keep the history of the ticks after their data is deleted
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickArrayArchivedEvent {
+    /// The pool of the tick array
+    pub pool_state: Pubkey,
+    /// The start tick index of the tick array
+    pub start_tick_index: i32,
+    /// Whether the account is closed, otherwise it is shrunk
+    pub closed: bool,
+    /// The allocated ticks removed from the account
+    pub ticks: Vec<TickArchive>,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+impl TickArrayArchivedEvent {
+    /// `removed_ticks` are the TickStates dropped by the shrink, or all of them if the account is closed
+    pub fn emit_from(
+        header: &DynTickArrayState,
+        removed_ticks: &[TickState],
+        closed: bool,
+        event_seq: u64,
+    ) {
+        emit!(Self::new(header, removed_ticks, closed, event_seq));
+    }
+
+    fn new(
+        header: &DynTickArrayState,
+        removed_ticks: &[TickState],
+        closed: bool,
+        event_seq: u64,
+    ) -> Self {
+        TickArrayArchivedEvent {
+            pool_state: header.pool_id,
+            start_tick_index: header.start_tick_index,
+            closed,
+            ticks: removed_ticks.iter().map(TickArchive::from).collect(),
+            event_seq,
+        }
+    }
+}
+
 /// Loader for dynamic TickArray accounts
 #[derive(Clone)]
 pub struct DynTickArrayLoader<'info> {
@@ -233,7 +514,7 @@ impl<'info> DynTickArrayLoader<'info> {
         }
         // Discriminator must match.
         let disc_bytes = array_ref![data, 0, 8];
-        if disc_bytes != &DynTickArrayState::DISCRIMINATOR {
+        if disc_bytes != DynTickArrayState::DISCRIMINATOR {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
         }
 
@@ -275,7 +556,7 @@ impl<'info> DynTickArrayLoader<'info> {
         }
 
         // write discriminator
-        data[..8].copy_from_slice(&DynTickArrayState::DISCRIMINATOR);
+        data[..8].copy_from_slice(DynTickArrayState::DISCRIMINATOR);
 
         // split the data into header and ticks part
         if data.len() < DynTickArrayState::HEADER_LEN {
@@ -321,7 +602,7 @@ impl<'info> DynTickArrayLoader<'info> {
                 return Err(ErrorCode::AccountDiscriminatorNotFound.into());
             }
             let disc_bytes = array_ref![data, 0, 8];
-            if disc_bytes != &DynTickArrayState::DISCRIMINATOR {
+            if disc_bytes != DynTickArrayState::DISCRIMINATOR {
                 return Err(ErrorCode::AccountDiscriminatorMismatch.into());
             }
         }
@@ -341,15 +622,23 @@ impl<'info> DynTickArrayLoader<'info> {
         });
 
         // ! 对账户进行 resize 后, 再次 deserialize 时, 数据长度和 header 中记录的长度会不一致
-        if !is_after_resize {
-            if data_len != header.all_data_len() {
-                return Err(ErrorCode::AccountDidNotDeserialize.into());
-            }
+        if !is_after_resize && data_len != header.all_data_len() {
+            return Err(ErrorCode::AccountDidNotDeserialize.into());
         }
 
         Ok((header, ticks))
     }
 
+    pub fn to_account_info(&self) -> AccountInfo<'info> {
+        self.acc_info.clone()
+    }
+
+    /// Resize the account data to `new_len`, the header must already describe the new length
+    pub fn resize(&self, new_len: usize) -> Result<()> {
+        self.acc_info.resize(new_len)?;
+        Ok(())
+    }
+
     /// Returns a Ref to the account data structure for reading.
     pub fn load<'a>(&'a self) -> Result<(Ref<'a, DynTickArrayState>, Ref<'a, [TickState]>)> {
         let data = self.acc_info.try_borrow_data()?;
@@ -361,7 +650,7 @@ impl<'info> DynTickArrayLoader<'info> {
             }
 
             let disc_bytes = array_ref![data, 0, 8];
-            if disc_bytes != &DynTickArrayState::DISCRIMINATOR {
+            if disc_bytes != DynTickArrayState::DISCRIMINATOR {
                 return Err(ErrorCode::AccountDiscriminatorMismatch.into());
             }
         }
@@ -445,10 +734,12 @@ pub mod dyn_tick_array_test {
         };
 
         for offset in tick_offsets {
-            let mut new_tick = TickState::default();
-            // Indicates tick is initialized
-            new_tick.liquidity_gross = 1;
-            new_tick.tick = start_index + (offset * tick_spacing as usize) as i32;
+            let new_tick = TickState {
+                // Indicates tick is initialized
+                liquidity_gross: 1,
+                tick: start_index + (offset * tick_spacing as usize) as i32,
+                ..Default::default()
+            };
 
             // 使用了 1 个 tick
             dyn_tick_header
@@ -519,10 +810,12 @@ pub mod dyn_tick_array_test {
     }
 
     pub fn build_tick(tick: i32, liquidity_gross: u128, liquidity_net: i128) -> RefCell<TickState> {
-        let mut new_tick = TickState::default();
-        new_tick.tick = tick;
-        new_tick.liquidity_gross = liquidity_gross;
-        new_tick.liquidity_net = liquidity_net;
+        let new_tick = TickState {
+            tick,
+            liquidity_gross,
+            liquidity_net,
+            ..Default::default()
+        };
         RefCell::new(new_tick)
     }
 
@@ -532,17 +825,21 @@ pub mod dyn_tick_array_test {
         fee_growth_outside_1_x64: u128,
         reward_growths_outside_x64: u128,
     ) -> RefCell<TickState> {
-        let mut new_tick = TickState::default();
-        new_tick.tick = tick;
-        new_tick.fee_growth_outside_0_x64 = fee_growth_outside_0_x64;
-        new_tick.fee_growth_outside_1_x64 = fee_growth_outside_1_x64;
-        new_tick.reward_growths_outside_x64 = [reward_growths_outside_x64, 0, 0];
+        let new_tick = TickState {
+            tick,
+            fee_growth_outside_0_x64,
+            fee_growth_outside_1_x64,
+            reward_growths_outside_x64: [reward_growths_outside_x64, 0, 0],
+            ..Default::default()
+        };
         RefCell::new(new_tick)
     }
 
+    #[allow(clippy::module_inception)]
     mod dyn_tick_array_test {
         use super::*;
         use crate::libraries::tick_math;
+        use crate::states::tick_array_test::build_fix_tick_array_with_tick_states;
         use std::convert::identity;
 
         #[test]
@@ -575,6 +872,256 @@ pub mod dyn_tick_array_test {
             );
         }
 
+        #[test]
+        fn tick_array_archived_event_test() {
+            let mut tick_state = build_tick(-120, 10, 10).take();
+            tick_state.fee_growth_outside_0_x64 = 1;
+            tick_state.fee_growth_outside_1_x64 = 2;
+            tick_state.reward_growths_outside_x64 = [3, 4, 5];
+            let (header, _) = build_dyn_tick_array_with_tick_states(
+                Pubkey::new_unique(),
+                -600,
+                10,
+                DynamicTickArrayBuildType::FromStartIndex,
+                vec![tick_state],
+            );
+            let header = header.take();
+
+            let event = TickArrayArchivedEvent::new(&header, &[tick_state], true, 7);
+            assert_eq!(event.pool_state, identity(header.pool_id));
+            assert_eq!(event.start_tick_index, -600);
+            assert!(event.closed);
+            assert_eq!(event.event_seq, 7);
+            assert_eq!(
+                event.ticks,
+                vec![TickArchive {
+                    tick: -120,
+                    fee_growth_outside_0_x64: 1,
+                    fee_growth_outside_1_x64: 2,
+                    reward_growths_outside_x64: [3, 4, 5],
+                }]
+            );
+        }
+
+        #[test]
+        fn check_empty_test() {
+            let (header, ticks) = build_dyn_tick_array_with_tick_states(
+                Pubkey::new_unique(),
+                -600,
+                10,
+                DynamicTickArrayBuildType::FromStartIndex,
+                vec![build_tick(-120, 10, 10).take(), build_tick(-60, 10, -10).take()],
+            );
+            let mut header = header.take();
+            let mut ticks = ticks.take();
+            assert_eq!(
+                header.check_empty(&ticks).err(),
+                Some(ClmmErrorCode::TickArrayNotEmpty.into())
+            );
+
+            // cleared ticks keep their index and are empty
+            for tick_state in ticks.iter_mut() {
+                tick_state.clear();
+            }
+            assert!(header.check_empty(&ticks).is_ok());
+
+            header.initialized_tick_count = 1;
+            assert_eq!(
+                header.check_empty(&ticks).err(),
+                Some(ClmmErrorCode::TickArrayNotEmpty.into())
+            );
+        }
+
+        #[test]
+        fn from_fixed_test() {
+            let tick_spacing = 10;
+            let pool_id = Pubkey::new_unique();
+            let tick_array = build_fix_tick_array_with_tick_states(
+                pool_id,
+                -600,
+                tick_spacing,
+                vec![
+                    build_tick(-10, 30, -30).take(),
+                    build_tick(-300, 0, 0).take(),
+                    build_tick(-590, 30, 30).take(),
+                ],
+            );
+            let (header, ticks) = DynTickArrayState::from_fixed(&tick_array.borrow());
+            assert_eq!(header.pool_id, pool_id);
+            assert_eq!(identity(header.start_tick_index), -600);
+            assert_eq!(header.alloc_tick_count, 2);
+            assert_eq!(header.initialized_tick_count, 2);
+            assert_eq!(header.reserved_tick_count, 0);
+            assert_eq!(
+                header.all_data_len(),
+                DynTickArrayState::HEADER_LEN + 2 * TickState::LEN
+            );
+
+            // the ticks are in offset order, the uninitialized one is dropped
+            assert_eq!(ticks.len(), 2);
+            assert_eq!(header.get_tick_index_in_array(-590, tick_spacing).unwrap(), 0);
+            assert_eq!(header.get_tick_index_in_array(-10, tick_spacing).unwrap(), 1);
+            assert_eq!(identity(ticks[1].tick), -10);
+            assert_eq!(
+                header.get_tick_index_in_array(-300, tick_spacing).err(),
+                Some(ClmmErrorCode::InvalidTickIndex.into())
+            );
+        }
+
+        #[test]
+        fn free_one_tick_test() {
+            let tick_spacing = 10;
+            let (header, ticks) = build_dyn_tick_array_with_tick_states(
+                Pubkey::new_unique(),
+                0,
+                tick_spacing,
+                DynamicTickArrayBuildType::FromStartIndex,
+                vec![
+                    build_tick(10, 10, 10).take(),
+                    build_tick(20, 0, 0).take(),
+                    build_tick(30, 10, -10).take(),
+                ],
+            );
+            let mut header = header.take();
+            let mut ticks = ticks.take();
+            header.reserve_ticks(1).unwrap();
+            ticks.push(TickState::default());
+            let data_len_before = header.all_data_len();
+
+            assert_eq!(
+                header.free_one_tick(&mut ticks, 10, tick_spacing).err(),
+                Some(ClmmErrorCode::TickInitialized.into())
+            );
+            assert_eq!(
+                header.free_one_tick(&mut ticks, 40, tick_spacing).err(),
+                Some(ClmmErrorCode::InvalidTickIndex.into())
+            );
+
+            // the last allocated tick takes the released slot
+            let released = header.free_one_tick(&mut ticks, 20, tick_spacing).unwrap();
+            assert_eq!(identity(released.tick), 20);
+            assert_eq!(header.alloc_tick_count, 2);
+            assert_eq!(header.reserved_tick_count, 1);
+            assert_eq!(header.all_data_len(), data_len_before - TickState::LEN);
+            assert_eq!(header.tick_offset_index[2], 0);
+            assert_eq!(header.get_tick_index_in_array(30, tick_spacing).unwrap(), 1);
+            assert_eq!(identity(ticks[1].tick), 30);
+            ticks.truncate(usize::from(header.alloc_tick_count + header.reserved_tick_count));
+            assert!(ticks[2..].iter().all(|tick_state| tick_state.tick == 0));
+
+            // the released tick can be used again, taking the reserved slot
+            assert_eq!(header.use_one_tick(20, tick_spacing).unwrap(), 2);
+            assert_eq!(header.reserved_tick_count, 0);
+
+            // releasing the last allocated tick doesn't move any other one
+            ticks[2].tick = 20;
+            header.free_one_tick(&mut ticks, 20, tick_spacing).unwrap();
+            assert_eq!(header.get_tick_index_in_array(10, tick_spacing).unwrap(), 0);
+            assert_eq!(header.get_tick_index_in_array(30, tick_spacing).unwrap(), 1);
+            assert_eq!(header.alloc_tick_count, 2);
+        }
+
+        #[test]
+        fn reserve_ticks_test() {
+            let tick_spacing = 10;
+            let (dyn_tick_header, _) = build_dyn_tick_array(
+                0,
+                tick_spacing,
+                DynamicTickArrayBuildType::FromStartIndex,
+                vec![0, 5],
+            );
+            let mut dyn_tick_header = dyn_tick_header.borrow_mut();
+            let data_len_before = dyn_tick_header.all_data_len();
+
+            dyn_tick_header.reserve_ticks(3).unwrap();
+            assert_eq!(dyn_tick_header.reserved_tick_count, 3);
+            assert_eq!(
+                dyn_tick_header.all_data_len(),
+                data_len_before + 3 * TickState::LEN
+            );
+
+            // reserved slots are used in order and don't change the data len
+            let index = dyn_tick_header.use_one_tick(100, tick_spacing).unwrap();
+            assert_eq!(index, 2);
+            let index = dyn_tick_header.use_one_tick(30, tick_spacing).unwrap();
+            assert_eq!(index, 3);
+            assert_eq!(dyn_tick_header.alloc_tick_count, 4);
+            assert_eq!(dyn_tick_header.reserved_tick_count, 1);
+            assert_eq!(
+                dyn_tick_header.all_data_len(),
+                data_len_before + 3 * TickState::LEN
+            );
+            assert_eq!(
+                dyn_tick_header
+                    .get_tick_index_in_array(30, tick_spacing)
+                    .unwrap(),
+                3
+            );
+
+            // can't reserve more than the ticks of a tick array
+            assert!(dyn_tick_header
+                .reserve_ticks(TICK_ARRAY_SIZE as u8 - 4)
+                .is_err());
+            dyn_tick_header
+                .reserve_ticks(TICK_ARRAY_SIZE as u8 - 5)
+                .unwrap();
+            assert_eq!(
+                dyn_tick_header.all_data_len(),
+                DynTickArrayState::HEADER_LEN + TICK_ARRAY_SIZE_USIZE * TickState::LEN
+            );
+            let capacity = dyn_tick_header.capacity();
+            assert_eq!(capacity.alloc_tick_count, 4);
+            assert_eq!(capacity.reserved_tick_count, TICK_ARRAY_SIZE as u8 - 4);
+            assert_eq!(capacity.initialized_tick_count, 0);
+            assert_eq!(capacity.headroom_tick_count, 0);
+            assert_eq!(
+                capacity.data_len as usize,
+                DynTickArrayState::HEADER_LEN + TICK_ARRAY_SIZE_USIZE * TickState::LEN
+            );
+
+            // a full tick array can't be reallocated to a larger size
+            assert_eq!(dyn_tick_header.all_data_len(), DynTickArrayState::MAX_LEN);
+            DynTickArrayState::check_realloc_len(dyn_tick_header.all_data_len()).unwrap();
+            assert_eq!(
+                DynTickArrayState::check_realloc_len(
+                    dyn_tick_header.all_data_len() + TickState::LEN
+                )
+                .err(),
+                Some(ClmmErrorCode::DynTickArrayExceedsMaxLen.into())
+            );
+        }
+
+        #[test]
+        fn prealloc_on_create_test() {
+            // as `create_dyn_tick_array_account` does with a pre-allocation count of 4
+            let tick_spacing = 10;
+            let mut dyn_tick_header = DynTickArrayState::default();
+            dyn_tick_header
+                .initialize(0, tick_spacing, Pubkey::new_unique())
+                .unwrap();
+            dyn_tick_header.reserve_ticks(4).unwrap();
+            let index = dyn_tick_header.use_one_tick(50, tick_spacing).unwrap();
+            assert_eq!(index, 0);
+            assert_eq!(dyn_tick_header.alloc_tick_count, 1);
+            assert_eq!(dyn_tick_header.reserved_tick_count, 3);
+            assert_eq!(
+                dyn_tick_header.all_data_len(),
+                DynTickArrayState::HEADER_LEN + 4 * TickState::LEN
+            );
+
+            // without pre-allocation the account holds just the accessed tick
+            let mut dyn_tick_header = DynTickArrayState::default();
+            dyn_tick_header
+                .initialize(0, tick_spacing, Pubkey::new_unique())
+                .unwrap();
+            dyn_tick_header.reserve_ticks(1).unwrap();
+            dyn_tick_header.use_one_tick(50, tick_spacing).unwrap();
+            assert_eq!(
+                dyn_tick_header.all_data_len(),
+                DynTickArrayState::FIRST_CREATE_LEN
+            );
+        }
+
         #[test]
         fn next_tick_arrary_start_index_test() {
             let tick_spacing = 15;
@@ -946,22 +1493,26 @@ pub mod dyn_tick_array_test {
                 );
 
             if fee_growth_global_0_x64 != 0 {
-                fee_growth_global_0_x64 = fee_growth_global_0_x64 + fee_growth_global_delta;
+                fee_growth_global_0_x64 += fee_growth_global_delta;
             }
             if fee_growth_global_1_x64 != 0 {
-                fee_growth_global_1_x64 = fee_growth_global_1_x64 + fee_growth_global_delta;
+                fee_growth_global_1_x64 += fee_growth_global_delta;
             }
             if cross_tick_lower {
                 tick_lower.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             } else {
                 tick_upper.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             }
 
@@ -1288,11 +1839,11 @@ pub mod dyn_tick_array_test {
                 &build_reward_infos(reward_growth_global_x64),
             )[0];
 
-            reward_growth_global_x64 = reward_growth_global_x64 + reward_growth_global_delta;
+            reward_growth_global_x64 += reward_growth_global_delta;
             if cross_tick_lower {
-                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             } else {
-                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             }
 
             tick_current = target_tick_current;
@@ -1448,6 +1999,38 @@ pub mod dyn_tick_array_test {
         use super::*;
         use anchor_lang::Discriminator;
 
+        #[test]
+        fn core_layout_test() {
+            use byreal_clmm_core::states::{self as core_states, AccountLayout};
+
+            let mut header = DynTickArrayState::default();
+            for (i, byte) in bytemuck::bytes_of_mut(&mut header).iter_mut().enumerate() {
+                *byte = (i % 251) as u8 + 1;
+            }
+            let mut data = DynTickArrayState::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(&header));
+            let layout = core_states::DynTickArrayState::try_from_account_data(&data).unwrap();
+            assert_core_layout!(
+                header,
+                layout,
+                pool_id,
+                start_tick_index,
+                padding_0,
+                tick_offset_index,
+                alloc_tick_count,
+                initialized_tick_count,
+                reserved_tick_count,
+                padding_1,
+                recent_epoch,
+                treasury_rent,
+                padding_2,
+            );
+            assert_eq!(
+                core_states::DynTickArrayState::LEN,
+                DynTickArrayState::HEADER_LEN
+            );
+        }
+
         #[test]
         fn dyn_test_tick_array_layout() {
             let pool_id = Pubkey::new_unique();
@@ -1469,24 +2052,24 @@ pub mod dyn_tick_array_test {
                 0x11223344556600778899aabbccddeeff,
                 0x11223344556677008899aabbccddeeff,
             ];
-            let mut tick_padding: [u32; 13] = [0u32; 13];
-            let mut tick_padding_data = [0u8; 4 * 13];
-            let mut offset = 0;
-            for i in 0..13 {
-                tick_padding[i] = u32::MAX - 3 * i as u32;
-                tick_padding_data[offset..offset + 4]
-                    .copy_from_slice(&tick_padding[i].to_le_bytes());
-                offset += 4;
-            }
+            let maker_rebate_growth_0_x64: u128 = 0x11223344556677880099aabbccddeeff;
+            let maker_rebate_growth_1_x64: u128 = 0x11223344556677889900aabbccddeeff;
+            let seconds_per_liquidity_outside_x64: u128 = 0x1122334455667788990aabbccddeeff0;
+            let seconds_outside: u32 = 0x12345670;
 
             let use_tick_index = start_tick_index + 2;
-            let mut tick_state_item = TickState::default();
-            tick_state_item.tick = use_tick_index;
-            tick_state_item.liquidity_net = liquidity_net;
-            tick_state_item.liquidity_gross = liquidity_gross;
-            tick_state_item.fee_growth_outside_0_x64 = fee_growth_outside_0_x64;
-            tick_state_item.fee_growth_outside_1_x64 = fee_growth_outside_1_x64;
-            tick_state_item.reward_growths_outside_x64 = reward_growths_outside_x64;
+            let tick_state_item = TickState {
+                tick: use_tick_index,
+                liquidity_net,
+                liquidity_gross,
+                fee_growth_outside_0_x64,
+                fee_growth_outside_1_x64,
+                reward_growths_outside_x64,
+                maker_rebate_growth_0_x64,
+                maker_rebate_growth_1_x64,
+                seconds_per_liquidity_outside_x64,
+                seconds_outside,
+            };
 
             // 可以存下已经全部60个 tick-state 的内存空间
             // build tick data byte array
@@ -1495,7 +2078,7 @@ pub mod dyn_tick_array_test {
 
             // write discriminator
             dyn_tick_array_full_account_data[..8]
-                .copy_from_slice(&DynTickArrayState::DISCRIMINATOR);
+                .copy_from_slice(DynTickArrayState::DISCRIMINATOR);
 
             let data = RefCell::new(&mut dyn_tick_array_full_account_data[..]);
 
@@ -1551,6 +2134,13 @@ pub mod dyn_tick_array_test {
                 assert!(tick_state.liquidity_gross == liquidity_gross);
                 assert!(tick_state.fee_growth_outside_0_x64 == fee_growth_outside_0_x64);
                 assert!(tick_state.fee_growth_outside_1_x64 == fee_growth_outside_1_x64);
+                assert!(tick_state.maker_rebate_growth_0_x64 == maker_rebate_growth_0_x64);
+                assert!(tick_state.maker_rebate_growth_1_x64 == maker_rebate_growth_1_x64);
+                assert!(
+                    tick_state.seconds_per_liquidity_outside_x64
+                        == seconds_per_liquidity_outside_x64
+                );
+                assert!(tick_state.seconds_outside == seconds_outside);
             }
         }
     }
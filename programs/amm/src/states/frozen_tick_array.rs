@@ -0,0 +1,219 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+/// The max count of non empty TickStates of a frozen tick array, so that they all fit in the thaw transaction
+pub const MAX_FROZEN_TICK_COUNT: usize = 4;
+/// The min distance, in tick arrays, between a tick array to freeze and the tick array of the current tick
+pub const FREEZE_MIN_TICK_ARRAY_DISTANCE: i32 = 10;
+
+/// The leaves of the merkle tree of a tick array, the TickStates padded with empty ones to a power of two
+const MERKLE_LEAF_COUNT: usize = TICK_ARRAY_SIZE_USIZE.next_power_of_two();
+const MERKLE_LEAF_PREFIX: &[u8] = &[0];
+const MERKLE_NODE_PREFIX: &[u8] = &[1];
+
+/// A fixed tick array frozen by `freeze_tick_array`, in place of the tick array account.
+/// Until it is thawed, the swaps and the positions using the tick array fail to load it.
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct FrozenTickArrayState {
+    pub pool_id: Pubkey,
+    pub start_tick_index: i32,
+    /// The merkle root of the TickStates of the tick array, see `tick_array_merkle_root`
+    pub root: [u8; 32],
+    /// Bit `i` is set if the TickState at offset `i` is not empty, they are supplied to thaw the tick array
+    pub non_empty_offsets: u64,
+    pub initialized_tick_count: u8,
+    pub recent_epoch: u64,
+    // Unused bytes for future upgrades.
+    pub padding: [u8; 32],
+}
+
+impl FrozenTickArrayState {
+    pub const LEN: usize = 8 + 32 + 4 + 32 + 8 + 1 + 8 + 32;
+
+    /// Check the tick array at `start_tick_index` is far enough from `tick_current` and has few enough
+    /// non empty TickStates to be frozen
+    pub fn check_freezable(
+        start_tick_index: i32,
+        tick_current: i32,
+        tick_spacing: u16,
+        non_empty_tick_count: usize,
+    ) -> Result<()> {
+        let ticks_in_array = TickUtils::tick_count(tick_spacing);
+        let current_start_index = TickUtils::get_array_start_index(tick_current, tick_spacing);
+        let distance = (start_tick_index - current_start_index).abs() / ticks_in_array;
+        require!(
+            distance >= FREEZE_MIN_TICK_ARRAY_DISTANCE
+                && non_empty_tick_count <= MAX_FROZEN_TICK_COUNT,
+            ErrorCode::TickArrayNotFreezable
+        );
+        Ok(())
+    }
+}
+
+/// A non empty TickState of a frozen tick array, at `offset` in the tick array
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrozenTick {
+    pub offset: u8,
+    /// The bytes of the TickState
+    pub data: [u8; TickState::LEN],
+}
+
+impl FrozenTick {
+    /// Collect the TickStates that are not all zeros, initialized or not, in offset order
+    pub fn non_empty(ticks: &[TickState; TICK_ARRAY_SIZE_USIZE]) -> Vec<Self> {
+        ticks
+            .iter()
+            .enumerate()
+            .filter_map(|(offset, tick_state)| {
+                let data = bytemuck::bytes_of(tick_state);
+                if data.iter().all(|byte| *byte == 0) {
+                    return None;
+                }
+                Some(FrozenTick {
+                    offset: offset as u8,
+                    data: data.try_into().unwrap(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn tick_state(&self) -> TickState {
+        bytemuck::pod_read_unaligned(&self.data)
+    }
+}
+
+pub fn merkle_leaf(tick_state: &TickState) -> [u8; 32] {
+    hashv(&[MERKLE_LEAF_PREFIX, bytemuck::bytes_of(tick_state)]).to_bytes()
+}
+
+pub fn merkle_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[MERKLE_NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Get the levels of the merkle tree of `ticks`, from the leaves to the root
+pub fn tick_array_merkle_tree(ticks: &[TickState; TICK_ARRAY_SIZE_USIZE]) -> Vec<Vec<[u8; 32]>> {
+    let empty_leaf = merkle_leaf(&TickState::default());
+    let mut level: Vec<[u8; 32]> = ticks.iter().map(merkle_leaf).collect();
+    level.resize(MERKLE_LEAF_COUNT, empty_leaf);
+    let mut levels = vec![level];
+    while levels.last().unwrap().len() > 1 {
+        let next_level = levels
+            .last()
+            .unwrap()
+            .chunks_exact(2)
+            .map(|pair| merkle_node(&pair[0], &pair[1]))
+            .collect();
+        levels.push(next_level);
+    }
+    levels
+}
+
+pub fn tick_array_merkle_root(ticks: &[TickState; TICK_ARRAY_SIZE_USIZE]) -> [u8; 32] {
+    tick_array_merkle_tree(ticks).last().unwrap()[0]
+}
+
+/// Emitted when a tick array is frozen, with the TickStates needed to thaw it
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickArrayFrozenEvent {
+    pub pool_state: Pubkey,
+    pub tick_array: Pubkey,
+    pub start_tick_index: i32,
+    pub root: [u8; 32],
+    pub ticks: Vec<FrozenTick>,
+    /// The rent refunded by the account shrink
+    pub refunded_lamports: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when a frozen tick array is restored
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickArrayThawedEvent {
+    pub pool_state: Pubkey,
+    pub tick_array: Pubkey,
+    pub start_tick_index: i32,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod frozen_tick_array_test {
+    use super::*;
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+
+    #[test]
+    fn frozen_tick_array_len_test() {
+        assert_eq!(
+            FrozenTickArrayState::LEN,
+            8 + std::mem::size_of::<FrozenTickArrayState>()
+        );
+    }
+
+    #[test]
+    fn check_freezable_test() {
+        let ticks_in_array = TickUtils::tick_count(10);
+        let far = FREEZE_MIN_TICK_ARRAY_DISTANCE * ticks_in_array;
+        assert!(FrozenTickArrayState::check_freezable(far, 5, 10, 1).is_ok());
+        assert!(FrozenTickArrayState::check_freezable(-far, 5, 10, 1).is_ok());
+        assert!(FrozenTickArrayState::check_freezable(far, 5, 10, MAX_FROZEN_TICK_COUNT).is_ok());
+        for (start_tick_index, non_empty_tick_count) in [
+            (far - ticks_in_array, 1),
+            (0, 0),
+            (far, MAX_FROZEN_TICK_COUNT + 1),
+        ] {
+            assert_eq!(
+                FrozenTickArrayState::check_freezable(
+                    start_tick_index,
+                    5,
+                    10,
+                    non_empty_tick_count
+                )
+                .err(),
+                Some(ErrorCode::TickArrayNotFreezable.into())
+            );
+        }
+    }
+
+    #[test]
+    fn merkle_root_test() {
+        let tick_array = build_fix_tick_array_with_tick_states(
+            Pubkey::new_unique(),
+            0,
+            10,
+            vec![
+                build_tick(10, 30, 30).take(),
+                build_tick(200, 30, -30).take(),
+            ],
+        );
+        let ticks = tick_array.borrow().ticks;
+        let root = tick_array_merkle_root(&ticks);
+        let tree = tick_array_merkle_tree(&ticks);
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree[0].len(), MERKLE_LEAF_COUNT);
+
+        let frozen_ticks = FrozenTick::non_empty(&ticks);
+        assert_eq!(
+            frozen_ticks.iter().map(|t| t.offset).collect::<Vec<_>>(),
+            vec![1, 20]
+        );
+        // the non empty ticks are enough to rebuild the tick array
+        let mut thawed = [TickState::default(); TICK_ARRAY_SIZE_USIZE];
+        for frozen_tick in &frozen_ticks {
+            thawed[usize::from(frozen_tick.offset)] = frozen_tick.tick_state();
+        }
+        assert_eq!(tick_array_merkle_root(&thawed), root);
+
+        // any change of a tick changes the root
+        thawed[20].liquidity_gross = 31;
+        assert_ne!(tick_array_merkle_root(&thawed), root);
+        thawed[20].liquidity_gross = 30;
+        thawed[59].tick = 590;
+        assert_ne!(tick_array_merkle_root(&thawed), root);
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+pub const KILL_SWITCH_SEED: &str = "kill_switch";
+
+/// The kill switch authority of an amm config, who can only disable or enable the swaps of the pools of that config.
+/// It can be held by an automated monitoring, it has no power over the other configs.
+#[account]
+#[derive(Default, Debug)]
+pub struct AmmConfigKillSwitch {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The amm config whose swaps the authority can flip
+    pub amm_config: Pubkey,
+    /// The kill switch authority, default pubkey if none
+    pub authority: Pubkey,
+    pub padding: [u64; 4],
+}
+
+impl AmmConfigKillSwitch {
+    pub const LEN: usize = 8 + 1 + 32 + 32 + 32;
+
+    pub fn key(amm_config: Pubkey, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[KILL_SWITCH_SEED.as_bytes(), amm_config.as_ref()],
+            program_id,
+        )
+        .0
+    }
+
+    /// Find the kill switch of `amm_config` in the remaining accounts, if the caller provided it.
+    pub fn find_in<'info>(
+        remaining_accounts: &'info [AccountInfo<'info>],
+        amm_config: Pubkey,
+    ) -> Option<&'info AccountInfo<'info>> {
+        let kill_switch_key = Self::key(amm_config, &crate::id());
+        remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == kill_switch_key)
+    }
+
+    /// Whether `authority` is the kill switch authority, never for the default pubkey
+    pub fn is_authority(&self, authority: &Pubkey) -> bool {
+        *authority == self.authority && *authority != Pubkey::default()
+    }
+}
+
+/// Emitted when the kill switch authority of an amm config is set
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct KillSwitchAuthorityChangeEvent {
+    pub amm_config: Pubkey,
+    pub kill_switch_authority: Pubkey,
+}
+
+#[cfg(test)]
+mod kill_switch_test {
+    use super::*;
+
+    #[test]
+    fn kill_switch_layout_test() {
+        let mut data = Vec::new();
+        AmmConfigKillSwitch::default().serialize(&mut data).unwrap();
+        assert_eq!(data.len() + 8, AmmConfigKillSwitch::LEN);
+    }
+
+    #[test]
+    fn is_authority_test() {
+        let kill_switch = AmmConfigKillSwitch {
+            authority: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert!(kill_switch.is_authority(&kill_switch.authority));
+        assert!(!kill_switch.is_authority(&Pubkey::new_unique()));
+        // no kill switch authority
+        assert!(!AmmConfigKillSwitch::default().is_authority(&Pubkey::default()));
+    }
+}
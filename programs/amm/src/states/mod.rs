@@ -1,29 +1,64 @@
+/// Assert each field of a zero copy account has the same bytes as in its plain layout in the core crate
+#[cfg(test)]
+macro_rules! assert_core_layout {
+    ($account:expr, $layout:expr, $($field:ident),* $(,)?) => {
+        assert_eq!(std::mem::size_of_val(&$account), std::mem::size_of_val(&$layout));
+        $(
+            assert_eq!(
+                bytemuck::bytes_of(&{ $account.$field }),
+                bytemuck::bytes_of(&{ $layout.$field }),
+                stringify!($field)
+            );
+        )*
+    };
+}
+
 pub mod admin_group;
 pub mod config;
+pub mod crank_bounty;
+pub mod deployment;
 pub mod dyn_tick_array;
+pub mod frozen_tick_array;
+pub mod kill_switch;
 pub mod offchain_reward_config;
 pub mod operation_account;
 pub mod oracle;
 pub mod personal_position;
 pub mod pool;
+pub mod pool_apr;
+pub mod pool_state_view;
+pub mod pool_template;
+pub mod position_collateral;
 pub mod protocol_position;
+pub mod rebalance_bounty;
 pub mod support_mint_associated;
 pub mod tick_array;
 pub mod tick_array_container;
+pub mod tick_rent_treasury;
 pub mod tick_state_utils;
 pub mod tickarray_bitmap_extension;
 
 pub use admin_group::*;
 pub use config::*;
+pub use crank_bounty::*;
+pub use deployment::*;
 pub use dyn_tick_array::*;
+pub use frozen_tick_array::*;
+pub use kill_switch::*;
 pub use offchain_reward_config::*;
 pub use operation_account::*;
 pub use oracle::*;
 pub use personal_position::*;
 pub use pool::*;
+pub use pool_apr::*;
+pub use pool_state_view::*;
+pub use pool_template::*;
+pub use position_collateral::*;
 pub use protocol_position::*;
+pub use rebalance_bounty::*;
 pub use support_mint_associated::*;
 pub use tick_array::*;
 pub use tick_array_container::*;
+pub use tick_rent_treasury::*;
 pub use tick_state_utils::*;
 pub use tickarray_bitmap_extension::*;
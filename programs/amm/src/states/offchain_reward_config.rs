@@ -1,4 +1,5 @@
 use crate::error::ErrorCode;
+use crate::util::get_rent;
 use anchor_lang::{prelude::*, system_program};
 
 pub const OFFCHAIN_REWARD_SEED: &str = "offchain_reward";
@@ -88,7 +89,7 @@ impl OffchainRewardConfig {
         AccountInfo::resize(&reward_config, new_size)?;
 
         // If more lamports are needed, transfer them to the account.
-        let rent_exempt_lamports = Rent::get().unwrap().minimum_balance(new_size).max(1);
+        let rent_exempt_lamports = get_rent()?.minimum_balance(new_size).max(1);
         let top_up_lamports =
             rent_exempt_lamports.saturating_sub(reward_config.to_account_info().lamports());
 
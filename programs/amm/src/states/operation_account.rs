@@ -40,7 +40,7 @@ impl OperationState {
         operation_owners.retain(|&item| item != Pubkey::default());
         let owners_set: HashSet<Pubkey> = HashSet::from_iter(operation_owners.iter().cloned());
         let mut updated_owner: Vec<Pubkey> = owners_set.into_iter().collect();
-        updated_owner.sort_by(|a, b| a.cmp(b));
+        updated_owner.sort();
         // clear
         self.operation_owners = [Pubkey::default(); OPERATION_SIZE_USIZE];
         // update
@@ -50,7 +50,7 @@ impl OperationState {
     pub fn remove_operation_owner(&mut self, keys: Vec<Pubkey>) {
         let mut operation_owners = self.operation_owners.to_vec();
         // remove keys from operation_owners
-        operation_owners.retain(|x| !keys.contains(&x));
+        operation_owners.retain(|x| !keys.contains(x));
         // clear
         self.operation_owners = [Pubkey::default(); OPERATION_SIZE_USIZE];
         // update
@@ -73,7 +73,7 @@ impl OperationState {
     pub fn remove_whitelist_mint(&mut self, keys: Vec<Pubkey>) {
         let mut whitelist_mints = self.whitelist_mints.to_vec();
         // remove keys from whitelist_mint
-        whitelist_mints.retain(|x| !keys.contains(&x));
+        whitelist_mints.retain(|x| !keys.contains(x));
         // clear
         self.whitelist_mints = [Pubkey::default(); WHITE_MINT_SIZE_USIZE];
         // update
@@ -92,12 +92,13 @@ mod test {
             operation_owners: [Pubkey::default(); OPERATION_SIZE_USIZE],
             whitelist_mints: [Pubkey::default(); WHITE_MINT_SIZE_USIZE],
         };
-        let mut keys = Vec::new();
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.sort_by(|a, b| a.cmp(b));
+        let mut keys = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        keys.sort();
         println!("{:?}", keys);
 
         operation_state.update_operation_owner(keys.clone());
@@ -118,12 +119,13 @@ mod test {
         operation_state.operation_owners[0] = Pubkey::new_unique();
         operation_state.operation_owners[1] = Pubkey::new_unique();
         operation_state.operation_owners[2] = Pubkey::new_unique();
-        let mut keys = Vec::new();
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.sort_by(|a, b| a.cmp(b));
+        let mut keys = vec![
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        keys.sort();
         println!("{:?}", keys);
 
         operation_state.update_operation_owner(keys.clone());
@@ -140,12 +142,13 @@ mod test {
         operation_state.operation_owners[0] = Pubkey::new_unique();
         operation_state.operation_owners[1] = Pubkey::new_unique();
         operation_state.operation_owners[2] = Pubkey::new_unique();
-        let mut keys = Vec::new();
-        keys.push(operation_state.operation_owners[0]);
-        keys.push(operation_state.operation_owners[1]);
-        keys.push(Pubkey::new_unique());
-        keys.push(Pubkey::new_unique());
-        keys.sort_by(|a, b| a.cmp(b));
+        let mut keys = vec![
+            operation_state.operation_owners[0],
+            operation_state.operation_owners[1],
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        keys.sort();
         println!("{:?}", keys);
 
         operation_state.update_operation_owner(keys.clone());
@@ -163,7 +166,7 @@ mod test {
         for _i in 0..10 {
             keys.push(Pubkey::new_unique());
         }
-        keys.sort_by(|a, b| a.cmp(b));
+        keys.sort();
         println!("{:?}", keys);
 
         operation_state.update_operation_owner(keys.clone());
@@ -186,7 +189,7 @@ mod test {
         for _i in 0..11 {
             keys.push(Pubkey::new_unique());
         }
-        keys.sort_by(|a, b| a.cmp(b));
+        keys.sort();
         println!("{:?}", keys);
 
         operation_state.update_operation_owner(keys.clone());
@@ -204,7 +207,7 @@ mod test {
             keys.push(Pubkey::new_unique());
         }
         keys.push(keys[0]);
-        keys.sort_by(|a, b| a.cmp(b));
+        keys.sort();
         operation_state.operation_owners[0..keys.len()].copy_from_slice(keys.clone().as_slice());
         operation_state.operation_owners[keys.len()] = Pubkey::new_unique();
         operation_state.operation_owners[keys.len() + 1] = Pubkey::new_unique();
@@ -227,16 +230,16 @@ mod test {
         let mut operation_data =
             [0u8; 8 + 1 + 32 * OPERATION_SIZE_USIZE + 32 * WHITE_MINT_SIZE_USIZE];
         let mut offset = 0;
-        operation_data[offset..offset + 8].copy_from_slice(&OperationState::DISCRIMINATOR);
+        operation_data[offset..offset + 8].copy_from_slice(OperationState::DISCRIMINATOR);
         offset += 8;
         operation_data[offset..offset + 1].copy_from_slice(&bump.to_le_bytes());
         offset += 1;
-        for i in 0..OPERATION_SIZE_USIZE {
-            operation_data[offset..offset + 32].copy_from_slice(&operation_owners[i].to_bytes());
+        for operation_owner in operation_owners {
+            operation_data[offset..offset + 32].copy_from_slice(&operation_owner.to_bytes());
             offset += 32;
         }
-        for i in 0..WHITE_MINT_SIZE_USIZE {
-            operation_data[offset..offset + 32].copy_from_slice(&whitelist_mints[i].to_bytes());
+        for whitelist_mint in whitelist_mints {
+            operation_data[offset..offset + 32].copy_from_slice(&whitelist_mint.to_bytes());
             offset += 32;
         }
 
@@ -254,13 +257,9 @@ mod test {
         // data check
         let unpack_bump = unpack_data.bump;
         assert_eq!(unpack_bump, bump);
-        for i in 0..OPERATION_SIZE_USIZE {
-            let unpack_operation_owners = unpack_data.operation_owners[i];
-            assert_eq!(unpack_operation_owners, operation_owners[i]);
-        }
-        for i in 0..WHITE_MINT_SIZE_USIZE {
-            let unpack_whitelist_mints = unpack_data.whitelist_mints[i];
-            assert_eq!(unpack_whitelist_mints, whitelist_mints[i]);
-        }
+        let unpack_operation_owners = unpack_data.operation_owners;
+        assert_eq!(unpack_operation_owners, operation_owners);
+        let unpack_whitelist_mints = unpack_data.whitelist_mints;
+        assert_eq!(unpack_whitelist_mints, whitelist_mints);
     }
 }
@@ -2,7 +2,10 @@
 ///
 use anchor_lang::prelude::*;
 
-use crate::util::get_recent_epoch;
+use crate::libraries::fixed_point_64;
+use crate::util::{get_recent_epoch, get_unix_timestamp};
+use bytemuck::{AnyBitPattern, NoUninit};
+use std::convert::identity;
 
 /// Seed to derive account address and signature
 pub const OBSERVATION_SEED: &str = "observation";
@@ -13,18 +16,20 @@ pub const OBSERVATION_UPDATE_DURATION_DEFAULT: u32 = 15;
 /// The element of observations in ObservationState
 #[zero_copy(unsafe)]
 #[repr(C, packed)]
-#[derive(Default, Debug)]
+#[derive(Default, Debug, NoUninit, AnyBitPattern)]
 pub struct Observation {
     /// The block timestamp of the observation
     pub block_timestamp: u32,
     /// the cumulative of tick during the duration time
     pub tick_cumulative: i64,
+    /// the cumulative of seconds per in range liquidity during the duration time, as a Q64.64
+    pub seconds_per_liquidity_cumulative_x64: u128,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 2],
 }
 
 impl Observation {
-    pub const LEN: usize = 4 + 8 + 8 * 4;
+    pub const LEN: usize = 4 + 8 + 16 + 8 * 2;
 }
 
 #[account(zero_copy(unsafe))]
@@ -41,8 +46,15 @@ pub struct ObservationState {
     pub pool_id: Pubkey,
     /// observation array
     pub observations: [Observation; OBSERVATION_NUM],
+    /// the most-recently updated index of the extension observations
+    pub extension_index: u16,
+    /// the number of extension observations in use, zero if the extension is disabled
+    pub extension_cardinality: u16,
+    /// the number of extension observations allocated, `extension_cardinality` grows to it when the index wraps
+    pub extension_cardinality_next: u16,
+    pub padding_u16: u16,
     /// padding for feature update
-    pub padding: [u64; 4],
+    pub padding: [u64; 3],
 }
 
 impl Default for ObservationState {
@@ -54,13 +66,28 @@ impl Default for ObservationState {
             observation_index: 0,
             pool_id: Pubkey::default(),
             observations: [Observation::default(); OBSERVATION_NUM],
-            padding: [0u64; 4],
+            extension_index: 0,
+            extension_cardinality: 0,
+            extension_cardinality_next: 0,
+            padding_u16: 0,
+            padding: [0u64; 3],
         }
     }
 }
 
 impl ObservationState {
-    pub const LEN: usize = 8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 8 * 4;
+    pub const LEN: usize =
+        8 + 1 + 8 + 2 + 32 + (Observation::LEN * OBSERVATION_NUM) + 2 * 4 + 8 * 3;
+
+    /// The account space with `extension_cardinality` extension observations
+    pub fn space(extension_cardinality: u16) -> usize {
+        Self::LEN + Observation::LEN * usize::from(extension_cardinality)
+    }
+
+    /// The offset in the account data of the extension observation at `index`
+    pub fn extension_offset(index: u16) -> usize {
+        Self::space(index)
+    }
 
     pub fn initialize(&mut self, pool_id: Pubkey) -> Result<()> {
         self.initialized = false;
@@ -68,7 +95,11 @@ impl ObservationState {
         self.observation_index = 0;
         self.pool_id = pool_id;
         self.observations = [Observation::default(); OBSERVATION_NUM];
-        self.padding = [0u64; 4];
+        self.extension_index = 0;
+        self.extension_cardinality = 0;
+        self.extension_cardinality_next = 0;
+        self.padding_u16 = 0;
+        self.padding = [0u64; 3];
         Ok(())
     }
 
@@ -78,13 +109,16 @@ impl ObservationState {
     ///
     /// * `self` - The ObservationState account to write in
     /// * `block_timestamp` - The current timestamp of to update
+    /// * `tick` - The tick of the pool since the last observation
+    /// * `liquidity` - The in range liquidity of the pool since the last observation
     ///
-    pub fn update(&mut self, block_timestamp: u32, tick: i32) {
+    pub fn update(&mut self, block_timestamp: u32, tick: i32, liquidity: u128) {
         let observation_index = self.observation_index;
         if !self.initialized {
             self.initialized = true;
             self.observations[observation_index as usize].block_timestamp = block_timestamp;
             self.observations[observation_index as usize].tick_cumulative = 0;
+            self.observations[observation_index as usize].seconds_per_liquidity_cumulative_x64 = 0;
         } else {
             let last_observation = self.observations[observation_index as usize];
             let delta_time = block_timestamp.saturating_sub(last_observation.block_timestamp);
@@ -102,15 +136,57 @@ impl ObservationState {
             self.observations[next_observation_index as usize].tick_cumulative = last_observation
                 .tick_cumulative
                 .wrapping_add(delta_tick_cumulative);
+            // the time is accrued to a liquidity of 1 if there is no liquidity in range
+            let delta_seconds_per_liquidity_x64 =
+                (u128::from(delta_time) << fixed_point_64::RESOLUTION) / liquidity.max(1);
+            self.observations[next_observation_index as usize]
+                .seconds_per_liquidity_cumulative_x64 = last_observation
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_add(delta_seconds_per_liquidity_x64);
             self.observation_index = next_observation_index;
         }
     }
+
+    /// Record the most recent observation in the extension observations after the account data of
+    /// `ObservationState`, if the extension is enabled and the observation is not recorded yet.
+    /// The extension observations are a ring buffer of `extension_cardinality` observations.
+    pub fn update_extension(observation_account: &AccountInfo) -> Result<()> {
+        let mut data = observation_account.try_borrow_mut_data()?;
+        let (state_data, extension_data) = data.split_at_mut(Self::LEN);
+        let state: &mut ObservationState = bytemuck::from_bytes_mut(&mut state_data[8..Self::LEN]);
+        if state.extension_cardinality_next == 0 || !state.initialized {
+            return Ok(());
+        }
+        let observation = state.observations[usize::from(state.observation_index)];
+
+        let extension_index = if state.extension_cardinality == 0 {
+            state.extension_cardinality = state.extension_cardinality_next;
+            0
+        } else {
+            let offset = usize::from(state.extension_index) * Observation::LEN;
+            let last_observation: &Observation =
+                bytemuck::from_bytes(&extension_data[offset..offset + Observation::LEN]);
+            if identity(last_observation.block_timestamp) == identity(observation.block_timestamp) {
+                return Ok(());
+            }
+            // only grow when the index wraps, so the observations stay in order
+            if state.extension_index == state.extension_cardinality - 1 {
+                state.extension_cardinality = state.extension_cardinality_next;
+            }
+            (state.extension_index + 1) % state.extension_cardinality
+        };
+        let offset = usize::from(extension_index) * Observation::LEN;
+        extension_data[offset..offset + Observation::LEN]
+            .copy_from_slice(bytemuck::bytes_of(&observation));
+        state.extension_index = extension_index;
+        Ok(())
+    }
 }
 
 /// Returns the block timestamp truncated to 32 bits, i.e. mod 2**32
 ///
 pub fn block_timestamp() -> u32 {
-    Clock::get().unwrap().unix_timestamp as u32 // truncation is desired
+    get_unix_timestamp().unwrap() as u32 // truncation is desired
 }
 
 #[cfg(test)]
@@ -132,48 +208,43 @@ pub mod oracle_layout_test {
         let recent_epoch: u64 = 0x123456789abcdef0;
         let observation_index: u16 = 0x1122;
         let pool_id: Pubkey = Pubkey::new_unique();
-        let padding: [u64; 4] = [
-            0x123456789abcde0f,
-            0x123456789abcd0ef,
-            0x123456789abc0def,
-            0x123456789ab0cdef,
-        ];
+        let extension_index: u16 = 0x3344;
+        let extension_cardinality: u16 = 0x5566;
+        let extension_cardinality_next: u16 = 0x7788;
+        let padding_u16: u16 = 0x99aa;
+        let padding: [u64; 3] = [0x123456789abcde0f, 0x123456789abcd0ef, 0x123456789abc0def];
 
         let mut observation_datas = [0u8; Observation::LEN * OBSERVATION_NUM];
         let mut observations = [Observation::default(); OBSERVATION_NUM];
         let mut offset = 0;
-        for i in 0..OBSERVATION_NUM {
+        for (i, observation) in observations.iter_mut().enumerate() {
             let index = i + 1;
             let block_timestamp: u32 = u32::MAX - 3 * index as u32;
             let tick_cumulative: i64 = i64::MAX - 3 * index as i64;
-            let padding: [u64; 4] = [
-                u64::MAX - index as u64,
-                u64::MAX - 2 * index as u64,
-                u64::MAX - 3 * index as u64,
-                u64::MAX - 4 * index as u64,
-            ];
-            observations[i].block_timestamp = block_timestamp;
-            observations[i].tick_cumulative = tick_cumulative;
-            observations[i].padding = padding;
+            let seconds_per_liquidity_cumulative_x64: u128 = u128::MAX - 3 * index as u128;
+            let padding: [u64; 2] = [u64::MAX - index as u64, u64::MAX - 2 * index as u64];
+            observation.block_timestamp = block_timestamp;
+            observation.tick_cumulative = tick_cumulative;
+            observation.seconds_per_liquidity_cumulative_x64 = seconds_per_liquidity_cumulative_x64;
+            observation.padding = padding;
             observation_datas[offset..offset + 4].copy_from_slice(&block_timestamp.to_le_bytes());
             offset += 4;
             observation_datas[offset..offset + 8].copy_from_slice(&tick_cumulative.to_le_bytes());
             offset += 8;
+            observation_datas[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_cumulative_x64.to_le_bytes());
+            offset += 16;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
             offset += 8;
             observation_datas[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
             offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
-            offset += 8;
-            observation_datas[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-            offset += 8;
         }
 
         // serialize original data
         let mut observation_state_data = [0u8; ObservationState::LEN];
         let mut offset = 0;
         observation_state_data[offset..offset + 8]
-            .copy_from_slice(&ObservationState::DISCRIMINATOR);
+            .copy_from_slice(ObservationState::DISCRIMINATOR);
         offset += 8;
         observation_state_data[offset..offset + 1]
             .copy_from_slice(&(initialized as u8).to_le_bytes());
@@ -188,14 +259,22 @@ pub mod oracle_layout_test {
         observation_state_data[offset..offset + Observation::LEN * OBSERVATION_NUM]
             .copy_from_slice(&observation_datas);
         offset += Observation::LEN * OBSERVATION_NUM;
+        observation_state_data[offset..offset + 2].copy_from_slice(&extension_index.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&extension_cardinality.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 2]
+            .copy_from_slice(&extension_cardinality_next.to_le_bytes());
+        offset += 2;
+        observation_state_data[offset..offset + 2].copy_from_slice(&padding_u16.to_le_bytes());
+        offset += 2;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[0].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[1].to_le_bytes());
         offset += 8;
         observation_state_data[offset..offset + 8].copy_from_slice(&padding[2].to_le_bytes());
         offset += 8;
-        observation_state_data[offset..offset + 8].copy_from_slice(&padding[3].to_le_bytes());
-        offset += 8;
         // len check
         assert_eq!(offset, observation_state_data.len());
         assert_eq!(
@@ -217,6 +296,17 @@ pub mod oracle_layout_test {
         assert_eq!(unpack_observation_index, observation_index);
         let unpack_pool_id = unpack_data.pool_id;
         assert_eq!(unpack_pool_id, pool_id);
+        let unpack_extension_index = unpack_data.extension_index;
+        assert_eq!(unpack_extension_index, extension_index);
+        let unpack_extension_cardinality = unpack_data.extension_cardinality;
+        assert_eq!(unpack_extension_cardinality, extension_cardinality);
+        let unpack_extension_cardinality_next = unpack_data.extension_cardinality_next;
+        assert_eq!(
+            unpack_extension_cardinality_next,
+            extension_cardinality_next
+        );
+        let unpack_padding_u16 = unpack_data.padding_u16;
+        assert_eq!(unpack_padding_u16, padding_u16);
         let unpack_padding = unpack_data.padding;
         assert_eq!(unpack_padding, padding);
         for (observation, unpack_observation) in
@@ -224,14 +314,109 @@ pub mod oracle_layout_test {
         {
             let block_timestamp = observation.block_timestamp;
             let tick_cumulative = observation.tick_cumulative;
+            let seconds_per_liquidity_cumulative_x64 =
+                observation.seconds_per_liquidity_cumulative_x64;
             let padding = observation.padding;
 
             let unpack_block_timestamp = unpack_observation.block_timestamp;
             let unpack_tick_cumulative = unpack_observation.tick_cumulative;
+            let unpack_seconds_per_liquidity_cumulative_x64 =
+                unpack_observation.seconds_per_liquidity_cumulative_x64;
             let unpack_padding = unpack_observation.padding;
             assert_eq!(block_timestamp, unpack_block_timestamp);
             assert_eq!(tick_cumulative, unpack_tick_cumulative);
+            assert_eq!(
+                seconds_per_liquidity_cumulative_x64,
+                unpack_seconds_per_liquidity_cumulative_x64
+            );
             assert_eq!(padding, unpack_padding);
         }
     }
 }
+
+#[cfg(test)]
+mod oracle_test {
+    use super::*;
+    use crate::libraries::test_account_utils::mock_anchor_account_info_v3;
+
+    #[test]
+    fn update_seconds_per_liquidity_test() {
+        let mut observation_state = ObservationState::default();
+        observation_state.update(1000, 10, 100);
+        observation_state.update(1000 + OBSERVATION_UPDATE_DURATION_DEFAULT, 10, 4);
+        let observation = observation_state.observations[1];
+        assert_eq!(
+            identity(observation.tick_cumulative),
+            10 * i64::from(OBSERVATION_UPDATE_DURATION_DEFAULT)
+        );
+        assert_eq!(
+            identity(observation.seconds_per_liquidity_cumulative_x64),
+            (u128::from(OBSERVATION_UPDATE_DURATION_DEFAULT) << 64) / 4
+        );
+
+        // no liquidity in range accrues as a liquidity of 1
+        observation_state.update(1000 + 2 * OBSERVATION_UPDATE_DURATION_DEFAULT, 10, 0);
+        assert_eq!(
+            identity(observation_state.observations[2].seconds_per_liquidity_cumulative_x64),
+            identity(observation.seconds_per_liquidity_cumulative_x64)
+                + (u128::from(OBSERVATION_UPDATE_DURATION_DEFAULT) << 64)
+        );
+    }
+
+    #[test]
+    fn update_extension_test() {
+        let key = Pubkey::new_unique();
+        let owner = crate::id();
+        let observation_state = ObservationState {
+            extension_cardinality_next: 2,
+            ..Default::default()
+        };
+        let extension_data = [0u8; Observation::LEN * 3];
+        let (account_info, _, data) =
+            mock_anchor_account_info_v3(&key, &owner, &observation_state, Some(&extension_data));
+        let load = |data: &[u8]| -> ObservationState {
+            bytemuck::pod_read_unaligned(&data[8..ObservationState::LEN])
+        };
+        let extension_observation = |data: &[u8], index: u16| -> Observation {
+            let offset = ObservationState::extension_offset(index);
+            bytemuck::pod_read_unaligned(&data[offset..offset + Observation::LEN])
+        };
+        let update = |block_timestamp: u32| {
+            let mut state = load(&data.borrow());
+            state.update(block_timestamp, 1, 1);
+            data.borrow_mut()[8..ObservationState::LEN].copy_from_slice(bytemuck::bytes_of(&state));
+            ObservationState::update_extension(&account_info).unwrap();
+        };
+
+        update(1000);
+        let state = load(&data.borrow());
+        assert_eq!(identity(state.extension_cardinality), 2);
+        assert_eq!(identity(state.extension_index), 0);
+        assert_eq!(
+            identity(extension_observation(&data.borrow(), 0).block_timestamp),
+            1000
+        );
+
+        // an observation is recorded once
+        update(1001);
+        assert_eq!(identity(load(&data.borrow()).extension_index), 0);
+
+        update(1000 + OBSERVATION_UPDATE_DURATION_DEFAULT);
+        assert_eq!(identity(load(&data.borrow()).extension_index), 1);
+
+        // the cardinality grows when the index wraps
+        let mut state = load(&data.borrow());
+        state.extension_cardinality_next = 3;
+        data.borrow_mut()[8..ObservationState::LEN].copy_from_slice(bytemuck::bytes_of(&state));
+        update(1000 + 2 * OBSERVATION_UPDATE_DURATION_DEFAULT);
+        let state = load(&data.borrow());
+        assert_eq!(identity(state.extension_cardinality), 3);
+        assert_eq!(identity(state.extension_index), 2);
+        update(1000 + 3 * OBSERVATION_UPDATE_DURATION_DEFAULT);
+        assert_eq!(identity(load(&data.borrow()).extension_index), 0);
+        assert_eq!(
+            identity(extension_observation(&data.borrow(), 0).block_timestamp),
+            1000 + 3 * OBSERVATION_UPDATE_DURATION_DEFAULT
+        );
+    }
+}
@@ -42,8 +42,16 @@ pub struct PersonalPositionState {
     pub reward_infos: [PositionRewardInfo; REWARD_NUM],
     // account update recent epoch
     pub recent_epoch: u64,
+    /// The maker rebate growth of the lower plus the upper tick as of the last action on the position, for token_0 and token_1
+    pub maker_rebate_growth_boundary_last_x64: [u128; 2],
+    /// The slot of the last liquidity increase, see `PoolState::check_position_age`
+    pub last_increase_slot: u64,
+    /// Whether the position has collateral terms, see `PositionCollateralState`.
+    /// The owner can't move its liquidity or close it until the collateral manager releases the terms.
+    pub collateralized: bool,
+    pub padding_0: [u8; 7],
     // Unused bytes for future upgrades.
-    pub padding: [u64; 7],
+    pub padding: [u64; 1],
 }
 
 impl PersonalPositionState {
@@ -52,7 +60,7 @@ impl PersonalPositionState {
 
     pub fn seeds(&self) -> [&[u8]; 3] {
         [
-            &POSITION_SEED.as_bytes(),
+            POSITION_SEED.as_bytes(),
             self.nft_mint.as_ref(),
             self.bump.as_ref(),
         ]
@@ -69,6 +77,7 @@ impl PersonalPositionState {
         fee_growth_inside_0_x64: u128,
         fee_growth_inside_1_x64: u128,
         reward_growths_inside: [u128; REWARD_NUM],
+        maker_rebate_growth_boundary_x64: [u128; 2],
         recent_epoch: u64,
     ) -> Result<()> {
         self.bump = [bump];
@@ -83,7 +92,18 @@ impl PersonalPositionState {
         // update rewards, must update before update liquidity
         self.update_rewards(reward_growths_inside, false, recent_epoch)?;
         self.liquidity = liquidity;
-        self.padding = [0; 7];
+        self.maker_rebate_growth_boundary_last_x64 = maker_rebate_growth_boundary_x64;
+        self.last_increase_slot = 0;
+        self.collateralized = false;
+        self.padding_0 = [0; 7];
+        self.padding = [0; 1];
+        Ok(())
+    }
+
+    /// The owner-side instructions moving the liquidity of the position or closing it fail while it is collateralized,
+    /// only `liquidate_position` signed by the collateral manager can decrease it
+    pub fn check_not_collateralized(&self) -> Result<()> {
+        require!(!self.collateralized, ErrorCode::PositionCollateralized);
         Ok(())
     }
 
@@ -93,12 +113,14 @@ impl PersonalPositionState {
         fee_growth_inside_0_x64_latest: u128,
         fee_growth_inside_1_x64_latest: u128,
         reward_growths_inside_latest: [u128; REWARD_NUM],
+        maker_rebate_growth_boundary_x64_latest: [u128; 2],
         recent_epoch: u64,
     ) -> Result<()> {
         self.update_fee_and_reward(
             fee_growth_inside_0_x64_latest,
             fee_growth_inside_1_x64_latest,
             reward_growths_inside_latest,
+            maker_rebate_growth_boundary_x64_latest,
             recent_epoch,
         )?;
         self.liquidity = self
@@ -114,12 +136,14 @@ impl PersonalPositionState {
         fee_growth_inside_0_x64_latest: u128,
         fee_growth_inside_1_x64_latest: u128,
         reward_growths_inside_latest: [u128; REWARD_NUM],
+        maker_rebate_growth_boundary_x64_latest: [u128; 2],
         recent_epoch: u64,
     ) -> Result<()> {
         self.update_fee_and_reward(
             fee_growth_inside_0_x64_latest,
             fee_growth_inside_1_x64_latest,
             reward_growths_inside_latest,
+            maker_rebate_growth_boundary_x64_latest,
             recent_epoch,
         )?;
         self.liquidity = self
@@ -128,11 +152,106 @@ impl PersonalPositionState {
             .ok_or(ErrorCode::CalculateOverflow)?;
         Ok(())
     }
+
+    /// Move `liquidity` of the position to `other`, a new position of the same range without liquidity,
+    /// with the same share of the fees and rewards owed. The position must be accrued up to now:
+    /// `other` takes its growth snapshots, so both accrue the same growths afterwards.
+    pub fn split_to(&mut self, other: &mut PersonalPositionState, liquidity: u128) -> Result<()> {
+        self.check_not_collateralized()?;
+        require_eq!(other.liquidity, 0, ErrorCode::InvalidLiquidity);
+        require_gt!(liquidity, 0, ErrorCode::InvalidLiquidity);
+        require_gt!(self.liquidity, liquidity, ErrorCode::InvalidLiquidity);
+        let liquidity_before = self.liquidity;
+        let share = |amount: u64| {
+            U256::from(amount)
+                .mul_div_floor(U256::from(liquidity), U256::from(liquidity_before))
+                .unwrap()
+                .as_u64()
+        };
+
+        other.fee_growth_inside_0_last_x64 = self.fee_growth_inside_0_last_x64;
+        other.fee_growth_inside_1_last_x64 = self.fee_growth_inside_1_last_x64;
+        other.maker_rebate_growth_boundary_last_x64 = self.maker_rebate_growth_boundary_last_x64;
+        other.recent_epoch = self.recent_epoch;
+        // the split liquidity can't be decreased earlier than in the original position
+        other.last_increase_slot = self.last_increase_slot;
+
+        let fees_owed_0 = share(self.token_fees_owed_0);
+        let fees_owed_1 = share(self.token_fees_owed_1);
+        self.token_fees_owed_0 -= fees_owed_0;
+        self.token_fees_owed_1 -= fees_owed_1;
+        other.token_fees_owed_0 = fees_owed_0;
+        other.token_fees_owed_1 = fees_owed_1;
+        for i in 0..REWARD_NUM {
+            let reward_amount_owed = share(self.reward_infos[i].reward_amount_owed);
+            self.reward_infos[i].reward_amount_owed -= reward_amount_owed;
+            other.reward_infos[i] = PositionRewardInfo {
+                growth_inside_last_x64: self.reward_infos[i].growth_inside_last_x64,
+                reward_amount_owed,
+            };
+        }
+
+        self.liquidity = liquidity_before - liquidity;
+        other.liquidity = liquidity;
+        Ok(())
+    }
+
+    /// Move all the liquidity and the fees and rewards owed of `other`, a position of the same range, to this position,
+    /// the reverse of `split_to`. The positions with liquidity must be accrued up to now.
+    pub fn merge_from(&mut self, other: &mut PersonalPositionState) -> Result<()> {
+        self.check_not_collateralized()?;
+        other.check_not_collateralized()?;
+        require!(
+            self.pool_id == other.pool_id
+                && self.tick_lower_index == other.tick_lower_index
+                && self.tick_upper_index == other.tick_upper_index,
+            ErrorCode::InvalidTickIndex
+        );
+        // the snapshots of a position without liquidity are stale, it takes the ones of `other`
+        if self.liquidity == 0 {
+            self.fee_growth_inside_0_last_x64 = other.fee_growth_inside_0_last_x64;
+            self.fee_growth_inside_1_last_x64 = other.fee_growth_inside_1_last_x64;
+            self.maker_rebate_growth_boundary_last_x64 = other.maker_rebate_growth_boundary_last_x64;
+            for i in 0..REWARD_NUM {
+                self.reward_infos[i].growth_inside_last_x64 =
+                    other.reward_infos[i].growth_inside_last_x64;
+            }
+            self.recent_epoch = other.recent_epoch;
+        }
+
+        self.liquidity = self
+            .liquidity
+            .checked_add(other.liquidity)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        self.token_fees_owed_0 = self
+            .token_fees_owed_0
+            .checked_add(other.token_fees_owed_0)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        self.token_fees_owed_1 = self
+            .token_fees_owed_1
+            .checked_add(other.token_fees_owed_1)
+            .ok_or(ErrorCode::CalculateOverflow)?;
+        for i in 0..REWARD_NUM {
+            self.reward_infos[i].reward_amount_owed = self.reward_infos[i]
+                .reward_amount_owed
+                .checked_add(other.reward_infos[i].reward_amount_owed)
+                .ok_or(ErrorCode::CalculateOverflow)?;
+            other.reward_infos[i].reward_amount_owed = 0;
+        }
+        self.last_increase_slot = self.last_increase_slot.max(other.last_increase_slot);
+
+        other.liquidity = 0;
+        other.token_fees_owed_0 = 0;
+        other.token_fees_owed_1 = 0;
+        Ok(())
+    }
+
     fn update_fee_and_reward(
         &mut self,
         fee_growth_inside_0_x64_latest: u128,
         fee_growth_inside_1_x64_latest: u128,
         reward_growths_inside_latest: [u128; REWARD_NUM],
+        maker_rebate_growth_boundary_x64_latest: [u128; 2],
         recent_epoch: u64,
     ) -> Result<()> {
         self.token_fees_owed_0 = calculate_latest_token_fees(
@@ -151,6 +270,21 @@ impl PersonalPositionState {
         self.fee_growth_inside_0_last_x64 = fee_growth_inside_0_x64_latest;
         self.fee_growth_inside_1_last_x64 = fee_growth_inside_1_x64_latest;
 
+        // the maker rebate is paid with the fees
+        self.token_fees_owed_0 = calculate_latest_token_fees(
+            self.token_fees_owed_0,
+            self.maker_rebate_growth_boundary_last_x64[0],
+            maker_rebate_growth_boundary_x64_latest[0],
+            self.liquidity,
+        );
+        self.token_fees_owed_1 = calculate_latest_token_fees(
+            self.token_fees_owed_1,
+            self.maker_rebate_growth_boundary_last_x64[1],
+            maker_rebate_growth_boundary_x64_latest[1],
+            self.liquidity,
+        );
+        self.maker_rebate_growth_boundary_last_x64 = maker_rebate_growth_boundary_x64_latest;
+
         // update rewards, must update before increase liquidity
         self.update_rewards(reward_growths_inside_latest, true, recent_epoch)?;
 
@@ -163,6 +297,7 @@ impl PersonalPositionState {
         add_delta: bool,
         recent_epoch: u64,
     ) -> Result<()> {
+        #[allow(clippy::needless_range_loop)] // the index is logged
         for i in 0..REWARD_NUM {
             let reward_growth_inside = reward_growths_inside[i];
             let curr_reward_info = self.reward_infos[i];
@@ -174,10 +309,8 @@ impl PersonalPositionState {
                 let reward_growth_delta =
                     reward_growth_inside.wrapping_sub(curr_reward_info.growth_inside_last_x64);
 
-                let amount_owed_delta = U256::from(reward_growth_delta)
-                    .mul_div_floor(U256::from(self.liquidity), U256::from(fixed_point_64::Q64))
-                    .unwrap()
-                    .to_underflow_u64();
+                let amount_owed_delta =
+                    PositionRewardInfo::amount_owed_delta(reward_growth_delta, self.liquidity);
 
                 // Overflows not allowed. Must collect rewards owed before overflow.
                 self.reward_infos[i].reward_amount_owed = curr_reward_info
@@ -185,8 +318,7 @@ impl PersonalPositionState {
                     .checked_add(amount_owed_delta)
                     .unwrap();
 
-                #[cfg(feature = "enable-log")]
-                msg!("update personal reward, index:{}, owed_before:{:?}, amount_owed_delta:{}, owed_after:{}, reward_growth_delta:{}, self.liquidity:{}", i, curr_reward_info.reward_amount_owed,amount_owed_delta, self.reward_infos[i].reward_amount_owed,reward_growth_delta,self.liquidity );
+                log_debug!(ix = "update_personal_rewards", pool = self.pool_id, "index:{}, owed_before:{:?}, amount_owed_delta:{}, owed_after:{}, reward_growth_delta:{}, self.liquidity:{}", i, curr_reward_info.reward_amount_owed,amount_owed_delta, self.reward_infos[i].reward_amount_owed,reward_growth_delta,self.liquidity );
             }
             self.reward_infos[i].growth_inside_last_x64 = reward_growth_inside;
         }
@@ -204,6 +336,15 @@ pub struct PositionRewardInfo {
 
 impl PositionRewardInfo {
     pub const LEN: usize = 16 + 8;
+
+    /// Get the reward owed to `liquidity` for a reward growth of `reward_growth_delta_x64` inside its range,
+    /// zero on overflow
+    pub fn amount_owed_delta(reward_growth_delta_x64: u128, liquidity: u128) -> u64 {
+        U256::from(reward_growth_delta_x64)
+            .mul_div_floor(U256::from(liquidity), U256::from(fixed_point_64::Q64))
+            .unwrap()
+            .to_underflow_u64()
+    }
 }
 
 /// Emitted when create a new position
@@ -239,6 +380,9 @@ pub struct CreatePersonalPositionEvent {
 
     /// The token transfer fee for deposit_amount_1
     pub deposit_amount_1_transfer_fee: u64,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted when liquidity is increased.
@@ -262,6 +406,9 @@ pub struct IncreaseLiquidityEvent {
 
     /// The token transfer fee for amount_1
     pub amount_1_transfer_fee: u64,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted when liquidity is decreased.
@@ -286,6 +433,8 @@ pub struct DecreaseLiquidityEvent {
     pub transfer_fee_0: u64,
     /// The amount of token_1 transfer fee
     pub transfer_fee_1: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted when liquidity decreased or increase.
@@ -310,6 +459,8 @@ pub struct LiquidityCalculateEvent {
     pub transfer_fee_0: u64,
     /// The amount of token_1 transfer fee without trade_fee_amount_0
     pub transfer_fee_1: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted when tokens are collected for a position
@@ -330,6 +481,69 @@ pub struct CollectPersonalFeeEvent {
 
     /// The amount of token_1 owed to the position that was collected
     pub amount_1: u64,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when the rewards of a position are claimed and swapped into a single token by `claim_rewards_as`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ClaimRewardsAsEvent {
+    pub position_nft_mint: Pubkey,
+    /// The token all the rewards were swapped into
+    pub token_mint: Pubkey,
+    /// The claimed amount of each reward, before the swaps
+    pub reward_amounts: [u64; REWARD_NUM],
+    /// The total `token_mint` amount received
+    pub amount_out: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when liquidity is moved between two positions of the same owner and range by `transfer_liquidity`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LiquidityTransferredEvent {
+    pub pool_state: Pubkey,
+    /// The position the liquidity is moved from
+    pub from_position_nft_mint: Pubkey,
+    /// The position the liquidity is moved to
+    pub to_position_nft_mint: Pubkey,
+    pub liquidity: u128,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when a position is split into a new position of the same range by `split_position`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PositionSplitEvent {
+    pub pool_state: Pubkey,
+    /// The position split
+    pub position_nft_mint: Pubkey,
+    /// The new position holding the split liquidity
+    pub new_position_nft_mint: Pubkey,
+    /// The owner of the new position NFT
+    pub new_position_nft_owner: Pubkey,
+    pub liquidity: u128,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when a position is merged into another position of the same range by `merge_positions`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PositionsMergedEvent {
+    pub pool_state: Pubkey,
+    /// The position kept
+    pub position_nft_mint: Pubkey,
+    /// The position merged and closed
+    pub merged_position_nft_mint: Pubkey,
+    /// The liquidity moved from the merged position
+    pub liquidity: u128,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted when Reward are updated for a pool
@@ -338,4 +552,125 @@ pub struct CollectPersonalFeeEvent {
 pub struct UpdateRewardInfosEvent {
     /// Reward info
     pub reward_growth_global_x64: [u128; REWARD_NUM],
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod personal_position_test {
+    use super::*;
+
+    #[test]
+    fn split_to_test() {
+        let mut position = PersonalPositionState {
+            liquidity: 3000,
+            fee_growth_inside_0_last_x64: 11,
+            fee_growth_inside_1_last_x64: 12,
+            token_fees_owed_0: 100,
+            token_fees_owed_1: 7,
+            last_increase_slot: 99,
+            ..Default::default()
+        };
+        position.reward_infos[0] = PositionRewardInfo {
+            growth_inside_last_x64: 13,
+            reward_amount_owed: 31,
+        };
+        let mut new_position = PersonalPositionState::default();
+
+        position.split_to(&mut new_position, 1000).unwrap();
+        assert_eq!(position.liquidity, 2000);
+        assert_eq!(new_position.liquidity, 1000);
+        // the owed amounts are shared pro-rata, rounded down for the new position, nothing is lost
+        assert_eq!(new_position.token_fees_owed_0, 33);
+        assert_eq!(position.token_fees_owed_0, 67);
+        assert_eq!(new_position.token_fees_owed_1, 2);
+        assert_eq!(position.token_fees_owed_1, 5);
+        assert_eq!(new_position.reward_infos[0].reward_amount_owed, 10);
+        assert_eq!(position.reward_infos[0].reward_amount_owed, 21);
+        assert_eq!(new_position.reward_infos[0].growth_inside_last_x64, 13);
+        assert_eq!(new_position.fee_growth_inside_0_last_x64, 11);
+        assert_eq!(new_position.fee_growth_inside_1_last_x64, 12);
+        assert_eq!(new_position.last_increase_slot, 99);
+
+        // all the liquidity is a transfer, not a split
+        let mut other_position = PersonalPositionState::default();
+        assert!(position.split_to(&mut other_position, 2000).is_err());
+        assert!(position.split_to(&mut other_position, 0).is_err());
+        // the new position must be empty
+        assert!(position.split_to(&mut new_position, 1).is_err());
+        // a collateralized position can't be split
+        position.collateralized = true;
+        assert!(position.split_to(&mut other_position, 1).is_err());
+        position.collateralized = false;
+
+        // merged back, nothing is lost
+        position.merge_from(&mut new_position).unwrap();
+        assert_eq!(position.liquidity, 3000);
+        assert_eq!(position.token_fees_owed_0, 100);
+        assert_eq!(position.token_fees_owed_1, 7);
+        assert_eq!(position.reward_infos[0].reward_amount_owed, 31);
+        assert_eq!(new_position.liquidity, 0);
+        assert_eq!(new_position.token_fees_owed_0, 0);
+        assert_eq!(new_position.reward_infos[0].reward_amount_owed, 0);
+    }
+
+    #[test]
+    fn merge_from_test() {
+        let mut position = PersonalPositionState {
+            tick_lower_index: -60,
+            tick_upper_index: 60,
+            fee_growth_inside_0_last_x64: 1,
+            last_increase_slot: 10,
+            ..Default::default()
+        };
+        let mut other = PersonalPositionState {
+            tick_lower_index: -60,
+            tick_upper_index: 60,
+            liquidity: 500,
+            fee_growth_inside_0_last_x64: 9,
+            token_fees_owed_1: 4,
+            last_increase_slot: 20,
+            ..Default::default()
+        };
+        other.reward_infos[1].growth_inside_last_x64 = 8;
+
+        // an empty position takes the snapshots of the merged one
+        position.merge_from(&mut other).unwrap();
+        assert_eq!(position.liquidity, 500);
+        assert_eq!(position.fee_growth_inside_0_last_x64, 9);
+        assert_eq!(position.reward_infos[1].growth_inside_last_x64, 8);
+        assert_eq!(position.token_fees_owed_1, 4);
+        assert_eq!(position.last_increase_slot, 20);
+
+        // another range
+        let mut other = PersonalPositionState {
+            tick_lower_index: -120,
+            tick_upper_index: 60,
+            ..Default::default()
+        };
+        assert!(position.merge_from(&mut other).is_err());
+
+        // neither position can be collateralized
+        let mut other = PersonalPositionState {
+            tick_lower_index: -60,
+            tick_upper_index: 60,
+            liquidity: 100,
+            collateralized: true,
+            ..Default::default()
+        };
+        assert!(position.merge_from(&mut other).is_err());
+        other.collateralized = false;
+        position.collateralized = true;
+        assert!(position.merge_from(&mut other).is_err());
+        assert_eq!(position.liquidity, 500);
+        assert_eq!(other.liquidity, 100);
+    }
+
+    #[test]
+    fn check_not_collateralized_test() {
+        let mut position = PersonalPositionState::default();
+        assert!(position.check_not_collateralized().is_ok());
+        position.collateralized = true;
+        assert!(position.check_not_collateralized().is_err());
+    }
 }
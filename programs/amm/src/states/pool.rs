@@ -1,7 +1,7 @@
 use crate::error::ErrorCode;
 use crate::libraries::{
     big_num::{U1024, U128, U256},
-    check_current_tick_array_is_initialized, fixed_point_64,
+    check_current_tick_array_is_initialized, fee_math, fixed_point_64,
     full_math::MulDiv,
     tick_array_bit_map, tick_math,
 };
@@ -11,10 +11,13 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::program_option::COption;
 use anchor_spl::token_interface::Mint;
 
-#[cfg(feature = "enable-log")]
+#[cfg(feature = "enable-debug-log")]
 use std::convert::identity;
 use std::ops::{BitAnd, BitOr, BitXor};
 
+/// The min dormancy for a pool to be marked inactive by `sunset_pool`
+pub const MIN_SUNSET_DORMANCY_SECONDS: u64 = 30 * 24 * 3600;
+
 /// Seed to derive account address and signature
 pub const POOL_SEED: &str = "pool";
 pub const POOL_VAULT_SEED: &str = "pool_vault";
@@ -22,6 +25,8 @@ pub const POOL_REWARD_VAULT_SEED: &str = "pool_reward_vault";
 pub const POOL_TICK_ARRAY_BITMAP_SEED: &str = "pool_tick_array_bitmap_extension";
 // Number of rewards Token
 pub const REWARD_NUM: usize = 3;
+// Number of recently crossed tick arrays remembered by the pool
+pub const HOT_TICK_ARRAY_NUM: usize = 8;
 
 #[cfg(feature = "paramset")]
 pub mod reward_period_limit {
@@ -141,6 +146,7 @@ pub struct PoolState {
     /// bit0, 1: use decay fee, 0: not use decay fee
     /// bit1, 1: use decay fee on sell for mint0, 0: not use decay fee on sell for mint0
     /// bit2, 1: use decay fee on sell for mint1, 0: not use decay fee on sell for mint1
+    /// bit3, 1: enforce `bootstrap_sqrt_price_floor_x64` while the decay fee is enabled
     pub decay_fee_flag: u8,
     /// The initial decay fee rate for the pool, in percentage.(1=1%)
     pub decay_fee_init_fee_rate: u8,
@@ -148,12 +154,133 @@ pub struct PoolState {
     pub decay_fee_decrease_rate: u8,
     /// The interval for decreasing the decay fee, in seconds.
     pub decay_fee_decrease_interval: u8,
-    // Unused bytes for future upgrades.
-    pub padding1_1: [u8; 4],
-    pub padding1: [u64; 23],
+    /// The share of the LP fee of a swap step crossing an initialized tick, paid to the positions bounded by that tick,
+    /// in hundredths of a bip (10^-6). 0 disables the maker rebate.
+    pub maker_rebate_rate: u32,
+    /// Sequence number of the last event emitted for this pool, starts from 0 when the pool is created,
+    /// so the first event is stamped 1. It wraps around to 0 after u64::MAX.
+    /// Pools created before this field existed also start from 0, since it takes over zeroed padding.
+    pub event_seq: u64,
+    /// The price floor of the sold mint during the decay window, see `initialize_bootstrap_price_floor`
+    pub bootstrap_sqrt_price_floor_x64: u128,
+    /// tick array flag
+    /// bit0, 1: create new tick arrays as fixed tick arrays, 0: as dynamic tick arrays
+    /// bit1, 1: `tick_array_count` counts the tick arrays since the pool creation, 0: pool created before the count
+    pub tick_array_flag: u8,
+    /// The min slots between a liquidity increase of a position and its next decrease or fee collection,
+    /// to keep single block JIT liquidity from sandwiching swaps. 0 to disable
+    pub min_position_age_slots: u16,
+    /// The TickState slots allocated up front when a dynamic tick array is created, copied from the amm config,
+    /// see `AmmConfig::default_prealloc_ticks`. 0 allocates only the accessed tick, same as 1
+    pub prealloc_tick_count: u8,
+    /// The number of tick array accounts of the pool, created by `get_or_create_tick_array` and closed by
+    /// `close_tick_array`, frozen ones included. Only exact if `counts_tick_arrays`
+    pub tick_array_count: u32,
+    /// Ring of the start indexes of the tick arrays recently crossed by swaps, see `record_hot_tick_array`
+    pub hot_tick_array_start_indexes: [i32; HOT_TICK_ARRAY_NUM],
+    /// The slot of `hot_tick_array_start_indexes` written next
+    pub hot_tick_array_head: u8,
+    /// The number of valid entries of `hot_tick_array_start_indexes`
+    pub hot_tick_array_len: u8,
+    pub padding1_3: [u8; 6],
+    /// The position width limits copied from the amm config, see `check_range_width`
+    pub min_range_ticks: u32,
+    pub max_range_ticks: u32,
+    /// The block timestamp of the last swap
+    pub last_swap_ts: u64,
+    /// The block timestamp of the last liquidity increase or decrease
+    pub last_liquidity_change_ts: u64,
+    /// The timestamp the pool was marked inactive by `sunset_pool`, 0 if the pool is active.
+    /// An inactive pool takes no new deposits, withdrawals, fee collection and swaps still work.
+    pub sunset_ts: u64,
+    /// The slot of the last swap, see `check_last_swap_slot`
+    pub last_swap_slot: u64,
+    /// The decay fee ends at this timestamp whatever its rate, 0 if it only ends when it decays to the trade fee.
+    /// Once passed, the decay fee flag is cleared by the next swap or by `clear_decay_fee`.
+    pub decay_fee_end_time: u64,
+    /// The cumulative of seconds per unit of in range liquidity as a Q64.64, accrued up to `seconds_per_liquidity_updated_at`
+    /// by `update_seconds_per_liquidity`. Only the difference between two values is meaningful
+    pub seconds_per_liquidity_cumulative_x64: u128,
+    /// The block timestamp `seconds_per_liquidity_cumulative_x64` is accrued up to
+    pub seconds_per_liquidity_updated_at: u64,
+    /// The amm config the pool address is derived from, set by `retier` when it moves the pool to another config.
+    /// Default for a pool never retiered, whose address is derived from `amm_config`
+    pub seed_amm_config: Pubkey,
+    pub padding1: [u64; 1],
     pub padding2: [u64; 32],
 }
 
+/// Offsets of the PoolState fields in the account data, including the 8 bytes discriminator.
+/// Used by `PoolStateView` and for the memcmp filters of `getProgramAccounts`.
+/// A field reusing padding must be added here, the layout test checks every entry against the struct.
+pub mod offsets {
+    use super::PoolState;
+    use core::mem::{offset_of, size_of};
+
+    macro_rules! pool_state_offsets {
+        ($($name:ident => $field:ident: $ty:ty),* $(,)?) => {
+            $(
+                pub const $name: usize = 8 + offset_of!(PoolState, $field);
+                // fails to compile if `$ty` is not the type of the field
+                const _: fn(&PoolState) -> $ty = |pool_state| pool_state.$field;
+            )*
+
+            /// The (name, offset, size) of every field above
+            pub const FIELDS: &[(&str, usize, usize)] = &[$((stringify!($name), $name, size_of::<$ty>())),*];
+
+            /// Assert the bytes at each offset of `data` are the field of `pool_state`
+            #[cfg(test)]
+            pub fn assert_layout(pool_state: &PoolState, data: &[u8]) {
+                $(
+                    assert_eq!(
+                        &data[$name..$name + size_of::<$ty>()],
+                        bytemuck::bytes_of(&{ pool_state.$field }),
+                        stringify!($name)
+                    );
+                )*
+            }
+        };
+    }
+
+    pool_state_offsets! {
+        BUMP => bump: [u8; 1],
+        AMM_CONFIG => amm_config: anchor_lang::prelude::Pubkey,
+        OWNER => owner: anchor_lang::prelude::Pubkey,
+        TOKEN_MINT_0 => token_mint_0: anchor_lang::prelude::Pubkey,
+        TOKEN_MINT_1 => token_mint_1: anchor_lang::prelude::Pubkey,
+        TOKEN_VAULT_0 => token_vault_0: anchor_lang::prelude::Pubkey,
+        TOKEN_VAULT_1 => token_vault_1: anchor_lang::prelude::Pubkey,
+        OBSERVATION_KEY => observation_key: anchor_lang::prelude::Pubkey,
+        MINT_DECIMALS_0 => mint_decimals_0: u8,
+        MINT_DECIMALS_1 => mint_decimals_1: u8,
+        TICK_SPACING => tick_spacing: u16,
+        LIQUIDITY => liquidity: u128,
+        SQRT_PRICE => sqrt_price_x64: u128,
+        TICK_CURRENT => tick_current: i32,
+        FEE_GROWTH_GLOBAL_0 => fee_growth_global_0_x64: u128,
+        FEE_GROWTH_GLOBAL_1 => fee_growth_global_1_x64: u128,
+        STATUS => status: u8,
+        OPEN_TIME => open_time: u64,
+        RECENT_EPOCH => recent_epoch: u64,
+        MAKER_REBATE_RATE => maker_rebate_rate: u32,
+        EVENT_SEQ => event_seq: u64,
+        TICK_ARRAY_FLAG => tick_array_flag: u8,
+        MIN_POSITION_AGE_SLOTS => min_position_age_slots: u16,
+        PREALLOC_TICK_COUNT => prealloc_tick_count: u8,
+        TICK_ARRAY_COUNT => tick_array_count: u32,
+        MIN_RANGE_TICKS => min_range_ticks: u32,
+        MAX_RANGE_TICKS => max_range_ticks: u32,
+        LAST_SWAP_TS => last_swap_ts: u64,
+        LAST_LIQUIDITY_CHANGE_TS => last_liquidity_change_ts: u64,
+        SUNSET_TS => sunset_ts: u64,
+        LAST_SWAP_SLOT => last_swap_slot: u64,
+        DECAY_FEE_END_TIME => decay_fee_end_time: u64,
+        SECONDS_PER_LIQUIDITY_CUMULATIVE => seconds_per_liquidity_cumulative_x64: u128,
+        SECONDS_PER_LIQUIDITY_UPDATED_AT => seconds_per_liquidity_updated_at: u64,
+        SEED_AMM_CONFIG => seed_amm_config: anchor_lang::prelude::Pubkey,
+    }
+}
+
 impl PoolState {
     pub const LEN: usize = 8
         + 1
@@ -179,10 +306,23 @@ impl PoolState {
         + 8 * 16
         + 512;
 
+    /// The length of the pool accounts created with the first layout
+    pub const LEN_V1: usize = Self::LEN;
+
+    /// The min length of a pool account, all the fields of `offsets` are within it.
+    /// The loaders accept longer accounts and ignore the trailing bytes, so that a later layout
+    /// can extend the pool with a realloc without bricking the pools created before.
+    pub const MIN_LEN: usize = Self::LEN_V1;
+
     pub fn seeds(&self) -> [&[u8]; 5] {
+        let seed_amm_config = if self.seed_amm_config == Pubkey::default() {
+            &self.amm_config
+        } else {
+            &self.seed_amm_config
+        };
         [
-            &POOL_SEED.as_bytes(),
-            self.amm_config.as_ref(),
+            POOL_SEED.as_bytes(),
+            seed_amm_config.as_ref(),
             self.token_mint_0.as_ref(),
             self.token_mint_1.as_ref(),
             self.bump.as_ref(),
@@ -243,8 +383,28 @@ impl PoolState {
         self.open_time = open_time;
         self.recent_epoch = get_recent_epoch()?;
         self.decay_fee_flag = 0; // default, don't use dynamic fee
-        self.padding1_1 = [0; 4];
-        self.padding1 = [0; 23];
+        self.maker_rebate_rate = 0;
+        self.event_seq = 0;
+        self.bootstrap_sqrt_price_floor_x64 = 0;
+        self.tick_array_flag = 1 << 1; // default, create dynamic tick arrays and count them
+        self.min_position_age_slots = 0;
+        self.prealloc_tick_count = amm_config.default_prealloc_ticks;
+        self.tick_array_count = 0;
+        self.hot_tick_array_start_indexes = [0; HOT_TICK_ARRAY_NUM];
+        self.hot_tick_array_head = 0;
+        self.hot_tick_array_len = 0;
+        self.padding1_3 = [0; 6];
+        self.min_range_ticks = amm_config.min_range_ticks;
+        self.max_range_ticks = amm_config.max_range_ticks;
+        self.last_swap_ts = 0;
+        self.last_liquidity_change_ts = 0;
+        self.sunset_ts = 0;
+        self.last_swap_slot = 0;
+        self.decay_fee_end_time = 0;
+        self.seconds_per_liquidity_cumulative_x64 = 0;
+        self.seconds_per_liquidity_updated_at = open_time;
+        self.seed_amm_config = Pubkey::default();
+        self.padding1 = [0; 1];
         self.padding2 = [0; 32];
         self.observation_key = observation_state_key;
 
@@ -283,6 +443,24 @@ impl PoolState {
         Ok(())
     }
 
+    /// End the decay fee `max_window` seconds after the open time at the latest
+    pub fn set_decay_fee_max_window(&mut self, max_window: u64) -> Result<()> {
+        require!(self.is_decay_fee_enabled(), ErrorCode::InvalidDecayFeeWindow);
+        require_gt!(max_window, 0, ErrorCode::InvalidDecayFeeWindow);
+        self.decay_fee_end_time = self
+            .open_time
+            .checked_add(max_window)
+            .ok_or(ErrorCode::InvalidDecayFeeWindow)?;
+        Ok(())
+    }
+
+    /// Whether the decay fee is enabled past its end time, it is cleared by `disable_decay_fee`
+    pub fn is_decay_fee_expired(&self, current_timestamp: u64) -> bool {
+        self.is_decay_fee_enabled()
+            && self.decay_fee_end_time != 0
+            && current_timestamp >= self.decay_fee_end_time
+    }
+
     /// disable decay fee config
     pub fn disable_decay_fee(&mut self) -> Result<()> {
         self.decay_fee_flag &= !(1 << 0);
@@ -302,6 +480,238 @@ impl PoolState {
         self.decay_fee_flag & (1 << 2) != 0
     }
 
+    /// Enforce a price floor on the sold mint while the decay fee is enabled, for liquidity bootstrapping launches.
+    /// The decay fee must be on sell for exactly one mint, the floor bounds the price of that mint:
+    /// a lower bound of sqrt_price_x64 if it is mint0, an upper bound if it is mint1.
+    pub fn initialize_bootstrap_price_floor(&mut self, sqrt_price_floor_x64: u128) -> Result<()> {
+        require!(
+            self.is_decay_fee_enabled()
+                && self.is_decay_fee_on_sell_mint0() != self.is_decay_fee_on_sell_mint1(),
+            ErrorCode::InvalidBootstrapPriceFloor
+        );
+        require!(
+            sqrt_price_floor_x64 > tick_math::MIN_SQRT_PRICE_X64
+                && sqrt_price_floor_x64 < tick_math::MAX_SQRT_PRICE_X64,
+            ErrorCode::InvalidBootstrapPriceFloor
+        );
+        // the initial price must not break the floor
+        if self.is_decay_fee_on_sell_mint0() {
+            require_gte!(
+                self.sqrt_price_x64,
+                sqrt_price_floor_x64,
+                ErrorCode::InvalidBootstrapPriceFloor
+            );
+        } else {
+            require_gte!(
+                sqrt_price_floor_x64,
+                self.sqrt_price_x64,
+                ErrorCode::InvalidBootstrapPriceFloor
+            );
+        }
+        self.decay_fee_flag |= 1 << 3;
+        self.bootstrap_sqrt_price_floor_x64 = sqrt_price_floor_x64;
+        Ok(())
+    }
+
+    /// Create new tick arrays of the pool as fixed tick arrays, for pools expecting dense tick usage
+    /// where the dynamic tick array indirection costs more compute than it saves in rent
+    pub fn set_use_fixed_tick_array(&mut self, use_fixed_tick_array: bool) {
+        if use_fixed_tick_array {
+            self.tick_array_flag |= 1 << 0;
+        } else {
+            self.tick_array_flag &= !(1 << 0);
+        }
+    }
+
+    pub fn use_fixed_tick_array(&self) -> bool {
+        self.tick_array_flag & (1 << 0) != 0
+    }
+
+    /// Whether `tick_array_count` is exact, the tick arrays of a pool created before the count are unknown
+    pub fn counts_tick_arrays(&self) -> bool {
+        self.tick_array_flag & (1 << 1) != 0
+    }
+
+    /// Count a tick array account created in the pool
+    pub fn add_tick_array(&mut self) {
+        self.tick_array_count = self.tick_array_count.saturating_add(1);
+    }
+
+    /// Uncount a tick array account closed in the pool, never below zero for a pool created before the count
+    pub fn remove_tick_array(&mut self) {
+        self.tick_array_count = self.tick_array_count.saturating_sub(1);
+    }
+
+    pub fn set_min_position_age_slots(&mut self, min_position_age_slots: u16) {
+        self.min_position_age_slots = min_position_age_slots;
+    }
+
+    /// Check a position increased at `last_increase_slot` is old enough to decrease liquidity or collect fees at `slot`.
+    /// Positions not increased since this field existed have a last increase slot of 0 and are always old enough.
+    pub fn check_position_age(&self, last_increase_slot: u64, slot: u64) -> Result<()> {
+        let min_position_age_slots = u64::from(self.min_position_age_slots);
+        require!(
+            min_position_age_slots == 0
+                || last_increase_slot == 0
+                || slot.saturating_sub(last_increase_slot) >= min_position_age_slots,
+            ErrorCode::PositionTooYoung
+        );
+        Ok(())
+    }
+
+    /// The timestamp of the last swap or liquidity change, or the open time if there was none.
+    /// Pools created before the activity timestamps existed count from the open time until their next activity.
+    pub fn last_activity_ts(&self) -> u64 {
+        self.open_time
+            .max(self.last_swap_ts)
+            .max(self.last_liquidity_change_ts)
+    }
+
+    /// Check the last swap of the pool happened within `max_slot_age` slots before `slot`
+    /// and not after `max_last_swap_slot`, the slot of the snapshot a quote was made off.
+    /// Pools not swapped since this field existed have a last swap slot of 0.
+    pub fn check_last_swap_slot(
+        &self,
+        slot: u64,
+        max_slot_age: u64,
+        max_last_swap_slot: u64,
+    ) -> Result<()> {
+        require_gte!(
+            self.last_swap_slot.saturating_add(max_slot_age),
+            slot,
+            ErrorCode::PoolUpdateTooOld
+        );
+        require_gte!(
+            max_last_swap_slot,
+            self.last_swap_slot,
+            ErrorCode::PoolUpdatedAfterQuote
+        );
+        Ok(())
+    }
+
+    pub fn is_inactive(&self) -> bool {
+        self.sunset_ts != 0
+    }
+
+    /// Mark the pool inactive if it has been dormant for `dormancy_seconds` at `timestamp`
+    pub fn sunset(&mut self, dormancy_seconds: u64, timestamp: u64) -> Result<()> {
+        require!(!self.is_inactive(), ErrorCode::PoolInactive);
+        require_gte!(
+            dormancy_seconds,
+            MIN_SUNSET_DORMANCY_SECONDS,
+            ErrorCode::PoolNotDormant
+        );
+        require_gte!(
+            timestamp,
+            self.last_activity_ts().saturating_add(dormancy_seconds),
+            ErrorCode::PoolNotDormant
+        );
+        self.sunset_ts = timestamp;
+        Ok(())
+    }
+
+    /// Copy the position width limits of the amm config, zero is no limit
+    pub fn set_range_limits(&mut self, amm_config: &AmmConfig) {
+        self.min_range_ticks = amm_config.min_range_ticks;
+        self.max_range_ticks = amm_config.max_range_ticks;
+    }
+
+    /// Copy the default count of TickState slots allocated by a new dynamic tick array from the amm config
+    pub fn set_prealloc_tick_count(&mut self, amm_config: &AmmConfig) {
+        self.prealloc_tick_count = amm_config.default_prealloc_ticks;
+    }
+
+    /// Check the width of a position is within the limits of the pool's fee tier
+    pub fn check_range_width(&self, tick_lower_index: i32, tick_upper_index: i32) -> Result<()> {
+        let width = i64::from(tick_upper_index) - i64::from(tick_lower_index);
+        let min_range_ticks = self.min_range_ticks;
+        let max_range_ticks = self.max_range_ticks;
+        require!(
+            min_range_ticks == 0 || width >= i64::from(min_range_ticks),
+            ErrorCode::RangeTooNarrow
+        );
+        require!(
+            max_range_ticks == 0 || width <= i64::from(max_range_ticks),
+            ErrorCode::RangeTooWide
+        );
+        Ok(())
+    }
+
+    /// Remember a tick array crossed by a swap, so that clients can find the tick arrays of small swaps
+    /// without scanning the bitmap. A tick array already in the ring is not added again.
+    pub fn record_hot_tick_array(&mut self, tick_array_start_index: i32) {
+        let len = usize::from(self.hot_tick_array_len);
+        let mut indexes = self.hot_tick_array_start_indexes;
+        if indexes[..len].contains(&tick_array_start_index) {
+            return;
+        }
+        let head = usize::from(self.hot_tick_array_head);
+        indexes[head] = tick_array_start_index;
+        self.hot_tick_array_start_indexes = indexes;
+        self.hot_tick_array_head = ((head + 1) % HOT_TICK_ARRAY_NUM) as u8;
+        if len < HOT_TICK_ARRAY_NUM {
+            self.hot_tick_array_len += 1;
+        }
+    }
+
+    /// The start indexes of the recently crossed tick arrays, the most recent first
+    pub fn hot_tick_array_start_indexes(&self) -> Vec<i32> {
+        let indexes = self.hot_tick_array_start_indexes;
+        let head = usize::from(self.hot_tick_array_head);
+        (1..=usize::from(self.hot_tick_array_len))
+            .map(|i| indexes[(head + HOT_TICK_ARRAY_NUM - i) % HOT_TICK_ARRAY_NUM])
+            .collect()
+    }
+
+    /// Move a pool without any tick array account to `new_amm_config` and its tick spacing, the pool keeps its
+    /// address. The only state derived from the old tick spacing is the tick array bitmap, which is empty,
+    /// and the recently crossed tick arrays.
+    /// A pool counting its tick arrays must have closed them all. A pool created before the count can't prove it,
+    /// its tick arrays left at the old tick spacing have no initialized tick, as its bitmaps are empty, and the
+    /// uninitialized TickStates are stamped with their tick again when loaded, see `get_tick_state_mut`.
+    pub fn retier(&mut self, new_amm_config: Pubkey, new_tick_spacing: u16) -> Result<()> {
+        require_gt!(new_tick_spacing, 0);
+        require_neq!(new_tick_spacing, self.tick_spacing);
+        require_eq!(self.liquidity, 0, ErrorCode::PoolNotEmpty);
+        if self.counts_tick_arrays() {
+            require_eq!(self.tick_array_count, 0, ErrorCode::PoolNotEmpty);
+        }
+        let tick_array_bitmap = { self.tick_array_bitmap };
+        require!(
+            tick_array_bitmap.iter().all(|word| *word == 0),
+            ErrorCode::PoolNotEmpty
+        );
+        if self.seed_amm_config == Pubkey::default() {
+            self.seed_amm_config = self.amm_config;
+        }
+        self.amm_config = new_amm_config;
+        self.tick_spacing = new_tick_spacing;
+        self.tick_array_bitmap = [0; 16];
+        self.hot_tick_array_start_indexes = [0; HOT_TICK_ARRAY_NUM];
+        self.hot_tick_array_head = 0;
+        self.hot_tick_array_len = 0;
+        Ok(())
+    }
+
+    /// The price floor only applies in the decay window
+    pub fn is_bootstrap_price_floor_enabled(&self) -> bool {
+        self.is_decay_fee_enabled() && self.decay_fee_flag & (1 << 3) != 0
+    }
+
+    /// Check the price a swap ends at doesn't break the bootstrap price floor of the sold mint
+    pub fn check_bootstrap_price_floor(&self, zero_for_one: bool, sqrt_price_x64: u128) -> Result<()> {
+        if !self.is_bootstrap_price_floor_enabled() {
+            return Ok(());
+        }
+        let floor = self.bootstrap_sqrt_price_floor_x64;
+        if zero_for_one && self.is_decay_fee_on_sell_mint0() {
+            require_gte!(sqrt_price_x64, floor, ErrorCode::BootstrapPriceFloorBreached);
+        } else if !zero_for_one && self.is_decay_fee_on_sell_mint1() {
+            require_gte!(floor, sqrt_price_x64, ErrorCode::BootstrapPriceFloorBreached);
+        }
+        Ok(())
+    }
+
     /// Get the decay fee rate based on the current time, in hunderedths of a bip (10^-6).
     pub fn get_decay_fee_rate(&self, current_timestamp: u64) -> u32 {
         if !self.is_decay_fee_enabled() {
@@ -313,38 +723,17 @@ impl PoolState {
             return 0u32;
         }
 
-        let interval_count =
-            (current_timestamp - self.open_time) / self.decay_fee_decrease_interval as u64;
-
-        let decay_fee_decrease_rate = self.decay_fee_decrease_rate as u64 * 10_000;
-
-        // 10^6
-        let hundredths_of_a_bip = FEE_RATE_DENOMINATOR_VALUE as u64;
-        let mut rate = hundredths_of_a_bip;
-        // use `fast-power` to calculate (1-x)^c
-        // x = decay_fee_decrease_rate / 10^6
-        // c = interval_count
-        {
-            let mut exp = interval_count;
-            let mut base = hundredths_of_a_bip
-                .checked_sub(decay_fee_decrease_rate)
-                .unwrap();
-
-            while exp > 0 {
-                if exp % 2 == 1 {
-                    rate = rate.mul_div_ceil(base, hundredths_of_a_bip).unwrap();
-                }
-                base = base.mul_div_ceil(base, hundredths_of_a_bip).unwrap();
-                exp /= 2;
-            }
+        if self.decay_fee_end_time != 0 && current_timestamp >= self.decay_fee_end_time {
+            return 0u32;
         }
 
-        // because decay_fee_init_fee_rate is in percentage, we need to divide it by 100
-        rate = rate
-            .mul_div_ceil(self.decay_fee_init_fee_rate as u64, 100u64)
-            .unwrap();
-
-        rate as u32
+        let interval_count =
+            (current_timestamp - self.open_time) / self.decay_fee_decrease_interval as u64;
+        fee_math::decay_fee_rate(
+            self.decay_fee_init_fee_rate,
+            self.decay_fee_decrease_rate,
+            interval_count,
+        )
     }
 
     pub fn initialize_reward(
@@ -432,8 +821,9 @@ impl PoolState {
         self.reward_infos[lowest_index].token_mint = *token_mint;
         self.reward_infos[lowest_index].token_vault = *token_vault;
         self.reward_infos[lowest_index].authority = *authority;
-        #[cfg(feature = "enable-log")]
-        msg!(
+        log_info!(
+            ix = "initialize_reward",
+            pool = self.key(),
             "reward_index:{}, reward_infos:{:?}",
             lowest_index,
             self.reward_infos[lowest_index],
@@ -442,14 +832,38 @@ impl PoolState {
         Ok(())
     }
 
+    /// Accrue the seconds since the last update per unit of the in range liquidity, or of a liquidity of 1 without
+    /// liquidity in range. Must be called before the in range liquidity changes, returns the updated cumulative.
+    /// A pool created before the accrual has never been updated, its first update starts the accrual from now.
+    pub fn update_seconds_per_liquidity(&mut self, current_timestamp: u64) -> u128 {
+        if self.seconds_per_liquidity_updated_at == 0 {
+            self.seconds_per_liquidity_updated_at = current_timestamp;
+        } else if current_timestamp > self.seconds_per_liquidity_updated_at {
+            let delta_seconds = current_timestamp - self.seconds_per_liquidity_updated_at;
+            self.seconds_per_liquidity_cumulative_x64 = self
+                .seconds_per_liquidity_cumulative_x64
+                .wrapping_add(
+                    (u128::from(delta_seconds) << fixed_point_64::RESOLUTION)
+                        / self.liquidity.max(1),
+                );
+            self.seconds_per_liquidity_updated_at = current_timestamp;
+        }
+        self.seconds_per_liquidity_cumulative_x64
+    }
+
     // Calculates the next global reward growth variables based on the given timestamp.
     // The provided timestamp must be greater than or equal to the last updated timestamp.
     pub fn update_reward_infos(&mut self, curr_timestamp: u64) -> Result<[RewardInfo; REWARD_NUM]> {
-        #[cfg(feature = "enable-log")]
-        msg!("current block timestamp:{}", curr_timestamp);
+        log_debug!(
+            ix = "update_reward_infos",
+            pool = self.key(),
+            "current block timestamp:{}",
+            curr_timestamp
+        );
 
         let mut next_reward_infos = self.reward_infos;
 
+        #[allow(clippy::needless_range_loop)] // the index is logged
         for i in 0..REWARD_NUM {
             let reward_info = &mut next_reward_infos[i];
             if !reward_info.initialized() {
@@ -466,16 +880,15 @@ impl PoolState {
                     .checked_sub(reward_info.last_update_time)
                     .unwrap();
 
-                let reward_growth_delta = U256::from(time_delta)
-                    .mul_div_floor(
-                        U256::from(reward_info.emissions_per_second_x64),
-                        U256::from(self.liquidity),
-                    )
-                    .unwrap();
+                let reward_growth_delta = RewardInfo::growth_delta_x64(
+                    time_delta,
+                    reward_info.emissions_per_second_x64,
+                    self.liquidity,
+                );
 
                 reward_info.reward_growth_global_x64 = reward_info
                     .reward_growth_global_x64
-                    .checked_add(reward_growth_delta.as_u128())
+                    .checked_add(reward_growth_delta)
                     .unwrap();
 
                 reward_info.reward_total_emissioned = reward_info
@@ -490,8 +903,9 @@ impl PoolState {
                             .as_u64(),
                     )
                     .unwrap();
-                #[cfg(feature = "enable-log")]
-                msg!(
+                log_debug!(
+                    ix = "update_reward_infos",
+                    pool = self.key(),
                     "reward_index:{},latest_update_timestamp:{},reward_info.reward_last_update_time:{},time_delta:{},reward_emission_per_second_x64:{},reward_growth_delta:{},reward_info.reward_growth_global_x64:{}, reward_info.reward_claim:{}",
                     i,
                     latest_update_timestamp,
@@ -514,8 +928,8 @@ impl PoolState {
             }
         }
         self.reward_infos = next_reward_infos;
-        #[cfg(feature = "enable-log")]
-        msg!("update pool reward info, reward_0_total_emissioned:{}, reward_1_total_emissioned:{}, reward_2_total_emissioned:{}, pool.liquidity:{}",
+        log_debug!(ix = "update_reward_infos", pool = self.key(),
+        "reward_0_total_emissioned:{}, reward_1_total_emissioned:{}, reward_2_total_emissioned:{}, pool.liquidity:{}",
         identity(self.reward_infos[0].reward_total_emissioned),identity(self.reward_infos[1].reward_total_emissioned),identity(self.reward_infos[2].reward_total_emissioned), identity(self.liquidity));
         self.recent_epoch = get_recent_epoch()?;
         Ok(next_reward_infos)
@@ -531,6 +945,19 @@ impl PoolState {
         Ok(())
     }
 
+    /// The token_0 and token_1 amounts the vaults owe besides the liquidity:
+    /// the unclaimed LP fees, the protocol fees and the fund fees
+    pub fn fee_liabilities(&self) -> (u64, u64) {
+        (
+            (self.total_fees_token_0 - self.total_fees_claimed_token_0)
+                .saturating_add(self.protocol_fees_token_0)
+                .saturating_add(self.fund_fees_token_0),
+            (self.total_fees_token_1 - self.total_fees_claimed_token_1)
+                .saturating_add(self.protocol_fees_token_1)
+                .saturating_add(self.fund_fees_token_1),
+        )
+    }
+
     pub fn add_reward_clamed(&mut self, index: usize, amount: u64) -> Result<()> {
         assert!(index < REWARD_NUM);
         self.reward_infos[index].reward_claimed = self.reward_infos[index]
@@ -555,7 +982,7 @@ impl PoolState {
         let tick_array_offset_in_bitmap = self.get_tick_array_offset(tick_array_start_index)?;
 
         let tick_array_bitmap = U1024(self.tick_array_bitmap);
-        let mask = U1024::one() << tick_array_offset_in_bitmap.try_into().unwrap();
+        let mask = U1024::one() << tick_array_offset_in_bitmap;
         self.tick_array_bitmap = tick_array_bitmap.bitxor(mask).0;
         Ok(())
     }
@@ -568,7 +995,7 @@ impl PoolState {
         if self.is_overflow_default_tickarray_bitmap(vec![tick_array_start_index]) {
             require_keys_eq!(
                 tickarray_bitmap_extension.unwrap().key(),
-                TickArrayBitmapExtension::key(self.key())
+                TickArrayBitmapExtension::key(self.key(), &crate::id())
             );
             AccountLoader::<TickArrayBitmapExtension>::try_from(
                 tickarray_bitmap_extension.unwrap(),
@@ -580,6 +1007,31 @@ impl PoolState {
         }
     }
 
+    /// Whether the bit of the tick array starting at `tick_array_start_index` is set in the bitmap of the pool,
+    /// or in the bitmap extension beyond the default bitmap
+    pub fn is_tick_array_initialized<'c: 'info, 'info>(
+        &self,
+        tickarray_bitmap_extension: Option<&'c AccountInfo<'info>>,
+        tick_array_start_index: i32,
+    ) -> Result<bool> {
+        if self.is_overflow_default_tickarray_bitmap(vec![tick_array_start_index]) {
+            let tickarray_bitmap_extension =
+                tickarray_bitmap_extension.ok_or(ErrorCode::AccountLack)?;
+            require_keys_eq!(
+                tickarray_bitmap_extension.key(),
+                TickArrayBitmapExtension::key(self.key(), &crate::id())
+            );
+            let (is_initialized, _) =
+                AccountLoader::<TickArrayBitmapExtension>::try_from(tickarray_bitmap_extension)?
+                    .load()?
+                    .check_tick_array_is_initialized(tick_array_start_index, self.tick_spacing)?;
+            Ok(is_initialized)
+        } else {
+            let tick_array_offset_in_bitmap = self.get_tick_array_offset(tick_array_start_index)?;
+            Ok(U1024(self.tick_array_bitmap).bit(tick_array_offset_in_bitmap))
+        }
+    }
+
     pub fn get_first_initialized_tick_array(
         &self,
         tickarray_bitmap_extension: &Option<TickArrayBitmapExtension>,
@@ -597,7 +1049,7 @@ impl PoolState {
                 check_current_tick_array_is_initialized(
                     U1024(self.tick_array_bitmap),
                     self.tick_current,
-                    self.tick_spacing.into(),
+                    self.tick_spacing,
                 )?
             };
         if is_initialized {
@@ -612,7 +1064,7 @@ impl PoolState {
             next_start_index.is_some(),
             ErrorCode::InsufficientLiquidityForDirection
         );
-        return Ok((false, next_start_index.unwrap()));
+        Ok((false, next_start_index.unwrap()))
     }
 
     pub fn next_initialized_tick_array_start_index(
@@ -653,31 +1105,57 @@ impl PoolState {
             }
             last_tick_array_start_index = start_index;
 
-            if last_tick_array_start_index < tick_math::MIN_TICK
-                || last_tick_array_start_index > tick_math::MAX_TICK
+            if !(tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&last_tick_array_start_index)
             {
                 return Ok(None);
             }
         }
     }
 
+    /// Bump the event sequence number, the returned value must be stamped into the next emitted event
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq = self.event_seq.wrapping_add(1);
+        self.event_seq
+    }
+
+    pub fn set_maker_rebate_rate(&mut self, maker_rebate_rate: u32) -> Result<()> {
+        require_gte!(FEE_RATE_DENOMINATOR_VALUE, maker_rebate_rate);
+        self.maker_rebate_rate = maker_rebate_rate;
+        Ok(())
+    }
+
+    /// The maker rebate taken out of the LP fee of a swap step that crosses an initialized tick
+    pub fn get_maker_rebate(&self, step_fee_amount: u64) -> u64 {
+        U128::from(step_fee_amount)
+            .checked_mul(self.maker_rebate_rate.into())
+            .unwrap()
+            .checked_div(FEE_RATE_DENOMINATOR_VALUE.into())
+            .unwrap()
+            .as_u64()
+    }
+
     pub fn set_status(&mut self, status: u8) {
         self.status = status
     }
 
+    /// Disable the actions of the bits of `set_mask` and enable those of `clear_mask`, keeping the other bits
+    pub fn update_status_bits(&mut self, set_mask: u8, clear_mask: u8) {
+        self.status = (self.status | set_mask) & !clear_mask;
+    }
+
     pub fn set_status_by_bit(&mut self, bit: PoolStatusBitIndex, flag: PoolStatusBitFlag) {
-        let s = u8::from(1) << (bit as u8);
+        let s = 1 << (bit as u8);
         if flag == PoolStatusBitFlag::Disable {
             self.status = self.status.bitor(s);
         } else {
-            let m = u8::from(255).bitxor(s);
+            let m = 255.bitxor(s);
             self.status = self.status.bitand(m);
         }
     }
 
     /// Get status by bit, if it is `noraml` status, return true
     pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
-        let status = u8::from(1) << (bit as u8);
+        let status = 1 << (bit as u8);
         self.status.bitand(status) == 0
     }
 
@@ -707,7 +1185,7 @@ impl PoolState {
             max_tick_boundary =
                 TickUtils::get_array_start_index(tick_math::MAX_TICK, self.tick_spacing);
             // find the next tick array start index
-            max_tick_boundary = max_tick_boundary + TickUtils::tick_count(self.tick_spacing);
+            max_tick_boundary += TickUtils::tick_count(self.tick_spacing);
         }
         if min_tick_boundary < tick_math::MIN_TICK {
             min_tick_boundary =
@@ -770,6 +1248,19 @@ impl RewardInfo {
         }
     }
 
+    /// Get the reward growth per unit of liquidity, as a Q64.64, of emitting `emissions_per_second_x64`
+    /// for `time_delta` seconds to `liquidity`, which must not be zero
+    pub fn growth_delta_x64(
+        time_delta: u64,
+        emissions_per_second_x64: u128,
+        liquidity: u128,
+    ) -> u128 {
+        U256::from(time_delta)
+            .mul_div_floor(U256::from(emissions_per_second_x64), U256::from(liquidity))
+            .unwrap()
+            .as_u128()
+    }
+
     /// Returns true if this reward is initialized.
     /// Once initialized, a reward cannot transition back to uninitialized.
     pub fn initialized(&self) -> bool {
@@ -812,6 +1303,36 @@ pub struct PoolCreatedEvent {
     pub token_vault_0: Pubkey,
     /// Vault of token_1
     pub token_vault_1: Pubkey,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when a pool is moved to another amm config and its tick spacing by `retier_pool`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolRetierEvent {
+    pub pool_state: Pubkey,
+    pub old_amm_config: Pubkey,
+    pub new_amm_config: Pubkey,
+    pub old_tick_spacing: u16,
+    pub new_tick_spacing: u16,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when the status bits of a pool are changed by `update_pool_status_bits`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolStatusBitsUpdatedEvent {
+    pub pool_state: Pubkey,
+    pub authority: Pubkey,
+    pub set_mask: u8,
+    pub clear_mask: u8,
+    pub status_before: u8,
+    pub status_after: u8,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted when the collected protocol fees are withdrawn by the factory owner
@@ -832,6 +1353,9 @@ pub struct CollectProtocolFeeEvent {
 
     /// The amount of token_0 protocol fees that is withdrawn
     pub amount_1: u64,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted by when a swap is performed for a pool
@@ -875,6 +1399,36 @@ pub struct SwapEvent {
 
     /// The log base 1.0001 of price of the pool after the swap
     pub tick: i32,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted by a swap moving the price across a range without liquidity,
+/// the price jumps to the next initialized tick or the price limit without trading.
+/// If the swap crosses several gaps separated by liquid ranges, one event is emitted per gap
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LiquidityGapEvent {
+    /// The pool for swap
+    pub pool_state: Pubkey,
+
+    pub zero_for_one: bool,
+
+    /// The current tick of the pool before the gap
+    pub tick_start: i32,
+
+    /// The current tick of the pool after the gap, the tick below the next initialized tick for zero_for_one
+    pub tick_end: i32,
+
+    /// The sqrt(price) before the gap, as a Q64.64
+    pub sqrt_price_start_x64: u128,
+
+    /// The sqrt(price) after the gap, as a Q64.64
+    pub sqrt_price_end_x64: u128,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 /// Emitted pool liquidity change when increase and decrease liquidity
@@ -898,6 +1452,9 @@ pub struct LiquidityChangeEvent {
 
     /// The liquidity of the pool after liquidity change
     pub liquidity_after: u128,
+
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
 }
 
 // /// Emitted when price move in a swap step
@@ -930,6 +1487,18 @@ pub struct LiquidityChangeEvent {
 //     pub zero_for_one: bool,
 // }
 
+/// Emitted when a dormant pool is marked inactive
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolSunsetEvent {
+    pub pool_state: Pubkey,
+    /// The timestamp of the last swap or liquidity change of the pool
+    pub last_activity_ts: u64,
+    pub sunset_ts: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
 #[cfg(test)]
 pub mod pool_test {
     use super::*;
@@ -954,7 +1523,7 @@ pub mod pool_test {
         new_pool.fee_growth_global_1_x64 = rand::random::<u128>();
         new_pool.bump = [Pubkey::find_program_address(
             &[
-                &POOL_SEED.as_bytes(),
+                POOL_SEED.as_bytes(),
                 new_pool.amm_config.as_ref(),
                 new_pool.token_mint_0.as_ref(),
                 new_pool.token_mint_1.as_ref(),
@@ -969,49 +1538,64 @@ pub mod pool_test {
 
         use super::*;
 
+        #[test]
+        fn is_tick_array_initialized_test() {
+            let mut pool_state = PoolState {
+                tick_spacing: 10,
+                ..Default::default()
+            };
+            pool_state.flip_tick_array_bit(None, -600).unwrap();
+            assert!(pool_state.is_tick_array_initialized(None, -600).unwrap());
+            assert!(!pool_state.is_tick_array_initialized(None, 0).unwrap());
+            pool_state.flip_tick_array_bit(None, -600).unwrap();
+            assert!(!pool_state.is_tick_array_initialized(None, -600).unwrap());
+            // beyond the default bitmap without the extension
+            assert!(pool_state.is_tick_array_initialized(None, 307200).is_err());
+        }
+
         #[test]
         fn get_arrary_start_index_negative() {
-            let mut pool_state = PoolState::default();
-            pool_state.tick_spacing = 10;
+            let mut pool_state = PoolState {
+                tick_spacing: 10,
+                ..Default::default()
+            };
             pool_state.flip_tick_array_bit(None, -600).unwrap();
-            assert!(U1024(pool_state.tick_array_bitmap).bit(511) == true);
+            assert!(U1024(pool_state.tick_array_bitmap).bit(511));
 
             pool_state.flip_tick_array_bit(None, -1200).unwrap();
-            assert!(U1024(pool_state.tick_array_bitmap).bit(510) == true);
+            assert!(U1024(pool_state.tick_array_bitmap).bit(510));
 
             pool_state.flip_tick_array_bit(None, -1800).unwrap();
-            assert!(U1024(pool_state.tick_array_bitmap).bit(509) == true);
+            assert!(U1024(pool_state.tick_array_bitmap).bit(509));
 
             pool_state.flip_tick_array_bit(None, -38400).unwrap();
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(-38400).unwrap())
-                    == true
             );
             pool_state.flip_tick_array_bit(None, -39000).unwrap();
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(-39000).unwrap())
-                    == true
             );
             pool_state.flip_tick_array_bit(None, -307200).unwrap();
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(-307200).unwrap())
-                    == true
             );
         }
 
         #[test]
         fn get_arrary_start_index_positive() {
-            let mut pool_state = PoolState::default();
-            pool_state.tick_spacing = 10;
+            let mut pool_state = PoolState {
+                tick_spacing: 10,
+                ..Default::default()
+            };
             pool_state.flip_tick_array_bit(None, 0).unwrap();
             assert!(pool_state.get_tick_array_offset(0).unwrap() == 512);
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(0).unwrap())
-                    == true
             );
 
             pool_state.flip_tick_array_bit(None, 600).unwrap();
@@ -1019,21 +1603,18 @@ pub mod pool_test {
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(600).unwrap())
-                    == true
             );
 
             pool_state.flip_tick_array_bit(None, 1200).unwrap();
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(1200).unwrap())
-                    == true
             );
 
             pool_state.flip_tick_array_bit(None, 38400).unwrap();
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(38400).unwrap())
-                    == true
             );
 
             pool_state.flip_tick_array_bit(None, 306600).unwrap();
@@ -1041,30 +1622,31 @@ pub mod pool_test {
             assert!(
                 U1024(pool_state.tick_array_bitmap)
                     .bit(pool_state.get_tick_array_offset(306600).unwrap())
-                    == true
             );
         }
 
         #[test]
         fn default_tick_array_start_index_range_test() {
-            let mut pool_state = PoolState::default();
-            pool_state.tick_spacing = 60;
+            let mut pool_state = PoolState {
+                tick_spacing: 60,
+                ..Default::default()
+            };
             // -443580 is the min tick can use to open a position when tick_spacing is 60 due to MIN_TICK is -443636
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-443580]) == false);
+            assert!(!pool_state.is_overflow_default_tickarray_bitmap(vec![-443580]));
             // 443580 is the min tick can use to open a position when tick_spacing is 60 due to MAX_TICK is 443636
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![443580]) == false);
+            assert!(!pool_state.is_overflow_default_tickarray_bitmap(vec![443580]));
 
             pool_state.tick_spacing = 10;
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-307200]) == false);
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-307201]) == true);
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![307200]) == true);
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![307199]) == false);
+            assert!(!pool_state.is_overflow_default_tickarray_bitmap(vec![-307200]));
+            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-307201]));
+            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![307200]));
+            assert!(!pool_state.is_overflow_default_tickarray_bitmap(vec![307199]));
 
             pool_state.tick_spacing = 1;
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-30720]) == false);
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-30721]) == true);
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![30720]) == true);
-            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![30719]) == false);
+            assert!(!pool_state.is_overflow_default_tickarray_bitmap(vec![-30720]));
+            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![-30721]));
+            assert!(pool_state.is_overflow_default_tickarray_bitmap(vec![30720]));
+            assert!(!pool_state.is_overflow_default_tickarray_bitmap(vec![30719]));
         }
     }
 
@@ -1075,56 +1657,584 @@ pub mod pool_test {
         fn get_set_status_by_bit() {
             let mut pool_state = PoolState::default();
             pool_state.set_status(17); // 00010001
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
-                false
+            assert!(
+                !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
             );
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity),
-                false
+            assert!(
+                !pool_state.get_status_by_bit(PoolStatusBitIndex::OpenPositionOrIncreaseLiquidity)
             );
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
-                true
+            assert!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
             );
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee),
-                true
+            assert!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee)
             );
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward),
-                true
+            assert!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::CollectReward)
             );
 
             // disable -> disable, nothing to change
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Disable);
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::Swap),
-                false
+            assert!(
+                !pool_state.get_status_by_bit(PoolStatusBitIndex::Swap)
             );
 
             // disable -> enable
             pool_state.set_status_by_bit(PoolStatusBitIndex::Swap, PoolStatusBitFlag::Enable);
-            assert_eq!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap), true);
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
 
             // enable -> enable, nothing to change
             pool_state.set_status_by_bit(
                 PoolStatusBitIndex::DecreaseLiquidity,
                 PoolStatusBitFlag::Enable,
             );
-            assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
-                true
+            assert!(
+                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
             );
             // enable -> disable
             pool_state.set_status_by_bit(
                 PoolStatusBitIndex::DecreaseLiquidity,
                 PoolStatusBitFlag::Disable,
             );
+            assert!(
+                !pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity)
+            );
+        }
+    }
+
+    mod update_status_bits_test {
+        use super::*;
+
+        #[test]
+        fn update_status_bits_test() {
+            let swap = 1u8 << (PoolStatusBitIndex::Swap as u8);
+            let collect_fee = 1u8 << (PoolStatusBitIndex::CollectFee as u8);
+            let mut pool_state = PoolState::default();
+            pool_state.update_status_bits(swap | collect_fee, 0);
+            assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+            assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::CollectFee));
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+
+            pool_state.update_status_bits(0, swap);
+            assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+            assert_eq!({ pool_state.status }, collect_fee);
+
+            // clearing a frozen pool
+            pool_state.set_status(255);
+            pool_state.update_status_bits(0, u8::MAX);
+            assert_eq!({ pool_state.status }, 0);
+        }
+    }
+
+    mod event_seq_test {
+        use super::*;
+
+        #[test]
+        fn next_event_seq_test() {
+            let mut pool_state = PoolState::default();
+            assert_eq!({ pool_state.event_seq }, 0);
+            // the first event of a new pool is stamped 1
+            assert_eq!(pool_state.next_event_seq(), 1);
+            assert_eq!(pool_state.next_event_seq(), 2);
+            assert_eq!({ pool_state.event_seq }, 2);
+
+            // wrap around to 0 after u64::MAX
+            pool_state.event_seq = u64::MAX - 1;
+            assert_eq!(pool_state.next_event_seq(), u64::MAX);
+            assert_eq!(pool_state.next_event_seq(), 0);
+            assert_eq!(pool_state.next_event_seq(), 1);
+        }
+    }
+
+    mod tick_array_flag_test {
+        use super::*;
+
+        #[test]
+        fn use_fixed_tick_array_test() {
+            let mut pool_state = PoolState::default();
+            assert!(!pool_state.use_fixed_tick_array());
+            pool_state.set_use_fixed_tick_array(true);
+            assert!(pool_state.use_fixed_tick_array());
+            assert_eq!({ pool_state.tick_array_flag }, 1);
+            pool_state.set_use_fixed_tick_array(false);
+            assert!(!pool_state.use_fixed_tick_array());
+        }
+
+        #[test]
+        fn tick_array_count_test() {
+            let mut pool_state = PoolState::default();
+            assert!(!pool_state.counts_tick_arrays());
+            pool_state.tick_array_flag = 1 << 1;
+            pool_state.set_use_fixed_tick_array(true);
+            assert!(pool_state.counts_tick_arrays());
+            pool_state.add_tick_array();
+            pool_state.add_tick_array();
+            pool_state.remove_tick_array();
+            assert_eq!({ pool_state.tick_array_count }, 1);
+            pool_state.remove_tick_array();
+            // a tick array of a pool created before the count
+            pool_state.remove_tick_array();
+            assert_eq!({ pool_state.tick_array_count }, 0);
+        }
+    }
+
+    mod position_age_test {
+        use super::*;
+
+        #[test]
+        fn check_position_age_test() {
+            let mut pool_state = PoolState::default();
+            // disabled
+            assert!(pool_state.check_position_age(100, 100).is_ok());
+
+            pool_state.set_min_position_age_slots(2);
+            assert_eq!(
+                pool_state.check_position_age(100, 100).err(),
+                Some(ErrorCode::PositionTooYoung.into())
+            );
+            assert!(pool_state.check_position_age(100, 101).is_err());
+            assert!(pool_state.check_position_age(100, 102).is_ok());
+            // positions never increased since the field was added
+            assert!(pool_state.check_position_age(0, 1).is_ok());
+            assert!(pool_state.check_position_age(u64::MAX, u64::MAX).is_err());
+        }
+    }
+
+    mod sunset_test {
+        use super::*;
+
+        #[test]
+        fn sunset_test() {
+            let mut pool_state = PoolState {
+                open_time: 1000,
+                ..Default::default()
+            };
+            assert_eq!(pool_state.last_activity_ts(), 1000);
+            pool_state.last_swap_ts = 5000;
+            pool_state.last_liquidity_change_ts = 3000;
+            assert_eq!(pool_state.last_activity_ts(), 5000);
+
+            // the dormancy is too short
+            assert_eq!(
+                pool_state.sunset(3600, u64::MAX).err(),
+                Some(ErrorCode::PoolNotDormant.into())
+            );
+            // not dormant long enough
+            assert!(pool_state
+                .sunset(MIN_SUNSET_DORMANCY_SECONDS, 5000 + MIN_SUNSET_DORMANCY_SECONDS - 1)
+                .is_err());
+            assert!(!pool_state.is_inactive());
+
+            pool_state
+                .sunset(MIN_SUNSET_DORMANCY_SECONDS, 5000 + MIN_SUNSET_DORMANCY_SECONDS)
+                .unwrap();
+            assert!(pool_state.is_inactive());
+            assert_eq!(
+                { pool_state.sunset_ts },
+                5000 + MIN_SUNSET_DORMANCY_SECONDS
+            );
+            assert_eq!(
+                pool_state.sunset(MIN_SUNSET_DORMANCY_SECONDS, u64::MAX).err(),
+                Some(ErrorCode::PoolInactive.into())
+            );
+        }
+    }
+
+    mod offsets_test {
+        use super::*;
+        use anchor_lang::Discriminator;
+
+        #[test]
+        fn offsets_layout_test() {
+            // every byte of the known struct is distinct from its neighbours
+            let mut pool_state = PoolState::default();
+            for (i, byte) in bytemuck::bytes_of_mut(&mut pool_state).iter_mut().enumerate() {
+                *byte = (i % 251) as u8 + 1;
+            }
+            let mut data = PoolState::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+            offsets::assert_layout(&pool_state, &data);
+
+            // in order, not overlapping and inside the account
+            assert_eq!(offsets::BUMP, 8);
+            for pair in offsets::FIELDS.windows(2) {
+                assert!(pair[0].1 + pair[0].2 <= pair[1].1, "{} overlaps {}", pair[0].0, pair[1].0);
+            }
+            let (_, offset, size) = offsets::FIELDS[offsets::FIELDS.len() - 1];
+            assert!(offset + size <= PoolState::MIN_LEN);
+        }
+    }
+
+    mod core_layout_test {
+        use super::*;
+        use anchor_lang::Discriminator;
+        use byreal_clmm_core::states::{self as core_states, AccountLayout};
+
+        #[test]
+        fn core_layout_test() {
+            let mut pool_state = PoolState::default();
+            for (i, byte) in bytemuck::bytes_of_mut(&mut pool_state).iter_mut().enumerate() {
+                *byte = (i % 251) as u8 + 1;
+            }
+            let mut data = PoolState::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+            let layout = core_states::PoolState::try_from_account_data(&data).unwrap();
+            assert_core_layout!(
+                pool_state,
+                layout,
+                bump,
+                amm_config,
+                owner,
+                token_mint_0,
+                token_mint_1,
+                token_vault_0,
+                token_vault_1,
+                observation_key,
+                mint_decimals_0,
+                mint_decimals_1,
+                tick_spacing,
+                liquidity,
+                sqrt_price_x64,
+                tick_current,
+                padding3,
+                padding4,
+                fee_growth_global_0_x64,
+                fee_growth_global_1_x64,
+                protocol_fees_token_0,
+                protocol_fees_token_1,
+                swap_in_amount_token_0,
+                swap_out_amount_token_1,
+                swap_in_amount_token_1,
+                swap_out_amount_token_0,
+                status,
+                padding,
+                tick_array_bitmap,
+                total_fees_token_0,
+                total_fees_claimed_token_0,
+                total_fees_token_1,
+                total_fees_claimed_token_1,
+                fund_fees_token_0,
+                fund_fees_token_1,
+                open_time,
+                recent_epoch,
+                decay_fee_flag,
+                decay_fee_init_fee_rate,
+                decay_fee_decrease_rate,
+                decay_fee_decrease_interval,
+                maker_rebate_rate,
+                event_seq,
+                bootstrap_sqrt_price_floor_x64,
+                tick_array_flag,
+                min_position_age_slots,
+                prealloc_tick_count,
+                tick_array_count,
+                hot_tick_array_start_indexes,
+                hot_tick_array_head,
+                hot_tick_array_len,
+                padding1_3,
+                min_range_ticks,
+                max_range_ticks,
+                last_swap_ts,
+                last_liquidity_change_ts,
+                sunset_ts,
+                last_swap_slot,
+                decay_fee_end_time,
+                seconds_per_liquidity_cumulative_x64,
+                seconds_per_liquidity_updated_at,
+                seed_amm_config,
+                padding1,
+                padding2,
+            );
+            // the program RewardInfo is not Pod, the reward infos are compared one by one
+            let reward_info = { pool_state.reward_infos }[1];
+            let layout_reward_info = { layout.reward_infos }[1];
+            assert_core_layout!(
+                reward_info,
+                layout_reward_info,
+                reward_state,
+                open_time,
+                end_time,
+                last_update_time,
+                emissions_per_second_x64,
+                reward_total_emissioned,
+                reward_claimed,
+                token_mint,
+                token_vault,
+                authority,
+                reward_growth_global_x64,
+            );
+            assert_eq!(core_states::PoolState::LEN, PoolState::LEN);
+            assert_eq!(core_states::REWARD_NUM, REWARD_NUM);
+            assert_eq!(core_states::HOT_TICK_ARRAY_NUM, HOT_TICK_ARRAY_NUM);
+            assert_eq!(
+                core_states::POOL_STATUS_SWAP_BIT,
+                PoolStatusBitIndex::Swap as u8
+            );
+
+            data[0] ^= 1;
+            assert_eq!(
+                core_states::PoolState::try_from_account_data(&data).err(),
+                Some(ErrorCode::InvalidAccount.into())
+            );
+        }
+    }
+
+    mod account_len_test {
+        use super::*;
+        use crate::libraries::test_account_utils::mock_account_info;
+        use crate::quote::deserialize_zero_copy;
+        use crate::states::PoolStateView;
+        use crate::util::AccountLoad;
+        use anchor_lang::Discriminator;
+        use std::convert::identity;
+
+        fn pool_fixture_data() -> (PoolState, Vec<u8>) {
+            let pool_state = PoolState {
+                amm_config: Pubkey::new_unique(),
+                tick_spacing: 10,
+                liquidity: 1_000_000,
+                tick_current: -123,
+                recent_epoch: 7,
+                ..Default::default()
+            };
+            let mut data = PoolState::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+            (pool_state, data)
+        }
+
+        #[test]
+        fn len_test() {
+            assert_eq!(PoolState::LEN, 8 + core::mem::size_of::<PoolState>());
+            const _: () = assert!(PoolState::MIN_LEN <= PoolState::LEN);
+            let (_, data) = pool_fixture_data();
+            assert_eq!(data.len(), PoolState::LEN_V1);
+        }
+
+        #[test]
+        fn extended_account_loads_test() {
+            let (pool_state, mut data) = pool_fixture_data();
+            // a later layout appends fields to the account
+            data.extend_from_slice(&[0xab; 64]);
+
+            let decoded = deserialize_zero_copy::<PoolState>(&data).unwrap();
+            assert_eq!(identity(decoded.amm_config), pool_state.amm_config);
+            assert_eq!(identity(decoded.liquidity), 1_000_000);
+            let view = PoolStateView::try_from_data(&data[..]).unwrap();
+            assert_eq!(view.tick_current(), -123);
+
+            let key = Pubkey::new_unique();
+            let owner = crate::id();
+            let (account_info, _lamports, account_data) =
+                mock_account_info(&key, &owner, false, true, 0, data.len());
+            account_data.borrow_mut().copy_from_slice(&data);
+            let loader = AccountLoad::<PoolState>::try_from(&account_info).unwrap();
+            let loaded = loader.load().unwrap();
+            assert_eq!(identity(loaded.amm_config), pool_state.amm_config);
+            assert_eq!(identity(loaded.recent_epoch), 7);
+            assert_eq!(identity(loaded.tick_spacing), 10);
+        }
+
+        #[test]
+        fn truncated_account_fails_test() {
+            let (_, data) = pool_fixture_data();
+            let data = &data[..PoolState::MIN_LEN - 1];
+            assert!(deserialize_zero_copy::<PoolState>(data).is_err());
+            assert!(PoolStateView::try_from_data(data).is_err());
+
+            let key = Pubkey::new_unique();
+            let owner = crate::id();
+            let (account_info, _lamports, account_data) =
+                mock_account_info(&key, &owner, false, true, 0, data.len());
+            account_data.borrow_mut().copy_from_slice(data);
+            assert!(AccountLoad::<PoolState>::try_from(&account_info).is_err());
+        }
+    }
+
+    mod last_swap_slot_test {
+        use super::*;
+
+        #[test]
+        fn check_last_swap_slot_test() {
+            let pool_state = PoolState {
+                last_swap_slot: 1000,
+                ..Default::default()
+            };
+
+            pool_state.check_last_swap_slot(1010, 10, 1000).unwrap();
+            pool_state.check_last_swap_slot(1000, 0, u64::MAX).unwrap();
+            assert_eq!(
+                pool_state.check_last_swap_slot(1011, 10, u64::MAX).err(),
+                Some(ErrorCode::PoolUpdateTooOld.into())
+            );
+            // swapped after the quoted snapshot
+            assert_eq!(
+                pool_state.check_last_swap_slot(1010, 10, 999).err(),
+                Some(ErrorCode::PoolUpdatedAfterQuote.into())
+            );
+            // no overflow for an unbounded age
+            pool_state.check_last_swap_slot(u64::MAX, u64::MAX, u64::MAX).unwrap();
+        }
+    }
+
+    mod range_limits_test {
+        use super::*;
+
+        #[test]
+        fn check_range_width_test() {
+            let mut pool_state = PoolState::default();
+            // no limit
+            assert!(pool_state.check_range_width(-60, 0).is_ok());
+            assert!(pool_state
+                .check_range_width(tick_math::MIN_TICK, tick_math::MAX_TICK)
+                .is_ok());
+
+            pool_state.set_range_limits(&AmmConfig {
+                min_range_ticks: 600,
+                max_range_ticks: 60000,
+                ..Default::default()
+            });
+            assert_eq!(
+                pool_state.check_range_width(-300, 240).err(),
+                Some(ErrorCode::RangeTooNarrow.into())
+            );
+            assert!(pool_state.check_range_width(-300, 300).is_ok());
+            assert!(pool_state.check_range_width(-30000, 30000).is_ok());
+            assert_eq!(
+                pool_state
+                    .check_range_width(tick_math::MIN_TICK, tick_math::MAX_TICK)
+                    .err(),
+                Some(ErrorCode::RangeTooWide.into())
+            );
+
+            // only the max limit
+            pool_state.set_range_limits(&AmmConfig {
+                max_range_ticks: 60000,
+                ..Default::default()
+            });
+            assert!(pool_state.check_range_width(-60, 0).is_ok());
+        }
+
+        #[test]
+        fn check_range_limits_test() {
+            assert!(AmmConfig::check_range_limits(0, 0).is_ok());
+            assert!(AmmConfig::check_range_limits(600, 0).is_ok());
+            assert!(AmmConfig::check_range_limits(0, 600).is_ok());
+            assert!(AmmConfig::check_range_limits(600, 600).is_ok());
+            assert!(AmmConfig::check_range_limits(601, 600).is_err());
+        }
+    }
+
+    mod hot_tick_array_test {
+        use super::*;
+
+        #[test]
+        fn record_hot_tick_array_test() {
+            let mut pool_state = PoolState::default();
+            assert!(pool_state.hot_tick_array_start_indexes().is_empty());
+
+            pool_state.record_hot_tick_array(0);
+            pool_state.record_hot_tick_array(-3600);
+            // already in the ring
+            pool_state.record_hot_tick_array(0);
+            assert_eq!(pool_state.hot_tick_array_start_indexes(), vec![-3600, 0]);
+
+            // the oldest ones are overwritten
+            for i in 1..=HOT_TICK_ARRAY_NUM as i32 {
+                pool_state.record_hot_tick_array(i * 3600);
+            }
             assert_eq!(
-                pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity),
-                false
+                pool_state.hot_tick_array_start_indexes(),
+                (1..=HOT_TICK_ARRAY_NUM as i32)
+                    .rev()
+                    .map(|i| i * 3600)
+                    .collect::<Vec<i32>>()
+            );
+        }
+    }
+
+    mod retier_test {
+        use super::*;
+        use std::convert::identity;
+
+        #[test]
+        fn retier_test() {
+            let pool_state = build_pool(-32395, 60, 3651942632306380802, 0);
+            pool_state.borrow_mut().tick_array_flag = 1 << 1;
+            let pool_key = pool_state.borrow().key();
+            let old_amm_config = pool_state.borrow().amm_config;
+            let new_amm_config = Pubkey::new_unique();
+
+            // a tick array account without initialized tick, not in the bitmap
+            pool_state.borrow_mut().add_tick_array();
+            assert!(
+                pool_state.borrow_mut().retier(new_amm_config, 10).err()
+                    == Some(ErrorCode::PoolNotEmpty.into())
+            );
+            pool_state.borrow_mut().remove_tick_array();
+
+            pool_state.borrow_mut().record_hot_tick_array(-32400);
+            pool_state.borrow_mut().retier(new_amm_config, 10).unwrap();
+            assert_eq!(identity(pool_state.borrow().tick_spacing), 10);
+            assert!(pool_state.borrow().hot_tick_array_start_indexes().is_empty());
+            // the pool is bound to the new config and keeps its address
+            assert_eq!(identity(pool_state.borrow().amm_config), new_amm_config);
+            assert_eq!(identity(pool_state.borrow().seed_amm_config), old_amm_config);
+            assert_eq!(pool_state.borrow().key(), pool_key);
+
+            // same tick spacing
+            assert!(pool_state.borrow_mut().retier(new_amm_config, 10).is_err());
+            assert!(pool_state.borrow_mut().retier(new_amm_config, 0).is_err());
+
+            // an initialized tick array
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, -33000)
+                .unwrap();
+            assert!(
+                pool_state.borrow_mut().retier(old_amm_config, 60).err()
+                    == Some(ErrorCode::PoolNotEmpty.into())
+            );
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, -33000)
+                .unwrap();
+
+            // active liquidity
+            pool_state.borrow_mut().liquidity = 1;
+            assert!(
+                pool_state.borrow_mut().retier(old_amm_config, 60).err()
+                    == Some(ErrorCode::PoolNotEmpty.into())
+            );
+            assert_eq!(identity(pool_state.borrow().tick_spacing), 10);
+            pool_state.borrow_mut().liquidity = 0;
+
+            // moving it back keeps the config its address is derived from
+            pool_state.borrow_mut().retier(old_amm_config, 60).unwrap();
+            assert_eq!(identity(pool_state.borrow().seed_amm_config), old_amm_config);
+            assert_eq!(pool_state.borrow().key(), pool_key);
+        }
+
+        #[test]
+        fn retier_legacy_pool_test() {
+            // a pool created before the tick array count only needs an empty bitmap
+            let pool_state = build_pool(-32395, 60, 3651942632306380802, 0);
+            let new_amm_config = Pubkey::new_unique();
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, -36000)
+                .unwrap();
+            assert!(
+                pool_state.borrow_mut().retier(new_amm_config, 10).err()
+                    == Some(ErrorCode::PoolNotEmpty.into())
             );
+            pool_state
+                .borrow_mut()
+                .flip_tick_array_bit(None, -36000)
+                .unwrap();
+
+            pool_state.borrow_mut().retier(new_amm_config, 10).unwrap();
+            assert_eq!(identity(pool_state.borrow().tick_spacing), 10);
+            assert_eq!(identity(pool_state.borrow().amm_config), new_amm_config);
         }
     }
 
@@ -1217,7 +2327,7 @@ pub mod pool_test {
             let pool_state_refcel = build_pool(
                 tick_current,
                 tick_spacing.try_into().unwrap(),
-                tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+                tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
                 0,
             );
 
@@ -1258,27 +2368,27 @@ pub mod pool_test {
             let (is_first_initilzied, start_index) = pool_state
                 .get_first_initialized_tick_array(&tick_array_bitmap_extension, true)
                 .unwrap();
-            assert!(is_first_initilzied == false);
+            assert!(!is_first_initilzied);
             assert!(start_index == -tick_spacing * TICK_ARRAY_SIZE * 513);
 
             let (is_first_initilzied, start_index) = pool_state
                 .get_first_initialized_tick_array(&tick_array_bitmap_extension, false)
                 .unwrap();
-            assert!(is_first_initilzied == false);
+            assert!(!is_first_initilzied);
             assert!(start_index == tick_spacing * TICK_ARRAY_SIZE * 511);
 
             pool_state.tick_current = tick_spacing * TICK_ARRAY_SIZE * 511;
             let (is_first_initilzied, start_index) = pool_state
                 .get_first_initialized_tick_array(&tick_array_bitmap_extension, true)
                 .unwrap();
-            assert!(is_first_initilzied == true);
+            assert!(is_first_initilzied);
             assert!(start_index == tick_spacing * TICK_ARRAY_SIZE * 511);
 
             pool_state.tick_current = tick_spacing * TICK_ARRAY_SIZE * 512;
             let (is_first_initilzied, start_index) = pool_state
                 .get_first_initialized_tick_array(&tick_array_bitmap_extension, true)
                 .unwrap();
-            assert!(is_first_initilzied == true);
+            assert!(is_first_initilzied);
             assert!(start_index == tick_spacing * TICK_ARRAY_SIZE * 512);
         }
 
@@ -1293,7 +2403,7 @@ pub mod pool_test {
                 let pool_state_refcel = build_pool(
                     tick_current,
                     tick_spacing.try_into().unwrap(),
-                    tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+                    tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
                     0,
                 );
 
@@ -1381,7 +2491,7 @@ pub mod pool_test {
                         true,
                     )
                     .unwrap();
-                assert!(start_index.is_none() == true);
+                assert!(start_index.is_none());
             }
 
             #[test]
@@ -1392,7 +2502,7 @@ pub mod pool_test {
                 let pool_state_refcel = build_pool(
                     tick_current,
                     tick_spacing.try_into().unwrap(),
-                    tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+                    tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
                     0,
                 );
 
@@ -1469,7 +2579,7 @@ pub mod pool_test {
                         false,
                     )
                     .unwrap();
-                assert!(start_index.is_none() == true);
+                assert!(start_index.is_none());
             }
 
             #[test]
@@ -1480,7 +2590,7 @@ pub mod pool_test {
                 let pool_state_refcel = build_pool(
                     tick_current,
                     tick_spacing.try_into().unwrap(),
-                    tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+                    tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
                     0,
                 );
 
@@ -1538,7 +2648,7 @@ pub mod pool_test {
                 let pool_state_refcel = build_pool(
                     tick_current,
                     tick_spacing.try_into().unwrap(),
-                    tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+                    tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
                     0,
                 );
 
@@ -1589,9 +2699,11 @@ pub mod pool_test {
 
             #[test]
             fn no_initialized_tick_array() {
-                let mut pool_state = PoolState::default();
-                pool_state.tick_spacing = 1;
-                pool_state.tick_current = 0;
+                let mut pool_state = PoolState {
+                    tick_spacing: 1,
+                    tick_current: 0,
+                    ..Default::default()
+                };
 
                 let param: &mut BuildExtensionAccountInfo =
                     &mut BuildExtensionAccountInfo::default();
@@ -1641,7 +2753,7 @@ pub mod pool_test {
                 let pool_state_refcel = build_pool(
                     tick_current,
                     tick_spacing.try_into().unwrap(),
-                    tick_math::get_sqrt_price_at_tick(tick_current).unwrap(),
+                    tick_math::get_sqrt_price_at_tick_raw(tick_current).unwrap(),
                     0,
                 );
 
@@ -1778,10 +2890,9 @@ pub mod pool_test {
             let mut tick_array_bitmap: [u64; 16] = [0u64; 16];
             let mut tick_array_bitmap_data = [0u8; 8 * 16];
             let mut offset = 0;
-            for i in 0..16 {
-                tick_array_bitmap[i] = u64::MAX << i;
-                tick_array_bitmap_data[offset..offset + 8]
-                    .copy_from_slice(&tick_array_bitmap[i].to_le_bytes());
+            for (i, word) in tick_array_bitmap.iter_mut().enumerate() {
+                *word = u64::MAX << i;
+                tick_array_bitmap_data[offset..offset + 8].copy_from_slice(&word.to_le_bytes());
                 offset += 8;
             }
             let total_fees_token_0: u64 = 0x1234567809abcdef;
@@ -1797,14 +2908,35 @@ pub mod pool_test {
             let decay_fee_init_fee_rate: u8 = 0x0d;
             let decay_fee_decrease_rate: u8 = 0x0e;
             let decay_fee_decrease_interval: u8 = 0x0f;
-            let padding1_1: [u8; 4] = [0; 4];
-
-            let mut padding1: [u64; 23] = [0u64; 23];
-            let mut padding1_data = [0u8; 8 * 23];
+            let maker_rebate_rate: u32 = 0x10203040;
+            let event_seq: u64 = 0x0123456789abcdef;
+            let bootstrap_sqrt_price_floor_x64: u128 = 0x1032547698badcfe1032547698badcfe;
+            let tick_array_flag: u8 = 0x01;
+            let min_position_age_slots: u16 = 0x1213;
+            let prealloc_tick_count: u8 = 0x11;
+            let tick_array_count: u32 = 0x12131415;
+            let hot_tick_array_start_indexes: [i32; HOT_TICK_ARRAY_NUM] =
+                [-0x123456, -0x1234, -60, 0, 60, 0x1234, 0x123456, 0x7fffffff];
+            let hot_tick_array_head: u8 = 0x03;
+            let hot_tick_array_len: u8 = 0x08;
+            let padding1_3: [u8; 6] = [0x21, 0x22, 0x23, 0x24, 0x25, 0x26];
+            let min_range_ticks: u32 = 0x01020304;
+            let max_range_ticks: u32 = 0x05060708;
+            let last_swap_ts: u64 = 0x1112131415161718;
+            let last_liquidity_change_ts: u64 = 0x2122232425262728;
+            let sunset_ts: u64 = 0x3132333435363738;
+            let last_swap_slot: u64 = 0x4142434445464748;
+            let decay_fee_end_time: u64 = 0x5152535455565758;
+            let seconds_per_liquidity_cumulative_x64: u128 = 0x6162636465666768696a6b6c6d6e6f70;
+            let seconds_per_liquidity_updated_at: u64 = 0x7172737475767778;
+            let seed_amm_config = Pubkey::new_unique();
+
+            let mut padding1: [u64; 1] = [0u64; 1];
+            let mut padding1_data = [0u8; 8];
             let mut offset = 0;
-            for i in 0..23 {
-                padding1[i] = u64::MAX - i as u64;
-                padding1_data[offset..offset + 8].copy_from_slice(&padding1[i].to_le_bytes());
+            for (i, padding) in padding1.iter_mut().enumerate() {
+                *padding = u64::MAX - i as u64;
+                padding1_data[offset..offset + 8].copy_from_slice(&padding.to_le_bytes());
                 offset += 8;
             }
             let mut padding2: [u64; 32] = [0u64; 32];
@@ -1818,7 +2950,7 @@ pub mod pool_test {
             // serialize original data
             let mut pool_data = [0u8; PoolState::LEN];
             let mut offset = 0;
-            pool_data[offset..offset + 8].copy_from_slice(&PoolState::DISCRIMINATOR);
+            pool_data[offset..offset + 8].copy_from_slice(PoolState::DISCRIMINATOR);
             offset += 8;
             pool_data[offset..offset + 1].copy_from_slice(&bump.to_le_bytes());
             offset += 1;
@@ -1905,11 +3037,56 @@ pub mod pool_test {
             pool_data[offset..offset + 1]
                 .copy_from_slice(&decay_fee_decrease_interval.to_le_bytes());
             offset += 1;
-            pool_data[offset..offset + 4].copy_from_slice(&padding1_1);
+            pool_data[offset..offset + 4].copy_from_slice(&maker_rebate_rate.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 8].copy_from_slice(&event_seq.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 16]
+                .copy_from_slice(&bootstrap_sqrt_price_floor_x64.to_le_bytes());
+            offset += 16;
+            pool_data[offset..offset + 1].copy_from_slice(&tick_array_flag.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 2].copy_from_slice(&min_position_age_slots.to_le_bytes());
+            offset += 2;
+            pool_data[offset..offset + 1].copy_from_slice(&prealloc_tick_count.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 4].copy_from_slice(&tick_array_count.to_le_bytes());
             offset += 4;
+            for start_index in hot_tick_array_start_indexes {
+                pool_data[offset..offset + 4].copy_from_slice(&start_index.to_le_bytes());
+                offset += 4;
+            }
+            pool_data[offset..offset + 1].copy_from_slice(&hot_tick_array_head.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 1].copy_from_slice(&hot_tick_array_len.to_le_bytes());
+            offset += 1;
+            pool_data[offset..offset + 6].copy_from_slice(&padding1_3);
+            offset += 6;
+            pool_data[offset..offset + 4].copy_from_slice(&min_range_ticks.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 4].copy_from_slice(&max_range_ticks.to_le_bytes());
+            offset += 4;
+            pool_data[offset..offset + 8].copy_from_slice(&last_swap_ts.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&last_liquidity_change_ts.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&sunset_ts.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&last_swap_slot.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 8].copy_from_slice(&decay_fee_end_time.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_cumulative_x64.to_le_bytes());
+            offset += 16;
+            pool_data[offset..offset + 8]
+                .copy_from_slice(&seconds_per_liquidity_updated_at.to_le_bytes());
+            offset += 8;
+            pool_data[offset..offset + 32].copy_from_slice(&seed_amm_config.to_bytes());
+            offset += 32;
 
-            pool_data[offset..offset + 8 * 23].copy_from_slice(&padding1_data);
-            offset += 8 * 23;
+            pool_data[offset..offset + 8].copy_from_slice(&padding1_data);
+            offset += 8;
             pool_data[offset..offset + 8 * 32].copy_from_slice(&padding2_data);
             offset += 8 * 32;
 
@@ -2022,6 +3199,61 @@ pub mod pool_test {
             assert_eq!(unpack_open_time, pool_open_time);
             let unpack_recent_epoch = unpack_data.recent_epoch;
             assert_eq!(unpack_recent_epoch, recent_epoch);
+            let unpack_maker_rebate_rate = unpack_data.maker_rebate_rate;
+            assert_eq!(unpack_maker_rebate_rate, maker_rebate_rate);
+            let unpack_event_seq = unpack_data.event_seq;
+            assert_eq!(unpack_event_seq, event_seq);
+            let unpack_bootstrap_sqrt_price_floor_x64 = unpack_data.bootstrap_sqrt_price_floor_x64;
+            assert_eq!(
+                unpack_bootstrap_sqrt_price_floor_x64,
+                bootstrap_sqrt_price_floor_x64
+            );
+            let unpack_tick_array_flag = unpack_data.tick_array_flag;
+            assert_eq!(unpack_tick_array_flag, tick_array_flag);
+            let unpack_min_position_age_slots = unpack_data.min_position_age_slots;
+            assert_eq!(unpack_min_position_age_slots, min_position_age_slots);
+            let unpack_prealloc_tick_count = unpack_data.prealloc_tick_count;
+            assert_eq!(unpack_prealloc_tick_count, prealloc_tick_count);
+            let unpack_tick_array_count = unpack_data.tick_array_count;
+            assert_eq!(unpack_tick_array_count, tick_array_count);
+            let unpack_hot_tick_array_start_indexes = unpack_data.hot_tick_array_start_indexes;
+            assert_eq!(
+                unpack_hot_tick_array_start_indexes,
+                hot_tick_array_start_indexes
+            );
+            let unpack_hot_tick_array_head = unpack_data.hot_tick_array_head;
+            assert_eq!(unpack_hot_tick_array_head, hot_tick_array_head);
+            let unpack_hot_tick_array_len = unpack_data.hot_tick_array_len;
+            assert_eq!(unpack_hot_tick_array_len, hot_tick_array_len);
+            let unpack_padding1_3 = unpack_data.padding1_3;
+            assert_eq!(unpack_padding1_3, padding1_3);
+            let unpack_min_range_ticks = unpack_data.min_range_ticks;
+            assert_eq!(unpack_min_range_ticks, min_range_ticks);
+            let unpack_max_range_ticks = unpack_data.max_range_ticks;
+            assert_eq!(unpack_max_range_ticks, max_range_ticks);
+            let unpack_last_swap_ts = unpack_data.last_swap_ts;
+            assert_eq!(unpack_last_swap_ts, last_swap_ts);
+            let unpack_last_liquidity_change_ts = unpack_data.last_liquidity_change_ts;
+            assert_eq!(unpack_last_liquidity_change_ts, last_liquidity_change_ts);
+            let unpack_sunset_ts = unpack_data.sunset_ts;
+            assert_eq!(unpack_sunset_ts, sunset_ts);
+            let unpack_last_swap_slot = unpack_data.last_swap_slot;
+            assert_eq!(unpack_last_swap_slot, last_swap_slot);
+            let unpack_decay_fee_end_time = unpack_data.decay_fee_end_time;
+            assert_eq!(unpack_decay_fee_end_time, decay_fee_end_time);
+            let unpack_seconds_per_liquidity_cumulative_x64 =
+                unpack_data.seconds_per_liquidity_cumulative_x64;
+            assert_eq!(
+                unpack_seconds_per_liquidity_cumulative_x64,
+                seconds_per_liquidity_cumulative_x64
+            );
+            let unpack_seconds_per_liquidity_updated_at = unpack_data.seconds_per_liquidity_updated_at;
+            assert_eq!(
+                unpack_seconds_per_liquidity_updated_at,
+                seconds_per_liquidity_updated_at
+            );
+            let unpack_seed_amm_config = unpack_data.seed_amm_config;
+            assert_eq!(unpack_seed_amm_config, seed_amm_config);
             let unpack_padding1 = unpack_data.padding1;
             assert_eq!(unpack_padding1, padding1);
             let unpack_padding2 = unpack_data.padding2;
@@ -2029,9 +3261,86 @@ pub mod pool_test {
         }
     }
 
+    mod seconds_per_liquidity_test {
+        use super::*;
+
+        #[test]
+        fn update_seconds_per_liquidity_test() {
+            let mut pool_state = PoolState {
+                seconds_per_liquidity_updated_at: 100,
+                liquidity: 4,
+                ..Default::default()
+            };
+            assert_eq!(
+                pool_state.update_seconds_per_liquidity(110),
+                (10u128 << 64) / 4
+            );
+            // no time elapsed, or a timestamp in the past, accrues nothing
+            assert_eq!(
+                pool_state.update_seconds_per_liquidity(110),
+                (10u128 << 64) / 4
+            );
+            assert_eq!(
+                pool_state.update_seconds_per_liquidity(105),
+                (10u128 << 64) / 4
+            );
+            assert_eq!({ pool_state.seconds_per_liquidity_updated_at }, 110);
+
+            // without liquidity in range the time accrues to a liquidity of 1
+            pool_state.liquidity = 0;
+            assert_eq!(
+                pool_state.update_seconds_per_liquidity(120),
+                (10u128 << 64) / 4 + (10u128 << 64)
+            );
+        }
+
+        #[test]
+        fn update_seconds_per_liquidity_never_updated_test() {
+            // a pool created before the accrual starts it from now, rather than from the unix epoch
+            let mut pool_state = PoolState {
+                liquidity: 4,
+                ..Default::default()
+            };
+            assert_eq!(pool_state.update_seconds_per_liquidity(1_700_000_000), 0);
+            assert_eq!(
+                { pool_state.seconds_per_liquidity_updated_at },
+                1_700_000_000
+            );
+            assert_eq!(
+                pool_state.update_seconds_per_liquidity(1_700_000_010),
+                (10u128 << 64) / 4
+            );
+        }
+    }
+
     mod decay_fee_test {
         use super::*;
 
+        #[test]
+        fn decay_fee_max_window_test() {
+            let mut pool_state = PoolState::default();
+            // the decay fee must be enabled
+            assert!(pool_state.set_decay_fee_max_window(100).is_err());
+
+            pool_state.open_time = 1000;
+            pool_state
+                .initialize_decay_fee(true, false, 80, 10, 10)
+                .unwrap();
+            assert!(pool_state.set_decay_fee_max_window(0).is_err());
+            assert!(!pool_state.is_decay_fee_expired(u64::MAX));
+
+            pool_state.set_decay_fee_max_window(100).unwrap();
+            assert_eq!({ pool_state.decay_fee_end_time }, 1100);
+            assert!(pool_state.get_decay_fee_rate(1099) > 0);
+            assert!(!pool_state.is_decay_fee_expired(1099));
+            // still above any trade fee, but over
+            assert_eq!(pool_state.get_decay_fee_rate(1100), 0);
+            assert!(pool_state.is_decay_fee_expired(1100));
+
+            pool_state.disable_decay_fee().unwrap();
+            assert!(!pool_state.is_decay_fee_expired(1100));
+        }
+
         #[test]
         fn decay_fee_test() {
             let mut pool_state = PoolState::default();
@@ -2047,9 +3356,9 @@ pub mod pool_test {
                 let open_time = pool_state.open_time;
                 assert_eq!(open_time, 0u64);
             }
-            assert_eq!(pool_state.is_decay_fee_enabled(), true);
-            assert_eq!(pool_state.is_decay_fee_on_sell_mint0(), true);
-            assert_eq!(pool_state.is_decay_fee_on_sell_mint1(), true);
+            assert!(pool_state.is_decay_fee_enabled());
+            assert!(pool_state.is_decay_fee_on_sell_mint0());
+            assert!(pool_state.is_decay_fee_on_sell_mint1());
 
             assert_eq!(pool_state.decay_fee_init_fee_rate, 80);
             assert_eq!(pool_state.decay_fee_decrease_rate, 10);
@@ -2097,7 +3406,7 @@ pub mod pool_test {
                 let fee_rate2 = pool_state.get_decay_fee_rate(59);
                 assert_eq!(fee_rate1, fee_rate2);
 
-                let diff = (fee_rate1 as i64 - 472392).abs() as u64;
+                let diff = (fee_rate1 as i64 - 472392).unsigned_abs();
                 assert!(diff < 10);
             }
 
@@ -2107,7 +3416,7 @@ pub mod pool_test {
                 let fee_rate2 = pool_state.get_decay_fee_rate(109);
                 assert_eq!(fee_rate1, fee_rate2);
 
-                let diff = (fee_rate1 as i64 - 278942).abs() as u64;
+                let diff = (fee_rate1 as i64 - 278942).unsigned_abs();
                 assert!(diff < 10);
             }
 
@@ -2117,7 +3426,7 @@ pub mod pool_test {
                 let fee_rate2 = pool_state.get_decay_fee_rate(209);
                 assert_eq!(fee_rate1, fee_rate2);
 
-                let diff = (fee_rate1 as i64 - 97261).abs() as u64;
+                let diff = (fee_rate1 as i64 - 97261).unsigned_abs();
                 assert!(diff < 10);
             }
 
@@ -2127,7 +3436,7 @@ pub mod pool_test {
                 let fee_rate2 = pool_state.get_decay_fee_rate(509);
                 assert_eq!(fee_rate1, fee_rate2);
 
-                let diff = (fee_rate1 as i64 - 4123).abs() as u64;
+                let diff = (fee_rate1 as i64 - 4123).unsigned_abs();
                 assert!(diff < 10);
             }
 
@@ -2137,9 +3446,67 @@ pub mod pool_test {
                 let fee_rate2 = pool_state.get_decay_fee_rate(1009);
                 assert_eq!(fee_rate1, fee_rate2);
 
-                let diff = (fee_rate1 as i64 - 21).abs() as u64;
+                let diff = (fee_rate1 as i64 - 21).unsigned_abs();
                 assert!(diff < 10);
             }
         }
+
+        #[test]
+        fn bootstrap_price_floor_test() {
+            let floor = 1u128 << 64;
+            let mut pool_state = PoolState {
+                sqrt_price_x64: floor * 2,
+                ..Default::default()
+            };
+
+            // the decay fee must be on sell for exactly one mint
+            assert!(pool_state.initialize_bootstrap_price_floor(floor).is_err());
+            pool_state
+                .initialize_decay_fee(true, true, 80, 10, 10)
+                .unwrap();
+            assert!(pool_state.initialize_bootstrap_price_floor(floor).is_err());
+
+            pool_state
+                .initialize_decay_fee(true, false, 80, 10, 10)
+                .unwrap();
+            // the initial price breaks the floor
+            assert!(pool_state
+                .initialize_bootstrap_price_floor(floor * 3)
+                .is_err());
+            pool_state.initialize_bootstrap_price_floor(floor).unwrap();
+            assert!(pool_state.is_bootstrap_price_floor_enabled());
+
+            // sells of mint0 can't push the price below the floor
+            assert!(pool_state.check_bootstrap_price_floor(true, floor).is_ok());
+            assert!(pool_state
+                .check_bootstrap_price_floor(true, floor - 1)
+                .is_err());
+            // buys of mint0 are not limited
+            assert!(pool_state
+                .check_bootstrap_price_floor(false, floor * 4)
+                .is_ok());
+
+            // the floor is lifted with the decay fee
+            pool_state.disable_decay_fee().unwrap();
+            assert!(!pool_state.is_bootstrap_price_floor_enabled());
+            assert!(pool_state
+                .check_bootstrap_price_floor(true, floor - 1)
+                .is_ok());
+
+            // on sell for mint1, the floor of mint1 is an upper bound of sqrt_price_x64
+            pool_state
+                .initialize_decay_fee(false, true, 80, 10, 10)
+                .unwrap();
+            pool_state
+                .initialize_bootstrap_price_floor(floor * 3)
+                .unwrap();
+            assert!(pool_state
+                .check_bootstrap_price_floor(false, floor * 3)
+                .is_ok());
+            assert!(pool_state
+                .check_bootstrap_price_floor(false, floor * 3 + 1)
+                .is_err());
+            assert!(pool_state.check_bootstrap_price_floor(true, 1).is_ok());
+        }
     }
 }
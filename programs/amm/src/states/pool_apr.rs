@@ -0,0 +1,257 @@
+use crate::libraries::{big_num::U256, fixed_point_64, full_math::MulDiv};
+use crate::states::FEE_RATE_DENOMINATOR_VALUE;
+use anchor_lang::prelude::*;
+
+/// Seed to derive account address and signature
+pub const POOL_APR_SEED: &str = "pool_apr";
+/// Number of FeeSnapshot elements, hourly snapshots of 7 days and the one 7 days ago
+pub const FEE_SNAPSHOT_NUM: usize = 7 * 24 + 1;
+/// The min seconds between two snapshots
+pub const FEE_SNAPSHOT_INTERVAL: u64 = 3600;
+pub const APR_WINDOW_24H: u64 = 24 * 3600;
+pub const APR_WINDOW_7D: u64 = 7 * 24 * 3600;
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 3600;
+
+/// The cumulative LP fees of the pool at a timestamp
+#[zero_copy(unsafe)]
+#[repr(C, packed)]
+#[derive(Default, Debug)]
+pub struct FeeSnapshot {
+    pub timestamp: u64,
+    pub total_fees_token_0: u64,
+    pub total_fees_token_1: u64,
+}
+
+impl FeeSnapshot {
+    pub const LEN: usize = 8 * 3;
+}
+
+/// The trailing fee APRs of a pool, updated by the permissionless `update_pool_apr` crank,
+/// so that other programs can read the yield of a pool on chain.
+/// PDA of `[POOL_APR_SEED, pool_id]`
+#[account(zero_copy(unsafe))]
+#[repr(C, packed)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PoolAprState {
+    pub bump: u8,
+    pub pool_id: Pubkey,
+    /// The LP fees of the last 24 hours over the pool TVL, annualized, in hundredths of a bip (10^-6).
+    /// The fees and the TVL are valued in token_1 at the pool price of the update.
+    /// Computed over the available snapshots if the account is younger than the window.
+    pub fee_apr_24h: u64,
+    /// Same as `fee_apr_24h` over the last 7 days
+    pub fee_apr_7d: u64,
+    /// The block timestamp of the last update
+    pub last_update_ts: u64,
+    /// The slot of `snapshots` written last
+    pub snapshot_head: u16,
+    /// The number of valid entries of `snapshots`
+    pub snapshot_len: u16,
+    pub padding_0: [u8; 4],
+    pub snapshots: [FeeSnapshot; FEE_SNAPSHOT_NUM],
+    pub padding: [u64; 8],
+}
+
+impl Default for PoolAprState {
+    #[inline]
+    fn default() -> PoolAprState {
+        PoolAprState {
+            bump: 0,
+            pool_id: Pubkey::default(),
+            fee_apr_24h: 0,
+            fee_apr_7d: 0,
+            last_update_ts: 0,
+            snapshot_head: 0,
+            snapshot_len: 0,
+            padding_0: [0; 4],
+            snapshots: [FeeSnapshot::default(); FEE_SNAPSHOT_NUM],
+            padding: [0; 8],
+        }
+    }
+}
+
+impl PoolAprState {
+    pub const LEN: usize =
+        8 + 1 + 32 + 8 * 3 + 2 + 2 + 4 + FeeSnapshot::LEN * FEE_SNAPSHOT_NUM + 8 * 8;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey) {
+        *self = Self::default();
+        self.bump = bump;
+        self.pool_id = pool_id;
+    }
+
+    /// Record the cumulative fees of the pool if the last snapshot is older than `FEE_SNAPSHOT_INTERVAL`
+    pub fn record_snapshot(&mut self, snapshot: FeeSnapshot) {
+        if self.snapshot_len > 0 {
+            let last_timestamp = self.snapshots[self.snapshot_head as usize].timestamp;
+            if snapshot.timestamp < last_timestamp + FEE_SNAPSHOT_INTERVAL {
+                return;
+            }
+            self.snapshot_head = ((self.snapshot_head as usize + 1) % FEE_SNAPSHOT_NUM) as u16;
+        }
+        self.snapshots[self.snapshot_head as usize] = snapshot;
+        if (self.snapshot_len as usize) < FEE_SNAPSHOT_NUM {
+            self.snapshot_len += 1;
+        }
+    }
+
+    /// The newest snapshot at least `window` seconds before `timestamp`, or the oldest one if none is old enough
+    pub fn snapshot_before(&self, timestamp: u64, window: u64) -> Option<FeeSnapshot> {
+        let len = self.snapshot_len as usize;
+        let head = self.snapshot_head as usize;
+        let mut oldest = None;
+        // from the newest to the oldest
+        for i in 0..len {
+            let snapshot = self.snapshots[(head + FEE_SNAPSHOT_NUM - i) % FEE_SNAPSHOT_NUM];
+            if snapshot.timestamp + window <= timestamp {
+                return Some(snapshot);
+            }
+            oldest = Some(snapshot);
+        }
+        oldest
+    }
+
+    /// Update the trailing APRs with the current cumulative fees and TVL of the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The cumulative fees of the pool now, recorded as a snapshot if the interval elapsed
+    /// * `sqrt_price_x64` - The pool price the fees and TVL are valued at
+    /// * `tvl_amount_0` - The token_0 amount of the pool, excluding the protocol and fund fees
+    /// * `tvl_amount_1` - The token_1 amount of the pool, excluding the protocol and fund fees
+    ///
+    pub fn update(
+        &mut self,
+        current: FeeSnapshot,
+        sqrt_price_x64: u128,
+        tvl_amount_0: u64,
+        tvl_amount_1: u64,
+    ) {
+        self.record_snapshot(current);
+        let tvl = value_in_token_1(tvl_amount_0, tvl_amount_1, sqrt_price_x64);
+        let mut aprs = [0u64; 2];
+        for (apr, window) in aprs.iter_mut().zip([APR_WINDOW_24H, APR_WINDOW_7D]) {
+            if let Some(start) = self.snapshot_before(current.timestamp, window) {
+                let fees = value_in_token_1(
+                    current
+                        .total_fees_token_0
+                        .saturating_sub(start.total_fees_token_0),
+                    current
+                        .total_fees_token_1
+                        .saturating_sub(start.total_fees_token_1),
+                    sqrt_price_x64,
+                );
+                *apr =
+                    annualized_rate(fees, tvl, current.timestamp.saturating_sub(start.timestamp));
+            }
+        }
+        self.fee_apr_24h = aprs[0];
+        self.fee_apr_7d = aprs[1];
+        self.last_update_ts = current.timestamp;
+    }
+}
+
+/// Value `amount_0` and `amount_1` in token_1 at the price `sqrt_price_x64`
+pub fn value_in_token_1(amount_0: u64, amount_1: u64, sqrt_price_x64: u128) -> U256 {
+    let q64 = U256::from(fixed_point_64::Q64);
+    let sqrt_price = U256::from(sqrt_price_x64);
+    U256::from(amount_0)
+        .mul_div_floor(sqrt_price, q64)
+        .unwrap()
+        .mul_div_floor(sqrt_price, q64)
+        .unwrap()
+        + U256::from(amount_1)
+}
+
+/// `fees / tvl` over `elapsed` seconds annualized, in hundredths of a bip (10^-6), 0 without TVL or elapsed time
+pub fn annualized_rate(fees: U256, tvl: U256, elapsed: u64) -> u64 {
+    if tvl.is_zero() || elapsed == 0 {
+        return 0;
+    }
+    let numerator = fees
+        .saturating_mul(U256::from(FEE_RATE_DENOMINATOR_VALUE))
+        .saturating_mul(U256::from(SECONDS_PER_YEAR));
+    let rate = numerator / tvl.saturating_mul(U256::from(elapsed));
+    if rate > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        rate.as_u64()
+    }
+}
+
+#[cfg(test)]
+mod pool_apr_test {
+    use super::*;
+    use std::convert::identity;
+
+    fn snapshot(timestamp: u64, total_fees_token_0: u64, total_fees_token_1: u64) -> FeeSnapshot {
+        FeeSnapshot {
+            timestamp,
+            total_fees_token_0,
+            total_fees_token_1,
+        }
+    }
+
+    #[test]
+    fn record_snapshot_test() {
+        let mut pool_apr = PoolAprState::default();
+        pool_apr.record_snapshot(snapshot(1000, 0, 0));
+        // too close to the last snapshot
+        pool_apr.record_snapshot(snapshot(1000 + FEE_SNAPSHOT_INTERVAL - 1, 1, 1));
+        assert_eq!(identity(pool_apr.snapshot_len), 1);
+
+        for i in 1..(FEE_SNAPSHOT_NUM as u64 + 10) {
+            pool_apr.record_snapshot(snapshot(1000 + i * FEE_SNAPSHOT_INTERVAL, i, i));
+        }
+        assert_eq!(pool_apr.snapshot_len as usize, FEE_SNAPSHOT_NUM);
+        let now = 1000 + (FEE_SNAPSHOT_NUM as u64 + 9) * FEE_SNAPSHOT_INTERVAL;
+        let start = pool_apr.snapshot_before(now, APR_WINDOW_24H).unwrap();
+        assert_eq!({ start.timestamp }, now - APR_WINDOW_24H);
+        let start = pool_apr.snapshot_before(now, APR_WINDOW_7D).unwrap();
+        assert_eq!({ start.timestamp }, now - APR_WINDOW_7D);
+        // older than the history, the oldest snapshot
+        let start = pool_apr.snapshot_before(now, APR_WINDOW_7D * 2).unwrap();
+        assert_eq!({ start.timestamp }, now - APR_WINDOW_7D);
+    }
+
+    #[test]
+    fn update_test() {
+        // price 1
+        let sqrt_price_x64 = fixed_point_64::Q64;
+        let mut pool_apr = PoolAprState::default();
+        pool_apr.update(snapshot(0, 0, 0), sqrt_price_x64, 1_000_000, 1_000_000);
+        assert_eq!({ pool_apr.fee_apr_24h }, 0);
+
+        // 2_000_000 TVL earning 200 a day for 7 days
+        for day in 1..=7 {
+            for hour in 1..=24 {
+                let timestamp = (day - 1) * APR_WINDOW_24H + hour * 3600;
+                let fees = (day - 1) * 200 + hour * 200 / 24;
+                pool_apr.update(
+                    snapshot(timestamp, fees / 2, fees - fees / 2),
+                    sqrt_price_x64,
+                    1_000_000,
+                    1_000_000,
+                );
+            }
+        }
+        // 200 / 2_000_000 * 365 = 3.65%
+        assert_eq!({ pool_apr.fee_apr_24h }, 36_500);
+        assert_eq!({ pool_apr.fee_apr_7d }, 36_500);
+        assert_eq!({ pool_apr.last_update_ts }, APR_WINDOW_7D);
+    }
+
+    #[test]
+    fn annualized_rate_test() {
+        assert_eq!(annualized_rate(U256::from(1), U256::zero(), 1), 0);
+        assert_eq!(annualized_rate(U256::from(1), U256::from(1), 0), 0);
+        assert_eq!(
+            annualized_rate(U256::from(1), U256::from(100), SECONDS_PER_YEAR),
+            10_000
+        );
+        assert_eq!(
+            annualized_rate(U256::from(u64::MAX), U256::from(1), 1),
+            u64::MAX
+        );
+    }
+}
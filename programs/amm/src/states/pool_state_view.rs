@@ -0,0 +1,186 @@
+use crate::states::pool::offsets;
+use crate::states::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use std::cell::Ref;
+use std::ops::Deref;
+
+/// A read-only view of a PoolState account, reading only the accessed fields from the account data.
+/// Used in the read paths that only need a few fields, instead of borrowing the whole PoolState through
+/// `AccountLoader::load`, and the pool account can stay read-only in those instructions.
+pub struct PoolStateView<D: Deref<Target = [u8]>> {
+    data: D,
+}
+
+impl<'a> PoolStateView<Ref<'a, [u8]>> {
+    /// Load the view of a pool account owned by this program
+    pub fn load(account_info: &'a AccountInfo) -> Result<Self> {
+        if account_info.owner != &crate::id() {
+            return Err(Error::from(ErrorCode::AccountOwnedByWrongProgram)
+                .with_pubkeys((*account_info.owner, crate::id())));
+        }
+        let data = Ref::map(account_info.try_borrow_data()?, |data| &data[..]);
+        Self::try_from_data(data)
+    }
+}
+
+impl<D: Deref<Target = [u8]>> PoolStateView<D> {
+    /// Check the discriminator and length of the account data
+    pub fn try_from_data(data: D) -> Result<Self> {
+        if data.len() < PoolState::DISCRIMINATOR.len() {
+            return err!(ErrorCode::AccountDiscriminatorNotFound);
+        }
+        if !data.starts_with(PoolState::DISCRIMINATOR) {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        }
+        if data.len() < PoolState::MIN_LEN {
+            return err!(ErrorCode::AccountDidNotDeserialize);
+        }
+        Ok(Self { data })
+    }
+
+    fn read<const N: usize>(&self, offset: usize) -> [u8; N] {
+        self.data[offset..offset + N].try_into().unwrap()
+    }
+
+    fn read_pubkey(&self, offset: usize) -> Pubkey {
+        Pubkey::new_from_array(self.read(offset))
+    }
+
+    pub fn amm_config(&self) -> Pubkey {
+        self.read_pubkey(offsets::AMM_CONFIG)
+    }
+
+    pub fn owner(&self) -> Pubkey {
+        self.read_pubkey(offsets::OWNER)
+    }
+
+    pub fn token_mint_0(&self) -> Pubkey {
+        self.read_pubkey(offsets::TOKEN_MINT_0)
+    }
+
+    pub fn token_mint_1(&self) -> Pubkey {
+        self.read_pubkey(offsets::TOKEN_MINT_1)
+    }
+
+    pub fn token_vault_0(&self) -> Pubkey {
+        self.read_pubkey(offsets::TOKEN_VAULT_0)
+    }
+
+    pub fn token_vault_1(&self) -> Pubkey {
+        self.read_pubkey(offsets::TOKEN_VAULT_1)
+    }
+
+    pub fn observation_key(&self) -> Pubkey {
+        self.read_pubkey(offsets::OBSERVATION_KEY)
+    }
+
+    pub fn tick_spacing(&self) -> u16 {
+        u16::from_le_bytes(self.read(offsets::TICK_SPACING))
+    }
+
+    pub fn liquidity(&self) -> u128 {
+        u128::from_le_bytes(self.read(offsets::LIQUIDITY))
+    }
+
+    pub fn sqrt_price_x64(&self) -> u128 {
+        u128::from_le_bytes(self.read(offsets::SQRT_PRICE))
+    }
+
+    pub fn tick_current(&self) -> i32 {
+        i32::from_le_bytes(self.read(offsets::TICK_CURRENT))
+    }
+
+    pub fn status(&self) -> u8 {
+        self.data[offsets::STATUS]
+    }
+
+    pub fn open_time(&self) -> u64 {
+        u64::from_le_bytes(self.read(offsets::OPEN_TIME))
+    }
+
+    pub fn sunset_ts(&self) -> u64 {
+        u64::from_le_bytes(self.read(offsets::SUNSET_TS))
+    }
+
+    pub fn last_swap_slot(&self) -> u64 {
+        u64::from_le_bytes(self.read(offsets::LAST_SWAP_SLOT))
+    }
+
+    /// Same as `PoolState::is_inactive`, routers can skip the inactive pools
+    pub fn is_inactive(&self) -> bool {
+        self.sunset_ts() != 0
+    }
+
+    /// Same as `PoolState::get_status_by_bit`, true if the operation is enabled
+    pub fn get_status_by_bit(&self, bit: PoolStatusBitIndex) -> bool {
+        self.status() & (1 << (bit as u8)) == 0
+    }
+}
+
+#[cfg(test)]
+mod pool_state_view_test {
+    use super::*;
+
+    #[test]
+    fn pool_state_view_layout_test() {
+        // the offsets of the fields before the reward infos can't change without breaking the pool accounts
+        assert_eq!(offsets::AMM_CONFIG, 9);
+        assert_eq!(offsets::OWNER, 41);
+        assert_eq!(offsets::TOKEN_MINT_0, 73);
+        assert_eq!(offsets::TOKEN_MINT_1, 105);
+        assert_eq!(offsets::TOKEN_VAULT_0, 137);
+        assert_eq!(offsets::TOKEN_VAULT_1, 169);
+        assert_eq!(offsets::OBSERVATION_KEY, 201);
+        assert_eq!(offsets::TICK_SPACING, 235);
+        assert_eq!(offsets::LIQUIDITY, 237);
+        assert_eq!(offsets::SQRT_PRICE, 253);
+        assert_eq!(offsets::TICK_CURRENT, 269);
+        assert_eq!(offsets::STATUS, 389);
+
+        let pool_state = PoolState {
+            amm_config: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            token_mint_0: Pubkey::new_unique(),
+            token_mint_1: Pubkey::new_unique(),
+            token_vault_0: Pubkey::new_unique(),
+            token_vault_1: Pubkey::new_unique(),
+            observation_key: Pubkey::new_unique(),
+            tick_spacing: 0x1234,
+            liquidity: 0x11223344556677889900aabbccddeeff,
+            sqrt_price_x64: 0xffeeddccbbaa00998877665544332211,
+            tick_current: -0x123456,
+            status: 1 << PoolStatusBitIndex::Swap as u8,
+            open_time: 0x0102030405060708,
+            sunset_ts: 0x1112131415161718,
+            last_swap_slot: 0x2122232425262728,
+            ..Default::default()
+        };
+
+        let mut data = PoolState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&pool_state));
+        let view = PoolStateView::try_from_data(&data[..]).unwrap();
+        assert_eq!(view.amm_config(), { pool_state.amm_config });
+        assert_eq!(view.owner(), { pool_state.owner });
+        assert_eq!(view.token_mint_0(), { pool_state.token_mint_0 });
+        assert_eq!(view.token_mint_1(), { pool_state.token_mint_1 });
+        assert_eq!(view.token_vault_0(), { pool_state.token_vault_0 });
+        assert_eq!(view.token_vault_1(), { pool_state.token_vault_1 });
+        assert_eq!(view.observation_key(), { pool_state.observation_key });
+        assert_eq!(view.tick_spacing(), 0x1234);
+        assert_eq!(view.liquidity(), 0x11223344556677889900aabbccddeeff);
+        assert_eq!(view.sqrt_price_x64(), 0xffeeddccbbaa00998877665544332211);
+        assert_eq!(view.tick_current(), -0x123456);
+        assert_eq!(view.open_time(), 0x0102030405060708);
+        assert_eq!(view.sunset_ts(), 0x1112131415161718);
+        assert!(view.is_inactive());
+        assert_eq!(view.last_swap_slot(), 0x2122232425262728);
+        assert!(!view.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(view.get_status_by_bit(PoolStatusBitIndex::CollectFee));
+
+        // wrong discriminator or truncated data
+        assert!(PoolStateView::try_from_data(&data[8..]).is_err());
+        assert!(PoolStateView::try_from_data(&data[..PoolState::MIN_LEN - 1]).is_err());
+    }
+}
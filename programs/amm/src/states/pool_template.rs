@@ -0,0 +1,184 @@
+use crate::error::ErrorCode;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+pub const POOL_TEMPLATE_SEED: &str = "pool_template";
+
+/// The parameters of the pools created from a template
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct PoolTemplateParams {
+    /// Whether the pools use decay fee, the following decay fields are only used when it is true.
+    pub use_decay_fee: bool,
+    pub decay_fee_on_sell_mint0: bool,
+    pub decay_fee_on_sell_mint1: bool,
+    /// The initial decay fee rate, in percentage.(1=1%)
+    pub init_decay_fee_rate: u8,
+    /// decrease rate for the decay fee, in percentage.(1=1%)
+    pub decay_fee_decrease_rate: u8,
+    /// The interval for decreasing the decay fee, in seconds.
+    pub decay_fee_decrease_interval: u8,
+    /// The maker rebate rate, in hundredths of a bip (10^-6), 0 to disable
+    pub maker_rebate_rate: u32,
+    /// The status bits the pools are created with, see `PoolStatusBitIndex`
+    pub status: u8,
+    /// Create the tick arrays of the pools as fixed tick arrays
+    pub use_fixed_tick_array: bool,
+}
+
+/// A reusable set of pool parameters, so that launchpads create pools with consistent configurations.
+/// The fee tier and tick spacing come from `amm_config`, they are snapshotted to detect a config updated after the template was created.
+#[account]
+#[derive(Default, Debug)]
+pub struct PoolTemplate {
+    /// Bump to identify PDA
+    pub bump: u8,
+    pub index: u16,
+    /// The config the pools are created under
+    pub amm_config: Pubkey,
+    /// The tick spacing of `amm_config` when the template was created
+    pub tick_spacing: u16,
+    /// The trade fee rate of `amm_config` when the template was created
+    pub trade_fee_rate: u32,
+    pub params: PoolTemplateParams,
+    pub padding: [u64; 8],
+}
+
+impl PoolTemplate {
+    pub const LEN: usize = 8 + 1 + 2 + 32 + 2 + 4 + (1 + 1 + 1 + 1 + 1 + 1 + 4 + 1 + 1) + 64;
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        index: u16,
+        amm_config_key: Pubkey,
+        amm_config: &AmmConfig,
+        params: PoolTemplateParams,
+    ) -> Result<()> {
+        Self::validate_params(&params)?;
+        self.bump = bump;
+        self.index = index;
+        self.amm_config = amm_config_key;
+        self.tick_spacing = amm_config.tick_spacing;
+        self.trade_fee_rate = amm_config.trade_fee_rate;
+        self.params = params;
+        Ok(())
+    }
+
+    /// The same checks as creating a pool with these parameters, so a template can't fail every pool it creates
+    pub fn validate_params(params: &PoolTemplateParams) -> Result<()> {
+        if params.use_decay_fee {
+            require!(
+                params.decay_fee_on_sell_mint0 || params.decay_fee_on_sell_mint1,
+                ErrorCode::DecayFeeNeitherOnSellMint0NorMint1
+            );
+            require!(
+                params.init_decay_fee_rate <= 100
+                    && params.decay_fee_decrease_rate <= 100
+                    && params.decay_fee_decrease_interval > 0,
+                ErrorCode::InvalidPoolTemplate
+            );
+        }
+        require_gte!(
+            FEE_RATE_DENOMINATOR_VALUE,
+            params.maker_rebate_rate,
+            ErrorCode::InvalidPoolTemplate
+        );
+        Ok(())
+    }
+
+    /// Check the pool is created under the config of the template, and the config hasn't changed since
+    pub fn check_config(&self, amm_config_key: Pubkey, amm_config: &AmmConfig) -> Result<()> {
+        require_keys_eq!(
+            self.amm_config,
+            amm_config_key,
+            ErrorCode::InvalidPoolTemplate
+        );
+        require!(
+            self.tick_spacing == amm_config.tick_spacing
+                && self.trade_fee_rate == amm_config.trade_fee_rate,
+            ErrorCode::PoolTemplateOutdated
+        );
+        Ok(())
+    }
+
+    /// Apply the template parameters that are not part of `CreatePoolDecayFeeParams` to a new pool
+    pub fn apply(&self, pool_state: &mut PoolState) -> Result<()> {
+        pool_state.set_maker_rebate_rate(self.params.maker_rebate_rate)?;
+        pool_state.set_status(self.params.status);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod pool_template_test {
+    use super::*;
+
+    #[test]
+    fn pool_template_test() {
+        let amm_config = AmmConfig {
+            tick_spacing: 60,
+            trade_fee_rate: 2500,
+            ..Default::default()
+        };
+        let amm_config_key = Pubkey::new_unique();
+        let params = PoolTemplateParams {
+            use_decay_fee: true,
+            decay_fee_on_sell_mint0: true,
+            init_decay_fee_rate: 80,
+            decay_fee_decrease_rate: 10,
+            decay_fee_decrease_interval: 10,
+            maker_rebate_rate: 100_000,
+            status: 1 << PoolStatusBitIndex::Swap as u8,
+            ..Default::default()
+        };
+
+        // invalid decay params
+        let mut template = PoolTemplate::default();
+        assert!(template
+            .initialize(
+                255,
+                0,
+                amm_config_key,
+                &amm_config,
+                PoolTemplateParams {
+                    decay_fee_on_sell_mint0: false,
+                    ..params.clone()
+                },
+            )
+            .is_err());
+        assert!(template
+            .initialize(
+                255,
+                0,
+                amm_config_key,
+                &amm_config,
+                PoolTemplateParams {
+                    decay_fee_decrease_interval: 0,
+                    ..params.clone()
+                },
+            )
+            .is_err());
+
+        template
+            .initialize(255, 0, amm_config_key, &amm_config, params)
+            .unwrap();
+        assert!(template.check_config(amm_config_key, &amm_config).is_ok());
+        assert!(template
+            .check_config(Pubkey::new_unique(), &amm_config)
+            .is_err());
+        // the config is updated after the template was created
+        let updated_amm_config = AmmConfig {
+            trade_fee_rate: 3000,
+            ..amm_config
+        };
+        assert!(template
+            .check_config(amm_config_key, &updated_amm_config)
+            .is_err());
+
+        let mut pool_state = PoolState::default();
+        template.apply(&mut pool_state).unwrap();
+        assert_eq!({ pool_state.maker_rebate_rate }, 100_000);
+        assert!(!pool_state.get_status_by_bit(PoolStatusBitIndex::Swap));
+        assert!(pool_state.get_status_by_bit(PoolStatusBitIndex::DecreaseLiquidity));
+    }
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+pub const POSITION_COLLATERAL_SEED: &str = "position_collateral";
+
+/// The owner-signed terms under which a collateral manager, e.g. a lending program, can liquidate a position.
+/// Only the collateral manager can release the terms. While they exist the position NFT is escrowed in `custody`,
+/// the associated token account of the terms, and returned to the owner who signed them when they are released.
+/// The position is marked as collateralized meanwhile, see `PersonalPositionState::collateralized`.
+#[account]
+#[derive(Default, Debug)]
+pub struct PositionCollateralState {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The mint of the position NFT
+    pub nft_mint: Pubkey,
+    /// The position owner who signed the terms, gets the rent back when the terms are released
+    pub owner: Pubkey,
+    /// The authority allowed to decrease the liquidity of the position and collect its fees and rewards
+    pub collateral_manager: Pubkey,
+    /// The owner of the token accounts receiving the liquidated tokens
+    pub receiver: Pubkey,
+    /// The pool of the position, whose event sequence stamps the events of the terms
+    pub pool_id: Pubkey,
+    /// The token account of the terms holding the position NFT until the terms are released
+    pub custody: Pubkey,
+    pub padding: [u64; 4],
+}
+
+impl PositionCollateralState {
+    pub const LEN: usize = 8 + 1 + 32 * 6 + 32;
+
+    pub fn initialize(
+        &mut self,
+        bump: u8,
+        nft_mint: Pubkey,
+        owner: Pubkey,
+        collateral_manager: Pubkey,
+        receiver: Pubkey,
+        pool_id: Pubkey,
+        custody: Pubkey,
+    ) {
+        self.bump = bump;
+        self.nft_mint = nft_mint;
+        self.owner = owner;
+        self.collateral_manager = collateral_manager;
+        self.receiver = receiver;
+        self.pool_id = pool_id;
+        self.custody = custody;
+    }
+
+    /// The seeds signing for `custody`
+    pub fn seeds(&self) -> [&[u8]; 3] {
+        [
+            POSITION_COLLATERAL_SEED.as_bytes(),
+            self.nft_mint.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+
+    pub fn key(nft_mint: Pubkey, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[POSITION_COLLATERAL_SEED.as_bytes(), nft_mint.as_ref()],
+            program_id,
+        )
+        .0
+    }
+}
+
+#[cfg(test)]
+mod position_collateral_test {
+    use super::*;
+
+    #[test]
+    fn position_collateral_layout_test() {
+        let mut data = Vec::new();
+        PositionCollateralState::default()
+            .serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len() + 8, PositionCollateralState::LEN);
+    }
+
+    #[test]
+    fn position_collateral_seeds_test() {
+        let nft_mint = Pubkey::new_unique();
+        let (key, bump) = Pubkey::find_program_address(
+            &[POSITION_COLLATERAL_SEED.as_bytes(), nft_mint.as_ref()],
+            &crate::id(),
+        );
+        let mut position_collateral = PositionCollateralState::default();
+        position_collateral.initialize(
+            bump,
+            nft_mint,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        assert_eq!(PositionCollateralState::key(nft_mint, &crate::id()), key);
+        assert_eq!(
+            Pubkey::create_program_address(&position_collateral.seeds(), &crate::id()).unwrap(),
+            key
+        );
+    }
+}
+
+/// Emitted when the collateral terms of a position are set or released
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PositionCollateralEvent {
+    pub position_nft_mint: Pubkey,
+    pub collateral_manager: Pubkey,
+    pub receiver: Pubkey,
+    /// false when the terms are set, true when they are released
+    pub released: bool,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
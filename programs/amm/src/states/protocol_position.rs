@@ -66,11 +66,11 @@ impl ProtocolPositionState {
             return Ok(());
         }
         require!(
-            tick_lower_index >= tick_math::MIN_TICK && tick_lower_index <= tick_math::MAX_TICK,
+            (tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&tick_lower_index),
             ErrorCode::TickLowerOverflow
         );
         require!(
-            tick_upper_index >= tick_math::MIN_TICK && tick_upper_index <= tick_math::MAX_TICK,
+            (tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&tick_upper_index),
             ErrorCode::TickUpperOverflow
         );
         // calculate accumulated Fees
@@ -86,7 +86,7 @@ impl ProtocolPositionState {
                 .to_underflow_u64();
 
         // Update the position liquidity
-        self.liquidity = liquidity_math::add_delta(self.liquidity, liquidity_delta)?;
+        self.liquidity = liquidity_math::add_delta_raw(self.liquidity, liquidity_delta)?;
         self.fee_growth_inside_0_last_x64 = fee_growth_inside_0_x64;
         self.fee_growth_inside_1_last_x64 = fee_growth_inside_1_x64;
         self.tick_lower_index = tick_lower_index;
@@ -95,9 +95,10 @@ impl ProtocolPositionState {
             self.token_fees_owed_0 = self.token_fees_owed_0.checked_add(tokens_owed_0).unwrap();
             self.token_fees_owed_1 = self.token_fees_owed_1.checked_add(tokens_owed_1).unwrap();
         }
-        #[cfg(feature = "enable-log")]
-        msg!(
-            "protocol position reward_growths_inside:{:?}",
+        log_debug!(
+            ix = "update_protocol_position",
+            pool = self.pool_id,
+            "reward_growths_inside:{:?}",
             reward_growths_inside
         );
         self.update_reward_growths_inside(reward_growths_inside);
@@ -0,0 +1,264 @@
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+
+pub const REBALANCE_BOUNTY_SEED: &str = "rebalance_bounty";
+pub const REBALANCE_VAULT_SEED: &str = "rebalance_vault";
+
+/// Lamports prepaid by the pool creator to pay the keepers rebalancing the registered vault positions of the pool.
+/// A keeper is paid `bounty_lamports` when a vault position moves back into range,
+/// after the price has left the previous one for at least `min_out_of_range_slots`.
+#[account]
+#[derive(Default, Debug)]
+pub struct RebalanceBounty {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool whose vault positions are rebalanced
+    pub pool_id: Pubkey,
+    /// The lamports paid to the keeper of a rebalance
+    pub bounty_lamports: u64,
+    /// The min slots a vault position must be out of range before its rebalance is paid
+    pub min_out_of_range_slots: u64,
+    /// Total lamports deposited into the bounty
+    pub total_deposited: u64,
+    /// Total lamports paid to the keepers
+    pub total_paid: u64,
+    pub padding: [u64; 8],
+}
+
+impl RebalanceBounty {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 + 8 + 8 + 64;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+    }
+
+    pub fn key(pool_id: Pubkey, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[REBALANCE_BOUNTY_SEED.as_bytes(), pool_id.as_ref()],
+            program_id,
+        )
+        .0
+    }
+}
+
+/// A position of protocol-owned liquidity registered for rebalancing bounties by the pool creator.
+/// The vault follows its position across rebalances: a paid rebalance re-points it to the new position.
+#[account]
+#[derive(Default, Debug)]
+pub struct RebalanceVault {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool of the vault positions
+    pub pool_id: Pubkey,
+    /// The owner of the vault position NFTs, e.g. the authority of a vault program
+    pub owner: Pubkey,
+    /// The current vault position
+    pub personal_position: Pubkey,
+    /// The lower tick of the current vault position
+    pub tick_lower_index: i32,
+    /// The upper tick of the current vault position
+    pub tick_upper_index: i32,
+    /// The liquidity of the current vault position when it was tracked,
+    /// a paid rebalance must move at least as much liquidity into the new position
+    pub liquidity: u128,
+    /// The first slot the current vault position was observed out of range, zero while it is in range
+    pub out_of_range_since_slot: u64,
+    /// How many rebalances were paid
+    pub rebalance_count: u64,
+    pub padding: [u64; 6],
+}
+
+impl RebalanceVault {
+    pub const LEN: usize = 8 + 1 + 32 * 3 + 4 + 4 + 16 + 8 + 8 + 48;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey, owner: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+        self.owner = owner;
+    }
+
+    pub fn key(pool_id: Pubkey, owner: Pubkey, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                REBALANCE_VAULT_SEED.as_bytes(),
+                pool_id.as_ref(),
+                owner.as_ref(),
+            ],
+            program_id,
+        )
+        .0
+    }
+
+    /// Track a new vault position, its out of range time starts over
+    pub fn set_position(
+        &mut self,
+        personal_position: Pubkey,
+        tick_lower_index: i32,
+        tick_upper_index: i32,
+        liquidity: u128,
+    ) {
+        self.personal_position = personal_position;
+        self.tick_lower_index = tick_lower_index;
+        self.tick_upper_index = tick_upper_index;
+        self.liquidity = liquidity;
+        self.out_of_range_since_slot = 0;
+    }
+
+    pub fn is_in_range(&self, tick_current: i32) -> bool {
+        self.tick_lower_index <= tick_current && tick_current < self.tick_upper_index
+    }
+
+    /// Record the first slot the vault position is seen out of range, or reset it when it is back in range
+    pub fn observe(&mut self, tick_current: i32, slot: u64) {
+        if self.is_in_range(tick_current) {
+            self.out_of_range_since_slot = 0;
+        } else if self.out_of_range_since_slot == 0 {
+            self.out_of_range_since_slot = slot;
+        }
+    }
+
+    /// Check the vault position is still out of range and has been for at least `min_out_of_range_slots`,
+    /// returns the slots it has been out of range
+    pub fn check_rebalance_due(
+        &self,
+        tick_current: i32,
+        slot: u64,
+        min_out_of_range_slots: u64,
+    ) -> Result<u64> {
+        let out_of_range_slots = slot.saturating_sub(self.out_of_range_since_slot);
+        require!(
+            self.out_of_range_since_slot != 0
+                && !self.is_in_range(tick_current)
+                && out_of_range_slots >= min_out_of_range_slots,
+            ErrorCode::RebalanceNotDue
+        );
+        Ok(out_of_range_slots)
+    }
+
+    /// Check the tracked vault position was emptied and at least its tracked liquidity is in the new position,
+    /// so an unrelated position already in range can't be claimed as a rebalance
+    pub fn check_liquidity_moved(&self, from_liquidity: u128, to_liquidity: u128) -> Result<()> {
+        require!(
+            from_liquidity == 0 && to_liquidity >= self.liquidity,
+            ErrorCode::RebalanceLiquidityNotMoved
+        );
+        Ok(())
+    }
+}
+
+/// Emitted when the rebalance bounty of a pool is funded or its parameters are changed
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FundRebalanceBountyEvent {
+    pub pool_state: Pubkey,
+    /// The account that funded the bounty
+    pub funder: Pubkey,
+    /// The lamports deposited in this instruction
+    pub amount: u64,
+    pub bounty_lamports: u64,
+    pub min_out_of_range_slots: u64,
+    /// Total lamports deposited into the bounty so far
+    pub total_deposited: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when a vault position is registered, or replaced by its owner
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalanceVaultRegisteredEvent {
+    pub pool_state: Pubkey,
+    pub rebalance_vault: Pubkey,
+    pub owner: Pubkey,
+    pub personal_position: Pubkey,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when the keeper of a rebalance is paid
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RebalanceBountyClaimedEvent {
+    pub pool_state: Pubkey,
+    pub rebalance_vault: Pubkey,
+    pub keeper: Pubkey,
+    /// The vault position left out of range
+    pub from_personal_position: Pubkey,
+    /// The vault position in range after the rebalance
+    pub to_personal_position: Pubkey,
+    /// The slots the previous vault position was out of range
+    pub out_of_range_slots: u64,
+    /// The lamports paid to the keeper
+    pub bounty: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod rebalance_bounty_test {
+    use super::*;
+
+    #[test]
+    fn observe_test() {
+        let vault = &mut RebalanceVault::default();
+        vault.set_position(Pubkey::new_unique(), -600, 600, 1000);
+
+        vault.observe(0, 100);
+        assert_eq!(vault.out_of_range_since_slot, 0);
+        // the upper tick is out of range
+        vault.observe(600, 200);
+        assert_eq!(vault.out_of_range_since_slot, 200);
+        // the first slot out of range is kept
+        vault.observe(-601, 300);
+        assert_eq!(vault.out_of_range_since_slot, 200);
+        vault.observe(-600, 400);
+        assert_eq!(vault.out_of_range_since_slot, 0);
+
+        vault.observe(700, 500);
+        vault.set_position(Pubkey::new_unique(), 600, 1200, 1000);
+        assert_eq!(vault.out_of_range_since_slot, 0);
+    }
+
+    #[test]
+    fn check_rebalance_due_test() {
+        let vault = &mut RebalanceVault::default();
+        vault.set_position(Pubkey::new_unique(), -600, 600, 1000);
+        // never observed out of range
+        assert_eq!(
+            vault.check_rebalance_due(700, 1000, 50).err(),
+            Some(ErrorCode::RebalanceNotDue.into())
+        );
+
+        vault.observe(700, 1000);
+        assert_eq!(
+            vault.check_rebalance_due(700, 1049, 50).err(),
+            Some(ErrorCode::RebalanceNotDue.into())
+        );
+        assert_eq!(vault.check_rebalance_due(700, 1050, 50).unwrap(), 50);
+        // the price came back before anyone observed it
+        assert_eq!(
+            vault.check_rebalance_due(0, 1050, 50).err(),
+            Some(ErrorCode::RebalanceNotDue.into())
+        );
+    }
+
+    #[test]
+    fn check_liquidity_moved_test() {
+        let vault = &mut RebalanceVault::default();
+        vault.set_position(Pubkey::new_unique(), -600, 600, 1000);
+        assert_eq!(vault.liquidity, 1000);
+
+        // farming: a position already in range is claimed while the tracked one keeps its liquidity
+        assert_eq!(
+            vault.check_liquidity_moved(1000, 5000).err(),
+            Some(ErrorCode::RebalanceLiquidityNotMoved.into())
+        );
+        // the tracked position was emptied but the liquidity went elsewhere
+        assert_eq!(
+            vault.check_liquidity_moved(0, 999).err(),
+            Some(ErrorCode::RebalanceLiquidityNotMoved.into())
+        );
+        vault.check_liquidity_moved(0, 1000).unwrap();
+    }
+}
@@ -10,15 +10,37 @@ pub struct SupportMintAssociated {
     pub bump: u8,
     /// Address of the supported token22 mint
     pub mint: Pubkey,
-    pub padding: [u64; 8],
+    /// Allow the mint to create pools and rewards even with an extension of the mint extension denylist,
+    /// for a mint reviewed not to use them, such as confidential transfers never enabled
+    pub allow_denied_extensions: bool,
+    pub padding_u8: [u8; 7],
+    pub padding: [u64; 7],
 }
 
 impl SupportMintAssociated {
     pub const LEN: usize = 8 + 1 + 32 + 64;
 
-    pub fn initialize<'info>(&mut self, bump: u8, mint: Pubkey) -> Result<()> {
+    pub fn initialize(&mut self, bump: u8, mint: Pubkey) -> Result<()> {
         self.bump = bump;
         self.mint = mint;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod support_mint_associated_test {
+    use super::*;
+
+    #[test]
+    fn support_mint_associated_layout_test() {
+        let mint_associated = SupportMintAssociated {
+            allow_denied_extensions: true,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        mint_associated.serialize(&mut data).unwrap();
+        assert_eq!(data.len() + 8, SupportMintAssociated::LEN);
+        // the flag takes the first padding byte of the accounts created before it, which is zero
+        assert_eq!(data[1 + 32], 1);
+    }
+}
@@ -82,7 +82,7 @@ impl TickArrayState {
                 if self.ticks[i as usize].is_initialized() {
                     return Ok(self.ticks.get_mut(i as usize).unwrap());
                 }
-                i = i - 1;
+                i -= 1;
             }
         } else {
             let mut i = 0;
@@ -90,7 +90,7 @@ impl TickArrayState {
                 if self.ticks[i].is_initialized() {
                     return Ok(self.ticks.get_mut(i).unwrap());
                 }
-                i = i + 1;
+                i += 1;
             }
         }
         err!(ErrorCode::InvalidTickArray)
@@ -118,15 +118,15 @@ impl TickArrayState {
                 if self.ticks[offset_in_array as usize].is_initialized() {
                     return Ok(self.ticks.get_mut(offset_in_array as usize));
                 }
-                offset_in_array = offset_in_array - 1;
+                offset_in_array -= 1;
             }
         } else {
-            offset_in_array = offset_in_array + 1;
+            offset_in_array += 1;
             while offset_in_array < TICK_ARRAY_SIZE {
                 if self.ticks[offset_in_array as usize].is_initialized() {
                     return Ok(self.ticks.get_mut(offset_in_array as usize));
                 }
-                offset_in_array = offset_in_array + 1;
+                offset_in_array += 1;
             }
         }
         Ok(None)
@@ -141,6 +141,68 @@ impl TickArrayState {
             self.start_tick_index + ticks_in_array
         }
     }
+
+    /// Scan the TickStates to measure how much of this fixed tick array is in use,
+    /// and the rent that would be freed by migrating it to a dynamic tick array with only the initialized TickStates.
+    pub fn audit(&self, rent: &Rent) -> TickArrayAudit {
+        let scanned_initialized_tick_count = self
+            .ticks
+            .iter()
+            .filter(|tick| tick.is_initialized())
+            .count() as u8;
+        let dyn_data_len = DynTickArrayState::HEADER_LEN
+            + usize::from(scanned_initialized_tick_count.max(1)) * TickState::LEN;
+        let fixed_rent = rent.minimum_balance(TickArrayState::LEN);
+        let dyn_rent = rent.minimum_balance(dyn_data_len);
+        TickArrayAudit {
+            start_tick_index: self.start_tick_index,
+            initialized_tick_count: self.initialized_tick_count,
+            scanned_initialized_tick_count,
+            dyn_data_len: dyn_data_len as u32,
+            rent_savings: fixed_rent.saturating_sub(dyn_rent),
+        }
+    }
+}
+
+/// The usage of a fixed tick array, returned by `audit_tick_array`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickArrayAudit {
+    pub start_tick_index: i32,
+    /// The initialized tick count recorded in the tick array
+    pub initialized_tick_count: u8,
+    /// The TickStates with liquidity_gross > 0 found by scanning all the slots,
+    /// differs from `initialized_tick_count` only if the counter is out of sync
+    pub scanned_initialized_tick_count: u8,
+    /// The account data length of the dynamic tick array holding only the initialized TickStates
+    pub dyn_data_len: u32,
+    /// The lamports freed by migrating to the dynamic tick array
+    pub rent_savings: u64,
+}
+
+/// Emitted by `audit_tick_array`
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickArrayAuditEvent {
+    /// The pool of the tick array
+    pub pool_state: Pubkey,
+    pub tick_array: Pubkey,
+    pub audit: TickArrayAudit,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted by `repair_tick` when the growths outside of an uninitialized tick are reseeded
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TickRepairedEvent {
+    /// The pool of the tick array
+    pub pool_state: Pubkey,
+    pub tick_array: Pubkey,
+    pub tick: i32,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+    pub reward_growths_outside_x64: [u128; REWARD_NUM],
+    pub event_seq: u64,
 }
 
 impl Default for TickArrayState {
@@ -178,10 +240,12 @@ pub mod tick_array_test {
             .unwrap();
 
         for offset in initialized_tick_offsets {
-            let mut new_tick = TickState::default();
-            // Indicates tick is initialized
-            new_tick.liquidity_gross = 1;
-            new_tick.tick = start_index + (offset * tick_spacing as usize) as i32;
+            let new_tick = TickState {
+                // Indicates tick is initialized
+                liquidity_gross: 1,
+                tick: start_index + (offset * tick_spacing as usize) as i32,
+                ..Default::default()
+            };
             new_tick_array.ticks[offset] = new_tick;
         }
         RefCell::new(new_tick_array)
@@ -209,10 +273,12 @@ pub mod tick_array_test {
     }
 
     pub fn build_tick(tick: i32, liquidity_gross: u128, liquidity_net: i128) -> RefCell<TickState> {
-        let mut new_tick = TickState::default();
-        new_tick.tick = tick;
-        new_tick.liquidity_gross = liquidity_gross;
-        new_tick.liquidity_net = liquidity_net;
+        let new_tick = TickState {
+            tick,
+            liquidity_gross,
+            liquidity_net,
+            ..Default::default()
+        };
         RefCell::new(new_tick)
     }
 
@@ -222,14 +288,17 @@ pub mod tick_array_test {
         fee_growth_outside_1_x64: u128,
         reward_growths_outside_x64: u128,
     ) -> RefCell<TickState> {
-        let mut new_tick = TickState::default();
-        new_tick.tick = tick;
-        new_tick.fee_growth_outside_0_x64 = fee_growth_outside_0_x64;
-        new_tick.fee_growth_outside_1_x64 = fee_growth_outside_1_x64;
-        new_tick.reward_growths_outside_x64 = [reward_growths_outside_x64, 0, 0];
+        let new_tick = TickState {
+            tick,
+            fee_growth_outside_0_x64,
+            fee_growth_outside_1_x64,
+            reward_growths_outside_x64: [reward_growths_outside_x64, 0, 0],
+            ..Default::default()
+        };
         RefCell::new(new_tick)
     }
 
+    #[allow(clippy::module_inception)]
     mod tick_array_test {
         use super::*;
         use crate::libraries::tick_math;
@@ -265,6 +334,60 @@ pub mod tick_array_test {
             );
         }
 
+        #[test]
+        fn usable_tick_test() {
+            assert_eq!(TickUtils::min_usable_tick(1), tick_math::MIN_TICK);
+            assert_eq!(TickUtils::max_usable_tick(1), tick_math::MAX_TICK);
+            for (tick_spacing, max_usable_tick) in [
+                (1, 443636),
+                (2, 443636),
+                (4, 443636),
+                (5, 443635),
+                (8, 443632),
+                (10, 443630),
+                (16, 443632),
+                (20, 443620),
+                (30, 443610),
+                (50, 443600),
+                (60, 443580),
+                (64, 443584),
+                (100, 443600),
+                (120, 443520),
+                (128, 443520),
+                (200, 443600),
+                (256, 443392),
+            ] {
+                assert_eq!(TickUtils::max_usable_tick(tick_spacing), max_usable_tick);
+                assert_eq!(TickUtils::min_usable_tick(tick_spacing), -max_usable_tick);
+            }
+
+            for tick_spacing in 1..=u16::MAX {
+                let min_usable_tick = TickUtils::min_usable_tick(tick_spacing);
+                let max_usable_tick = TickUtils::max_usable_tick(tick_spacing);
+                TickUtils::check_ticks_order(min_usable_tick, max_usable_tick).unwrap();
+                TickUtils::check_tick_array_start_index(
+                    TickUtils::get_array_start_index(min_usable_tick, tick_spacing),
+                    min_usable_tick,
+                    tick_spacing,
+                )
+                .unwrap();
+                TickUtils::check_tick_array_start_index(
+                    TickUtils::get_array_start_index(max_usable_tick, tick_spacing),
+                    max_usable_tick,
+                    tick_spacing,
+                )
+                .unwrap();
+                // one more tick spacing is out of the boundary
+                let tick_spacing = i32::from(tick_spacing);
+                assert!(TickState::check_is_out_of_boundary(
+                    min_usable_tick - tick_spacing
+                ));
+                assert!(TickState::check_is_out_of_boundary(
+                    max_usable_tick + tick_spacing
+                ));
+            }
+        }
+
         #[test]
         fn next_tick_arrary_start_index_test() {
             let tick_spacing = 15;
@@ -424,11 +547,49 @@ pub mod tick_array_test {
             next_tick_state = tick_array.next_initialized_tick(-10, 15, false).unwrap();
             assert!(next_tick_state.is_none());
         }
+
+        #[test]
+        fn audit_test() {
+            let rent = Rent::default();
+            // the counter is not updated by the builder
+            let tick_array = build_fix_tick_array(-900, 15, vec![0, 5, 59]);
+            let audit = tick_array.borrow().audit(&rent);
+            assert_eq!(audit.start_tick_index, -900);
+            assert_eq!(audit.initialized_tick_count, 0);
+            assert_eq!(audit.scanned_initialized_tick_count, 3);
+            assert_eq!(
+                audit.dyn_data_len as usize,
+                DynTickArrayState::HEADER_LEN + 3 * TickState::LEN
+            );
+            assert_eq!(
+                audit.rent_savings,
+                rent.minimum_balance(TickArrayState::LEN)
+                    - rent.minimum_balance(audit.dyn_data_len as usize)
+            );
+
+            // an empty tick array still needs the first TickState of a dynamic tick array
+            let audit = build_fix_tick_array(-900, 15, vec![]).borrow().audit(&rent);
+            assert_eq!(audit.scanned_initialized_tick_count, 0);
+            assert_eq!(
+                audit.dyn_data_len as usize,
+                DynTickArrayState::FIRST_CREATE_LEN
+            );
+
+            // a full tick array saves nothing
+            let audit = build_fix_tick_array(-900, 15, (0..TICK_ARRAY_SIZE_USIZE).collect())
+                .borrow()
+                .audit(&rent);
+            assert_eq!(
+                audit.scanned_initialized_tick_count,
+                TICK_ARRAY_SIZE_USIZE as u8
+            );
+            assert_eq!(audit.rent_savings, 0);
+        }
     }
 
     mod get_fee_growth_inside_test {
         use super::*;
-        use crate::states::*;
+        
 
         fn fee_growth_inside_delta_when_price_move(
             init_fee_growth_global_0_x64: u128,
@@ -452,22 +613,26 @@ pub mod tick_array_test {
                 );
 
             if fee_growth_global_0_x64 != 0 {
-                fee_growth_global_0_x64 = fee_growth_global_0_x64 + fee_growth_global_delta;
+                fee_growth_global_0_x64 += fee_growth_global_delta;
             }
             if fee_growth_global_1_x64 != 0 {
-                fee_growth_global_1_x64 = fee_growth_global_1_x64 + fee_growth_global_delta;
+                fee_growth_global_1_x64 += fee_growth_global_delta;
             }
             if cross_tick_lower {
                 tick_lower.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             } else {
                 tick_upper.cross(
                     fee_growth_global_0_x64,
                     fee_growth_global_1_x64,
                     &[RewardInfo::default(); 3],
+                    0,
+                    0,
                 );
             }
 
@@ -762,7 +927,7 @@ pub mod tick_array_test {
 
     mod get_reward_growths_inside_test {
         use super::*;
-        use crate::states::*;
+        
         use anchor_lang::prelude::Pubkey;
 
         fn build_reward_infos(reward_growth_global_x64: u128) -> [RewardInfo; 3] {
@@ -794,11 +959,11 @@ pub mod tick_array_test {
                 &build_reward_infos(reward_growth_global_x64),
             )[0];
 
-            reward_growth_global_x64 = reward_growth_global_x64 + reward_growth_global_delta;
+            reward_growth_global_x64 += reward_growth_global_delta;
             if cross_tick_lower {
-                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_lower.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             } else {
-                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64));
+                tick_upper.cross(0, 0, &build_reward_infos(reward_growth_global_x64), 0, 0);
             }
 
             tick_current = target_tick_current;
@@ -948,9 +1113,240 @@ pub mod tick_array_test {
             assert_eq!(reward_frowth_inside_delta, 500);
         }
     }
+
+    mod maker_rebate_test {
+        use super::*;
+        use crate::instructions::calculate_latest_token_fees;
+
+        #[test]
+        fn accrue_maker_rebate_test() {
+            // two positions bounded by the ticks, 100 liquidity in [-10, 10], 300 liquidity in [10, 20]
+            let mut tick_lower = build_tick(-10, 100, 100).take();
+            let mut tick_middle = build_tick(10, 400, 200).take();
+            let tick_upper = build_tick(20, 300, -300).take();
+            let position_0_last =
+                TickUtils::get_maker_rebate_growth_boundary(&tick_lower, &tick_middle);
+            let position_1_last =
+                TickUtils::get_maker_rebate_growth_boundary(&tick_middle, &tick_upper);
+
+            // a zero_for_one swap crosses tick 10 then tick -10
+            tick_middle.accrue_maker_rebate(4000, true);
+            tick_lower.accrue_maker_rebate(1000, true);
+
+            let position_0_latest =
+                TickUtils::get_maker_rebate_growth_boundary(&tick_lower, &tick_middle);
+            let position_1_latest =
+                TickUtils::get_maker_rebate_growth_boundary(&tick_middle, &tick_upper);
+            // token_1 is not touched
+            assert_eq!(position_0_latest[1], position_0_last[1]);
+            assert_eq!(
+                calculate_latest_token_fees(0, position_0_last[0], position_0_latest[0], 100),
+                1000 + 1000
+            );
+            assert_eq!(
+                calculate_latest_token_fees(0, position_1_last[0], position_1_latest[0], 300),
+                3000
+            );
+
+            // a cleared tick starts over
+            tick_lower.clear();
+            assert_eq!(
+                TickUtils::get_maker_rebate_growth_boundary(&tick_lower, &tick_upper),
+                [0, 0]
+            );
+            // no rebate without liquidity
+            tick_lower.accrue_maker_rebate(1000, false);
+            assert_eq!({ tick_lower.maker_rebate_growth_1_x64 }, 0);
+        }
+    }
+
+    mod seed_growths_outside_test {
+        use super::*;
+
+        #[test]
+        fn seed_growths_outside_test() {
+            let reward_infos = [
+                RewardInfo {
+                    token_mint: Pubkey::new_unique(),
+                    reward_growth_global_x64: 300,
+                    ..Default::default()
+                },
+                RewardInfo::default(),
+                RewardInfo::default(),
+            ];
+            // allocated but never initialized ticks with stale growths outside above the global values
+            let mut tick_below = build_tick_with_fee_reward_growth(-10, 5000, 5000, 5000).take();
+            tick_below.liquidity_net = 100;
+            tick_below.seconds_per_liquidity_outside_x64 = 5000;
+            tick_below.seconds_outside = 5000;
+            let mut tick_above = build_tick_with_fee_reward_growth(10, 5000, 5000, 5000).take();
+
+            assert!(tick_below.seed_growths_outside(0, 1000, 2000, &reward_infos));
+            assert_eq!({ tick_below.fee_growth_outside_0_x64 }, 1000);
+            assert_eq!({ tick_below.fee_growth_outside_1_x64 }, 2000);
+            assert_eq!({ tick_below.reward_growths_outside_x64 }, [300, 0, 0]);
+            assert_eq!({ tick_below.liquidity_net }, 0);
+            assert_eq!({ tick_below.seconds_per_liquidity_outside_x64 }, 0);
+            assert_eq!({ tick_below.seconds_outside }, 0);
+
+            assert!(tick_above.seed_growths_outside(0, 1000, 2000, &reward_infos));
+            assert_eq!({ tick_above.fee_growth_outside_0_x64 }, 0);
+            assert_eq!({ tick_above.fee_growth_outside_1_x64 }, 0);
+            assert_eq!({ tick_above.reward_growths_outside_x64 }, [0, 0, 0]);
+
+            // crossing the seeded tick doesn't underflow and adds no liquidity
+            assert_eq!(tick_above.cross(1000, 2000, &reward_infos, 0, 0), 0);
+            assert_eq!({ tick_above.fee_growth_outside_0_x64 }, 1000);
+            assert_eq!({ tick_above.fee_growth_outside_1_x64 }, 2000);
+            assert_eq!({ tick_above.reward_growths_outside_x64 }, [300, 0, 0]);
+
+            // an initialized tick is left unchanged, even with growths outside above the global values
+            let mut tick_initialized = build_tick_with_fee_reward_growth(10, 50, 60, 5000).take();
+            tick_initialized.liquidity_gross = 100;
+            tick_initialized.liquidity_net = -100;
+            assert!(!tick_initialized.seed_growths_outside(0, 1000, 2000, &reward_infos));
+            assert_eq!({ tick_initialized.fee_growth_outside_0_x64 }, 50);
+            assert_eq!({ tick_initialized.fee_growth_outside_1_x64 }, 60);
+            assert_eq!({ tick_initialized.liquidity_net }, -100);
+            assert_eq!({ tick_initialized.reward_growths_outside_x64 }, [5000, 0, 0]);
+        }
+    }
+
+    mod seconds_inside_test {
+        use super::*;
+
+        #[test]
+        fn get_seconds_per_liquidity_inside_test() {
+            let reward_infos = [RewardInfo::default(); 3];
+            let tick_lower = &mut build_tick(-10, 0, 0).take();
+            let tick_upper = &mut build_tick(10, 0, 0).take();
+            // initialized at time 100 with the price between the ticks
+            tick_lower
+                .update(0, 100, 0, 0, false, &reward_infos, u128::MAX, 1000, 100)
+                .unwrap();
+            tick_upper
+                .update(0, 100, 0, 0, true, &reward_infos, u128::MAX, 1000, 100)
+                .unwrap();
+            assert_eq!({ tick_lower.seconds_per_liquidity_outside_x64 }, 1000);
+            assert_eq!({ tick_lower.seconds_outside }, 100);
+            assert_eq!({ tick_upper.seconds_per_liquidity_outside_x64 }, 0);
+            assert_eq!({ tick_upper.seconds_outside }, 0);
+
+            assert_eq!(
+                TickUtils::get_seconds_per_liquidity_inside(tick_lower, tick_upper, 0, 1500),
+                500
+            );
+            assert_eq!(TickUtils::get_seconds_inside(tick_lower, tick_upper, 0, 150), 50);
+
+            // the price leaves the range above at time 150, the values inside stop growing
+            tick_upper.cross(0, 0, &reward_infos, 1500, 150);
+            assert_eq!(
+                TickUtils::get_seconds_per_liquidity_inside(tick_lower, tick_upper, 20, 2000),
+                500
+            );
+            assert_eq!(TickUtils::get_seconds_inside(tick_lower, tick_upper, 20, 200), 50);
+
+            // and grow again once the price is back at time 300
+            tick_upper.cross(0, 0, &reward_infos, 2500, 300);
+            assert_eq!(
+                TickUtils::get_seconds_per_liquidity_inside(tick_lower, tick_upper, 0, 3000),
+                1000
+            );
+            assert_eq!(TickUtils::get_seconds_inside(tick_lower, tick_upper, 0, 350), 100);
+
+            tick_lower.clear();
+            assert_eq!({ tick_lower.seconds_per_liquidity_outside_x64 }, 0);
+            assert_eq!({ tick_lower.seconds_outside }, 0);
+        }
+    }
+
+    mod liquidity_change_test {
+        use super::*;
+        use crate::error::ErrorCode;
+
+        #[test]
+        fn max_liquidity_per_tick_test() {
+            assert_eq!(TickUtils::max_liquidity_per_tick(1), u128::MAX / 887273);
+            assert_eq!(TickUtils::max_liquidity_per_tick(10), u128::MAX / 88727);
+            assert_eq!(TickUtils::max_liquidity_per_tick(60), u128::MAX / 14787);
+            assert_eq!(TickUtils::max_liquidity_per_tick(200), u128::MAX / 4437);
+        }
+
+        #[test]
+        fn apply_liquidity_change_test() {
+            let tick_state = &mut build_tick(60, 0, 0).take();
+            tick_state.apply_liquidity_change(100, false, 1000).unwrap();
+            tick_state.apply_liquidity_change(300, true, 1000).unwrap();
+            assert_eq!({ tick_state.liquidity_gross }, 400);
+            assert_eq!({ tick_state.liquidity_net }, -200);
+
+            // above the max liquidity per tick, the tick is unchanged
+            assert_eq!(
+                tick_state.apply_liquidity_change(601, false, 1000).err(),
+                Some(ErrorCode::TickLiquidityOverflow.into())
+            );
+            assert_eq!({ tick_state.liquidity_gross }, 400);
+            assert_eq!({ tick_state.liquidity_net }, -200);
+
+            // removing liquidity is not limited
+            tick_state.apply_liquidity_change(-300, true, 100).unwrap();
+            assert_eq!({ tick_state.liquidity_gross }, 100);
+            assert_eq!({ tick_state.liquidity_net }, 100);
+
+            // liquidity_net overflow
+            let tick_state = &mut build_tick(60, 1, i128::MIN + 1).take();
+            assert_eq!(
+                tick_state.apply_liquidity_change(2, true, u128::MAX).err(),
+                Some(ErrorCode::TickLiquidityOverflow.into())
+            );
+        }
+    }
+
     mod tick_array_layout_test {
         use super::*;
         use anchor_lang::Discriminator;
+        #[test]
+        fn core_layout_test() {
+            use byreal_clmm_core::states::{self as core_states, AccountLayout};
+
+            let mut tick_array = TickArrayState::default();
+            for (i, byte) in bytemuck::bytes_of_mut(&mut tick_array).iter_mut().enumerate() {
+                *byte = (i % 251) as u8 + 1;
+            }
+            let mut data = TickArrayState::DISCRIMINATOR.to_vec();
+            data.extend_from_slice(bytemuck::bytes_of(&tick_array));
+            let layout = core_states::TickArrayState::try_from_account_data(&data).unwrap();
+            assert_core_layout!(
+                tick_array,
+                layout,
+                pool_id,
+                start_tick_index,
+                initialized_tick_count,
+                recent_epoch,
+                padding,
+            );
+            // the program TickState is not Pod, the ticks are compared one by one
+            let tick_state = tick_array.ticks[7];
+            let layout_tick_state = { layout.ticks }[7];
+            assert_core_layout!(
+                tick_state,
+                layout_tick_state,
+                tick,
+                liquidity_net,
+                liquidity_gross,
+                fee_growth_outside_0_x64,
+                fee_growth_outside_1_x64,
+                reward_growths_outside_x64,
+                maker_rebate_growth_0_x64,
+                maker_rebate_growth_1_x64,
+                seconds_per_liquidity_outside_x64,
+                seconds_outside,
+            );
+            assert_eq!(core_states::TickArrayState::LEN, TickArrayState::LEN);
+            assert_eq!(core_states::TICK_ARRAY_SIZE_USIZE, TICK_ARRAY_SIZE_USIZE);
+            assert_eq!(core_states::TICK_ARRAY_SIZE, TICK_ARRAY_SIZE);
+        }
+
         #[test]
         fn test_tick_array_layout() {
             let pool_id = Pubkey::new_unique();
@@ -974,15 +1370,10 @@ pub mod tick_array_test {
                 0x11223344556600778899aabbccddeeff,
                 0x11223344556677008899aabbccddeeff,
             ];
-            let mut tick_padding: [u32; 13] = [0u32; 13];
-            let mut tick_padding_data = [0u8; 4 * 13];
-            let mut offset = 0;
-            for i in 0..13 {
-                tick_padding[i] = u32::MAX - 3 * i as u32;
-                tick_padding_data[offset..offset + 4]
-                    .copy_from_slice(&tick_padding[i].to_le_bytes());
-                offset += 4;
-            }
+            let maker_rebate_growth_0_x64: u128 = 0x11223344556677880099aabbccddeeff;
+            let maker_rebate_growth_1_x64: u128 = 0x11223344556677889900aabbccddeeff;
+            let seconds_per_liquidity_outside_x64: u128 = 0x1122334455667788990aabbccddeeff0;
+            let seconds_outside: u32 = 0x12345670;
 
             let mut tick_data = [0u8; TickState::LEN];
             let mut offset = 0;
@@ -996,20 +1387,29 @@ pub mod tick_array_test {
             offset += 16;
             tick_data[offset..offset + 16].copy_from_slice(&fee_growth_outside_1_x64.to_le_bytes());
             offset += 16;
-            for i in 0..REWARD_NUM {
+            for reward_growth_outside_x64 in reward_growths_outside_x64 {
                 tick_data[offset..offset + 16]
-                    .copy_from_slice(&reward_growths_outside_x64[i].to_le_bytes());
+                    .copy_from_slice(&reward_growth_outside_x64.to_le_bytes());
                 offset += 16;
             }
-            tick_data[offset..offset + 4 * 13].copy_from_slice(&tick_padding_data);
-            offset += 4 * 13;
+            tick_data[offset..offset + 16]
+                .copy_from_slice(&maker_rebate_growth_0_x64.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 16]
+                .copy_from_slice(&maker_rebate_growth_1_x64.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 16]
+                .copy_from_slice(&seconds_per_liquidity_outside_x64.to_le_bytes());
+            offset += 16;
+            tick_data[offset..offset + 4].copy_from_slice(&seconds_outside.to_le_bytes());
+            offset += 4;
             assert_eq!(offset, tick_data.len());
             assert_eq!(tick_data.len(), core::mem::size_of::<TickState>());
 
             // serialize original data
             let mut tick_array_data = [0u8; TickArrayState::LEN];
             let mut offset = 0;
-            tick_array_data[offset..offset + 8].copy_from_slice(&TickArrayState::DISCRIMINATOR);
+            tick_array_data[offset..offset + 8].copy_from_slice(TickArrayState::DISCRIMINATOR);
             offset += 8;
             tick_array_data[offset..offset + 32].copy_from_slice(&pool_id.to_bytes());
             offset += 32;
@@ -1060,8 +1460,18 @@ pub mod tick_array_test {
                     unpack_reward_growths_outside_x64,
                     reward_growths_outside_x64
                 );
-                let unpack_tick_padding = tick_item.padding;
-                assert_eq!(unpack_tick_padding, tick_padding);
+                let unpack_maker_rebate_growth_0_x64 = tick_item.maker_rebate_growth_0_x64;
+                assert_eq!(unpack_maker_rebate_growth_0_x64, maker_rebate_growth_0_x64);
+                let unpack_maker_rebate_growth_1_x64 = tick_item.maker_rebate_growth_1_x64;
+                assert_eq!(unpack_maker_rebate_growth_1_x64, maker_rebate_growth_1_x64);
+                let unpack_seconds_per_liquidity_outside_x64 =
+                    tick_item.seconds_per_liquidity_outside_x64;
+                assert_eq!(
+                    unpack_seconds_per_liquidity_outside_x64,
+                    seconds_per_liquidity_outside_x64
+                );
+                let unpack_seconds_outside = tick_item.seconds_outside;
+                assert_eq!(unpack_seconds_outside, seconds_outside);
             }
             let unpack_initialized_tick_count = unpack_data.initialized_tick_count;
             assert_eq!(unpack_initialized_tick_count, initialized_tick_count);
@@ -8,8 +8,9 @@ use std::ops::DerefMut;
 
 use crate::error::ErrorCode as ClmmErrorCode;
 use crate::states::{
-    DynTickArrayLoader, DynTickArrayState, PoolState, TickArrayState, TickState, TickUtils,
-    TICK_ARRAY_SEED,
+    DynTickArrayLoader, DynTickArrayState, PoolState, TickArrayArchivedEvent, TickArrayCapacity,
+    TickArrayResizedEvent, TickArrayState, TickRentTreasury, TickState, TickUtils, TICK_ARRAY_SEED,
+    TICK_ARRAY_SIZE_USIZE,
 };
 use crate::util::*;
 
@@ -25,6 +26,32 @@ pub enum TickArrayContainerRefMut<'info> {
     Dynamic((RefMut<'info, DynTickArrayState>, RefMut<'info, [TickState]>)),
 }
 
+/// Iterator over the initialized ticks of a tick array, fixed or dynamic, in the order a swap crosses them:
+/// from the highest tick down if `zero_for_one`, otherwise from the lowest tick up.
+/// The ticks are indexed by their offset in the tick array on the stack, nothing is allocated.
+pub struct InitializedTicksIterMut<'a> {
+    ticks: std::array::IntoIter<Option<&'a mut TickState>, TICK_ARRAY_SIZE_USIZE>,
+    zero_for_one: bool,
+}
+
+impl<'a> Iterator for InitializedTicksIterMut<'a> {
+    type Item = &'a mut TickState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let tick_state = if self.zero_for_one {
+                self.ticks.next_back()?
+            } else {
+                self.ticks.next()?
+            };
+            match tick_state {
+                Some(tick_state) if tick_state.is_initialized() => return Some(tick_state),
+                _ => continue,
+            }
+        }
+    }
+}
+
 impl TickArrayContainer<'_> {
     /// Get mutable reference to the underlying TickArrayState or (DynTickArrayState, [TickState])
     pub fn get_ref_mut(&self) -> Result<TickArrayContainerRefMut<'_>> {
@@ -64,7 +91,7 @@ impl TickArrayContainer<'_> {
 
         let disc_bytes = array_ref![data, 0, 8];
 
-        if disc_bytes == DynTickArrayState::DISCRIMINATOR {
+        let tick_array = if disc_bytes == DynTickArrayState::DISCRIMINATOR {
             let (header, ticks) = RefMut::map_split(data, |data_slice| {
                 let (header_bytes, ticks_bytes) =
                     data_slice.split_at_mut(DynTickArrayState::HEADER_LEN);
@@ -83,34 +110,59 @@ impl TickArrayContainer<'_> {
                 return Err(ErrorCode::AccountDidNotDeserialize.into());
             }
 
-            Ok(TickArrayContainerRefMut::Dynamic((header, ticks)))
+            TickArrayContainerRefMut::Dynamic((header, ticks))
         } else if disc_bytes == TickArrayState::DISCRIMINATOR {
+            if data_len < TickArrayState::LEN {
+                return Err(ErrorCode::AccountDidNotDeserialize.into());
+            }
             let tick_array = RefMut::map(data, |data| {
                 bytemuck::from_bytes_mut(
                     &mut data.deref_mut()[8..mem::size_of::<TickArrayState>() + 8],
                 )
             });
 
-            Ok(TickArrayContainerRefMut::Fixed(tick_array))
+            TickArrayContainerRefMut::Fixed(tick_array)
         } else {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
-        }
+        };
+
+        // a tick array owned by the program must also be at the PDA of its pool and start tick index
+        check_tick_array_address(&tick_array.key(), acc_info.key)?;
+        Ok(tick_array)
     }
 }
 
+/// Check the address of a tick array account is the PDA re-derived from the pool id and start tick index it stores.
+/// The compare doesn't short-circuit on the first different byte.
+pub fn check_tick_array_address(expected: &Pubkey, actual: &Pubkey) -> Result<()> {
+    let diff = expected
+        .as_ref()
+        .iter()
+        .zip(actual.as_ref())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    require!(diff == 0, ClmmErrorCode::InvalidTickArrayAddress);
+    Ok(())
+}
+
 /// static methods
 impl<'info> TickArrayContainer<'info> {
     /// Load a TickArrayState of type AccountLoader from tickarray account info, if tickarray account does not exist, then create it.
     /// `access_tick_index` is the tick index that will be accessed in this tick array, in dynamic tick array, may be have to allocate more space to store TickState.
     /// `tick_array_start_index` is the start index of this tick array
+    /// `tick_rent_treasury` if provided, the rent of creating or growing the tick array is drawn from it first, the payer covers the shortfall.
+    /// `pool_state` is the loaded `pool_state_loader`, used to stamp the emitted events.
+    /// `prealloc_tick_count` the TickState slots allocated when a dynamic tick array is created, `pool_state.prealloc_tick_count` if none.
     pub fn get_or_create_tick_array(
         payer: AccountInfo<'info>,
         tick_array_account_info: AccountInfo<'info>,
         system_program: AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
         pool_state_loader: &AccountLoader<'info, PoolState>,
+        pool_state: &mut PoolState,
         tick_array_start_index: i32,
         access_tick_index: i32,
         tick_spacing: u16,
+        prealloc_tick_count: Option<u8>,
     ) -> Result<Self> {
         require!(
             TickUtils::check_is_valid_start_index(tick_array_start_index, tick_spacing),
@@ -122,16 +174,34 @@ impl<'info> TickArrayContainer<'info> {
         );
 
         if tick_array_account_info.owner == &system_program::ID {
+            if pool_state.use_fixed_tick_array() {
+                let tick_array_loader = Self::create_fixed_tick_array_account(
+                    payer,
+                    tick_array_account_info,
+                    system_program,
+                    tick_rent_treasury,
+                    pool_state_loader,
+                    tick_array_start_index,
+                    access_tick_index,
+                    tick_spacing,
+                )?;
+                pool_state.add_tick_array();
+                return Ok(TickArrayContainer::Fixed(tick_array_loader));
+            }
             let tick_array_state_loader = Self::create_dyn_tick_array_account(
                 payer,
                 tick_array_account_info,
                 system_program,
+                tick_rent_treasury,
                 pool_state_loader,
+                pool_state,
                 tick_array_start_index,
                 access_tick_index,
                 tick_spacing,
+                prealloc_tick_count.unwrap_or(pool_state.prealloc_tick_count),
             )?;
-            return Ok(TickArrayContainer::Dynamic(tick_array_state_loader));
+            pool_state.add_tick_array();
+            Ok(TickArrayContainer::Dynamic(tick_array_state_loader))
         } else {
             // If the account is already initialized, just load it.
             // check account owner first
@@ -153,7 +223,7 @@ impl<'info> TickArrayContainer<'info> {
                     tick_spacing,
                 )?;
 
-                return Ok(TickArrayContainer::Fixed(tick_array_loader));
+                Ok(TickArrayContainer::Fixed(tick_array_loader))
             } else if Self::is_match_discriminator(
                 &tick_array_account_info,
                 DynTickArrayState::DISCRIMINATOR,
@@ -163,17 +233,19 @@ impl<'info> TickArrayContainer<'info> {
                     payer,
                     tick_array_account_info,
                     system_program,
+                    tick_rent_treasury,
                     pool_state_loader,
+                    pool_state,
                     tick_array_start_index,
                     access_tick_index,
                     tick_spacing,
                 )?;
 
-                return Ok(TickArrayContainer::Dynamic(dyn_tick_array_loader));
+                Ok(TickArrayContainer::Dynamic(dyn_tick_array_loader))
             } else {
-                return Err(ErrorCode::AccountDiscriminatorMismatch.into());
-            };
-        };
+                Err(ErrorCode::AccountDiscriminatorMismatch.into())
+            }
+        }
     }
 
     /// Try to load a TickArrayState of type AccountLoader or DynTickArrayLoader from tickarray account info
@@ -266,7 +338,51 @@ impl<'info> TickArrayContainer<'info> {
         Ok(TickArrayContainer::Dynamic(dyn_tick_array_loader))
     }
 
-    /// Try to load a TickArrayState of type AccountLoader or DynTickArrayLoader from tickarray account info without checking access_tick_index
+    /// Same as `try_from`, and check the tick array belongs to `pool_id` and is at its PDA.
+    /// The growths outside of its ticks are only meaningful with the growths global of its own pool.
+    pub fn try_from_pool(
+        tick_array_account_info: &AccountInfo<'info>,
+        pool_id: &Pubkey,
+        access_tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<TickArrayContainer<'info>> {
+        let tick_array = Self::try_from(tick_array_account_info, access_tick_index, tick_spacing)?;
+        require_keys_eq!(tick_array.get_pool_id()?, *pool_id);
+        check_tick_array_address(&tick_array.key()?, tick_array_account_info.key)?;
+        Ok(tick_array)
+    }
+
+    /// Same as `try_from`, for adding liquidity to an existing position of `pool_state_loader`.
+    /// The TickState of a position tick released by `free_one_tick` once its liquidity was removed is allocated again,
+    /// in a reserved slot, or by growing the account with the rent left in it, topped up from the tick rent
+    /// treasury if provided. There is no payer here, `get_or_create_tick_array` allocates it if the rent is short.
+    /// `pool_state` is the loaded `pool_state_loader`, used to stamp the emitted event.
+    pub fn try_from_to_add_liquidity(
+        tick_array_account_info: &AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+        pool_state_loader: &AccountLoader<'info, PoolState>,
+        pool_state: &mut PoolState,
+        access_tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<TickArrayContainer<'info>> {
+        if tick_array_account_info.owner == &crate::id()
+            && Self::is_match_discriminator(
+                tick_array_account_info,
+                DynTickArrayState::DISCRIMINATOR,
+            )?
+        {
+            Self::reallocate_released_tick(
+                tick_array_account_info,
+                tick_rent_treasury,
+                pool_state_loader,
+                pool_state,
+                access_tick_index,
+                tick_spacing,
+            )?;
+        }
+        Self::try_from(tick_array_account_info, access_tick_index, tick_spacing)
+    }
+
     /// This function is mainly used in decrease_liquidity_v2 instruction, where access_tick_index is not known
     /// after loading, will NOT check if the access_tick_index is in
     pub fn try_from_without_check(
@@ -292,8 +408,56 @@ impl<'info> TickArrayContainer<'info> {
 
             Ok(TickArrayContainer::Dynamic(dyn_tick_array_loader))
         } else {
-            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+            Err(ErrorCode::AccountDiscriminatorMismatch.into())
+        }
+    }
+
+    /// Pre-allocate `count` TickStates in a dynamic tick array with one reallocation,
+    /// so that later positions touching new ticks in this tick array don't have to grow the account one by one.
+    /// `pool_state` is the loaded `pool_state_loader`, used to stamp the emitted event.
+    pub fn reserve_ticks(
+        payer: AccountInfo<'info>,
+        tick_array_account_info: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+        pool_state_loader: &AccountLoader<'info, PoolState>,
+        pool_state: &mut PoolState,
+        count: u8,
+    ) -> Result<DynTickArrayLoader<'info>> {
+        let dyn_tick_array_loader = DynTickArrayLoader::try_from(&tick_array_account_info)?;
+
+        let new_account_space;
+        {
+            let (mut dyn_tick_header, _) = dyn_tick_array_loader.load_mut(false)?;
+            require_keys_eq!(
+                dyn_tick_header.pool_id,
+                pool_state_loader.key(),
+                ClmmErrorCode::InvalidTickArray
+            );
+            dyn_tick_header.reserve_ticks(count)?;
+            new_account_space = dyn_tick_header.all_data_len();
+        }
+        DynTickArrayState::check_realloc_len(new_account_space)?;
+
+        let treasury_rent = Self::prepay_rent_from_treasury(
+            tick_rent_treasury,
+            &tick_array_account_info,
+            pool_state_loader.key(),
+            new_account_space,
+        )?;
+        realloc_account_if_needed(
+            &tick_array_account_info,
+            new_account_space,
+            &payer,
+            &system_program,
+        )?;
+        {
+            let (mut dyn_tick_header, _) = dyn_tick_array_loader.load_mut(false)?;
+            dyn_tick_header.add_treasury_rent(treasury_rent);
+            TickArrayResizedEvent::emit_from(&dyn_tick_header, pool_state.next_event_seq());
         }
+
+        Ok(dyn_tick_array_loader)
     }
 
     /// Read the discriminator of an account
@@ -316,23 +480,33 @@ impl<'info> TickArrayContainer<'info> {
 
 /// private static functions
 impl<'info> TickArrayContainer<'info> {
-    /// Create a dynamic TickArray account, and initialize the access_tick_index in this tick array.
+    /// Create a dynamic TickArray account with `prealloc_tick_count` TickStates, at least one,
+    /// and initialize the access_tick_index in this tick array. The TickStates not used yet are reserved.
     fn create_dyn_tick_array_account(
         payer: AccountInfo<'info>,
         tick_array_account_info: AccountInfo<'info>,
         system_program: AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
         pool_state_loader: &AccountLoader<'info, PoolState>,
+        pool_state: &mut PoolState,
         tick_array_start_index: i32,
         access_tick_index: i32,
         tick_spacing: u16,
+        prealloc_tick_count: u8,
     ) -> Result<DynTickArrayLoader<'info>> {
-        #[cfg(all(feature = "localnet", feature = "enable-log"))]
-        msg!(
-            "create_dyn_tick_array_account, tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}",
+        log_debug!(
+            ix = "create_dyn_tick_array_account",
+            pool = pool_state_loader.key(),
+            "tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}, prealloc_tick_count: {}",
             tick_array_start_index,
             access_tick_index,
-            tick_spacing
+            tick_spacing,
+            prealloc_tick_count
         );
+        let prealloc_tick_count = prealloc_tick_count.max(1);
+        let account_space = DynTickArrayState::HEADER_LEN
+            + usize::from(prealloc_tick_count) * TickState::LEN;
+        DynTickArrayState::check_realloc_len(account_space)?;
 
         // If the account is not initialized, create it. check PDA first
         let (expect_pda_address, bump) = Pubkey::find_program_address(
@@ -345,6 +519,14 @@ impl<'info> TickArrayContainer<'info> {
         );
         require_keys_eq!(expect_pda_address, tick_array_account_info.key());
 
+        // prefund the account from the treasury, `create_or_allocate_account` only asks the payer for the shortfall
+        let treasury_rent = Self::prepay_rent_from_treasury(
+            tick_rent_treasury,
+            &tick_array_account_info,
+            pool_state_loader.key(),
+            account_space,
+        )?;
+
         // in new version of clmm, we only create dynamic tick array account
         create_or_allocate_account(
             &crate::id(),
@@ -357,7 +539,7 @@ impl<'info> TickArrayContainer<'info> {
                 &tick_array_start_index.to_be_bytes(),
                 &[bump],
             ],
-            DynTickArrayState::FIRST_CREATE_LEN,
+            account_space,
         )?;
 
         let tick_array_state_loader =
@@ -365,20 +547,96 @@ impl<'info> TickArrayContainer<'info> {
         {
             let (mut dyn_tick_header, mut dyn_tick_states) = tick_array_state_loader.load_init()?;
 
-            require_eq!(dyn_tick_states.len(), 1);
+            require_eq!(dyn_tick_states.len(), usize::from(prealloc_tick_count));
 
             dyn_tick_header.initialize(
                 tick_array_start_index,
                 tick_spacing,
                 pool_state_loader.key(),
             )?;
+            dyn_tick_header.reserve_ticks(prealloc_tick_count)?;
+            dyn_tick_header.add_treasury_rent(treasury_rent);
             let tick_state_index = dyn_tick_header.use_one_tick(access_tick_index, tick_spacing)?;
             dyn_tick_states[tick_state_index as usize].tick = access_tick_index;
+            TickArrayResizedEvent::emit_from(&dyn_tick_header, pool_state.next_event_seq());
         }
 
         Ok(tick_array_state_loader)
     }
 
+    /// Create a fixed TickArray account for the pools with `PoolState::use_fixed_tick_array`, and initialize the access_tick_index in it.
+    fn create_fixed_tick_array_account(
+        payer: AccountInfo<'info>,
+        tick_array_account_info: AccountInfo<'info>,
+        system_program: AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+        pool_state_loader: &AccountLoader<'info, PoolState>,
+        tick_array_start_index: i32,
+        access_tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<AccountLoad<'info, TickArrayState>> {
+        log_debug!(
+            ix = "create_fixed_tick_array_account",
+            pool = pool_state_loader.key(),
+            "tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}",
+            tick_array_start_index,
+            access_tick_index,
+            tick_spacing
+        );
+
+        let (expect_pda_address, bump) = Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_state_loader.key().as_ref(),
+                &tick_array_start_index.to_be_bytes(),
+            ],
+            &crate::id(),
+        );
+        require_keys_eq!(expect_pda_address, tick_array_account_info.key());
+
+        Self::prepay_rent_from_treasury(
+            tick_rent_treasury,
+            &tick_array_account_info,
+            pool_state_loader.key(),
+            TickArrayState::LEN,
+        )?;
+
+        create_or_allocate_account(
+            &crate::id(),
+            payer,
+            system_program,
+            tick_array_account_info.clone(),
+            &[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_state_loader.key().as_ref(),
+                &tick_array_start_index.to_be_bytes(),
+                &[bump],
+            ],
+            TickArrayState::LEN,
+        )?;
+
+        let tick_array_loader = AccountLoad::<TickArrayState>::try_from_unchecked(
+            &crate::id(),
+            &tick_array_account_info,
+        )?;
+        {
+            let mut tick_array = tick_array_loader.load_init()?;
+            tick_array.initialize(
+                tick_array_start_index,
+                tick_spacing,
+                pool_state_loader.key(),
+            )?;
+            let offset_in_array = TickUtils::get_tick_offset_in_tick_array(
+                tick_array_start_index,
+                access_tick_index,
+                tick_spacing,
+            )?;
+            tick_array.ticks[offset_in_array].tick = access_tick_index;
+        }
+
+        Ok(tick_array_loader)
+    }
+
     /// Check and load a fixed TickArray account, and initialize the access_tick_index in this tick array if not initialized.
     fn check_and_load_fix_tick_array_account(
         tick_array_account_info: AccountInfo<'info>,
@@ -387,9 +645,10 @@ impl<'info> TickArrayContainer<'info> {
         access_tick_index: i32,
         tick_spacing: u16,
     ) -> Result<AccountLoad<'info, TickArrayState>> {
-        #[cfg(all(feature = "localnet", feature = "enable-log"))]
-        msg!(
-            "check_and_load_fix_tick_array_account, tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}",
+        log_debug!(
+            ix = "check_and_load_fix_tick_array_account",
+            pool = pool_state_loader.key(),
+            "tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}",
             tick_array_start_index,
             access_tick_index,
             tick_spacing
@@ -432,14 +691,17 @@ impl<'info> TickArrayContainer<'info> {
         payer: AccountInfo<'info>,
         tick_array_account_info: AccountInfo<'info>,
         system_program: AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
         pool_state_loader: &AccountLoader<'info, PoolState>,
+        pool_state: &mut PoolState,
         tick_array_start_index: i32,
         access_tick_index: i32,
         tick_spacing: u16,
     ) -> Result<DynTickArrayLoader<'info>> {
-        #[cfg(all(feature = "localnet", feature = "enable-log"))]
-        msg!(
-            "check_and_load_dyn_tick_array_account, tick_array_account: {}, tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}",
+        log_debug!(
+            ix = "check_and_load_dyn_tick_array_account",
+            pool = pool_state_loader.key(),
+            "tick_array_account: {}, tick_array_start_index: {}, access_tick_index:{}, tick_spacing: {}",
             tick_array_account_info.key.to_string(),
             tick_array_start_index,
             access_tick_index,
@@ -450,6 +712,7 @@ impl<'info> TickArrayContainer<'info> {
         let dyn_tick_array_loader = DynTickArrayLoader::try_from(&tick_array_account_info)?;
 
         let mut need_add_one_more_tick_state = false;
+        let mut has_reserved_tick_state = false;
         let tick_array_account_size;
         {
             let (dyn_tick_header, _) = dyn_tick_array_loader.load()?;
@@ -474,14 +737,31 @@ impl<'info> TickArrayContainer<'info> {
             if dyn_tick_header.tick_offset_index[offset_in_array] == 0 {
                 // we need to initialize this tick state, so has to add one more tick state
                 need_add_one_more_tick_state = true;
+                has_reserved_tick_state = dyn_tick_header.reserved_tick_count > 0;
             }
             tick_array_account_size = dyn_tick_header.all_data_len();
             require_eq!(tick_array_account_size, tick_array_account_info.data_len())
         }
 
-        if need_add_one_more_tick_state {
+        if need_add_one_more_tick_state && has_reserved_tick_state {
+            // a pre-allocated TickState is available, no need to reallocate
+            {
+                let (mut dyn_tick_header, mut dyn_tick_state) =
+                    dyn_tick_array_loader.load_mut(false)?;
+                let array_index = dyn_tick_header.use_one_tick(access_tick_index, tick_spacing)?;
+                dyn_tick_state[array_index as usize].tick = access_tick_index;
+            }
+            Ok(dyn_tick_array_loader)
+        } else if need_add_one_more_tick_state {
             // reallocate the account to add one more TickState
             let new_account_space = tick_array_account_size + TickState::LEN;
+            DynTickArrayState::check_realloc_len(new_account_space)?;
+            let treasury_rent = Self::prepay_rent_from_treasury(
+                tick_rent_treasury,
+                &tick_array_account_info,
+                pool_state_loader.key(),
+                new_account_space,
+            )?;
             realloc_account_if_needed(
                 &tick_array_account_info,
                 new_account_space,
@@ -493,9 +773,11 @@ impl<'info> TickArrayContainer<'info> {
             {
                 let (mut dyn_tick_header, mut dyn_tick_state) =
                     new_dyn_tick_array_loader.load_mut(true)?;
+                dyn_tick_header.add_treasury_rent(treasury_rent);
 
                 let array_index = dyn_tick_header.use_one_tick(access_tick_index, tick_spacing)?;
                 dyn_tick_state[array_index as usize].tick = access_tick_index;
+                TickArrayResizedEvent::emit_from(&dyn_tick_header, pool_state.next_event_seq());
                 // !这里只是开辟 TickState 空间，并在header中标记该tick已被使用，具体的 TickState 初始化留到后续使用时进行
             }
 
@@ -504,6 +786,123 @@ impl<'info> TickArrayContainer<'info> {
             Ok(dyn_tick_array_loader)
         }
     }
+
+    /// Allocate the TickState of `access_tick_index` in a dynamic tick array if it has been released,
+    /// nothing happens if it is still allocated
+    fn reallocate_released_tick(
+        tick_array_account_info: &AccountInfo<'info>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+        pool_state_loader: &AccountLoader<'info, PoolState>,
+        pool_state: &mut PoolState,
+        access_tick_index: i32,
+        tick_spacing: u16,
+    ) -> Result<()> {
+        let dyn_tick_array_loader = DynTickArrayLoader::try_from(tick_array_account_info)?;
+        let has_reserved_tick_state;
+        let mut treasury_rent = 0;
+        {
+            let (dyn_tick_header, _) = dyn_tick_array_loader.load()?;
+            require_keys_eq!(
+                dyn_tick_header.pool_id,
+                pool_state_loader.key(),
+                ClmmErrorCode::InvalidTickArray
+            );
+            TickUtils::check_tick_array_start_index(
+                dyn_tick_header.start_tick_index,
+                access_tick_index,
+                tick_spacing,
+            )?;
+            let offset_in_array = TickUtils::get_tick_offset_in_tick_array(
+                dyn_tick_header.start_tick_index,
+                access_tick_index,
+                tick_spacing,
+            )?;
+            if dyn_tick_header.tick_offset_index[offset_in_array] != 0 {
+                return Ok(());
+            }
+            has_reserved_tick_state = dyn_tick_header.reserved_tick_count > 0;
+        }
+
+        if !has_reserved_tick_state {
+            let new_account_space = tick_array_account_info.data_len() + TickState::LEN;
+            DynTickArrayState::check_realloc_len(new_account_space)?;
+            treasury_rent = Self::prepay_rent_from_treasury(
+                tick_rent_treasury,
+                tick_array_account_info,
+                pool_state_loader.key(),
+                new_account_space,
+            )?;
+            require_gte!(
+                tick_array_account_info.lamports(),
+                get_rent()?.minimum_balance(new_account_space),
+                ClmmErrorCode::TickArrayRentShortfall
+            );
+            dyn_tick_array_loader.resize(new_account_space)?;
+        }
+
+        let (mut dyn_tick_header, mut dyn_tick_states) =
+            dyn_tick_array_loader.load_mut(!has_reserved_tick_state)?;
+        dyn_tick_header.add_treasury_rent(treasury_rent);
+        let array_index = dyn_tick_header.use_one_tick(access_tick_index, tick_spacing)?;
+        dyn_tick_states[array_index as usize].tick = access_tick_index;
+        TickArrayResizedEvent::emit_from(&dyn_tick_header, pool_state.next_event_seq());
+        Ok(())
+    }
+
+    /// Top up the tick array account from the pool's tick rent treasury, so that it is rent exempt for `space`.
+    /// Nothing happens if the treasury is not provided or is empty.
+    /// Returns the lamports drawn, a dynamic tick array records them in `DynTickArrayState::treasury_rent`.
+    fn prepay_rent_from_treasury(
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+        tick_array_account_info: &AccountInfo<'info>,
+        pool_id: Pubkey,
+        space: usize,
+    ) -> Result<u64> {
+        let Some(treasury_info) = tick_rent_treasury else {
+            return Ok(0);
+        };
+        let required_lamports = get_rent()?
+            .minimum_balance(space)
+            .saturating_sub(tick_array_account_info.lamports());
+        TickRentTreasury::draw_rent(
+            treasury_info,
+            tick_array_account_info,
+            pool_id,
+            required_lamports,
+        )
+    }
+
+    /// Move the lamports of a shrunk tick array above its rent exemption back to who paid for its growth,
+    /// up to `treasury_rent` to the tick rent treasury and the rest to `rent_recipient`.
+    /// Returns the treasury rent still held by the account.
+    fn return_freed_rent(
+        tick_array_account_info: &AccountInfo<'info>,
+        pool_id: Pubkey,
+        treasury_rent: u64,
+        rent_recipient: Option<&AccountInfo<'info>>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+    ) -> Result<u64> {
+        let freed_lamports = tick_array_account_info
+            .lamports()
+            .saturating_sub(get_rent()?.minimum_balance(tick_array_account_info.data_len()));
+        let treasury_share = freed_lamports.min(treasury_rent);
+        let mut treasury_rent_left = treasury_rent;
+        if let Some(treasury_info) = tick_rent_treasury {
+            TickRentTreasury::return_rent(
+                treasury_info,
+                tick_array_account_info,
+                pool_id,
+                treasury_share,
+            )?;
+            treasury_rent_left -= treasury_share;
+        }
+        if let Some(rent_recipient) = rent_recipient {
+            let payer_share = freed_lamports - treasury_share;
+            **tick_array_account_info.try_borrow_mut_lamports()? -= payer_share;
+            **rent_recipient.try_borrow_mut_lamports()? += payer_share;
+        }
+        Ok(treasury_rent_left)
+    }
 }
 
 /// member methods for non-mutable reference
@@ -564,6 +963,22 @@ impl<'info> TickArrayContainer<'info> {
         Ok(initialized_tick_count)
     }
 
+    /// get the space usage of this tick array, a fixed tick array always has all TickStates allocated
+    pub fn get_capacity(&self) -> Result<TickArrayCapacity> {
+        let capacity = match self {
+            TickArrayContainer::Fixed(loader) => {
+                let tick_array = loader.load()?;
+                TickArrayCapacity::fixed(tick_array.initialized_tick_count)
+            }
+            TickArrayContainer::Dynamic(loader) => {
+                let (header, _) = loader.load()?;
+                header.capacity()
+            }
+        };
+
+        Ok(capacity)
+    }
+
     /// get the start tick index of this tick array
     pub fn get_start_tick_index(&self) -> Result<i32> {
         let start_tick_index = match self {
@@ -579,10 +994,74 @@ impl<'info> TickArrayContainer<'info> {
 
         Ok(start_tick_index)
     }
+
+    /// Get a copy of the TickState of `tick_index`, without borrowing the account mutably
+    pub fn get_tick_state(&self, tick_index: i32, tick_spacing: u16) -> Result<TickState> {
+        match self {
+            TickArrayContainer::Fixed(loader) => {
+                let tick_array = loader.load()?;
+                let offset = tick_array.get_tick_offset_in_array(tick_index, tick_spacing)?;
+                Ok(tick_array.ticks[offset])
+            }
+            TickArrayContainer::Dynamic(loader) => {
+                let (header, tick_states) = loader.load()?;
+                let offset = header.get_tick_index_in_array(tick_index, tick_spacing)?;
+                Ok(tick_states[offset as usize])
+            }
+        }
+    }
+
+    /// Release the TickState of the uninitialized `tick_index` from a dynamic tick array and shrink the account
+    /// by one TickState, a fixed tick array keeps all its TickStates.
+    /// The rent freed by the shrink goes back to who paid for the growth: the part drawn from the tick rent treasury
+    /// to the treasury, the rest to `rent_recipient`. A share whose account is not provided stays in the tick array,
+    /// for its next growth or until `close_tick_array`.
+    /// `pool_state` is used to stamp the emitted event.
+    pub fn free_one_tick(
+        &self,
+        tick_index: i32,
+        tick_spacing: u16,
+        pool_state: &mut PoolState,
+        rent_recipient: Option<&AccountInfo<'info>>,
+        tick_rent_treasury: Option<&'info AccountInfo<'info>>,
+    ) -> Result<()> {
+        let TickArrayContainer::Dynamic(dyn_tick_array_loader) = self else {
+            return Ok(());
+        };
+        let (new_account_space, pool_id, treasury_rent) = {
+            let (mut dyn_tick_header, mut dyn_tick_states) =
+                dyn_tick_array_loader.load_mut(false)?;
+            let released_tick_state =
+                dyn_tick_header.free_one_tick(&mut dyn_tick_states, tick_index, tick_spacing)?;
+            TickArrayArchivedEvent::emit_from(
+                &dyn_tick_header,
+                &[released_tick_state],
+                false,
+                pool_state.next_event_seq(),
+            );
+            (
+                dyn_tick_header.all_data_len(),
+                dyn_tick_header.pool_id,
+                dyn_tick_header.treasury_rent,
+            )
+        };
+        dyn_tick_array_loader.resize(new_account_space)?;
+
+        let treasury_rent_left = Self::return_freed_rent(
+            &dyn_tick_array_loader.to_account_info(),
+            pool_id,
+            treasury_rent,
+            rent_recipient,
+            tick_rent_treasury,
+        )?;
+        let (mut dyn_tick_header, _) = dyn_tick_array_loader.load_mut(false)?;
+        dyn_tick_header.treasury_rent = treasury_rent_left;
+        Ok(())
+    }
 }
 
 /// member methods for non-mutable reference
-impl<'info> TickArrayContainerRefMut<'_> {
+impl TickArrayContainerRefMut<'_> {
     /// Get the Pubkey of this tick array account
     pub fn key(&self) -> Pubkey {
         let (pool_id, start_tick_index) = match self {
@@ -640,16 +1119,21 @@ impl TickArrayContainerRefMut<'_> {
         tick_index: i32,
         tick_spacing: u16,
     ) -> Result<&mut TickState> {
-        match self {
+        let tick_state = match self {
             TickArrayContainerRefMut::Fixed(tick_array) => {
-                Ok(tick_array.get_tick_state_mut(tick_index, tick_spacing)?)
+                tick_array.get_tick_state_mut(tick_index, tick_spacing)?
             }
             TickArrayContainerRefMut::Dynamic((header, states)) => {
                 let index = header.get_tick_index_in_array(tick_index, tick_spacing)? as usize;
 
-                Ok(&mut states[index])
+                &mut states[index]
             }
+        };
+        // an uninitialized TickState may have been left at the old tick spacing of a pool moved by `PoolState::retier`
+        if !tick_state.is_initialized() {
+            tick_state.tick = tick_index;
         }
+        Ok(tick_state)
     }
 
     /// Update the TickState for a given tick_index in this tick array
@@ -695,6 +1179,38 @@ impl TickArrayContainerRefMut<'_> {
         Ok(())
     }
 
+    /// The initialized ticks of this tick array in traversal order, see `InitializedTicksIterMut`
+    pub fn iter_initialized_ticks(&mut self, zero_for_one: bool) -> InitializedTicksIterMut<'_> {
+        let mut ticks: [Option<&mut TickState>; TICK_ARRAY_SIZE_USIZE] =
+            std::array::from_fn(|_| None);
+        match self {
+            TickArrayContainerRefMut::Fixed(tick_array) => {
+                for (offset, tick_state) in tick_array.ticks.iter_mut().enumerate() {
+                    ticks[offset] = Some(tick_state);
+                }
+            }
+            TickArrayContainerRefMut::Dynamic((header, states)) => {
+                // the TickStates are stored in allocation order, map each of them back to its offset
+                let tick_offset_index = header.tick_offset_index;
+                let mut slot_offsets = [None; TICK_ARRAY_SIZE_USIZE];
+                for (offset, slot) in tick_offset_index.iter().enumerate() {
+                    if *slot > 0 {
+                        slot_offsets[usize::from(*slot) - 1] = Some(offset);
+                    }
+                }
+                for (slot, tick_state) in states.iter_mut().enumerate() {
+                    if let Some(Some(offset)) = slot_offsets.get(slot) {
+                        ticks[*offset] = Some(tick_state);
+                    }
+                }
+            }
+        }
+        InitializedTicksIterMut {
+            ticks: ticks.into_iter(),
+            zero_for_one,
+        }
+    }
+
     /// Get next initialized tick in tick array, `current_tick_index` can be any tick index, in other words, `current_tick_index` not exactly a point in the tickarray,
     /// and current_tick_index % tick_spacing maybe not equal zero.
     /// If price move to left tick <= current_tick_index, or to right tick > current_tick_index
@@ -704,47 +1220,169 @@ impl TickArrayContainerRefMut<'_> {
         tick_spacing: u16,
         zero_for_one: bool,
     ) -> Result<Option<&mut TickState>> {
-        match self {
-            TickArrayContainerRefMut::Fixed(tick_array) => {
-                tick_array.next_initialized_tick(current_tick_index, tick_spacing, zero_for_one)
-            }
-            TickArrayContainerRefMut::Dynamic((header, states)) => {
-                let index = header.next_initialized_tick_index(
-                    &states,
-                    current_tick_index,
-                    tick_spacing,
-                    zero_for_one,
-                )?;
-
-                if let Some(i) = index {
-                    Ok(Some(&mut states[i as usize]))
+        if TickUtils::get_array_start_index(current_tick_index, tick_spacing)
+            != self.get_start_tick_index()
+        {
+            return Ok(None);
+        }
+        Ok(self
+            .iter_initialized_ticks(zero_for_one)
+            .find(|tick_state| {
+                if zero_for_one {
+                    tick_state.tick <= current_tick_index
                 } else {
-                    Ok(None)
+                    tick_state.tick > current_tick_index
                 }
-            }
-        }
+            }))
     }
 
     /// Base on swap directioin, return the first initialized tick in the tick array.
     pub fn first_initialized_tick(&mut self, zero_for_one: bool) -> Result<&mut TickState> {
-        match self {
-            TickArrayContainerRefMut::Fixed(tick_array) => {
-                tick_array.first_initialized_tick(zero_for_one)
-            }
-            TickArrayContainerRefMut::Dynamic((header, states)) => {
-                let index = header.first_initialized_tick_index(&states, zero_for_one)? as usize;
-
-                Ok(&mut states[index])
-            }
-        }
+        self.iter_initialized_ticks(zero_for_one)
+            .next()
+            .ok_or_else(|| error!(ClmmErrorCode::InvalidTickArray))
     }
 }
 
 #[cfg(test)]
 mod tick_array_container_tests {
     use super::*;
-    use crate::libraries::mock_anchor_account_info_v3;
+    use crate::libraries::{mock_account_info, mock_anchor_account_info_v3};
+    use crate::states::dyn_tick_array_test::{
+        build_dyn_tick_array, build_dyn_tick_array_with_tick_states, DynamicTickArrayBuildType,
+    };
+    use crate::states::tick_array_test::{
+        build_fix_tick_array, build_fix_tick_array_with_tick_states,
+    };
     use anchor_lang::solana_program::pubkey::Pubkey;
+    use std::convert::identity;
+
+    fn initialized_ticks(
+        tick_array: &mut TickArrayContainerRefMut,
+        zero_for_one: bool,
+    ) -> Vec<i32> {
+        tick_array
+            .iter_initialized_ticks(zero_for_one)
+            .map(|tick_state| identity(tick_state.tick))
+            .collect()
+    }
+
+    #[test]
+    fn iter_initialized_ticks_test() {
+        let tick_spacing = 15;
+        // initialized ticks [-900, -855, -300, -15]
+        let offsets = vec![0, 3, 40, 59];
+        let expected = vec![-900, -855, -300, -15];
+        let expected_rev: Vec<i32> = expected.iter().rev().copied().collect();
+
+        let fixed = build_fix_tick_array(-900, tick_spacing, offsets.clone());
+        let mut tick_array = TickArrayContainerRefMut::Fixed(fixed.borrow_mut());
+        assert_eq!(initialized_ticks(&mut tick_array, false), expected);
+        assert_eq!(initialized_ticks(&mut tick_array, true), expected_rev);
+
+        for build_type in [
+            DynamicTickArrayBuildType::FromStartIndex,
+            DynamicTickArrayBuildType::FromEndIndex,
+            DynamicTickArrayBuildType::RandomIndex,
+        ] {
+            let (header, ticks) =
+                build_dyn_tick_array(-900, tick_spacing, build_type, offsets.clone());
+            // an allocated but uninitialized tick is skipped
+            ticks.borrow_mut()[1].liquidity_gross = 0;
+            let uninitialized_tick = ticks.borrow()[1].tick;
+            let expected: Vec<i32> = expected
+                .iter()
+                .copied()
+                .filter(|tick| *tick != uninitialized_tick)
+                .collect();
+            let expected_rev: Vec<i32> = expected.iter().rev().copied().collect();
+
+            let mut tick_array = TickArrayContainerRefMut::Dynamic((
+                header.borrow_mut(),
+                RefMut::map(ticks.borrow_mut(), |ticks| ticks.as_mut_slice()),
+            ));
+            assert_eq!(initialized_ticks(&mut tick_array, false), expected);
+            assert_eq!(initialized_ticks(&mut tick_array, true), expected_rev);
+            assert_eq!(
+                identity(tick_array.first_initialized_tick(false).unwrap().tick),
+                expected[0]
+            );
+            assert_eq!(
+                identity(tick_array.first_initialized_tick(true).unwrap().tick),
+                expected_rev[0]
+            );
+            // the ticks can be mutated through the iterator
+            for tick_state in tick_array.iter_initialized_ticks(true) {
+                tick_state.liquidity_net = 1;
+            }
+            assert!(ticks_liquidity_net_set(&tick_array, &expected));
+        }
+    }
+
+    fn ticks_liquidity_net_set(tick_array: &TickArrayContainerRefMut, ticks: &[i32]) -> bool {
+        match tick_array {
+            TickArrayContainerRefMut::Dynamic((_, states)) => states
+                .iter()
+                .filter(|tick_state| ticks.contains(&identity(tick_state.tick)))
+                .all(|tick_state| identity(tick_state.liquidity_net) == 1),
+            TickArrayContainerRefMut::Fixed(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn get_tick_state_mut_stamps_uninitialized_tick_test() {
+        let tick_spacing = 15;
+        let fixed = build_fix_tick_array(-900, tick_spacing, vec![0, 3]);
+        // an uninitialized TickState left at the tick of another tick spacing
+        fixed.borrow_mut().ticks[3].liquidity_gross = 0;
+        fixed.borrow_mut().ticks[3].tick = -870;
+        let mut tick_array = TickArrayContainerRefMut::Fixed(fixed.borrow_mut());
+        let tick_state = tick_array.get_tick_state_mut(-855, tick_spacing).unwrap();
+        assert_eq!(identity(tick_state.tick), -855);
+        assert_eq!(
+            identity(tick_array.get_tick_state_mut(-900, tick_spacing).unwrap().tick),
+            -900
+        );
+    }
+
+    #[test]
+    fn next_initialized_tick_test() {
+        let tick_spacing = 15;
+        // initialized ticks [0, 30, 105]
+        for build_type in [
+            DynamicTickArrayBuildType::FromStartIndex,
+            DynamicTickArrayBuildType::FromEndIndex,
+            DynamicTickArrayBuildType::RandomIndex,
+        ] {
+            let (header, ticks) = build_dyn_tick_array(0, tick_spacing, build_type, vec![0, 2, 7]);
+            let fixed = build_fix_tick_array(0, tick_spacing, vec![0, 2, 7]);
+            let mut tick_arrays = [
+                TickArrayContainerRefMut::Dynamic((
+                    header.borrow_mut(),
+                    RefMut::map(ticks.borrow_mut(), |ticks| ticks.as_mut_slice()),
+                )),
+                TickArrayContainerRefMut::Fixed(fixed.borrow_mut()),
+            ];
+            for tick_array in tick_arrays.iter_mut() {
+                let mut next = |current: i32, zero_for_one: bool| {
+                    tick_array
+                        .next_initialized_tick(current, tick_spacing, zero_for_one)
+                        .unwrap()
+                        .map(|tick_state| identity(tick_state.tick))
+                };
+                assert_eq!(next(0, true), Some(0));
+                assert_eq!(next(29, true), Some(0));
+                assert_eq!(next(30, true), Some(30));
+                assert_eq!(next(200, true), Some(105));
+                assert_eq!(next(0, false), Some(30));
+                assert_eq!(next(30, false), Some(105));
+                assert_eq!(next(105, false), None);
+                // another tick array
+                assert_eq!(next(-1, true), None);
+                assert_eq!(next(900, false), None);
+            }
+        }
+    }
 
     #[test]
     fn test_is_match_discriminator() {
@@ -769,4 +1407,338 @@ mod tick_array_container_tests {
         .unwrap();
         assert!(!is_fixed);
     }
+
+    #[test]
+    fn test_load_data_mut_check_address() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let tick_array = TickArrayState {
+            pool_id,
+            start_tick_index: -3600,
+            ..Default::default()
+        };
+        let (pda, _) = Pubkey::find_program_address(
+            &[
+                TICK_ARRAY_SEED.as_bytes(),
+                pool_id.as_ref(),
+                &(-3600i32).to_be_bytes(),
+            ],
+            &owner,
+        );
+
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&pda, &owner, &tick_array, None);
+        assert!(TickArrayContainer::load_data_mut(&account_info).is_ok());
+
+        // a tick array of the pool, but not at its PDA
+        let spoofed_key = Pubkey::new_unique();
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&spoofed_key, &owner, &tick_array, None);
+        assert_eq!(
+            TickArrayContainer::load_data_mut(&account_info).err(),
+            Some(ClmmErrorCode::InvalidTickArrayAddress.into())
+        );
+    }
+
+    #[test]
+    fn test_try_from_pool() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let other_pool_id = Pubkey::new_unique();
+        let tick_spacing = 10;
+        let tick_state = TickState {
+            tick: -100,
+            liquidity_gross: 1,
+            ..Default::default()
+        };
+        let tick_array_pda = |pool_id: &Pubkey| {
+            Pubkey::find_program_address(
+                &[
+                    TICK_ARRAY_SEED.as_bytes(),
+                    pool_id.as_ref(),
+                    &(-600i32).to_be_bytes(),
+                ],
+                &owner,
+            )
+            .0
+        };
+
+        let tick_array =
+            build_fix_tick_array_with_tick_states(pool_id, -600, tick_spacing, vec![tick_state]);
+        let key = tick_array_pda(&pool_id);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        assert!(
+            TickArrayContainer::try_from_pool(&account_info, &pool_id, -100, tick_spacing).is_ok()
+        );
+
+        // a tick array of the pool, but not at its PDA
+        let spoofed_key = Pubkey::new_unique();
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&spoofed_key, &owner, &*tick_array.borrow(), None);
+        assert_eq!(
+            TickArrayContainer::try_from_pool(&account_info, &pool_id, -100, tick_spacing).err(),
+            Some(ClmmErrorCode::InvalidTickArrayAddress.into())
+        );
+
+        // a tick array at the same start index of another pool
+        let tick_array = build_fix_tick_array_with_tick_states(
+            other_pool_id,
+            -600,
+            tick_spacing,
+            vec![tick_state],
+        );
+        let key = tick_array_pda(&other_pool_id);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &*tick_array.borrow(), None);
+        assert_eq!(
+            TickArrayContainer::try_from_pool(&account_info, &pool_id, -100, tick_spacing).err(),
+            Some(ErrorCode::RequireKeysEqViolated.into())
+        );
+    }
+
+    /// A dynamic tick array of `pool_id` whose tick -100 has been released by `free_one_tick` when the last
+    /// position on it was emptied, with `reserved_tick_count` reserved TickStates left
+    fn build_released_tick_array(
+        pool_id: Pubkey,
+        reserved_tick_count: u8,
+    ) -> (DynTickArrayState, Vec<TickState>) {
+        let tick_spacing = 10;
+        let tick_states = [-100, -50]
+            .iter()
+            .map(|tick| TickState {
+                tick: *tick,
+                liquidity_gross: 1,
+                ..Default::default()
+            })
+            .collect();
+        let (header, ticks) = build_dyn_tick_array_with_tick_states(
+            pool_id,
+            -600,
+            tick_spacing,
+            DynamicTickArrayBuildType::FromStartIndex,
+            tick_states,
+        );
+        let mut header = header.into_inner();
+        let mut ticks = ticks.into_inner();
+        ticks[0].liquidity_gross = 0;
+        header.initialized_tick_count = 1;
+        header
+            .free_one_tick(&mut ticks, -100, tick_spacing)
+            .unwrap();
+        // the account is shrunk by the released TickState
+        ticks.pop();
+        if reserved_tick_count > 0 {
+            header.reserve_ticks(reserved_tick_count).unwrap();
+            ticks.extend(vec![TickState::default(); usize::from(reserved_tick_count)]);
+        }
+        (header, ticks)
+    }
+
+    #[test]
+    fn try_from_to_add_liquidity_released_tick_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let tick_spacing = 10;
+        let pool_state = PoolState::default();
+        let (pool_info, _pool_lamports, _pool_data) =
+            mock_anchor_account_info_v3(&pool_id, &owner, &pool_state, None);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&pool_info).unwrap();
+        let mut pool_state = pool_state;
+
+        let (header, ticks) = build_released_tick_array(pool_id, 1);
+        let key = Pubkey::new_unique();
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &header, Some(bytemuck::cast_slice(&ticks)));
+        // the position emptied by decrease_liquidity can't be loaded to add liquidity back
+        assert_eq!(
+            TickArrayContainer::try_from(&account_info, -100, tick_spacing).err(),
+            Some(ClmmErrorCode::InvalidTickIndex.into())
+        );
+
+        // the released tick is allocated again in the reserved TickState
+        let tick_array = TickArrayContainer::try_from_to_add_liquidity(
+            &account_info,
+            None,
+            &pool_state_loader,
+            &mut pool_state,
+            -100,
+            tick_spacing,
+        )
+        .unwrap();
+        let tick_state = tick_array.get_tick_state(-100, tick_spacing).unwrap();
+        assert_eq!(identity(tick_state.tick), -100);
+        assert!(!tick_state.is_initialized());
+        assert_eq!(
+            identity(tick_array.get_tick_state(-50, tick_spacing).unwrap().tick),
+            -50
+        );
+        {
+            let TickArrayContainer::Dynamic(loader) = &tick_array else {
+                unreachable!()
+            };
+            let (header, _) = loader.load().unwrap();
+            assert_eq!({ header.alloc_tick_count }, 2);
+            assert_eq!({ header.reserved_tick_count }, 0);
+        }
+
+        // loading it again doesn't allocate anything
+        assert!(TickArrayContainer::try_from_to_add_liquidity(
+            &account_info,
+            None,
+            &pool_state_loader,
+            &mut pool_state,
+            -100,
+            tick_spacing,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn try_from_to_add_liquidity_rent_shortfall_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let tick_spacing = 10;
+        let pool_state = PoolState::default();
+        let (pool_info, _pool_lamports, _pool_data) =
+            mock_anchor_account_info_v3(&pool_id, &owner, &pool_state, None);
+        let pool_state_loader = AccountLoader::<PoolState>::try_from(&pool_info).unwrap();
+        let mut pool_state = pool_state;
+
+        // no reserved TickState and no lamports left to grow the account
+        let (header, ticks) = build_released_tick_array(pool_id, 0);
+        let key = Pubkey::new_unique();
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &header, Some(bytemuck::cast_slice(&ticks)));
+        assert_eq!(
+            TickArrayContainer::try_from_to_add_liquidity(
+                &account_info,
+                None,
+                &pool_state_loader,
+                &mut pool_state,
+                -100,
+                tick_spacing,
+            )
+            .err(),
+            Some(ClmmErrorCode::TickArrayRentShortfall.into())
+        );
+        // a tick array of another pool is not touched
+        let (header, ticks) = build_released_tick_array(Pubkey::new_unique(), 1);
+        let (account_info, _lamports_box, _data_box) =
+            mock_anchor_account_info_v3(&key, &owner, &header, Some(bytemuck::cast_slice(&ticks)));
+        assert_eq!(
+            TickArrayContainer::try_from_to_add_liquidity(
+                &account_info,
+                None,
+                &pool_state_loader,
+                &mut pool_state,
+                -100,
+                tick_spacing,
+            )
+            .err(),
+            Some(ClmmErrorCode::InvalidTickArray.into())
+        );
+    }
+
+    #[test]
+    fn return_freed_rent_test() {
+        let owner = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let rent = get_rent().unwrap();
+        let data_len = DynTickArrayState::HEADER_LEN + TickState::LEN;
+        let rent_exempt_lamports = rent.minimum_balance(data_len);
+        // the rent of the TickState released by the shrink
+        let freed_lamports = rent.minimum_balance(data_len + TickState::LEN) - rent_exempt_lamports;
+
+        let treasury_key = TickRentTreasury::key(pool_id, &owner);
+        let treasury_rent_exempt_lamports = rent.minimum_balance(TickRentTreasury::LEN);
+        let (treasury_info, _treasury_lamports, treasury_data) = mock_account_info(
+            &treasury_key,
+            &owner,
+            false,
+            true,
+            treasury_rent_exempt_lamports,
+            TickRentTreasury::LEN,
+        );
+        let mut treasury = TickRentTreasury::default();
+        treasury.deposit(255, pool_id, 0);
+        treasury
+            .try_serialize(&mut &mut treasury_data.borrow_mut()[..])
+            .unwrap();
+        let recipient_key = Pubkey::new_unique();
+        let system_program_id = Pubkey::default();
+        let (recipient_info, _recipient_lamports, _recipient_data) =
+            mock_account_info(&recipient_key, &system_program_id, true, true, 0, 0);
+
+        // the treasury paid part of the growth, it gets its part back and the payer the rest
+        let key = Pubkey::new_unique();
+        let (account_info, _lamports_box, _data_box) = mock_account_info(
+            &key,
+            &owner,
+            false,
+            true,
+            rent_exempt_lamports + freed_lamports,
+            data_len,
+        );
+        let treasury_rent_left = TickArrayContainer::return_freed_rent(
+            &account_info,
+            pool_id,
+            1000,
+            Some(&recipient_info),
+            Some(&treasury_info),
+        )
+        .unwrap();
+        assert_eq!(treasury_rent_left, 0);
+        assert_eq!(account_info.lamports(), rent_exempt_lamports);
+        assert_eq!(treasury_info.lamports(), treasury_rent_exempt_lamports + 1000);
+        assert_eq!(recipient_info.lamports(), freed_lamports - 1000);
+        let treasury =
+            TickRentTreasury::try_deserialize(&mut &treasury_data.borrow()[..]).unwrap();
+        assert_eq!(treasury.total_returned, 1000);
+
+        // without the treasury its part stays in the tick array, the payer still gets the rest
+        let (account_info, _lamports_box, _data_box) = mock_account_info(
+            &key,
+            &owner,
+            false,
+            true,
+            rent_exempt_lamports + freed_lamports,
+            data_len,
+        );
+        let treasury_rent_left = TickArrayContainer::return_freed_rent(
+            &account_info,
+            pool_id,
+            1000,
+            Some(&recipient_info),
+            None,
+        )
+        .unwrap();
+        assert_eq!(treasury_rent_left, 1000);
+        assert_eq!(account_info.lamports(), rent_exempt_lamports + 1000);
+        assert_eq!(recipient_info.lamports(), 2 * (freed_lamports - 1000));
+
+        // the treasury can't get more than what was freed
+        let (account_info, _lamports_box, _data_box) = mock_account_info(
+            &key,
+            &owner,
+            false,
+            true,
+            rent_exempt_lamports + freed_lamports,
+            data_len,
+        );
+        let treasury_rent_left = TickArrayContainer::return_freed_rent(
+            &account_info,
+            pool_id,
+            freed_lamports + 1000,
+            None,
+            Some(&treasury_info),
+        )
+        .unwrap();
+        assert_eq!(treasury_rent_left, 1000);
+        assert_eq!(account_info.lamports(), rent_exempt_lamports);
+        assert_eq!(
+            treasury_info.lamports(),
+            treasury_rent_exempt_lamports + 1000 + freed_lamports
+        );
+    }
 }
@@ -0,0 +1,391 @@
+use crate::error::ErrorCode;
+use crate::util::get_rent;
+use anchor_lang::prelude::*;
+
+pub const TICK_RENT_TREASURY_SEED: &str = "tick_rent_treasury";
+
+/// Lamport budget prepaid by the pool creator, used to pay the rent of dynamic tick arrays
+/// when they are created or grown, instead of charging the instruction payer.
+/// The pool owner can withdraw the lamports above the rent exemption of the treasury.
+#[account]
+#[derive(Default, Debug)]
+pub struct TickRentTreasury {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// The pool this treasury pays tick array rent for
+    pub pool_id: Pubkey,
+    /// Total lamports deposited into the treasury
+    pub total_deposited: u64,
+    /// Total lamports drawn from the treasury to pay tick array rent
+    pub total_drawn: u64,
+    /// Total lamports withdrawn from the treasury by the pool owner
+    pub total_withdrawn: u64,
+    /// Total lamports returned to the treasury by the tick arrays it paid the rent of, when they shrink
+    pub total_returned: u64,
+    pub padding: [u64; 6],
+}
+
+impl TickRentTreasury {
+    pub const LEN: usize = 8 + 1 + 32 + 8 + 8 + 8 + 56;
+
+    pub fn initialize(&mut self, bump: u8, pool_id: Pubkey) {
+        self.bump = bump;
+        self.pool_id = pool_id;
+    }
+
+    /// Account a deposit of `amount` lamports, the treasury is initialized by its first funding
+    pub fn deposit(&mut self, bump: u8, pool_id: Pubkey, amount: u64) {
+        if self.pool_id == Pubkey::default() {
+            self.initialize(bump, pool_id);
+        }
+        self.total_deposited = self.total_deposited.checked_add(amount).unwrap();
+    }
+
+    pub fn key(pool_id: Pubkey, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[TICK_RENT_TREASURY_SEED.as_bytes(), pool_id.as_ref()],
+            program_id,
+        )
+        .0
+    }
+
+    /// Find the treasury of `pool_id` in the remaining accounts, if the caller provided it.
+    pub fn find_in<'info>(
+        remaining_accounts: &'info [AccountInfo<'info>],
+        pool_id: Pubkey,
+    ) -> Option<&'info AccountInfo<'info>> {
+        let treasury_key = Self::key(pool_id, &crate::id());
+        remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == treasury_key)
+    }
+
+    /// Find the treasury of `pool_id` for an instruction allocating ticks no position needs yet.
+    /// It is only drawn from for the pool owner who funds it, any other payer pays the rent itself.
+    pub fn find_for_pool_owner<'info>(
+        remaining_accounts: &'info [AccountInfo<'info>],
+        pool_id: Pubkey,
+        pool_owner: Pubkey,
+        payer: Pubkey,
+    ) -> Option<&'info AccountInfo<'info>> {
+        if payer != pool_owner {
+            return None;
+        }
+        Self::find_in(remaining_accounts, pool_id)
+    }
+
+    /// Move up to `lamports` from the treasury to `target_account`, the treasury itself always stays rent exempt.
+    /// Returns the amount of lamports actually drawn, the caller is responsible for the shortfall.
+    pub fn draw_rent<'info>(
+        treasury_info: &'info AccountInfo<'info>,
+        target_account: &AccountInfo<'info>,
+        pool_id: Pubkey,
+        lamports: u64,
+    ) -> Result<u64> {
+        if lamports == 0 {
+            return Ok(0);
+        }
+        require_keys_eq!(
+            *treasury_info.owner,
+            crate::id(),
+            ErrorCode::IllegalAccountOwner
+        );
+        require!(treasury_info.is_writable, ErrorCode::InvalidAccount);
+
+        let mut treasury = Account::<TickRentTreasury>::try_from(treasury_info)?;
+        require_keys_eq!(treasury.pool_id, pool_id, ErrorCode::InvalidAccount);
+
+        let drawn = lamports.min(Self::surplus_lamports(treasury_info)?);
+        if drawn == 0 {
+            return Ok(0);
+        }
+
+        **treasury_info.try_borrow_mut_lamports()? -= drawn;
+        **target_account.try_borrow_mut_lamports()? += drawn;
+
+        treasury.total_drawn = treasury.total_drawn.checked_add(drawn).unwrap();
+        treasury.exit(&crate::id())?;
+
+        log_info!(
+            ix = "draw_rent",
+            pool = pool_id,
+            "target_account:{}, requested:{}, drawn:{}",
+            target_account.key,
+            lamports,
+            drawn
+        );
+
+        Ok(drawn)
+    }
+
+    /// Move `lamports` of rent drawn by `source_account` back to the treasury, once the account has shrunk.
+    /// `source_account` must be owned by this program.
+    pub fn return_rent<'info>(
+        treasury_info: &'info AccountInfo<'info>,
+        source_account: &AccountInfo<'info>,
+        pool_id: Pubkey,
+        lamports: u64,
+    ) -> Result<()> {
+        if lamports == 0 {
+            return Ok(());
+        }
+        require_keys_eq!(
+            *treasury_info.owner,
+            crate::id(),
+            ErrorCode::IllegalAccountOwner
+        );
+        require!(treasury_info.is_writable, ErrorCode::InvalidAccount);
+
+        let mut treasury = Account::<TickRentTreasury>::try_from(treasury_info)?;
+        require_keys_eq!(treasury.pool_id, pool_id, ErrorCode::InvalidAccount);
+
+        **source_account.try_borrow_mut_lamports()? -= lamports;
+        **treasury_info.try_borrow_mut_lamports()? += lamports;
+
+        treasury.total_returned = treasury.total_returned.checked_add(lamports).unwrap();
+        treasury.exit(&crate::id())?;
+
+        log_info!(
+            ix = "return_rent",
+            pool = pool_id,
+            "source_account:{}, returned:{}",
+            source_account.key,
+            lamports
+        );
+
+        Ok(())
+    }
+
+    /// The lamports of the treasury above its rent exemption, the most that can be drawn or withdrawn
+    pub fn surplus_lamports(treasury_info: &AccountInfo) -> Result<u64> {
+        let rent_exempt_lamports = get_rent()?.minimum_balance(treasury_info.data_len());
+        Ok(treasury_info
+            .lamports()
+            .saturating_sub(rent_exempt_lamports))
+    }
+}
+
+/// Emitted when the tick rent treasury of a pool is funded
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FundTickRentTreasuryEvent {
+    /// The pool whose tick array rent is prepaid
+    pub pool_state: Pubkey,
+    /// The account that funded the treasury
+    pub funder: Pubkey,
+    /// The lamports deposited in this instruction
+    pub amount: u64,
+    /// Total lamports deposited into the treasury so far
+    pub total_deposited: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+/// Emitted when the pool owner withdraws from the tick rent treasury of a pool
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct WithdrawTickRentTreasuryEvent {
+    /// The pool whose tick rent treasury is withdrawn from
+    pub pool_state: Pubkey,
+    /// The pool owner, who receives the lamports
+    pub owner: Pubkey,
+    /// The lamports withdrawn in this instruction
+    pub amount: u64,
+    /// Total lamports withdrawn from the treasury so far
+    pub total_withdrawn: u64,
+    /// The sequence number of this event in the pool, see `PoolState::event_seq`
+    pub event_seq: u64,
+}
+
+#[cfg(test)]
+mod tick_rent_treasury_test {
+    use super::*;
+    use crate::libraries::mock_account_info;
+
+    const TREASURY_LEN: usize = TickRentTreasury::LEN;
+
+    fn serialize_treasury(treasury: &TickRentTreasury, data: &mut [u8]) {
+        treasury.try_serialize(&mut &mut data[..]).unwrap();
+    }
+
+    fn deserialize_treasury(data: &[u8]) -> TickRentTreasury {
+        TickRentTreasury::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn deposit_test() {
+        let pool_id = Pubkey::new_unique();
+        let mut treasury = TickRentTreasury::default();
+        treasury.deposit(254, pool_id, 1000);
+        assert_eq!(treasury.bump, 254);
+        assert_eq!(treasury.pool_id, pool_id);
+        assert_eq!(treasury.total_deposited, 1000);
+
+        // a later funding only adds to the deposits
+        treasury.deposit(0, Pubkey::new_unique(), 500);
+        assert_eq!(treasury.bump, 254);
+        assert_eq!(treasury.pool_id, pool_id);
+        assert_eq!(treasury.total_deposited, 1500);
+    }
+
+    #[test]
+    fn treasury_layout_test() {
+        let mut data = Vec::new();
+        TickRentTreasury::default().serialize(&mut data).unwrap();
+        assert_eq!(data.len() + 8, TREASURY_LEN);
+    }
+
+    #[test]
+    fn find_for_pool_owner_test() {
+        let program_id = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let pool_owner = Pubkey::new_unique();
+        let treasury_key = TickRentTreasury::key(pool_id, &program_id);
+        let (treasury_info, _treasury_lamports, _treasury_data) =
+            mock_account_info(&treasury_key, &program_id, false, true, 0, TREASURY_LEN);
+        let remaining_accounts = vec![treasury_info];
+
+        assert_eq!(
+            TickRentTreasury::find_for_pool_owner(
+                &remaining_accounts,
+                pool_id,
+                pool_owner,
+                pool_owner
+            )
+            .map(|account_info| account_info.key()),
+            Some(treasury_key)
+        );
+        // any other payer pays the rent itself
+        assert!(TickRentTreasury::find_for_pool_owner(
+            &remaining_accounts,
+            pool_id,
+            pool_owner,
+            Pubkey::new_unique()
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn surplus_lamports_test() {
+        let program_id = crate::id();
+        let rent_exempt_lamports = get_rent().unwrap().minimum_balance(TREASURY_LEN);
+        let treasury_key = Pubkey::new_unique();
+        let (treasury_info, _treasury_lamports, _treasury_data) = mock_account_info(
+            &treasury_key,
+            &program_id,
+            false,
+            true,
+            rent_exempt_lamports + 3000,
+            TREASURY_LEN,
+        );
+        assert_eq!(
+            TickRentTreasury::surplus_lamports(&treasury_info).unwrap(),
+            3000
+        );
+
+        // the rent exemption is never withdrawable
+        **treasury_info.try_borrow_mut_lamports().unwrap() -= 3001;
+        assert_eq!(
+            TickRentTreasury::surplus_lamports(&treasury_info).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn draw_rent_test() {
+        let program_id = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let rent_exempt_lamports = get_rent().unwrap().minimum_balance(TREASURY_LEN);
+        let mut treasury = TickRentTreasury::default();
+        treasury.deposit(255, pool_id, 10_000);
+
+        let treasury_key = Pubkey::new_unique();
+        let (treasury_info, _treasury_lamports, treasury_data) = mock_account_info(
+            &treasury_key,
+            &program_id,
+            false,
+            true,
+            rent_exempt_lamports + 10_000,
+            TREASURY_LEN,
+        );
+        serialize_treasury(&treasury, &mut treasury_data.borrow_mut());
+        let target_key = Pubkey::new_unique();
+        let (target_info, _target_lamports, _target_data) =
+            mock_account_info(&target_key, &program_id, false, true, 0, 0);
+
+        assert_eq!(
+            TickRentTreasury::draw_rent(&treasury_info, &target_info, pool_id, 4000).unwrap(),
+            4000
+        );
+        assert_eq!(treasury_info.lamports(), rent_exempt_lamports + 6000);
+        assert_eq!(target_info.lamports(), 4000);
+        let treasury = deserialize_treasury(&treasury_data.borrow());
+        assert_eq!(treasury.total_deposited, 10_000);
+        assert_eq!(treasury.total_drawn, 4000);
+
+        // nothing to draw
+        assert_eq!(
+            TickRentTreasury::draw_rent(&treasury_info, &target_info, pool_id, 0).unwrap(),
+            0
+        );
+        // the treasury of another pool
+        assert_eq!(
+            TickRentTreasury::draw_rent(&treasury_info, &target_info, Pubkey::new_unique(), 4000)
+                .err(),
+            Some(ErrorCode::InvalidAccount.into())
+        );
+        assert_eq!(target_info.lamports(), 4000);
+    }
+
+    #[test]
+    fn draw_rent_shortfall_test() {
+        let program_id = crate::id();
+        let pool_id = Pubkey::new_unique();
+        let rent = get_rent().unwrap();
+        let rent_exempt_lamports = rent.minimum_balance(TREASURY_LEN);
+        let mut treasury = TickRentTreasury::default();
+        treasury.deposit(255, pool_id, 1000);
+
+        let treasury_key = Pubkey::new_unique();
+        let (treasury_info, _treasury_lamports, treasury_data) = mock_account_info(
+            &treasury_key,
+            &program_id,
+            false,
+            true,
+            rent_exempt_lamports + 1000,
+            TREASURY_LEN,
+        );
+        serialize_treasury(&treasury, &mut treasury_data.borrow_mut());
+        let target_key = Pubkey::new_unique();
+        let (target_info, _target_lamports, _target_data) =
+            mock_account_info(&target_key, &program_id, false, true, 0, 0);
+
+        // the treasury stays rent exempt, only its surplus is drawn
+        let space = 100;
+        let required_lamports = rent.minimum_balance(space);
+        assert_eq!(
+            TickRentTreasury::draw_rent(&treasury_info, &target_info, pool_id, required_lamports)
+                .unwrap(),
+            1000
+        );
+        assert_eq!(treasury_info.lamports(), rent_exempt_lamports);
+        assert_eq!(
+            deserialize_treasury(&treasury_data.borrow()).total_drawn,
+            1000
+        );
+        // the payer is asked for the shortfall when the account is created, see `create_or_allocate_account`
+        assert_eq!(
+            rent.minimum_balance(space)
+                .saturating_sub(target_info.lamports()),
+            required_lamports - 1000
+        );
+
+        // an emptied treasury draws nothing, the payer pays all the rent
+        assert_eq!(
+            TickRentTreasury::draw_rent(&treasury_info, &target_info, pool_id, required_lamports)
+                .unwrap(),
+            0
+        );
+        assert_eq!(target_info.lamports(), 1000);
+    }
+}
@@ -1,9 +1,9 @@
-#[cfg(feature = "enable-log")]
+#[cfg(feature = "enable-debug-log")]
 use std::convert::identity;
 
 use crate::error::ErrorCode;
-use crate::libraries::{liquidity_math, tick_math};
-use crate::pool::{RewardInfo, REWARD_NUM};
+use crate::libraries::{big_num::U128, fixed_point_64, full_math::MulDiv, liquidity_math, tick_math};
+use crate::states::pool::{RewardInfo, REWARD_NUM};
 use crate::states::*;
 use crate::Result;
 use anchor_lang::prelude::*;
@@ -26,12 +26,21 @@ pub struct TickState {
 
     // Reward growth per unit of liquidity like fee, array of Q64.64
     pub reward_growths_outside_x64: [u128; REWARD_NUM],
-    // Unused bytes for future upgrades.
-    pub padding: [u32; 13],
+
+    /// Maker rebate per unit of liquidity_gross, accrued to the positions bounded by this tick when a swap crosses it.
+    /// Only the difference between two checkpoints is meaningful, it is reset when the tick is cleared.
+    pub maker_rebate_growth_0_x64: u128,
+    pub maker_rebate_growth_1_x64: u128,
+
+    /// Seconds per unit of liquidity on the _other_ side of this tick (relative to the current tick), see
+    /// `PoolState::seconds_per_liquidity_cumulative_x64`. Like the fee growth, only has relative meaning
+    pub seconds_per_liquidity_outside_x64: u128,
+    /// Seconds spent on the _other_ side of this tick (relative to the current tick), only has relative meaning
+    pub seconds_outside: u32,
 }
 
 impl TickState {
-    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 + 16 + 8 + 8 + 4;
+    pub const LEN: usize = 4 + 16 + 16 + 16 + 16 + 16 * REWARD_NUM + 16 + 16 + 16 + 4;
 
     pub fn initialize(&mut self, tick: i32, tick_spacing: u16) -> Result<()> {
         if TickState::check_is_out_of_boundary(tick) {
@@ -51,9 +60,13 @@ impl TickState {
         fee_growth_global_1_x64: u128,
         upper: bool,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        max_liquidity_per_tick: u128,
+        seconds_per_liquidity_cumulative_x64: u128,
+        time: u32,
     ) -> Result<bool> {
         let liquidity_gross_before = self.liquidity_gross;
-        let liquidity_gross_after = liquidity_math::add_delta(liquidity_gross_before, liquidity_delta)?;
+        let liquidity_gross_after =
+            liquidity_math::add_delta_raw(liquidity_gross_before, liquidity_delta)?;
 
         // Either liquidity_gross_after becomes 0 (uninitialized) XOR liquidity_gross_before
         // was zero (initialized)
@@ -64,20 +77,41 @@ impl TickState {
                 self.fee_growth_outside_0_x64 = fee_growth_global_0_x64;
                 self.fee_growth_outside_1_x64 = fee_growth_global_1_x64;
                 self.reward_growths_outside_x64 = RewardInfo::get_reward_growths(reward_infos);
+                self.seconds_per_liquidity_outside_x64 = seconds_per_liquidity_cumulative_x64;
+                self.seconds_outside = time;
             }
         }
 
-        self.liquidity_gross = liquidity_gross_after;
+        self.apply_liquidity_change(liquidity_delta, upper, max_liquidity_per_tick)?;
+        Ok(flipped)
+    }
+
+    /// Apply the liquidity delta of a position bounded by this tick to liquidity_gross and liquidity_net.
+    /// An added liquidity can't take liquidity_gross above `max_liquidity_per_tick`, so that liquidity_net
+    /// and the liquidity of the pool crossing the tick can't overflow.
+    pub fn apply_liquidity_change(
+        &mut self,
+        liquidity_delta: i128,
+        upper: bool,
+        max_liquidity_per_tick: u128,
+    ) -> Result<()> {
+        let liquidity_gross = liquidity_math::add_delta_raw(self.liquidity_gross, liquidity_delta)?;
+        if liquidity_delta > 0 {
+            require_gte!(max_liquidity_per_tick, liquidity_gross, ErrorCode::TickLiquidityOverflow);
+        }
 
         // when the lower (upper) tick is crossed left to right (right to left),
         // liquidity must be added (removed)
-        self.liquidity_net = if upper {
+        let liquidity_net = if upper {
             self.liquidity_net.checked_sub(liquidity_delta)
         } else {
             self.liquidity_net.checked_add(liquidity_delta)
         }
-        .unwrap();
-        Ok(flipped)
+        .ok_or(ErrorCode::TickLiquidityOverflow)?;
+
+        self.liquidity_gross = liquidity_gross;
+        self.liquidity_net = liquidity_net;
+        Ok(())
     }
 
     /// Transitions to the current tick as needed by price movement, returning the amount of liquidity
@@ -87,6 +121,8 @@ impl TickState {
         fee_growth_global_0_x64: u128,
         fee_growth_global_1_x64: u128,
         reward_infos: &[RewardInfo; REWARD_NUM],
+        seconds_per_liquidity_cumulative_x64: u128,
+        time: u32,
     ) -> i128 {
         self.fee_growth_outside_0_x64 = fee_growth_global_0_x64
             .checked_sub(self.fee_growth_outside_0_x64)
@@ -95,6 +131,7 @@ impl TickState {
             .checked_sub(self.fee_growth_outside_1_x64)
             .unwrap();
 
+        #[allow(clippy::needless_range_loop)] // the packed tick fields can't be borrowed
         for i in 0..REWARD_NUM {
             if !reward_infos[i].initialized() {
                 continue;
@@ -105,16 +142,70 @@ impl TickState {
                 .checked_sub(self.reward_growths_outside_x64[i])
                 .unwrap();
         }
+        self.seconds_per_liquidity_outside_x64 =
+            seconds_per_liquidity_cumulative_x64.wrapping_sub(self.seconds_per_liquidity_outside_x64);
+        self.seconds_outside = time.wrapping_sub(self.seconds_outside);
 
         self.liquidity_net
     }
 
+    /// Seed the growths outside an uninitialized tick from the global values by the convention of `update`,
+    /// and clear its liquidity_net and seconds outside.
+    /// A tick allocated but never initialized may hold stale growths outside, which corrupt the growths inside once
+    /// the tick is crossed. The growths outside of an initialized tick are the checkpoints of the growths inside of
+    /// its positions, reseeding them would shift the fees and rewards of the positions.
+    /// Returns false and leaves the tick unchanged if it is initialized.
+    pub fn seed_growths_outside(
+        &mut self,
+        tick_current: i32,
+        fee_growth_global_0_x64: u128,
+        fee_growth_global_1_x64: u128,
+        reward_infos: &[RewardInfo; REWARD_NUM],
+    ) -> bool {
+        if self.is_initialized() {
+            return false;
+        }
+        if self.tick <= tick_current {
+            self.fee_growth_outside_0_x64 = fee_growth_global_0_x64;
+            self.fee_growth_outside_1_x64 = fee_growth_global_1_x64;
+            self.reward_growths_outside_x64 = RewardInfo::get_reward_growths(reward_infos);
+        } else {
+            self.fee_growth_outside_0_x64 = 0;
+            self.fee_growth_outside_1_x64 = 0;
+            self.reward_growths_outside_x64 = [0; REWARD_NUM];
+        }
+        self.seconds_per_liquidity_outside_x64 = 0;
+        self.seconds_outside = 0;
+        self.liquidity_net = 0;
+        true
+    }
+
+    /// Share `rebate_amount` of the input token among the positions bounded by this tick, by their liquidity.
+    pub fn accrue_maker_rebate(&mut self, rebate_amount: u64, zero_for_one: bool) {
+        if self.liquidity_gross == 0 {
+            return;
+        }
+        let rebate_growth_delta = U128::from(rebate_amount)
+            .mul_div_floor(U128::from(fixed_point_64::Q64), U128::from(self.liquidity_gross))
+            .unwrap()
+            .as_u128();
+        if zero_for_one {
+            self.maker_rebate_growth_0_x64 = self.maker_rebate_growth_0_x64.wrapping_add(rebate_growth_delta);
+        } else {
+            self.maker_rebate_growth_1_x64 = self.maker_rebate_growth_1_x64.wrapping_add(rebate_growth_delta);
+        }
+    }
+
     pub fn clear(&mut self) {
         self.liquidity_net = 0;
         self.liquidity_gross = 0;
         self.fee_growth_outside_0_x64 = 0;
         self.fee_growth_outside_1_x64 = 0;
         self.reward_growths_outside_x64 = [0; REWARD_NUM];
+        self.maker_rebate_growth_0_x64 = 0;
+        self.maker_rebate_growth_1_x64 = 0;
+        self.seconds_per_liquidity_outside_x64 = 0;
+        self.seconds_outside = 0;
     }
 
     pub fn is_initialized(self) -> bool {
@@ -124,7 +215,7 @@ impl TickState {
     /// Common checks for a valid tick input.
     /// A tick is valid if it lies within tick boundaries
     pub fn check_is_out_of_boundary(tick: i32) -> bool {
-        tick < tick_math::MIN_TICK || tick > tick_math::MAX_TICK
+        !(tick_math::MIN_TICK..=tick_math::MAX_TICK).contains(&tick)
     }
 }
 
@@ -137,7 +228,7 @@ impl TickUtils {
         let mut start = tick_index / ticks_in_array;
 
         if tick_index < 0 && tick_index % ticks_in_array != 0 {
-            start = start - 1
+            start -= 1
         }
 
         start * ticks_in_array
@@ -166,6 +257,27 @@ impl TickUtils {
         TICK_ARRAY_SIZE * i32::from(tick_spacing)
     }
 
+    /// The smallest tick divisible by `tick_spacing` that is not below MIN_TICK, the lowest tick a position can use.
+    /// MIN_TICK is rounded up toward zero, e.g. -443580 for the tick spacing 60, not -443640.
+    pub fn min_usable_tick(tick_spacing: u16) -> i32 {
+        -Self::max_usable_tick(tick_spacing)
+    }
+
+    /// The largest tick divisible by `tick_spacing` that is not above MAX_TICK, the highest tick a position can use.
+    pub fn max_usable_tick(tick_spacing: u16) -> i32 {
+        let tick_spacing = i32::from(tick_spacing);
+        tick_math::MAX_TICK / tick_spacing * tick_spacing
+    }
+
+    /// The max liquidity_gross of a tick, u128::MAX shared by all the usable ticks of the tick spacing,
+    /// so that the liquidity of the pool can't overflow even if every tick is initialized at the max.
+    pub fn max_liquidity_per_tick(tick_spacing: u16) -> u128 {
+        let num_ticks = (Self::max_usable_tick(tick_spacing) - Self::min_usable_tick(tick_spacing))
+            / i32::from(tick_spacing)
+            + 1;
+        u128::MAX / num_ticks as u128
+    }
+
     /// check that the tick_array_start_index is correct for the given tick_index and tick_spacing
     pub fn check_tick_array_start_index(tick_array_start_index: i32, tick_index: i32, tick_spacing: u16) -> Result<()> {
         require!(tick_index >= tick_math::MIN_TICK, ErrorCode::TickLowerOverflow);
@@ -183,6 +295,15 @@ impl TickUtils {
         Ok(())
     }
 
+    /// The maker rebate growth a position bounded by tick_lower and tick_upper is entitled to, for token_0 and token_1.
+    /// Only the difference between two checkpoints of the same position is meaningful.
+    pub fn get_maker_rebate_growth_boundary(tick_lower: &TickState, tick_upper: &TickState) -> [u128; 2] {
+        [
+            tick_lower.maker_rebate_growth_0_x64.wrapping_add(tick_upper.maker_rebate_growth_0_x64),
+            tick_lower.maker_rebate_growth_1_x64.wrapping_add(tick_upper.maker_rebate_growth_1_x64),
+        ]
+    }
+
     // Calculates the fee growths inside of tick_lower and tick_upper based on their positions relative to tick_current.
     /// `fee_growth_inside = fee_growth_global - fee_growth_below(lower) - fee_growth_above(upper)`
     ///
@@ -230,6 +351,46 @@ impl TickUtils {
         (fee_growth_inside_0_x64, fee_growth_inside_1_x64)
     }
 
+    /// Calculates the seconds per unit of liquidity spent inside of tick_lower and tick_upper, by the same convention as
+    /// `get_fee_growth_inside`. Only the difference between two values of the same range is meaningful, e.g. the seconds
+    /// a position was in range are its liquidity times the difference of the values when it is closed and opened.
+    pub fn get_seconds_per_liquidity_inside(
+        tick_lower: &TickState,
+        tick_upper: &TickState,
+        tick_current: i32,
+        seconds_per_liquidity_cumulative_x64: u128,
+    ) -> u128 {
+        let seconds_per_liquidity_below_x64 = if tick_current >= tick_lower.tick {
+            tick_lower.seconds_per_liquidity_outside_x64
+        } else {
+            seconds_per_liquidity_cumulative_x64.wrapping_sub(tick_lower.seconds_per_liquidity_outside_x64)
+        };
+        let seconds_per_liquidity_above_x64 = if tick_current < tick_upper.tick {
+            tick_upper.seconds_per_liquidity_outside_x64
+        } else {
+            seconds_per_liquidity_cumulative_x64.wrapping_sub(tick_upper.seconds_per_liquidity_outside_x64)
+        };
+        seconds_per_liquidity_cumulative_x64
+            .wrapping_sub(seconds_per_liquidity_below_x64)
+            .wrapping_sub(seconds_per_liquidity_above_x64)
+    }
+
+    /// Calculates the seconds the price spent inside of tick_lower and tick_upper, up to `time`, by the same convention
+    /// as `get_seconds_per_liquidity_inside`
+    pub fn get_seconds_inside(tick_lower: &TickState, tick_upper: &TickState, tick_current: i32, time: u32) -> u32 {
+        let seconds_below = if tick_current >= tick_lower.tick {
+            tick_lower.seconds_outside
+        } else {
+            time.wrapping_sub(tick_lower.seconds_outside)
+        };
+        let seconds_above = if tick_current < tick_upper.tick {
+            tick_upper.seconds_outside
+        } else {
+            time.wrapping_sub(tick_upper.seconds_outside)
+        };
+        time.wrapping_sub(seconds_below).wrapping_sub(seconds_above)
+    }
+
     // Calculates the reward growths inside of tick_lower and tick_upper based on their positions relative to tick_current.
     pub fn get_reward_growths_inside(
         tick_lower: &TickState,
@@ -266,9 +427,9 @@ impl TickUtils {
                 .wrapping_sub(reward_growths_below)
                 .wrapping_sub(reward_growths_above);
 
-            #[cfg(feature = "enable-log")]
-            msg!(
-            "get_reward_growths_inside,i:{},reward_growth_global:{},reward_growth_below:{},reward_growth_above:{}, reward_growth_inside:{}",
+            log_debug!(
+            ix = "get_reward_growths_inside",
+            "i:{},reward_growth_global:{},reward_growth_below:{},reward_growth_above:{}, reward_growth_inside:{}",
             i,
             identity(reward_infos[i].reward_growth_global_x64),
             reward_growths_below,
@@ -44,10 +44,20 @@ impl TickArrayBitmapExtension {
         self.negative_tick_array_bitmap = [[0; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
     }
 
-    pub fn key(pool_id: Pubkey) -> Pubkey {
+    /// No tick array is initialized in the ranges of the extension
+    pub fn is_empty(&self) -> bool {
+        let positive_tick_array_bitmap = { self.positive_tick_array_bitmap };
+        let negative_tick_array_bitmap = { self.negative_tick_array_bitmap };
+        positive_tick_array_bitmap
+            .iter()
+            .chain(negative_tick_array_bitmap.iter())
+            .all(|bitmap| bitmap.iter().all(|word| *word == 0))
+    }
+
+    pub fn key(pool_id: Pubkey, program_id: &Pubkey) -> Pubkey {
         Pubkey::find_program_address(
             &[POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool_id.as_ref()],
-            &crate::id(),
+            program_id,
         )
         .0
     }
@@ -117,9 +127,9 @@ impl TickArrayBitmapExtension {
         let tick_array_bitmap = U512(tick_array_bitmap);
         let mask = U512::one() << tick_array_offset_in_bitmap;
         if tick_array_start_index < 0 {
-            self.negative_tick_array_bitmap[offset as usize] = tick_array_bitmap.bitxor(mask).0;
+            self.negative_tick_array_bitmap[offset] = tick_array_bitmap.bitxor(mask).0;
         } else {
-            self.positive_tick_array_bitmap[offset as usize] = tick_array_bitmap.bitxor(mask).0;
+            self.positive_tick_array_bitmap[offset] = tick_array_bitmap.bitxor(mask).0;
         }
         Ok(())
     }
@@ -182,13 +192,13 @@ impl TickArrayBitmapExtension {
                 Some(u16::try_from(offset_bit_map.leading_zeros()).unwrap())
             };
 
-            if next_bit.is_some() {
+            if let Some(next_bit) = next_bit {
                 let next_array_start_index = next_tick_array_start_index
-                    - i32::from(next_bit.unwrap()) * TickUtils::tick_count(tick_spacing);
-                return (true, next_array_start_index);
+                    - i32::from(next_bit) * TickUtils::tick_count(tick_spacing);
+                (true, next_array_start_index)
             } else {
                 // not found til to the end
-                return (false, bitmap_min_tick_boundary);
+                (false, bitmap_min_tick_boundary)
             }
         } else {
             // tick from lower to upper
@@ -200,16 +210,16 @@ impl TickArrayBitmapExtension {
             } else {
                 Some(u16::try_from(offset_bit_map.trailing_zeros()).unwrap())
             };
-            if next_bit.is_some() {
+            if let Some(next_bit) = next_bit {
                 let next_array_start_index = next_tick_array_start_index
-                    + i32::from(next_bit.unwrap()) * TickUtils::tick_count(tick_spacing);
-                return (true, next_array_start_index);
+                    + i32::from(next_bit) * TickUtils::tick_count(tick_spacing);
+                (true, next_array_start_index)
             } else {
                 // not found til to the end
-                return (
+                (
                     false,
                     bitmap_max_tick_boundary - TickUtils::tick_count(tick_spacing),
-                );
+                )
             }
         }
     }
@@ -260,13 +270,11 @@ pub mod tick_array_bitmap_extension_test {
         }
     }
 
-    pub fn build_tick_array_bitmap_extension_info<'info>(
+    pub fn build_tick_array_bitmap_extension_info(
         param: &mut BuildExtensionAccountInfo,
     ) -> AccountInfo<'_> {
         let disc_bytes = [60, 150, 36, 219, 97, 128, 139, 153];
-        for i in 0..8 {
-            param.data[i] = disc_bytes[i];
-        }
+        param.data[..8].copy_from_slice(&disc_bytes);
         AccountInfo::new(
             &param.key,
             false,
@@ -365,7 +373,7 @@ pub mod tick_array_bitmap_extension_test {
         let offset = tick_array_bitmap_extension
             .get_bitmap(tick_spacing * TICK_ARRAY_SIZE * 511, tick_spacing as u16)
             .is_err();
-        assert!(offset == true);
+        assert!(offset);
 
         let (offset, _) = tick_array_bitmap_extension
             .get_bitmap(tick_spacing * TICK_ARRAY_SIZE * 512, tick_spacing as u16)
@@ -380,7 +388,7 @@ pub mod tick_array_bitmap_extension_test {
         let offset = tick_array_bitmap_extension
             .get_bitmap(-tick_spacing * TICK_ARRAY_SIZE * 512, tick_spacing as u16)
             .is_err();
-        assert!(offset == true);
+        assert!(offset);
 
         let (offset, _) = tick_array_bitmap_extension
             .get_bitmap(-tick_spacing * TICK_ARRAY_SIZE * 513, tick_spacing as u16)
@@ -388,6 +396,27 @@ pub mod tick_array_bitmap_extension_test {
         assert!(offset == 0);
     }
 
+    #[test]
+    fn is_empty_test() {
+        let tick_spacing = 1;
+        let mut tick_array_bitmap_extension = TickArrayBitmapExtension::default();
+        assert!(tick_array_bitmap_extension.is_empty());
+
+        let start_index = -tick_spacing * TICK_ARRAY_SIZE * 600;
+        flip_tick_array_bit_helper(
+            &mut tick_array_bitmap_extension,
+            tick_spacing as u16,
+            vec![start_index],
+        );
+        assert!(!tick_array_bitmap_extension.is_empty());
+        flip_tick_array_bit_helper(
+            &mut tick_array_bitmap_extension,
+            tick_spacing as u16,
+            vec![start_index],
+        );
+        assert!(tick_array_bitmap_extension.is_empty());
+    }
+
     #[test]
     fn flip_tick_array_bit_test() {
         let tick_array_bitmap_extension = &mut TickArrayBitmapExtension::default();
@@ -406,13 +435,13 @@ pub mod tick_array_bitmap_extension_test {
             ],
         );
 
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0) == true);
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(1) == true);
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[13]).bit(225) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(510) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(0) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[13]).bit(286) == true);
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0));
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(1));
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[13]).bit(225));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(510));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(0));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[13]).bit(286));
 
         flip_tick_array_bit_helper(
             tick_array_bitmap_extension,
@@ -426,12 +455,12 @@ pub mod tick_array_bitmap_extension_test {
                 -tick_spacing * TICK_ARRAY_SIZE * 7394, // max negative tick array start index boundary in extension
             ],
         );
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0) == false);
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(1) == false);
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[13]).bit(225) == false);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511) == false);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(510) == false);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[13]).bit(286) == false);
+        assert!(!U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0));
+        assert!(!U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(1));
+        assert!(!U512(tick_array_bitmap_extension.positive_tick_array_bitmap[13]).bit(225));
+        assert!(!U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511));
+        assert!(!U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(510));
+        assert!(!U512(tick_array_bitmap_extension.negative_tick_array_bitmap[13]).bit(286));
 
         let tick_array_bitmap_extension = &mut TickArrayBitmapExtension::default();
         let tick_spacing = 3;
@@ -446,10 +475,10 @@ pub mod tick_array_bitmap_extension_test {
             ],
         );
 
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0) == true);
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[3]).bit(416) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[3]).bit(95) == true);
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0));
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[3]).bit(416));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[3]).bit(95));
 
         let tick_array_bitmap_extension = &mut TickArrayBitmapExtension::default();
         let tick_spacing = 10;
@@ -464,10 +493,10 @@ pub mod tick_array_bitmap_extension_test {
             ],
         );
 
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0) == true);
-        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(227) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511) == true);
-        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(284) == true);
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(0));
+        assert!(U512(tick_array_bitmap_extension.positive_tick_array_bitmap[0]).bit(227));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(511));
+        assert!(U512(tick_array_bitmap_extension.negative_tick_array_bitmap[0]).bit(284));
     }
 
     #[test]
@@ -510,7 +539,7 @@ pub mod tick_array_bitmap_extension_test {
                 false,
             )
             .unwrap();
-        assert!(next.0 == false);
+        assert!(!next.0);
 
         // zero_for_one.
         let (_, next) = tick_array_bitmap_extension
@@ -607,16 +636,16 @@ pub mod tick_array_bitmap_extension_test {
                 true,
             )
             .unwrap();
-        assert!(next.0 == false);
+        assert!(!next.0);
     }
 
     #[test]
     fn next_initialized_tick_array_with_all_initialized_bit_test() {
         let tick_array_bitmap_extension = &mut TickArrayBitmapExtension::default();
         tick_array_bitmap_extension.negative_tick_array_bitmap =
-            [[u64::max_value(); 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
+            [[u64::MAX; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
         tick_array_bitmap_extension.positive_tick_array_bitmap =
-            [[u64::max_value(); 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
+            [[u64::MAX; 8]; EXTENSION_TICKARRAY_BITMAP_SIZE];
 
         for tick_spacing in [1, 10, 60] {
             let tick_boundary = max_tick_in_tickarray_bitmap(tick_spacing);
@@ -631,7 +660,7 @@ pub mod tick_array_bitmap_extension_test {
                     .next_initialized_tick_array_from_one_bitmap(start_index, tick_spacing, false)
                     .unwrap();
 
-                assert_eq!(is_found, true);
+                assert!(is_found);
                 assert_eq!(array_start_index, expect_index);
                 start_index = array_start_index;
             }
@@ -643,7 +672,7 @@ pub mod tick_array_bitmap_extension_test {
                     .next_initialized_tick_array_from_one_bitmap(start_index, tick_spacing, true)
                     .unwrap();
 
-                assert_eq!(is_found, true);
+                assert!(is_found);
                 assert_eq!(array_start_index, expect_index);
                 start_index = array_start_index;
             }
@@ -662,24 +691,24 @@ pub mod tick_array_bitmap_extension_test {
         let mut bitmap_extension_data = [0u8; 8 + 32 + 64 * EXTENSION_TICKARRAY_BITMAP_SIZE * 2];
         let mut offset = 0;
         bitmap_extension_data[offset..offset + 8]
-            .copy_from_slice(&TickArrayBitmapExtension::DISCRIMINATOR);
+            .copy_from_slice(TickArrayBitmapExtension::DISCRIMINATOR);
         offset += 8;
         bitmap_extension_data[offset..offset + 32].copy_from_slice(&pool_id.to_bytes());
         offset += 32;
 
         let mut init_data = u64::MAX;
-        for i in 0..EXTENSION_TICKARRAY_BITMAP_SIZE {
-            for j in 0..8 {
+        for bitmap in positive_tick_array_bitmap.iter_mut() {
+            for word in bitmap.iter_mut() {
                 init_data -= 1;
-                positive_tick_array_bitmap[i][j] = init_data;
+                *word = init_data;
                 bitmap_extension_data[offset..offset + 8].copy_from_slice(&init_data.to_le_bytes());
                 offset += 8;
             }
         }
-        for i in 0..EXTENSION_TICKARRAY_BITMAP_SIZE {
-            for j in 0..8 {
+        for bitmap in negative_tick_array_bitmap.iter_mut() {
+            for word in bitmap.iter_mut() {
                 init_data -= 1;
-                negative_tick_array_bitmap[i][j] = init_data;
+                *word = init_data;
                 bitmap_extension_data[offset..offset + 8].copy_from_slice(&init_data.to_le_bytes());
                 offset += 8;
             }
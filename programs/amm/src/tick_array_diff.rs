@@ -0,0 +1,207 @@
+use crate::error::ErrorCode as ClmmErrorCode;
+use crate::quote::TickArrayData;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+/// A tick whose liquidity or fee growth changed between two snapshots of a tick array.
+/// The values are taken from the new snapshot, they are all zero if the tick was cleared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickDiff {
+    /// The offset of the tick in the tick array, in `0..TICK_ARRAY_SIZE`
+    pub offset: u8,
+    pub tick: i32,
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+    pub fee_growth_outside_0_x64: u128,
+    pub fee_growth_outside_1_x64: u128,
+}
+
+impl TickArrayData {
+    /// Get the TickState at `offset` in the tick array, `None` if it is not allocated in a dynamic tick array
+    pub fn tick_state_at(&self, offset: usize) -> Option<&TickState> {
+        match self {
+            TickArrayData::Fixed(tick_array) => tick_array.ticks.get(offset),
+            TickArrayData::Dynamic(header, ticks) => match header.tick_offset_index.get(offset) {
+                Some(&index) if index > 0 => ticks.get(usize::from(index) - 1),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Compare two snapshots of the same tick array account and return the ticks that changed,
+/// ordered by tick index. Both fixed and dynamic tick arrays are supported, in any combination,
+/// so that market data services can publish incremental updates instead of full snapshots.
+pub fn diff_tick_array(old_bytes: &[u8], new_bytes: &[u8]) -> Result<Vec<TickDiff>> {
+    let old = TickArrayData::try_deserialize(old_bytes)?;
+    let new = TickArrayData::try_deserialize(new_bytes)?;
+    require_keys_eq!(
+        old.pool_id(),
+        new.pool_id(),
+        ClmmErrorCode::InvalidTickArray
+    );
+    require_eq!(
+        old.start_tick_index(),
+        new.start_tick_index(),
+        ClmmErrorCode::InvalidTickArray
+    );
+
+    let empty = TickState::default();
+    let mut diffs = Vec::new();
+    for offset in 0..TICK_ARRAY_SIZE_USIZE {
+        let old_tick = old.tick_state_at(offset).unwrap_or(&empty);
+        let new_tick = new.tick_state_at(offset).unwrap_or(&empty);
+        if old_tick.liquidity_net == new_tick.liquidity_net
+            && old_tick.liquidity_gross == new_tick.liquidity_gross
+            && old_tick.fee_growth_outside_0_x64 == new_tick.fee_growth_outside_0_x64
+            && old_tick.fee_growth_outside_1_x64 == new_tick.fee_growth_outside_1_x64
+        {
+            continue;
+        }
+        // an uninitialized TickState in a fixed tick array doesn't hold its tick index
+        let tick = if new_tick.tick != 0 {
+            new_tick.tick
+        } else {
+            old_tick.tick
+        };
+        diffs.push(TickDiff {
+            offset: offset as u8,
+            tick,
+            liquidity_net: new_tick.liquidity_net,
+            liquidity_gross: new_tick.liquidity_gross,
+            fee_growth_outside_0_x64: new_tick.fee_growth_outside_0_x64,
+            fee_growth_outside_1_x64: new_tick.fee_growth_outside_1_x64,
+        });
+    }
+    Ok(diffs)
+}
+
+#[cfg(test)]
+mod tick_array_diff_test {
+    use super::*;
+    use crate::states::dyn_tick_array_test::{
+        build_dyn_tick_array_with_tick_states, DynamicTickArrayBuildType,
+    };
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+    use anchor_lang::Discriminator;
+
+    fn fixed_bytes(tick_array: &TickArrayState) -> Vec<u8> {
+        let mut data = TickArrayState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(tick_array));
+        data
+    }
+
+    fn dyn_bytes(header: &DynTickArrayState, ticks: &[TickState]) -> Vec<u8> {
+        let mut data = DynTickArrayState::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(header));
+        data.extend_from_slice(bytemuck::cast_slice(ticks));
+        data
+    }
+
+    #[test]
+    fn diff_fixed_tick_array_test() {
+        let pool_id = Pubkey::new_unique();
+        let old = build_fix_tick_array_with_tick_states(
+            pool_id,
+            -3600,
+            60,
+            vec![
+                build_tick(-3540, 100, 100).take(),
+                build_tick(-60, 100, -100).take(),
+            ],
+        );
+        let mut new = *old.borrow();
+        assert!(
+            diff_tick_array(&fixed_bytes(&old.borrow()), &fixed_bytes(&new))
+                .unwrap()
+                .is_empty()
+        );
+
+        // clear -3540, add -1200 and accrue fee on -60
+        new.ticks[1] = TickState::default();
+        new.ticks[40] = build_tick(-1200, 50, 50).take();
+        new.ticks[59].fee_growth_outside_0_x64 = 1 << 64;
+        let diffs = diff_tick_array(&fixed_bytes(&old.borrow()), &fixed_bytes(&new)).unwrap();
+        assert_eq!(
+            diffs,
+            vec![
+                TickDiff {
+                    offset: 1,
+                    tick: -3540,
+                    ..Default::default()
+                },
+                TickDiff {
+                    offset: 40,
+                    tick: -1200,
+                    liquidity_net: 50,
+                    liquidity_gross: 50,
+                    ..Default::default()
+                },
+                TickDiff {
+                    offset: 59,
+                    tick: -60,
+                    liquidity_net: -100,
+                    liquidity_gross: 100,
+                    fee_growth_outside_0_x64: 1 << 64,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_dyn_tick_array_test() {
+        let pool_id = Pubkey::new_unique();
+        let (old_header, old_ticks) = build_dyn_tick_array_with_tick_states(
+            pool_id,
+            0,
+            10,
+            DynamicTickArrayBuildType::RandomIndex,
+            vec![
+                build_tick(100, 10, 10).take(),
+                build_tick(200, 10, -10).take(),
+            ],
+        );
+        let old_bytes = dyn_bytes(&old_header.borrow(), &old_ticks.borrow());
+
+        // a newly allocated tick at 50
+        let mut new_header = *old_header.borrow();
+        let mut new_ticks = old_ticks.borrow().clone();
+        new_header.use_one_tick(50, 10).unwrap();
+        new_ticks.push(build_tick(50, 7, 7).take());
+        let new_bytes = dyn_bytes(&new_header, &new_ticks);
+        assert_eq!(
+            diff_tick_array(&old_bytes, &new_bytes).unwrap(),
+            vec![TickDiff {
+                offset: 5,
+                tick: 50,
+                liquidity_net: 7,
+                liquidity_gross: 7,
+                ..Default::default()
+            }]
+        );
+
+        // the same ticks in a fixed tick array are not a change
+        let fixed = build_fix_tick_array_with_tick_states(
+            pool_id,
+            0,
+            10,
+            vec![
+                build_tick(100, 10, 10).take(),
+                build_tick(200, 10, -10).take(),
+            ],
+        );
+        assert!(diff_tick_array(&old_bytes, &fixed_bytes(&fixed.borrow()))
+            .unwrap()
+            .is_empty());
+
+        // tick arrays of another pool can't be compared
+        let other = build_fix_tick_array_with_tick_states(
+            Pubkey::new_unique(),
+            0,
+            10,
+            vec![build_tick(100, 10, 10).take()],
+        );
+        assert!(diff_tick_array(&old_bytes, &fixed_bytes(&other.borrow())).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+use crate::states::*;
+
+/// Get the merkle proof of the TickState at `offset` in `ticks`, the siblings from the leaf to the root
+pub fn tick_merkle_proof(
+    ticks: &[TickState; TICK_ARRAY_SIZE_USIZE],
+    offset: usize,
+) -> Vec<[u8; 32]> {
+    let tree = tick_array_merkle_tree(ticks);
+    let mut index = offset;
+    let mut proof = Vec::with_capacity(tree.len() - 1);
+    for level in &tree[..tree.len() - 1] {
+        proof.push(level[index ^ 1]);
+        index /= 2;
+    }
+    proof
+}
+
+/// Verify `tick_state` is the TickState at `offset` of the tick array committed to by `root`,
+/// so that the ticks of a frozen tick array can be read off chain without the whole tick array
+pub fn verify_tick_merkle_proof(
+    root: &[u8; 32],
+    offset: usize,
+    tick_state: &TickState,
+    proof: &[[u8; 32]],
+) -> bool {
+    let mut index = offset;
+    let mut hash = merkle_leaf(tick_state);
+    for sibling in proof {
+        #[allow(clippy::manual_is_multiple_of)] // is_multiple_of is newer than the rustc of the Solana platform tools
+        let is_left = index % 2 == 0;
+        hash = if is_left {
+            merkle_node(&hash, sibling)
+        } else {
+            merkle_node(sibling, &hash)
+        };
+        index /= 2;
+    }
+    index == 0 && hash == *root
+}
+
+/// Check the `ticks` of a `TickArrayFrozenEvent` thaw `frozen_tick_array`, before sending `thaw_tick_array`
+pub fn check_thaw_ticks(frozen_tick_array: &FrozenTickArrayState, ticks: &[FrozenTick]) -> bool {
+    let mut tick_states = [TickState::default(); TICK_ARRAY_SIZE_USIZE];
+    let mut offsets = 0u64;
+    for tick in ticks {
+        let Some(tick_state) = tick_states.get_mut(usize::from(tick.offset)) else {
+            return false;
+        };
+        *tick_state = tick.tick_state();
+        offsets |= 1 << tick.offset;
+    }
+    offsets == frozen_tick_array.non_empty_offsets
+        && tick_array_merkle_root(&tick_states) == frozen_tick_array.root
+}
+
+#[cfg(test)]
+mod tick_array_proof_test {
+    use super::*;
+    use crate::states::tick_array_test::{build_fix_tick_array_with_tick_states, build_tick};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn tick_merkle_proof_test() {
+        let tick_array = build_fix_tick_array_with_tick_states(
+            Pubkey::new_unique(),
+            -600,
+            10,
+            vec![
+                build_tick(-590, 30, 30).take(),
+                build_tick(-10, 30, -30).take(),
+            ],
+        );
+        let ticks = tick_array.borrow().ticks;
+        let root = tick_array_merkle_root(&ticks);
+
+        // an empty tick
+        assert!(verify_tick_merkle_proof(
+            &root,
+            30,
+            &TickState::default(),
+            &tick_merkle_proof(&ticks, 30)
+        ));
+        for offset in [1, 59] {
+            let proof = tick_merkle_proof(&ticks, offset);
+            assert_eq!(proof.len(), 6);
+            assert!(verify_tick_merkle_proof(
+                &root,
+                offset,
+                &ticks[offset],
+                &proof
+            ));
+            // another tick or another offset
+            assert!(!verify_tick_merkle_proof(
+                &root,
+                offset,
+                &build_tick(-590, 31, 30).take(),
+                &proof
+            ));
+            assert!(!verify_tick_merkle_proof(
+                &root,
+                offset ^ 2,
+                &ticks[offset],
+                &proof
+            ));
+        }
+
+        let mut ticks_to_thaw = FrozenTick::non_empty(&ticks);
+        let frozen_tick_array = FrozenTickArrayState {
+            root,
+            non_empty_offsets: 1 << 1 | 1 << 59,
+            ..Default::default()
+        };
+        assert!(check_thaw_ticks(&frozen_tick_array, &ticks_to_thaw));
+        ticks_to_thaw.pop();
+        assert!(!check_thaw_ticks(&frozen_tick_array, &ticks_to_thaw));
+    }
+}
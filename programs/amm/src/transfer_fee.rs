@@ -0,0 +1,158 @@
+//! The Token-2022 transfer fees the quotes charge, decoded from the raw mint data by the core crate.
+//! The tests check they are the same as the fees of `spl_token_2022`, which the swap instructions use.
+
+pub use byreal_clmm_core::transfer_fee::*;
+
+#[cfg(test)]
+mod transfer_fee_test {
+    use super::*;
+    use crate::util::{calculate_transfer_fee, calculate_transfer_inverse_fee};
+    use proptest::prelude::*;
+    use spl_token_2022::extension::{
+        mint_close_authority::MintCloseAuthority, transfer_fee, BaseStateWithExtensionsMut,
+        ExtensionType, StateWithExtensionsMut,
+    };
+    use spl_token_2022::state::Mint;
+
+    fn spl_transfer_fee(transfer_fee: &TransferFee) -> transfer_fee::TransferFee {
+        transfer_fee::TransferFee {
+            epoch: transfer_fee.epoch.into(),
+            maximum_fee: transfer_fee.maximum_fee.into(),
+            transfer_fee_basis_points: transfer_fee.transfer_fee_basis_points.into(),
+        }
+    }
+
+    fn spl_transfer_fee_config(config: &TransferFeeConfig) -> transfer_fee::TransferFeeConfig {
+        transfer_fee::TransferFeeConfig {
+            older_transfer_fee: spl_transfer_fee(&config.older_transfer_fee),
+            newer_transfer_fee: spl_transfer_fee(&config.newer_transfer_fee),
+            ..Default::default()
+        }
+    }
+
+    /// The data of an initialized mint with the `extension_types`, the transfer fee set to `config`
+    fn mint_data(extension_types: &[ExtensionType], config: &TransferFeeConfig) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(extension_types).unwrap();
+        let mut data = vec![0; len];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        for extension_type in extension_types {
+            match extension_type {
+                ExtensionType::TransferFeeConfig => {
+                    *mint
+                        .init_extension::<transfer_fee::TransferFeeConfig>(true)
+                        .unwrap() = spl_transfer_fee_config(config);
+                }
+                ExtensionType::MintCloseAuthority => {
+                    mint.init_extension::<MintCloseAuthority>(true).unwrap();
+                }
+                _ => unreachable!(),
+            }
+        }
+        mint.base.is_initialized = true;
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn transfer_fee_strategy() -> impl Strategy<Value = TransferFee> {
+        (
+            0..20u64,
+            prop_oneof![Just(u64::MAX), Just(0u64), any::<u64>()],
+            prop_oneof![
+                Just(0u16),
+                Just(MAX_FEE_BASIS_POINTS),
+                0..=MAX_FEE_BASIS_POINTS
+            ],
+        )
+            .prop_map(
+                |(epoch, maximum_fee, transfer_fee_basis_points)| TransferFee {
+                    epoch,
+                    maximum_fee,
+                    transfer_fee_basis_points,
+                },
+            )
+    }
+
+    #[test]
+    fn from_mint_data_test() {
+        let config = TransferFeeConfig {
+            older_transfer_fee: TransferFee {
+                epoch: 3,
+                maximum_fee: 1_000,
+                transfer_fee_basis_points: 100,
+            },
+            newer_transfer_fee: TransferFee {
+                epoch: 10,
+                maximum_fee: 5_000,
+                transfer_fee_basis_points: 250,
+            },
+        };
+        // a mint without extensions
+        assert_eq!(
+            TransferFeeConfig::from_mint_data(&mint_data(&[], &config)[..MINT_LEN]),
+            None
+        );
+        assert_eq!(
+            TransferFeeConfig::from_mint_data(&mint_data(
+                &[ExtensionType::MintCloseAuthority],
+                &config
+            )),
+            None
+        );
+        assert_eq!(
+            TransferFeeConfig::from_mint_data(&mint_data(
+                &[ExtensionType::TransferFeeConfig],
+                &config
+            )),
+            Some(config)
+        );
+        // the transfer fee config after another extension
+        let data = mint_data(
+            &[
+                ExtensionType::MintCloseAuthority,
+                ExtensionType::TransferFeeConfig,
+            ],
+            &config,
+        );
+        assert_eq!(TransferFeeConfig::from_mint_data(&data), Some(config));
+
+        // an uninitialized mint
+        let mut uninitialized_data = data.clone();
+        uninitialized_data[MINT_IS_INITIALIZED_OFFSET] = 0;
+        assert_eq!(TransferFeeConfig::from_mint_data(&uninitialized_data), None);
+    }
+
+    proptest! {
+        #[test]
+        fn transfer_fee_matches_spl_token_2022_test(
+            older_transfer_fee in transfer_fee_strategy(),
+            newer_transfer_fee in transfer_fee_strategy(),
+            epoch in 0..20u64,
+            amount in prop_oneof![Just(0u64), Just(u64::MAX), any::<u64>(), 0..1_000_000u64],
+        ) {
+            let config = TransferFeeConfig {
+                older_transfer_fee,
+                newer_transfer_fee,
+            };
+            let spl_config = spl_transfer_fee_config(&config);
+
+            let data = mint_data(&[ExtensionType::TransferFeeConfig], &config);
+            prop_assert_eq!(TransferFeeConfig::from_mint_data(&data), Some(config));
+
+            prop_assert_eq!(
+                config.calculate_epoch_fee(epoch, amount),
+                calculate_transfer_fee(&spl_config, epoch, amount)
+            );
+            // both panic when the amount and its fee overflow u64
+            let transfer_fee = config.get_epoch_fee(epoch);
+            prop_assume!(transfer_fee
+                .calculate_inverse_fee(amount)
+                .and_then(|fee| amount.checked_add(fee))
+                .is_some());
+            prop_assert_eq!(
+                config.calculate_inverse_epoch_fee(epoch, amount),
+                calculate_transfer_inverse_fee(&spl_config, epoch, amount)
+            );
+        }
+    }
+}
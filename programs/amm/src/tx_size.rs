@@ -0,0 +1,241 @@
+use crate::quote::tick_array_address;
+use crate::states::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::address_lookup_table::AddressLookupTableAccount;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use std::collections::BTreeSet;
+
+/// The max serialized size of a transaction
+pub const PACKET_DATA_SIZE: usize = 1232;
+
+const SIGNATURE_LEN: usize = 64;
+const PUBKEY_LEN: usize = 32;
+const MESSAGE_HEADER_LEN: usize = 3;
+const BLOCKHASH_LEN: usize = 32;
+
+/// The serialized sizes of a transaction of some instructions, as a legacy and as a v0 transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxSizeEstimate {
+    pub legacy: usize,
+    pub v0: usize,
+}
+
+impl TxSizeEstimate {
+    pub fn fits_legacy(&self) -> bool {
+        self.legacy <= PACKET_DATA_SIZE
+    }
+
+    pub fn fits_v0(&self) -> bool {
+        self.v0 <= PACKET_DATA_SIZE
+    }
+}
+
+/// The length of the compact-u16 prefix of an array of `len` elements
+fn compact_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Estimate the serialized size of a transaction of `instructions` paid by `payer`, as a legacy transaction
+/// and as a v0 transaction loading the accounts it can from `lookup_tables`.
+/// Append the remaining accounts the instructions may need first, e.g. with `swap_worst_case_remaining_accounts`,
+/// to decide between a legacy and a v0 transaction, or whether to split the instructions, before sending them.
+pub fn estimate_tx_size(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> TxSizeEstimate {
+    let mut signers = BTreeSet::from([*payer]);
+    let mut program_ids = BTreeSet::new();
+    let mut keys = BTreeSet::from([*payer]);
+    let mut instructions_len = compact_len(instructions.len());
+    for ix in instructions {
+        program_ids.insert(ix.program_id);
+        keys.insert(ix.program_id);
+        for account in &ix.accounts {
+            keys.insert(account.pubkey);
+            if account.is_signer {
+                signers.insert(account.pubkey);
+            }
+        }
+        instructions_len += 1
+            + compact_len(ix.accounts.len())
+            + ix.accounts.len()
+            + compact_len(ix.data.len())
+            + ix.data.len();
+    }
+    let signatures_len = compact_len(signers.len()) + signers.len() * SIGNATURE_LEN;
+    let static_keys_len = |count: usize| compact_len(count) + count * PUBKEY_LEN;
+
+    let legacy = signatures_len
+        + MESSAGE_HEADER_LEN
+        + static_keys_len(keys.len())
+        + BLOCKHASH_LEN
+        + instructions_len;
+
+    // the signers and the invoked programs can't be loaded from a lookup table
+    let mut loaded = vec![0usize; lookup_tables.len()];
+    let mut static_count = 0;
+    for key in &keys {
+        let table = if signers.contains(key) || program_ids.contains(key) {
+            None
+        } else {
+            lookup_tables
+                .iter()
+                .position(|table| table.addresses.contains(key))
+        };
+        match table {
+            Some(index) => loaded[index] += 1,
+            None => static_count += 1,
+        }
+    }
+    let used_tables: Vec<usize> = loaded.into_iter().filter(|count| *count > 0).collect();
+    // each table lists the indexes of its writable and readonly accounts, an account takes one byte
+    let lookups_len = compact_len(used_tables.len())
+        + used_tables
+            .iter()
+            .map(|count| PUBKEY_LEN + 2 + count)
+            .sum::<usize>();
+    let v0 = signatures_len
+        + 1
+        + MESSAGE_HEADER_LEN
+        + static_keys_len(static_count)
+        + BLOCKHASH_LEN
+        + instructions_len
+        + lookups_len;
+
+    TxSizeEstimate { legacy, v0 }
+}
+
+/// The remaining accounts of the worst case of a swap: the tick array bitmap extension,
+/// and the `tick_array_count` consecutive tick arrays from the current one in the swap direction,
+/// whether they are initialized or not.
+pub fn swap_worst_case_remaining_accounts(
+    program_id: &Pubkey,
+    pool_id: &Pubkey,
+    pool_state: &PoolState,
+    zero_for_one: bool,
+    tick_array_count: usize,
+) -> Vec<AccountMeta> {
+    let tick_spacing = pool_state.tick_spacing;
+    let ticks_in_array = TickUtils::tick_count(tick_spacing);
+    let mut start_index = TickUtils::get_array_start_index(pool_state.tick_current, tick_spacing);
+    let mut accounts = vec![AccountMeta::new_readonly(
+        TickArrayBitmapExtension::key(*pool_id, program_id),
+        false,
+    )];
+    for _ in 0..tick_array_count {
+        if !TickUtils::check_is_valid_start_index(start_index, tick_spacing) {
+            break;
+        }
+        accounts.push(AccountMeta::new(
+            tick_array_address(pool_id, start_index, program_id),
+            false,
+        ));
+        start_index = if zero_for_one {
+            start_index - ticks_in_array
+        } else {
+            start_index + ticks_in_array
+        };
+    }
+    accounts
+}
+
+#[cfg(test)]
+mod tx_size_test {
+    use super::*;
+    use crate::libraries::tick_math;
+    use crate::states::pool_test::build_pool;
+
+    fn key(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn estimate_tx_size_test() {
+        let payer = key(1);
+        let ix = Instruction {
+            program_id: crate::id(),
+            accounts: vec![],
+            data: vec![],
+        };
+        // 1 signature, header, 2 keys, blockhash, 1 instruction with no account and data
+        let estimate = estimate_tx_size(std::slice::from_ref(&ix), &payer, &[]);
+        assert_eq!(estimate.legacy, 1 + 64 + 3 + 1 + 64 + 32 + 1 + 3);
+        assert_eq!(estimate.v0, estimate.legacy + 1 + 1);
+
+        // the accounts in a lookup table take one byte instead of a key
+        let accounts: Vec<AccountMeta> = (10..30)
+            .map(|seed| AccountMeta::new(key(seed), false))
+            .collect();
+        let ix = Instruction {
+            program_id: crate::id(),
+            accounts: accounts.clone(),
+            data: vec![0; 40],
+        };
+        let table = AddressLookupTableAccount {
+            key: key(2),
+            addresses: accounts.iter().map(|account| account.pubkey).collect(),
+        };
+        let estimate = estimate_tx_size(std::slice::from_ref(&ix), &payer, &[table]);
+        assert_eq!(
+            estimate.legacy,
+            1 + 64 + 3 + 1 + 22 * 32 + 32 + 1 + 1 + 1 + 20 + 1 + 40
+        );
+        assert_eq!(
+            estimate.v0,
+            1 + 64 + 1 + 3 + 1 + 2 * 32 + 32 + 1 + 1 + 1 + 20 + 1 + 40 + 1 + 32 + 2 + 20
+        );
+        assert!(estimate.fits_legacy() && estimate.fits_v0());
+
+        // a signer is never loaded from a lookup table
+        let mut signer_ix = ix;
+        signer_ix.accounts[0].is_signer = true;
+        let table = AddressLookupTableAccount {
+            key: key(2),
+            addresses: accounts.iter().map(|account| account.pubkey).collect(),
+        };
+        let with_signer = estimate_tx_size(&[signer_ix], &payer, &[table]);
+        assert_eq!(with_signer.v0, estimate.v0 + 64 + 32 - 1);
+    }
+
+    #[test]
+    fn swap_worst_case_remaining_accounts_test() {
+        let pool_state = build_pool(-32395, 60, 3651942632306380802, 5124165121219).take();
+        let pool_id = pool_state.key();
+        let program_id = crate::id();
+        let accounts =
+            swap_worst_case_remaining_accounts(&program_id, &pool_id, &pool_state, true, 3);
+        assert_eq!(accounts.len(), 4);
+        assert_eq!(
+            accounts[0].pubkey,
+            TickArrayBitmapExtension::key(pool_id, &program_id)
+        );
+        assert_eq!(
+            accounts[1].pubkey,
+            tick_array_address(&pool_id, -32400, &program_id)
+        );
+        assert_eq!(
+            accounts[3].pubkey,
+            tick_array_address(&pool_id, -39600, &program_id)
+        );
+        assert!(accounts[1..].iter().all(|account| account.is_writable));
+
+        // a swap can't need more tick arrays than the valid ones
+        let accounts = swap_worst_case_remaining_accounts(
+            &program_id,
+            &pool_id,
+            &pool_state,
+            false,
+            usize::MAX,
+        );
+        let max_start_index = TickUtils::get_array_start_index(tick_math::MAX_TICK, 60);
+        assert_eq!(
+            accounts.last().unwrap().pubkey,
+            tick_array_address(&pool_id, max_start_index, &program_id)
+        );
+    }
+}
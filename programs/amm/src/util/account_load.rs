@@ -33,9 +33,10 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }
         // Discriminator must match.
         let disc_bytes = array_ref![data, 0, 8];
-        if disc_bytes != &T::DISCRIMINATOR {
+        if disc_bytes != T::DISCRIMINATOR {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
         }
+        check_data_len::<T>(data)?;
 
         Ok(AccountLoad::new(acc_info.clone()))
     }
@@ -59,6 +60,7 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }
 
         let mut data = self.acc_info.try_borrow_mut_data()?;
+        check_data_len::<T>(&data)?;
 
         // The discriminator should be zero, since we're initializing.
         let mut disc_bytes = [0u8; 8];
@@ -69,7 +71,7 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }
 
         // write discriminator
-        data[..8].copy_from_slice(&T::DISCRIMINATOR);
+        data[..8].copy_from_slice(T::DISCRIMINATOR);
 
         Ok(RefMut::map(data, |data| {
             bytemuck::from_bytes_mut(&mut data.deref_mut()[8..mem::size_of::<T>() + 8])
@@ -93,9 +95,10 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }
 
         let disc_bytes = array_ref![data, 0, 8];
-        if disc_bytes != &T::DISCRIMINATOR {
+        if disc_bytes != T::DISCRIMINATOR {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
         }
+        check_data_len::<T>(&data)?;
 
         Ok(RefMut::map(data, |data| {
             bytemuck::from_bytes_mut(&mut data.deref_mut()[8..mem::size_of::<T>() + 8])
@@ -110,9 +113,10 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }
 
         let disc_bytes = array_ref![data, 0, 8];
-        if disc_bytes != &T::DISCRIMINATOR {
+        if disc_bytes != T::DISCRIMINATOR {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
         }
+        check_data_len::<T>(&data)?;
 
         Ok(Ref::map(data, |data| {
             bytemuck::from_bytes(&data[8..mem::size_of::<T>() + 8])
@@ -133,9 +137,10 @@ impl<'info, T: ZeroCopy + Owner> AccountLoad<'info, T> {
         }
 
         let disc_bytes = array_ref![data, 0, 8];
-        if disc_bytes != &T::DISCRIMINATOR {
+        if disc_bytes != T::DISCRIMINATOR {
             return Err(ErrorCode::AccountDiscriminatorMismatch.into());
         }
+        check_data_len::<T>(&data)?;
 
         Ok(RefMut::map(data, |data| {
             bytemuck::from_bytes_mut(&mut data.deref_mut()[8..mem::size_of::<T>() + 8])
@@ -159,3 +164,12 @@ impl<'info, T: ZeroCopy + Owner> Key for AccountLoad<'info, T> {
         *self.acc_info.key
     }
 }
+
+/// The account must be at least as long as the layout of `T`, it may be longer,
+/// e.g. extended by a later version of the program, the trailing bytes are ignored.
+fn check_data_len<T: ZeroCopy>(data: &[u8]) -> Result<()> {
+    if data.len() < 8 + mem::size_of::<T>() {
+        return Err(ErrorCode::AccountDidNotDeserialize.into());
+    }
+    Ok(())
+}
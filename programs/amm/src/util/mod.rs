@@ -4,5 +4,8 @@ pub use token::*;
 pub mod system;
 pub use system::*;
 
+pub mod runtime;
+pub use runtime::*;
+
 pub mod account_load;
 pub use account_load::*;
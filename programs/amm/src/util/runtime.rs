@@ -0,0 +1,127 @@
+//! Access to the Clock and Rent sysvars.
+//! On chain they are read from the runtime, in tests and off-chain simulation (the `client` feature)
+//! they can be stubbed with `set_simulated_runtime`, so that the decay fee, open_time and rent
+//! math can be simulated at arbitrary timestamps.
+use anchor_lang::prelude::*;
+
+#[cfg(not(any(test, feature = "client")))]
+pub fn get_unix_timestamp() -> Result<u64> {
+    Ok(Clock::get()?.unix_timestamp as u64)
+}
+
+#[cfg(not(any(test, feature = "client")))]
+pub fn get_slot() -> Result<u64> {
+    Ok(Clock::get()?.slot)
+}
+
+#[cfg(not(any(test, feature = "client")))]
+pub fn get_recent_epoch() -> Result<u64> {
+    Ok(Clock::get()?.epoch)
+}
+
+#[cfg(not(any(test, feature = "client")))]
+pub fn get_rent() -> Result<Rent> {
+    Ok(Rent::get()?)
+}
+
+/// The stubbed sysvars, the unset values are derived from the system time
+#[cfg(any(test, feature = "client"))]
+#[derive(Clone, Debug, Default)]
+pub struct SimulatedRuntime {
+    pub unix_timestamp: Option<u64>,
+    pub slot: Option<u64>,
+    pub epoch: Option<u64>,
+    pub rent: Rent,
+}
+
+#[cfg(any(test, feature = "client"))]
+thread_local! {
+    static SIMULATED_RUNTIME: std::cell::RefCell<SimulatedRuntime> =
+        std::cell::RefCell::new(SimulatedRuntime::default());
+}
+
+/// Stub the sysvars of the current thread
+#[cfg(any(test, feature = "client"))]
+pub fn set_simulated_runtime(runtime: SimulatedRuntime) {
+    SIMULATED_RUNTIME.with(|r| *r.borrow_mut() = runtime);
+}
+
+/// Restore the sysvars of the current thread derived from the system time
+#[cfg(any(test, feature = "client"))]
+pub fn reset_simulated_runtime() {
+    set_simulated_runtime(SimulatedRuntime::default());
+}
+
+#[cfg(any(test, feature = "client"))]
+pub fn get_unix_timestamp() -> Result<u64> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    Ok(SIMULATED_RUNTIME
+        .with(|r| r.borrow().unix_timestamp)
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+        }))
+}
+
+/// Without a stubbed slot, assume 400ms slots since the unix epoch
+#[cfg(any(test, feature = "client"))]
+pub fn get_slot() -> Result<u64> {
+    match SIMULATED_RUNTIME.with(|r| r.borrow().slot) {
+        Some(slot) => Ok(slot),
+        None => Ok(get_unix_timestamp()? * 5 / 2),
+    }
+}
+
+/// Without a stubbed epoch, assume 2 days epochs since the unix epoch
+#[cfg(any(test, feature = "client"))]
+pub fn get_recent_epoch() -> Result<u64> {
+    match SIMULATED_RUNTIME.with(|r| r.borrow().epoch) {
+        Some(epoch) => Ok(epoch),
+        None => Ok(get_unix_timestamp()? / (2 * 24 * 3600)),
+    }
+}
+
+#[cfg(any(test, feature = "client"))]
+pub fn get_rent() -> Result<Rent> {
+    Ok(SIMULATED_RUNTIME.with(|r| r.borrow().rent.clone()))
+}
+
+#[cfg(test)]
+mod runtime_test {
+    use super::*;
+
+    #[test]
+    fn simulated_runtime_test() {
+        set_simulated_runtime(SimulatedRuntime {
+            unix_timestamp: Some(1_000_000),
+            ..Default::default()
+        });
+        assert_eq!(get_unix_timestamp().unwrap(), 1_000_000);
+        // derived from the stubbed timestamp
+        assert_eq!(get_recent_epoch().unwrap(), 1_000_000 / (2 * 24 * 3600));
+        assert_eq!(get_slot().unwrap(), 2_500_000);
+
+        set_simulated_runtime(SimulatedRuntime {
+            unix_timestamp: Some(1_000_000),
+            slot: Some(42),
+            epoch: Some(7),
+            rent: Rent {
+                lamports_per_byte_year: 1,
+                exemption_threshold: 2.0,
+                burn_percent: 0,
+            },
+        });
+        assert_eq!(get_slot().unwrap(), 42);
+        assert_eq!(get_recent_epoch().unwrap(), 7);
+        assert_eq!(get_rent().unwrap().minimum_balance(0), 2 * 128);
+
+        reset_simulated_runtime();
+        assert!(get_unix_timestamp().unwrap() > 1_000_000);
+        assert_eq!(
+            get_rent().unwrap().minimum_balance(100),
+            Rent::default().minimum_balance(100)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::get_rent;
 use crate::error::ErrorCode as ClmmErrorCode;
 use anchor_lang::{prelude::*, system_program};
 
@@ -9,12 +10,12 @@ pub fn create_or_allocate_account<'a>(
     siger_seed: &[&[u8]],
     space: usize,
 ) -> Result<()> {
-    let rent = Rent::get()?;
+    let rent = get_rent()?;
     let current_lamports = target_account.lamports();
 
-    #[cfg(all(feature = "localnet", feature = "enable-log"))]
-    msg!(
-        "create_or_allocate_account, target_account: {}, current_lamports: {}, cur_space:{}, target_space: {}",
+    log_debug!(
+        ix = "create_or_allocate_account",
+        "target_account: {}, current_lamports: {}, cur_space:{}, target_space: {}",
         target_account.key.to_string(),
         current_lamports,
         target_account.data_len(),
@@ -91,10 +92,7 @@ pub fn realloc_account_if_needed<'a>(
     AccountInfo::resize(target_account, new_account_space)?;
 
     // If more lamports are needed, transfer them to the account.
-    let rent_exempt_lamports = Rent::get()
-        .unwrap()
-        .minimum_balance(new_account_space)
-        .max(1);
+    let rent_exempt_lamports = get_rent()?.minimum_balance(new_account_space).max(1);
     let top_up_lamports =
         rent_exempt_lamports.saturating_sub(target_account.to_account_info().lamports());
 
@@ -119,18 +117,3 @@ pub fn realloc_account_if_needed<'a>(
 
     Ok(true)
 }
-
-#[cfg(not(any(test, feature = "client")))]
-pub fn get_recent_epoch() -> Result<u64> {
-    Ok(Clock::get()?.epoch)
-}
-
-#[cfg(any(test, feature = "client"))]
-pub fn get_recent_epoch() -> Result<u64> {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    Ok(SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-        / (2 * 24 * 3600))
-}
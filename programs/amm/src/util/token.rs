@@ -1,4 +1,4 @@
-use super::{create_or_allocate_account, get_recent_epoch};
+use super::{create_or_allocate_account, get_recent_epoch, get_rent};
 use crate::error::ErrorCode;
 use crate::states::*;
 use anchor_lang::{
@@ -23,7 +23,7 @@ use spl_token_2022::{
 };
 use std::collections::HashSet;
 
-const MINT_WHITELIST: [&'static str; 6] = [
+const MINT_WHITELIST: [&str; 6] = [
     "HVbpJAQGNpkgBaYBZQBR1t7yFdvaYVp2vCQQfKKEN4tM",
     "Crn4x1Y2HUKko7ox2EZMT6N2t2ZyH7eKtwkBGVnhEq1g",
     "FrBfWJ4qE5sCzKm3k3JaAtqZcXUh4LvJygDeketsrsH4",
@@ -32,12 +32,21 @@ const MINT_WHITELIST: [&'static str; 6] = [
     "AUSD1jCcCyPLybk1YnvPWsHQSrZ46dxwoMniN4N2UEB9",
 ];
 
+/// Mint extensions no new pool or reward can use, they are rejected even if the mint is whitelisted,
+/// unless its `SupportMintAssociated` allows them.
+/// The confidential transfer extensions keep balances encrypted, which the vault accounting can't follow.
+/// Only checked where pools and rewards are created, the transfers of existing pools are never blocked.
+const MINT_EXTENSION_DENYLIST: [ExtensionType; 2] = [
+    ExtensionType::ConfidentialTransferMint,
+    ExtensionType::ConfidentialTransferFeeConfig,
+];
+
 pub fn invoke_memo_instruction<'info>(
     memo_msg: &[u8],
     memo_program: AccountInfo<'info>,
 ) -> solana_program::entrypoint::ProgramResult {
     let ix = spl_memo::build_memo(memo_msg, &Vec::new());
-    let accounts = vec![memo_program];
+    let accounts = [memo_program];
     solana_program::program::invoke(&ix, &accounts[..])
 }
 
@@ -88,6 +97,78 @@ pub fn transfer_from_user_to_pool_vault<'info>(
     }
 }
 
+/// Transfer from a token account whose owner approved `delegate`, a program PDA signing with `delegate_seeds`,
+/// rather than from the signer's own token account. The approval is the allowance: the token program decrements
+/// the delegated amount by each transfer and clears the delegate once it reaches zero, the owner revokes it at any time.
+pub fn transfer_from_delegated_account_to_pool_vault<'info>(
+    delegate: &AccountInfo<'info>,
+    delegate_seeds: &[&[u8]],
+    from: &AccountInfo<'info>,
+    to_vault: &AccountInfo<'info>,
+    mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+    token_program: &AccountInfo<'info>,
+    token_program_2022: Option<AccountInfo<'info>>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    check_delegated_allowance(from, delegate.key, amount)?;
+    let mut token_program_info = token_program.to_account_info();
+    let from_token_info = from.to_account_info();
+    match (mint, token_program_2022) {
+        (Some(mint), Some(token_program_2022)) => {
+            if from_token_info.owner == token_program_2022.key {
+                token_program_info = token_program_2022.to_account_info()
+            }
+            token_2022::transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program_info,
+                    token_2022::TransferChecked {
+                        from: from_token_info,
+                        to: to_vault.to_account_info(),
+                        authority: delegate.to_account_info(),
+                        mint: mint.to_account_info(),
+                    },
+                    &[delegate_seeds],
+                ),
+                amount,
+                mint.decimals,
+            )
+        }
+        _ => token::transfer(
+            CpiContext::new_with_signer(
+                token_program_info,
+                token::Transfer {
+                    from: from_token_info,
+                    to: to_vault.to_account_info(),
+                    authority: delegate.to_account_info(),
+                },
+                &[delegate_seeds],
+            ),
+            amount,
+        ),
+    }
+}
+
+/// Fail with a specific error if `delegate` is not the approved delegate of the token account, e.g. it was revoked,
+/// or if the remaining allowance is below `amount`, rather than letting the transfer CPI fail with an opaque error.
+pub fn check_delegated_allowance(
+    token_account_info: &AccountInfo,
+    delegate: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    let token_account_data = token_account_info.try_borrow_data()?;
+    let token_account =
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&token_account_data)?;
+    require!(
+        token_account.base.delegate == Some(*delegate).into()
+            && token_account.base.delegated_amount >= amount,
+        ErrorCode::InsufficientDelegateAllowance
+    );
+    Ok(())
+}
+
 pub fn transfer_from_pool_vault_to_user<'info>(
     pool_state_loader: &AccountLoader<'info, PoolState>,
     from_vault: &AccountInfo<'info>,
@@ -137,7 +218,7 @@ pub fn transfer_from_pool_vault_to_user<'info>(
     }
 }
 
-pub fn close_spl_account<'a, 'b, 'c, 'info>(
+pub fn close_spl_account<'info>(
     owner: &AccountInfo<'info>,
     destination: &AccountInfo<'info>,
     close_account: &AccountInfo<'info>,
@@ -155,7 +236,7 @@ pub fn close_spl_account<'a, 'b, 'c, 'info>(
     ))
 }
 
-pub fn burn<'a, 'b, 'c, 'info>(
+pub fn burn<'info>(
     owner: &Signer<'info>,
     mint: &AccountInfo<'info>,
     burn_account: &AccountInfo<'info>,
@@ -180,6 +261,7 @@ pub fn burn<'a, 'b, 'c, 'info>(
 }
 
 /// Calculate the fee for output amount
+#[allow(clippy::boxed_local)] // the instructions pass their boxed mint accounts
 pub fn get_transfer_inverse_fee(
     mint_account: Box<InterfaceAccount<Mint>>,
     post_fee_amount: u64,
@@ -192,30 +274,37 @@ pub fn get_transfer_inverse_fee(
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
 
     let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
-        let epoch = get_recent_epoch()?;
-
-        let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
-        if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
-            u64::from(transfer_fee.maximum_fee)
-        } else {
-            let transfer_fee = transfer_fee_config
-                .calculate_inverse_epoch_fee(epoch, post_fee_amount)
-                .unwrap();
-            let transfer_fee_for_check = transfer_fee_config
-                .calculate_epoch_fee(epoch, post_fee_amount.checked_add(transfer_fee).unwrap())
-                .unwrap();
-            if transfer_fee != transfer_fee_for_check {
-                return err!(ErrorCode::TransferFeeCalculateNotMatch);
-            }
-            transfer_fee
-        }
+        calculate_transfer_inverse_fee(transfer_fee_config, get_recent_epoch()?, post_fee_amount)?
     } else {
         0
     };
     Ok(fee)
 }
 
+/// Calculate the fee for output amount with the transfer fee config of a mint at `epoch`
+pub fn calculate_transfer_inverse_fee(
+    transfer_fee_config: &TransferFeeConfig,
+    epoch: u64,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    let transfer_fee = transfer_fee_config.get_epoch_fee(epoch);
+    if u16::from(transfer_fee.transfer_fee_basis_points) == MAX_FEE_BASIS_POINTS {
+        return Ok(u64::from(transfer_fee.maximum_fee));
+    }
+    let transfer_fee = transfer_fee_config
+        .calculate_inverse_epoch_fee(epoch, post_fee_amount)
+        .unwrap();
+    let transfer_fee_for_check = transfer_fee_config
+        .calculate_epoch_fee(epoch, post_fee_amount.checked_add(transfer_fee).unwrap())
+        .unwrap();
+    if transfer_fee != transfer_fee_for_check {
+        return err!(ErrorCode::TransferFeeCalculateNotMatch);
+    }
+    Ok(transfer_fee)
+}
+
 /// Calculate the fee for input amount
+#[allow(clippy::boxed_local)] // the instructions pass their boxed mint accounts
 pub fn get_transfer_fee(
     mint_account: Box<InterfaceAccount<Mint>>,
     pre_fee_amount: u64,
@@ -228,28 +317,37 @@ pub fn get_transfer_fee(
     let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
 
     let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
-        transfer_fee_config
-            .calculate_epoch_fee(get_recent_epoch()?, pre_fee_amount)
-            .unwrap()
+        calculate_transfer_fee(transfer_fee_config, get_recent_epoch()?, pre_fee_amount)
     } else {
         0
     };
     Ok(fee)
 }
 
-pub fn support_mint_associated_is_initialized(
+/// Calculate the fee for input amount with the transfer fee config of a mint at `epoch`
+pub fn calculate_transfer_fee(
+    transfer_fee_config: &TransferFeeConfig,
+    epoch: u64,
+    pre_fee_amount: u64,
+) -> u64 {
+    transfer_fee_config
+        .calculate_epoch_fee(epoch, pre_fee_amount)
+        .unwrap()
+}
+
+/// Find the `SupportMintAssociated` of `token_mint` in the remaining accounts
+pub fn get_support_mint_associated(
     remaining_accounts: &[AccountInfo],
     token_mint: &InterfaceAccount<Mint>,
-) -> Result<bool> {
-    if remaining_accounts.len() == 0 {
-        return Ok(false);
+) -> Result<Option<SupportMintAssociated>> {
+    if remaining_accounts.is_empty() {
+        return Ok(None);
     }
     let (expect_mint_associated, __bump) = Pubkey::find_program_address(
         &[SUPPORT_MINT_SEED.as_bytes(), token_mint.key().as_ref()],
         &crate::id(),
     );
-    let mut mint_associated_is_initialized = false;
-    for mint_associated_info in remaining_accounts.into_iter() {
+    for mint_associated_info in remaining_accounts.iter() {
         if *mint_associated_info.owner != crate::id()
             || mint_associated_info.key() != expect_mint_associated
         {
@@ -259,26 +357,47 @@ pub fn support_mint_associated_is_initialized(
             &mut mint_associated_info.data.borrow().as_ref(),
         )?;
         if mint_associated.mint == token_mint.key() {
-            mint_associated_is_initialized = true;
-            break;
+            return Ok(Some(mint_associated));
         }
     }
-    return Ok(mint_associated_is_initialized);
+    Ok(None)
+}
+
+/// Fail with a specific error if the mint has an extension in `MINT_EXTENSION_DENYLIST`,
+/// rather than letting the transfer CPIs of the pool fail with an opaque error.
+pub fn check_mint_extensions_not_denied(mint_info: &AccountInfo) -> Result<()> {
+    if *mint_info.owner != Token2022::id() {
+        return Ok(());
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let extensions = mint.get_extension_types()?;
+    if extensions
+        .iter()
+        .any(|e| MINT_EXTENSION_DENYLIST.contains(e))
+    {
+        return err!(ErrorCode::ConfidentialTransferNotSupported);
+    }
+    Ok(())
 }
 
 pub fn is_supported_mint(
     mint_account: &InterfaceAccount<Mint>,
-    mint_associated_is_initialized: bool,
+    mint_associated: Option<&SupportMintAssociated>,
 ) -> Result<bool> {
     let mint_info = mint_account.to_account_info();
     if *mint_info.owner == Token::id() {
         return Ok(true);
     }
+    // the denylist overrides the whitelist, only a SupportMintAssociated can allow the denied extensions of its mint
+    if !mint_associated.is_some_and(|mint_associated| mint_associated.allow_denied_extensions) {
+        check_mint_extensions_not_denied(&mint_info)?;
+    }
     let mint_whitelist: HashSet<&str> = MINT_WHITELIST.into_iter().collect();
     if mint_whitelist.contains(mint_account.key().to_string().as_str()) {
         return Ok(true);
     }
-    if mint_associated_is_initialized {
+    if mint_associated.is_some() {
         return Ok(true);
     }
     let mint_data = mint_info.try_borrow_data()?;
@@ -304,21 +423,17 @@ pub fn create_position_nft_mint_with_extensions<'info>(
     mint_close_authority: &AccountInfo<'info>,
     system_program: &Program<'info, System>,
     token_2022_program: &Program<'info, Token2022>,
-    with_matedata: bool,
 ) -> Result<()> {
-    let extensions = if with_matedata {
-        [
-            ExtensionType::MintCloseAuthority,
-            ExtensionType::MetadataPointer,
-        ]
-        .to_vec()
-    } else {
-        [ExtensionType::MintCloseAuthority].to_vec()
-    };
+    // the metadata pointer is always initialized, so that the metadata can be attached after the position is opened
+    let extensions = [
+        ExtensionType::MintCloseAuthority,
+        ExtensionType::MetadataPointer,
+    ]
+    .to_vec();
     let space =
         ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extensions)?;
 
-    let lamports = Rent::get()?.minimum_balance(space);
+    let lamports = get_rent()?.minimum_balance(space);
 
     // create mint account
     create_account(